@@ -6,6 +6,7 @@ pragma solidity ^0.7.0;
 /// @author Matter Labs
 contract Config {
     /// @dev ERC20 token withdrawal gas limit, used only for complete withdrawals
+    /// @dev Must match `ERC20_WITHDRAWAL_GAS_LIMIT` in `core/lib/types/src/gas_counter.rs`
     uint256 constant ERC20_WITHDRAWAL_GAS_LIMIT = 50000;
 
     /// @dev ETH token withdrawal gas limit, used only for complete withdrawals
@@ -1,6 +1,7 @@
 // Built-in deps
 use std::str::FromStr;
 // Workspace deps
+use zksync_crypto::Fr;
 use zksync_storage::{data_restore::records::NewBlockEvent, StorageProcessor};
 use zksync_types::{
     Action, Operation, Token, TokenGenesisListItem, TokenId,
@@ -60,11 +61,11 @@ impl<'a> DatabaseStorageInteractor<'a> {
 #[async_trait::async_trait]
 impl StorageInteractor for DatabaseStorageInteractor<'_> {
     async fn save_rollup_ops(&mut self, blocks: &[RollupOpsBlock]) {
-        let mut ops: Vec<(u32, &ZkSyncOp, u32)> = vec![];
+        let mut ops: Vec<(u32, &ZkSyncOp, u32, Option<Fr>)> = vec![];
 
         for block in blocks {
             for op in &block.ops {
-                ops.push((block.block_num, op, block.fee_account));
+                ops.push((block.block_num, op, block.fee_account, block.new_root_hash));
             }
         }
 
@@ -13,12 +13,44 @@ pub mod tree_state;
 mod tests;
 
 use crate::storage_interactor::StorageInteractor;
-use zksync_types::tokens::get_genesis_token_list;
+use serde::Deserialize;
+use zksync_config::ConfigurationOptions;
+use zksync_types::{tokens::get_genesis_token_list, Address, H256};
 
 // How many blocks we will process at once.
 pub const ETH_BLOCKS_STEP: u64 = 10_000;
 pub const END_ETH_BLOCKS_OFFSET: u64 = 40;
 
+/// Addresses of the contracts to follow and the network parameters.
+#[derive(Debug, Deserialize)]
+pub struct ContractsConfig {
+    pub eth_network: String,
+    pub governance_addr: Address,
+    pub genesis_tx_hash: H256,
+    pub contract_addr: Address,
+    pub available_block_chunk_sizes: Vec<usize>,
+}
+
+impl ContractsConfig {
+    pub fn from_file(path: &str) -> Self {
+        let content =
+            std::fs::read_to_string(path).expect("Unable to find the specified config file");
+        serde_json::from_str(&content).expect("Invalid configuration file provided")
+    }
+
+    pub fn from_env() -> Self {
+        let config_opts = ConfigurationOptions::from_env();
+
+        Self {
+            eth_network: config_opts.eth_network,
+            governance_addr: config_opts.governance_eth_addr,
+            genesis_tx_hash: config_opts.genesis_tx_hash,
+            contract_addr: config_opts.contract_eth_addr,
+            available_block_chunk_sizes: config_opts.available_block_chunk_sizes,
+        }
+    }
+}
+
 pub async fn add_tokens_to_storage<I: StorageInteractor>(interactor: &mut I, eth_network: &str) {
     let genesis_tokens =
         get_genesis_token_list(&eth_network).expect("Initial token list not found");
@@ -1,14 +1,14 @@
-use serde::Deserialize;
 use structopt::StructOpt;
 use web3::transports::Http;
 use zksync_config::ConfigurationOptions;
 use zksync_crypto::convert::FeConvert;
+use zksync_prometheus_exporter::install_prometheus_exporter;
 use zksync_storage::ConnectionPool;
-use zksync_types::{Address, H256};
 
 use zksync_data_restore::{
     add_tokens_to_storage, data_restore_driver::DataRestoreDriver,
-    database_storage_interactor::DatabaseStorageInteractor, END_ETH_BLOCKS_OFFSET, ETH_BLOCKS_STEP,
+    database_storage_interactor::DatabaseStorageInteractor, ContractsConfig, END_ETH_BLOCKS_OFFSET,
+    ETH_BLOCKS_STEP,
 };
 
 #[derive(StructOpt)]
@@ -43,41 +43,13 @@ struct Opt {
     config_path: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct ContractsConfig {
-    eth_network: String,
-    governance_addr: Address,
-    genesis_tx_hash: H256,
-    contract_addr: Address,
-    available_block_chunk_sizes: Vec<usize>,
-}
-
-impl ContractsConfig {
-    pub fn from_file(path: &str) -> Self {
-        let content =
-            std::fs::read_to_string(path).expect("Unable to find the specified config file");
-        serde_json::from_str(&content).expect("Invalid configuration file provided")
-    }
-
-    pub fn from_env() -> Self {
-        let config_opts = ConfigurationOptions::from_env();
-
-        Self {
-            eth_network: config_opts.eth_network,
-            governance_addr: config_opts.governance_eth_addr,
-            genesis_tx_hash: config_opts.genesis_tx_hash,
-            contract_addr: config_opts.contract_eth_addr,
-            available_block_chunk_sizes: config_opts.available_block_chunk_sizes,
-        }
-    }
-}
-
 #[tokio::main]
 async fn main() {
     log::info!("Restoring zkSync state from the contract");
     env_logger::init();
     let connection_pool = ConnectionPool::new(Some(1));
     let config_opts = ConfigurationOptions::from_env();
+    install_prometheus_exporter(config_opts.prometheus_export_port);
 
     let opt = Opt::from_args();
 
@@ -1,7 +1,12 @@
-use crate::eth_tx_helpers::{get_ethereum_transaction, get_input_data_from_ethereum_transaction};
+use crate::eth_tx_helpers::{
+    get_ethereum_transaction, get_input_data_from_ethereum_transaction, FUNC_NAME_HASH_LENGTH,
+};
 use crate::events::BlockEvent;
-use ethabi::ParamType;
+use anyhow::format_err;
+use ethabi::{ParamType, Token};
 use web3::{Transport, Web3};
+use zksync_contracts::zksync_contract;
+use zksync_crypto::{convert::FeConvert, Fr};
 use zksync_types::operations::ZkSyncOp;
 
 /// Description of a Rollup operations block
@@ -13,6 +18,9 @@ pub struct RollupOpsBlock {
     pub ops: Vec<ZkSyncOp>,
     /// Fee account
     pub fee_account: u32,
+    /// Root hash of the state after the block, as committed to the contract.
+    /// The restored state is checked against it, if it's known.
+    pub new_root_hash: Option<Fr>,
 }
 
 impl RollupOpsBlock {
@@ -30,7 +38,89 @@ impl RollupOpsBlock {
         let transaction = get_ethereum_transaction(web3, &event_data.transaction_hash).await?;
         let input_data = get_input_data_from_ethereum_transaction(&transaction)?;
 
+        let commit_blocks_signature = zksync_contract()
+            .function("commitBlocks")
+            .expect("Main contract abi error")
+            .short_signature();
+        // Blocks committed before the contract upgrade use the legacy `commitBlock` function.
+        if transaction.input.0[..FUNC_NAME_HASH_LENGTH] == commit_blocks_signature {
+            Self::from_commit_blocks_data(event_data.block_num, &input_data)
+        } else {
+            Self::from_legacy_commit_block_data(event_data.block_num, &input_data)
+        }
+    }
+
+    /// Parses the block from the input data of the `commitBlocks` transaction,
+    /// which may contain several blocks.
+    fn from_commit_blocks_data(block_num: u32, input_data: &[u8]) -> Result<Self, anyhow::Error> {
+        let stored_block_info = ParamType::Tuple(vec![
+            Box::new(ParamType::Uint(32)),       // uint32 blockNumber
+            Box::new(ParamType::Uint(64)),       // uint64 priorityOperations
+            Box::new(ParamType::FixedBytes(32)), // bytes32 pendingOnchainOperationsHash
+            Box::new(ParamType::Uint(256)),      // uint256 timestamp
+            Box::new(ParamType::FixedBytes(32)), // bytes32 stateHash
+            Box::new(ParamType::FixedBytes(32)), // bytes32 commitment
+        ]);
+        let onchain_operation_data = ParamType::Tuple(vec![
+            Box::new(ParamType::Uint(32)), // uint32 publicDataOffset
+            Box::new(ParamType::Bytes),    // bytes ethWitness
+        ]);
+        let commit_block_info = ParamType::Tuple(vec![
+            Box::new(ParamType::Uint(32)),       // uint32 blockNumber
+            Box::new(ParamType::Uint(32)),       // uint32 feeAccount
+            Box::new(ParamType::FixedBytes(32)), // bytes32 newStateHash
+            Box::new(ParamType::Bytes),          // bytes publicData
+            Box::new(ParamType::Uint(256)),      // uint256 timestamp
+            Box::new(ParamType::Array(Box::new(onchain_operation_data))), // OnchainOperationData[] onchainOperations
+        ]);
+        let decoded_commitment_parameters = ethabi::decode(
+            &[
+                stored_block_info, // StoredBlockInfo _lastCommittedBlockData
+                ParamType::Array(Box::new(commit_block_info)), // CommitBlockInfo[] _newBlocksData
+            ],
+            input_data,
+        )
+        .map_err(|_| format_err!("can't get decoded parameters from commitment transaction"))?;
+
+        let blocks = match &decoded_commitment_parameters[1] {
+            Token::Array(blocks) => blocks,
+            _ => return Err(format_err!("can't parse commitment parameters")),
+        };
+        for block in blocks {
+            if let Token::Tuple(block) = block {
+                if let (
+                    Token::Uint(number),
+                    Token::Uint(fee_acc),
+                    Token::FixedBytes(root_hash),
+                    Token::Bytes(public_data),
+                ) = (&block[0], &block[1], &block[2], &block[3])
+                {
+                    if number.as_u32() != block_num {
+                        continue;
+                    }
+                    return Ok(RollupOpsBlock {
+                        block_num,
+                        ops: Self::get_rollup_ops_from_data(public_data.as_slice())?,
+                        fee_account: fee_acc.as_u32(),
+                        new_root_hash: Some(Fr::from_bytes(root_hash)?),
+                    });
+                }
+            }
+            return Err(format_err!("can't parse commitment parameters"));
+        }
+        Err(format_err!(
+            "block {} is not found in the commitment transaction",
+            block_num
+        ))
+    }
+
+    /// Parses the block from the input data of the legacy `commitBlock` transaction.
+    fn from_legacy_commit_block_data(
+        block_num: u32,
+        input_data: &[u8],
+    ) -> Result<Self, anyhow::Error> {
         let fee_account_argument_id = 1;
+        let new_roots_argument_id = 2;
         let public_data_argument_id = 3;
         let decoded_commitment_parameters = ethabi::decode(
             vec![
@@ -42,34 +132,31 @@ impl RollupOpsBlock {
                 ParamType::Array(Box::new(ParamType::Uint(32))), // uint32[] calldata _ethWitnessSizes
             ]
             .as_slice(),
-            input_data.as_slice(),
+            input_data,
         )
-        .map_err(|_| {
-            anyhow::Error::from(Box::new(std::io::Error::new(
-                std::io::ErrorKind::NotFound,
-                "can't get decoded parameters from commitment transaction",
-            )))
-        })?;
+        .map_err(|_| format_err!("can't get decoded parameters from commitment transaction"))?;
 
-        if let (ethabi::Token::Uint(fee_acc), ethabi::Token::Bytes(public_data)) = (
+        if let (Token::Uint(fee_acc), Token::Array(new_roots), Token::Bytes(public_data)) = (
             &decoded_commitment_parameters[fee_account_argument_id],
+            &decoded_commitment_parameters[new_roots_argument_id],
             &decoded_commitment_parameters[public_data_argument_id],
         ) {
             let ops = RollupOpsBlock::get_rollup_ops_from_data(public_data.as_slice())?;
             let fee_account = fee_acc.as_u32();
+            let new_root_hash = match new_roots.first() {
+                Some(Token::FixedBytes(root_hash)) => Some(Fr::from_bytes(root_hash)?),
+                _ => None,
+            };
 
             let block = RollupOpsBlock {
-                block_num: event_data.block_num,
+                block_num,
                 ops,
                 fee_account,
+                new_root_hash,
             };
             Ok(block)
         } else {
-            Err(std::io::Error::new(
-                std::io::ErrorKind::NotFound,
-                "can't parse commitment parameters",
-            )
-            .into())
+            Err(format_err!("can't parse commitment parameters"))
         }
     }
 
@@ -103,8 +190,12 @@ impl RollupOpsBlock {
 mod test {
     use crate::rollup_ops::RollupOpsBlock;
     use num::BigUint;
+    use zksync_crypto::{ff::PrimeField, Fr};
+    use zksync_types::aggregated_operations::BlocksCommitOperation;
+    use zksync_types::block::Block;
     use zksync_types::operations::ChangePubKeyOp;
     use zksync_types::tx::{ChangePubKey, TxSignature};
+    use zksync_types::H256;
     use zksync_types::{
         Close, CloseOp, Deposit, DepositOp, FullExit, FullExitOp, PubKeyHash, Transfer, TransferOp,
         TransferToNewOp, Withdraw, WithdrawOp, ZkSyncOp,
@@ -283,4 +374,37 @@ mod test {
         let pub_data2 = op2.public_data();
         assert_eq!(pub_data1, pub_data2);
     }
+
+    #[test]
+    fn test_commit_blocks_data() {
+        let create_block = |block_number, root_hash| {
+            Block::new(
+                block_number,
+                Fr::from_str(root_hash).unwrap(),
+                2,
+                vec![],
+                (0, 0),
+                10,
+                1_000_000.into(),
+                1_500_000.into(),
+                H256::default(),
+                0,
+            )
+        };
+        let commit_operation = BlocksCommitOperation {
+            last_committed_block: create_block(1, "1"),
+            blocks: vec![create_block(2, "2"), create_block(3, "3")],
+        };
+        let input_data = ethabi::encode(&commit_operation.get_eth_tx_args());
+
+        let block = RollupOpsBlock::from_commit_blocks_data(3, &input_data)
+            .expect("cant parse commitment data");
+        assert_eq!(block.block_num, 3);
+        assert_eq!(block.fee_account, 2);
+        assert_eq!(block.new_root_hash, Some(Fr::from_str("3").unwrap()));
+        assert!(block.ops.iter().all(|op| matches!(op, ZkSyncOp::Noop(_))));
+
+        // Block is not the part of the commitment.
+        assert!(RollupOpsBlock::from_commit_blocks_data(4, &input_data).is_err());
+    }
 }
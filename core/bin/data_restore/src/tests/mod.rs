@@ -26,7 +26,9 @@ use crate::{
     data_restore_driver::DataRestoreDriver,
     database_storage_interactor::DatabaseStorageInteractor,
     inmemory_storage_interactor::InMemoryStorageInteractor,
+    rollup_ops::RollupOpsBlock,
     tests::utils::{create_log, u32_to_32bytes},
+    tree_state::TreeState,
     END_ETH_BLOCKS_OFFSET, ETH_BLOCKS_STEP,
 };
 
@@ -45,6 +47,7 @@ fn create_withdraw_operations(
         success: false,
         op: Some(withdraw_op),
         fail_reason: None,
+        fail_trace: None,
         block_index: None,
         created_at: Utc::now(),
         batch_id: None,
@@ -93,6 +96,24 @@ fn create_block(block_number: u32, transactions: Vec<ExecutedOperations>) -> Blo
     )
 }
 
+/// Sets the root hashes of the blocks to the ones the restored state will have,
+/// since the restored state is checked against the committed root hashes.
+fn set_restored_root_hashes(blocks: &mut [Block]) {
+    let mut tree_state = TreeState::new(vec![6, 30]);
+    for block in blocks {
+        let ops_block = RollupOpsBlock {
+            block_num: block.block_number,
+            ops: RollupOpsBlock::get_rollup_ops_from_data(&block.get_eth_public_data()).unwrap(),
+            fee_account: block.fee_account,
+            new_root_hash: None,
+        };
+        let (restored_block, _) = tree_state
+            .update_tree_states_from_ops_block(&ops_block)
+            .unwrap();
+        block.new_root_hash = restored_block.new_root_hash;
+    }
+}
+
 fn create_transaction(number: u32, block: Block) -> Transaction {
     let hash: H256 = u32_to_32bytes(number).into();
     let root = block.get_eth_encoded_root();
@@ -310,27 +331,28 @@ async fn test_run_state_update(mut storage: StorageProcessor<'_>) {
         )],
     );
 
-    transport.push_transactions(vec![
-        create_transaction(
+    let mut blocks = vec![
+        create_block(
             1,
-            create_block(
-                1,
-                vec![create_deposit(Default::default(), Default::default(), 50)],
-            ),
+            vec![create_deposit(Default::default(), Default::default(), 50)],
         ),
-        create_transaction(
+        create_block(
             2,
-            create_block(
-                2,
-                vec![create_withdraw_operations(
-                    0,
-                    Default::default(),
-                    Default::default(),
-                    10,
-                )],
-            ),
+            vec![create_withdraw_operations(
+                0,
+                Default::default(),
+                Default::default(),
+                10,
+            )],
         ),
-    ]);
+    ];
+    set_restored_root_hashes(&mut blocks);
+    transport.push_transactions(
+        blocks
+            .into_iter()
+            .map(|block| create_transaction(block.block_number, block))
+            .collect(),
+    );
 
     let mut driver = DataRestoreDriver::new(
         transport.clone(),
@@ -459,27 +481,28 @@ async fn test_with_inmemory_storage() {
         )],
     );
 
-    transport.push_transactions(vec![
-        create_transaction(
+    let mut blocks = vec![
+        create_block(
             1,
-            create_block(
-                1,
-                vec![create_deposit(Default::default(), Default::default(), 50)],
-            ),
+            vec![create_deposit(Default::default(), Default::default(), 50)],
         ),
-        create_transaction(
+        create_block(
             2,
-            create_block(
-                2,
-                vec![create_withdraw_operations(
-                    0,
-                    Default::default(),
-                    Default::default(),
-                    10,
-                )],
-            ),
+            vec![create_withdraw_operations(
+                0,
+                Default::default(),
+                Default::default(),
+                10,
+            )],
         ),
-    ]);
+    ];
+    set_restored_root_hashes(&mut blocks);
+    transport.push_transactions(
+        blocks
+            .into_iter()
+            .map(|block| create_transaction(block.block_number, block))
+            .collect(),
+    );
 
     let mut driver = DataRestoreDriver::new(
         transport.clone(),
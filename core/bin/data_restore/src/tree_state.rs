@@ -1,5 +1,5 @@
 use crate::rollup_ops::RollupOpsBlock;
-use anyhow::format_err;
+use anyhow::{ensure, format_err};
 use web3::types::Address;
 use zksync_crypto::Fr;
 use zksync_state::{
@@ -315,6 +315,16 @@ impl TreeState {
             0,
         );
 
+        // The contract accepts the proof only for the committed root hash,
+        // so a mismatch means the state was restored incorrectly.
+        if let Some(committed_root_hash) = ops_block.new_root_hash {
+            ensure!(
+                block.new_root_hash == committed_root_hash,
+                "Root hash of the restored block {} doesn't match the one committed to the contract",
+                ops_block.block_num
+            );
+        }
+
         self.state.block_number += 1;
 
         Ok((block, accounts_updated))
@@ -400,6 +410,7 @@ impl TreeState {
             success: true,
             op: Some(executed_op),
             fail_reason: None,
+            fail_trace: None,
             block_index: Some(block_index),
             created_at: chrono::Utc::now(),
             batch_id: None, // Currently `data_restore` is unable to restore `transaction <--> batch` relation
@@ -460,6 +471,7 @@ mod test {
             block_num: 1,
             ops: ops1,
             fee_account: 0,
+            new_root_hash: None,
         };
 
         // Withdraw 20 with 1 fee from 7 to 10
@@ -484,6 +496,7 @@ mod test {
             block_num: 2,
             ops: ops2,
             fee_account: 0,
+            new_root_hash: None,
         };
 
         // Transfer 40 with 1 fee from 7 to 8
@@ -509,6 +522,7 @@ mod test {
             block_num: 3,
             ops: ops3,
             fee_account: 0,
+            new_root_hash: None,
         };
 
         // Transfer 19 with 1 fee from 8 to 7
@@ -534,6 +548,7 @@ mod test {
             block_num: 4,
             ops: ops4,
             fee_account: 0,
+            new_root_hash: None,
         };
 
         let pub_key_hash_7 = PubKeyHash::from_hex("sync:8888888888888888888888888888888888888888")
@@ -559,6 +574,7 @@ mod test {
             block_num: 5,
             ops: ops5,
             fee_account: 0,
+            new_root_hash: None,
         };
 
         // Full exit for 8
@@ -578,6 +594,7 @@ mod test {
             block_num: 5,
             ops: ops6,
             fee_account: 0,
+            new_root_hash: None,
         };
 
         // Forced exit for 7
@@ -594,6 +611,7 @@ mod test {
             block_num: 7,
             ops: ops7,
             fee_account: 1,
+            new_root_hash: None,
         };
         // This transaction have to be deleted, do not uncomment. Delete it after removing the corresponding code        // let tx6 = Close {
         //     account: Address::from_hex("sync:8888888888888888888888888888888888888888").unwrap(),
@@ -665,6 +683,42 @@ mod test {
         assert_eq!(first_acc.get_balance(1), BigUint::from(1u32));
     }
 
+    #[test]
+    fn test_root_hash_mismatch() {
+        let tx = Deposit {
+            from: [1u8; 20].into(),
+            token: 1,
+            amount: BigUint::from(1000u32),
+            to: [7u8; 20].into(),
+        };
+        let op = ZkSyncOp::Deposit(Box::new(DepositOp {
+            priority_op: tx,
+            account_id: 0,
+        }));
+        let ops =
+            RollupOpsBlock::get_rollup_ops_from_data(&op.public_data()).expect("cant get ops");
+        let mut block = RollupOpsBlock {
+            block_num: 1,
+            ops,
+            fee_account: 0,
+            new_root_hash: Some(Default::default()),
+        };
+
+        let mut tree = TreeState::new(vec![50]);
+        assert!(tree.update_tree_states_from_ops_block(&block).is_err());
+
+        let mut tree = TreeState::new(vec![50]);
+        block.new_root_hash = None;
+        let (restored_block, _) = tree
+            .update_tree_states_from_ops_block(&block)
+            .expect("Cant update state from block");
+
+        let mut tree = TreeState::new(vec![50]);
+        block.new_root_hash = Some(restored_block.new_root_hash);
+        tree.update_tree_states_from_ops_block(&block)
+            .expect("Cant update state from block");
+    }
+
     #[test]
     fn test_update_tree_with_multiple_txs_per_block() {
         let tx1 = Deposit {
@@ -781,6 +835,7 @@ mod test {
             block_num: 1,
             ops,
             fee_account: 0,
+            new_root_hash: None,
         };
 
         let mut tree = TreeState::new(vec![50]);
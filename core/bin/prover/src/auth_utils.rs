@@ -11,36 +11,36 @@ struct PayloadAuthToken {
 }
 
 impl PayloadAuthToken {
-    pub fn new(exp: usize) -> Self {
-        Self {
-            sub: "Authorization".to_string(),
-            exp,
-        }
+    pub fn new(sub: String, exp: usize) -> Self {
+        Self { sub, exp }
     }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AuthTokenGenerator {
     secret: String,
+    /// Name of the prover, the server uses it to find the secret of the prover.
+    subject: String,
     period_availability: time::Duration,
 }
 
 impl AuthTokenGenerator {
-    pub fn new(secret: String, period_availability: time::Duration) -> Self {
+    pub fn new(secret: String, subject: String, period_availability: time::Duration) -> Self {
         Self {
             secret,
+            subject,
             period_availability,
         }
     }
 
-    /// Encode JsonWebToken with shared secret
+    /// Encode JsonWebToken with the prover secret
     pub fn encode(&self) -> jsonwebtoken::errors::Result<String> {
         // Time (Unix Timestamp) until which the token will be valid
         let exp = time::UNIX_EPOCH.elapsed().unwrap() + self.period_availability;
 
         encode_token(
             &Header::default(),
-            &PayloadAuthToken::new(exp.as_secs() as usize),
+            &PayloadAuthToken::new(self.subject.clone(), exp.as_secs() as usize),
             &EncodingKey::from_secret(self.secret.as_ref()),
         )
     }
@@ -1,9 +1,12 @@
 use anyhow::Error;
+use ethabi::Token;
+use web3::types::{Address, CallRequest, U256};
+use zksync_contracts::verifier_contract;
 use zksync_prover::cli_utils::main_for_prover_impl;
 use zksync_prover::{ProverConfig, ProverImpl};
 use zksync_prover_utils::api::{JobRequestData, JobResultData};
 use zksync_prover_utils::fs_utils::{load_correct_aggregated_proof, load_correct_single_proof};
-use zksync_utils::get_env;
+use zksync_utils::{get_env, parse_env, parse_env_with};
 
 #[derive(Debug)]
 pub struct DummyProverConfig {
@@ -59,7 +62,63 @@ impl ProverImpl for DummyProver {
     }
 }
 
+/// Checks that the contract is deployed with the dummy verifier, since the placeholder
+/// proofs are rejected by the real one.
+async fn check_dummy_verifier() -> Result<(), Error> {
+    anyhow::ensure!(
+        parse_env::<bool>("DUMMY_VERIFIER"),
+        "Dummy prover requires the dummy verifier to be enabled (DUMMY_VERIFIER=true)"
+    );
+
+    let transport = web3::transports::Http::new(&get_env("WEB3_URL"))?;
+    let web3 = web3::Web3::new(transport);
+    let verifier_address: Address = parse_env_with("VERIFIER_ADDR", |s| &s[2..]);
+
+    // Dummy verifier accepts any block proof, while the real one can't verify the empty proof.
+    let function = verifier_contract()
+        .function("verifyAggregatedProof")
+        .expect("failed to get function parameters")
+        .clone();
+    let data = function
+        .encode_input(&[
+            Token::Array(Vec::new()),
+            Token::Array(Vec::new()),
+            Token::Array(Vec::new()),
+            Token::Array(Vec::new()),
+            Token::FixedArray(vec![Token::Uint(U256::zero()); 16]),
+            Token::Bool(true),
+        ])
+        .expect("failed to encode parameters");
+    let call_request = CallRequest {
+        from: None,
+        to: Some(verifier_address),
+        gas: None,
+        gas_price: None,
+        value: None,
+        data: Some(data.into()),
+    };
+    let accepted = match web3.eth().call(call_request, None).await {
+        Ok(output) => function
+            .decode_output(&output.0)
+            .map(|tokens| tokens == vec![Token::Bool(true)])
+            .unwrap_or(false),
+        Err(_) => false,
+    };
+    anyhow::ensure!(
+        accepted,
+        "Verifier contract {:?} is not the dummy verifier, the contract should be redeployed with DUMMY_VERIFIER=true",
+        verifier_address
+    );
+
+    Ok(())
+}
+
 #[tokio::main]
 async fn main() {
+    if let Err(e) = check_dummy_verifier().await {
+        eprintln!("Dummy prover can't be started: {}", e);
+        std::process::exit(1);
+    }
+
     main_for_prover_impl::<DummyProver>().await;
 }
@@ -4,7 +4,8 @@ use std::time::Duration;
 use structopt::StructOpt;
 // Workspace deps
 use zksync_config::ProverOptions;
-use zksync_utils::{get_env, parse_env};
+use zksync_prometheus_exporter::install_prometheus_exporter;
+use zksync_utils::{get_env, parse_env, parse_env_if_exists};
 // Local deps
 use crate::{client, prover_work_cycle, ProverConfig, ProverImpl, ShutdownRequest};
 
@@ -12,7 +13,20 @@ fn api_client_from_env(worker_name: &str) -> client::ApiClient {
     let server_api_url = parse_env("PROVER_SERVER_URL");
     let request_timout = Duration::from_secs(parse_env::<u64>("REQ_SERVER_TIMEOUT"));
     let secret = get_env("PROVER_SECRET_AUTH");
-    client::ApiClient::new(&server_api_url, worker_name, request_timout, &secret)
+    let root_certificate =
+        parse_env_if_exists::<String>("PROVER_SERVER_CA_CERT_PATH").map(|path| {
+            let pem =
+                std::fs::read(&path).expect("Unable to read the prover server CA certificate");
+            reqwest::Certificate::from_pem(&pem)
+                .expect("Unable to parse the prover server CA certificate")
+        });
+    client::ApiClient::new(
+        &server_api_url,
+        worker_name,
+        request_timout,
+        &secret,
+        root_certificate,
+    )
 }
 
 #[derive(StructOpt)]
@@ -62,6 +76,18 @@ pub async fn main_for_prover_impl<P: ProverImpl + 'static + Send + Sync>() {
         .expect("Failed to register ctrlc handler");
     }
 
+    // Provers usually run on the separate machines, so the metrics are exported only if configured.
+    if let Some(port) = parse_env_if_exists("PROMETHEUS_EXPORT_PORT") {
+        install_prometheus_exporter(port);
+    }
+
     let prover_options = ProverOptions::from_env();
-    prover_work_cycle(prover, api_client, shutdown_request, prover_options).await;
+    prover_work_cycle(
+        prover,
+        api_client,
+        shutdown_request,
+        prover_options,
+        worker_name,
+    )
+    .await;
 }
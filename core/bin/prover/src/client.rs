@@ -31,16 +31,23 @@ impl ApiClient {
         worker: &str,
         req_server_timeout: time::Duration,
         secret: &str,
+        root_certificate: Option<reqwest::Certificate>,
     ) -> Self {
         if worker == "" {
             panic!("worker name cannot be empty")
         }
-        let http_client = reqwest::ClientBuilder::new()
-            .timeout(req_server_timeout)
+        let mut http_client = reqwest::ClientBuilder::new().timeout(req_server_timeout);
+        if let Some(root_certificate) = root_certificate {
+            http_client = http_client.add_root_certificate(root_certificate);
+        }
+        let http_client = http_client
             .build()
             .expect("Failed to create request client");
-        let auth_token_generator =
-            AuthTokenGenerator::new(secret.to_string(), Self::AUTH_TOKEN_LIFETIME);
+        let auth_token_generator = AuthTokenGenerator::new(
+            secret.to_string(),
+            worker.to_string(),
+            Self::AUTH_TOKEN_LIFETIME,
+        );
         Self {
             get_job_url: base_url.join("/get_job").unwrap(),
             working_on_url: base_url.join("/working_on").unwrap(),
@@ -51,7 +58,6 @@ impl ApiClient {
         }
     }
 
-    #[allow(dead_code)]
     fn get_encoded_token(&self) -> anyhow::Result<String> {
         self.auth_token_generator
             .encode()
@@ -67,27 +73,35 @@ impl crate::ApiClient for ApiClient {
         let response = self
             .http_client
             .get(self.get_job_url.clone())
+            .bearer_auth(self.get_encoded_token()?)
             .json(&req)
             .send()
             .await?;
         Ok(response.json().await?)
     }
 
-    async fn working_on(&self, job_id: i32, prover_name: &str) -> Result<(), anyhow::Error> {
-        self.http_client
+    async fn working_on(&self, job_id: i32, prover_name: &str) -> Result<bool, anyhow::Error> {
+        let response = self
+            .http_client
             .post(self.working_on_url.clone())
+            .bearer_auth(self.get_encoded_token()?)
             .json(&WorkingOn {
                 job_id,
                 prover_name: prover_name.to_string(),
             })
             .send()
             .await?;
-        Ok(())
+        if response.status() == reqwest::StatusCode::CONFLICT {
+            return Ok(false);
+        }
+        response.error_for_status()?;
+        Ok(true)
     }
 
     async fn publish(&self, data: ProverOutputRequest) -> Result<(), anyhow::Error> {
         self.http_client
             .post(self.publish_url.clone())
+            .bearer_auth(self.get_encoded_token()?)
             .json(&data)
             .send()
             .await?;
@@ -97,6 +111,7 @@ impl crate::ApiClient for ApiClient {
     async fn prover_stopped(&self, prover_id: ProverId) -> Result<(), anyhow::Error> {
         self.http_client
             .post(self.stopped_url.clone())
+            .bearer_auth(self.get_encoded_token()?)
             .json(&ProverStopped { prover_id })
             .send()
             .await?;
@@ -1,17 +1,99 @@
 //! Generate exit proof for exodus mode given account and token
 
-use anyhow::format_err;
+use anyhow::{ensure, format_err};
+use ethabi::Token;
 use log::info;
 use num::BigUint;
+use serde::Serialize;
 use std::time::Instant;
 use zksync_circuit::exit_circuit::create_exit_circuit_with_public_input;
 use zksync_config::AvailableBlockSizesConfig;
+use zksync_contracts::zksync_contract;
 use zksync_crypto::circuit::account::CircuitAccount;
 use zksync_crypto::circuit::CircuitAccountTree;
 use zksync_crypto::proof::EncodedAggregatedProof;
 use zksync_prover_utils::aggregated_proofs::{gen_aggregate_proof, SingleProofData};
 use zksync_prover_utils::{gen_verified_proof_for_exit_circuit, PlonkVerificationKey};
-use zksync_types::{AccountId, AccountMap, Address, TokenId};
+use zksync_state::state::ZkSyncState;
+use zksync_types::{
+    aggregated_operations::stored_block_info, block::Block, AccountId, AccountMap, Address,
+    BlockNumber, TokenId, U256,
+};
+use zksync_utils::BigUintSerdeWrapper;
+
+/// Exit proof along with the inputs of the `exit` function of the zkSync contract.
+#[derive(Debug, Serialize)]
+pub struct ExitProofData {
+    /// The last verified block the proof is made against.
+    pub block_number: BlockNumber,
+    pub token_id: TokenId,
+    pub account_id: AccountId,
+    pub account_address: Address,
+    pub amount: BigUintSerdeWrapper,
+    pub proof: EncodedAggregatedProof,
+    /// Calldata of the `exit` call, to be sent to the zkSync contract from the account address.
+    pub calldata: String,
+}
+
+/// Creates the exit proof for the account balance in the given token along with the `exit` calldata.
+///
+/// `accounts` must be the state after the `last_verified_block`, e.g. loaded from the database
+/// or restored from the backup. The state is checked against the root hash of the block, since
+/// the contract rejects the proofs made for any other state.
+pub fn create_exit_proof_data(
+    last_verified_block: &Block,
+    accounts: AccountMap,
+    account_id: AccountId,
+    token_id: TokenId,
+) -> Result<ExitProofData, anyhow::Error> {
+    let state = ZkSyncState::from_acc_map(accounts.clone(), last_verified_block.block_number + 1);
+    ensure!(
+        state.root_hash() == last_verified_block.new_root_hash,
+        "Accounts state doesn't match the root hash of the block #{}",
+        last_verified_block.block_number
+    );
+
+    let account_address = accounts
+        .get(&account_id)
+        .ok_or_else(|| format_err!("Account {} not found", account_id))?
+        .address;
+    let (proof, amount) = create_exit_proof(accounts, account_id, account_address, token_id)?;
+    let calldata = exit_calldata(last_verified_block, account_id, token_id, &amount, &proof)?;
+
+    Ok(ExitProofData {
+        block_number: last_verified_block.block_number,
+        token_id,
+        account_id,
+        account_address,
+        amount: amount.into(),
+        proof,
+        calldata: format!("0x{}", hex::encode(calldata)),
+    })
+}
+
+/// Encodes the call of the `exit` function of the zkSync contract.
+pub fn exit_calldata(
+    last_verified_block: &Block,
+    account_id: AccountId,
+    token_id: TokenId,
+    amount: &BigUint,
+    proof: &EncodedAggregatedProof,
+) -> Result<Vec<u8>, anyhow::Error> {
+    ensure!(amount.bits() <= 128, "Amount doesn't fit into uint128");
+
+    let contract = zksync_contract();
+    let exit = contract
+        .function("exit")
+        .map_err(|e| format_err!("Failed to get the `exit` function: {}", e))?;
+    exit.encode_input(&[
+        stored_block_info(last_verified_block),
+        Token::Uint(U256::from(account_id)),
+        Token::Uint(U256::from(token_id)),
+        Token::Uint(U256::from_big_endian(&amount.to_bytes_be())),
+        proof.get_eth_tx_args(),
+    ])
+    .map_err(|e| format_err!("Failed to encode the `exit` call: {}", e))
+}
 
 pub fn create_exit_proof(
     accounts: AccountMap,
@@ -58,3 +140,76 @@ pub fn create_exit_proof(
     info!("Exit proof created: {} s", timer.elapsed().as_secs());
     Ok((aggreagated_proof.serialize_aggregated_proof(), balance))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_crypto::ff::Field;
+    use zksync_crypto::Fr;
+    use zksync_types::{Account, H256};
+
+    fn verified_block(new_root_hash: Fr) -> Block {
+        Block::new(
+            5,
+            new_root_hash,
+            0,
+            vec![],
+            (0, 0),
+            10,
+            1_000_000.into(),
+            1_500_000.into(),
+            H256::repeat_byte(0x11),
+            0,
+        )
+    }
+
+    /// Checks that the proof is not created for the state which doesn't match the block.
+    #[test]
+    fn state_mismatch_is_rejected() {
+        let mut account = Account::default_with_address(&Address::repeat_byte(0x22));
+        account.set_balance(0, BigUint::from(100u32));
+        let mut accounts = AccountMap::default();
+        accounts.insert(1, account);
+
+        let err = create_exit_proof_data(&verified_block(Fr::one()), accounts, 1, 0)
+            .expect_err("proof is created for the mismatched state");
+        assert_eq!(
+            err.to_string(),
+            "Accounts state doesn't match the root hash of the block #5"
+        );
+    }
+
+    /// Checks that the calldata is the `exit` call with the stored block, account, token,
+    /// amount and proof as its arguments.
+    #[test]
+    fn exit_calldata_encoding() {
+        let block = verified_block(Fr::one());
+        let amount = BigUint::from(12_345u32);
+        let proof = EncodedAggregatedProof::default();
+
+        let calldata = exit_calldata(&block, 1, 2, &amount, &proof).unwrap();
+
+        let contract = zksync_contract();
+        let exit = contract.function("exit").unwrap();
+        assert_eq!(calldata[..4], exit.short_signature());
+        assert_eq!(
+            exit.decode_input(&calldata[4..]).unwrap(),
+            vec![
+                stored_block_info(&block),
+                Token::Uint(U256::from(1)),
+                Token::Uint(U256::from(2)),
+                Token::Uint(U256::from(12_345)),
+                proof.get_eth_tx_args(),
+            ]
+        );
+    }
+
+    /// Checks that the amount which doesn't fit into `uint128` is not encoded.
+    #[test]
+    fn exit_calldata_amount_overflow() {
+        let amount = BigUint::from(1u32) << 128;
+        let proof = EncodedAggregatedProof::default();
+
+        assert!(exit_calldata(&verified_block(Fr::one()), 1, 2, &amount, &proof).is_err());
+    }
+}
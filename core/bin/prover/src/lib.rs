@@ -11,7 +11,7 @@ use std::sync::{
     atomic::{AtomicBool, AtomicI32, Ordering},
     Arc,
 };
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::oneshot;
 // External deps
 use zksync_crypto::rand::{
@@ -20,6 +20,7 @@ use zksync_crypto::rand::{
 };
 // Workspace deps
 use zksync_config::ProverOptions;
+use zksync_prometheus_exporter::{register_metrics, Metric};
 use zksync_prover_utils::api::{
     JobRequestData, JobResultData, ProverId, ProverInputRequest, ProverInputRequestAuxData,
     ProverInputResponse, ProverOutputRequest,
@@ -27,6 +28,22 @@ use zksync_prover_utils::api::{
 
 const ABSENT_PROVER_ID: i32 = -1;
 
+/// Metrics reported by the prover.
+const METRICS: &[Metric] = &[
+    Metric::histogram(
+        "prover_client.proving_time",
+        "Time spent on computing the proof of a job",
+    ),
+    Metric::counter(
+        "prover_client.proofs_published",
+        "Number of the proofs published to the prover server",
+    ),
+    Metric::counter(
+        "prover_client.publish_failures",
+        "Number of the proofs the prover failed to publish",
+    ),
+];
+
 #[derive(Debug, Clone)]
 pub struct ShutdownRequest {
     shutdown_requested: Arc<AtomicBool>,
@@ -88,7 +105,8 @@ pub trait ProverImpl {
 #[async_trait::async_trait]
 pub trait ApiClient: Debug {
     async fn get_job(&self, req: ProverInputRequest) -> Result<ProverInputResponse, anyhow::Error>;
-    async fn working_on(&self, job_id: i32, prover_name: &str) -> Result<(), anyhow::Error>;
+    /// Extends the lease of the job, returns `false` if the job is not leased to the prover anymore.
+    async fn working_on(&self, job_id: i32, prover_name: &str) -> Result<bool, anyhow::Error>;
     async fn publish(&self, data: ProverOutputRequest) -> Result<(), anyhow::Error>;
     async fn prover_stopped(&self, prover_id: ProverId) -> Result<(), anyhow::Error>;
 }
@@ -114,11 +132,12 @@ async fn prover_work_cycle<PROVER, CLIENT>(
     client: CLIENT,
     shutdown: ShutdownRequest,
     prover_options: ProverOptions,
+    prover_name: String,
 ) where
     CLIENT: 'static + Sync + Send + ApiClient,
     PROVER: ProverImpl + Send + Sync + 'static,
 {
-    let prover_name = String::from("localhost");
+    register_metrics("prover", METRICS);
 
     let mut new_job_poll_timer = tokio::time::interval(prover_options.cycle_wait);
     loop {
@@ -168,16 +187,22 @@ async fn prover_work_cycle<PROVER, CLIENT>(
                 };
 
                 tokio::time::delay_for(timeout_value).await;
-                client
-                    .working_on(job_id, &prover_name)
-                    .await
-                    .map_err(|e| log::warn!("Failed to send hearbeat: {}", e))
-                    .unwrap_or_default();
+                match client.working_on(job_id, &prover_name).await {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        // The job was given to another prover, there's no reason to extend the lease,
+                        // but the proof is still finished and published, since it will be valid anyway.
+                        log::warn!("Job {} is not leased to the prover anymore", job_id);
+                        futures::future::pending::<()>().await;
+                    }
+                    Err(e) => log::warn!("Failed to send hearbeat: {}", e),
+                }
             }
         }
         .fuse();
         pin_mut!(heartbeat_future_handle);
 
+        let proving_start = Instant::now();
         let compute_proof_future = compute_proof_no_blocking(prover, job_data).fuse();
         pin_mut!(compute_proof_future);
 
@@ -188,16 +213,22 @@ async fn prover_work_cycle<PROVER, CLIENT>(
             _ = heartbeat_future_handle => unreachable!(),
         };
         prover = ret_prover;
+        metrics::histogram!("prover_client.proving_time", proving_start.elapsed());
 
-        client
+        let publish_result = client
             .publish(ProverOutputRequest {
                 job_id,
                 first_block,
                 last_block,
                 data: proof,
             })
-            .await
-            .map_err(|e| log::warn!("Failed to publish proof: {}", e))
-            .unwrap_or_default();
+            .await;
+        match publish_result {
+            Ok(()) => metrics::counter!("prover_client.proofs_published", 1),
+            Err(e) => {
+                log::warn!("Failed to publish proof: {}", e);
+                metrics::counter!("prover_client.publish_failures", 1);
+            }
+        }
     }
 }
@@ -1,22 +1,15 @@
 //! Generate exit proof for exodus mode given account and token
 //! correct verified state should be present in the db (could be restored using `data-restore` module)
+//! or in the backup file created by the server with the `backup create` subcommand.
 
-use serde::Serialize;
-use std::time::Instant;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Instant,
+};
 use structopt::StructOpt;
-use zksync_crypto::proof::EncodedProofPlonk;
-use zksync_storage::ConnectionPool;
-use zksync_types::{AccountId, Address, TokenId, TokenLike};
-use zksync_utils::BigUintSerdeWrapper;
-
-#[derive(Serialize, Debug)]
-struct ExitProofData {
-    token_id: TokenId,
-    account_id: AccountId,
-    account_address: Address,
-    amount: BigUintSerdeWrapper,
-    proof: EncodedProofPlonk,
-}
+use zksync_storage::{backup::records::StateBackup, ConnectionPool};
+use zksync_types::{block::Block, AccountId, AccountMap, TokenId, TokenLike};
 
 #[derive(StructOpt)]
 #[structopt(
@@ -32,19 +25,14 @@ struct Opt {
     /// Token to withdraw - "ETH" or address of the ERC20 token
     #[structopt(long)]
     token: String,
-}
-
-#[tokio::main]
-async fn main() {
-    env_logger::init();
-
-    let opt = Opt::from_args();
 
-    let account_id = opt.account_id.parse::<AccountId>().unwrap();
-    let token = TokenLike::parse(&opt.token);
+    /// Backup file to load the verified state from instead of the db
+    #[structopt(long, parse(from_os_str))]
+    backup: Option<PathBuf>,
+}
 
-    let timer = Instant::now();
-    log::info!("Restoring state from db");
+/// Loads the last verified block and the state after it from the db.
+async fn load_from_db(token: TokenLike) -> (Block, AccountMap, TokenId) {
     let connection_pool = ConnectionPool::new(Some(1));
     let mut storage = connection_pool
         .access_storage()
@@ -61,41 +49,84 @@ async fn main() {
                   it may not be available after data restore. Try using token address in that case",
         )
         .id;
-    let address = storage
-        .chain()
-        .account_schema()
-        .last_verified_state_for_account(account_id)
-        .await
-        .expect("DB access fail")
-        .expect("Account not found in the db")
-        .address;
-    let accounts = storage
+    let (block_number, accounts) = storage
         .chain()
         .state_schema()
         .load_verified_state()
         .await
-        .expect("Failed to load verified state")
-        .1;
+        .expect("Failed to load verified state");
+    let block = storage
+        .chain()
+        .block_schema()
+        .get_block(block_number)
+        .await
+        .expect("Db access fail")
+        .expect("Last verified block not found in the db");
 
-    log::info!("Restored state from db: {} s", timer.elapsed().as_secs());
+    (block, accounts, token_id)
+}
 
-    let (proof, amount) =
-        zksync_prover::exit_proof::create_exit_proof(accounts, account_id, address, token_id)
-            .expect("Failed to generate exit proof");
+/// Loads the last verified block and the state after it from the backup file.
+fn load_from_backup(path: &Path, token: TokenLike) -> (Block, AccountMap, TokenId) {
+    let backup: StateBackup =
+        serde_json::from_slice(&fs::read(path).expect("Failed to read the backup file"))
+            .expect("Failed to parse the backup file");
 
-    let proof_data = ExitProofData {
+    let token_id = backup
+        .tokens
+        .iter()
+        .find(|known_token| match &token {
+            TokenLike::Id(id) => known_token.id == *id,
+            TokenLike::Address(address) => known_token.address == *address,
+            TokenLike::Symbol(symbol) => known_token.symbol.eq_ignore_ascii_case(symbol),
+        })
+        .expect("Token not found in the backup")
+        .id;
+
+    (
+        backup.block,
+        backup.accounts.into_iter().collect(),
         token_id,
-        account_id,
-        account_address: address,
-        amount: amount.into(),
-        proof,
+    )
+}
+
+#[tokio::main]
+async fn main() {
+    env_logger::init();
+
+    let opt = Opt::from_args();
+
+    let account_id = opt.account_id.parse::<AccountId>().unwrap();
+    let token = TokenLike::parse(&opt.token);
+
+    let timer = Instant::now();
+    let (block, accounts, token_id) = match &opt.backup {
+        Some(path) => {
+            tracing::info!("Restoring state from backup");
+            load_from_backup(path, token)
+        }
+        None => {
+            tracing::info!("Restoring state from db");
+            load_from_db(token).await
+        }
     };
 
+    tracing::info!(
+        "Restored state at the block #{}: {} s",
+        block.block_number,
+        timer.elapsed().as_secs()
+    );
+
+    let proof_data =
+        zksync_prover::exit_proof::create_exit_proof_data(&block, accounts, account_id, token_id)
+            .expect("Failed to generate exit proof");
+
     println!("\n\n");
     println!("==========================");
     println!("Generating proof completed");
     println!("Below you can see the input data for the exit transaction on zkSync contract");
     println!("Look up the manuals of your desired smart wallet in order to know how to sign and send this transaction to the Ethereum");
+    println!("The transaction must be sent from the account address to the zkSync contract with the provided calldata");
     println!("==========================");
 
     println!("Exit transaction inputs:");
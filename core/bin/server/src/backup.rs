@@ -0,0 +1,138 @@
+//! Backup and restore of the server state.
+//!
+//! Backup is a JSON file with the state at the last verified block (see `zksync_storage::backup`),
+//! along with the token overrides and the runtime settings changed through the admin API.
+//! Root hash of the exported accounts is checked against the block on export, before import
+//! and after import, so a corrupted backup is never written or left in the database unnoticed.
+
+// Built-in uses
+use std::{fs, path::Path};
+// Workspace uses
+use zksync_state::state::ZkSyncState;
+use zksync_storage::{backup::records::StateBackup, StorageProcessor};
+use zksync_types::{block::Block, Account, AccountId};
+
+/// Checks that the accounts state matches the root hash of the block.
+fn verify_root_hash(block: &Block, accounts: Vec<(AccountId, Account)>) -> anyhow::Result<()> {
+    let state = ZkSyncState::from_acc_map(accounts.into_iter().collect(), block.block_number + 1);
+    anyhow::ensure!(
+        state.root_hash() == block.new_root_hash,
+        "Root hash of the accounts state doesn't match the root hash of the block #{}",
+        block.block_number
+    );
+    Ok(())
+}
+
+/// Exports the state at the last verified block into the file.
+pub async fn create_backup(path: &Path) -> anyhow::Result<()> {
+    let mut storage = StorageProcessor::establish_connection().await?;
+    let backup = storage.backup_schema().create_backup().await?;
+    verify_root_hash(&backup.block, backup.accounts.clone())?;
+
+    fs::write(path, serde_json::to_vec(&backup)?)?;
+    tracing::info!(
+        "State at the block #{} ({} accounts) is saved to {}",
+        backup.block.block_number,
+        backup.accounts.len(),
+        path.display()
+    );
+    Ok(())
+}
+
+/// Reads the backup from the file and checks it against the root hash of its block.
+fn read_backup(path: &Path) -> anyhow::Result<StateBackup> {
+    let backup: StateBackup = serde_json::from_slice(&fs::read(path)?)?;
+    verify_root_hash(&backup.block, backup.accounts.clone())?;
+    Ok(backup)
+}
+
+/// Imports the state from the file into the fresh database.
+pub async fn restore_backup(path: &Path) -> anyhow::Result<()> {
+    let backup = read_backup(path)?;
+    let block = backup.block.clone();
+
+    let mut storage = StorageProcessor::establish_connection().await?;
+    let mut transaction = storage.start_transaction().await?;
+    transaction.backup_schema().restore_backup(backup).await?;
+
+    // Check the imported state before committing it.
+    let (block_number, accounts) = transaction
+        .chain()
+        .state_schema()
+        .load_verified_state()
+        .await?;
+    anyhow::ensure!(
+        block_number == block.block_number,
+        "Restored state is at the block #{}, expected #{}",
+        block_number,
+        block.block_number
+    );
+    verify_root_hash(&block, accounts.into_iter().collect())?;
+    transaction.commit().await?;
+
+    tracing::info!(
+        "State at the block #{} is restored from {}",
+        block.block_number,
+        path.display()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_storage::{
+        backup::records::{EthParametersBackup, RuntimeSettingsBackup},
+        test_data::{gen_operation, BLOCK_SIZE_CHUNKS},
+    };
+    use zksync_types::{Action, Address};
+
+    fn write_backup(name: &str, backup: &StateBackup) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("{}_{}.json", name, std::process::id()));
+        fs::write(&path, serde_json::to_vec(backup).unwrap()).unwrap();
+        path
+    }
+
+    fn backup() -> StateBackup {
+        let mut account = Account::default_with_address(&Address::repeat_byte(1));
+        account.set_balance(0, 1_000u32.into());
+        let accounts = vec![(1, account)];
+
+        let mut block = gen_operation(5, Action::Commit, BLOCK_SIZE_CHUNKS).block;
+        block.new_root_hash =
+            ZkSyncState::from_acc_map(accounts.clone().into_iter().collect(), 6).root_hash();
+
+        StateBackup {
+            block,
+            tokens: Vec::new(),
+            accounts,
+            aggregated_operations: Vec::new(),
+            eth_parameters: EthParametersBackup {
+                nonce: 0,
+                gas_price_limit: 1000,
+                average_gas_price: None,
+                commit_ops: 5,
+                verify_ops: 5,
+                withdraw_ops: 0,
+            },
+            token_overrides: Vec::new(),
+            runtime_settings: RuntimeSettingsBackup::default(),
+        }
+    }
+
+    /// Checks that the backup is read only if its accounts match the root hash of the block.
+    #[test]
+    fn root_hash_mismatch() {
+        let backup = backup();
+        let path = write_backup("backup_root_hash_match", &backup);
+        assert_eq!(read_backup(&path).unwrap().accounts, backup.accounts);
+        fs::remove_file(&path).unwrap();
+
+        let mut corrupted = backup;
+        corrupted.accounts[0].1.set_balance(0, 2_000u32.into());
+        let path = write_backup("backup_root_hash_mismatch", &corrupted);
+        let err = read_backup(&path).unwrap_err();
+        assert!(err.to_string().contains("doesn't match the root hash"));
+        fs::remove_file(&path).unwrap();
+    }
+}
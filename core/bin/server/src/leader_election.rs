@@ -0,0 +1,95 @@
+//! Leader election between the server replicas sharing the database.
+//!
+//! Only the leader runs the actors which change the state of the network and sign the Ethereum
+//! transactions, while the standby replicas serve the read-only API. Leadership is the Postgres
+//! advisory lock held by the dedicated database session, so once the leader dies (or loses its
+//! connection to the database) the lock is released and one of the standby replicas takes over.
+//!
+//! The former leader may not notice the loss of the lock immediately, so every new leader starts
+//! a new leader epoch, and the Ethereum sender of the former leader is fenced by its epoch.
+
+// External uses
+use tokio::{task::JoinHandle, time};
+// Workspace uses
+use zksync_config::LeaderElectionOptions;
+use zksync_storage::{QueryResult, StorageProcessor};
+
+/// Tries to acquire the leadership lock in a new database session, which is returned
+/// if the lock is acquired.
+pub async fn try_acquire_leadership(
+    lock_id: i64,
+) -> QueryResult<Option<StorageProcessor<'static>>> {
+    let mut storage = StorageProcessor::establish_connection().await?;
+    let acquired = storage
+        .leader_election_schema()
+        .try_acquire_lock(lock_id)
+        .await?;
+    Ok(if acquired { Some(storage) } else { None })
+}
+
+/// Session holding the leadership lock along with the leader epoch started by it.
+#[derive(Debug)]
+pub struct Leadership {
+    pub storage: StorageProcessor<'static>,
+    pub epoch: i64,
+}
+
+async fn try_start_leadership(lock_id: i64) -> QueryResult<Option<Leadership>> {
+    let mut storage = match try_acquire_leadership(lock_id).await? {
+        Some(storage) => storage,
+        None => return Ok(None),
+    };
+    let epoch = storage
+        .leader_election_schema()
+        .start_leader_epoch()
+        .await?;
+    Ok(Some(Leadership { storage, epoch }))
+}
+
+/// Waits until this replica becomes the leader, returns the session holding the leadership lock.
+pub async fn acquire_leadership(options: &LeaderElectionOptions) -> Leadership {
+    tracing::info!("Waiting for the leadership, the server runs in the standby mode");
+    loop {
+        match try_start_leadership(options.lock_id).await {
+            Ok(Some(leadership)) => {
+                tracing::info!("Leadership is acquired, leader epoch {}", leadership.epoch);
+                return leadership;
+            }
+            Ok(None) => {}
+            Err(err) => tracing::warn!("Unable to acquire the leadership: {}", err),
+        }
+        time::delay_for(options.check_interval).await;
+    }
+}
+
+/// Periodically checks that the leadership lock is still held by the session.
+///
+/// Once the session is lost, another replica may already be the leader, so the server is
+/// stopped immediately rather than gracefully: finishing the work in progress could result
+/// in both replicas committing the blocks and sending the Ethereum transactions.
+pub fn start_leadership_watchdog(
+    mut storage: StorageProcessor<'static>,
+    options: LeaderElectionOptions,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            time::delay_for(options.check_interval).await;
+
+            let result = storage
+                .leader_election_schema()
+                .is_lock_held(options.lock_id)
+                .await;
+            match result {
+                Ok(true) => {}
+                Ok(false) => {
+                    vlog::critical!("Leadership lock is not held by the session, exiting");
+                    std::process::exit(1);
+                }
+                Err(err) => {
+                    vlog::critical!("Leadership is lost: {}, exiting", err);
+                    std::process::exit(1);
+                }
+            }
+        }
+    })
+}
@@ -1,19 +1,62 @@
 use futures::{channel::mpsc, executor::block_on, SinkExt, StreamExt};
-use std::cell::RefCell;
+use std::{cell::RefCell, path::PathBuf};
 use structopt::StructOpt;
-use zksync_api::run_api;
-use zksync_config::{ConfigurationOptions, EthClientOptions, EthSenderOptions, ProverOptions};
+use tokio::signal::unix::{signal, SignalKind};
+use zksync_api::{api_server::set_standby_mode, run_api};
+use zksync_config::{
+    loader::{
+        config_report, load_config, load_network_profile, reload_config, CONFIG_FILE_VARIABLE,
+    },
+    AlertOptions, ConfigurationOptions, EthClientOptions, EthSenderOptions, LeaderElectionOptions,
+    ProverOptions,
+};
+use zksync_contracts::zksync_contract;
 use zksync_core::{genesis_init, run_core, wait_for_tasks};
-use zksync_eth_sender::run_eth_sender;
+use zksync_eth_client::check_network;
+use zksync_eth_sender::{revert_unverified_blocks, run_eth_sender};
 use zksync_prometheus_exporter::run_prometheus_exporter;
+use zksync_utils::shutdown::ShutdownSignal;
 use zksync_witness_generator::run_prover_server;
 
-use zksync_storage::ConnectionPool;
+use zksync_storage::{admin::BLOCKS_REVERT_FLAG, ConnectionPool};
+
+use crate::backup::{create_backup, restore_backup};
+use crate::leader_election::{
+    acquire_leadership, start_leadership_watchdog, try_acquire_leadership,
+};
+
+mod backup;
+mod leader_election;
 
 #[derive(Debug, Clone, Copy)]
 pub enum ServerCommand {
     Genesis,
+    RevertBlocks,
     Launch,
+    ApiNode,
+}
+
+#[derive(StructOpt)]
+enum Command {
+    /// Backup of the server state at the last verified block.
+    Backup(BackupCommand),
+}
+
+#[derive(StructOpt)]
+enum BackupCommand {
+    /// Export the state at the last verified block (accounts, tokens, the last block,
+    /// the `eth_sender` data, the token overrides and the runtime settings) into the file.
+    /// All the committed blocks must be verified.
+    Create {
+        #[structopt(parse(from_os_str))]
+        file: PathBuf,
+    },
+    /// Import the state exported with `backup create` into the fresh database (without
+    /// the genesis block). Root hash of the imported state is checked against the last block.
+    Restore {
+        #[structopt(parse(from_os_str))]
+        file: PathBuf,
+    },
 }
 
 #[derive(StructOpt)]
@@ -22,33 +65,129 @@ struct Opt {
     /// Generate genesis block for the first contract deployment
     #[structopt(long)]
     genesis: bool,
+    /// Revert all the committed but not verified blocks, both on the contract and in the database.
+    /// Transactions from the reverted blocks are returned to the mempool.
+    /// The leadership lock is taken for the revert, so the server leader must be stopped.
+    /// The revert can also be requested through the admin API, then it's performed by the leader.
+    #[structopt(long)]
+    revert_blocks: bool,
+    /// Serve only the read-only API from the shared database, without the Core actors,
+    /// the Ethereum sender and the prover server. Incoming transactions are rejected,
+    /// so such nodes are used to scale the queries independently of the sequencing server.
+    #[structopt(long)]
+    api_only: bool,
+    /// TOML file with the configuration, the environment variables override its values.
+    #[structopt(long, parse(from_os_str))]
+    config: Option<PathBuf>,
+    /// Network profile from `etc/networks` (e.g. `localhost`, `goerli`, `mainnet`), its values
+    /// override both the environment and the configuration file.
+    #[structopt(long)]
+    network: Option<String>,
+    /// Print the validated configuration and exit.
+    #[structopt(long)]
+    print_config: bool,
+    #[structopt(subcommand)]
+    command: Option<Command>,
+}
+
+/// Reverts the unverified blocks, must be invoked by the leader before the actors are started.
+async fn revert_blocks() -> anyhow::Result<()> {
+    tracing::info!("Reverting the unverified blocks");
+    let last_block = revert_unverified_blocks(
+        ConnectionPool::new(Some(1)),
+        EthClientOptions::from_env(),
+        EthSenderOptions::from_env(),
+    )
+    .await?;
+    tracing::info!("Blocks after {} are reverted", last_block);
+    Ok(())
 }
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    env_logger::init();
+    vlog::init();
     let opt = Opt::from_args();
 
+    // Report all the configuration problems at once instead of panicking in the actors.
+    if let Some(network) = &opt.network {
+        load_network_profile(network)?;
+    }
+    load_config(opt.config.as_deref())?;
+    if opt.print_config {
+        print!("{}", config_report());
+        return Ok(());
+    }
+
     let server_mode = if opt.genesis {
         ServerCommand::Genesis
+    } else if opt.revert_blocks {
+        ServerCommand::RevertBlocks
+    } else if opt.api_only {
+        ServerCommand::ApiNode
     } else {
         ServerCommand::Launch
     };
 
     if let ServerCommand::Genesis = server_mode {
-        log::info!("Performing the server genesis initialization");
+        tracing::info!("Performing the server genesis initialization");
         genesis_init().await;
         return Ok(());
     }
 
-    // It's a `ServerCommand::Launch`, perform the usual routine.
-    log::info!("Running the zkSync server");
+    if let ServerCommand::RevertBlocks = server_mode {
+        let options = LeaderElectionOptions::from_env();
+        let _leadership = try_acquire_leadership(options.lock_id)
+            .await?
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "The server leader is running, it must be stopped before the revert"
+                )
+            })?;
+        revert_blocks().await?;
+        return Ok(());
+    }
+
+    if let Some(Command::Backup(command)) = &opt.command {
+        match command {
+            BackupCommand::Create { file } => {
+                tracing::info!("Creating the backup of the verified state");
+                create_backup(file).await?;
+            }
+            BackupCommand::Restore { file } => {
+                tracing::info!("Restoring the state from the backup");
+                restore_backup(file).await?;
+            }
+        }
+        return Ok(());
+    }
+
+    // It's either a `ServerCommand::Launch` or a `ServerCommand::ApiNode`, perform the usual routine.
+    let api_only = matches!(server_mode, ServerCommand::ApiNode);
+    if api_only {
+        tracing::info!("Running the zkSync read-only API node");
+    } else {
+        tracing::info!("Running the zkSync server");
+    }
 
     let connection_pool = ConnectionPool::new(None);
     let config_options = ConfigurationOptions::from_env();
     let eth_client_options = EthClientOptions::from_env();
     let eth_sender_options = EthSenderOptions::from_env();
     let prover_options = ProverOptions::from_env();
+    let leader_election_options = LeaderElectionOptions::from_env();
+
+    // Make sure the selected profile matches the network the server is connected to.
+    if let Some(network) = &opt.network {
+        check_network(
+            &eth_client_options.web3_url,
+            eth_client_options.chain_id,
+            eth_client_options.contract_eth_addr,
+            zksync_contract(),
+            config_options.governance_eth_addr,
+        )
+        .await?;
+        tracing::info!("Connected to the '{}' network", network);
+    }
 
     // Handle Ctrl+C
     let (stop_signal_sender, mut stop_signal_receiver) = mpsc::channel(256);
@@ -61,32 +200,115 @@ async fn main() -> anyhow::Result<()> {
         .expect("Error setting Ctrl+C handler");
     }
 
+    // Report the panics and critical errors of all the actors, so the server doesn't keep running
+    // with some of them dead unnoticed.
+    let alert_options = AlertOptions::from_env();
+    let _alerts = vlog::alerts::init(
+        alert_options.sentry_dsn.as_deref(),
+        alert_options.webhook_url.as_ref().map(|url| url.as_str()),
+        if alert_options.shutdown_on_panic {
+            Some(stop_signal_sender.clone())
+        } else {
+            None
+        },
+    );
+
+    // Reload the runtime-tunable parameters from the configuration file on `SIGHUP`.
+    if std::env::var_os(CONFIG_FILE_VARIABLE).is_some() {
+        let mut hangup = signal(SignalKind::hangup()).expect("Unable to listen to SIGHUP");
+        tokio::spawn(async move {
+            while hangup.recv().await.is_some() {
+                if let Err(err) = reload_config() {
+                    tracing::error!("Unable to reload the configuration: {}", err);
+                }
+            }
+        });
+    }
+
     // Run prometheus data exporter.
     let (prometheus_task_handle, counter_task_handle) = run_prometheus_exporter(
         connection_pool.clone(),
         config_options.prometheus_export_port,
     );
 
-    // Run core actors.
-    log::info!("Starting the Core actors");
-    let core_task_handles = run_core(connection_pool.clone(), stop_signal_sender.clone())
-        .await
-        .expect("Unable to start Core actors");
-
-    // Run API actors.
-    log::info!("Starting the API server actors");
+    // Run API actors. Until this replica becomes the leader, the API is read-only.
+    tracing::info!("Starting the API server actors");
+    set_standby_mode(api_only || leader_election_options.enabled);
     let api_task_handle = run_api(connection_pool.clone(), stop_signal_sender.clone());
 
+    // Read-only API node never runs the other actors.
+    if api_only {
+        tokio::select! {
+            _ = async { api_task_handle.await } => {
+                panic!("API server actors aren't supposed to finish their execution")
+            },
+            _ = async { prometheus_task_handle.await } => {
+                panic!("Prometheus exporter actors aren't supposed to finish their execution")
+            },
+            _ = async { counter_task_handle.await } => {
+                panic!("Operation counting actor is not supposed to finish its execution")
+            },
+            _ = async { stop_signal_receiver.next().await } => {
+                tracing::warn!("Stop signal received, shutting down");
+            }
+        };
+        return Ok(());
+    }
+
+    // Other actors are run only by the leader replica.
+    let mut leader_epoch = None;
+    if leader_election_options.enabled {
+        let leadership = tokio::select! {
+            leadership = acquire_leadership(&leader_election_options) => leadership,
+            _ = async { stop_signal_receiver.next().await } => {
+                tracing::warn!("Stop signal received in the standby mode, shutting down");
+                return Ok(());
+            }
+        };
+        leader_epoch = Some(leadership.epoch);
+        start_leadership_watchdog(leadership.storage, leader_election_options);
+        set_standby_mode(false);
+    }
+
+    // The revert requested through the admin API is performed before the actors are started.
+    let revert_requested = connection_pool
+        .access_storage()
+        .await?
+        .admin_schema()
+        .take_flag(BLOCKS_REVERT_FLAG)
+        .await?;
+    if revert_requested {
+        if let Err(err) = revert_blocks().await {
+            vlog::critical!("Unable to revert the unverified blocks: {}", err);
+        }
+    }
+
+    // Actors which have to finish their work in progress before the server exits.
+    let core_shutdown = ShutdownSignal::new();
+    let eth_sender_shutdown = ShutdownSignal::new();
+
+    // Run core actors.
+    tracing::info!("Starting the Core actors");
+    let core_task_handles = run_core(
+        connection_pool.clone(),
+        stop_signal_sender.clone(),
+        core_shutdown.clone(),
+    )
+    .await
+    .expect("Unable to start Core actors");
+
     // Run Ethereum sender actors.
-    log::info!("Starting the Ethereum sender actors");
+    tracing::info!("Starting the Ethereum sender actors");
     let eth_sender_task_handle = run_eth_sender(
         connection_pool.clone(),
         eth_client_options,
         eth_sender_options,
+        leader_epoch,
+        eth_sender_shutdown.clone(),
     );
 
     // Run prover server & witness generator.
-    log::info!("Starting the Prover server actors");
+    tracing::info!("Starting the Prover server actors");
     run_prover_server(connection_pool, stop_signal_sender, prover_options);
 
     tokio::select! {
@@ -106,9 +328,27 @@ async fn main() -> anyhow::Result<()> {
             panic!("Operation counting actor is not supposed to finish its execution")
         },
         _ = async { stop_signal_receiver.next().await } => {
-            log::warn!("Stop signal received, shutting down");
+            tracing::warn!("Stop signal received, shutting down");
         }
     };
 
+    // The Core actors are stopped first, so the pending block is sealed and stored before the
+    // server exits. The Ethereum sender is stopped between its iterations and doesn't send the
+    // new operations anymore: its queue is kept in the database and restored on the next start.
+    let graceful_shutdown = async {
+        core_shutdown.request();
+        core_shutdown.stopped().await;
+        eth_sender_shutdown.request();
+        eth_sender_shutdown.stopped().await;
+    };
+    let shutdown_timeout = config_options.shutdown_timeout;
+    match tokio::time::timeout(shutdown_timeout, graceful_shutdown).await {
+        Ok(()) => tracing::info!("Server actors are stopped"),
+        Err(_) => tracing::warn!(
+            "Server actors didn't stop in {:?}, exiting anyway",
+            shutdown_timeout
+        ),
+    }
+
     Ok(())
 }
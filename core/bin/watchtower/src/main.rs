@@ -0,0 +1,71 @@
+use structopt::StructOpt;
+use web3::transports::Http;
+use zksync_config::ConfigurationOptions;
+use zksync_prometheus_exporter::install_prometheus_exporter;
+use zksync_storage::ConnectionPool;
+
+use zksync_data_restore::{
+    database_storage_interactor::DatabaseStorageInteractor, ContractsConfig, END_ETH_BLOCKS_OFFSET,
+    ETH_BLOCKS_STEP,
+};
+
+use crate::watchtower::Watchtower;
+
+mod watchtower;
+
+#[derive(StructOpt)]
+#[structopt(
+    name = "zkSync watchtower",
+    author = "Matter Labs",
+    rename_all = "snake_case"
+)]
+struct Opt {
+    /// Sets the web3 API to be used to interact with the Ethereum blockchain
+    #[structopt(long = "web3", name = "web3")]
+    web3_url: Option<String>,
+
+    /// Provides a path to the configuration file with the contracts
+    #[structopt(long = "config", name = "config")]
+    config_path: Option<String>,
+}
+
+#[tokio::main]
+async fn main() {
+    env_logger::init();
+    log::info!("Starting the watchtower");
+
+    // The database is only read, so the connection may point to a read-only replica
+    // of the `data_restore` database.
+    let connection_pool = ConnectionPool::new(Some(1));
+    let config_opts = ConfigurationOptions::from_env();
+    install_prometheus_exporter(config_opts.prometheus_export_port);
+
+    let opt = Opt::from_args();
+
+    let web3_url = opt.web3_url.unwrap_or(config_opts.web3_url);
+
+    let transport = Http::new(&web3_url).expect("failed to start web3 transport");
+
+    let config = opt
+        .config_path
+        .map(|path| ContractsConfig::from_file(&path))
+        .unwrap_or_else(ContractsConfig::from_env);
+
+    let storage = connection_pool.access_storage().await.unwrap();
+    let mut interactor = DatabaseStorageInteractor::new(storage);
+
+    let mut watchtower = Watchtower::new(
+        transport,
+        config.governance_addr,
+        config.contract_addr,
+        ETH_BLOCKS_STEP,
+        END_ETH_BLOCKS_OFFSET,
+        config.available_block_chunk_sizes,
+    );
+    watchtower.load_state(&mut interactor).await;
+
+    if let Err(err) = watchtower.run(&mut interactor).await {
+        log::error!("Watchtower stopped: {}", err);
+        std::process::exit(1);
+    }
+}
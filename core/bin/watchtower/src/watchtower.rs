@@ -0,0 +1,330 @@
+//! Watchtower follows the zkSync contract and checks every committed block independently of the
+//! operator: the block operations (taken from the commitment calldata) are applied to the local
+//! state, and the resulting root hash is compared with the one committed to the contract.
+//!
+//! The local state starts from the verified state restored by `data_restore`, which is only read,
+//! so the watchtower can use a read-only replica of its database.
+
+// Built-in deps
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+// External deps
+use web3::{
+    contract::Contract,
+    types::{H160, H256},
+    Transport, Web3,
+};
+// Workspace deps
+use zksync_contracts::{governance_contract, zksync_contract};
+use zksync_crypto::Fr;
+use zksync_data_restore::{
+    events::BlockEvent, events_state::EventsState, rollup_ops::RollupOpsBlock,
+    storage_interactor::StorageInteractor, tree_state::TreeState,
+};
+use zksync_types::BlockNumber;
+
+/// Interval between the checks for the new events.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Outcome of the committed block check.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BlockCheck {
+    /// Root hash of the block applied to the local state matches the committed one.
+    Matched,
+    /// Committed root hash doesn't follow from the block operations.
+    RootHashMismatch { expected: Fr, committed: Fr },
+    /// Root hash isn't a part of the commitment, the block is applied without the check.
+    Unchecked,
+}
+
+/// Applies the committed block to the local state and compares the resulting root hash
+/// with the committed one.
+///
+/// Returns an error if the block operations can't be applied, which means that
+/// the operator committed an invalid block as well.
+pub fn check_block(
+    tree_state: &mut TreeState,
+    mut ops_block: RollupOpsBlock,
+) -> anyhow::Result<BlockCheck> {
+    // The root hash is compared here rather than by the tree state, so the mismatch
+    // is reported along with both hashes.
+    let committed = ops_block.new_root_hash.take();
+    let (block, _) = tree_state.update_tree_states_from_ops_block(&ops_block)?;
+
+    Ok(match committed {
+        Some(committed) if committed == block.new_root_hash => BlockCheck::Matched,
+        Some(committed) => BlockCheck::RootHashMismatch {
+            expected: block.new_root_hash,
+            committed,
+        },
+        None => BlockCheck::Unchecked,
+    })
+}
+
+/// Returns the latest commitment of every block, in the order of the block numbers.
+/// Reverted blocks may be committed several times, and only the latest commitment matters.
+fn latest_commitments(committed_events: &[BlockEvent]) -> BTreeMap<BlockNumber, BlockEvent> {
+    committed_events
+        .iter()
+        .map(|event| (event.block_num, *event))
+        .collect()
+}
+
+pub struct Watchtower<T: Transport> {
+    web3: Web3<T>,
+    governance_contract: (ethabi::Contract, Contract<T>),
+    zksync_contract: (ethabi::Contract, Contract<T>),
+    events_state: EventsState,
+    tree_state: TreeState,
+    available_block_chunk_sizes: Vec<usize>,
+    eth_blocks_step: u64,
+    end_eth_blocks_offset: u64,
+    /// Hashes of the commitment transactions of the checked blocks,
+    /// a new commitment of the checked block means that the block was reverted.
+    checked_commitments: HashMap<BlockNumber, H256>,
+}
+
+impl<T: Transport> Watchtower<T> {
+    pub fn new(
+        web3_transport: T,
+        governance_contract_eth_addr: H160,
+        zksync_contract_eth_addr: H160,
+        eth_blocks_step: u64,
+        end_eth_blocks_offset: u64,
+        available_block_chunk_sizes: Vec<usize>,
+    ) -> Self {
+        let web3 = Web3::new(web3_transport);
+
+        let governance_contract = {
+            let abi = governance_contract();
+            (
+                abi.clone(),
+                Contract::new(web3.eth(), governance_contract_eth_addr, abi),
+            )
+        };
+
+        let zksync_contract = {
+            let abi = zksync_contract();
+            (
+                abi.clone(),
+                Contract::new(web3.eth(), zksync_contract_eth_addr, abi),
+            )
+        };
+
+        Self {
+            web3,
+            governance_contract,
+            zksync_contract,
+            events_state: EventsState::default(),
+            tree_state: TreeState::new(available_block_chunk_sizes.clone()),
+            available_block_chunk_sizes,
+            eth_blocks_step,
+            end_eth_blocks_offset,
+            checked_commitments: HashMap::new(),
+        }
+    }
+
+    /// Loads the verified state and the contract events restored by `data_restore`.
+    pub async fn load_state<I: StorageInteractor>(&mut self, interactor: &mut I) {
+        let tree_state = interactor.get_tree_state().await;
+        self.tree_state = TreeState::load(
+            tree_state.last_block_number,
+            tree_state.account_map,
+            tree_state.unprocessed_prior_ops,
+            tree_state.fee_acc_id,
+            self.available_block_chunk_sizes.clone(),
+        );
+        self.events_state = interactor.get_block_events_state_from_storage().await;
+        self.checked_commitments.clear();
+
+        log::info!(
+            "State is loaded: block {}, root hash {:?}, last watched Ethereum block {}",
+            self.tree_state.state.block_number,
+            self.tree_state.root_hash(),
+            self.events_state.last_watched_eth_block_number
+        );
+    }
+
+    /// Checks the committed blocks until the mismatch is found.
+    ///
+    /// Errors of the Ethereum node are retried, while the returned error means
+    /// that the operator committed a block that doesn't follow from the local state.
+    pub async fn run<I: StorageInteractor>(&mut self, interactor: &mut I) -> anyhow::Result<()> {
+        loop {
+            let is_reverted = match self.update_events().await {
+                Ok(is_reverted) => is_reverted,
+                Err(err) => {
+                    log::warn!("Unable to load the contract events: {}", err);
+                    false
+                }
+            };
+            if is_reverted {
+                self.load_state(interactor).await;
+                continue;
+            }
+
+            self.check_committed_blocks().await?;
+
+            tokio::time::delay_for(POLL_INTERVAL).await;
+        }
+    }
+
+    /// Loads the new contract events, returns `true` if the checked block was committed again,
+    /// i.e. the local state contains the reverted blocks and has to be reloaded.
+    async fn update_events(&mut self) -> anyhow::Result<bool> {
+        self.events_state
+            .update_events_state(
+                &self.web3,
+                &self.zksync_contract,
+                &self.governance_contract,
+                self.eth_blocks_step,
+                self.end_eth_blocks_offset,
+            )
+            .await?;
+
+        for (block_number, event) in latest_commitments(&self.events_state.committed_events) {
+            match self.checked_commitments.get(&block_number) {
+                Some(tx_hash) if *tx_hash != event.transaction_hash => {
+                    log::warn!(
+                        "Block {} is committed again in the transaction {:#x}, the previous commitment was reverted",
+                        block_number,
+                        event.transaction_hash
+                    );
+                    return Ok(true);
+                }
+                _ => {}
+            }
+        }
+        Ok(false)
+    }
+
+    /// Checks the committed blocks following the local state.
+    /// The returned error means that the committed block doesn't match the local state.
+    async fn check_committed_blocks(&mut self) -> anyhow::Result<()> {
+        let commitments = latest_commitments(&self.events_state.committed_events);
+        let next_block = self.tree_state.state.block_number + 1;
+
+        for (block_number, event) in commitments.range(next_block..) {
+            if *block_number != self.tree_state.state.block_number + 1 {
+                // Blocks are committed in order, so the missing one is yet to be loaded.
+                break;
+            }
+
+            let ops_block = match RollupOpsBlock::get_rollup_ops_block(&self.web3, event).await {
+                Ok(ops_block) => ops_block,
+                Err(err) => {
+                    log::warn!(
+                        "Unable to load the committed block {}: {}",
+                        block_number,
+                        err
+                    );
+                    // The block will be loaded again on the next iteration.
+                    return Ok(());
+                }
+            };
+            let check = check_block(&mut self.tree_state, ops_block).map_err(|err| {
+                log::error!(
+                    "ALERT: Block {} committed in the transaction {:#x} can't be applied to the local state: {}",
+                    block_number,
+                    event.transaction_hash,
+                    err
+                );
+                err
+            })?;
+
+            match check {
+                BlockCheck::Matched => log::info!(
+                    "Block {} is checked: root hash {:?}",
+                    block_number,
+                    self.tree_state.root_hash()
+                ),
+                BlockCheck::Unchecked => log::warn!(
+                    "Block {} is applied, but its root hash is not committed and can't be checked",
+                    block_number
+                ),
+                BlockCheck::RootHashMismatch {
+                    expected,
+                    committed,
+                } => {
+                    log::error!(
+                        "ALERT: Block {} committed in the transaction {:#x} has root hash {:?}, \
+                         while {:?} follows from the local state",
+                        block_number,
+                        event.transaction_hash,
+                        committed,
+                        expected
+                    );
+                    anyhow::bail!("Root hash of block {} doesn't match", block_number);
+                }
+            }
+            self.checked_commitments
+                .insert(*block_number, event.transaction_hash);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num::BigUint;
+    use zksync_data_restore::events::EventType;
+    use zksync_types::{Deposit, DepositOp, ZkSyncOp};
+
+    fn deposit_block(block_num: BlockNumber, new_root_hash: Option<Fr>) -> RollupOpsBlock {
+        let op = ZkSyncOp::Deposit(Box::new(DepositOp {
+            priority_op: Deposit {
+                from: [1u8; 20].into(),
+                token: 1,
+                amount: BigUint::from(1000u32),
+                to: [7u8; 20].into(),
+            },
+            account_id: 0,
+        }));
+        RollupOpsBlock {
+            block_num,
+            ops: RollupOpsBlock::get_rollup_ops_from_data(&op.public_data()).expect("cant get ops"),
+            fee_account: 0,
+            new_root_hash,
+        }
+    }
+
+    #[test]
+    fn committed_root_hash_check() {
+        let mut tree = TreeState::new(vec![50]);
+        assert_eq!(
+            check_block(&mut tree, deposit_block(1, None)).unwrap(),
+            BlockCheck::Unchecked
+        );
+        let expected = tree.root_hash();
+
+        let mut tree = TreeState::new(vec![50]);
+        assert_eq!(
+            check_block(&mut tree, deposit_block(1, Some(expected))).unwrap(),
+            BlockCheck::Matched
+        );
+
+        let mut tree = TreeState::new(vec![50]);
+        assert_eq!(
+            check_block(&mut tree, deposit_block(1, Some(Fr::default()))).unwrap(),
+            BlockCheck::RootHashMismatch {
+                expected,
+                committed: Fr::default(),
+            }
+        );
+    }
+
+    #[test]
+    fn latest_commitment_is_used() {
+        let event = |block_num, tx_hash| BlockEvent {
+            block_num,
+            transaction_hash: H256::from_low_u64_be(tx_hash),
+            block_type: EventType::Committed,
+        };
+        let commitments = latest_commitments(&[event(1, 1), event(2, 1), event(2, 2)]);
+
+        assert_eq!(commitments.len(), 2);
+        assert_eq!(commitments[&2].transaction_hash, H256::from_low_u64_be(2));
+    }
+}
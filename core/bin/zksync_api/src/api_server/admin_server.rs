@@ -1,4 +1,5 @@
 // Built-in deps
+use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::thread;
 
@@ -10,15 +11,39 @@ use actix_web_httpauth::extractors::{
     AuthenticationError,
 };
 use actix_web_httpauth::middleware::HttpAuthentication;
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
 use futures::channel::mpsc;
 use jsonwebtoken::errors::Error as JwtError;
 use jsonwebtoken::{decode, DecodingKey, Validation};
 use serde::{Deserialize, Serialize};
 
 // Local uses
-use zksync_types::{tokens, Address, TokenId};
+use super::fee_subsidy::{start_of_day, FEE_TYPE_NAMES};
+use crate::utils::runtime_settings::{invalidate_runtime_settings, LiquidityTier};
+use zksync_config::loader::reload_config;
+use zksync_storage::accounting::records::StoredBlockAccounting;
+use zksync_storage::admin::{
+    records::{StorageSubsidyReport, StorageSubsidyRule, StorageTokenSettings},
+    BLOCKS_REVERT_FLAG, ETH_OPERATION_DROP, ETH_OPERATION_REQUEUE, ETH_SENDER_RELOAD_FLAG,
+    ETH_SENDER_RESUBMIT_FLAG, MAINTENANCE_MODE_FLAG, TX_ACCEPTANCE_PAUSED_FLAG,
+};
+use zksync_storage::ethereum::records::StoredContractUpgrade;
+use zksync_storage::event_journal::records::StoredJournalEvent;
+use zksync_types::{
+    admin::{ContractUpgradeInfo, EthOperationInfo, MaintenanceStatus},
+    ethereum::ContractUpgradeStage,
+    gas_counter::ERC20_WITHDRAWAL_GAS_LIMIT,
+    tokens, Address, BlockNumber, TokenId, TokenLike,
+};
 use zksync_utils::panic_notify::ThreadPanicNotify;
 
+/// Maximum amount of the blocks included in a single accounting report.
+const MAX_ACCOUNTING_REPORT_BLOCKS: BlockNumber = 10_000;
+/// Default and maximum amount of the journal events delivered to the consumer at once.
+const DEFAULT_JOURNAL_EVENTS_LIMIT: u32 = 100;
+const MAX_JOURNAL_EVENTS_LIMIT: u32 = 1000;
+
 #[derive(Debug, Serialize, Deserialize)]
 struct PayloadAuthToken {
     /// Subject (whom auth token refers to).
@@ -56,6 +81,212 @@ struct AddTokenRequest {
     pub decimals: u8,
 }
 
+/// Overrides the server configuration on whether the token can be used to pay fees.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct FeeTokenRequest {
+    /// `None` makes the server configuration decide again.
+    pub allowed: Option<bool>,
+}
+
+/// Market liquidity tier of the token, illiquid tokens aren't accepted to pay fees.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct LiquidityTierRequest {
+    /// `None` resets the assessment.
+    pub tier: Option<LiquidityTier>,
+}
+
+/// Gas limit of the token withdrawal in L1, for the tokens requiring more gas than usual.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct WithdrawalGasLimitRequest {
+    /// Gas spent by the token transfer, at most `ERC20_WITHDRAWAL_GAS_LIMIT`.
+    /// `None` resets the gas limit to the default one.
+    pub gas_limit: Option<u64>,
+}
+
+/// Token symbol and decimals set manually instead of the ones declared by the token contract.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct TokenMetadataRequest {
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+/// Own secret of the prover used to sign its auth tokens instead of the shared one.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct ProverSecretRequest {
+    pub secret: String,
+}
+
+/// Rule under which the operator covers the fee of the transactions.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct SubsidyRuleRequest {
+    /// Fee type of the subsidized transactions, e.g. `Transfer`.
+    pub tx_type: String,
+    /// Token the fee is paid in, `None` matches any token.
+    pub token_id: Option<TokenId>,
+    /// Share (in percent) of the required fee the subsidy can cover.
+    pub subsidy_percent: u8,
+    /// Maximum amount (in USD) subsidized under the rule during a UTC day.
+    pub daily_budget_usd: BigDecimal,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct SubsidyRuleResponse {
+    pub id: i32,
+    pub tx_type: String,
+    pub token_id: Option<i32>,
+    pub subsidy_percent: i32,
+    pub daily_budget_usd: BigDecimal,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<StorageSubsidyRule> for SubsidyRuleResponse {
+    fn from(rule: StorageSubsidyRule) -> Self {
+        Self {
+            id: rule.id,
+            tx_type: rule.tx_type,
+            token_id: rule.token_id,
+            subsidy_percent: rule.subsidy_percent,
+            daily_budget_usd: rule.daily_budget_usd,
+            created_at: rule.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct SubsidyReportQuery {
+    /// Start of the reported period, the start of the current UTC day by default.
+    pub since: Option<DateTime<Utc>>,
+}
+
+/// Fee subsidized under a single rule in a single token.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct SubsidyReportItem {
+    pub rule_id: i32,
+    pub tx_type: String,
+    pub token_id: i32,
+    pub tx_count: i64,
+    pub subsidized_amount: BigDecimal,
+    pub subsidized_usd: BigDecimal,
+}
+
+impl From<StorageSubsidyReport> for SubsidyReportItem {
+    fn from(item: StorageSubsidyReport) -> Self {
+        Self {
+            rule_id: item.rule_id,
+            tx_type: item.tx_type,
+            token_id: item.token_id,
+            tx_count: item.tx_count,
+            subsidized_amount: item.subsidized_amount,
+            subsidized_usd: item.subsidized_usd,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct AccountingReportQuery {
+    pub from_block: BlockNumber,
+    pub to_block: BlockNumber,
+}
+
+/// Fees collected by the block compared to the ETH spent on its Ethereum transactions, in wei.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct BlockAccountingItem {
+    pub block_number: BlockNumber,
+    pub collected_fees_wei: BigDecimal,
+    pub commit_cost_wei: BigDecimal,
+    pub proof_cost_wei: BigDecimal,
+    pub execute_cost_wei: BigDecimal,
+    /// Negative if the block costs more than it collected.
+    pub margin_wei: BigDecimal,
+}
+
+impl From<StoredBlockAccounting> for BlockAccountingItem {
+    fn from(block: StoredBlockAccounting) -> Self {
+        Self {
+            block_number: block.block_number as BlockNumber,
+            collected_fees_wei: block.collected_fees_wei,
+            commit_cost_wei: block.commit_cost_wei,
+            proof_cost_wei: block.proof_cost_wei,
+            execute_cost_wei: block.execute_cost_wei,
+            margin_wei: block.margin_wei,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct AccountingReport {
+    pub blocks_count: i64,
+    pub collected_fees_wei: BigDecimal,
+    pub spent_wei: BigDecimal,
+    pub margin_wei: BigDecimal,
+    pub blocks: Vec<BlockAccountingItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct JournalEventsQuery {
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct JournalEventItem {
+    /// Offset to acknowledge once the event is processed.
+    pub id: i64,
+    pub event_type: String,
+    pub block_number: BlockNumber,
+    pub data: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<StoredJournalEvent> for JournalEventItem {
+    fn from(event: StoredJournalEvent) -> Self {
+        Self {
+            id: event.id,
+            event_type: event.event_type,
+            block_number: event.block_number as BlockNumber,
+            data: event.data,
+            created_at: event.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct JournalEvents {
+    /// ID of the last event acknowledged by the consumer.
+    pub acked_offset: i64,
+    pub events: Vec<JournalEventItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct AckEventsRequest {
+    pub offset: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct RegisterConsumerRequest {
+    /// Whether the consumer receives all the stored events, or only the ones recorded after the registration.
+    pub from_start: bool,
+}
+
+/// Converts the stored contract upgrade into its admin API representation.
+fn contract_upgrade_info(upgrade: StoredContractUpgrade) -> ContractUpgradeInfo {
+    ContractUpgradeInfo {
+        version_id: upgrade.version_id as u64,
+        stage: upgrade.stage,
+        eth_block: upgrade.eth_block as u64,
+        confirmed_at: upgrade.confirmed_at,
+    }
+}
+
+/// Settings changed at runtime through the admin API.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct SettingsResponse {
+    pub tx_acceptance_paused: bool,
+    pub maintenance_mode: bool,
+    pub tokens: Vec<StorageTokenSettings>,
+    pub withdrawal_gas_limits: HashMap<TokenId, u64>,
+    pub pinned_blocks: Vec<BlockNumber>,
+}
+
 struct AuthTokenValidator<'a> {
     decoding_key: DecodingKey<'a>,
 }
@@ -125,6 +356,877 @@ async fn add_token(
     Ok(HttpResponse::Ok().json(token))
 }
 
+fn storage_error(e: anyhow::Error) -> actix_web::Error {
+    vlog::warn!(
+        "failed to access the admin settings in progress request: {}",
+        e
+    );
+    actix_web::error::ErrorInternalServerError("storage layer error")
+}
+
+/// Ensures that the token exists, since the settings can't be stored for the unknown tokens.
+async fn check_token(
+    storage: &mut zksync_storage::StorageProcessor<'_>,
+    token_id: TokenId,
+) -> actix_web::Result<()> {
+    storage
+        .tokens_schema()
+        .get_token(TokenLike::Id(token_id))
+        .await
+        .map_err(storage_error)?
+        .ok_or_else(|| actix_web::error::ErrorNotFound("token not found"))?;
+    Ok(())
+}
+
+async fn settings(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
+    let mut storage = data.access_storage().await?;
+
+    let tx_acceptance_paused = storage
+        .admin_schema()
+        .is_flag_enabled(TX_ACCEPTANCE_PAUSED_FLAG)
+        .await
+        .map_err(storage_error)?;
+    let maintenance_mode = storage
+        .admin_schema()
+        .is_flag_enabled(MAINTENANCE_MODE_FLAG)
+        .await
+        .map_err(storage_error)?;
+    let tokens = storage
+        .admin_schema()
+        .load_token_settings()
+        .await
+        .map_err(storage_error)?;
+    let withdrawal_gas_limits = storage
+        .tokens_schema()
+        .load_withdrawal_gas_limits()
+        .await
+        .map_err(storage_error)?;
+    let pinned_blocks = storage
+        .prover_schema()
+        .load_pinned_blocks()
+        .await
+        .map_err(storage_error)?;
+
+    Ok(HttpResponse::Ok().json(SettingsResponse {
+        tx_acceptance_paused,
+        maintenance_mode,
+        tokens,
+        withdrawal_gas_limits,
+        pinned_blocks,
+    }))
+}
+
+async fn set_token_disabled(
+    data: web::Data<AppState>,
+    token_id: TokenId,
+    disabled: bool,
+) -> actix_web::Result<HttpResponse> {
+    let mut storage = data.access_storage().await?;
+    check_token(&mut storage, token_id).await?;
+
+    storage
+        .admin_schema()
+        .set_token_disabled(token_id, disabled)
+        .await
+        .map_err(storage_error)?;
+    invalidate_runtime_settings();
+
+    tracing::info!("Token {} disabled: {}", token_id, disabled);
+    Ok(HttpResponse::Ok().finish())
+}
+
+async fn disable_token(
+    data: web::Data<AppState>,
+    token_id: web::Path<TokenId>,
+) -> actix_web::Result<HttpResponse> {
+    set_token_disabled(data, token_id.into_inner(), true).await
+}
+
+async fn enable_token(
+    data: web::Data<AppState>,
+    token_id: web::Path<TokenId>,
+) -> actix_web::Result<HttpResponse> {
+    set_token_disabled(data, token_id.into_inner(), false).await
+}
+
+async fn set_fee_token(
+    data: web::Data<AppState>,
+    token_id: web::Path<TokenId>,
+    request: web::Json<FeeTokenRequest>,
+) -> actix_web::Result<HttpResponse> {
+    let token_id = token_id.into_inner();
+    let mut storage = data.access_storage().await?;
+    check_token(&mut storage, token_id).await?;
+
+    storage
+        .admin_schema()
+        .set_token_fee_allowed(token_id, request.allowed)
+        .await
+        .map_err(storage_error)?;
+    invalidate_runtime_settings();
+
+    tracing::info!("Token {} allowed for fees: {:?}", token_id, request.allowed);
+    Ok(HttpResponse::Ok().finish())
+}
+
+async fn set_liquidity_tier(
+    data: web::Data<AppState>,
+    token_id: web::Path<TokenId>,
+    request: web::Json<LiquidityTierRequest>,
+) -> actix_web::Result<HttpResponse> {
+    let token_id = token_id.into_inner();
+    let mut storage = data.access_storage().await?;
+    check_token(&mut storage, token_id).await?;
+
+    storage
+        .admin_schema()
+        .set_token_liquidity_tier(token_id, request.tier.map(LiquidityTier::as_str))
+        .await
+        .map_err(storage_error)?;
+    invalidate_runtime_settings();
+
+    tracing::info!("Token {} liquidity tier: {:?}", token_id, request.tier);
+    Ok(HttpResponse::Ok().finish())
+}
+
+async fn set_withdrawal_gas_limit(
+    data: web::Data<AppState>,
+    token_id: web::Path<TokenId>,
+    request: web::Json<WithdrawalGasLimitRequest>,
+) -> actix_web::Result<HttpResponse> {
+    let token_id = token_id.into_inner();
+    if request.gas_limit == Some(0) {
+        return Err(actix_web::error::ErrorBadRequest(
+            "gas limit must be positive",
+        ));
+    }
+    // The contract doesn't spend more gas on the transfer anyway.
+    if request.gas_limit > Some(ERC20_WITHDRAWAL_GAS_LIMIT) {
+        return Err(actix_web::error::ErrorBadRequest(format!(
+            "gas limit must not exceed the contract withdrawal gas limit {}",
+            ERC20_WITHDRAWAL_GAS_LIMIT
+        )));
+    }
+    let mut storage = data.access_storage().await?;
+    check_token(&mut storage, token_id).await?;
+
+    storage
+        .tokens_schema()
+        .set_withdrawal_gas_limit(token_id, request.gas_limit)
+        .await
+        .map_err(storage_error)?;
+
+    tracing::info!(
+        "Token {} withdrawal gas limit: {:?}",
+        token_id,
+        request.gas_limit
+    );
+    Ok(HttpResponse::Ok().finish())
+}
+
+async fn override_token_metadata(
+    data: web::Data<AppState>,
+    token_id: web::Path<TokenId>,
+    request: web::Json<TokenMetadataRequest>,
+) -> actix_web::Result<HttpResponse> {
+    let token_id = token_id.into_inner();
+    if request.symbol.trim().is_empty() {
+        return Err(actix_web::error::ErrorBadRequest(
+            "token symbol must not be empty",
+        ));
+    }
+    let mut storage = data.access_storage().await?;
+    check_token(&mut storage, token_id).await?;
+    // Tokens are looked up by symbol, so it must be unique.
+    let other_token = storage
+        .tokens_schema()
+        .get_token(TokenLike::Symbol(request.symbol.clone()))
+        .await
+        .map_err(storage_error)?
+        .filter(|other_token| other_token.id != token_id);
+    if let Some(other_token) = other_token {
+        return Err(actix_web::error::ErrorBadRequest(format!(
+            "token symbol is already used by token {}",
+            other_token.id
+        )));
+    }
+
+    storage
+        .tokens_schema()
+        .override_token_metadata(token_id, &request.symbol, request.decimals)
+        .await
+        .map_err(storage_error)?;
+
+    tracing::info!(
+        "Token {} metadata overridden: symbol {}, decimals {}",
+        token_id,
+        request.symbol,
+        request.decimals
+    );
+    Ok(HttpResponse::Ok().finish())
+}
+
+async fn reset_token_metadata_override(
+    data: web::Data<AppState>,
+    token_id: web::Path<TokenId>,
+) -> actix_web::Result<HttpResponse> {
+    let token_id = token_id.into_inner();
+    let mut storage = data.access_storage().await?;
+    check_token(&mut storage, token_id).await?;
+
+    storage
+        .tokens_schema()
+        .reset_token_metadata_override(token_id)
+        .await
+        .map_err(storage_error)?;
+
+    tracing::info!("Token {} metadata override reset", token_id);
+    Ok(HttpResponse::Ok().finish())
+}
+
+async fn reload_configuration() -> actix_web::Result<HttpResponse> {
+    let changes = reload_config().map_err(|err| {
+        tracing::warn!("Unable to reload the configuration: {}", err);
+        actix_web::error::ErrorBadRequest(err.to_string())
+    })?;
+
+    let changes: Vec<_> = changes.iter().map(ToString::to_string).collect();
+    Ok(HttpResponse::Ok().json(changes))
+}
+
+async fn set_tx_acceptance_paused(
+    data: web::Data<AppState>,
+    paused: bool,
+) -> actix_web::Result<HttpResponse> {
+    let mut storage = data.access_storage().await?;
+    storage
+        .admin_schema()
+        .set_flag(TX_ACCEPTANCE_PAUSED_FLAG, paused)
+        .await
+        .map_err(storage_error)?;
+    invalidate_runtime_settings();
+
+    tracing::info!("Transactions acceptance paused: {}", paused);
+    Ok(HttpResponse::Ok().finish())
+}
+
+async fn pause_tx_acceptance(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
+    set_tx_acceptance_paused(data, true).await
+}
+
+async fn resume_tx_acceptance(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
+    set_tx_acceptance_paused(data, false).await
+}
+
+async fn set_maintenance_mode(
+    data: web::Data<AppState>,
+    enabled: bool,
+) -> actix_web::Result<HttpResponse> {
+    let mut storage = data.access_storage().await?;
+    storage
+        .admin_schema()
+        .set_flag(MAINTENANCE_MODE_FLAG, enabled)
+        .await
+        .map_err(storage_error)?;
+    invalidate_runtime_settings();
+
+    tracing::info!("Maintenance mode enabled: {}", enabled);
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Makes the server reject the incoming transactions and seal the pending block,
+/// while `eth_sender` keeps sending the operations of the sealed blocks.
+async fn enable_maintenance_mode(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
+    set_maintenance_mode(data, true).await
+}
+
+async fn disable_maintenance_mode(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
+    set_maintenance_mode(data, false).await
+}
+
+async fn maintenance_status(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
+    let mut storage = data.access_storage().await?;
+
+    let enabled = storage
+        .admin_schema()
+        .is_flag_enabled(MAINTENANCE_MODE_FLAG)
+        .await
+        .map_err(storage_error)?;
+    let last_committed_block = storage
+        .chain()
+        .block_schema()
+        .get_last_committed_block()
+        .await
+        .map_err(storage_error)?;
+    // Pending block remains stored for a while after it's sealed.
+    let pending_block_operations = storage
+        .chain()
+        .block_schema()
+        .load_pending_block()
+        .await
+        .map_err(storage_error)?
+        .filter(|block| block.number > last_committed_block)
+        .map_or(0, |block| {
+            block.success_operations.len() + block.failed_txs.len()
+        });
+    // Transactions accepted before the maintenance mode was enabled are yet to be executed.
+    let mempool_txs = storage
+        .chain()
+        .mempool_schema()
+        .get_stats()
+        .await
+        .map_err(storage_error)?
+        .txs_count as usize;
+    let unconfirmed_eth_operations = storage
+        .ethereum_schema()
+        .load_unconfirmed_operations()
+        .await
+        .map_err(storage_error)?
+        .len();
+
+    Ok(HttpResponse::Ok().json(MaintenanceStatus {
+        enabled,
+        mempool_txs,
+        pending_block_operations,
+        unconfirmed_eth_operations,
+    }))
+}
+
+/// Makes `eth_sender` resend all the pending Ethereum transactions with the increased gas price.
+async fn resubmit_eth_txs(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
+    let mut storage = data.access_storage().await?;
+    storage
+        .admin_schema()
+        .set_flag(ETH_SENDER_RESUBMIT_FLAG, true)
+        .await
+        .map_err(storage_error)?;
+
+    tracing::info!("Resubmission of the pending Ethereum transactions requested");
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Lists the Ethereum operations sent by `eth_sender` and not confirmed yet.
+async fn eth_operations(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
+    let mut storage = data.access_storage().await?;
+    let operations: Vec<EthOperationInfo> = storage
+        .ethereum_schema()
+        .load_unconfirmed_operations()
+        .await
+        .map_err(storage_error)?
+        .into_iter()
+        .map(EthOperationInfo::from)
+        .collect();
+
+    Ok(HttpResponse::Ok().json(operations))
+}
+
+/// Stores the request for `eth_sender` to requeue or drop the unconfirmed operation.
+/// `eth_sender` checks that none of the operation transactions is mined before handling it.
+async fn request_eth_operation_action(
+    data: web::Data<AppState>,
+    eth_op_id: i64,
+    action: &str,
+) -> actix_web::Result<HttpResponse> {
+    let mut storage = data.access_storage().await?;
+    let is_unconfirmed = storage
+        .ethereum_schema()
+        .load_unconfirmed_operations()
+        .await
+        .map_err(storage_error)?
+        .iter()
+        .any(|op| op.id == eth_op_id);
+    if !is_unconfirmed {
+        return Err(actix_web::error::ErrorNotFound(
+            "unconfirmed Ethereum operation not found",
+        ));
+    }
+
+    storage
+        .admin_schema()
+        .request_eth_operation_action(eth_op_id, action)
+        .await
+        .map_err(storage_error)?;
+
+    tracing::info!("Ethereum operation {} {} requested", eth_op_id, action);
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Makes `eth_sender` forget the transactions of the latest operation and send it again.
+async fn requeue_eth_operation(
+    data: web::Data<AppState>,
+    eth_op_id: web::Path<i64>,
+) -> actix_web::Result<HttpResponse> {
+    request_eth_operation_action(data, eth_op_id.into_inner(), ETH_OPERATION_REQUEUE).await
+}
+
+/// Makes `eth_sender` stop tracking the latest operation without sending it again,
+/// the affected blocks are expected to be reverted afterwards.
+async fn drop_eth_operation(
+    data: web::Data<AppState>,
+    eth_op_id: web::Path<i64>,
+) -> actix_web::Result<HttpResponse> {
+    request_eth_operation_action(data, eth_op_id.into_inner(), ETH_OPERATION_DROP).await
+}
+
+/// Makes `eth_sender` reload the unconfirmed operations and check their confirmations again.
+async fn reload_eth_operations(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
+    let mut storage = data.access_storage().await?;
+    storage
+        .admin_schema()
+        .set_flag(ETH_SENDER_RELOAD_FLAG, true)
+        .await
+        .map_err(storage_error)?;
+
+    tracing::info!("Reload of the unconfirmed Ethereum operations requested");
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Requests the revert of the unverified blocks. The revert is performed by the server
+/// replica once it becomes the leader, before starting the actors: the leader has to be
+/// restarted (or stopped, so one of the standby replicas takes over).
+async fn revert_blocks(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
+    let mut storage = data.access_storage().await?;
+    storage
+        .admin_schema()
+        .set_flag(BLOCKS_REVERT_FLAG, true)
+        .await
+        .map_err(storage_error)?;
+
+    tracing::info!("Revert of the unverified blocks requested");
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Lists the observed upgrades of the zkSync contract.
+async fn contract_upgrades(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
+    let mut storage = data.access_storage().await?;
+    let upgrades: Vec<ContractUpgradeInfo> = storage
+        .ethereum_schema()
+        .load_contract_upgrades()
+        .await
+        .map_err(storage_error)?
+        .into_iter()
+        .map(contract_upgrade_info)
+        .collect();
+
+    Ok(HttpResponse::Ok().json(upgrades))
+}
+
+/// Confirms that the ABI used by `eth_sender` matches the upgraded contract (or the ABI
+/// of the new contract version is in place), so `eth_sender` resumes sending transactions.
+async fn confirm_contract_upgrade(
+    data: web::Data<AppState>,
+    version_id: web::Path<u64>,
+) -> actix_web::Result<HttpResponse> {
+    let version_id = version_id.into_inner();
+    let mut storage = data.access_storage().await?;
+    let upgrade = storage
+        .ethereum_schema()
+        .load_contract_upgrades()
+        .await
+        .map_err(storage_error)?
+        .into_iter()
+        .find(|upgrade| upgrade.version_id as u64 == version_id)
+        .ok_or_else(|| actix_web::error::ErrorNotFound("contract upgrade not found"))?;
+    if upgrade.stage != ContractUpgradeStage::Completed.to_string() {
+        return Err(actix_web::error::ErrorBadRequest(
+            "contract upgrade is not completed",
+        ));
+    }
+    if upgrade.confirmed_at.is_some() {
+        return Err(actix_web::error::ErrorBadRequest(
+            "contract upgrade is already confirmed",
+        ));
+    }
+
+    storage
+        .ethereum_schema()
+        .confirm_contract_upgrade(version_id)
+        .await
+        .map_err(storage_error)?;
+
+    tracing::info!("Contract upgrade to version {} is confirmed", version_id);
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Lists the provers that have not reported the stop along with their jobs, so the
+/// ones that died silently can be spotted by the last heartbeat.
+async fn provers(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
+    let mut storage = data.access_storage().await?;
+    let provers = storage
+        .prover_schema()
+        .load_provers()
+        .await
+        .map_err(storage_error)?;
+
+    Ok(HttpResponse::Ok().json(provers))
+}
+
+/// Makes the provers prove the block before any other one.
+async fn pin_block(
+    data: web::Data<AppState>,
+    block_number: web::Path<BlockNumber>,
+) -> actix_web::Result<HttpResponse> {
+    let block_number = block_number.into_inner();
+    let mut storage = data.access_storage().await?;
+    storage
+        .prover_schema()
+        .pin_block(block_number)
+        .await
+        .map_err(storage_error)?;
+
+    tracing::info!("Block {} pinned for proving", block_number);
+    Ok(HttpResponse::Ok().finish())
+}
+
+async fn unpin_block(
+    data: web::Data<AppState>,
+    block_number: web::Path<BlockNumber>,
+) -> actix_web::Result<HttpResponse> {
+    let block_number = block_number.into_inner();
+    let mut storage = data.access_storage().await?;
+    storage
+        .prover_schema()
+        .unpin_block(block_number)
+        .await
+        .map_err(storage_error)?;
+
+    tracing::info!("Block {} unpinned for proving", block_number);
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Makes the prover sign its auth tokens with its own secret instead of the shared one.
+/// The prover server picks up the change within a few seconds.
+async fn set_prover_secret(
+    data: web::Data<AppState>,
+    prover_name: web::Path<String>,
+    request: web::Json<ProverSecretRequest>,
+) -> actix_web::Result<HttpResponse> {
+    let prover_name = prover_name.into_inner();
+    if request.secret.is_empty() {
+        return Err(actix_web::error::ErrorBadRequest("empty secret"));
+    }
+
+    let mut storage = data.access_storage().await?;
+    storage
+        .prover_schema()
+        .store_prover_secret(&prover_name, &request.secret)
+        .await
+        .map_err(storage_error)?;
+
+    tracing::info!("Secret of prover '{}' set", prover_name);
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Makes the prover authenticate with the shared secret again.
+async fn remove_prover_secret(
+    data: web::Data<AppState>,
+    prover_name: web::Path<String>,
+) -> actix_web::Result<HttpResponse> {
+    let prover_name = prover_name.into_inner();
+    let mut storage = data.access_storage().await?;
+    storage
+        .prover_schema()
+        .remove_prover_secret(&prover_name)
+        .await
+        .map_err(storage_error)?;
+
+    tracing::info!("Secret of prover '{}' removed", prover_name);
+    Ok(HttpResponse::Ok().finish())
+}
+
+async fn subsidy_rules(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
+    let mut storage = data.access_storage().await?;
+    let rules: Vec<SubsidyRuleResponse> = storage
+        .admin_schema()
+        .load_subsidy_rules()
+        .await
+        .map_err(storage_error)?
+        .into_iter()
+        .map(SubsidyRuleResponse::from)
+        .collect();
+
+    Ok(HttpResponse::Ok().json(rules))
+}
+
+/// Adds the rule under which the fee of the transactions is subsidized.
+async fn add_subsidy_rule(
+    data: web::Data<AppState>,
+    request: web::Json<SubsidyRuleRequest>,
+) -> actix_web::Result<HttpResponse> {
+    let request = request.into_inner();
+    if !FEE_TYPE_NAMES.contains(&request.tx_type.as_str()) {
+        return Err(actix_web::error::ErrorBadRequest(format!(
+            "unknown tx type, expected one of {:?}",
+            FEE_TYPE_NAMES
+        )));
+    }
+    if request.subsidy_percent > 100 {
+        return Err(actix_web::error::ErrorBadRequest(
+            "subsidy can't exceed 100 percent",
+        ));
+    }
+    if request.daily_budget_usd < BigDecimal::from(0) {
+        return Err(actix_web::error::ErrorBadRequest("negative daily budget"));
+    }
+
+    let mut storage = data.access_storage().await?;
+    if let Some(token_id) = request.token_id {
+        check_token(&mut storage, token_id).await?;
+    }
+    let id = storage
+        .admin_schema()
+        .add_subsidy_rule(
+            &request.tx_type,
+            request.token_id,
+            request.subsidy_percent,
+            request.daily_budget_usd.clone(),
+        )
+        .await
+        .map_err(storage_error)?;
+
+    tracing::info!("Fee subsidy rule {} added: {:?}", id, request);
+    Ok(HttpResponse::Ok().json(id))
+}
+
+async fn remove_subsidy_rule(
+    data: web::Data<AppState>,
+    rule_id: web::Path<i32>,
+) -> actix_web::Result<HttpResponse> {
+    let rule_id = rule_id.into_inner();
+    let mut storage = data.access_storage().await?;
+    let removed = storage
+        .admin_schema()
+        .remove_subsidy_rule(rule_id)
+        .await
+        .map_err(storage_error)?;
+    if !removed {
+        return Err(actix_web::error::ErrorNotFound("subsidy rule not found"));
+    }
+
+    tracing::info!("Fee subsidy rule {} removed", rule_id);
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Reports the fee subsidized for the transactions executed since the given moment per rule and token.
+async fn subsidy_report(
+    data: web::Data<AppState>,
+    query: web::Query<SubsidyReportQuery>,
+) -> actix_web::Result<HttpResponse> {
+    let since = query.since.unwrap_or_else(|| start_of_day(Utc::now()));
+    let mut storage = data.access_storage().await?;
+    let report: Vec<SubsidyReportItem> = storage
+        .admin_schema()
+        .load_subsidy_report(since)
+        .await
+        .map_err(storage_error)?
+        .into_iter()
+        .map(SubsidyReportItem::from)
+        .collect();
+
+    Ok(HttpResponse::Ok().json(report))
+}
+
+/// Reports the fees collected by the blocks within the range against the ETH spent
+/// by `eth_sender` on their commit, proof and execute transactions.
+async fn accounting_report(
+    data: web::Data<AppState>,
+    query: web::Query<AccountingReportQuery>,
+) -> actix_web::Result<HttpResponse> {
+    let AccountingReportQuery {
+        from_block,
+        to_block,
+    } = query.into_inner();
+    if from_block > to_block {
+        return Err(actix_web::error::ErrorBadRequest(
+            "from_block can't exceed to_block",
+        ));
+    }
+    if to_block - from_block >= MAX_ACCOUNTING_REPORT_BLOCKS {
+        return Err(actix_web::error::ErrorBadRequest(format!(
+            "at most {} blocks can be reported at once",
+            MAX_ACCOUNTING_REPORT_BLOCKS
+        )));
+    }
+
+    let mut storage = data.access_storage().await?;
+    let totals = storage
+        .accounting_schema()
+        .load_accounting_totals(from_block, to_block)
+        .await
+        .map_err(storage_error)?;
+    let blocks = storage
+        .accounting_schema()
+        .load_block_accounting(from_block, to_block)
+        .await
+        .map_err(storage_error)?
+        .into_iter()
+        .map(BlockAccountingItem::from)
+        .collect();
+
+    Ok(HttpResponse::Ok().json(AccountingReport {
+        blocks_count: totals.blocks_count,
+        collected_fees_wei: totals.collected_fees_wei,
+        spent_wei: totals.spent_wei,
+        margin_wei: totals.margin_wei,
+        blocks,
+    }))
+}
+
+fn consumer_not_found() -> actix_web::Error {
+    actix_web::error::ErrorNotFound("consumer is not registered")
+}
+
+/// Registers the consumer of the journal events. Events are kept until all the registered
+/// consumers acknowledge them.
+async fn register_journal_consumer(
+    data: web::Data<AppState>,
+    consumer: web::Path<String>,
+    request: web::Json<RegisterConsumerRequest>,
+) -> actix_web::Result<HttpResponse> {
+    let consumer = consumer.into_inner();
+    if consumer.is_empty() {
+        return Err(actix_web::error::ErrorBadRequest("empty consumer name"));
+    }
+
+    let mut storage = data.access_storage().await?;
+    let acked_offset = if request.from_start {
+        0
+    } else {
+        storage
+            .event_journal_schema()
+            .get_last_event_id()
+            .await
+            .map_err(storage_error)?
+    };
+    let registered = storage
+        .event_journal_schema()
+        .register_consumer(&consumer, acked_offset)
+        .await
+        .map_err(storage_error)?;
+    if !registered {
+        return Err(actix_web::error::ErrorConflict(
+            "consumer is already registered",
+        ));
+    }
+
+    tracing::info!(
+        "Journal consumer '{}' registered with offset {}",
+        consumer,
+        acked_offset
+    );
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Removes the consumer of the journal events, so the events it didn't acknowledge are no longer kept.
+async fn remove_journal_consumer(
+    data: web::Data<AppState>,
+    consumer: web::Path<String>,
+) -> actix_web::Result<HttpResponse> {
+    let consumer = consumer.into_inner();
+    let mut storage = data.access_storage().await?;
+    let removed = storage
+        .event_journal_schema()
+        .remove_consumer(&consumer)
+        .await
+        .map_err(storage_error)?;
+    if !removed {
+        return Err(consumer_not_found());
+    }
+    remove_acked_journal_events(&mut storage).await?;
+
+    tracing::info!("Journal consumer '{}' removed", consumer);
+    Ok(HttpResponse::Ok().finish())
+}
+
+async fn remove_acked_journal_events(
+    storage: &mut zksync_storage::StorageProcessor<'_>,
+) -> actix_web::Result<()> {
+    let removed = storage
+        .event_journal_schema()
+        .remove_acked_events()
+        .await
+        .map_err(storage_error)?;
+    if removed > 0 {
+        tracing::debug!(
+            "Removed {} journal events acknowledged by all the consumers",
+            removed
+        );
+    }
+    Ok(())
+}
+
+/// Returns the journal events following the last one acknowledged by the consumer.
+/// Events are returned again and again until the consumer acknowledges them.
+async fn journal_events(
+    data: web::Data<AppState>,
+    consumer: web::Path<String>,
+    query: web::Query<JournalEventsQuery>,
+) -> actix_web::Result<HttpResponse> {
+    let consumer = consumer.into_inner();
+    let limit = query.limit.unwrap_or(DEFAULT_JOURNAL_EVENTS_LIMIT);
+    if limit == 0 || limit > MAX_JOURNAL_EVENTS_LIMIT {
+        return Err(actix_web::error::ErrorBadRequest(format!(
+            "limit must be between 1 and {}",
+            MAX_JOURNAL_EVENTS_LIMIT
+        )));
+    }
+
+    let mut storage = data.access_storage().await?;
+    let acked_offset = storage
+        .event_journal_schema()
+        .get_consumer_offset(&consumer)
+        .await
+        .map_err(storage_error)?
+        .ok_or_else(consumer_not_found)?;
+    let events = storage
+        .event_journal_schema()
+        .load_events(acked_offset, limit)
+        .await
+        .map_err(storage_error)?
+        .into_iter()
+        .map(JournalEventItem::from)
+        .collect();
+
+    Ok(HttpResponse::Ok().json(JournalEvents {
+        acked_offset,
+        events,
+    }))
+}
+
+/// Acknowledges the journal events up to the given offset, so they are no longer
+/// delivered to the consumer. Events acknowledged by all the consumers are removed.
+async fn ack_journal_events(
+    data: web::Data<AppState>,
+    consumer: web::Path<String>,
+    request: web::Json<AckEventsRequest>,
+) -> actix_web::Result<HttpResponse> {
+    let consumer = consumer.into_inner();
+    let mut storage = data.access_storage().await?;
+    let last_event_id = storage
+        .event_journal_schema()
+        .get_last_event_id()
+        .await
+        .map_err(storage_error)?;
+    if request.offset < 0 || request.offset > last_event_id {
+        return Err(actix_web::error::ErrorBadRequest(format!(
+            "offset must be between 0 and the last event ID {}",
+            last_event_id
+        )));
+    }
+
+    let acked = storage
+        .event_journal_schema()
+        .ack_events(&consumer, request.offset)
+        .await
+        .map_err(storage_error)?;
+    if !acked {
+        return Err(consumer_not_found());
+    }
+    remove_acked_journal_events(&mut storage).await?;
+
+    Ok(HttpResponse::Ok().finish())
+}
+
 async fn run_server(app_state: AppState, bind_to: SocketAddr) {
     HttpServer::new(move || {
         let auth = HttpAuthentication::bearer(move |req, credentials| async {
@@ -142,6 +1244,87 @@ async fn run_server(app_state: AppState, bind_to: SocketAddr) {
             .wrap(auth)
             .data(app_state.clone())
             .route("/tokens", web::post().to(add_token))
+            .route("/tokens/{id}/disable", web::post().to(disable_token))
+            .route("/tokens/{id}/enable", web::post().to(enable_token))
+            .route("/tokens/{id}/fee", web::put().to(set_fee_token))
+            .route(
+                "/tokens/{id}/liquidity_tier",
+                web::put().to(set_liquidity_tier),
+            )
+            .route(
+                "/tokens/{id}/withdrawal_gas_limit",
+                web::put().to(set_withdrawal_gas_limit),
+            )
+            .route(
+                "/tokens/{id}/metadata",
+                web::put().to(override_token_metadata),
+            )
+            .route(
+                "/tokens/{id}/metadata",
+                web::delete().to(reset_token_metadata_override),
+            )
+            .route("/settings", web::get().to(settings))
+            .route("/config/reload", web::post().to(reload_configuration))
+            .route("/tx_acceptance/pause", web::post().to(pause_tx_acceptance))
+            .route(
+                "/tx_acceptance/resume",
+                web::post().to(resume_tx_acceptance),
+            )
+            .route("/maintenance", web::get().to(maintenance_status))
+            .route(
+                "/maintenance/enable",
+                web::post().to(enable_maintenance_mode),
+            )
+            .route(
+                "/maintenance/disable",
+                web::post().to(disable_maintenance_mode),
+            )
+            .route("/eth_sender/resubmit", web::post().to(resubmit_eth_txs))
+            .route("/eth_sender/reload", web::post().to(reload_eth_operations))
+            .route("/eth_sender/operations", web::get().to(eth_operations))
+            .route(
+                "/eth_sender/operations/{id}/requeue",
+                web::post().to(requeue_eth_operation),
+            )
+            .route(
+                "/eth_sender/operations/{id}/drop",
+                web::post().to(drop_eth_operation),
+            )
+            .route(
+                "/eth_sender/contract_upgrades",
+                web::get().to(contract_upgrades),
+            )
+            .route(
+                "/eth_sender/contract_upgrades/{version}/confirm",
+                web::post().to(confirm_contract_upgrade),
+            )
+            .route("/blocks/revert", web::post().to(revert_blocks))
+            .route("/provers", web::get().to(provers))
+            .route("/prover/blocks/{id}/pin", web::post().to(pin_block))
+            .route("/prover/blocks/{id}/unpin", web::post().to(unpin_block))
+            .route("/provers/{name}/secret", web::put().to(set_prover_secret))
+            .route(
+                "/provers/{name}/secret",
+                web::delete().to(remove_prover_secret),
+            )
+            .route("/subsidies/rules", web::get().to(subsidy_rules))
+            .route("/subsidies/rules", web::post().to(add_subsidy_rule))
+            .route(
+                "/subsidies/rules/{id}",
+                web::delete().to(remove_subsidy_rule),
+            )
+            .route("/subsidies/report", web::get().to(subsidy_report))
+            .route("/accounting/report", web::get().to(accounting_report))
+            .route("/events/{consumer}", web::get().to(journal_events))
+            .route(
+                "/events/{consumer}",
+                web::put().to(register_journal_consumer),
+            )
+            .route(
+                "/events/{consumer}",
+                web::delete().to(remove_journal_consumer),
+            )
+            .route("/events/{consumer}/ack", web::post().to(ack_journal_events))
     })
     .workers(1)
     .bind(&bind_to)
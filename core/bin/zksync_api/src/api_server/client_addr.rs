@@ -0,0 +1,351 @@
+//! Addresses of the API clients used for the rate limiting.
+//!
+//! The client is identified by the address of the connected peer. The forwarding headers
+//! (`cf-connecting-ip`, `X-Forwarded-For`) are set by the clients at will, so they're taken
+//! into account only if the peer is one of the trusted reverse proxies.
+//!
+//! JSON-RPC servers don't report the peer address, so they listen on the loopback interface,
+//! and the public address is served by the proxy, which terminates TLS (if enabled) and sets
+//! the peer address header for every forwarded HTTP request.
+
+// Built-in uses
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::Arc,
+};
+// External uses
+use anyhow::Context;
+use futures::channel::mpsc;
+use tokio::{
+    io::{
+        AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt,
+        BufReader,
+    },
+    net::{TcpListener, TcpStream},
+};
+use tokio_rustls::TlsAcceptor;
+// Local uses
+use super::tls::{run_in_thread, TlsConfig};
+
+/// Header with the address of the peer connected to the proxy. The header sent by the client
+/// is dropped by the proxy, so the backend can rely on it.
+pub const PEER_ADDR_HEADER: &str = "x-zksync-peer-addr";
+/// Header with the client address set by Cloudflare.
+const CF_CONNECTING_IP_HEADER: &str = "cf-connecting-ip";
+/// Header with the chain of the addresses the request was forwarded from.
+const FORWARDED_FOR_HEADER: &str = "x-forwarded-for";
+/// Maximum size of the request line and headers accepted by the proxy.
+const MAX_HEADERS_SIZE: usize = 64 * 1024;
+
+/// Returns the address of the client connected from the `peer` address.
+///
+/// If the peer is one of the trusted proxies, the client address reported by it is used.
+/// Every proxy appends the address it's connected from to `X-Forwarded-For`, so the client
+/// is the closest address which doesn't belong to the trusted proxies.
+pub fn client_ip<'a>(
+    peer: IpAddr,
+    header: impl Fn(&str) -> Option<&'a str>,
+    trusted_proxies: &[IpAddr],
+) -> IpAddr {
+    if !trusted_proxies.contains(&peer) {
+        return peer;
+    }
+
+    if let Some(ip) = header(CF_CONNECTING_IP_HEADER).and_then(|value| value.trim().parse().ok()) {
+        return ip;
+    }
+    header(FORWARDED_FOR_HEADER)
+        .and_then(|value| {
+            value
+                .rsplit(',')
+                .filter_map(|ip| ip.trim().parse().ok())
+                .find(|ip| !trusted_proxies.contains(ip))
+        })
+        .unwrap_or(peer)
+}
+
+/// Length of the HTTP request body.
+enum BodyLength {
+    Fixed(u64),
+    Chunked,
+}
+
+/// Reads the line including the line break, fails if the connection is closed.
+async fn read_line<R: AsyncBufRead + Unpin>(reader: &mut R) -> anyhow::Result<Vec<u8>> {
+    let mut line = Vec::new();
+    let read = reader.read_until(b'\n', &mut line).await?;
+    anyhow::ensure!(read > 0, "Connection closed within the request");
+    Ok(line)
+}
+
+async fn copy_exact<R, W>(reader: &mut R, writer: &mut W, length: u64) -> anyhow::Result<()>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let copied = tokio::io::copy(&mut reader.take(length), writer).await?;
+    anyhow::ensure!(
+        copied == length,
+        "Connection closed within the request body"
+    );
+    Ok(())
+}
+
+/// Forwards the HTTP/1 requests from the client to the backend, replacing the peer address
+/// header of every request with the address of the client.
+///
+/// Once the connection is upgraded to another protocol (e.g. WebSocket), the rest of it
+/// is forwarded as is.
+pub async fn forward_requests<R, W>(
+    client: &mut R,
+    backend: &mut W,
+    peer: IpAddr,
+) -> anyhow::Result<()>
+where
+    R: AsyncBufRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    loop {
+        let mut head = Vec::new();
+        if client.read_until(b'\n', &mut head).await? == 0 {
+            return Ok(());
+        }
+
+        let mut body_length = BodyLength::Fixed(0);
+        let mut upgrade = false;
+        loop {
+            let line = read_line(client).await?;
+            anyhow::ensure!(
+                head.len() + line.len() <= MAX_HEADERS_SIZE,
+                "Request headers are too large"
+            );
+            if line == b"\r\n" || line == b"\n" {
+                head.extend_from_slice(format!("{}: {}\r\n", PEER_ADDR_HEADER, peer).as_bytes());
+                head.extend_from_slice(&line);
+                break;
+            }
+
+            let text = String::from_utf8_lossy(&line);
+            if let Some(separator) = text.find(':') {
+                let name = text[..separator].trim().to_ascii_lowercase();
+                let value = text[separator + 1..].trim().to_ascii_lowercase();
+                match name.as_str() {
+                    PEER_ADDR_HEADER => continue,
+                    "content-length" => {
+                        body_length =
+                            BodyLength::Fixed(value.parse().context("Invalid Content-Length")?)
+                    }
+                    "transfer-encoding" if value.contains("chunked") => {
+                        body_length = BodyLength::Chunked
+                    }
+                    "upgrade" => upgrade = true,
+                    _ => {}
+                }
+            }
+            head.extend_from_slice(&line);
+        }
+        backend.write_all(&head).await?;
+
+        if upgrade {
+            tokio::io::copy(client, backend).await?;
+            return Ok(());
+        }
+
+        match body_length {
+            BodyLength::Fixed(length) => copy_exact(client, backend, length).await?,
+            BodyLength::Chunked => loop {
+                let size_line = read_line(client).await?;
+                backend.write_all(&size_line).await?;
+                let size = String::from_utf8_lossy(&size_line);
+                let size = size.split(';').next().unwrap_or_default().trim();
+                let size = u64::from_str_radix(size, 16).context("Invalid chunk size")?;
+
+                if size == 0 {
+                    // Trailer headers are terminated by the empty line.
+                    loop {
+                        let line = read_line(client).await?;
+                        backend.write_all(&line).await?;
+                        if line == b"\r\n" || line == b"\n" {
+                            break;
+                        }
+                    }
+                    break;
+                }
+                // Chunk data is followed by the line break.
+                copy_exact(client, backend, size + 2).await?;
+            },
+        }
+    }
+}
+
+async fn proxy_connection<S>(
+    client: S,
+    peer: IpAddr,
+    backend_addr: SocketAddr,
+) -> anyhow::Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let backend = TcpStream::connect(backend_addr).await?;
+
+    let (client_read, mut client_write) = tokio::io::split(client);
+    let (mut backend_read, mut backend_write) = tokio::io::split(backend);
+    let to_backend = async {
+        forward_requests(&mut BufReader::new(client_read), &mut backend_write, peer).await?;
+        backend_write.shutdown().await?;
+        Ok::<_, anyhow::Error>(())
+    };
+    let to_client = async {
+        tokio::io::copy(&mut backend_read, &mut client_write).await?;
+        client_write.shutdown().await?;
+        Ok::<_, anyhow::Error>(())
+    };
+    futures::future::try_join(to_backend, to_client).await?;
+
+    Ok(())
+}
+
+/// Returns the address the JSON-RPC server proxied by `start_rpc_proxy` should listen on.
+pub fn backend_address() -> SocketAddr {
+    (Ipv4Addr::LOCALHOST, 0).into()
+}
+
+/// Accepts the connections on the public address and forwards them to the JSON-RPC server
+/// along with the peer addresses. TLS is terminated by the proxy if it's configured.
+pub fn start_rpc_proxy(
+    name: &str,
+    tls: Option<&TlsConfig>,
+    public_addr: SocketAddr,
+    backend_addr: SocketAddr,
+    panic_notify: mpsc::Sender<bool>,
+) {
+    let acceptor = tls.map(|tls| TlsAcceptor::from(Arc::new(tls.server_config())));
+
+    run_in_thread(name, panic_notify, async move {
+        let mut listener = TcpListener::bind(public_addr)
+            .await
+            .expect("Unable to bind JSON-RPC proxy");
+
+        loop {
+            let (stream, peer) = match listener.accept().await {
+                Ok(connection) => connection,
+                Err(err) => {
+                    vlog::warn!("Unable to accept JSON-RPC connection: {}", err);
+                    continue;
+                }
+            };
+
+            let acceptor = acceptor.clone();
+            tokio::spawn(async move {
+                let result = match acceptor {
+                    Some(acceptor) => match acceptor.accept(stream).await {
+                        Ok(client) => proxy_connection(client, peer.ip(), backend_addr).await,
+                        Err(err) => Err(err.into()),
+                    },
+                    None => proxy_connection(stream, peer.ip(), backend_addr).await,
+                };
+                if let Err(err) = result {
+                    tracing::debug!("JSON-RPC connection closed with error: {}", err);
+                }
+            });
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    const PEER: [u8; 4] = [192, 168, 0, 1];
+    const PROXY: [u8; 4] = [10, 0, 0, 1];
+
+    fn resolve(peer: [u8; 4], headers: &[(&str, &str)]) -> IpAddr {
+        let headers: HashMap<_, _> = headers.iter().copied().collect();
+        client_ip(
+            peer.into(),
+            |name| headers.get(name).copied(),
+            &[PROXY.into()],
+        )
+    }
+
+    #[test]
+    fn forwarding_headers_are_trusted_only_from_proxies() {
+        let forwarded = [(FORWARDED_FOR_HEADER, "1.1.1.1")];
+        assert_eq!(resolve(PEER, &forwarded), IpAddr::from(PEER));
+        assert_eq!(resolve(PEER, &[]), IpAddr::from(PEER));
+        assert_eq!(resolve(PROXY, &forwarded), IpAddr::from([1, 1, 1, 1]));
+        // Proxy doesn't report the client.
+        assert_eq!(resolve(PROXY, &[]), IpAddr::from(PROXY));
+
+        // The addresses prepended by the client are ignored.
+        let spoofed = [(FORWARDED_FOR_HEADER, "2.2.2.2, 1.1.1.1, 10.0.0.1")];
+        assert_eq!(resolve(PROXY, &spoofed), IpAddr::from([1, 1, 1, 1]));
+
+        let cloudflare = [(CF_CONNECTING_IP_HEADER, "3.3.3.3"), forwarded[0]];
+        assert_eq!(resolve(PROXY, &cloudflare), IpAddr::from([3, 3, 3, 3]));
+        assert_eq!(resolve(PEER, &cloudflare), IpAddr::from(PEER));
+    }
+
+    async fn forward(input: &str) -> anyhow::Result<String> {
+        let mut backend = Vec::new();
+        forward_requests(&mut input.as_bytes(), &mut backend, PEER.into()).await?;
+        Ok(String::from_utf8(backend).unwrap())
+    }
+
+    #[tokio::test]
+    async fn peer_header_is_set_for_every_request() {
+        let input =
+            "POST / HTTP/1.1\r\nX-Zksync-Peer-Addr: 1.1.1.1\r\nContent-Length: 4\r\n\r\nbody\
+                     POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n\
+                     2\r\nab\r\n0\r\n\r\n\
+                     GET /ws HTTP/1.1\r\nUpgrade: websocket\r\n\r\nraw";
+        let expected = "POST / HTTP/1.1\r\nContent-Length: 4\r\nx-zksync-peer-addr: 192.168.0.1\r\n\r\nbody\
+                        POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\nx-zksync-peer-addr: 192.168.0.1\r\n\r\n\
+                        2\r\nab\r\n0\r\n\r\n\
+                        GET /ws HTTP/1.1\r\nUpgrade: websocket\r\nx-zksync-peer-addr: 192.168.0.1\r\n\r\nraw";
+        assert_eq!(forward(input).await.unwrap(), expected);
+    }
+
+    /// Checks that the backend sees the address of the client connected to the proxy
+    /// rather than the address of the proxy itself.
+    #[tokio::test]
+    async fn proxy_passes_peer_address() {
+        let mut backend = TcpListener::bind(backend_address()).await.unwrap();
+        let backend_addr = backend.local_addr().unwrap();
+        let mut public = TcpListener::bind(backend_address()).await.unwrap();
+        let mut client = TcpStream::connect(public.local_addr().unwrap())
+            .await
+            .unwrap();
+        let (stream, _) = public.accept().await.unwrap();
+        let proxy = tokio::spawn(proxy_connection(stream, PEER.into(), backend_addr));
+
+        client
+            .write_all(b"GET / HTTP/1.1\r\nX-Zksync-Peer-Addr: 127.0.0.1\r\n\r\n")
+            .await
+            .unwrap();
+        client.shutdown().await.unwrap();
+
+        let (mut backend_stream, _) = backend.accept().await.unwrap();
+        let mut request = String::new();
+        backend_stream.read_to_string(&mut request).await.unwrap();
+        assert_eq!(
+            request,
+            "GET / HTTP/1.1\r\nx-zksync-peer-addr: 192.168.0.1\r\n\r\n"
+        );
+
+        drop(backend_stream);
+        proxy.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn truncated_requests_are_rejected() {
+        assert!(forward("POST / HTTP/1.1\r\nContent-Length: 10\r\n\r\nbody")
+            .await
+            .is_err());
+        assert!(forward("POST / HTTP/1.1\r\nContent-Length").await.is_err());
+        assert!(forward("POST / HTTP/1.1\r\nContent-Length: x\r\n\r\n")
+            .await
+            .is_err());
+    }
+}
@@ -1,11 +1,16 @@
-use super::ExecutedOps;
+use super::{AcknowledgedDeposit, CompletedWithdrawal, ExecutedOps};
+use crate::api_server::rpc_server::types::{DepositAcknowledgment, WithdrawalStatusResp};
+use crate::utils::account_id_cache::AccountIdCache;
 use futures::{channel::mpsc, SinkExt};
 use std::time::{Duration, Instant};
 use zksync_storage::ConnectionPool;
 use zksync_types::{
-    block::ExecutedOperations, block::PendingBlock, ActionType, BlockNumber, Operation,
+    block::ExecutedOperations, block::PendingBlock, ActionType, Address, BlockNumber, Operation,
 };
 
+/// Maximum amount of the deposit acknowledgments loaded at once.
+const ACKNOWLEDGMENTS_BATCH_SIZE: u32 = 1000;
+
 /// Simple awaiter for the database futures, which will add a log entry upon DB failure
 /// and execute `on_exit` statement.
 macro_rules! await_db {
@@ -13,7 +18,7 @@ macro_rules! await_db {
         match $e.await {
             Ok(res) => res,
             Err(err) => {
-                log::warn!("Unable to connect to the database: {}", err);
+                tracing::warn!("Unable to connect to the database: {}", err);
                 $on_exit
             }
         };
@@ -21,40 +26,55 @@ macro_rules! await_db {
 }
 
 /// Event fetcher is an actor which polls the database from time to time in order to see
-/// whether new blocks were committed or verified.
+/// whether new blocks were committed or verified, new deposits were acknowledged or
+/// withdrawals were completed.
 ///
 /// Once tha new data is available, it is sent to the `OperationNotifier`, which broadcasts it
-/// to the subscribers.
+/// to the subscribers. The accounts created by the executed operations are reported
+/// to the `AccountIdCache`.
 #[derive(Debug)]
 pub struct EventFetcher {
     miniblock_interval: Duration,
     db_pool: ConnectionPool,
+    account_ids: AccountIdCache,
 
     last_committed_block: BlockNumber,
     last_verified_block: BlockNumber,
     pending_block: Option<PendingBlock>,
+    last_acknowledgment_id: i64,
+    last_completed_withdrawal_block: BlockNumber,
 
     operations_sender: mpsc::Sender<Operation>,
     txs_sender: mpsc::Sender<ExecutedOps>,
+    acknowledgments_sender: mpsc::Sender<AcknowledgedDeposit>,
+    withdrawals_sender: mpsc::Sender<CompletedWithdrawal>,
 }
 
 impl EventFetcher {
     pub async fn new(
         db_pool: ConnectionPool,
         miniblock_interval: Duration,
+        account_ids: AccountIdCache,
         operations_sender: mpsc::Sender<Operation>,
         txs_sender: mpsc::Sender<ExecutedOps>,
+        acknowledgments_sender: mpsc::Sender<AcknowledgedDeposit>,
+        withdrawals_sender: mpsc::Sender<CompletedWithdrawal>,
     ) -> anyhow::Result<Self> {
         let mut fetcher = EventFetcher {
             miniblock_interval,
             db_pool,
+            account_ids,
 
             last_committed_block: 0,
             last_verified_block: 0,
             pending_block: None,
+            last_acknowledgment_id: 0,
+            last_completed_withdrawal_block: 0,
 
             operations_sender,
             txs_sender,
+            acknowledgments_sender,
+            withdrawals_sender,
         };
 
         let pending_block = fetcher.load_pending_block().await?;
@@ -63,6 +83,9 @@ impl EventFetcher {
 
         fetcher.last_committed_block = last_committed_block;
         fetcher.last_verified_block = last_verified_block;
+        // Only the deposits acknowledged and the withdrawals completed after the start are reported.
+        fetcher.last_acknowledgment_id = fetcher.last_acknowledgment_id().await?;
+        fetcher.last_completed_withdrawal_block = fetcher.last_completed_withdrawal_block().await?;
         if let Some(block) = pending_block {
             // We only want to set this field if the pending block is actually the latest block (ahead of last committed one).
             if block.number > fetcher.last_committed_block {
@@ -108,9 +131,30 @@ impl EventFetcher {
             if let Some(pending_block) = pending_block {
                 // We're only interested in the pending blocks **newer** than the last committed blocks;
                 if let Some(executed_ops) = self.update_pending_block(pending_block) {
+                    self.account_ids
+                        .handle_executed_ops(&executed_ops.operations)
+                        .await;
                     self.txs_sender.send(executed_ops).await.unwrap_or_default();
                 }
             }
+
+            // 4. Report the newly acknowledged deposits.
+            let acknowledgments = await_db!(self.load_new_acknowledgments(), continue);
+            for acknowledgment in acknowledgments {
+                self.acknowledgments_sender
+                    .send(acknowledgment)
+                    .await
+                    .unwrap_or_default();
+            }
+
+            // 5. Report the withdrawals completed on Ethereum.
+            let withdrawals = await_db!(self.load_completed_withdrawals(), continue);
+            for withdrawal in withdrawals {
+                self.withdrawals_sender
+                    .send(withdrawal)
+                    .await
+                    .unwrap_or_default();
+            }
         }
     }
 
@@ -174,6 +218,12 @@ impl EventFetcher {
         // There may be more than one block in the gap.
         for block_idx in (current_last_block + 1)..=new_last_operation {
             let operation = await_db!(self.load_operation(block_idx, action), continue);
+            if action == ActionType::COMMIT {
+                // Blocks may be sealed before the pending block with their operations is seen.
+                self.account_ids
+                    .handle_executed_ops(&operation.block.block_transactions)
+                    .await;
+            }
             self.operations_sender
                 .send(operation)
                 .await
@@ -231,6 +281,95 @@ impl EventFetcher {
         Ok(last_block)
     }
 
+    async fn last_acknowledgment_id(&mut self) -> anyhow::Result<i64> {
+        let mut storage = self
+            .db_pool
+            .access_storage()
+            .await
+            .expect("Can't get access to the storage");
+
+        storage
+            .chain()
+            .operations_schema()
+            .get_last_priority_op_acknowledgment_id()
+            .await
+    }
+
+    async fn load_new_acknowledgments(&mut self) -> anyhow::Result<Vec<AcknowledgedDeposit>> {
+        let start = Instant::now();
+        let mut storage = self
+            .db_pool
+            .access_storage()
+            .await
+            .expect("Can't get access to the storage");
+
+        let acknowledgments = storage
+            .chain()
+            .operations_schema()
+            .load_priority_op_acknowledgments(
+                self.last_acknowledgment_id,
+                ACKNOWLEDGMENTS_BATCH_SIZE,
+            )
+            .await?;
+        if let Some(last) = acknowledgments.last() {
+            self.last_acknowledgment_id = last.id;
+        }
+
+        metrics::histogram!(
+            "api.event_fetcher.load_new_acknowledgments",
+            start.elapsed()
+        );
+        Ok(acknowledgments
+            .into_iter()
+            .map(|stored| AcknowledgedDeposit {
+                address: Address::from_slice(&stored.address),
+                acknowledgment: DepositAcknowledgment::from(stored),
+            })
+            .collect())
+    }
+
+    async fn last_completed_withdrawal_block(&mut self) -> anyhow::Result<BlockNumber> {
+        let mut storage = self
+            .db_pool
+            .access_storage()
+            .await
+            .expect("Can't get access to the storage");
+
+        storage
+            .withdrawals_schema()
+            .get_last_completed_withdrawal_block()
+            .await
+    }
+
+    async fn load_completed_withdrawals(&mut self) -> anyhow::Result<Vec<CompletedWithdrawal>> {
+        let start = Instant::now();
+        let mut storage = self
+            .db_pool
+            .access_storage()
+            .await
+            .expect("Can't get access to the storage");
+
+        let withdrawals = storage
+            .withdrawals_schema()
+            .load_completed_withdrawals(self.last_completed_withdrawal_block)
+            .await?;
+        if let Some(last) = withdrawals.last() {
+            self.last_completed_withdrawal_block = last.block_number as BlockNumber;
+        }
+
+        metrics::histogram!(
+            "api.event_fetcher.load_completed_withdrawals",
+            start.elapsed()
+        );
+        Ok(withdrawals
+            .into_iter()
+            .map(|stored| CompletedWithdrawal {
+                address: Address::from_slice(&stored.address),
+                status: WithdrawalStatusResp::new(stored, self.last_verified_block),
+            })
+            .collect())
+    }
+
     async fn load_operation(
         &mut self,
         block_number: BlockNumber,
@@ -1,5 +1,12 @@
-use super::rpc_server::types::{ETHOpInfoResp, ResponseAccountState, TransactionInfoResp};
-use futures::{channel::mpsc, select, stream::StreamExt};
+use super::rpc_server::types::{
+    AccountEvent, DepositAcknowledgment, ETHOpInfoResp, ResponseAccountState, TransactionInfoResp,
+    TxStatusEvent, WithdrawalStatusResp,
+};
+use futures::{
+    channel::{mpsc, oneshot},
+    select,
+    stream::StreamExt,
+};
 use jsonrpc_pubsub::{
     typed::{Sink, Subscriber},
     SubscriptionId,
@@ -11,10 +18,12 @@ use zksync_types::BlockNumber;
 use zksync_types::{block::ExecutedOperations, ActionType, Address};
 
 use self::{event_fetcher::EventFetcher, operation_notifier::OperationNotifier};
+use crate::utils::account_id_cache::AccountIdCache;
 
 mod event_fetcher;
 mod operation_notifier;
 mod state;
+mod stream_sub_store;
 mod sub_store;
 
 const NOTIFIER_CHANNEL_CAPACITY: usize = 32_768;
@@ -25,6 +34,21 @@ pub struct ExecutedOps {
     pub block_number: BlockNumber,
 }
 
+/// Deposit acknowledged by the Ethereum watcher, along with the recipient address.
+#[derive(Debug)]
+pub struct AcknowledgedDeposit {
+    pub address: Address,
+    pub acknowledgment: DepositAcknowledgment,
+}
+
+/// Withdrawal completed on Ethereum, along with the address of the account
+/// the funds were withdrawn from.
+#[derive(Debug)]
+pub struct CompletedWithdrawal {
+    pub address: Address,
+    pub status: WithdrawalStatusResp,
+}
+
 pub enum EventSubscribeRequest {
     Transaction {
         hash: TxHash,
@@ -41,6 +65,23 @@ pub enum EventSubscribeRequest {
         action: ActionType,
         subscriber: Subscriber<ResponseAccountState>,
     },
+    /// Subscription to every status change of the transaction until it's verified or rejected.
+    TxStatus {
+        hash: TxHash,
+        subscriber: Subscriber<TxStatusEvent>,
+    },
+    /// Subscription to every event of the account.
+    AccountEvents {
+        address: Address,
+        subscriber: Subscriber<AccountEvent>,
+    },
+    /// One-time request to report the transaction once it reaches the given state.
+    /// Rejected transactions are reported regardless of the requested state.
+    WaitTransaction {
+        hash: TxHash,
+        action: ActionType,
+        response: oneshot::Sender<TransactionInfoResp>,
+    },
 }
 
 pub enum EventNotifierRequest {
@@ -57,11 +98,15 @@ struct SubscriptionSender<T> {
 pub fn start_sub_notifier(
     db_pool: ConnectionPool,
     mut subscription_stream: mpsc::Receiver<EventNotifierRequest>,
+    account_ids: AccountIdCache,
     api_requests_caches_size: usize,
     miniblock_interval: Duration,
 ) -> tokio::task::JoinHandle<()> {
     let (new_block_sender, mut new_block_receiver) = mpsc::channel(NOTIFIER_CHANNEL_CAPACITY);
     let (new_txs_sender, mut new_txs_receiver) = mpsc::channel(NOTIFIER_CHANNEL_CAPACITY);
+    let (acknowledgments_sender, mut acknowledgments_receiver) =
+        mpsc::channel(NOTIFIER_CHANNEL_CAPACITY);
+    let (withdrawals_sender, mut withdrawals_receiver) = mpsc::channel(NOTIFIER_CHANNEL_CAPACITY);
 
     let mut notifier = OperationNotifier::new(api_requests_caches_size, db_pool.clone());
 
@@ -69,8 +114,11 @@ pub fn start_sub_notifier(
         let fetcher = EventFetcher::new(
             db_pool,
             miniblock_interval,
+            account_ids,
             new_block_sender,
             new_txs_sender,
+            acknowledgments_sender,
+            withdrawals_sender,
         )
         .await
         .expect("Unable to create event fetcher");
@@ -83,22 +131,32 @@ pub fn start_sub_notifier(
                     if let Some(new_block) = new_block {
                         notifier.handle_new_block(new_block)
                             .await
-                            .map_err(|e| log::warn!("Failed to handle new block: {}",e))
+                            .map_err(|e| tracing::warn!("Failed to handle new block: {}",e))
                             .unwrap_or_default();
                     }
                 },
                 new_exec_batch = new_txs_receiver.next() => {
                     if let Some(new_exec_batch) = new_exec_batch {
                         notifier.handle_new_executed_batch(new_exec_batch)
-                            .map_err(|e| log::warn!("Failed to handle new exec batch: {}",e))
+                            .map_err(|e| tracing::warn!("Failed to handle new exec batch: {}",e))
                             .unwrap_or_default();
                     }
                 },
+                acknowledgment = acknowledgments_receiver.next() => {
+                    if let Some(acknowledgment) = acknowledgment {
+                        notifier.handle_deposit_acknowledgment(acknowledgment);
+                    }
+                },
+                withdrawal = withdrawals_receiver.next() => {
+                    if let Some(withdrawal) = withdrawal {
+                        notifier.handle_completed_withdrawal(withdrawal);
+                    }
+                },
                 new_sub = subscription_stream.next() => {
                     if let Some(new_sub) = new_sub {
                         notifier.handle_notify_req(new_sub)
                             .await
-                            .map_err(|e| log::warn!("Failed to handle notify request: {}",e))
+                            .map_err(|e| tracing::warn!("Failed to handle notify request: {}",e))
                             .unwrap_or_default();
                     }
                 },
@@ -1,24 +1,64 @@
 use crate::api_server::rpc_server::types::{
-    BlockInfo, ETHOpInfoResp, ResponseAccountState, TransactionInfoResp,
+    AccountEvent, BlockInfo, ETHOpInfoResp, ResponseAccountState, TransactionInfoResp, TxStatus,
+    TxStatusEvent,
 };
+use futures::channel::oneshot;
 use jsonrpc_pubsub::{typed::Subscriber, SubscriptionId};
-use std::time::Instant;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
 use zksync_storage::ConnectionPool;
 use zksync_types::tx::TxHash;
 use zksync_types::BlockNumber;
-use zksync_types::{block::ExecutedOperations, AccountId, ActionType, Address, Operation};
+use zksync_types::{
+    block::ExecutedOperations, operations::DepositOp, AccountId, ActionType, Address, Operation,
+    ZkSyncOp,
+};
 
 use super::{
-    state::NotifierState, sub_store::SubStorage, EventNotifierRequest, EventSubscribeRequest,
+    state::NotifierState, stream_sub_store::StreamSubStorage, sub_store::SubStorage,
+    AcknowledgedDeposit, CompletedWithdrawal, EventNotifierRequest, EventSubscribeRequest,
     ExecutedOps,
 };
 
+/// Maximum amount of the requests waiting for the transactions at once.
+const MAX_TX_WAITERS: usize = 10_000;
+/// Waiting requests are removed after this time, even if the client still waits for the response.
+/// Must be longer than the maximum wait timeout of the `wait_for_tx` RPC method.
+const TX_WAITER_TTL: Duration = Duration::from_secs(120);
+
+/// One-time request waiting for the transaction to reach the state of `action`.
+struct TxWaiter {
+    action: ActionType,
+    response: oneshot::Sender<TransactionInfoResp>,
+    created_at: Instant,
+}
+
+impl TxWaiter {
+    fn is_stale(&self) -> bool {
+        self.response.is_canceled() || self.created_at.elapsed() >= TX_WAITER_TTL
+    }
+}
+
 pub struct OperationNotifier {
     state: NotifierState,
 
     tx_subs: SubStorage<TxHash, TransactionInfoResp>,
     prior_op_subs: SubStorage<u64, ETHOpInfoResp>,
     account_subs: SubStorage<AccountId, ResponseAccountState>,
+
+    tx_status_subs: StreamSubStorage<TxHash, TxStatusEvent>,
+    /// Last status reported to the subscribers of the transaction.
+    tx_statuses: HashMap<TxHash, TxStatus>,
+    account_event_subs: StreamSubStorage<Address, AccountEvent>,
+    /// Known IDs of the accounts having event subscribers.
+    account_event_ids: HashMap<AccountId, Address>,
+    /// Serial ID of the last deposit reported to the subscribers of the account.
+    last_notified_deposits: HashMap<Address, u64>,
+
+    /// Pending one-time requests waiting for the transaction to reach the given state.
+    tx_waiters: HashMap<TxHash, Vec<TxWaiter>>,
+    /// Total amount of the requests in `tx_waiters`.
+    tx_waiters_count: usize,
 }
 
 impl OperationNotifier {
@@ -28,6 +68,13 @@ impl OperationNotifier {
             tx_subs: SubStorage::new(),
             prior_op_subs: SubStorage::new(),
             account_subs: SubStorage::new(),
+            tx_status_subs: StreamSubStorage::new("txstatussub"),
+            tx_statuses: HashMap::new(),
+            account_event_subs: StreamSubStorage::new("accevsub"),
+            account_event_ids: HashMap::new(),
+            last_notified_deposits: HashMap::new(),
+            tx_waiters: HashMap::new(),
+            tx_waiters_count: 0,
         }
     }
 
@@ -59,6 +106,18 @@ impl OperationNotifier {
                     self.add_account_update_sub(address, action, subscriber)
                         .await
                 }
+                EventSubscribeRequest::TxStatus { hash, subscriber } => {
+                    self.add_tx_status_sub(hash, subscriber).await
+                }
+                EventSubscribeRequest::AccountEvents {
+                    address,
+                    subscriber,
+                } => self.add_account_events_sub(address, subscriber).await,
+                EventSubscribeRequest::WaitTransaction {
+                    hash,
+                    action,
+                    response,
+                } => self.add_tx_waiter(hash, action, response).await,
             }
             .map_err(|e| anyhow::format_err!("Failed to add sub: {}", e)),
             EventNotifierRequest::Unsub(sub_id) => self
@@ -86,12 +145,12 @@ impl OperationNotifier {
             .flatten()
             .collect();
 
-        for id in updated_accounts {
+        for &id in &updated_accounts {
             if self.account_subs.subscriber_exists(id, action) {
                 let account_state = match self.state.get_account_state(id, action).await? {
                     Some(account_state) => account_state,
                     None => {
-                        log::warn!(
+                        tracing::warn!(
                             "Account is updated but not stored in DB, id: {}, block: {:#?}",
                             id,
                             op.block
@@ -104,6 +163,30 @@ impl OperationNotifier {
             }
         }
 
+        let mut balance_changed: Vec<(AccountId, Address)> = updated_accounts
+            .into_iter()
+            .filter_map(|id| {
+                self.account_event_ids
+                    .get(&id)
+                    .map(|&address| (id, address))
+            })
+            .collect();
+        balance_changed.sort_unstable();
+        balance_changed.dedup();
+        for (id, address) in balance_changed {
+            if let Some(state) = self.state.get_account_state(id, action).await? {
+                let event = AccountEvent::BalanceChanged {
+                    block_number: op.block.block_number,
+                    verified: action == ActionType::VERIFY,
+                    state,
+                };
+                self.account_event_subs.notify(&address, event);
+            }
+        }
+
+        // Requests for the transactions which are never executed would stay forever otherwise.
+        self.remove_stale_tx_waiters();
+
         metrics::histogram!("api.notifier.handle_new_block", start.elapsed());
         Ok(())
     }
@@ -120,6 +203,22 @@ impl OperationNotifier {
             match tx {
                 ExecutedOperations::Tx(tx) => {
                     let hash = tx.signed_tx.hash();
+                    let status = if !tx.success {
+                        TxStatus::Rejected
+                    } else if action == ActionType::VERIFY {
+                        TxStatus::Verified
+                    } else {
+                        TxStatus::Committed
+                    };
+                    self.notify_tx_status(
+                        hash,
+                        TxStatusEvent {
+                            status,
+                            block_number: Some(block_number),
+                            fail_reason: tx.fail_reason.clone(),
+                        },
+                    );
+
                     let resp = TransactionInfoResp {
                         executed: true,
                         success: Some(tx.success),
@@ -130,10 +229,16 @@ impl OperationNotifier {
                             verified: action == ActionType::VERIFY,
                         }),
                     };
+                    self.notify_tx_waiters(hash, action, &resp);
                     self.tx_subs.notify(hash, action, resp);
                 }
                 ExecutedOperations::PriorityOp(prior_op) => {
                     let id = prior_op.priority_op.serial_id;
+                    if action == ActionType::COMMIT {
+                        if let ZkSyncOp::Deposit(deposit) = &prior_op.op {
+                            self.notify_deposit(id, block_number, deposit);
+                        }
+                    }
                     let resp = ETHOpInfoResp {
                         executed: true,
                         block: Some(BlockInfo {
@@ -141,6 +246,7 @@ impl OperationNotifier {
                             committed: true,
                             verified: action == ActionType::VERIFY,
                         }),
+                        acknowledgment: None,
                     };
                     self.prior_op_subs.notify(id, action, resp);
                 }
@@ -150,6 +256,109 @@ impl OperationNotifier {
         Ok(())
     }
 
+    /// Reports the new transaction status, unless it was already reported.
+    /// Subscriptions are removed once the final status is reached.
+    fn notify_tx_status(&mut self, hash: TxHash, event: TxStatusEvent) {
+        if !self.tx_status_subs.subscriber_exists(&hash) {
+            return;
+        }
+        if let Some(&last_status) = self.tx_statuses.get(&hash) {
+            if last_status >= event.status {
+                return;
+            }
+        }
+
+        match event.status {
+            TxStatus::Verified | TxStatus::Rejected => {
+                self.tx_status_subs.notify_last(&hash, event);
+                self.tx_statuses.remove(&hash);
+            }
+            TxStatus::Queued | TxStatus::Committed => {
+                self.tx_statuses.insert(hash, event.status);
+                self.tx_status_subs.notify(&hash, event);
+            }
+        }
+    }
+
+    /// Responds to the requests waiting for the transaction to reach the state of `action`.
+    fn notify_tx_waiters(&mut self, hash: TxHash, action: ActionType, resp: &TransactionInfoResp) {
+        let waiters = match self.tx_waiters.remove(&hash) {
+            Some(waiters) => waiters,
+            None => return,
+        };
+        self.tx_waiters_count -= waiters.len();
+
+        // Rejected transaction won't ever be verified, so all the waiters are notified.
+        let rejected = resp.success == Some(false);
+        let pending: Vec<_> = waiters
+            .into_iter()
+            .filter_map(|waiter| {
+                if rejected || waiter.action <= action {
+                    waiter.response.send(resp.clone()).unwrap_or_default();
+                    None
+                } else {
+                    Some(waiter)
+                }
+            })
+            .filter(|waiter| !waiter.is_stale())
+            .collect();
+
+        if !pending.is_empty() {
+            self.tx_waiters_count += pending.len();
+            self.tx_waiters.insert(hash, pending);
+        }
+    }
+
+    /// Removes the requests which are timed out or cancelled by the client.
+    fn remove_stale_tx_waiters(&mut self) {
+        let mut count = 0;
+        self.tx_waiters.retain(|_, waiters| {
+            waiters.retain(|waiter| !waiter.is_stale());
+            count += waiters.len();
+            !waiters.is_empty()
+        });
+        self.tx_waiters_count = count;
+        metrics::gauge!("api.notifier.tx_waiters", count as f64);
+    }
+
+    /// Reports the executed deposit to the subscribers of the recipient account.
+    fn notify_deposit(&mut self, serial_id: u64, block_number: BlockNumber, deposit: &DepositOp) {
+        let address = deposit.priority_op.to;
+        if !self.account_event_subs.subscriber_exists(&address) {
+            return;
+        }
+        // Account may be created by the deposit itself.
+        self.account_event_ids.insert(deposit.account_id, address);
+
+        // Deposit is reported both for the pending and the committed block.
+        if let Some(&last_serial_id) = self.last_notified_deposits.get(&address) {
+            if last_serial_id >= serial_id {
+                return;
+            }
+        }
+        self.last_notified_deposits.insert(address, serial_id);
+
+        let event = AccountEvent::DepositReceived {
+            serial_id,
+            block_number,
+            token: deposit.priority_op.token,
+            amount: deposit.priority_op.amount.clone().into(),
+        };
+        self.account_event_subs.notify(&address, event);
+    }
+
+    /// Reports the deposit seen on Ethereum to the subscribers of the recipient account.
+    pub fn handle_deposit_acknowledgment(&self, deposit: AcknowledgedDeposit) {
+        let event = AccountEvent::DepositAcknowledged(deposit.acknowledgment);
+        self.account_event_subs.notify(&deposit.address, event);
+    }
+
+    /// Reports the withdrawal completed on Ethereum to the subscribers of the account.
+    pub fn handle_completed_withdrawal(&self, withdrawal: CompletedWithdrawal) {
+        let event = AccountEvent::WithdrawalCompleted(withdrawal.status);
+        self.account_event_subs.notify(&withdrawal.address, event);
+    }
+
     /// More convenient alias for `handle_executed_operations`.
     pub fn handle_new_executed_batch(
         &mut self,
@@ -164,6 +373,22 @@ impl OperationNotifier {
 
     /// Removes provided subscription from the list.
     fn handle_unsub(&mut self, sub_id: SubscriptionId) -> Result<(), anyhow::Error> {
+        // Stream subscription IDs have a different format, so they're checked first.
+        if let Some(hash) = self.tx_status_subs.remove(&sub_id) {
+            if !self.tx_status_subs.subscriber_exists(&hash) {
+                self.tx_statuses.remove(&hash);
+            }
+            return Ok(());
+        }
+        if let Some(address) = self.account_event_subs.remove(&sub_id) {
+            if !self.account_event_subs.subscriber_exists(&address) {
+                self.account_event_ids
+                    .retain(|_, subscribed| *subscribed != address);
+                self.last_notified_deposits.remove(&address);
+            }
+            return Ok(());
+        }
+
         self.prior_op_subs.remove(sub_id.clone())?;
         self.tx_subs.remove(sub_id.clone())?;
         self.account_subs.remove(sub_id)?;
@@ -196,6 +421,7 @@ impl OperationNotifier {
                         let resp = ETHOpInfoResp {
                             executed: true,
                             block: Some(block_info),
+                            acknowledgment: None,
                         };
                         self.prior_op_subs.respond_once(sub_id, sub, resp)?;
                         return Ok(());
@@ -205,6 +431,7 @@ impl OperationNotifier {
                             let resp = ETHOpInfoResp {
                                 executed: true,
                                 block: Some(block_info),
+                                acknowledgment: None,
                             };
                             self.prior_op_subs.respond_once(sub_id, sub, resp)?;
                             return Ok(());
@@ -279,4 +506,230 @@ impl OperationNotifier {
         metrics::histogram!("api.notifier.add_account_update_sub", start.elapsed());
         Ok(())
     }
+
+    /// Add transaction status subscription.
+    async fn add_tx_status_sub(
+        &mut self,
+        hash: TxHash,
+        sub: Subscriber<TxStatusEvent>,
+    ) -> Result<(), anyhow::Error> {
+        let start = Instant::now();
+        let sub_id = self.tx_status_subs.generate_sub_id();
+
+        let current_status = if let Some(receipt) = self.state.get_tx_receipt(&hash).await? {
+            let status = if !receipt.success {
+                TxStatus::Rejected
+            } else if receipt.verified {
+                TxStatus::Verified
+            } else {
+                TxStatus::Committed
+            };
+            Some(TxStatusEvent {
+                status,
+                block_number: Some(receipt.block_number as BlockNumber),
+                fail_reason: receipt.fail_reason,
+            })
+        } else if self.state.is_tx_queued(hash).await? {
+            Some(TxStatusEvent {
+                status: TxStatus::Queued,
+                block_number: None,
+                fail_reason: None,
+            })
+        } else {
+            None
+        };
+
+        match current_status {
+            Some(event) if matches!(event.status, TxStatus::Verified | TxStatus::Rejected) => {
+                self.tx_status_subs.respond_once(sub_id, sub, event)?;
+                return Ok(());
+            }
+            Some(event) => {
+                let last_status = self.tx_statuses.entry(hash).or_insert(event.status);
+                *last_status = std::cmp::max(*last_status, event.status);
+                self.tx_status_subs
+                    .insert_new(sub_id, sub, hash, Some(event))?;
+            }
+            None => {
+                self.tx_status_subs.insert_new(sub_id, sub, hash, None)?;
+            }
+        }
+        metrics::histogram!("api.notifier.add_tx_status_sub", start.elapsed());
+        Ok(())
+    }
+
+    /// Add account events subscription.
+    async fn add_account_events_sub(
+        &mut self,
+        address: Address,
+        sub: Subscriber<AccountEvent>,
+    ) -> Result<(), anyhow::Error> {
+        let start = Instant::now();
+        let sub_id = self.account_event_subs.generate_sub_id();
+
+        if let Some(account_id) = self.state.get_account_id(address).await? {
+            self.account_event_ids.insert(account_id, address);
+        }
+        self.account_event_subs
+            .insert_new(sub_id, sub, address, None)?;
+        metrics::histogram!("api.notifier.add_account_events_sub", start.elapsed());
+        Ok(())
+    }
+
+    /// Add one-time request waiting for the transaction to reach the given state.
+    async fn add_tx_waiter(
+        &mut self,
+        hash: TxHash,
+        action: ActionType,
+        response: oneshot::Sender<TransactionInfoResp>,
+    ) -> Result<(), anyhow::Error> {
+        let start = Instant::now();
+
+        if let Some(receipt) = self.state.get_tx_receipt(&hash).await? {
+            if !receipt.success || action == ActionType::COMMIT || receipt.verified {
+                let resp = TransactionInfoResp {
+                    executed: true,
+                    success: Some(receipt.success),
+                    fail_reason: receipt.fail_reason,
+                    block: Some(BlockInfo {
+                        block_number: receipt.block_number,
+                        committed: receipt.success,
+                        verified: receipt.verified,
+                    }),
+                };
+                response.send(resp).unwrap_or_default();
+                return Ok(());
+            }
+        }
+
+        self.push_tx_waiter(hash, action, response)?;
+
+        metrics::histogram!("api.notifier.add_tx_waiter", start.elapsed());
+        Ok(())
+    }
+
+    /// Stores the request waiting for the transaction, unless there are too many of them already.
+    /// Dropped request is answered with the current state of the transaction.
+    fn push_tx_waiter(
+        &mut self,
+        hash: TxHash,
+        action: ActionType,
+        response: oneshot::Sender<TransactionInfoResp>,
+    ) -> Result<(), anyhow::Error> {
+        if self.tx_waiters_count >= MAX_TX_WAITERS {
+            self.remove_stale_tx_waiters();
+            anyhow::ensure!(
+                self.tx_waiters_count < MAX_TX_WAITERS,
+                "Too many requests waiting for the transactions"
+            );
+        }
+
+        self.tx_waiters.entry(hash).or_default().push(TxWaiter {
+            action,
+            response,
+            created_at: Instant::now(),
+        });
+        self.tx_waiters_count += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notifier() -> OperationNotifier {
+        OperationNotifier::new(10, ConnectionPool::new(Some(1)))
+    }
+
+    fn committed_tx(success: bool) -> TransactionInfoResp {
+        TransactionInfoResp {
+            executed: true,
+            success: Some(success),
+            fail_reason: None,
+            block: Some(BlockInfo {
+                block_number: 1,
+                committed: true,
+                verified: false,
+            }),
+        }
+    }
+
+    /// Checks that the waiters are responded once the transaction reaches the requested state.
+    #[test]
+    fn tx_waiters_are_notified() {
+        let mut notifier = notifier();
+        let hash = TxHash::default();
+        let (commit_sender, mut commit_receiver) = oneshot::channel();
+        let (verify_sender, mut verify_receiver) = oneshot::channel();
+        notifier
+            .push_tx_waiter(hash, ActionType::COMMIT, commit_sender)
+            .unwrap();
+        notifier
+            .push_tx_waiter(hash, ActionType::VERIFY, verify_sender)
+            .unwrap();
+
+        notifier.notify_tx_waiters(hash, ActionType::COMMIT, &committed_tx(true));
+        assert!(commit_receiver.try_recv().unwrap().is_some());
+        assert!(verify_receiver.try_recv().unwrap().is_none());
+        assert_eq!(notifier.tx_waiters_count, 1);
+
+        // Rejected transaction won't be verified, so the remaining waiter is notified as well.
+        notifier.notify_tx_waiters(hash, ActionType::COMMIT, &committed_tx(false));
+        assert!(verify_receiver.try_recv().unwrap().is_some());
+        assert_eq!(notifier.tx_waiters_count, 0);
+        assert!(notifier.tx_waiters.is_empty());
+    }
+
+    /// Checks that the waiters of the transactions which are never executed don't pile up.
+    #[test]
+    fn stale_tx_waiters_are_removed() {
+        let mut notifier = notifier();
+        let (cancelled_sender, cancelled_receiver) = oneshot::channel();
+        let (expired_sender, _expired_receiver) = oneshot::channel();
+        let (active_sender, _active_receiver) = oneshot::channel();
+        notifier
+            .push_tx_waiter(TxHash::default(), ActionType::COMMIT, cancelled_sender)
+            .unwrap();
+        notifier
+            .push_tx_waiter(TxHash::default(), ActionType::COMMIT, expired_sender)
+            .unwrap();
+        notifier
+            .push_tx_waiter(TxHash::default(), ActionType::VERIFY, active_sender)
+            .unwrap();
+
+        drop(cancelled_receiver);
+        notifier.tx_waiters.get_mut(&TxHash::default()).unwrap()[1].created_at -= TX_WAITER_TTL;
+
+        notifier.remove_stale_tx_waiters();
+        assert_eq!(notifier.tx_waiters_count, 1);
+        assert_eq!(notifier.tx_waiters[&TxHash::default()].len(), 1);
+    }
+
+    /// Checks that the amount of the waiters is limited.
+    #[test]
+    fn tx_waiters_limit() {
+        let mut notifier = notifier();
+        let mut receivers = Vec::new();
+        for _ in 0..MAX_TX_WAITERS {
+            let (sender, receiver) = oneshot::channel();
+            notifier
+                .push_tx_waiter(TxHash::default(), ActionType::COMMIT, sender)
+                .unwrap();
+            receivers.push(receiver);
+        }
+
+        let (sender, _receiver) = oneshot::channel();
+        assert!(notifier
+            .push_tx_waiter(TxHash::default(), ActionType::COMMIT, sender)
+            .is_err());
+
+        // Once the clients stop waiting, there is a room for the new requests.
+        receivers.clear();
+        let (sender, _receiver) = oneshot::channel();
+        notifier
+            .push_tx_waiter(TxHash::default(), ActionType::COMMIT, sender)
+            .unwrap();
+        assert_eq!(notifier.tx_waiters_count, 1);
+    }
 }
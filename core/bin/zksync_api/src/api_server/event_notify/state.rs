@@ -64,6 +64,26 @@ impl NotifierState {
         Ok(res)
     }
 
+    /// Checks whether the transaction waits for execution in the mempool.
+    pub async fn is_tx_queued(&self, hash: TxHash) -> anyhow::Result<bool> {
+        let mut storage = self.db_pool.access_storage().await?;
+        let queued = storage.chain().mempool_schema().contains_tx(hash).await?;
+
+        Ok(queued)
+    }
+
+    /// Returns the ID of the account with the given address, if it exists.
+    pub async fn get_account_id(&self, address: Address) -> anyhow::Result<Option<AccountId>> {
+        let mut storage = self.db_pool.access_storage().await?;
+        let account_state = storage
+            .chain()
+            .account_schema()
+            .account_state_by_address(address)
+            .await?;
+
+        Ok(account_state.committed.map(|(id, _)| id))
+    }
+
     pub async fn get_block_info(
         &mut self,
         block_number: u32,
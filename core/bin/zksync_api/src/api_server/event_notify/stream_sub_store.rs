@@ -0,0 +1,115 @@
+//! Storage for the long-living subscriptions, which receive every event
+//! related to the entity until unsubscribed (unlike the one-shot `SubStorage` subscriptions).
+use super::SubscriptionSender;
+use futures::{compat::Future01CompatExt, FutureExt};
+use std::{collections::HashMap, hash::Hash};
+
+use jsonrpc_pubsub::{typed::Subscriber, SubscriptionId};
+
+const MAX_LISTENERS_PER_ENTITY: usize = 2048;
+
+#[derive(Debug)]
+pub struct StreamSubStorage<K, RESP> {
+    prefix: &'static str,
+    subs: HashMap<K, Vec<SubscriptionSender<RESP>>>,
+    keys: HashMap<SubscriptionId, K>,
+}
+
+impl<K, RESP> StreamSubStorage<K, RESP>
+where
+    K: Hash + Eq + Clone,
+    RESP: serde::Serialize + Clone + std::fmt::Debug,
+{
+    pub fn new(prefix: &'static str) -> Self {
+        Self {
+            prefix,
+            subs: HashMap::new(),
+            keys: HashMap::new(),
+        }
+    }
+
+    pub fn generate_sub_id(&self) -> SubscriptionId {
+        SubscriptionId::String(format!(
+            "{}/{}",
+            self.prefix,
+            zksync_crypto::rand::random::<u64>()
+        ))
+    }
+
+    /// Adds a new subscription, sending it the initial event if provided.
+    pub fn insert_new(
+        &mut self,
+        sub_id: SubscriptionId,
+        sub: Subscriber<RESP>,
+        key: K,
+        initial_event: Option<RESP>,
+    ) -> anyhow::Result<()> {
+        let subs = self.subs.entry(key.clone()).or_default();
+        if subs.len() >= MAX_LISTENERS_PER_ENTITY {
+            return Ok(());
+        }
+
+        let sink = sub
+            .assign_id(sub_id.clone())
+            .map_err(|_| anyhow::format_err!("SubIdAssign"))?;
+        if let Some(event) = initial_event {
+            tokio::spawn(sink.notify(Ok(event)).compat().map(drop));
+        }
+        subs.push(SubscriptionSender {
+            id: sub_id.clone(),
+            sink,
+        });
+        self.keys.insert(sub_id, key);
+
+        Ok(())
+    }
+
+    /// Sends the only event to the new subscriber without storing the subscription.
+    pub fn respond_once(
+        &mut self,
+        sub_id: SubscriptionId,
+        sub: Subscriber<RESP>,
+        event: RESP,
+    ) -> anyhow::Result<()> {
+        let sink = sub
+            .assign_id(sub_id)
+            .map_err(|_| anyhow::format_err!("SubIdAssign"))?;
+        tokio::spawn(sink.notify(Ok(event)).compat().map(drop));
+        Ok(())
+    }
+
+    /// Removes the subscription, returning the key of the entity it was subscribed to.
+    pub fn remove(&mut self, sub_id: &SubscriptionId) -> Option<K> {
+        let key = self.keys.remove(sub_id)?;
+        if let Some(subs) = self.subs.get_mut(&key) {
+            subs.retain(|sub| &sub.id != sub_id);
+            if subs.is_empty() {
+                self.subs.remove(&key);
+            }
+        }
+        Some(key)
+    }
+
+    pub fn subscriber_exists(&self, key: &K) -> bool {
+        self.subs.contains_key(key)
+    }
+
+    /// Sends the event to all the subscribers of the entity, keeping the subscriptions.
+    pub fn notify(&self, key: &K, event: RESP) {
+        if let Some(subs) = self.subs.get(key) {
+            for sub in subs {
+                tokio::spawn(sub.sink.notify(Ok(event.clone())).compat().map(drop));
+            }
+        }
+    }
+
+    /// Sends the final event to all the subscribers of the entity and removes their subscriptions.
+    pub fn notify_last(&mut self, key: &K, event: RESP) {
+        self.notify(key, event);
+        if let Some(subs) = self.subs.remove(key) {
+            for sub in subs {
+                self.keys.remove(&sub.id);
+            }
+        }
+    }
+}
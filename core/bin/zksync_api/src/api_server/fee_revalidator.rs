@@ -0,0 +1,176 @@
+//! Revalidation of the pending transactions fees.
+//!
+//! Fees are checked against the ticker quotes once the transaction is submitted, but
+//! the transaction may stay in the mempool for a while. If the L1 gas price rises sharply
+//! during this time, the fee provided by the transaction may no longer cover the cost of
+//! its processing. This module watches the average gas price and once it rises above the
+//! configured threshold (compared to the lowest price observed since the last revalidation),
+//! fees of all the pending transactions are checked again, and the underpaid ones are removed
+//! from the mempool, as well as the ones paying fees in tokens no longer accepted for it.
+
+// Built-in uses
+use std::time::Duration;
+
+// External uses
+use tokio::time;
+
+// Workspace uses
+use zksync_config::FeeTickerOptions;
+use zksync_types::{mempool::SignedTxVariant, tx::TxHash, ZkSyncTx};
+
+// Local uses
+use super::{
+    fee_subsidy::FeeCheck,
+    is_standby,
+    tx_sender::{SubmitError, TxSender},
+};
+use crate::tx_error::TxAddError;
+
+struct FeeRevalidator {
+    tx_sender: TxSender,
+    gas_price_rise_threshold: f64,
+    /// Gas price the pending transactions fees were last validated against.
+    last_gas_price: Option<u64>,
+}
+
+impl FeeRevalidator {
+    async fn check_gas_price(&mut self) -> anyhow::Result<()> {
+        let gas_price = self
+            .tx_sender
+            .pool
+            .access_storage()
+            .await?
+            .ethereum_schema()
+            .load_average_gas_price()
+            .await?;
+        let gas_price = match gas_price {
+            Some(gas_price) => gas_price.as_u64(),
+            None => return Ok(()),
+        };
+
+        match self.last_gas_price {
+            Some(last_gas_price)
+                if is_sharp_rise(last_gas_price, gas_price, self.gas_price_rise_threshold) =>
+            {
+                tracing::info!(
+                    "Gas price rose from {} to {}, revalidating fees of the pending transactions",
+                    last_gas_price,
+                    gas_price
+                );
+                self.revalidate_fees().await?;
+            }
+            // Gas price is measured from the lowest value since the last revalidation,
+            // so the gradual rise will trigger the revalidation as well.
+            Some(last_gas_price) if gas_price >= last_gas_price => return Ok(()),
+            _ => {}
+        }
+
+        self.last_gas_price = Some(gas_price);
+        Ok(())
+    }
+
+    async fn revalidate_fees(&self) -> anyhow::Result<()> {
+        let pending_txs = self
+            .tx_sender
+            .pool
+            .access_storage()
+            .await?
+            .chain()
+            .mempool_schema()
+            .load_txs()
+            .await?;
+
+        let mut underpaid_txs: Vec<TxHash> = Vec::new();
+        for element in pending_txs {
+            let check_result = match &element {
+                SignedTxVariant::Tx(tx) => match self.tx_sender.check_tx_fee(&tx.tx).await {
+                    // Fee no longer covered by the transaction is subsidized from now on,
+                    // unless the budget is exhausted.
+                    Ok(FeeCheck::Subsidized(subsidy)) => {
+                        self.tx_sender
+                            .reserve_fee_subsidy(tx.tx.hash(), &subsidy)
+                            .await
+                    }
+                    result => result.map(drop),
+                },
+                SignedTxVariant::Batch(batch) => {
+                    let txs: Vec<ZkSyncTx> = batch.txs.iter().map(|tx| tx.tx.clone()).collect();
+                    self.tx_sender.check_batch_fee(&txs).await
+                }
+            };
+
+            match check_result {
+                Ok(()) => {}
+                Err(SubmitError::TxAdd(TxAddError::TxFeeTooLow))
+                | Err(SubmitError::TxAdd(TxAddError::TxBatchFeeTooLow))
+                // Token is no longer accepted to pay fees, e.g. it was assessed as illiquid.
+                | Err(SubmitError::InappropriateFeeToken) => {
+                    underpaid_txs.extend(element.hashes());
+                }
+                Err(err) => {
+                    tracing::warn!(
+                        "Unable to revalidate the fee of the pending transactions {:?}: {}",
+                        element.hashes(),
+                        err
+                    );
+                }
+            }
+        }
+
+        if !underpaid_txs.is_empty() {
+            tracing::info!(
+                "Removing {} underpaid transactions from the mempool",
+                underpaid_txs.len()
+            );
+            self.tx_sender
+                .core_api_client
+                .remove_txs(underpaid_txs)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn run(mut self, revalidation_interval: Duration) {
+        let mut timer = time::interval(revalidation_interval);
+        loop {
+            timer.tick().await;
+            // Mempool is managed by the leader replica.
+            if is_standby() {
+                continue;
+            }
+
+            if let Err(err) = self.check_gas_price().await {
+                tracing::warn!("Pending transactions fee revalidation failed: {}", err);
+            }
+        }
+    }
+}
+
+/// Checks whether the gas price has risen by more than `threshold` (relative value).
+fn is_sharp_rise(previous: u64, current: u64, threshold: f64) -> bool {
+    current as f64 > previous as f64 * (1.0 + threshold)
+}
+
+pub fn start_fee_revalidator(tx_sender: TxSender, ticker_options: &FeeTickerOptions) {
+    let revalidator = FeeRevalidator {
+        tx_sender,
+        gas_price_rise_threshold: ticker_options.gas_price_rise_threshold,
+        last_gas_price: None,
+    };
+
+    tokio::spawn(revalidator.run(ticker_options.fee_revalidation_interval));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sharp_gas_price_rise() {
+        assert!(!is_sharp_rise(100, 100, 0.3));
+        assert!(!is_sharp_rise(100, 130, 0.3));
+        assert!(is_sharp_rise(100, 131, 0.3));
+        assert!(!is_sharp_rise(100, 50, 0.3));
+    }
+}
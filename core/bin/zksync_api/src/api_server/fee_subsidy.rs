@@ -0,0 +1,220 @@
+//! Subsidies of the transactions fees.
+//!
+//! To onboard the users holding no funds, the operator may cover the fee of their transactions.
+//! Subsidy rules are managed through the admin API: each rule matches the fee type of the
+//! transaction and optionally the token the fee is paid in, and covers up to the configured
+//! share of the required fee until the daily (UTC) budget of the rule is exhausted.
+//!
+//! Only single transactions are subsidized. The subsidy is reserved right before the transaction
+//! is sent to the mempool: the budget is checked again with the rule locked, so concurrently
+//! submitted transactions can't exceed it. Reservation is released if the transaction is
+//! rejected, fails or is removed from the mempool, and it's charged once the transaction is
+//! executed. Until then it counts towards the budget of the day the transaction was accepted.
+
+// External uses
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+// Workspace uses
+use zksync_storage::{admin::records::StorageSubsidyRule, StorageProcessor};
+use zksync_types::{TokenId, TxFeeTypes};
+
+/// Fee type names the subsidy rules can be created for.
+pub(crate) const FEE_TYPE_NAMES: &[&str] =
+    &["Transfer", "Withdraw", "FastWithdraw", "ChangePubKey"];
+
+/// Returns the name the subsidy rules are matched against the fee type by.
+pub(crate) fn fee_type_name(tx_type: TxFeeTypes) -> &'static str {
+    match tx_type {
+        TxFeeTypes::Transfer => "Transfer",
+        TxFeeTypes::Withdraw => "Withdraw",
+        TxFeeTypes::FastWithdraw => "FastWithdraw",
+        TxFeeTypes::ChangePubKey { .. } => "ChangePubKey",
+    }
+}
+
+/// Outcome of the transaction fee check.
+#[derive(Debug, Clone)]
+pub(crate) enum FeeCheck {
+    /// Fee provided by the transaction covers the required one.
+    Paid,
+    /// Missing part of the fee can be covered by the subsidy,
+    /// it must be reserved before the transaction is accepted.
+    Subsidized(FeeSubsidy),
+    /// Missing part of the fee is covered by the subsidy reserved
+    /// once the transaction was accepted.
+    SubsidyReserved,
+}
+
+/// Part of the transaction fee covered by the subsidy rule.
+#[derive(Debug, Clone)]
+pub(crate) struct FeeSubsidy {
+    pub rule: StorageSubsidyRule,
+    pub token_id: TokenId,
+    /// Covered amount in the token units.
+    pub amount: BigDecimal,
+    pub amount_usd: BigDecimal,
+}
+
+/// Selects the rule for the transaction, rules for the specific token take precedence
+/// over the ones matching any token.
+fn select_rule<'a>(
+    rules: &'a [StorageSubsidyRule],
+    tx_type: &str,
+    token_id: TokenId,
+) -> Option<&'a StorageSubsidyRule> {
+    let token_id = i32::from(token_id);
+    let mut matching = rules.iter().filter(|rule| rule.tx_type == tx_type);
+
+    matching
+        .clone()
+        .find(|rule| rule.token_id == Some(token_id))
+        .or_else(|| matching.find(|rule| rule.token_id.is_none()))
+}
+
+/// Checks that the rule is able to cover the missing part of the fee.
+fn rule_covers(
+    rule: &StorageSubsidyRule,
+    required_fee: &BigDecimal,
+    missing_fee: &BigDecimal,
+    missing_fee_usd: &BigDecimal,
+    subsidized_today_usd: &BigDecimal,
+) -> bool {
+    let max_covered_fee =
+        required_fee * BigDecimal::from(rule.subsidy_percent) / BigDecimal::from(100);
+
+    missing_fee <= &max_covered_fee
+        && subsidized_today_usd + missing_fee_usd <= rule.daily_budget_usd
+}
+
+/// Returns the moment the daily budgets of the rules are reset at.
+pub(crate) fn start_of_day(now: DateTime<Utc>) -> DateTime<Utc> {
+    now.date().and_hms(0, 0, 0)
+}
+
+/// Looks for the rule covering the difference between the required and the provided fee.
+/// `token_price_usd` is the price of the smallest token unit.
+pub(crate) async fn find_subsidy(
+    storage: &mut StorageProcessor<'_>,
+    tx_type: TxFeeTypes,
+    token_id: TokenId,
+    required_fee: &BigDecimal,
+    provided_fee: &BigDecimal,
+    token_price_usd: &BigDecimal,
+) -> anyhow::Result<Option<FeeSubsidy>> {
+    let rules = storage.admin_schema().load_subsidy_rules().await?;
+    let rule = match select_rule(&rules, fee_type_name(tx_type), token_id) {
+        Some(rule) => rule.clone(),
+        None => return Ok(None),
+    };
+
+    let missing_fee = required_fee - provided_fee;
+    let missing_fee_usd = &missing_fee * token_price_usd;
+    let subsidized_today_usd = storage
+        .admin_schema()
+        .subsidized_usd_since(rule.id, start_of_day(Utc::now()))
+        .await?;
+    if !rule_covers(
+        &rule,
+        required_fee,
+        &missing_fee,
+        &missing_fee_usd,
+        &subsidized_today_usd,
+    ) {
+        return Ok(None);
+    }
+
+    Ok(Some(FeeSubsidy {
+        rule,
+        token_id,
+        amount: missing_fee,
+        amount_usd: missing_fee_usd,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn rule(id: i32, tx_type: &str, token_id: Option<i32>, percent: i32) -> StorageSubsidyRule {
+        StorageSubsidyRule {
+            id,
+            tx_type: tx_type.to_string(),
+            token_id,
+            subsidy_percent: percent,
+            daily_budget_usd: BigDecimal::from(10),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn rule_selection() {
+        let rules = vec![
+            rule(1, "Transfer", None, 100),
+            rule(2, "Transfer", Some(1), 50),
+            rule(3, "Withdraw", Some(1), 50),
+        ];
+
+        let selected = |tx_type: &str, token_id: TokenId| {
+            select_rule(&rules, tx_type, token_id).map(|rule| rule.id)
+        };
+        assert_eq!(selected("Transfer", 1), Some(2));
+        assert_eq!(selected("Transfer", 0), Some(1));
+        assert_eq!(selected("Withdraw", 1), Some(3));
+        assert_eq!(selected("Withdraw", 0), None);
+        assert_eq!(selected("ChangePubKey", 1), None);
+    }
+
+    #[test]
+    fn rule_coverage() {
+        let decimal = |value: &str| BigDecimal::from_str(value).unwrap();
+        let rule = rule(1, "Transfer", None, 50);
+        let required_fee = decimal("1000");
+
+        // Half of the fee is covered.
+        assert!(rule_covers(
+            &rule,
+            &required_fee,
+            &decimal("500"),
+            &decimal("1"),
+            &decimal("0"),
+        ));
+        assert!(!rule_covers(
+            &rule,
+            &required_fee,
+            &decimal("501"),
+            &decimal("1"),
+            &decimal("0"),
+        ));
+        // Daily budget is not exceeded.
+        assert!(rule_covers(
+            &rule,
+            &required_fee,
+            &decimal("500"),
+            &decimal("1"),
+            &decimal("9"),
+        ));
+        assert!(!rule_covers(
+            &rule,
+            &required_fee,
+            &decimal("500"),
+            &decimal("1"),
+            &decimal("9.5"),
+        ));
+    }
+
+    #[test]
+    fn fee_type_names() {
+        let tx_types = vec![
+            TxFeeTypes::Transfer,
+            TxFeeTypes::Withdraw,
+            TxFeeTypes::FastWithdraw,
+            TxFeeTypes::ChangePubKey {
+                onchain_pubkey_auth: false,
+            },
+        ];
+        for tx_type in tx_types {
+            assert!(FEE_TYPE_NAMES.contains(&fee_type_name(tx_type)));
+        }
+    }
+}
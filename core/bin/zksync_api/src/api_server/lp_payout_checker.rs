@@ -0,0 +1,442 @@
+//! Confirmation of the fast withdrawals payouts.
+//!
+//! Liquidity providers report the L1 transactions paying out the fast withdrawals, but
+//! the reported transaction may fail, be dropped from the Ethereum mempool or not pay
+//! the withdrawal at all. This module follows the reported transactions in L1: once the
+//! transaction is confirmed and it pays out the expected amount to the recipient, the
+//! payout is marked as confirmed. Otherwise the payout is discarded, so the withdrawal
+//! is shown in the provider's feed of the unpaid withdrawals again.
+
+// Built-in uses
+use std::time::Duration;
+
+// External uses
+use num::BigUint;
+use tokio::time;
+use web3::{
+    transports::Http,
+    types::{TransactionId, U256},
+    Web3,
+};
+
+// Workspace uses
+use zksync_config::ConfigurationOptions;
+use zksync_storage::{lp_withdrawals::records::StorageLpWithdrawal, ConnectionPool};
+use zksync_types::{tx::TxHash, Address, TokenLike, H256};
+
+// Local uses
+use super::is_standby;
+
+/// Interval between the checks of the reported payouts.
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+/// Maximum amount of the payouts checked at once.
+const CHECK_BATCH_SIZE: u32 = 100;
+/// Reported payout transaction that isn't found for this long (in minutes) is considered dropped.
+const MAX_PENDING_MINUTES: i64 = 60;
+
+/// Topic of the ERC20 `Transfer(address,address,uint256)` event.
+const ERC20_TRANSFER_TOPIC: [u8; 32] = [
+    0xdd, 0xf2, 0x52, 0xad, 0x1b, 0xe2, 0xc8, 0x9b, 0x69, 0xc2, 0xb0, 0x68, 0xfc, 0x37, 0x8d, 0xaa,
+    0x95, 0x2b, 0xa7, 0xf1, 0x63, 0xc4, 0xa1, 0x16, 0x28, 0xf5, 0x5a, 0x4d, 0xf5, 0x23, 0xb3, 0xef,
+];
+
+/// Event log of the payout transaction.
+#[derive(Debug, Clone, PartialEq)]
+struct PayoutLog {
+    address: Address,
+    topics: Vec<H256>,
+    data: Vec<u8>,
+}
+
+/// Parts of the L1 payout transaction and its receipt relevant for the check.
+#[derive(Debug, Clone, PartialEq)]
+struct PayoutTx {
+    from: Address,
+    to: Option<Address>,
+    value: U256,
+    /// Block the transaction is mined in, `None` if it's still pending.
+    block_number: Option<u64>,
+    success: bool,
+    logs: Vec<PayoutLog>,
+}
+
+/// Payout the provider is expected to make.
+#[derive(Debug, Clone, PartialEq)]
+struct ExpectedPayout {
+    lp_address: Address,
+    recipient: Address,
+    /// Address of the withdrawn ERC20 token, `None` for ETH.
+    token_address: Option<Address>,
+    amount: U256,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum PayoutCheck {
+    /// The transaction is not mined or doesn't have enough confirmations yet.
+    Pending,
+    Confirmed,
+    /// The transaction doesn't pay out the withdrawal.
+    Invalid(&'static str),
+}
+
+fn check_payout(
+    expected: &ExpectedPayout,
+    tx: &PayoutTx,
+    current_block: u64,
+    confirmations: u64,
+) -> PayoutCheck {
+    if tx.from != expected.lp_address {
+        return PayoutCheck::Invalid("Transaction is not sent by the liquidity provider");
+    }
+    let block_number = match tx.block_number {
+        Some(block_number) => block_number,
+        None => return PayoutCheck::Pending,
+    };
+    if !tx.success {
+        return PayoutCheck::Invalid("Transaction has failed");
+    }
+
+    let paid_out = match expected.token_address {
+        None => tx.to == Some(expected.recipient) && tx.value >= expected.amount,
+        Some(token_address) => tx.logs.iter().any(|log| {
+            log.address == token_address
+                && log.topics.len() == 3
+                && log.topics[0] == H256(ERC20_TRANSFER_TOPIC)
+                && log.topics[1] == H256::from(expected.lp_address)
+                && log.topics[2] == H256::from(expected.recipient)
+                && log.data.len() == 32
+                && U256::from_big_endian(&log.data) >= expected.amount
+        }),
+    };
+    if !paid_out {
+        return PayoutCheck::Invalid("Transaction doesn't pay out the withdrawal");
+    }
+
+    if current_block.saturating_sub(block_number) < confirmations {
+        return PayoutCheck::Pending;
+    }
+    PayoutCheck::Confirmed
+}
+
+struct LpPayoutChecker {
+    pool: ConnectionPool,
+    web3: Web3<Http>,
+    confirmations: u64,
+}
+
+impl LpPayoutChecker {
+    async fn load_payout_tx(&self, l1_tx_hash: H256) -> anyhow::Result<Option<PayoutTx>> {
+        let tx = match self
+            .web3
+            .eth()
+            .transaction(TransactionId::Hash(l1_tx_hash))
+            .await?
+        {
+            Some(tx) => tx,
+            None => return Ok(None),
+        };
+        let receipt = self.web3.eth().transaction_receipt(l1_tx_hash).await?;
+
+        let (block_number, success, logs) = match receipt {
+            Some(receipt) => (
+                receipt.block_number.map(|block| block.as_u64()),
+                receipt
+                    .status
+                    .map(|status| status.as_u64() == 1)
+                    .unwrap_or(false),
+                receipt
+                    .logs
+                    .into_iter()
+                    .map(|log| PayoutLog {
+                        address: log.address,
+                        topics: log.topics,
+                        data: log.data.0,
+                    })
+                    .collect(),
+            ),
+            None => (None, false, Vec::new()),
+        };
+
+        Ok(Some(PayoutTx {
+            from: tx.from,
+            to: tx.to,
+            value: tx.value,
+            block_number,
+            success,
+            logs,
+        }))
+    }
+
+    async fn expected_payout(
+        &self,
+        withdrawal: &StorageLpWithdrawal,
+    ) -> anyhow::Result<ExpectedPayout> {
+        let token_address = if withdrawal.token_id == 0 {
+            None
+        } else {
+            let token = self
+                .pool
+                .access_storage()
+                .await?
+                .tokens_schema()
+                .get_token(TokenLike::Id(withdrawal.token_id as u16))
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("Unknown token {}", withdrawal.token_id))?;
+            Some(token.address)
+        };
+        let amount = withdrawal
+            .payout_amount
+            .to_string()
+            .parse::<BigUint>()
+            .map_err(|e| anyhow::anyhow!("Incorrect payout amount: {}", e))?;
+
+        Ok(ExpectedPayout {
+            lp_address: Address::from_slice(&withdrawal.lp_address),
+            recipient: Address::from_slice(&withdrawal.l1_recipient),
+            token_address,
+            amount: U256::from_big_endian(&amount.to_bytes_be()),
+        })
+    }
+
+    async fn check_payouts(&self) -> anyhow::Result<()> {
+        let payouts = self
+            .pool
+            .access_storage()
+            .await?
+            .lp_withdrawals_schema()
+            .load_unconfirmed_payouts(CHECK_BATCH_SIZE)
+            .await?;
+        if payouts.is_empty() {
+            return Ok(());
+        }
+        let current_block = self.web3.eth().block_number().await?.as_u64();
+
+        for withdrawal in payouts {
+            let tx_hash = TxHash::from_slice(&withdrawal.tx_hash).unwrap();
+            let l1_tx_hash = H256::from_slice(withdrawal.l1_tx_hash.as_ref().unwrap());
+
+            let check = match self.load_payout_tx(l1_tx_hash).await? {
+                Some(tx) => check_payout(
+                    &self.expected_payout(&withdrawal).await?,
+                    &tx,
+                    current_block,
+                    self.confirmations,
+                ),
+                None => {
+                    let reported_at = withdrawal.paid_at.unwrap_or(withdrawal.created_at);
+                    if chrono::Utc::now() - reported_at
+                        > chrono::Duration::minutes(MAX_PENDING_MINUTES)
+                    {
+                        PayoutCheck::Invalid("Transaction is not found")
+                    } else {
+                        PayoutCheck::Pending
+                    }
+                }
+            };
+
+            let mut storage = self.pool.access_storage().await?;
+            match check {
+                PayoutCheck::Pending => {}
+                PayoutCheck::Confirmed => {
+                    storage
+                        .lp_withdrawals_schema()
+                        .confirm_payout(&tx_hash, l1_tx_hash)
+                        .await?;
+                }
+                PayoutCheck::Invalid(reason) => {
+                    tracing::warn!(
+                        "Payout {:?} of the fast withdrawal {} is discarded: {}",
+                        l1_tx_hash,
+                        tx_hash.to_string(),
+                        reason
+                    );
+                    metrics::counter!("api.fast_withdraw.rejected_payouts", 1);
+                    storage
+                        .lp_withdrawals_schema()
+                        .reject_payout(&tx_hash, l1_tx_hash)
+                        .await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn run(self) {
+        let mut timer = time::interval(CHECK_INTERVAL);
+        loop {
+            timer.tick().await;
+            // Payouts are checked by the leader replica only.
+            if is_standby() {
+                continue;
+            }
+
+            if let Err(err) = self.check_payouts().await {
+                tracing::warn!("Fast withdrawals payouts check failed: {}", err);
+            }
+        }
+    }
+}
+
+pub fn start_lp_payout_checker(pool: ConnectionPool, config_options: &ConfigurationOptions) {
+    let transport = Http::new(&config_options.web3_url).expect("Failed to create web3 transport");
+    let checker = LpPayoutChecker {
+        pool,
+        web3: Web3::new(transport),
+        confirmations: config_options.confirmations_for_eth_event,
+    };
+
+    tokio::spawn(checker.run());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lp() -> Address {
+        Address::repeat_byte(1)
+    }
+
+    fn recipient() -> Address {
+        Address::repeat_byte(2)
+    }
+
+    fn token() -> Address {
+        Address::repeat_byte(3)
+    }
+
+    fn eth_payout(amount: u64) -> PayoutTx {
+        PayoutTx {
+            from: lp(),
+            to: Some(recipient()),
+            value: amount.into(),
+            block_number: Some(100),
+            success: true,
+            logs: Vec::new(),
+        }
+    }
+
+    fn erc20_payout(sender: Address, recipient: Address, amount: u64) -> PayoutTx {
+        let mut data = [0u8; 32];
+        U256::from(amount).to_big_endian(&mut data);
+        PayoutTx {
+            from: lp(),
+            to: Some(token()),
+            value: U256::zero(),
+            block_number: Some(100),
+            success: true,
+            logs: vec![PayoutLog {
+                address: token(),
+                topics: vec![
+                    H256(ERC20_TRANSFER_TOPIC),
+                    H256::from(sender),
+                    H256::from(recipient),
+                ],
+                data: data.to_vec(),
+            }],
+        }
+    }
+
+    #[test]
+    fn eth_payout_check() {
+        let expected = ExpectedPayout {
+            lp_address: lp(),
+            recipient: recipient(),
+            token_address: None,
+            amount: 1000.into(),
+        };
+
+        assert_eq!(
+            check_payout(&expected, &eth_payout(1000), 110, 10),
+            PayoutCheck::Confirmed
+        );
+        // Not enough confirmations yet.
+        assert_eq!(
+            check_payout(&expected, &eth_payout(1000), 105, 10),
+            PayoutCheck::Pending
+        );
+        let pending = PayoutTx {
+            block_number: None,
+            ..eth_payout(1000)
+        };
+        assert_eq!(
+            check_payout(&expected, &pending, 110, 10),
+            PayoutCheck::Pending
+        );
+
+        let failed = PayoutTx {
+            success: false,
+            ..eth_payout(1000)
+        };
+        assert!(matches!(
+            check_payout(&expected, &failed, 110, 10),
+            PayoutCheck::Invalid(_)
+        ));
+        assert!(matches!(
+            check_payout(&expected, &eth_payout(999), 110, 10),
+            PayoutCheck::Invalid(_)
+        ));
+        let other_recipient = PayoutTx {
+            to: Some(Address::repeat_byte(4)),
+            ..eth_payout(1000)
+        };
+        assert!(matches!(
+            check_payout(&expected, &other_recipient, 110, 10),
+            PayoutCheck::Invalid(_)
+        ));
+        let other_sender = PayoutTx {
+            from: Address::repeat_byte(4),
+            ..eth_payout(1000)
+        };
+        assert!(matches!(
+            check_payout(&expected, &other_sender, 110, 10),
+            PayoutCheck::Invalid(_)
+        ));
+    }
+
+    #[test]
+    fn erc20_payout_check() {
+        let expected = ExpectedPayout {
+            lp_address: lp(),
+            recipient: recipient(),
+            token_address: Some(token()),
+            amount: 1000.into(),
+        };
+
+        assert_eq!(
+            check_payout(&expected, &erc20_payout(lp(), recipient(), 1000), 110, 10),
+            PayoutCheck::Confirmed
+        );
+        assert!(matches!(
+            check_payout(&expected, &erc20_payout(lp(), recipient(), 999), 110, 10),
+            PayoutCheck::Invalid(_)
+        ));
+        assert!(matches!(
+            check_payout(
+                &expected,
+                &erc20_payout(lp(), Address::repeat_byte(4), 1000),
+                110,
+                10
+            ),
+            PayoutCheck::Invalid(_)
+        ));
+        // Transfer of another token doesn't pay out the withdrawal.
+        // Tokens transferred from another account (e.g. via `transferFrom` called by the
+        // provider) don't pay out the withdrawal either.
+        assert!(matches!(
+            check_payout(
+                &expected,
+                &erc20_payout(Address::repeat_byte(4), recipient(), 1000),
+                110,
+                10
+            ),
+            PayoutCheck::Invalid(_)
+        ));
+        let mut other_token = erc20_payout(lp(), recipient(), 1000);
+        other_token.logs[0].address = Address::repeat_byte(5);
+        assert!(matches!(
+            check_payout(&expected, &other_token, 110, 10),
+            PayoutCheck::Invalid(_)
+        ));
+    }
+}
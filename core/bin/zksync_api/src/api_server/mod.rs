@@ -7,27 +7,96 @@
 // Public uses
 pub use rest::v1;
 
+// Built-in uses
+use std::sync::atomic::{AtomicBool, Ordering};
 // External uses
 use futures::channel::mpsc;
 // Workspace uses
-use zksync_config::{AdminServerOptions, ApiServerOptions, ConfigurationOptions};
+use zksync_config::{AdminServerOptions, ApiServerOptions, ConfigurationOptions, FeeTickerOptions};
+use zksync_prometheus_exporter::{register_metrics, Metric};
 use zksync_storage::ConnectionPool;
 // Local uses
+use self::{
+    event_notify::start_sub_notifier, rate_limiter::SubmissionLimiter, tls::TlsConfig,
+    tx_sender::TxSender,
+};
 use crate::fee_ticker::TickerRequest;
 use crate::signature_checker;
+use crate::utils::account_id_cache::AccountIdCache;
 
 mod admin_server;
+mod client_addr;
 mod event_notify;
+mod fee_revalidator;
+mod fee_subsidy;
 mod helpers;
 mod loggers;
+mod lp_payout_checker;
+mod rate_limiter;
 mod rest;
 pub mod rpc_server;
 mod rpc_subscriptions;
+mod tls;
 mod tx_sender;
 
 /// Amount of threads used by each server to serve requests.
 const THREADS_PER_SERVER: usize = 128;
 
+/// Whether the server replica is a standby one: its Core actors aren't running,
+/// so only the read-only API is served and the incoming transactions are rejected.
+static STANDBY_MODE: AtomicBool = AtomicBool::new(false);
+
+/// Switches the API of the server replica to the standby mode or back.
+pub fn set_standby_mode(standby: bool) {
+    STANDBY_MODE.store(standby, Ordering::SeqCst);
+}
+
+pub(crate) fn is_standby() -> bool {
+    STANDBY_MODE.load(Ordering::SeqCst)
+}
+
+/// Metrics reported by the API servers. The latency of every JSON RPC and REST method
+/// is reported as the `api.rpc.<method>` and `api.v01.<method>` histograms.
+const METRICS: &[Metric] = &[
+    Metric::counter(
+        "api.accepted_txs",
+        "Number of the transactions accepted by the API",
+    ),
+    Metric::counter(
+        "api.rpc.rejected_requests",
+        "Number of the JSON RPC requests rejected by the access limiter by reason",
+    ),
+    Metric::histogram("api.rpc.batch_size", "Size of the JSON RPC batch requests"),
+    Metric::histogram(
+        "api.rpc.tx_submit",
+        "Time spent on the transaction submission",
+    ),
+    Metric::histogram(
+        "api.rpc.submit_txs_batch",
+        "Time spent on the batch submission",
+    ),
+    Metric::histogram(
+        "api.notifier.handle_new_block",
+        "Time spent on notifying the subscribers about a new block",
+    ),
+    Metric::counter(
+        "api.fast_withdraw.rejected_payouts",
+        "Number of the fast withdrawals payouts discarded after the L1 check",
+    ),
+    Metric::counter(
+        "api.account_id_cache.hits",
+        "Number of the addresses resolved to the account IDs from the cache",
+    ),
+    Metric::counter(
+        "eth_checker.eip1271_cache_hits",
+        "Number of the EIP-1271 signature checks served from the cache",
+    ),
+    Metric::counter(
+        "eth_checker.priority_ops_cache_hits",
+        "Number of the priority operation lookups served from the cache",
+    ),
+];
+
 #[allow(clippy::too_many_arguments)]
 pub fn start_api_server(
     connection_pool: ConnectionPool,
@@ -37,7 +106,37 @@ pub fn start_api_server(
     api_server_opts: ApiServerOptions,
     admin_server_opts: AdminServerOptions,
 ) {
+    register_metrics("API server", METRICS);
+
     let (sign_check_sender, sign_check_receiver) = mpsc::channel(8192);
+    // Limiter is shared between all the servers, so the limits can't be bypassed by
+    // switching between them.
+    let submission_limiter = SubmissionLimiter::new(&api_server_opts);
+    // Account IDs cache is kept up to date by the notifier, which sees the created accounts.
+    let account_ids = AccountIdCache::new(
+        connection_pool.clone(),
+        api_server_opts.api_requests_caches_size,
+    );
+    // Notifier is shared by the WebSocket subscriptions and the HTTP requests waiting for events.
+    let (event_sub_sender, event_sub_receiver) = mpsc::channel(2048);
+    start_sub_notifier(
+        connection_pool.clone(),
+        event_sub_receiver,
+        account_ids.clone(),
+        api_server_opts.api_requests_caches_size,
+        config_options
+            .miniblock_timings
+            .miniblock_iteration_interval,
+    );
+
+    // Certificate is shared by all the servers, so it's reloaded for all of them at once.
+    let tls = api_server_opts
+        .tls
+        .clone()
+        .map(|options| TlsConfig::load(options).expect("Unable to load the TLS certificate"));
+    if let Some(tls) = &tls {
+        tls::start_certificate_reloader(tls.clone(), panic_notify.clone());
+    }
 
     signature_checker::start_sign_checker_detached(
         config_options.clone(),
@@ -52,19 +151,38 @@ pub fn start_api_server(
         panic_notify.clone(),
         ticker_request_sender.clone(),
         sign_check_sender.clone(),
+        submission_limiter.clone(),
+        account_ids,
         config_options.clone(),
         api_server_opts.clone(),
+        tls.clone(),
     );
 
     rpc_subscriptions::start_ws_server(
         connection_pool.clone(),
         sign_check_sender.clone(),
         ticker_request_sender.clone(),
+        submission_limiter.clone(),
+        event_sub_sender.clone(),
         panic_notify.clone(),
         config_options.clone(),
         api_server_opts.clone(),
+        tls.clone(),
     );
 
+    fee_revalidator::start_fee_revalidator(
+        TxSender::new(
+            connection_pool.clone(),
+            sign_check_sender.clone(),
+            ticker_request_sender.clone(),
+            submission_limiter.clone(),
+            &api_server_opts,
+        ),
+        &FeeTickerOptions::from_env(),
+    );
+
+    lp_payout_checker::start_lp_payout_checker(connection_pool.clone(), &config_options);
+
     admin_server::start_admin_server(
         admin_server_opts.admin_http_server_address,
         admin_server_opts.secret_auth,
@@ -76,8 +194,11 @@ pub fn start_api_server(
         connection_pool,
         sign_check_sender,
         ticker_request_sender,
+        submission_limiter,
+        event_sub_sender,
         panic_notify,
         config_options,
         api_server_opts,
+        tls,
     );
 }
@@ -0,0 +1,379 @@
+//! Rate limiting of the transactions submission and JSON-RPC requests.
+//!
+//! Limits are applied per account and per client IP by the `TxSender`, so they're the same
+//! for every transport. Client IP is resolved by the transport (see `client_addr`). Limiters are
+//! shared between all the API servers, so switching to another transport doesn't reset the counters.
+//!
+//! JSON-RPC requests are additionally limited per client: clients with API keys have
+//! individual limits and allowed methods, other clients are limited per source IP.
+
+// Built-in uses
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    hash::Hash,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+// External uses
+use thiserror::Error;
+
+// Workspace uses
+use zksync_config::{ApiKeyOptions, ApiServerOptions};
+use zksync_types::Address;
+
+// Local uses
+use super::tx_sender::SubmitError;
+
+/// Time window the transaction limits are applied to.
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+
+/// Once the amount of the tracked keys exceeds this value, keys without recent events are removed.
+const KEYS_CLEANUP_THRESHOLD: usize = 10_000;
+
+/// Converts the time to wait before retrying into seconds.
+pub(crate) fn retry_after_secs(retry_after: Duration) -> u64 {
+    // Round up, so the client won't retry too early.
+    retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0)
+}
+
+/// Sliding window rate limiter: allows at most `limit` events per `window` for every key.
+#[derive(Debug, Clone)]
+struct RateLimiter<K> {
+    limit: usize,
+    window: Duration,
+    events: Arc<Mutex<HashMap<K, VecDeque<Instant>>>>,
+}
+
+impl<K: Hash + Eq + Clone> RateLimiter<K> {
+    fn new(limit: usize, window: Duration) -> Self {
+        Self {
+            limit,
+            window,
+            events: Arc::default(),
+        }
+    }
+
+    /// Registers an event for every provided key (the same key may be provided several times).
+    ///
+    /// If the limit is exceeded for any of the keys, no events are registered and
+    /// the time after which the events may be accepted is returned.
+    fn acquire(&self, keys: &[K], now: Instant) -> Result<(), Duration> {
+        let mut requested: HashMap<&K, usize> = HashMap::new();
+        for key in keys {
+            *requested.entry(key).or_insert(0) += 1;
+        }
+
+        let mut events = self.events.lock().expect("rate limiter lock poisoned");
+        if events.len() > KEYS_CLEANUP_THRESHOLD {
+            let window = self.window;
+            events.retain(|_, key_events| {
+                key_events
+                    .back()
+                    .map_or(false, |&last| now.duration_since(last) < window)
+            });
+        }
+
+        let mut retry_after = Duration::default();
+        for (&key, &count) in &requested {
+            let key_events = match events.get_mut(key) {
+                Some(key_events) => key_events,
+                None if count <= self.limit => continue,
+                None => return Err(self.window),
+            };
+            while let Some(&oldest) = key_events.front() {
+                if now.duration_since(oldest) < self.window {
+                    break;
+                }
+                key_events.pop_front();
+            }
+
+            let total = key_events.len() + count;
+            if total > self.limit {
+                // Events may be accepted once enough old events leave the window.
+                let key_retry_after = if count > self.limit {
+                    self.window
+                } else {
+                    let last_expiring = key_events[total - self.limit - 1];
+                    self.window - now.duration_since(last_expiring)
+                };
+                retry_after = retry_after.max(key_retry_after);
+            }
+        }
+        if retry_after > Duration::default() {
+            return Err(retry_after);
+        }
+
+        for (key, count) in requested {
+            events
+                .entry(key.clone())
+                .or_default()
+                .extend(std::iter::repeat(now).take(count));
+        }
+        Ok(())
+    }
+}
+
+/// Limits of the transactions submission.
+#[derive(Debug, Clone)]
+pub struct SubmissionLimiter {
+    accounts: RateLimiter<Address>,
+    ips: RateLimiter<IpAddr>,
+}
+
+impl SubmissionLimiter {
+    pub fn new(api_server_options: &ApiServerOptions) -> Self {
+        Self {
+            accounts: RateLimiter::new(
+                api_server_options.max_txs_per_account_per_minute,
+                RATE_LIMIT_WINDOW,
+            ),
+            ips: RateLimiter::new(
+                api_server_options.max_txs_per_ip_per_minute,
+                RATE_LIMIT_WINDOW,
+            ),
+        }
+    }
+
+    /// Registers transactions sent by the provided accounts (one entry per transaction).
+    pub fn check_accounts(&self, accounts: &[Address]) -> Result<(), SubmitError> {
+        self.accounts
+            .acquire(accounts, Instant::now())
+            .map_err(SubmitError::rate_limited)
+    }
+
+    /// Registers `txs_count` transactions sent from the provided IP address.
+    pub fn check_ip(&self, ip: IpAddr, txs_count: usize) -> Result<(), SubmitError> {
+        self.ips
+            .acquire(&vec![ip; txs_count], Instant::now())
+            .map_err(SubmitError::rate_limited)
+    }
+}
+
+/// Reason of the JSON-RPC request rejection.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum RequestRejection {
+    #[error("Unknown API key.")]
+    UnknownApiKey,
+    #[error("API key is required.")]
+    ApiKeyRequired,
+    #[error("Method is not allowed for the API key.")]
+    MethodNotAllowed,
+    #[error("Too many requests, retry after {retry_after} seconds.")]
+    RateLimited { retry_after: u64 },
+}
+
+impl RequestRejection {
+    fn rate_limited(retry_after: Duration) -> Self {
+        Self::RateLimited {
+            retry_after: retry_after_secs(retry_after),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::UnknownApiKey => "unknown_api_key",
+            Self::ApiKeyRequired => "api_key_required",
+            Self::MethodNotAllowed => "method_not_allowed",
+            Self::RateLimited { .. } => "rate_limited",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct ApiKeyTier {
+    limiter: RateLimiter<()>,
+    allowed_methods: Option<HashSet<String>>,
+}
+
+/// Tiered limits of the JSON-RPC requests.
+#[derive(Debug, Clone)]
+pub struct RequestLimiter {
+    api_keys: Arc<HashMap<String, ApiKeyTier>>,
+    /// Limits of the clients without API key, `None` if they're not limited.
+    anonymous: Option<RateLimiter<Option<IpAddr>>>,
+}
+
+impl RequestLimiter {
+    pub fn new(api_server_options: &ApiServerOptions) -> Self {
+        Self::with_limits(
+            &api_server_options.api_keys,
+            api_server_options.anonymous_rpc_requests_per_minute,
+        )
+    }
+
+    fn with_limits(api_keys: &[ApiKeyOptions], anonymous_limit: Option<usize>) -> Self {
+        let api_keys = api_keys
+            .iter()
+            .map(|options| {
+                let tier = ApiKeyTier {
+                    limiter: RateLimiter::new(options.requests_per_minute, RATE_LIMIT_WINDOW),
+                    allowed_methods: options.allowed_methods.clone(),
+                };
+                (options.key.clone(), tier)
+            })
+            .collect();
+
+        Self {
+            api_keys: Arc::new(api_keys),
+            anonymous: anonymous_limit.map(|limit| RateLimiter::new(limit, RATE_LIMIT_WINDOW)),
+        }
+    }
+
+    /// Registers the request to the `method`. Clients without API key are
+    /// identified by the IP address, if it's known.
+    pub fn check(
+        &self,
+        api_key: Option<&str>,
+        ip: Option<IpAddr>,
+        method: &str,
+    ) -> Result<(), RequestRejection> {
+        self.check_at(api_key, ip, method, Instant::now())
+    }
+
+    fn check_at(
+        &self,
+        api_key: Option<&str>,
+        ip: Option<IpAddr>,
+        method: &str,
+        now: Instant,
+    ) -> Result<(), RequestRejection> {
+        if let Some(api_key) = api_key {
+            let tier = self
+                .api_keys
+                .get(api_key)
+                .ok_or(RequestRejection::UnknownApiKey)?;
+            if let Some(allowed_methods) = &tier.allowed_methods {
+                if !allowed_methods.contains(method) {
+                    return Err(RequestRejection::MethodNotAllowed);
+                }
+            }
+            return tier
+                .limiter
+                .acquire(&[()], now)
+                .map_err(RequestRejection::rate_limited);
+        }
+
+        match &self.anonymous {
+            Some(limiter) if limiter.limit == 0 => Err(RequestRejection::ApiKeyRequired),
+            Some(limiter) => limiter
+                .acquire(&[ip], now)
+                .map_err(RequestRejection::rate_limited),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_limit_window() {
+        let limiter = RateLimiter::new(2, Duration::from_secs(60));
+        let start = Instant::now();
+
+        limiter.acquire(&["a"], start).unwrap();
+        limiter
+            .acquire(&["a"], start + Duration::from_secs(10))
+            .unwrap();
+        // Other keys are not affected.
+        limiter.acquire(&["b", "b"], start).unwrap();
+
+        // The first event leaves the window in 40 seconds.
+        let retry_after = limiter
+            .acquire(&["a"], start + Duration::from_secs(20))
+            .unwrap_err();
+        assert_eq!(retry_after, Duration::from_secs(40));
+
+        limiter
+            .acquire(&["a"], start + Duration::from_secs(60))
+            .unwrap();
+    }
+
+    #[test]
+    fn rate_limit_is_atomic() {
+        let limiter = RateLimiter::new(2, Duration::from_secs(60));
+        let start = Instant::now();
+
+        limiter.acquire(&["a", "a"], start).unwrap();
+        // Request exceeds the limit for "a", so no event must be registered for "b".
+        limiter.acquire(&["a", "b"], start).unwrap_err();
+        limiter.acquire(&["b", "b"], start).unwrap();
+
+        // Requests which can never fit into the limit.
+        assert_eq!(
+            limiter.acquire(&["c", "c", "c"], start).unwrap_err(),
+            Duration::from_secs(60)
+        );
+    }
+
+    fn request_limiter(anonymous_limit: Option<usize>) -> RequestLimiter {
+        let api_keys = vec![
+            "partner:3".parse().unwrap(),
+            "restricted:10:get_token_price|tokens".parse().unwrap(),
+        ];
+        RequestLimiter::with_limits(&api_keys, anonymous_limit)
+    }
+
+    #[test]
+    fn requests_limited_per_api_key() {
+        let limiter = request_limiter(Some(1));
+        let start = Instant::now();
+        let ip = Some([127, 0, 0, 1].into());
+
+        for _ in 0..3 {
+            limiter
+                .check_at(Some("partner"), ip, "account_info", start)
+                .unwrap();
+        }
+        assert_eq!(
+            limiter.check_at(Some("partner"), ip, "account_info", start),
+            Err(RequestRejection::RateLimited { retry_after: 60 })
+        );
+
+        // Anonymous limit is separate from the API key one.
+        limiter.check_at(None, ip, "account_info", start).unwrap();
+        assert!(limiter.check_at(None, ip, "account_info", start).is_err());
+        // Other IP addresses are limited separately.
+        limiter
+            .check_at(None, Some([127, 0, 0, 2].into()), "account_info", start)
+            .unwrap();
+
+        assert_eq!(
+            limiter.check_at(Some("unknown"), ip, "account_info", start),
+            Err(RequestRejection::UnknownApiKey)
+        );
+    }
+
+    #[test]
+    fn api_key_allowed_methods() {
+        let limiter = request_limiter(None);
+        let start = Instant::now();
+
+        limiter
+            .check_at(Some("restricted"), None, "tokens", start)
+            .unwrap();
+        assert_eq!(
+            limiter.check_at(Some("restricted"), None, "tx_submit", start),
+            Err(RequestRejection::MethodNotAllowed)
+        );
+
+        // Anonymous clients aren't limited without the configured limit.
+        for _ in 0..100 {
+            limiter.check_at(None, None, "tx_submit", start).unwrap();
+        }
+    }
+
+    #[test]
+    fn anonymous_access_disabled() {
+        let limiter = request_limiter(Some(0));
+
+        assert_eq!(
+            limiter.check(None, None, "tokens"),
+            Err(RequestRejection::ApiKeyRequired)
+        );
+        limiter.check(Some("partner"), None, "tokens").unwrap();
+    }
+}
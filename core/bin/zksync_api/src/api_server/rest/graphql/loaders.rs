@@ -0,0 +1,58 @@
+//! Data loaders of the objects referenced from the lists.
+//!
+//! E.g. every transaction of the block list resolves its block and token. Loaders collect
+//! the keys requested concurrently and load them with a single query, so the number of
+//! the database queries doesn't depend on the size of the lists.
+
+// Built-in uses
+use std::{collections::HashMap, sync::Arc};
+
+// External uses
+use async_graphql::dataloader::Loader;
+
+// Workspace uses
+use zksync_storage::{chain::block::records::BlockDetails, ConnectionPool};
+use zksync_types::{BlockNumber, Token, TokenId};
+
+/// Loads the details of the committed blocks by their numbers.
+pub struct BlockLoader(pub ConnectionPool);
+
+#[async_trait::async_trait]
+impl Loader<BlockNumber> for BlockLoader {
+    type Value = BlockDetails;
+    type Error = Arc<anyhow::Error>;
+
+    async fn load(
+        &self,
+        keys: &[BlockNumber],
+    ) -> Result<HashMap<BlockNumber, Self::Value>, Self::Error> {
+        let mut storage = self.0.access_storage().await?;
+        let blocks = storage
+            .chain()
+            .block_schema()
+            .load_blocks_details(keys)
+            .await?;
+
+        Ok(blocks
+            .into_iter()
+            .map(|block| (block.block_number as BlockNumber, block))
+            .collect())
+    }
+}
+
+/// Loads the tokens by their IDs.
+pub struct TokenLoader(pub ConnectionPool);
+
+#[async_trait::async_trait]
+impl Loader<TokenId> for TokenLoader {
+    type Value = Token;
+    type Error = Arc<anyhow::Error>;
+
+    async fn load(&self, keys: &[TokenId]) -> Result<HashMap<TokenId, Self::Value>, Self::Error> {
+        let mut storage = self.0.access_storage().await?;
+        let mut tokens = storage.tokens_schema().load_tokens().await?;
+        tokens.retain(|id, _| keys.contains(id));
+
+        Ok(tokens)
+    }
+}
@@ -0,0 +1,171 @@
+//! GraphQL API for the explorer-style queries.
+//!
+//! Unlike the REST API, it allows to fetch e.g. a block along with its transactions,
+//! or an account along with its balances and history within a single request.
+//! To protect the server from expensive queries, the depth and the complexity of
+//! the queries are limited, as well as the size of every requested list. Complexity
+//! of the list fields is multiplied by the requested size, and the objects referenced
+//! from the lists are batched by the data loaders.
+
+// External uses
+use actix_web::{web, HttpResponse, Scope};
+use async_graphql::{
+    dataloader::DataLoader,
+    http::{playground_source, GraphQLPlaygroundConfig},
+    EmptyMutation, EmptySubscription, Schema,
+};
+use async_graphql_actix_web::{Request, Response};
+
+// Workspace uses
+use zksync_storage::ConnectionPool;
+
+// Local uses
+use self::{
+    loaders::{BlockLoader, TokenLoader},
+    query::QueryRoot,
+};
+
+mod loaders;
+mod query;
+mod types;
+
+/// Maximum nesting level of the query.
+const MAX_DEPTH: usize = 6;
+/// Maximum complexity of the query, i.e. the total number of the requested fields
+/// taking the sizes of the requested lists into account.
+const MAX_COMPLEXITY: usize = 2500;
+/// Maximum number of the items in the requested lists.
+const MAX_LIMIT: u32 = 100;
+
+pub type ApiSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+pub fn build_schema(pool: ConnectionPool) -> ApiSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .data(DataLoader::new(BlockLoader(pool.clone())))
+        .data(DataLoader::new(TokenLoader(pool.clone())))
+        .data(pool)
+        .limit_depth(MAX_DEPTH)
+        .limit_complexity(MAX_COMPLEXITY)
+        .finish()
+}
+
+async fn graphql(schema: web::Data<ApiSchema>, request: Request) -> Response {
+    schema.execute(request.into_inner()).await.into()
+}
+
+async fn playground() -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(playground_source(GraphQLPlaygroundConfig::new("/graphql")))
+}
+
+pub fn api_scope(pool: ConnectionPool) -> Scope {
+    web::scope("/graphql")
+        .data(build_schema(pool))
+        .route("", web::post().to(graphql))
+        .route("", web::get().to(playground))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_server::rest::v1::test_utils::TestServerConfig;
+    use serde_json::{json, Value};
+
+    async fn execute(schema: &ApiSchema, query: &str) -> Value {
+        let response = schema.execute(query).await;
+        serde_json::to_value(response).unwrap()
+    }
+
+    #[actix_rt::test]
+    async fn test_graphql_queries() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let schema = build_schema(cfg.pool.clone());
+
+        let expected_blocks = cfg
+            .pool
+            .access_storage()
+            .await?
+            .chain()
+            .block_schema()
+            .load_block_range(1, 1)
+            .await?;
+        let expected_txs = cfg
+            .pool
+            .access_storage()
+            .await?
+            .chain()
+            .block_schema()
+            .get_block_transactions(1)
+            .await?;
+
+        let response = execute(
+            &schema,
+            "{ block(number: 1) { number transactions { txHash block { number } } } }",
+        )
+        .await;
+        assert!(response.get("errors").is_none(), "{}", response);
+
+        let block = &response["data"]["block"];
+        assert_eq!(block["number"], json!(expected_blocks[0].block_number));
+        let txs = block["transactions"].as_array().unwrap();
+        assert_eq!(txs.len(), expected_txs.len());
+        for (tx, expected_tx) in txs.iter().zip(&expected_txs) {
+            assert_eq!(tx["txHash"], json!(expected_tx.tx_hash));
+            assert_eq!(tx["block"]["number"], json!(1));
+        }
+
+        // Transactions are paginated.
+        let response = execute(
+            &schema,
+            "{ block(number: 1) { transactions(offset: 1, limit: 1) { txHash } } }",
+        )
+        .await;
+        assert!(response.get("errors").is_none(), "{}", response);
+        let txs = response["data"]["block"]["transactions"]
+            .as_array()
+            .unwrap();
+        assert_eq!(txs.len(), 1);
+        assert_eq!(txs[0]["txHash"], json!(expected_txs[1].tx_hash));
+
+        let response = execute(
+            &schema,
+            "{ tokens { id symbol } token(token: \"ETH\") { id } }",
+        )
+        .await;
+        assert!(response.get("errors").is_none(), "{}", response);
+        assert_eq!(response["data"]["token"]["id"], json!(0));
+        assert_eq!(response["data"]["tokens"][0]["symbol"], json!("ETH"));
+
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    async fn test_graphql_limits() -> anyhow::Result<()> {
+        let schema = build_schema(ConnectionPool::new(Some(1)));
+
+        // Too deep query is rejected before the execution.
+        let response = execute(
+            &schema,
+            "{ blocks { transactions { block { transactions { block { transactions { txHash } } } } } } }",
+        )
+        .await;
+        assert!(response.get("errors").is_some(), "{}", response);
+
+        // Limit of the list size is checked before accessing the database.
+        let response = execute(&schema, "{ blocks(limit: 1000) { number } }").await;
+        assert!(response.get("errors").is_some(), "{}", response);
+
+        // Nested lists multiply the complexity, even if each of them is within the limit.
+        let response = execute(
+            &schema,
+            "{ blocks(limit: 100) { transactions(limit: 100) { txHash } } }",
+        )
+        .await;
+        let errors = response["errors"].to_string();
+        assert!(errors.contains("complex"), "{}", response);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,90 @@
+//! Root of the GraphQL queries.
+
+// Built-in uses
+use std::str::FromStr;
+
+// External uses
+use async_graphql::{Context, Object, Result};
+
+// Workspace uses
+use zksync_types::{Address, BlockNumber, TokenLike};
+
+// Local uses
+use super::types::{access_storage, check_limit, load_block, Account, Block, Token, Transaction};
+use super::MAX_LIMIT;
+use crate::api_server::helpers::try_parse_hash;
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    async fn block(&self, ctx: &Context<'_>, number: BlockNumber) -> Result<Option<Block>> {
+        load_block(ctx, number).await
+    }
+
+    /// Blocks in the descending order, starting from `maxBlock` or the last committed block.
+    #[graphql(complexity = "limit as usize * child_complexity")]
+    async fn blocks(
+        &self,
+        ctx: &Context<'_>,
+        max_block: Option<BlockNumber>,
+        #[graphql(default = 20)] limit: u32,
+    ) -> Result<Vec<Block>> {
+        let limit = check_limit(limit)?;
+
+        let mut storage = access_storage(ctx).await?;
+        let blocks = storage
+            .chain()
+            .block_schema()
+            .load_block_range(max_block.unwrap_or(BlockNumber::MAX), limit)
+            .await?;
+
+        Ok(blocks.into_iter().map(Block).collect())
+    }
+
+    /// Executed transaction or priority operation by its hash.
+    async fn transaction(&self, ctx: &Context<'_>, hash: String) -> Result<Option<Transaction>> {
+        let hash = try_parse_hash(&hash).map_err(|_| "Invalid transaction hash")?;
+
+        let mut storage = access_storage(ctx).await?;
+        let tx = storage
+            .chain()
+            .operations_ext_schema()
+            .get_tx_by_hash(hash.as_ref())
+            .await?;
+
+        Ok(tx.map(Transaction))
+    }
+
+    async fn account(&self, address: String) -> Result<Account> {
+        let address = Address::from_str(address.trim_start_matches("0x"))
+            .map_err(|_| "Invalid account address")?;
+        Ok(Account { address })
+    }
+
+    #[graphql(complexity = "MAX_LIMIT as usize * child_complexity")]
+    async fn tokens(&self, ctx: &Context<'_>) -> Result<Vec<Token>> {
+        let mut storage = access_storage(ctx).await?;
+        let mut tokens: Vec<_> = storage
+            .tokens_schema()
+            .load_tokens()
+            .await?
+            .into_iter()
+            .map(|(_, token)| token)
+            .collect();
+        tokens.sort_by_key(|token| token.id);
+
+        Ok(tokens.into_iter().map(Token).collect())
+    }
+
+    /// Token by its ID, address or symbol.
+    async fn token(&self, ctx: &Context<'_>, token: String) -> Result<Option<Token>> {
+        let mut storage = access_storage(ctx).await?;
+        let token = storage
+            .tokens_schema()
+            .get_token(TokenLike::parse(&token))
+            .await?;
+
+        Ok(token.map(Token))
+    }
+}
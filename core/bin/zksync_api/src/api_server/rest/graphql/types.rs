@@ -0,0 +1,364 @@
+//! GraphQL objects of the explorer API.
+//!
+//! Objects wrap the storage records and resolve the nested objects lazily,
+//! so the database is accessed only for the fields requested by the client.
+
+// External uses
+use async_graphql::{dataloader::DataLoader, Context, Json, Object, Result};
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+// Workspace uses
+use zksync_storage::{
+    chain::{
+        block::records::{BlockDetails, BlockTransactionItem},
+        operations_ext::records::{TransactionsHistoryItem, TxByHashResponse},
+    },
+    ConnectionPool, StorageProcessor,
+};
+use zksync_types::{Address, BlockNumber, TokenId};
+
+// Local uses
+use super::{
+    loaders::{BlockLoader, TokenLoader},
+    MAX_LIMIT,
+};
+
+pub(super) async fn access_storage<'a>(ctx: &Context<'a>) -> Result<StorageProcessor<'a>> {
+    let pool = ctx.data::<ConnectionPool>()?;
+    Ok(pool.access_storage().await?)
+}
+
+pub(super) fn check_limit(limit: u32) -> Result<u32> {
+    if limit > MAX_LIMIT {
+        return Err(format!("Limit must not exceed {}", MAX_LIMIT).into());
+    }
+    Ok(limit)
+}
+
+pub(super) async fn load_block(ctx: &Context<'_>, number: BlockNumber) -> Result<Option<Block>> {
+    let loader = ctx.data::<DataLoader<BlockLoader>>()?;
+    let block = loader.load_one(number).await?;
+    Ok(block.map(Block))
+}
+
+async fn load_token(ctx: &Context<'_>, token_id: TokenId) -> Result<Option<Token>> {
+    let loader = ctx.data::<DataLoader<TokenLoader>>()?;
+    let token = loader.load_one(token_id).await?;
+    Ok(token.map(Token))
+}
+
+fn eth_tx_hash(hash: &[u8]) -> String {
+    format!("0x{}", hex::encode(hash))
+}
+
+pub struct Block(pub BlockDetails);
+
+#[Object]
+impl Block {
+    async fn number(&self) -> i64 {
+        self.0.block_number
+    }
+
+    /// Root hash of the state tree with the `sync-bl:` prefix.
+    async fn new_state_root(&self) -> String {
+        format!("sync-bl:{}", hex::encode(&self.0.new_state_root))
+    }
+
+    async fn block_size(&self) -> i64 {
+        self.0.block_size
+    }
+
+    async fn commit_tx_hash(&self) -> Option<String> {
+        self.0.commit_tx_hash.as_deref().map(eth_tx_hash)
+    }
+
+    async fn verify_tx_hash(&self) -> Option<String> {
+        self.0.verify_tx_hash.as_deref().map(eth_tx_hash)
+    }
+
+    async fn committed_at(&self) -> DateTime<Utc> {
+        self.0.committed_at
+    }
+
+    async fn verified_at(&self) -> Option<DateTime<Utc>> {
+        self.0.verified_at
+    }
+
+    /// Transactions of the block from the newest to the oldest one.
+    #[graphql(complexity = "limit as usize * child_complexity")]
+    async fn transactions(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default)] offset: u64,
+        #[graphql(default = 20)] limit: u32,
+    ) -> Result<Vec<BlockTransaction>> {
+        let limit = check_limit(limit)?;
+
+        let mut storage = access_storage(ctx).await?;
+        let txs = storage
+            .chain()
+            .block_schema()
+            .get_block_transactions_page(self.0.block_number as BlockNumber, offset, Some(limit))
+            .await?;
+
+        Ok(txs.into_iter().map(BlockTransaction).collect())
+    }
+}
+
+pub struct BlockTransaction(pub BlockTransactionItem);
+
+#[Object]
+impl BlockTransaction {
+    async fn tx_hash(&self) -> &str {
+        &self.0.tx_hash
+    }
+
+    /// Executed operation, either a transaction or a priority operation.
+    async fn op(&self) -> Json<Value> {
+        Json(self.0.op.clone())
+    }
+
+    async fn success(&self) -> Option<bool> {
+        self.0.success
+    }
+
+    async fn fail_reason(&self) -> Option<&str> {
+        self.0.fail_reason.as_deref()
+    }
+
+    async fn created_at(&self) -> DateTime<Utc> {
+        self.0.created_at
+    }
+
+    async fn block(&self, ctx: &Context<'_>) -> Result<Option<Block>> {
+        load_block(ctx, self.0.block_number as BlockNumber).await
+    }
+}
+
+pub struct Transaction(pub TxByHashResponse);
+
+#[Object]
+impl Transaction {
+    async fn tx_type(&self) -> &str {
+        &self.0.tx_type
+    }
+
+    async fn from(&self) -> &str {
+        &self.0.from
+    }
+
+    async fn to(&self) -> &str {
+        &self.0.to
+    }
+
+    async fn amount(&self) -> &str {
+        &self.0.amount
+    }
+
+    async fn fee(&self) -> Option<&str> {
+        self.0.fee.as_deref()
+    }
+
+    async fn nonce(&self) -> i64 {
+        self.0.nonce
+    }
+
+    async fn created_at(&self) -> &str {
+        &self.0.created_at
+    }
+
+    async fn fail_reason(&self) -> Option<&str> {
+        self.0.fail_reason.as_deref()
+    }
+
+    /// Transaction or priority operation in the form it was submitted.
+    async fn tx(&self) -> Json<Value> {
+        Json(self.0.tx.clone())
+    }
+
+    async fn token(&self, ctx: &Context<'_>) -> Result<Option<Token>> {
+        load_token(ctx, self.0.token as TokenId).await
+    }
+
+    /// Block containing the transaction, absent for the unconfirmed priority operations.
+    async fn block(&self, ctx: &Context<'_>) -> Result<Option<Block>> {
+        if self.0.block_number < 0 {
+            return Ok(None);
+        }
+        load_block(ctx, self.0.block_number as BlockNumber).await
+    }
+}
+
+pub struct Account {
+    pub address: Address,
+}
+
+#[Object]
+impl Account {
+    async fn address(&self) -> String {
+        format!("{:?}", self.address)
+    }
+
+    /// Account ID, absent if the account is not created yet.
+    async fn id(&self, ctx: &Context<'_>) -> Result<Option<u32>> {
+        let mut storage = access_storage(ctx).await?;
+        let id = storage
+            .chain()
+            .account_schema()
+            .account_id_by_address(self.address)
+            .await?;
+        Ok(id)
+    }
+
+    async fn committed(&self, ctx: &Context<'_>) -> Result<Option<AccountState>> {
+        let mut storage = access_storage(ctx).await?;
+        let state = storage
+            .chain()
+            .account_schema()
+            .account_state_by_address(self.address)
+            .await?;
+        Ok(state.committed.map(|(_, account)| AccountState(account)))
+    }
+
+    async fn verified(&self, ctx: &Context<'_>) -> Result<Option<AccountState>> {
+        let mut storage = access_storage(ctx).await?;
+        let state = storage
+            .chain()
+            .account_schema()
+            .account_state_by_address(self.address)
+            .await?;
+        Ok(state.verified.map(|(_, account)| AccountState(account)))
+    }
+
+    /// Transactions history of the account from the oldest to the newest one.
+    #[graphql(complexity = "limit as usize * child_complexity")]
+    async fn transactions(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default)] offset: u64,
+        #[graphql(default = 20)] limit: u32,
+    ) -> Result<Vec<HistoryItem>> {
+        let limit = check_limit(limit)?;
+
+        let mut storage = access_storage(ctx).await?;
+        let txs = storage
+            .chain()
+            .operations_ext_schema()
+            .get_account_transactions_history(&self.address, offset, limit as u64)
+            .await?;
+
+        Ok(txs.into_iter().map(HistoryItem).collect())
+    }
+}
+
+pub struct AccountState(pub zksync_types::Account);
+
+#[Object]
+impl AccountState {
+    async fn nonce(&self) -> u32 {
+        self.0.nonce
+    }
+
+    async fn pub_key_hash(&self) -> String {
+        self.0.pub_key_hash.to_hex()
+    }
+
+    #[graphql(complexity = "MAX_LIMIT as usize * child_complexity")]
+    async fn balances(&self) -> Vec<Balance> {
+        let mut balances: Vec<_> = self
+            .0
+            .get_nonzero_balances()
+            .into_iter()
+            .map(|(token_id, amount)| Balance {
+                token_id,
+                amount: amount.0.to_string(),
+            })
+            .collect();
+        balances.sort_by_key(|balance| balance.token_id);
+        balances
+    }
+}
+
+pub struct Balance {
+    token_id: TokenId,
+    amount: String,
+}
+
+#[Object]
+impl Balance {
+    async fn amount(&self) -> &str {
+        &self.amount
+    }
+
+    async fn token(&self, ctx: &Context<'_>) -> Result<Option<Token>> {
+        load_token(ctx, self.token_id).await
+    }
+}
+
+pub struct HistoryItem(pub TransactionsHistoryItem);
+
+#[Object]
+impl HistoryItem {
+    async fn tx_id(&self) -> &str {
+        &self.0.tx_id
+    }
+
+    async fn hash(&self) -> Option<&str> {
+        self.0.hash.as_deref()
+    }
+
+    async fn eth_block(&self) -> Option<i64> {
+        self.0.eth_block
+    }
+
+    async fn pq_id(&self) -> Option<i64> {
+        self.0.pq_id
+    }
+
+    async fn tx(&self) -> Json<Value> {
+        Json(self.0.tx.clone())
+    }
+
+    async fn success(&self) -> Option<bool> {
+        self.0.success
+    }
+
+    async fn fail_reason(&self) -> Option<&str> {
+        self.0.fail_reason.as_deref()
+    }
+
+    async fn committed(&self) -> bool {
+        self.0.commited
+    }
+
+    async fn verified(&self) -> bool {
+        self.0.verified
+    }
+
+    async fn created_at(&self) -> DateTime<Utc> {
+        self.0.created_at
+    }
+}
+
+pub struct Token(pub zksync_types::Token);
+
+#[Object]
+impl Token {
+    async fn id(&self) -> TokenId {
+        self.0.id
+    }
+
+    /// Address of the ERC20 contract, or zero address for ETH.
+    async fn address(&self) -> String {
+        format!("{:?}", self.0.address)
+    }
+
+    async fn symbol(&self) -> &str {
+        &self.0.symbol
+    }
+
+    async fn decimals(&self) -> u8 {
+        self.0.decimals
+    }
+}
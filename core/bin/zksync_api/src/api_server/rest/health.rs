@@ -0,0 +1,237 @@
+//! Health, readiness and version endpoints used by load balancers and orchestration tools.
+//!
+//! - `/healthz` reports whether the server is alive, i.e. it's able to reach the database.
+//! - `/readyz` reports whether the server should receive traffic: in addition to the database
+//!   it checks the Ethereum node, `eth_sender` progress and the age of the last sealed block.
+//!
+//! Both endpoints respond with `503 Service Unavailable` if any of the checks has failed,
+//! the response body contains the status of every check.
+
+// Built-in uses
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// External uses
+use actix_web::{web, HttpResponse};
+use serde::Serialize;
+use web3::{transports::Http, Web3};
+
+// Workspace uses
+use zksync_config::{ApiServerOptions, ConfigurationOptions};
+use zksync_storage::ConnectionPool;
+
+/// Maximum time a single check may take.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Status of a single subsystem.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckStatus {
+    pub name: &'static str,
+    pub healthy: bool,
+    pub details: String,
+}
+
+impl CheckStatus {
+    fn new(name: &'static str, result: Result<String, String>) -> Self {
+        let (healthy, details) = match result {
+            Ok(details) => (true, details),
+            Err(details) => (false, details),
+        };
+        Self {
+            name,
+            healthy,
+            details,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthReport {
+    pub healthy: bool,
+    pub checks: Vec<CheckStatus>,
+}
+
+impl HealthReport {
+    fn new(checks: Vec<CheckStatus>) -> Self {
+        Self {
+            healthy: checks.iter().all(|check| check.healthy),
+            checks,
+        }
+    }
+
+    fn into_response(self) -> HttpResponse {
+        if self.healthy {
+            HttpResponse::Ok().json(self)
+        } else {
+            HttpResponse::ServiceUnavailable().json(self)
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionInfo {
+    pub name: &'static str,
+    pub version: &'static str,
+}
+
+/// Shared data between the health endpoints.
+#[derive(Clone)]
+pub struct HealthData {
+    pool: ConnectionPool,
+    web3: Web3<Http>,
+    eth_sender_stall_blocks: u64,
+    max_sealed_block_age: Option<Duration>,
+}
+
+impl HealthData {
+    pub fn new(
+        pool: ConnectionPool,
+        env_options: &ConfigurationOptions,
+        api_server_options: &ApiServerOptions,
+    ) -> Self {
+        let transport = Http::new(&env_options.web3_url).expect("Failed to create web3 transport");
+        Self {
+            pool,
+            web3: Web3::new(transport),
+            eth_sender_stall_blocks: api_server_options.health_eth_sender_stall_blocks,
+            max_sealed_block_age: api_server_options.health_max_sealed_block_age,
+        }
+    }
+
+    /// Registers the health endpoints in the application root.
+    pub fn configure(self, cfg: &mut web::ServiceConfig) {
+        cfg.data(self)
+            .route("/healthz", web::get().to(healthz))
+            .route("/readyz", web::get().to(readyz))
+            .route("/version", web::get().to(version));
+    }
+
+    async fn check_database(&self) -> Result<String, String> {
+        let mut storage = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(|e| e.to_string())?;
+        let last_block = storage
+            .chain()
+            .block_schema()
+            .get_last_committed_block()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(format!("Last committed block: {}", last_block))
+    }
+
+    async fn ethereum_block(&self) -> Result<u64, String> {
+        let block = tokio::time::timeout(CHECK_TIMEOUT, self.web3.eth().block_number())
+            .await
+            .map_err(|_| "Ethereum node request timed out".to_string())?
+            .map_err(|e| e.to_string())?;
+
+        Ok(block.as_u64())
+    }
+
+    async fn check_eth_sender(&self, current_block: u64) -> Result<String, String> {
+        let mut storage = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(|e| e.to_string())?;
+        let unconfirmed_ops = storage
+            .ethereum_schema()
+            .load_unconfirmed_operations()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        // `eth_sender` resends stuck operations, so an operation which stays past
+        // its deadline for a long time means that `eth_sender` doesn't make progress.
+        let stalled_op = unconfirmed_ops
+            .iter()
+            .find(|op| current_block > op.last_deadline_block + self.eth_sender_stall_blocks);
+        match stalled_op {
+            Some(op) => Err(format!(
+                "Operation {} is not confirmed since the deadline block {}",
+                op.id, op.last_deadline_block
+            )),
+            None => Ok(format!("Unconfirmed operations: {}", unconfirmed_ops.len())),
+        }
+    }
+
+    async fn check_sealed_block_age(&self) -> Result<String, String> {
+        let mut storage = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(|e| e.to_string())?;
+        let timestamp = storage
+            .chain()
+            .block_schema()
+            .get_last_block_timestamp()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let timestamp = match timestamp {
+            Some(timestamp) => timestamp,
+            None => return Ok("Last block timestamp is unknown".to_string()),
+        };
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Current time is before the UNIX epoch")
+            .as_secs();
+        let age = Duration::from_secs(now.saturating_sub(timestamp));
+
+        let details = format!("Last block was sealed {} seconds ago", age.as_secs());
+        match self.max_sealed_block_age {
+            Some(max_age) if age > max_age => Err(details),
+            _ => Ok(details),
+        }
+    }
+
+    async fn readiness_checks(&self) -> Vec<CheckStatus> {
+        let mut checks = vec![CheckStatus::new("database", self.check_database().await)];
+
+        let ethereum_block = self.ethereum_block().await;
+        checks.push(CheckStatus::new(
+            "ethereum",
+            ethereum_block
+                .clone()
+                .map(|block| format!("Current block: {}", block)),
+        ));
+        let eth_sender = match ethereum_block {
+            Ok(block) => self.check_eth_sender(block).await,
+            Err(_) => Err("Ethereum node is unreachable".to_string()),
+        };
+        checks.push(CheckStatus::new("eth_sender", eth_sender));
+        checks.push(CheckStatus::new(
+            "sealed_block_age",
+            self.check_sealed_block_age().await,
+        ));
+
+        checks
+    }
+}
+
+// Server implementation
+
+async fn healthz(data: web::Data<HealthData>) -> HttpResponse {
+    let checks = vec![CheckStatus::new("database", data.check_database().await)];
+    HealthReport::new(checks).into_response()
+}
+
+async fn readyz(data: web::Data<HealthData>) -> HttpResponse {
+    let checks = data.readiness_checks().await;
+    let report = HealthReport::new(checks);
+    if !report.healthy {
+        vlog::warn!("Readiness check failed: {:?}", report.checks);
+    }
+    report.into_response()
+}
+
+async fn version() -> HttpResponse {
+    HttpResponse::Ok().json(VersionInfo {
+        name: env!("CARGO_PKG_NAME"),
+        version: env!("CARGO_PKG_VERSION"),
+    })
+}
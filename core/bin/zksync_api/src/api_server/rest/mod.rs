@@ -8,25 +8,51 @@ use zksync_types::H160;
 
 use zksync_utils::panic_notify::ThreadPanicNotify;
 
-use self::v01::api_decl::ApiV01;
-use crate::{fee_ticker::TickerRequest, signature_checker::VerifyTxSignatureRequest};
+use self::{health::HealthData, v01::api_decl::ApiV01};
+use crate::{
+    fee_ticker::TickerRequest, signature_checker::VerifyTxSignatureRequest,
+    utils::account_id_cache::AccountIdCache,
+};
 
-use super::tx_sender::TxSender;
+use super::{rate_limiter::SubmissionLimiter, tls::TlsConfig, tx_sender::TxSender};
 
+mod graphql;
+mod health;
 mod helpers;
 mod v01;
 pub mod v1;
 
+/// Builds CORS middleware allowing either the configured origins or any origin.
+fn cors(allowed_origins: &[String]) -> Cors {
+    let cors = Cors::new().max_age(3600);
+    if allowed_origins.is_empty() {
+        return cors.send_wildcard();
+    }
+
+    allowed_origins
+        .iter()
+        .fold(cors, |cors, origin| cors.allowed_origin(origin))
+}
+
 async fn start_server(
     api_v01: ApiV01,
     fee_ticker: mpsc::Sender<TickerRequest>,
     sign_verifier: mpsc::Sender<VerifyTxSignatureRequest>,
+    submission_limiter: SubmissionLimiter,
+    account_ids: AccountIdCache,
     bind_to: SocketAddr,
+    tls: Option<TlsConfig>,
 ) {
     let logger_format = crate::api_server::loggers::rest::get_logger_format();
+    let health_data = HealthData::new(
+        api_v01.connection_pool.clone(),
+        &api_v01.config_options,
+        &api_v01.api_server_options,
+    );
 
-    HttpServer::new(move || {
+    let server = HttpServer::new(move || {
         let api_v01 = api_v01.clone();
+        let health_data = health_data.clone();
 
         let api_v1_scope = {
             let env_options = api_v01.config_options.clone();
@@ -36,29 +62,42 @@ async fn start_server(
                 api_v01.connection_pool.clone(),
                 sign_verifier.clone(),
                 fee_ticker.clone(),
+                submission_limiter.clone(),
                 &api_server_options,
             );
-            v1::api_scope(tx_sender, env_options, api_server_options)
+            v1::api_scope(
+                tx_sender,
+                account_ids.clone(),
+                env_options,
+                api_server_options,
+            )
         };
 
         App::new()
             .wrap(middleware::Logger::new(&logger_format))
-            .wrap(Cors::new().send_wildcard().max_age(3600).finish())
+            .wrap(cors(&api_v01.api_server_options.cors_allowed_origins).finish())
             .service(api_v01.into_scope())
             .service(api_v1_scope)
+            .service(graphql::api_scope(api_v01.connection_pool.clone()))
+            .configure(|cfg| health_data.configure(cfg))
             // Endpoint needed for js isReachable
             .route(
                 "/favicon.ico",
                 web::get().to(|| HttpResponse::Ok().finish()),
             )
     })
-    .workers(super::THREADS_PER_SERVER)
-    .bind(bind_to)
-    .unwrap()
-    .shutdown_timeout(1)
-    .run()
-    .await
-    .expect("REST API server has crashed");
+    .workers(super::THREADS_PER_SERVER);
+
+    let server = match tls {
+        Some(tls) => server.bind_rustls(bind_to, tls.server_config()),
+        None => server.bind(bind_to),
+    };
+    server
+        .unwrap()
+        .shutdown_timeout(1)
+        .run()
+        .await
+        .expect("REST API server has crashed");
 }
 
 /// Start HTTP REST API
@@ -70,8 +109,11 @@ pub(super) fn start_server_thread_detached(
     panic_notify: mpsc::Sender<bool>,
     fee_ticker: mpsc::Sender<TickerRequest>,
     sign_verifier: mpsc::Sender<VerifyTxSignatureRequest>,
+    submission_limiter: SubmissionLimiter,
+    account_ids: AccountIdCache,
     config_options: ConfigurationOptions,
     api_server_options: ApiServerOptions,
+    tls: Option<TlsConfig>,
 ) {
     std::thread::Builder::new()
         .name("actix-rest-api".to_string())
@@ -87,7 +129,16 @@ pub(super) fn start_server_thread_detached(
                 );
                 api_v01.spawn_network_status_updater(panic_notify);
 
-                start_server(api_v01, fee_ticker, sign_verifier, listen_addr).await;
+                start_server(
+                    api_v01,
+                    fee_ticker,
+                    sign_verifier,
+                    submission_limiter,
+                    account_ids,
+                    listen_addr,
+                    tls,
+                )
+                .await;
             });
         })
         .expect("Api server thread");
@@ -100,6 +100,7 @@ impl ApiV01 {
                 "/withdrawal_processing_time",
                 web::get().to(Self::withdrawal_processing_time),
             )
+            .route("/spec", web::get().to(Self::spec))
     }
 
     pub(crate) async fn access_storage(&self) -> ActixResult<StorageProcessor<'_>> {
@@ -8,13 +8,16 @@ use crate::api_server::{
     helpers::try_parse_hash,
     rest::{
         helpers::{deposit_op_to_tx_by_hash, parse_tx_id, priority_op_to_tx_history},
-        v01::{api_decl::ApiV01, types::*},
+        v01::{api_decl::ApiV01, spec::api_spec, types::*},
     },
 };
 use actix_web::{web, HttpResponse, Result as ActixResult};
 use std::time::Instant;
-use zksync_storage::chain::operations_ext::SearchDirection;
-use zksync_types::{Address, BlockNumber};
+use zksync_storage::{
+    chain::operations_ext::{AccountTxsHistoryFilter, SearchDirection},
+    StorageProcessor,
+};
+use zksync_types::{Address, BlockNumber, PriorityOp, TokenLike, ZkSyncPriorityOp};
 
 /// Helper macro which wraps the serializable object into `Ok(HttpResponse::Ok().json(...))`.
 macro_rules! ok_json {
@@ -23,7 +26,51 @@ macro_rules! ok_json {
     };
 }
 
+/// Checks whether the unconfirmed deposit should be reported in the filtered history.
+fn ongoing_op_matches(filter: &AccountTxsHistoryFilter, op: &PriorityOp) -> bool {
+    let (tx_type, token) = match &op.data {
+        ZkSyncPriorityOp::Deposit(deposit) => ("Deposit", deposit.token),
+        ZkSyncPriorityOp::FullExit(full_exit) => ("FullExit", full_exit.token),
+    };
+
+    // Unconfirmed operations are the newest ones, so they can't match the upper time bound.
+    filter
+        .token
+        .map_or(true, |filter_token| filter_token == token)
+        && filter
+            .tx_type
+            .as_ref()
+            .map_or(true, |filter_type| filter_type == tx_type)
+        && filter.created_before.is_none()
+}
+
 impl ApiV01 {
+    /// Creates the storage filter from the history query, resolving the token.
+    async fn history_filter(
+        query: &TxHistoryQuery,
+        storage: &mut StorageProcessor<'_>,
+    ) -> ActixResult<AccountTxsHistoryFilter> {
+        let token = match &query.token {
+            Some(token) => {
+                let token = storage
+                    .tokens_schema()
+                    .get_token(TokenLike::parse(token))
+                    .await
+                    .map_err(Self::db_error)?
+                    .ok_or_else(|| HttpResponse::BadRequest().finish())?;
+                Some(token.id)
+            }
+            None => None,
+        };
+
+        Ok(AccountTxsHistoryFilter {
+            token,
+            tx_type: query.tx_type.clone(),
+            created_after: query.created_after,
+            created_before: query.created_before,
+        })
+    }
+
     pub async fn testnet_config(self_: web::Data<Self>) -> ActixResult<HttpResponse> {
         let start = Instant::now();
         let contract_address = self_.contract_address.clone();
@@ -51,7 +98,7 @@ impl ApiV01 {
         vec_tokens.sort_by_key(|t| t.id);
 
         metrics::histogram!("api.v01.tokens", start.elapsed());
-        ok_json!(vec_tokens)
+        ok_json!(into_responses::<_, TokenResponse>(vec_tokens))
     }
 
     pub async fn tx_history(
@@ -147,7 +194,9 @@ impl ApiV01 {
         transactions_history.append(&mut ongoing_transactions_history);
 
         metrics::histogram!("api.v01.tx_history", start.elapsed());
-        ok_json!(transactions_history)
+        ok_json!(into_responses::<_, TxHistoryItemResponse>(
+            transactions_history
+        ))
     }
 
     pub async fn tx_history_older_than(
@@ -167,12 +216,13 @@ impl ApiV01 {
         let mut transaction = storage.start_transaction().await.map_err(Self::db_error)?;
 
         let tx_id = parse_tx_id(&tx_id, &mut transaction).await?;
+        let filter = Self::history_filter(&query, &mut transaction).await?;
 
         let direction = SearchDirection::Older;
         let transactions_history = transaction
             .chain()
             .operations_ext_schema()
-            .get_account_transactions_history_from(&address, tx_id, direction, limit)
+            .get_account_transactions_history_from(&address, tx_id, direction, limit, &filter)
             .await
             .map_err(|err| {
                 vlog::warn!(
@@ -188,7 +238,9 @@ impl ApiV01 {
         transaction.commit().await.map_err(Self::db_error)?;
 
         metrics::histogram!("api.v01.tx_history_older_than", start.elapsed());
-        ok_json!(transactions_history)
+        ok_json!(into_responses::<_, TxHistoryItemResponse>(
+            transactions_history
+        ))
     }
 
     pub async fn tx_history_newer_than(
@@ -206,13 +258,14 @@ impl ApiV01 {
         }
 
         let direction = SearchDirection::Newer;
-        let mut transactions_history = {
+        let (filter, mut transactions_history) = {
             let mut storage = self_.access_storage().await?;
+            let filter = Self::history_filter(&query, &mut storage).await?;
             let tx_id = parse_tx_id(&tx_id, &mut storage).await?;
-            storage
+            let transactions_history = storage
                 .chain()
                 .operations_ext_schema()
-                .get_account_transactions_history_from(&address, tx_id, direction, limit)
+                .get_account_transactions_history_from(&address, tx_id, direction, limit, &filter)
                 .await
                 .map_err(|err| {
                     vlog::warn!(
@@ -223,7 +276,8 @@ impl ApiV01 {
                         limit,
                     );
                     HttpResponse::InternalServerError().finish()
-                })?
+                })?;
+            (filter, transactions_history)
         };
 
         limit -= transactions_history.len() as u64;
@@ -271,6 +325,7 @@ impl ApiV01 {
             // `limit` parameters.
             let mut txs: Vec<_> = ongoing_ops
                 .iter()
+                .filter(|(_, op)| ongoing_op_matches(&filter, op))
                 .map(|(block, op)| priority_op_to_tx_history(&tokens, *block, op))
                 .take(limit as usize)
                 .collect();
@@ -282,7 +337,9 @@ impl ApiV01 {
         }
 
         metrics::histogram!("api.v01.tx_history_newer_than", start.elapsed());
-        ok_json!(transactions_history)
+        ok_json!(into_responses::<_, TxHistoryItemResponse>(
+            transactions_history
+        ))
     }
 
     pub async fn executed_tx_by_hash(
@@ -299,7 +356,7 @@ impl ApiV01 {
         let tx_receipt = self_.get_tx_receipt(transaction_hash).await?;
 
         metrics::histogram!("api.v01.executed_tx_by_hash", start.elapsed());
-        ok_json!(tx_receipt)
+        ok_json!(tx_receipt.map(ExecutedTxResponse::from))
     }
 
     pub async fn tx_by_hash(
@@ -328,7 +385,7 @@ impl ApiV01 {
 
         // If storage returns Some, return the result.
         if res.is_some() {
-            return ok_json!(res);
+            return ok_json!(res.map(TxResponse::from));
         }
 
         // Or try to find this priority op in eth_watcher
@@ -362,7 +419,7 @@ impl ApiV01 {
         }
 
         metrics::histogram!("api.v01.tx_by_hash", start.elapsed());
-        ok_json!(res)
+        ok_json!(res.map(TxResponse::from))
     }
 
     pub async fn priority_op(
@@ -372,7 +429,7 @@ impl ApiV01 {
         let start = Instant::now();
         let receipt = self_.get_priority_op_receipt(pq_id).await?;
         metrics::histogram!("api.v01.priority_op", start.elapsed());
-        ok_json!(receipt)
+        ok_json!(PriorityOpResponse::from(receipt))
     }
 
     pub async fn block_tx(
@@ -383,7 +440,7 @@ impl ApiV01 {
         let exec_ops = self_.get_block_executed_ops(block_id).await?;
 
         let result = if let Some(exec_op) = exec_ops.get(tx_id as usize) {
-            ok_json!(exec_op.clone())
+            ok_json!(BlockOperationResponse(exec_op.clone()))
         } else {
             Err(HttpResponse::NotFound().finish().into())
         };
@@ -421,7 +478,7 @@ impl ApiV01 {
             })?;
 
         metrics::histogram!("api.v01.blocks", start.elapsed());
-        ok_json!(resp)
+        ok_json!(into_responses::<_, BlockResponse>(resp))
     }
 
     pub async fn block_by_id(
@@ -431,7 +488,7 @@ impl ApiV01 {
         let start = Instant::now();
         let block = self_.get_block_info(block_id).await?;
         let result = if let Some(block) = block {
-            ok_json!(block)
+            ok_json!(BlockResponse::from(block))
         } else {
             Err(HttpResponse::NotFound().finish().into())
         };
@@ -457,7 +514,7 @@ impl ApiV01 {
             })?;
 
         metrics::histogram!("api.v01.block_transactions", start.elapsed());
-        ok_json!(txs)
+        ok_json!(into_responses::<_, BlockTxResponse>(txs))
     }
 
     pub async fn explorer_search(
@@ -468,7 +525,7 @@ impl ApiV01 {
         let block = self_.get_block_by_height_or_hash(block_query.query).await?;
 
         let result = if let Some(block) = block {
-            ok_json!(block)
+            ok_json!(BlockResponse::from(block))
         } else {
             Err(HttpResponse::NotFound().finish().into())
         };
@@ -492,4 +549,8 @@ impl ApiV01 {
         metrics::histogram!("api.v01.withdrawal_processing_time", start.elapsed());
         ok_json!(processing_time)
     }
+
+    pub async fn spec() -> ActixResult<HttpResponse> {
+        ok_json!(api_spec())
+    }
 }
@@ -2,4 +2,5 @@ pub mod api_decl;
 pub mod api_impl;
 pub mod caches;
 pub mod network_status;
+pub mod spec;
 pub mod types;
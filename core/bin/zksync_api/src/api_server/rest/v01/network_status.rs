@@ -1,4 +1,5 @@
 use futures::channel::mpsc;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use std::time::Duration;
@@ -8,7 +9,7 @@ use zksync_storage::ConnectionPool;
 use zksync_types::BlockNumber;
 use zksync_utils::panic_notify::ThreadPanicNotify;
 
-#[derive(Default, Debug, Serialize, Deserialize, Clone)]
+#[derive(Default, Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct NetworkStatus {
     pub next_block_at_max: Option<u64>,
     pub last_committed: BlockNumber,
@@ -45,7 +46,7 @@ impl SharedNetworkStatus {
                         let mut storage = match connection_pool.access_storage().await {
                             Ok(storage) => storage,
                             Err(err) => {
-                                log::warn!("Unable to update the network status. Storage access failed: {}", err);
+                                tracing::warn!("Unable to update the network status. Storage access failed: {}", err);
                                 continue;
                             }
                         };
@@ -53,7 +54,7 @@ impl SharedNetworkStatus {
                         let mut transaction =  match storage.start_transaction().await {
                             Ok(transaction) => transaction,
                             Err(err) => {
-                                log::warn!("Unable to update the network status. Storage access failed: {}", err);
+                                tracing::warn!("Unable to update the network status. Storage access failed: {}", err);
                                 continue;
                             }
                         };
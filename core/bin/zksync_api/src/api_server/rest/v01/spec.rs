@@ -0,0 +1,339 @@
+//! OpenAPI specification of the REST API v0.1.
+//!
+//! The specification is generated from the request and response types declared in the
+//! `types` module, thus it's always consistent with the actual handlers. Every endpoint
+//! registered in `ApiV01::into_scope` must be described in the `endpoints` function, which
+//! is enforced by the `spec_covers_registered_routes` test.
+
+// External uses
+use once_cell::sync::Lazy;
+use schemars::{
+    gen::{SchemaGenerator, SchemaSettings},
+    schema::Schema,
+    JsonSchema,
+};
+use serde_json::{json, Map, Value};
+
+// Workspace uses
+use zksync_types::BlockNumber;
+
+// Local uses
+use super::{network_status::NetworkStatus, types::*};
+
+/// Specification is generated once, since it depends only on the types.
+static SPEC: Lazy<Value> = Lazy::new(generate);
+
+/// Description of a single `GET` endpoint.
+struct Endpoint {
+    path: &'static str,
+    summary: &'static str,
+    parameters: Vec<Value>,
+    response: Schema,
+    /// Whether the endpoint responds with `404 Not Found` for the missing entities.
+    not_found: bool,
+}
+
+impl Endpoint {
+    fn new(path: &'static str, summary: &'static str, response: Schema) -> Self {
+        Self {
+            path,
+            summary,
+            parameters: Vec::new(),
+            response,
+            not_found: false,
+        }
+    }
+
+    fn path_param<T: JsonSchema>(mut self, gen: &mut SchemaGenerator, name: &str) -> Self {
+        self.parameters.push(json!({
+            "name": name,
+            "in": "path",
+            "required": true,
+            "schema": gen.subschema_for::<T>(),
+        }));
+        self
+    }
+
+    /// Describes every field of the query structure as a separate query parameter.
+    fn query<T: JsonSchema>(mut self, gen: &mut SchemaGenerator) -> Self {
+        let schema = gen.root_schema_for::<T>().schema;
+        let object = schema
+            .object
+            .expect("Query parameters must be described by a structure");
+
+        for (name, schema) in object.properties {
+            let mut parameter = json!({
+                "name": name,
+                "in": "query",
+                "required": object.required.contains(&name),
+                "schema": schema,
+            });
+            // Move the field documentation to the parameter level.
+            if let Some(description) = parameter["schema"]
+                .as_object_mut()
+                .and_then(|schema| schema.remove("description"))
+            {
+                parameter["description"] = description;
+            }
+            self.parameters.push(parameter);
+        }
+        self
+    }
+
+    fn not_found(mut self) -> Self {
+        self.not_found = true;
+        self
+    }
+
+    fn into_operation(self) -> Value {
+        let mut responses = json!({
+            "200": {
+                "description": "Successful response",
+                "content": {
+                    "application/json": { "schema": self.response },
+                },
+            },
+            "400": { "description": "Malformed request" },
+            "500": { "description": "Internal server error" },
+        });
+        if self.not_found {
+            responses["404"] = json!({ "description": "Requested entity was not found" });
+        }
+
+        json!({
+            "get": {
+                "summary": self.summary,
+                "parameters": self.parameters,
+                "responses": responses,
+            }
+        })
+    }
+}
+
+fn endpoints(gen: &mut SchemaGenerator) -> Vec<Endpoint> {
+    vec![
+        Endpoint::new(
+            "/testnet_config",
+            "Network configuration",
+            gen.subschema_for::<TestnetConfigResponse>(),
+        ),
+        Endpoint::new(
+            "/status",
+            "Network status",
+            gen.subschema_for::<NetworkStatus>(),
+        ),
+        Endpoint::new(
+            "/tokens",
+            "List of the supported tokens",
+            gen.subschema_for::<Vec<TokenResponse>>(),
+        ),
+        Endpoint::new(
+            "/account/{address}/history/{offset}/{limit}",
+            "Account transactions history, including the unconfirmed deposits",
+            gen.subschema_for::<Vec<TxHistoryItemResponse>>(),
+        )
+        .path_param::<String>(gen, "address")
+        .path_param::<u64>(gen, "offset")
+        .path_param::<u64>(gen, "limit"),
+        Endpoint::new(
+            "/account/{address}/history/older_than",
+            "Account transactions older than the given one",
+            gen.subschema_for::<Vec<TxHistoryItemResponse>>(),
+        )
+        .path_param::<String>(gen, "address")
+        .query::<TxHistoryQuery>(gen),
+        Endpoint::new(
+            "/account/{address}/history/newer_than",
+            "Account transactions newer than the given one, including the unconfirmed deposits",
+            gen.subschema_for::<Vec<TxHistoryItemResponse>>(),
+        )
+        .path_param::<String>(gen, "address")
+        .query::<TxHistoryQuery>(gen),
+        Endpoint::new(
+            "/transactions/{tx_hash}",
+            "Receipt of the executed transaction",
+            gen.subschema_for::<Option<ExecutedTxResponse>>(),
+        )
+        .path_param::<String>(gen, "tx_hash"),
+        Endpoint::new(
+            "/transactions_all/{tx_hash}",
+            "Transaction or priority operation by its hash",
+            gen.subschema_for::<Option<TxResponse>>(),
+        )
+        .path_param::<String>(gen, "tx_hash"),
+        Endpoint::new(
+            "/priority_operations/{pq_id}/",
+            "Receipt of the priority operation",
+            gen.subschema_for::<PriorityOpResponse>(),
+        )
+        .path_param::<u32>(gen, "pq_id"),
+        Endpoint::new(
+            "/blocks/{block_id}/transactions/{tx_id}",
+            "Operation executed in the block",
+            gen.subschema_for::<BlockOperationResponse>(),
+        )
+        .path_param::<BlockNumber>(gen, "block_id")
+        .path_param::<u32>(gen, "tx_id")
+        .not_found(),
+        Endpoint::new(
+            "/blocks/{block_id}/transactions",
+            "Transactions executed in the block",
+            gen.subschema_for::<Vec<BlockTxResponse>>(),
+        )
+        .path_param::<BlockNumber>(gen, "block_id"),
+        Endpoint::new(
+            "/blocks/{block_id}",
+            "Block details",
+            gen.subschema_for::<BlockResponse>(),
+        )
+        .path_param::<BlockNumber>(gen, "block_id")
+        .not_found(),
+        Endpoint::new(
+            "/blocks",
+            "Blocks in the descending order",
+            gen.subschema_for::<Vec<BlockResponse>>(),
+        )
+        .query::<HandleBlocksQuery>(gen),
+        Endpoint::new(
+            "/search",
+            "Block search by number or hash",
+            gen.subschema_for::<BlockResponse>(),
+        )
+        .query::<BlockExplorerSearchQuery>(gen)
+        .not_found(),
+        Endpoint::new(
+            "/withdrawal_processing_time",
+            "Expected processing time of the withdrawals, in seconds",
+            gen.subschema_for::<WithdrawalProcessingTimeResponse>(),
+        ),
+        Endpoint::new(
+            "/spec",
+            "OpenAPI specification of this API",
+            gen.subschema_for::<Value>(),
+        ),
+    ]
+}
+
+fn generate() -> Value {
+    let mut gen = SchemaSettings::openapi3().into_generator();
+
+    let paths: Map<String, Value> = endpoints(&mut gen)
+        .into_iter()
+        .map(|endpoint| (endpoint.path.to_owned(), endpoint.into_operation()))
+        .collect();
+    let schemas = gen.take_definitions();
+
+    json!({
+        "openapi": "3.0.0",
+        "info": {
+            "title": "zkSync REST API",
+            "version": "0.1",
+        },
+        "servers": [{ "url": "/api/v0.1" }],
+        "paths": paths,
+        "components": { "schemas": schemas },
+    })
+}
+
+/// Returns the OpenAPI specification of the REST API v0.1.
+pub fn api_spec() -> &'static Value {
+    &SPEC
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect_refs(value: &Value, refs: &mut Vec<String>) {
+        match value {
+            Value::Object(map) => {
+                if let Some(Value::String(reference)) = map.get("$ref") {
+                    refs.push(reference.clone());
+                }
+                map.values().for_each(|value| collect_refs(value, refs));
+            }
+            Value::Array(values) => values.iter().for_each(|value| collect_refs(value, refs)),
+            _ => {}
+        }
+    }
+
+    /// Checks that every schema reference in the specification can be resolved.
+    #[test]
+    fn spec_references_are_resolved() {
+        let spec = api_spec();
+
+        let mut refs = Vec::new();
+        collect_refs(spec, &mut refs);
+        assert!(!refs.is_empty());
+
+        for reference in refs {
+            let name = reference
+                .strip_prefix("#/components/schemas/")
+                .unwrap_or_else(|| panic!("Unexpected reference: {}", reference));
+            assert!(
+                spec["components"]["schemas"].get(name).is_some(),
+                "Schema {} is not declared",
+                name
+            );
+        }
+    }
+
+    /// Extracts the paths of the routes registered in `ApiV01::into_scope`.
+    fn registered_routes() -> Vec<String> {
+        let source = include_str!("api_decl.rs");
+        let scope = &source[source
+            .find("fn into_scope")
+            .expect("`into_scope` is not declared")..];
+        let scope = &scope[..scope.find("\n    }\n").unwrap()];
+
+        scope
+            .split(".route(")
+            .skip(1)
+            .map(|route| {
+                let path = &route[route.find('"').unwrap() + 1..];
+                path[..path.find('"').unwrap()].to_owned()
+            })
+            .collect()
+    }
+
+    /// Checks that the specification describes exactly the registered routes.
+    #[test]
+    fn spec_covers_registered_routes() {
+        let routes = registered_routes();
+        assert!(!routes.is_empty());
+
+        let paths = api_spec()["paths"].as_object().unwrap();
+        for route in &routes {
+            assert!(
+                paths.contains_key(route),
+                "Route {} is not described",
+                route
+            );
+        }
+        for path in paths.keys() {
+            assert!(routes.contains(path), "Path {} is not registered", path);
+        }
+    }
+
+    /// Checks that path parameters are declared for every placeholder in the path.
+    #[test]
+    fn spec_path_parameters() {
+        let paths = api_spec()["paths"].as_object().unwrap();
+
+        for (path, operation) in paths {
+            let declared: Vec<_> = operation["get"]["parameters"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .filter(|parameter| parameter["in"] == "path")
+                .map(|parameter| format!("{{{}}}", parameter["name"].as_str().unwrap()))
+                .collect();
+            let placeholders = path.matches('{').count();
+
+            assert_eq!(declared.len(), placeholders, "{}", path);
+            for placeholder in declared {
+                assert!(path.contains(&placeholder), "{}", path);
+            }
+        }
+    }
+}
@@ -1,15 +1,31 @@
 //! Requests and responses used by the REST API.
+//!
+//! Responses are declared separately from the storage records, so that the schema
+//! of every endpoint is described by a single type. The OpenAPI specification is
+//! generated from these types, see the `spec` module.
 
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use zksync_types::{Account, AccountId};
+use serde_json::Value;
+use zksync_storage::{
+    chain::{
+        block::records::{BlockDetails, BlockTransactionItem},
+        operations_ext::records::{
+            PriorityOpReceiptResponse, TransactionsHistoryItem, TxByHashResponse, TxReceiptResponse,
+        },
+    },
+    prover::records::ProverRun,
+};
+use zksync_types::{Account, AccountId, Address, ExecutedOperations, Token, TokenId};
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct TestnetConfigResponse {
     pub contract_address: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct WithdrawalProcessingTimeResponse {
     pub normal: u64,
     pub fast: u64,
@@ -23,19 +39,239 @@ pub struct AccountStateResponse {
     pub verified: Account,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct TxHistoryQuery {
     pub tx_id: Option<String>,
     pub limit: Option<u64>,
+    /// Token ID, address or symbol to filter transactions by.
+    pub token: Option<String>,
+    /// Type of transactions to include, e.g. `Transfer` or `Deposit`.
+    pub tx_type: Option<String>,
+    /// Only include transactions created at or after this time.
+    pub created_after: Option<DateTime<Utc>>,
+    /// Only include transactions created before this time.
+    pub created_before: Option<DateTime<Utc>>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, JsonSchema)]
 pub struct HandleBlocksQuery {
     pub max_block: Option<u32>,
     pub limit: Option<u32>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, JsonSchema)]
 pub struct BlockExplorerSearchQuery {
+    /// Block number, root hash or hash of the commit/verify Ethereum transaction.
     pub query: String,
 }
+
+/// Token supported by the network.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TokenResponse {
+    pub id: TokenId,
+    /// Address of the ERC20 contract, or zero address for ETH.
+    #[schemars(with = "String")]
+    pub address: Address,
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+impl From<Token> for TokenResponse {
+    fn from(token: Token) -> Self {
+        Self {
+            id: token.id,
+            address: token.address,
+            symbol: token.symbol,
+            decimals: token.decimals,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TxHistoryItemResponse {
+    pub tx_id: String,
+    pub hash: Option<String>,
+    pub eth_block: Option<i64>,
+    pub pq_id: Option<i64>,
+    /// Transaction or priority operation in the form it was submitted.
+    pub tx: Value,
+    pub success: Option<bool>,
+    pub fail_reason: Option<String>,
+    pub commited: bool,
+    pub verified: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<TransactionsHistoryItem> for TxHistoryItemResponse {
+    fn from(item: TransactionsHistoryItem) -> Self {
+        Self {
+            tx_id: item.tx_id,
+            hash: item.hash,
+            eth_block: item.eth_block,
+            pq_id: item.pq_id,
+            tx: item.tx,
+            success: item.success,
+            fail_reason: item.fail_reason,
+            commited: item.commited,
+            verified: item.verified,
+            created_at: item.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ProverRunResponse {
+    pub id: i32,
+    pub block_number: i64,
+    pub worker: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<ProverRun> for ProverRunResponse {
+    fn from(run: ProverRun) -> Self {
+        Self {
+            id: run.id,
+            block_number: run.block_number,
+            worker: run.worker,
+            created_at: run.created_at,
+            updated_at: run.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ExecutedTxResponse {
+    pub tx_hash: String,
+    pub block_number: i64,
+    pub success: bool,
+    pub verified: bool,
+    pub fail_reason: Option<String>,
+    pub prover_run: Option<ProverRunResponse>,
+}
+
+impl From<TxReceiptResponse> for ExecutedTxResponse {
+    fn from(receipt: TxReceiptResponse) -> Self {
+        Self {
+            tx_hash: receipt.tx_hash,
+            block_number: receipt.block_number,
+            success: receipt.success,
+            verified: receipt.verified,
+            fail_reason: receipt.fail_reason,
+            prover_run: receipt.prover_run.map(From::from),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TxResponse {
+    pub tx_type: String,
+    pub from: String,
+    pub to: String,
+    pub token: i32,
+    pub amount: String,
+    pub fee: Option<String>,
+    pub block_number: i64,
+    pub nonce: i64,
+    pub created_at: String,
+    pub fail_reason: Option<String>,
+    pub tx: Value,
+}
+
+impl From<TxByHashResponse> for TxResponse {
+    fn from(tx: TxByHashResponse) -> Self {
+        Self {
+            tx_type: tx.tx_type,
+            from: tx.from,
+            to: tx.to,
+            token: tx.token,
+            amount: tx.amount,
+            fee: tx.fee,
+            block_number: tx.block_number,
+            nonce: tx.nonce,
+            created_at: tx.created_at,
+            fail_reason: tx.fail_reason,
+            tx: tx.tx,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PriorityOpResponse {
+    pub committed: bool,
+    pub verified: bool,
+    pub prover_run: Option<ProverRunResponse>,
+}
+
+impl From<PriorityOpReceiptResponse> for PriorityOpResponse {
+    fn from(receipt: PriorityOpReceiptResponse) -> Self {
+        Self {
+            committed: receipt.committed,
+            verified: receipt.verified,
+            prover_run: receipt.prover_run.map(From::from),
+        }
+    }
+}
+
+/// Operation executed in the block, either a transaction or a priority operation.
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(transparent)]
+pub struct BlockOperationResponse(#[schemars(with = "Value")] pub ExecutedOperations);
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct BlockResponse {
+    pub block_number: i64,
+    /// Root hash with the `sync-bl:` prefix.
+    pub new_state_root: String,
+    pub block_size: i64,
+    /// Hash of the Ethereum transaction with the `0x` prefix.
+    pub commit_tx_hash: Option<String>,
+    /// Hash of the Ethereum transaction with the `0x` prefix.
+    pub verify_tx_hash: Option<String>,
+    pub committed_at: DateTime<Utc>,
+    pub verified_at: Option<DateTime<Utc>>,
+}
+
+impl From<BlockDetails> for BlockResponse {
+    fn from(block: BlockDetails) -> Self {
+        let eth_tx_hash = |hash: Vec<u8>| format!("0x{}", hex::encode(hash));
+
+        Self {
+            block_number: block.block_number,
+            new_state_root: format!("sync-bl:{}", hex::encode(block.new_state_root)),
+            block_size: block.block_size,
+            commit_tx_hash: block.commit_tx_hash.map(eth_tx_hash),
+            verify_tx_hash: block.verify_tx_hash.map(eth_tx_hash),
+            committed_at: block.committed_at,
+            verified_at: block.verified_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct BlockTxResponse {
+    pub tx_hash: String,
+    pub block_number: i64,
+    pub op: Value,
+    pub success: Option<bool>,
+    pub fail_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<BlockTransactionItem> for BlockTxResponse {
+    fn from(item: BlockTransactionItem) -> Self {
+        Self {
+            tx_hash: item.tx_hash,
+            block_number: item.block_number,
+            op: item.op,
+            success: item.success,
+            fail_reason: item.fail_reason,
+            created_at: item.created_at,
+        }
+    }
+}
+
+/// Converts the storage records into the responses of the API.
+pub fn into_responses<T, R: From<T>>(items: Vec<T>) -> Vec<R> {
+    items.into_iter().map(R::from).collect()
+}
@@ -5,13 +5,14 @@
 // External uses
 
 // Workspace uses
+use zksync_types::Address;
 
 // Local uses
 use crate::api_server::v1::client::{Client, ClientError};
 
 use super::types::{
-    AccountInfo, AccountQuery, AccountReceipts, AccountReceiptsQuery, AccountTxReceipt,
-    PendingAccountTxReceipt,
+    AccountIdStatus, AccountInfo, AccountQuery, AccountReceipts, AccountReceiptsQuery,
+    AccountTxReceipt, PendingAccountTxReceipt,
 };
 
 /// Accounts API part.
@@ -26,6 +27,21 @@ impl Client {
         self.get(&format!("accounts/{}", account)).send().await
     }
 
+    /// Gets information of several accounts at once, in the order of the given queries.
+    /// The absent accounts are represented by `None`.
+    pub async fn accounts_info(
+        &self,
+        accounts: &[AccountQuery],
+    ) -> Result<Vec<Option<AccountInfo>>, ClientError> {
+        self.post("accounts/bulk/info").body(accounts).send().await
+    }
+
+    /// Resolves the address to the zkSync account ID. Accounts are created by the first
+    /// incoming transfer or deposit, so the funds can be sent to the address without the ID.
+    pub async fn account_id(&self, address: Address) -> Result<AccountIdStatus, ClientError> {
+        self.get(&format!("accounts/{:?}/id", address)).send().await
+    }
+
     pub async fn account_receipts(
         &self,
         account: impl Into<AccountQuery>,
@@ -1,7 +1,10 @@
 //! Accounts part of API implementation.
 
 // Public uses
-pub use self::types::{AccountInfo, AccountState, DepositingBalances, DepositingFunds};
+pub use self::types::{
+    AccountIdStatus, AccountInfo, AccountState, BalanceBreakdown, DepositingBalances,
+    DepositingFunds,
+};
 
 // Built-in uses
 
@@ -10,14 +13,18 @@ use actix_web::{
     web::{self, Json},
     Scope,
 };
+use futures::future::try_join_all;
 
 // Workspace uses
 use zksync_config::ConfigurationOptions;
-use zksync_storage::{QueryResult, StorageProcessor};
+use zksync_storage::{chain::account::StoredAccountState, QueryResult, StorageProcessor};
 use zksync_types::{AccountId, Address, BlockNumber, TokenId};
 
 // Local uses
-use crate::{core_api_client::CoreApiClient, utils::token_db_cache::TokenDBCache};
+use crate::{
+    core_api_client::CoreApiClient,
+    utils::{account_id_cache::AccountIdCache, token_db_cache::TokenDBCache},
+};
 
 use self::types::{
     AccountQuery, AccountReceiptsQuery, AccountTxReceipt, PendingAccountTxReceipt, SearchDirection,
@@ -30,6 +37,13 @@ mod client;
 mod tests;
 mod types;
 
+/// Maximum amount of accounts in the bulk request.
+pub const MAX_BULK_ACCOUNTS: usize = 1000;
+/// Maximum size of the bulk request body, enough for `MAX_BULK_ACCOUNTS` addresses.
+const MAX_BULK_REQUEST_SIZE: usize = 64 * 1024;
+/// Amount of accounts loaded from the database at once while serving the bulk request.
+const BULK_ACCOUNTS_CHUNK_SIZE: usize = 100;
+
 fn unable_to_find_token(token_id: TokenId) -> anyhow::Error {
     anyhow::anyhow!("Unable to find token with ID {}", token_id)
 }
@@ -46,6 +60,7 @@ fn parse_account_query(query: String) -> Result<AccountQuery, ApiError> {
 #[derive(Clone)]
 struct ApiAccountsData {
     tokens: TokenDBCache,
+    account_ids: AccountIdCache,
     core_api_client: CoreApiClient,
     confirmations_for_eth_event: BlockNumber,
 }
@@ -53,11 +68,13 @@ struct ApiAccountsData {
 impl ApiAccountsData {
     fn new(
         tokens: TokenDBCache,
+        account_ids: AccountIdCache,
         core_api_client: CoreApiClient,
         confirmations_for_eth_event: BlockNumber,
     ) -> Self {
         Self {
             tokens,
+            account_ids,
             core_api_client,
             confirmations_for_eth_event,
         }
@@ -78,19 +95,10 @@ impl ApiAccountsData {
             })
     }
 
-    async fn account_id(
-        storage: &mut StorageProcessor<'_>,
-        query: AccountQuery,
-    ) -> QueryResult<Option<AccountId>> {
+    async fn account_id(&self, query: AccountQuery) -> QueryResult<Option<AccountId>> {
         match query {
             AccountQuery::Id(id) => Ok(Some(id)),
-            AccountQuery::Address(address) => {
-                storage
-                    .chain()
-                    .account_schema()
-                    .account_id_by_address(address)
-                    .await
-            }
+            AccountQuery::Address(address) => self.account_ids.get_account_id(address).await,
         }
     }
 
@@ -108,14 +116,32 @@ impl ApiAccountsData {
         }
     }
 
-    async fn account_info(&self, query: AccountQuery) -> QueryResult<Option<AccountInfo>> {
+    async fn account_id_status(&self, address: Address) -> QueryResult<AccountIdStatus> {
         let mut storage = self.access_storage().await?;
-        let account_id = if let Some(id) = Self::account_id(&mut storage, query).await? {
+        let account_state = storage
+            .chain()
+            .account_schema()
+            .account_state_by_address(address)
+            .await?;
+
+        let status = match account_state.committed {
+            Some((id, _)) => AccountIdStatus::Created {
+                id,
+                verified: account_state.verified.is_some(),
+            },
+            None => AccountIdStatus::NotCreated,
+        };
+        Ok(status)
+    }
+
+    async fn account_info(&self, query: AccountQuery) -> QueryResult<Option<AccountInfo>> {
+        let account_id = if let Some(id) = self.account_id(query).await? {
             id
         } else {
             return Ok(None);
         };
 
+        let mut storage = self.access_storage().await?;
         let account_state = storage
             .chain()
             .account_schema()
@@ -126,15 +152,73 @@ impl ApiAccountsData {
         // TODO Rewrite `TokensDBCache` logic to make such errors impossible. ZKS-169
         drop(storage);
 
-        let (account_id, account) = if let Some(state) = account_state.committed {
+        self.account_info_from_state(&account_state).await
+    }
+
+    /// Loads the information of the accounts in the order of the queries, the absent accounts
+    /// are represented by `None`. Accounts are loaded by chunks, each of them takes a fixed amount
+    /// of the database queries.
+    async fn accounts_info(
+        &self,
+        queries: &[AccountQuery],
+    ) -> QueryResult<Vec<Option<AccountInfo>>> {
+        let mut infos = Vec::with_capacity(queries.len());
+
+        for chunk in queries.chunks(BULK_ACCOUNTS_CHUNK_SIZE) {
+            let addresses: Vec<_> = chunk
+                .iter()
+                .filter_map(|query| match query {
+                    AccountQuery::Address(address) => Some(*address),
+                    AccountQuery::Id(_) => None,
+                })
+                .collect();
+            let resolved = self.account_ids.get_account_ids(&addresses).await?;
+
+            let account_ids: Vec<_> = chunk
+                .iter()
+                .map(|query| match query {
+                    AccountQuery::Id(id) => Some(*id),
+                    AccountQuery::Address(address) => resolved.get(address).copied(),
+                })
+                .collect();
+
+            let account_states = {
+                let mut storage = self.access_storage().await?;
+                let existing: Vec<_> = account_ids.iter().flatten().copied().collect();
+                storage
+                    .chain()
+                    .account_schema()
+                    .account_states_by_ids(&existing)
+                    .await?
+            };
+
+            let account_states = &account_states;
+            let chunk_infos = try_join_all(account_ids.iter().map(|account_id| async move {
+                match account_id.and_then(|id| account_states.get(&id)) {
+                    Some(account_state) => self.account_info_from_state(account_state).await,
+                    None => Ok(None),
+                }
+            }))
+            .await?;
+            infos.extend(chunk_infos);
+        }
+
+        Ok(infos)
+    }
+
+    async fn account_info_from_state(
+        &self,
+        account_state: &StoredAccountState,
+    ) -> QueryResult<Option<AccountInfo>> {
+        let (account_id, account) = if let Some(state) = &account_state.committed {
             state
         } else {
             // This account has not been committed.
             return Ok(None);
         };
 
-        let committed = AccountState::from_storage(&account, &self.tokens).await?;
-        let verified = match account_state.verified {
+        let committed = AccountState::from_storage(account, &self.tokens).await?;
+        let verified = match &account_state.verified {
             Some(state) => AccountState::from_storage(&state.1, &self.tokens).await?,
             None => AccountState::default(),
         };
@@ -153,11 +237,13 @@ impl ApiAccountsData {
             .await?
         };
 
+        let balances = BalanceBreakdown::from_balances(&committed.balances, &verified.balances);
         let info = AccountInfo {
             address: account.address,
-            id: account_id,
+            id: *account_id,
             committed,
             verified,
+            balances,
             depositing,
         };
 
@@ -220,6 +306,44 @@ async fn account_info(
         .map_err(ApiError::internal)
 }
 
+async fn accounts_info(
+    data: web::Data<ApiAccountsData>,
+    Json(queries): Json<Vec<AccountQuery>>,
+) -> JsonResult<Vec<Option<AccountInfo>>> {
+    if queries.len() > MAX_BULK_ACCOUNTS {
+        return Err(
+            ApiError::bad_request("Too many accounts requested.").detail(format!(
+                "At most {} accounts can be requested",
+                MAX_BULK_ACCOUNTS
+            )),
+        );
+    }
+
+    data.accounts_info(&queries)
+        .await
+        .map(Json)
+        .map_err(ApiError::internal)
+}
+
+async fn account_id(
+    data: web::Data<ApiAccountsData>,
+    web::Path(query): web::Path<String>,
+) -> JsonResult<AccountIdStatus> {
+    let address = match parse_account_query(query)? {
+        AccountQuery::Address(address) => address,
+        AccountQuery::Id(_) => {
+            return Err(ApiError::bad_request(
+                "Must be specified an account address.",
+            ))
+        }
+    };
+
+    data.account_id_status(address)
+        .await
+        .map(Json)
+        .map_err(ApiError::internal)
+}
+
 async fn account_receipts(
     data: web::Data<ApiAccountsData>,
     web::Path(account_query): web::Path<String>,
@@ -253,17 +377,25 @@ async fn account_pending_receipts(
 pub fn api_scope(
     env_options: &ConfigurationOptions,
     tokens: TokenDBCache,
+    account_ids: AccountIdCache,
     core_api_client: CoreApiClient,
 ) -> Scope {
     let data = ApiAccountsData::new(
         tokens,
+        account_ids,
         core_api_client,
         env_options.confirmations_for_eth_event as BlockNumber,
     );
 
     web::scope("accounts")
         .data(data)
+        .service(
+            web::resource("bulk/info")
+                .app_data(web::JsonConfig::default().limit(MAX_BULK_REQUEST_SIZE))
+                .route(web::post().to(accounts_info)),
+        )
         .route("{id}", web::get().to(account_info))
+        .route("{id}/id", web::get().to(account_id))
         .route("{id}/receipts", web::get().to(account_receipts))
         .route(
             "{id}/receipts/pending",
@@ -1,5 +1,5 @@
 // Built-in uses
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 
 // External uses
 use actix_web::{
@@ -13,7 +13,11 @@ use tokio::sync::Mutex;
 use zksync_storage::{
     chain::operations_ext::records::AccountTxReceiptResponse, ConnectionPool, StorageProcessor,
 };
-use zksync_types::{tx::TxHash, Address, BlockNumber, H256};
+use zksync_types::{
+    tx::TxHash, Address, BlockNumber, Deposit, DepositOp, ExecutedOperations, ExecutedPriorityOp,
+    PriorityOp, ZkSyncOp, ZkSyncPriorityOp, H256,
+};
+use zksync_utils::BigUintSerdeWrapper;
 
 // Local uses
 use crate::{
@@ -22,12 +26,12 @@ use crate::{
         test_utils::TestServerConfig,
     },
     core_api_client::CoreApiClient,
-    utils::token_db_cache::TokenDBCache,
+    utils::{account_id_cache::AccountIdCache, token_db_cache::TokenDBCache},
 };
 
 use super::{
     api_scope,
-    types::{AccountReceipts, AccountTxReceipt},
+    types::{AccountIdStatus, AccountQuery, AccountReceipts, AccountTxReceipt, BalanceBreakdown},
 };
 
 type DepositsHandle = Arc<Mutex<serde_json::Value>>;
@@ -76,6 +80,7 @@ impl TestServer {
             api_scope(
                 &cfg.env_options,
                 TokenDBCache::new(cfg.pool.clone()),
+                AccountIdCache::new(cfg.pool.clone(), 100),
                 core_client.clone(),
             )
         });
@@ -125,6 +130,32 @@ async fn unconfirmed_deposits_loopback() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn balance_breakdown() {
+    let balances = |entries: &[(&str, u64)]| -> BTreeMap<String, BigUintSerdeWrapper> {
+        entries
+            .iter()
+            .map(|(token, amount)| (token.to_string(), BigUintSerdeWrapper((*amount).into())))
+            .collect()
+    };
+
+    let committed = balances(&[("ETH", 100), ("DAI", 5), ("GLM", 7)]);
+    let verified = balances(&[("ETH", 40), ("DAI", 10), ("MLTT", 3)]);
+    let breakdown = BalanceBreakdown::from_balances(&committed, &verified);
+
+    assert_eq!(breakdown.len(), 4);
+    assert_eq!(
+        breakdown["ETH"],
+        BalanceBreakdown::new(100u64.into(), 40u64.into())
+    );
+    assert_eq!(breakdown["ETH"].pending_increase.0, 60u64.into());
+    assert_eq!(breakdown["DAI"].pending_decrease.0, 5u64.into());
+    assert_eq!(breakdown["GLM"].verified.0, 0u64.into());
+    assert_eq!(breakdown["GLM"].pending_increase.0, 7u64.into());
+    assert_eq!(breakdown["MLTT"].committed.0, 0u64.into());
+    assert_eq!(breakdown["MLTT"].pending_decrease.0, 3u64.into());
+}
+
 #[actix_rt::test]
 async fn accounts_scope() -> anyhow::Result<()> {
     let (client, server) = TestServer::new().await?;
@@ -134,7 +165,37 @@ async fn accounts_scope() -> anyhow::Result<()> {
 
     let account_info = client.account_info(address).await?.unwrap();
     let id = account_info.id;
-    assert_eq!(client.account_info(id).await?, Some(account_info));
+    assert_eq!(
+        account_info.balances,
+        BalanceBreakdown::from_balances(
+            &account_info.committed.balances,
+            &account_info.verified.balances
+        )
+    );
+    assert_eq!(client.account_info(id).await?, Some(account_info.clone()));
+
+    // Get information of several accounts at once.
+    let accounts_info = client
+        .accounts_info(&[
+            AccountQuery::Id(id),
+            Address::repeat_byte(0x42).into(),
+            address.into(),
+        ])
+        .await?;
+    assert_eq!(
+        accounts_info,
+        vec![Some(account_info.clone()), None, Some(account_info)]
+    );
+
+    // Resolve the account ID by the address.
+    assert_eq!(
+        client.account_id(address).await?,
+        AccountIdStatus::Created { id, verified: true }
+    );
+    assert_eq!(
+        client.account_id(Address::repeat_byte(0x42)).await?,
+        AccountIdStatus::NotCreated
+    );
 
     // Provide unconfirmed deposits
     let deposits = json!([
@@ -196,6 +257,58 @@ async fn accounts_scope() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[actix_rt::test]
+async fn account_id_cache() -> anyhow::Result<()> {
+    let cfg = TestServerConfig::default();
+    cfg.fill_database().await?;
+    let account_ids = AccountIdCache::new(cfg.pool.clone(), 100);
+
+    let mut storage = cfg.pool.access_storage().await?;
+    let address = TestServer::account_address(&mut storage, 1).await?;
+    let id = storage
+        .chain()
+        .account_schema()
+        .account_id_by_address(address)
+        .await?
+        .unwrap();
+    drop(storage);
+
+    // The absence of the account is cached as well.
+    let new_address = Address::repeat_byte(0x42);
+    let resolved = account_ids.get_account_ids(&[address, new_address]).await?;
+    assert_eq!(resolved.len(), 1);
+    assert_eq!(resolved[&address], id);
+
+    // Once the account is created, the cached absence is replaced with its ID.
+    let deposit = Deposit {
+        from: Address::default(),
+        token: 0,
+        amount: 100u32.into(),
+        to: new_address,
+    };
+    let executed_op = ExecutedOperations::PriorityOp(Box::new(ExecutedPriorityOp {
+        priority_op: PriorityOp {
+            serial_id: 0,
+            data: ZkSyncPriorityOp::Deposit(deposit.clone()),
+            deadline_block: 0,
+            eth_hash: Vec::new(),
+            eth_block: 0,
+        },
+        op: ZkSyncOp::Deposit(Box::new(DepositOp {
+            priority_op: deposit,
+            account_id: 1000,
+        })),
+        block_index: 0,
+        created_at: chrono::Utc::now(),
+    }));
+    account_ids.handle_executed_ops(&[executed_op]).await;
+
+    assert_eq!(account_ids.get_account_id(new_address).await?, Some(1000));
+    assert_eq!(account_ids.get_account_id(address).await?, Some(id));
+
+    Ok(())
+}
+
 #[test]
 fn account_tx_response_to_receipt() {
     fn empty_hash() -> Vec<u8> {
@@ -4,6 +4,7 @@
 use std::{collections::BTreeMap, fmt::Display, str::FromStr};
 
 // External uses
+use num::{BigUint, Zero};
 use serde::{Deserialize, Serialize};
 
 // Workspace uses
@@ -31,7 +32,7 @@ use super::{
 };
 
 /// Account search query.
-#[derive(Debug, Serialize, Copy, Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
+#[derive(Debug, Deserialize, Serialize, Copy, Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
 #[serde(untagged, rename_all = "camelCase")]
 pub enum AccountQuery {
     /// Search account by ID.
@@ -40,6 +41,17 @@ pub enum AccountQuery {
     Address(Address),
 }
 
+/// Resolution of the address to the zkSync account ID.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum AccountIdStatus {
+    /// The account is created in the committed block, the ID is final once the block is verified.
+    Created { id: AccountId, verified: bool },
+    /// The account doesn't exist yet. It's created by the first incoming transfer or deposit,
+    /// so the address is already able to receive funds.
+    NotCreated,
+}
+
 /// Account state at the time of the zkSync block commit or verification.
 /// This means that each account has various states.
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default)]
@@ -71,6 +83,20 @@ pub struct DepositingBalances {
     pub balances: BTreeMap<String, DepositingFunds>,
 }
 
+/// Token balance split into the finalized part and the part pending finality.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BalanceBreakdown {
+    /// Balance in accordance with the actual verified block, i.e. the finalized one.
+    pub verified: BigUintSerdeWrapper,
+    /// Balance in accordance with the actual committed block.
+    pub committed: BigUintSerdeWrapper,
+    /// Amount by which the committed balance exceeds the verified one.
+    pub pending_increase: BigUintSerdeWrapper,
+    /// Amount by which the committed balance is less than the verified one.
+    pub pending_decrease: BigUintSerdeWrapper,
+}
+
 /// Account summary info in the zkSync network.
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -83,6 +109,8 @@ pub struct AccountInfo {
     pub committed: AccountState,
     /// Account state in accordance with the actual verified block.
     pub verified: AccountState,
+    /// Balances of the tokens present in either of the states, split by finality.
+    pub balances: BTreeMap<String, BalanceBreakdown>,
     /// Unconfirmed account deposits.
     pub depositing: DepositingBalances,
 }
@@ -216,6 +244,42 @@ impl FromStr for SearchDirection {
     }
 }
 
+impl BalanceBreakdown {
+    pub fn new(committed: BigUint, verified: BigUint) -> Self {
+        let (pending_increase, pending_decrease) = if committed >= verified {
+            (&committed - &verified, BigUint::zero())
+        } else {
+            (BigUint::zero(), &verified - &committed)
+        };
+
+        Self {
+            verified: verified.into(),
+            committed: committed.into(),
+            pending_increase: pending_increase.into(),
+            pending_decrease: pending_decrease.into(),
+        }
+    }
+
+    /// Creates the breakdown for every token present in either of the committed and verified balances.
+    pub fn from_balances<'a>(
+        committed: impl IntoIterator<Item = (&'a String, &'a BigUintSerdeWrapper)>,
+        verified: impl IntoIterator<Item = (&'a String, &'a BigUintSerdeWrapper)>,
+    ) -> BTreeMap<String, Self> {
+        let mut balances: BTreeMap<String, (BigUint, BigUint)> = BTreeMap::new();
+        for (token, amount) in committed {
+            balances.entry(token.clone()).or_default().0 = amount.0.clone();
+        }
+        for (token, amount) in verified {
+            balances.entry(token.clone()).or_default().1 = amount.0.clone();
+        }
+
+        balances
+            .into_iter()
+            .map(|(token, (committed, verified))| (token, Self::new(committed, verified)))
+            .collect()
+    }
+}
+
 impl AccountState {
     pub(crate) async fn from_storage(
         account: &Account,
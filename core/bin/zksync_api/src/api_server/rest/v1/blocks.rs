@@ -14,8 +14,8 @@ use serde_json::Value;
 // Workspace uses
 use zksync_config::ApiServerOptions;
 use zksync_crypto::{convert::FeConvert, serialization::FrSerde, Fr};
-use zksync_storage::{chain::block::records, ConnectionPool, QueryResult};
-use zksync_types::{tx::TxHash, BlockNumber};
+use zksync_storage::{chain::block::records, ConnectionPool, QueryResult, StorageProcessor};
+use zksync_types::{aggregated_operations::AggregatedActionType, tx::TxHash, BlockNumber, H256};
 
 // Local uses
 use super::{
@@ -24,6 +24,9 @@ use super::{
 };
 use crate::{api_server::helpers::try_parse_tx_hash, utils::shared_lru_cache::AsyncLruCache};
 
+/// Number of the recently executed operations used to estimate the block finality latency.
+const FINALITY_LATENCY_SAMPLE_SIZE: u32 = 10;
+
 /// Shared data between `api/v1/blocks` endpoints.
 #[derive(Debug, Clone)]
 struct ApiBlocksData {
@@ -100,6 +103,106 @@ impl ApiBlocksData {
             .get_block_transactions(block_number)
             .await
     }
+
+    /// Returns the state of the L1 transactions of the block with the specified number.
+    async fn block_finality(
+        &self,
+        block_number: BlockNumber,
+    ) -> QueryResult<Option<BlockFinality>> {
+        let mut storage = self.pool.access_storage().await?;
+        block_finality(&mut storage, block_number).await
+    }
+}
+
+/// Loads the state of the L1 operation of the given type affecting the block.
+///
+/// Returns the creation time of the operation along with the status.
+async fn l1_action_status(
+    storage: &mut StorageProcessor<'_>,
+    action_type: AggregatedActionType,
+    block_number: BlockNumber,
+) -> QueryResult<(L1ActionStatus, Option<DateTime<Utc>>)> {
+    let op = storage
+        .ethereum_schema()
+        .load_aggregated_op_status(action_type, block_number)
+        .await?;
+    let op = match op {
+        Some(op) => op,
+        None => return Ok((L1ActionStatus::default(), None)),
+    };
+
+    let queue_position = storage
+        .ethereum_schema()
+        .count_unconfirmed_aggregated_ops_before(op.op_id)
+        .await?;
+    let tx_hashes = if let Some(eth_op_id) = op.eth_op_id {
+        storage
+            .ethereum_schema()
+            .load_eth_tx_hashes(eth_op_id)
+            .await?
+    } else {
+        Vec::new()
+    };
+
+    let status = L1ActionStatus {
+        queue_position: Some(queue_position),
+        sent: !tx_hashes.is_empty(),
+        confirmed: op.confirmed.unwrap_or_default(),
+        tx_hashes,
+        final_hash: op.final_hash.map(|hash| H256::from_slice(&hash)),
+    };
+    Ok((status, Some(op.created_at)))
+}
+
+/// Returns the state of the L1 transactions of the block along with the estimated
+/// time left until the block is finalized, or `None` if the block doesn't exist.
+pub(super) async fn block_finality(
+    storage: &mut StorageProcessor<'_>,
+    block_number: BlockNumber,
+) -> QueryResult<Option<BlockFinality>> {
+    let last_committed_block = storage
+        .chain()
+        .block_schema()
+        .get_last_committed_block()
+        .await?;
+    if block_number == 0 || block_number > last_committed_block {
+        return Ok(None);
+    }
+
+    let (commit, committed_at) =
+        l1_action_status(storage, AggregatedActionType::CommitBlocks, block_number).await?;
+    let (publish_proof, _) = l1_action_status(
+        storage,
+        AggregatedActionType::PublishProofBlocksOnchain,
+        block_number,
+    )
+    .await?;
+    let (execute, _) =
+        l1_action_status(storage, AggregatedActionType::ExecuteBlocks, block_number).await?;
+
+    let finality_latency = storage
+        .ethereum_schema()
+        .average_finality_latency(FINALITY_LATENCY_SAMPLE_SIZE)
+        .await?;
+    let estimated_finality_secs = if execute.confirmed {
+        Some(0)
+    } else {
+        // The latency is measured from the creation of the commit operation, so the time
+        // passed since then is subtracted for the blocks that are already being committed.
+        let elapsed = committed_at
+            .and_then(|committed_at| (Utc::now() - committed_at).to_std().ok())
+            .unwrap_or_default();
+        finality_latency.map(|latency| latency.checked_sub(elapsed).unwrap_or_default().as_secs())
+    };
+
+    Ok(Some(BlockFinality {
+        block_number,
+        commit,
+        publish_proof,
+        execute,
+        finality_latency_secs: finality_latency.map(|latency| latency.as_secs()),
+        estimated_finality_secs,
+    }))
 }
 
 // Data transfer objects.
@@ -126,6 +229,32 @@ pub struct TransactionInfo {
     pub created_at: DateTime<Utc>,
 }
 
+/// State of the Ethereum transaction performing one of the L1 actions on the block.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct L1ActionStatus {
+    /// Number of the earlier operations that have to be confirmed on L1 before this one,
+    /// absent if the block is not yet included into an operation of this type.
+    pub queue_position: Option<u64>,
+    pub sent: bool,
+    pub confirmed: bool,
+    /// Hashes of the sent transactions, the latest sent one is the last.
+    pub tx_hashes: Vec<H256>,
+    pub final_hash: Option<H256>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BlockFinality {
+    pub block_number: BlockNumber,
+    pub commit: L1ActionStatus,
+    pub publish_proof: L1ActionStatus,
+    pub execute: L1ActionStatus,
+    /// Average time between the commitment and the execution on L1 of the recent blocks.
+    pub finality_latency_secs: Option<u64>,
+    /// Estimated time left until the block is executed on L1, zero for the executed block.
+    /// Absent if there is not enough data for the estimation yet.
+    pub estimated_finality_secs: Option<u64>,
+}
+
 impl From<records::BlockDetails> for BlockInfo {
     fn from(inner: records::BlockDetails) -> Self {
         Self {
@@ -199,6 +328,17 @@ impl Client {
             .await
     }
 
+    /// Returns the state of the L1 transactions of the block with the specified number
+    /// or null if block doesn't exist.
+    pub async fn block_finality(
+        &self,
+        block_number: BlockNumber,
+    ) -> client::Result<Option<BlockFinality>> {
+        self.get(&format!("blocks/{}/finality", block_number))
+            .send()
+            .await
+    }
+
     /// Returns information about several blocks in a range.
     pub async fn blocks_range(
         &self,
@@ -243,6 +383,18 @@ async fn block_transactions(
     ))
 }
 
+async fn block_finality_by_id(
+    data: web::Data<ApiBlocksData>,
+    web::Path(block_number): web::Path<BlockNumber>,
+) -> JsonResult<Option<BlockFinality>> {
+    let finality = data
+        .block_finality(block_number)
+        .await
+        .map_err(ApiError::internal)?;
+
+    Ok(Json(finality))
+}
+
 async fn blocks_range(
     data: web::Data<ApiBlocksData>,
     web::Query(pagination): web::Query<PaginationQuery>,
@@ -277,6 +429,7 @@ pub fn api_scope(api_server_options: &ApiServerOptions, pool: ConnectionPool) ->
         .route("", web::get().to(blocks_range))
         .route("{id}", web::get().to(block_by_id))
         .route("{id}/transactions", web::get().to(block_transactions))
+        .route("{id}/finality", web::get().to(block_finality_by_id))
 }
 
 #[cfg(test)]
@@ -330,6 +483,23 @@ mod tests {
         assert_eq!(client.block_transactions(1).await?, expected_txs);
         assert_eq!(client.block_transactions(6).await?, vec![]);
 
+        // Finality requests part.
+        let expected_finality = {
+            let mut storage = cfg.pool.access_storage().await?;
+            block_finality(&mut storage, 1).await?.unwrap()
+        };
+        let finality = client.block_finality(1).await?.unwrap();
+        assert_eq!(finality.block_number, 1);
+        assert_eq!(finality.commit, expected_finality.commit);
+        assert_eq!(finality.publish_proof, expected_finality.publish_proof);
+        assert_eq!(finality.execute, expected_finality.execute);
+        assert_eq!(
+            finality.finality_latency_secs,
+            expected_finality.finality_latency_secs
+        );
+        assert_eq!(client.block_finality(0).await?, None);
+        assert_eq!(client.block_finality(BlockNumber::MAX).await?, None);
+
         server.stop().await;
         Ok(())
     }
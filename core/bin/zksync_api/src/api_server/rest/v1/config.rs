@@ -8,9 +8,11 @@ use serde::{Deserialize, Serialize};
 
 // Workspace uses
 use zksync_config::ConfigurationOptions;
-use zksync_types::{network::Network, Address};
+use zksync_types::{network::Network, tx::Eip712Domain, Address};
 
 // Local uses
+use crate::signature_checker;
+
 use super::{
     client::{self, Client},
     Json,
@@ -22,6 +24,7 @@ struct ApiConfigData {
     contract_address: Address,
     deposit_confirmations: u64,
     network: Network,
+    eip712_domain: Option<Eip712Domain>,
 }
 
 impl ApiConfigData {
@@ -30,6 +33,7 @@ impl ApiConfigData {
             contract_address: env_options.contract_eth_addr,
             deposit_confirmations: env_options.confirmations_for_eth_event,
             network: env_options.eth_network.parse().unwrap(),
+            eip712_domain: signature_checker::eip712_domain(env_options),
         }
     }
 }
@@ -56,6 +60,12 @@ impl Client {
     pub async fn network(&self) -> client::Result<String> {
         self.get("config/network").send().await
     }
+
+    /// Returns the domain the transactions should be signed in according to EIP-712,
+    /// or `None` if EIP-712 signatures are not accepted.
+    pub async fn eip712_domain(&self) -> client::Result<Option<Eip712Domain>> {
+        self.get("config/eip712_domain").send().await
+    }
 }
 
 // Server implementation
@@ -74,6 +84,10 @@ async fn network(data: web::Data<ApiConfigData>) -> Json<Network> {
     Json(data.network)
 }
 
+async fn eip712_domain(data: web::Data<ApiConfigData>) -> Json<Option<Eip712Domain>> {
+    Json(data.eip712_domain.clone())
+}
+
 pub fn api_scope(env_options: &ConfigurationOptions) -> Scope {
     let data = ApiConfigData::new(env_options);
 
@@ -85,6 +99,7 @@ pub fn api_scope(env_options: &ConfigurationOptions) -> Scope {
             "deposit_confirmations",
             web::get().to(deposit_confirmations),
         )
+        .route("eip712_domain", web::get().to(eip712_domain))
 }
 
 #[cfg(test)]
@@ -108,6 +123,10 @@ mod tests {
                 contract: cfg.env_options.contract_eth_addr
             },
         );
+        assert_eq!(
+            client.eip712_domain().await?.is_some(),
+            cfg.env_options.eip712_signatures_enabled
+        );
 
         server.stop().await;
 
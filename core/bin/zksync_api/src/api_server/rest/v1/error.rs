@@ -50,11 +50,21 @@ impl Error {
         Self::with_code(StatusCode::INTERNAL_SERVER_ERROR, title)
     }
 
+    /// Creates a new Error with the TOO_MANY_REQUESTS (429) status code.
+    pub fn too_many_requests(title: impl Display) -> Self {
+        Self::with_code(StatusCode::TOO_MANY_REQUESTS, title)
+    }
+
     /// Creates a new Error with the NOT_IMPLEMENTED (501) status code.
     pub fn not_implemented(title: impl Display) -> Self {
         Self::with_code(StatusCode::NOT_IMPLEMENTED, title)
     }
 
+    /// Creates a new Error with the SERVICE_UNAVAILABLE (503) status code.
+    pub fn service_unavailable(title: impl Display) -> Self {
+        Self::with_code(StatusCode::SERVICE_UNAVAILABLE, title)
+    }
+
     fn with_code(http_code: StatusCode, title: impl Display) -> Self {
         Self {
             http_code,
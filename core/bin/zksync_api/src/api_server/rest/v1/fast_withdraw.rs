@@ -0,0 +1,564 @@
+//! Fast withdrawals part of API implementation.
+//!
+//! Fast withdrawal is handed off to the liquidity provider: the user transfers the funds
+//! to the provider's L2 account, and the provider pays out the amount minus its fee to
+//! the sender's address in L1 as soon as the transfer is executed, without waiting for
+//! the block to be verified. The recipient is the address that signed the transfer, so it
+//! can't be altered without the user's signature. Providers are registered in the server
+//! config and trusted to pay out the executed transfers, they follow the handed off
+//! withdrawals using the feed endpoint and report the L1 transactions back signed by their
+//! Ethereum keys. Reported transactions are checked in L1 before the withdrawal is
+//! considered paid out.
+
+// Built-in uses
+use std::str::FromStr;
+
+// External uses
+use actix_web::{
+    web::{self, Json},
+    HttpRequest, Scope,
+};
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+use num::{bigint::ToBigInt, BigUint, Zero};
+use serde::{Deserialize, Serialize};
+
+// Workspace uses
+use zksync_config::LiquidityProviderOptions;
+use zksync_storage::lp_withdrawals::records::StorageLpWithdrawal;
+use zksync_types::{
+    tx::{PackedEthSignature, TxEthSignature, TxHash},
+    Address, BlockNumber, TokenId, TokenLike, TxFeeTypes, ZkSyncTx, H256,
+};
+use zksync_utils::BigUintSerdeWrapper;
+
+// Local uses
+use super::{
+    client::Client, client::ClientError, transactions::source_ip, Error as ApiError, JsonResult,
+    MAX_LIMIT,
+};
+use crate::api_server::tx_sender::TxSender;
+
+/// Shared data between `api/v1/fast_withdraw` endpoints.
+#[derive(Clone)]
+struct ApiFastWithdrawData {
+    tx_sender: TxSender,
+    providers: Vec<LiquidityProviderOptions>,
+}
+
+impl ApiFastWithdrawData {
+    fn new(tx_sender: TxSender, providers: Vec<LiquidityProviderOptions>) -> Self {
+        Self {
+            tx_sender,
+            providers,
+        }
+    }
+
+    fn provider(&self, address: Address) -> Option<&LiquidityProviderOptions> {
+        self.providers
+            .iter()
+            .find(|provider| provider.address == address)
+    }
+
+    /// Returns the provider with the lowest fee.
+    fn cheapest_provider(&self) -> Option<&LiquidityProviderOptions> {
+        self.providers
+            .iter()
+            .min_by_key(|provider| provider.fee_bps)
+    }
+}
+
+/// Returns the fee the provider charges for paying out the amount in L1.
+fn provider_fee(amount: &BigUint, fee_bps: u32) -> BigUint {
+    amount * BigUint::from(fee_bps) / BigUint::from(10_000_u32)
+}
+
+/// Returns the message the provider signs to report the L1 transaction paying out
+/// the withdrawal.
+pub fn paid_message(tx_hash: &TxHash, l1_tx_hash: &H256) -> Vec<u8> {
+    format!(
+        "Fast withdrawal {} is paid out in the Ethereum transaction 0x{}",
+        tx_hash.to_string(),
+        hex::encode(l1_tx_hash.as_bytes())
+    )
+    .into_bytes()
+}
+
+fn biguint_to_decimal(value: &BigUint) -> BigDecimal {
+    BigDecimal::from(value.to_bigint().unwrap())
+}
+
+fn decimal_to_biguint(value: &BigDecimal) -> BigUint {
+    value.to_bigint().unwrap().to_biguint().unwrap()
+}
+
+// Data transfer objects.
+
+/// Terms of the fast withdrawal through the cheapest liquidity provider.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FastWithdrawQuote {
+    /// Address the transfer should be sent to.
+    pub liquidity_provider: Address,
+    pub provider_fee_bps: u32,
+    pub token_id: TokenId,
+    /// Amount to transfer to the provider.
+    pub amount: BigUintSerdeWrapper,
+    pub provider_fee: BigUintSerdeWrapper,
+    /// Amount paid out in L1.
+    pub payout_amount: BigUintSerdeWrapper,
+    /// Fee of the transfer to the provider, paid in the withdrawn token.
+    pub transfer_fee: BigUintSerdeWrapper,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct IncomingFastWithdraw {
+    tx: ZkSyncTx,
+    signature: Option<TxEthSignature>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct FastWithdrawPaid {
+    l1_tx_hash: H256,
+    /// Signature of the `paid_message` by the provider's Ethereum key.
+    signature: PackedEthSignature,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct ProviderWithdrawalsQuery {
+    /// Returns the withdrawals with IDs greater than the given one.
+    #[serde(default)]
+    after: i64,
+    limit: u32,
+    #[serde(default)]
+    unpaid_only: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum FastWithdrawStatus {
+    /// The transfer to the provider is not executed yet.
+    Pending,
+    /// The transfer is executed, the provider is expected to pay out the withdrawal.
+    Executed { block: BlockNumber },
+    /// The transfer has been rejected, so the withdrawal won't be paid out.
+    Rejected { reason: Option<String> },
+    /// The provider has reported the L1 transaction paying out the withdrawal, but
+    /// it's not confirmed yet.
+    #[serde(rename_all = "camelCase")]
+    PayoutSubmitted { l1_tx_hash: H256 },
+    /// The payout transaction is confirmed in L1.
+    #[serde(rename_all = "camelCase")]
+    Paid { l1_tx_hash: H256 },
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FastWithdrawInfo {
+    /// Cursor for the provider's withdrawals feed.
+    pub id: i64,
+    pub tx_hash: TxHash,
+    pub account: Address,
+    pub liquidity_provider: Address,
+    pub l1_recipient: Address,
+    pub token_id: TokenId,
+    pub amount: BigUintSerdeWrapper,
+    pub payout_amount: BigUintSerdeWrapper,
+    #[serde(flatten)]
+    pub status: FastWithdrawStatus,
+    pub created_at: DateTime<Utc>,
+    pub paid_at: Option<DateTime<Utc>>,
+    pub payout_confirmed_at: Option<DateTime<Utc>>,
+}
+
+impl From<StorageLpWithdrawal> for FastWithdrawInfo {
+    fn from(inner: StorageLpWithdrawal) -> Self {
+        let status = match (inner.l1_tx_hash, inner.success) {
+            (Some(l1_tx_hash), _) if inner.payout_confirmed_at.is_some() => {
+                FastWithdrawStatus::Paid {
+                    l1_tx_hash: H256::from_slice(&l1_tx_hash),
+                }
+            }
+            (Some(l1_tx_hash), _) => FastWithdrawStatus::PayoutSubmitted {
+                l1_tx_hash: H256::from_slice(&l1_tx_hash),
+            },
+            (None, Some(true)) => FastWithdrawStatus::Executed {
+                block: inner.block_number.unwrap_or_default() as BlockNumber,
+            },
+            (None, Some(false)) => FastWithdrawStatus::Rejected {
+                reason: inner.fail_reason,
+            },
+            (None, None) => FastWithdrawStatus::Pending,
+        };
+
+        Self {
+            id: inner.id,
+            tx_hash: TxHash::from_slice(&inner.tx_hash).unwrap(),
+            account: Address::from_slice(&inner.account_address),
+            liquidity_provider: Address::from_slice(&inner.lp_address),
+            l1_recipient: Address::from_slice(&inner.l1_recipient),
+            token_id: inner.token_id as TokenId,
+            amount: decimal_to_biguint(&inner.amount).into(),
+            payout_amount: decimal_to_biguint(&inner.payout_amount).into(),
+            status,
+            created_at: inner.created_at,
+            paid_at: inner.paid_at,
+            payout_confirmed_at: inner.payout_confirmed_at,
+        }
+    }
+}
+
+// Client implementation
+
+/// Fast withdrawals API part.
+impl Client {
+    /// Quotes the fast withdrawal of the amount through the cheapest liquidity provider.
+    pub async fn fast_withdraw_quote(
+        &self,
+        token: impl Into<TokenLike>,
+        amount: BigUint,
+    ) -> Result<FastWithdrawQuote, ClientError> {
+        self.get(&format!("fast_withdraw/quote/{}/{}", token.into(), amount))
+            .send()
+            .await
+    }
+
+    /// Submits the transfer to the liquidity provider, which pays out the withdrawal
+    /// to the sender's address in L1 once the transfer is executed.
+    pub async fn submit_fast_withdraw(
+        &self,
+        tx: ZkSyncTx,
+        signature: Option<TxEthSignature>,
+    ) -> Result<TxHash, ClientError> {
+        self.post("fast_withdraw/submit")
+            .body(&IncomingFastWithdraw { tx, signature })
+            .send()
+            .await
+    }
+
+    /// Gets the state of the fast withdrawal.
+    pub async fn fast_withdraw(
+        &self,
+        tx_hash: TxHash,
+    ) -> Result<Option<FastWithdrawInfo>, ClientError> {
+        self.get(&format!("fast_withdraw/{}", tx_hash.to_string()))
+            .send()
+            .await
+    }
+
+    /// Gets the withdrawals handed off to the liquidity provider with IDs greater than `after`.
+    pub async fn provider_fast_withdrawals(
+        &self,
+        provider: Address,
+        after: i64,
+        limit: u32,
+        unpaid_only: bool,
+    ) -> Result<Vec<FastWithdrawInfo>, ClientError> {
+        self.get(&format!("fast_withdraw/providers/{:?}", provider))
+            .query(&ProviderWithdrawalsQuery {
+                after,
+                limit,
+                unpaid_only,
+            })
+            .send()
+            .await
+    }
+
+    /// Reports the L1 transaction paying out the fast withdrawal, `signature` is made
+    /// by the provider's Ethereum key over the [`paid_message`](fn.paid_message.html).
+    pub async fn fast_withdraw_paid(
+        &self,
+        tx_hash: TxHash,
+        l1_tx_hash: H256,
+        signature: PackedEthSignature,
+    ) -> Result<bool, ClientError> {
+        self.post(&format!("fast_withdraw/{}/paid", tx_hash.to_string()))
+            .body(&FastWithdrawPaid {
+                l1_tx_hash,
+                signature,
+            })
+            .send()
+            .await
+    }
+}
+
+// Server implementation
+
+async fn fast_withdraw_quote(
+    data: web::Data<ApiFastWithdrawData>,
+    web::Path((token_like, amount)): web::Path<(String, String)>,
+) -> JsonResult<FastWithdrawQuote> {
+    let amount = BigUint::from_str(&amount).map_err(ApiError::bad_request)?;
+    let provider = data
+        .cheapest_provider()
+        .ok_or_else(|| ApiError::not_implemented("Fast withdrawals are disabled"))?;
+    let token = data
+        .tx_sender
+        .tokens
+        .get_token(TokenLike::parse(&token_like))
+        .await
+        .map_err(ApiError::internal)?
+        .ok_or_else(|| ApiError::bad_request("Token not found"))?;
+
+    let transfer_fee = data
+        .tx_sender
+        .tx_fee(
+            TxFeeTypes::Transfer,
+            provider.address,
+            TokenLike::Id(token.id),
+        )
+        .await
+        .map_err(ApiError::from)?
+        .total_fee;
+    let provider_fee = provider_fee(&amount, provider.fee_bps);
+    let payout_amount = &amount - &provider_fee;
+
+    Ok(Json(FastWithdrawQuote {
+        liquidity_provider: provider.address,
+        provider_fee_bps: provider.fee_bps,
+        token_id: token.id,
+        amount: amount.into(),
+        provider_fee: provider_fee.into(),
+        payout_amount: payout_amount.into(),
+        transfer_fee: transfer_fee.into(),
+    }))
+}
+
+async fn submit_fast_withdraw(
+    data: web::Data<ApiFastWithdrawData>,
+    req: HttpRequest,
+    Json(body): Json<IncomingFastWithdraw>,
+) -> JsonResult<TxHash> {
+    let transfer = match &body.tx {
+        ZkSyncTx::Transfer(transfer) => transfer.clone(),
+        _ => {
+            return Err(ApiError::bad_request(
+                "Fast withdrawal must be a transfer to the liquidity provider",
+            ))
+        }
+    };
+    let provider = data
+        .provider(transfer.to)
+        .ok_or_else(|| ApiError::bad_request("Transfer recipient is not a liquidity provider"))?;
+    if transfer.amount.is_zero() {
+        return Err(ApiError::bad_request("Withdrawal amount must be positive"));
+    }
+    let payout_amount = &transfer.amount - provider_fee(&transfer.amount, provider.fee_bps);
+
+    // The withdrawal is stored before the transfer is submitted: once the transfer is
+    // in the mempool, it may be executed at any moment, and the provider must be able
+    // to see the withdrawal it's paid for.
+    let tx_hash = body.tx.hash();
+    let mut storage = data
+        .tx_sender
+        .pool
+        .access_storage()
+        .await
+        .map_err(ApiError::internal)?;
+    storage
+        .lp_withdrawals_schema()
+        .store_withdrawal(
+            &tx_hash,
+            transfer.from,
+            transfer.to,
+            transfer.from,
+            transfer.token,
+            biguint_to_decimal(&transfer.amount),
+            biguint_to_decimal(&payout_amount),
+        )
+        .await
+        .map_err(ApiError::internal)?;
+
+    match data
+        .tx_sender
+        .submit_tx(
+            body.tx,
+            body.signature,
+            None,
+            source_ip(&req, &data.tx_sender.trusted_proxies),
+        )
+        .await
+    {
+        Ok(submitted_hash) => Ok(Json(submitted_hash)),
+        Err(err) => {
+            storage
+                .lp_withdrawals_schema()
+                .delete_withdrawal(&tx_hash)
+                .await
+                .map_err(ApiError::internal)?;
+            Err(ApiError::from(err))
+        }
+    }
+}
+
+async fn fast_withdraw_info(
+    data: web::Data<ApiFastWithdrawData>,
+    web::Path(tx_hash): web::Path<TxHash>,
+) -> JsonResult<Option<FastWithdrawInfo>> {
+    let mut storage = data
+        .tx_sender
+        .pool
+        .access_storage()
+        .await
+        .map_err(ApiError::internal)?;
+    let withdrawal = storage
+        .lp_withdrawals_schema()
+        .load_withdrawal(&tx_hash)
+        .await
+        .map_err(ApiError::internal)?;
+
+    Ok(Json(withdrawal.map(FastWithdrawInfo::from)))
+}
+
+async fn provider_withdrawals(
+    data: web::Data<ApiFastWithdrawData>,
+    web::Path(provider): web::Path<Address>,
+    web::Query(query): web::Query<ProviderWithdrawalsQuery>,
+) -> JsonResult<Vec<FastWithdrawInfo>> {
+    if query.limit == 0 || query.limit > MAX_LIMIT {
+        return Err(ApiError::bad_request("Incorrect limit")
+            .detail(format!("Limit should be between {} and {}", 1, MAX_LIMIT)));
+    }
+
+    let mut storage = data
+        .tx_sender
+        .pool
+        .access_storage()
+        .await
+        .map_err(ApiError::internal)?;
+    let withdrawals = storage
+        .lp_withdrawals_schema()
+        .load_lp_withdrawals(provider, query.after, query.unpaid_only, query.limit)
+        .await
+        .map_err(ApiError::internal)?;
+
+    Ok(Json(
+        withdrawals
+            .into_iter()
+            .map(FastWithdrawInfo::from)
+            .collect(),
+    ))
+}
+
+async fn fast_withdraw_paid(
+    data: web::Data<ApiFastWithdrawData>,
+    web::Path(tx_hash): web::Path<TxHash>,
+    Json(body): Json<FastWithdrawPaid>,
+) -> JsonResult<bool> {
+    let signer = body
+        .signature
+        .signature_recover_signer(&paid_message(&tx_hash, &body.l1_tx_hash))
+        .map_err(|_| ApiError::bad_request("Incorrect signature"))?;
+    if data.provider(signer).is_none() {
+        return Err(ApiError::bad_request("Signer is not a liquidity provider"));
+    }
+
+    let mut storage = data
+        .tx_sender
+        .pool
+        .access_storage()
+        .await
+        .map_err(ApiError::internal)?;
+    let updated = storage
+        .lp_withdrawals_schema()
+        .mark_paid(&tx_hash, signer, body.l1_tx_hash)
+        .await
+        .map_err(ApiError::internal)?;
+
+    Ok(Json(updated))
+}
+
+pub fn api_scope(tx_sender: TxSender, providers: Vec<LiquidityProviderOptions>) -> Scope {
+    let data = ApiFastWithdrawData::new(tx_sender, providers);
+
+    web::scope("fast_withdraw")
+        .data(data)
+        .route("quote/{token}/{amount}", web::get().to(fast_withdraw_quote))
+        .route("submit", web::post().to(submit_fast_withdraw))
+        .route("providers/{address}", web::get().to(provider_withdrawals))
+        .route("{tx_hash}", web::get().to(fast_withdraw_info))
+        .route("{tx_hash}/paid", web::post().to(fast_withdraw_paid))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn provider_fee_in_bps() {
+        let amount = BigUint::from(1_000_000_u32);
+        assert_eq!(provider_fee(&amount, 0), BigUint::zero());
+        assert_eq!(provider_fee(&amount, 30), BigUint::from(3_000_u32));
+        assert_eq!(provider_fee(&BigUint::from(99_u32), 100), BigUint::zero());
+    }
+
+    #[test]
+    fn withdrawal_status() {
+        let withdrawal = StorageLpWithdrawal {
+            id: 1,
+            tx_hash: vec![1; 32],
+            account_address: vec![2; 20],
+            lp_address: vec![3; 20],
+            l1_recipient: vec![4; 20],
+            token_id: 0,
+            amount: BigDecimal::from(1000),
+            payout_amount: BigDecimal::from(997),
+            l1_tx_hash: None,
+            created_at: Utc::now(),
+            paid_at: None,
+            payout_confirmed_at: None,
+            block_number: None,
+            success: None,
+            fail_reason: None,
+        };
+        let status =
+            |withdrawal: &StorageLpWithdrawal| FastWithdrawInfo::from(withdrawal.clone()).status;
+
+        assert_eq!(status(&withdrawal), FastWithdrawStatus::Pending);
+        let executed = StorageLpWithdrawal {
+            block_number: Some(5),
+            success: Some(true),
+            ..withdrawal.clone()
+        };
+        assert_eq!(status(&executed), FastWithdrawStatus::Executed { block: 5 });
+        let rejected = StorageLpWithdrawal {
+            block_number: Some(5),
+            success: Some(false),
+            fail_reason: Some("Not enough balance".into()),
+            ..withdrawal.clone()
+        };
+        assert_eq!(
+            status(&rejected),
+            FastWithdrawStatus::Rejected {
+                reason: Some("Not enough balance".into())
+            }
+        );
+        let submitted = StorageLpWithdrawal {
+            l1_tx_hash: Some(vec![5; 32]),
+            ..executed
+        };
+        assert_eq!(
+            status(&submitted),
+            FastWithdrawStatus::PayoutSubmitted {
+                l1_tx_hash: H256::repeat_byte(5)
+            }
+        );
+        let paid = StorageLpWithdrawal {
+            payout_confirmed_at: Some(Utc::now()),
+            ..submitted
+        };
+        assert_eq!(
+            status(&paid),
+            FastWithdrawStatus::Paid {
+                l1_tx_hash: H256::repeat_byte(5)
+            }
+        );
+
+        let info = FastWithdrawInfo::from(withdrawal);
+        assert_eq!(info.liquidity_provider, Address::repeat_byte(3));
+        assert_eq!(info.payout_amount.0, BigUint::from(997_u32));
+    }
+}
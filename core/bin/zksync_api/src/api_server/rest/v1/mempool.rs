@@ -0,0 +1,154 @@
+//! Mempool part of API implementation.
+
+// Built-in uses
+use std::collections::BTreeMap;
+
+// External uses
+use actix_web::{
+    web::{self, Json},
+    Scope,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+// Workspace uses
+use zksync_storage::{ConnectionPool, QueryResult};
+use zksync_types::{tx::TxHash, Nonce, TokenId};
+
+// Local uses
+use super::{
+    client::{self, Client},
+    Error as ApiError, JsonResult,
+};
+
+/// Shared data between `api/v1/mempool` endpoints.
+#[derive(Debug, Clone)]
+struct ApiMempoolData {
+    pool: ConnectionPool,
+}
+
+impl ApiMempoolData {
+    fn new(pool: ConnectionPool) -> Self {
+        Self { pool }
+    }
+
+    async fn stats(&self) -> QueryResult<MempoolStats> {
+        let mut storage = self.pool.access_storage().await?;
+        let stats = storage.chain().mempool_schema().get_stats().await?;
+
+        Ok(MempoolStats {
+            txs_count: stats.txs_count as u64,
+            batches_count: stats.batches_count as u64,
+            oldest_tx_age: stats
+                .oldest_tx_created_at
+                .map(|created_at| (Utc::now() - created_at).num_seconds().max(0) as u64),
+            txs_by_fee_token: stats
+                .txs_by_fee_token
+                .into_iter()
+                .map(|(token_id, count)| (token_id, count as u64))
+                .collect(),
+        })
+    }
+
+    async fn pending_tx(&self, tx_hash: TxHash) -> QueryResult<Option<PendingTxInfo>> {
+        let mut storage = self.pool.access_storage().await?;
+
+        let position = storage
+            .chain()
+            .mempool_schema()
+            .get_tx_position(tx_hash)
+            .await?;
+        let position = match position {
+            Some(position) => position,
+            None => return Ok(None),
+        };
+
+        let account_nonce = storage
+            .chain()
+            .account_schema()
+            .account_state_by_address(position.tx.account())
+            .await?
+            .committed
+            .map(|(_, account)| account.nonce)
+            .unwrap_or_default();
+
+        Ok(Some(PendingTxInfo {
+            received_at: position.created_at,
+            txs_ahead: position.txs_ahead as u64,
+            nonce: position.tx.nonce(),
+            account_nonce,
+            eligible: position.tx.nonce() == account_nonce,
+        }))
+    }
+}
+
+// Data transfer objects.
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MempoolStats {
+    /// Amount of the pending transactions, with every transaction of a batch counted separately.
+    pub txs_count: u64,
+    pub batches_count: u64,
+    /// Age of the oldest pending transaction in seconds.
+    pub oldest_tx_age: Option<u64>,
+    /// Amount of the pending transactions paying fee in each token.
+    pub txs_by_fee_token: BTreeMap<TokenId, u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingTxInfo {
+    pub received_at: DateTime<Utc>,
+    /// Amount of the pending transactions received before this one.
+    pub txs_ahead: u64,
+    pub nonce: Nonce,
+    /// Committed nonce of the transaction account.
+    pub account_nonce: Nonce,
+    /// Whether the transaction can be executed right away, i.e. it doesn't wait
+    /// for other pending transactions of the same account.
+    pub eligible: bool,
+}
+
+// Client implementation
+
+/// Mempool API part.
+impl Client {
+    /// Gets statistics of the pending transactions.
+    pub async fn mempool_stats(&self) -> client::Result<MempoolStats> {
+        self.get("mempool/stats").send().await
+    }
+
+    /// Gets position and eligibility of the pending transaction.
+    pub async fn pending_tx(&self, tx_hash: TxHash) -> client::Result<Option<PendingTxInfo>> {
+        self.get(&format!("mempool/tx/{}", tx_hash.to_string()))
+            .send()
+            .await
+    }
+}
+
+// Server implementation
+
+async fn stats(data: web::Data<ApiMempoolData>) -> JsonResult<MempoolStats> {
+    let stats = data.stats().await.map_err(ApiError::internal)?;
+
+    Ok(Json(stats))
+}
+
+async fn pending_tx(
+    data: web::Data<ApiMempoolData>,
+    web::Path(tx_hash): web::Path<TxHash>,
+) -> JsonResult<Option<PendingTxInfo>> {
+    let info = data.pending_tx(tx_hash).await.map_err(ApiError::internal)?;
+
+    Ok(Json(info))
+}
+
+pub fn api_scope(pool: ConnectionPool) -> Scope {
+    let data = ApiMempoolData::new(pool);
+
+    web::scope("mempool")
+        .data(data)
+        .route("stats", web::get().to(stats))
+        .route("tx/{tx_hash}", web::get().to(pending_tx))
+}
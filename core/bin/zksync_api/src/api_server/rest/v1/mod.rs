@@ -17,7 +17,7 @@ use zksync_config::{ApiServerOptions, ConfigurationOptions};
 use zksync_types::BlockNumber;
 
 // Local uses
-use crate::api_server::tx_sender::TxSender;
+use crate::{api_server::tx_sender::TxSender, utils::account_id_cache::AccountIdCache};
 
 use Error as ApiError;
 
@@ -26,10 +26,12 @@ mod blocks;
 pub mod client;
 mod config;
 mod error;
+mod fast_withdraw;
+mod mempool;
 mod operations;
 mod search;
 #[cfg(test)]
-mod test_utils;
+pub(crate) mod test_utils;
 mod tokens;
 mod transactions;
 
@@ -40,6 +42,7 @@ type JsonResult<T> = std::result::Result<web::Json<T>, Error>;
 
 pub(crate) fn api_scope(
     tx_sender: TxSender,
+    account_ids: AccountIdCache,
     env_options: ConfigurationOptions,
     api_server_options: ApiServerOptions,
 ) -> Scope {
@@ -47,6 +50,7 @@ pub(crate) fn api_scope(
         .service(accounts::api_scope(
             &env_options,
             tx_sender.tokens.clone(),
+            account_ids,
             tx_sender.core_api_client.clone(),
         ))
         .service(config::api_scope(&env_options))
@@ -55,7 +59,12 @@ pub(crate) fn api_scope(
             tx_sender.pool.clone(),
         ))
         .service(transactions::api_scope(tx_sender.clone()))
+        .service(fast_withdraw::api_scope(
+            tx_sender.clone(),
+            api_server_options.liquidity_providers.clone(),
+        ))
         .service(operations::api_scope(tx_sender.pool.clone()))
+        .service(mempool::api_scope(tx_sender.pool.clone()))
         .service(search::api_scope(tx_sender.pool.clone()))
         .service(tokens::api_scope(
             tx_sender.tokens,
@@ -1,6 +1,7 @@
 //! Search part of API implementation.
 
 // Built-in uses
+use std::str::FromStr;
 
 // External uses
 use actix_web::{
@@ -8,11 +9,14 @@ use actix_web::{
     Scope,
 };
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 // Workspace uses
 use zksync_crypto::{convert::FeConvert, Fr};
-use zksync_storage::{ConnectionPool, QueryResult};
-use zksync_types::{tx::TxHash, BlockNumber};
+use zksync_storage::{
+    chain::operations_ext::records::TxByHashResponse, ConnectionPool, QueryResult,
+};
+use zksync_types::{tx::TxHash, AccountId, Address, BlockNumber, Token, TokenId, TokenLike};
 
 // Local uses
 use super::{
@@ -20,6 +24,7 @@ use super::{
     client::{self, Client},
     Error as ApiError, JsonResult,
 };
+use crate::api_server::helpers::try_parse_hash;
 
 /// Shared data between `api/v1/search` endpoints.
 #[derive(Clone)]
@@ -43,6 +48,72 @@ impl ApiSearchData {
 
         Ok(block.map(BlockInfo::from))
     }
+
+    async fn search_transaction(&self, query: &str) -> QueryResult<Option<TransactionDetails>> {
+        let hash = match try_parse_hash(query) {
+            Ok(hash) => hash,
+            Err(_) => return Ok(None),
+        };
+
+        let mut storage = self.pool.access_storage().await?;
+        let tx = storage
+            .chain()
+            .operations_ext_schema()
+            .get_tx_by_hash(hash.as_bytes())
+            .await?;
+
+        Ok(tx.map(TransactionDetails::from))
+    }
+
+    /// Resolves the query to the entity it refers to.
+    ///
+    /// Numbers are treated as block numbers, hashes are looked up among the transactions
+    /// first and then among the blocks. Addresses are resolved to tokens if there is
+    /// a token with such address and to accounts otherwise. Any other query is treated
+    /// as a token symbol.
+    async fn search(&self, query: String) -> QueryResult<Option<SearchResult>> {
+        let query = query.trim();
+
+        if query.parse::<BlockNumber>().is_ok() {
+            let block = self.search_block(query.to_owned()).await?;
+            return Ok(block.map(SearchResult::Block));
+        }
+
+        if try_parse_hash(query).is_ok() {
+            if let Some(tx) = self.search_transaction(query).await? {
+                return Ok(Some(SearchResult::Transaction(tx)));
+            }
+
+            let block = self.search_block(query.to_owned()).await?;
+            return Ok(block.map(SearchResult::Block));
+        }
+
+        let mut storage = self.pool.access_storage().await?;
+
+        let address = query.strip_prefix("0x").unwrap_or(query);
+        if let Ok(address) = Address::from_str(address) {
+            let token = storage
+                .tokens_schema()
+                .get_token(TokenLike::Address(address))
+                .await?;
+            if let Some(token) = token {
+                return Ok(Some(SearchResult::Token(token)));
+            }
+
+            let id = storage
+                .chain()
+                .account_schema()
+                .account_id_by_address(address)
+                .await?;
+            return Ok(Some(SearchResult::Account { address, id }));
+        }
+
+        let token = storage
+            .tokens_schema()
+            .get_token(TokenLike::Symbol(query.to_owned()))
+            .await?;
+        Ok(token.map(SearchResult::Token))
+    }
 }
 
 // Data transfer objects.
@@ -52,6 +123,65 @@ pub struct BlockSearchQuery {
     query: String,
 }
 
+/// Executed transaction or priority operation.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TransactionDetails {
+    pub tx_type: String,
+    pub from: String,
+    pub to: String,
+    pub token: TokenId,
+    pub amount: String,
+    pub fee: Option<String>,
+    /// Absent for the priority operations which are not yet included into a block.
+    pub block_number: Option<BlockNumber>,
+    /// Absent for the priority operations.
+    pub nonce: Option<u32>,
+    pub created_at: String,
+    pub fail_reason: Option<String>,
+    /// Transaction or priority operation in the form it was submitted.
+    pub tx: Value,
+}
+
+impl From<TxByHashResponse> for TransactionDetails {
+    fn from(inner: TxByHashResponse) -> Self {
+        Self {
+            tx_type: inner.tx_type,
+            from: inner.from,
+            to: inner.to,
+            token: inner.token as TokenId,
+            amount: inner.amount,
+            fee: inner.fee,
+            block_number: if inner.block_number >= 0 {
+                Some(inner.block_number as BlockNumber)
+            } else {
+                None
+            },
+            nonce: if inner.nonce >= 0 {
+                Some(inner.nonce as u32)
+            } else {
+                None
+            },
+            created_at: inner.created_at,
+            fail_reason: inner.fail_reason,
+            tx: inner.tx,
+        }
+    }
+}
+
+/// Entity found by the universal search.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SearchResult {
+    Account {
+        address: Address,
+        /// Absent if the account is not created in zkSync yet.
+        id: Option<AccountId>,
+    },
+    Token(Token),
+    Block(BlockInfo),
+    Transaction(TransactionDetails),
+}
+
 // Client implementation
 
 impl From<BlockNumber> for BlockSearchQuery {
@@ -95,6 +225,30 @@ impl Client {
     ) -> client::Result<Option<BlockInfo>> {
         self.get("search").query(&query.into()).send().await
     }
+
+    /// Finds an executed transaction by its zkSync hash, or a priority operation
+    /// by the hash of the Ethereum transaction which created it.
+    pub async fn search_transaction(
+        &self,
+        hash: impl AsRef<str>,
+    ) -> client::Result<Option<TransactionDetails>> {
+        self.get("search/transaction")
+            .query(&BlockSearchQuery {
+                query: hash.as_ref().to_owned(),
+            })
+            .send()
+            .await
+    }
+
+    /// Resolves an arbitrary query to an account, token, block or transaction.
+    pub async fn search(&self, query: impl AsRef<str>) -> client::Result<Option<SearchResult>> {
+        self.get("search/any")
+            .query(&BlockSearchQuery {
+                query: query.as_ref().to_owned(),
+            })
+            .send()
+            .await
+    }
 }
 
 // Server implementation
@@ -111,12 +265,35 @@ async fn block_search(
     Ok(Json(block_info))
 }
 
+async fn transaction_search(
+    data: web::Data<ApiSearchData>,
+    web::Query(query): web::Query<BlockSearchQuery>,
+) -> JsonResult<Option<TransactionDetails>> {
+    let tx = data
+        .search_transaction(&query.query)
+        .await
+        .map_err(ApiError::internal)?;
+
+    Ok(Json(tx))
+}
+
+async fn universal_search(
+    data: web::Data<ApiSearchData>,
+    web::Query(query): web::Query<BlockSearchQuery>,
+) -> JsonResult<Option<SearchResult>> {
+    let result = data.search(query.query).await.map_err(ApiError::internal)?;
+
+    Ok(Json(result))
+}
+
 pub fn api_scope(pool: ConnectionPool) -> Scope {
     let data = ApiSearchData::new(pool);
 
     web::scope("search")
         .data(data)
         .route("", web::get().to(block_search))
+        .route("transaction", web::get().to(transaction_search))
+        .route("any", web::get().to(universal_search))
 }
 
 #[cfg(test)]
@@ -160,6 +337,49 @@ mod tests {
             block_info
         );
 
+        // Universal search for the block.
+        assert_eq!(
+            client.search("1").await?,
+            Some(SearchResult::Block(block_info))
+        );
+
+        // Search for the executed transaction.
+        let tx_hash = {
+            let mut storage = cfg.pool.access_storage().await?;
+            let transactions = storage
+                .chain()
+                .block_schema()
+                .get_block_transactions(1)
+                .await?;
+            transactions[0].tx_hash.clone()
+        };
+        let tx = client
+            .search_transaction(&tx_hash)
+            .await?
+            .expect("transaction should be exist");
+        assert_eq!(tx.block_number, Some(1));
+        assert_eq!(
+            client.search(&tx_hash).await?,
+            Some(SearchResult::Transaction(tx))
+        );
+        assert_eq!(client.search_transaction("0xdeadbeef").await?, None);
+
+        // Universal search for the tokens and accounts.
+        assert!(matches!(
+            client.search("ETH").await?,
+            Some(SearchResult::Token(_))
+        ));
+        assert_eq!(
+            client
+                .search(format!("{:?}", Address::repeat_byte(0x11)))
+                .await?,
+            Some(SearchResult::Account {
+                address: Address::repeat_byte(0x11),
+                id: None,
+            })
+        );
+        assert_eq!(client.search("UNKNOWN_TOKEN").await?, None);
+
         server.stop().await;
         Ok(())
     }
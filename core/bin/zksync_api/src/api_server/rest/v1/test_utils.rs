@@ -106,6 +106,7 @@ impl TestServerConfig {
                 success: true,
                 op: Some(zksync_op),
                 fail_reason: None,
+                fail_trace: None,
                 block_index: Some(1),
                 created_at: chrono::Utc::now(),
                 batch_id: None,
@@ -133,6 +134,7 @@ impl TestServerConfig {
                 success: true,
                 op: Some(zksync_op),
                 fail_reason: None,
+                fail_trace: None,
                 block_index: Some(2),
                 created_at: chrono::Utc::now(),
                 batch_id: None,
@@ -160,6 +162,7 @@ impl TestServerConfig {
                 success: false,
                 op: Some(zksync_op),
                 fail_reason: Some("Unknown token".to_string()),
+                fail_trace: None,
                 block_index: None,
                 created_at: chrono::Utc::now(),
                 batch_id: None,
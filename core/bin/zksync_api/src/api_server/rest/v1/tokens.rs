@@ -8,6 +8,8 @@ use actix_web::{
     Scope,
 };
 use bigdecimal::BigDecimal;
+use chrono::{DateTime, Duration, Utc};
+use num::bigint::ToBigInt;
 use serde::{Deserialize, Serialize};
 
 // Workspace uses
@@ -15,13 +17,14 @@ use futures::{
     channel::{mpsc, oneshot},
     prelude::*,
 };
-use zksync_storage::QueryResult;
-use zksync_types::{Token, TokenLike};
+use zksync_storage::{fee_history::records::StorageFeeQuote, QueryResult};
+use zksync_types::{Token, TokenId, TokenLike};
+use zksync_utils::BigUintSerdeWrapper;
 
 // Local uses
 use super::{
     client::{self, Client},
-    Error as ApiError, JsonResult,
+    Error as ApiError, JsonResult, MAX_LIMIT,
 };
 use crate::{
     fee_ticker::{TickerRequest, TokenPriceRequestType},
@@ -80,6 +83,23 @@ impl ApiTokensData {
             }
         }
     }
+
+    async fn fee_history(
+        &self,
+        token_id: TokenId,
+        query: &FeeHistoryQuery,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        limit: u32,
+    ) -> QueryResult<Vec<FeeQuoteInfo>> {
+        let mut storage = self.tokens.pool.access_storage().await?;
+
+        let quotes = storage
+            .fee_history_schema()
+            .load_fee_history(token_id, query.fee_type.as_deref(), from, to, limit)
+            .await?;
+        Ok(quotes.into_iter().map(FeeQuoteInfo::from).collect())
+    }
 }
 
 // Data transfer objects.
@@ -97,6 +117,59 @@ struct TokenPriceQuery {
     kind: TokenPriceKind,
 }
 
+/// Filter of the fee history, by default the quotes of all the fee types
+/// made within the last day are returned.
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeHistoryQuery {
+    /// Fee type as quoted, e.g. `TransferToNew`.
+    pub fee_type: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub limit: Option<u32>,
+}
+
+/// Fee quoted by the server along with the prices it was calculated with.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeQuoteInfo {
+    pub fee_type: String,
+    pub gas_tx_amount: BigUintSerdeWrapper,
+    pub gas_price_wei: BigUintSerdeWrapper,
+    pub gas_fee: BigUintSerdeWrapper,
+    pub zkp_fee: BigUintSerdeWrapper,
+    pub total_fee: BigUintSerdeWrapper,
+    /// Price of the whole token in USD.
+    pub token_price_usd: BigDecimal,
+    /// Price of the whole ETH in USD.
+    pub eth_price_usd: BigDecimal,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<StorageFeeQuote> for FeeQuoteInfo {
+    fn from(inner: StorageFeeQuote) -> Self {
+        let amount = |value: &BigDecimal| {
+            value
+                .to_bigint()
+                .and_then(|value| value.to_biguint())
+                .unwrap_or_default()
+                .into()
+        };
+
+        Self {
+            fee_type: inner.fee_type,
+            gas_tx_amount: amount(&inner.gas_tx_amount),
+            gas_price_wei: amount(&inner.gas_price_wei),
+            gas_fee: amount(&inner.gas_fee),
+            zkp_fee: amount(&inner.zkp_fee),
+            total_fee: amount(&inner.total_fee),
+            token_price_usd: inner.token_price_usd,
+            eth_price_usd: inner.eth_price_usd,
+            created_at: inner.created_at,
+        }
+    }
+}
+
 // Client implementation
 
 /// Tokens API part.
@@ -119,6 +192,18 @@ impl Client {
             .send()
             .await
     }
+
+    /// Returns the fees quoted in the token, newest first, or `None` if the token is unknown.
+    pub async fn token_fee_history(
+        &self,
+        token: &TokenLike,
+        query: &FeeHistoryQuery,
+    ) -> client::Result<Option<Vec<FeeQuoteInfo>>> {
+        self.get(&format!("tokens/{}/fee_history", token))
+            .query(query)
+            .send()
+            .await
+    }
 }
 
 // Server implementation
@@ -162,6 +247,32 @@ async fn token_price(
     Ok(Json(price))
 }
 
+async fn token_fee_history(
+    data: web::Data<ApiTokensData>,
+    web::Path(token_like): web::Path<String>,
+    web::Query(query): web::Query<FeeHistoryQuery>,
+) -> JsonResult<Option<Vec<FeeQuoteInfo>>> {
+    let limit = query.limit.unwrap_or(MAX_LIMIT);
+    if limit == 0 || limit > MAX_LIMIT {
+        return Err(ApiError::bad_request("Incorrect limit")
+            .detail(format!("Limit should be between {} and {}", 1, MAX_LIMIT)));
+    }
+    let to = query.to.unwrap_or_else(Utc::now);
+    let from = query.from.unwrap_or_else(|| to - Duration::days(1));
+
+    let token_like = TokenLike::parse(&token_like);
+    let token = match data.token(token_like).await.map_err(ApiError::internal)? {
+        Some(token) => token,
+        None => return Ok(Json(None)),
+    };
+
+    let history = data
+        .fee_history(token.id, &query, from, to, limit)
+        .await
+        .map_err(ApiError::internal)?;
+    Ok(Json(Some(history)))
+}
+
 pub fn api_scope(tokens_db: TokenDBCache, fee_ticker: mpsc::Sender<TickerRequest>) -> Scope {
     let data = ApiTokensData::new(tokens_db, fee_ticker);
 
@@ -170,12 +281,14 @@ pub fn api_scope(tokens_db: TokenDBCache, fee_ticker: mpsc::Sender<TickerRequest
         .route("", web::get().to(tokens))
         .route("{id}", web::get().to(token_by_id))
         .route("{id}/price", web::get().to(token_price))
+        .route("{id}/fee_history", web::get().to(token_fee_history))
 }
 
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
 
+    use zksync_storage::fee_history::records::NewFeeQuote;
     use zksync_types::Address;
 
     use super::{super::test_utils::TestServerConfig, *};
@@ -304,6 +417,92 @@ mod tests {
         Ok(())
     }
 
+    #[actix_rt::test]
+    async fn test_fee_history() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let quote = |fee_type: &str, total_fee: u64| NewFeeQuote {
+            token_id: 0,
+            fee_type: fee_type.into(),
+            gas_tx_amount: 2000.into(),
+            gas_price_wei: 10_000_000_000_u64.into(),
+            gas_fee: 1000.into(),
+            zkp_fee: 500.into(),
+            total_fee: total_fee.into(),
+            token_price_usd: 600.into(),
+            eth_price_usd: 600.into(),
+        };
+        {
+            let mut storage = cfg.pool.access_storage().await?;
+            // Remove the quotes left by the previous runs.
+            storage
+                .fee_history_schema()
+                .remove_fee_history_before(Utc::now() + Duration::hours(1))
+                .await?;
+            for quote in vec![quote("Transfer", 1500), quote("Withdraw", 2500)] {
+                storage.fee_history_schema().store_fee_quote(quote).await?;
+            }
+        }
+
+        let fee_ticker = dummy_fee_ticker(&[]);
+        let (client, server) = cfg.start_server(move |cfg| {
+            api_scope(TokenDBCache::new(cfg.pool.clone()), fee_ticker.clone())
+        });
+
+        let history = client
+            .token_fee_history(&TokenLike::Id(0), &FeeHistoryQuery::default())
+            .await?
+            .unwrap();
+        let total_fees: Vec<_> = history
+            .iter()
+            .map(|quote| quote.total_fee.0.clone())
+            .collect();
+        assert_eq!(total_fees, vec![2500_u64.into(), 1500_u64.into()]);
+        assert_eq!(history[1].fee_type, "Transfer");
+        assert_eq!(history[1].gas_price_wei.0, 10_000_000_000_u64.into());
+        assert_eq!(history[1].eth_price_usd, 600.into());
+
+        let query = FeeHistoryQuery {
+            fee_type: Some("Transfer".into()),
+            ..FeeHistoryQuery::default()
+        };
+        let history = client
+            .token_fee_history(&TokenLike::parse("ETH"), &query)
+            .await?
+            .unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].total_fee.0, 1500_u64.into());
+
+        let query = FeeHistoryQuery {
+            to: Some(Utc::now() - Duration::hours(1)),
+            ..FeeHistoryQuery::default()
+        };
+        assert!(client
+            .token_fee_history(&TokenLike::Id(0), &query)
+            .await?
+            .unwrap()
+            .is_empty());
+
+        let query = FeeHistoryQuery {
+            limit: Some(MAX_LIMIT + 1),
+            ..FeeHistoryQuery::default()
+        };
+        client
+            .token_fee_history(&TokenLike::Id(0), &query)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            client
+                .token_fee_history(&TokenLike::parse("XM"), &FeeHistoryQuery::default())
+                .await?,
+            None
+        );
+
+        server.stop().await;
+        Ok(())
+    }
+
     // Test special case for Golem: tGLM token name should be alias for the GNT.
     // By the way, since `TokenDBCache` is shared between this API implementation
     // and the old RPC code, there is no need to write a test for the old implementation.
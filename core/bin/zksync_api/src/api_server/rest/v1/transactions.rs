@@ -1,11 +1,12 @@
 //! Transactions part of API implementation.
 
 // Built-in uses
+use std::net::IpAddr;
 
 // External uses
 use actix_web::{
     web::{self, Json},
-    Scope,
+    HttpRequest, Scope,
 };
 use serde::{Deserialize, Serialize};
 
@@ -20,10 +21,13 @@ use zksync_types::{
 
 // Local uses
 use super::{
-    client::Client, client::ClientError, Error as ApiError, JsonResult, Pagination, PaginationQuery,
+    blocks::{block_finality, BlockFinality},
+    client::Client,
+    client::ClientError,
+    Error as ApiError, JsonResult, Pagination, PaginationQuery,
 };
-use crate::api_server::rpc_server::types::TxWithSignature;
 use crate::api_server::tx_sender::{SubmitError, TxSender};
+use crate::api_server::{client_addr::client_ip, rpc_server::types::TxWithSignature};
 
 #[derive(Debug, Clone, Copy)]
 pub enum SumbitErrorCode {
@@ -33,10 +37,14 @@ pub enum SumbitErrorCode {
     IncorrectTx = 104,
     TxAdd = 105,
     InappropriateFeeToken = 106,
+    RateLimited = 107,
+    TxAcceptancePaused = 108,
+    TokenDisabled = 109,
 
     Internal = 110,
     CommunicationCoreServer = 111,
     Other = 112,
+    MaintenanceMode = 113,
 }
 
 impl SumbitErrorCode {
@@ -48,6 +56,10 @@ impl SumbitErrorCode {
             SubmitError::IncorrectTx(_) => Self::IncorrectTx,
             SubmitError::TxAdd(_) => Self::TxAdd,
             SubmitError::InappropriateFeeToken => Self::InappropriateFeeToken,
+            SubmitError::RateLimited { .. } => Self::RateLimited,
+            SubmitError::TxAcceptancePaused => Self::TxAcceptancePaused,
+            SubmitError::MaintenanceMode => Self::MaintenanceMode,
+            SubmitError::TokenDisabled(_) => Self::TokenDisabled,
             SubmitError::CommunicationCoreServer(_) => Self::CommunicationCoreServer,
             SubmitError::Internal(_) => Self::Internal,
             SubmitError::Other(_) => Self::Other,
@@ -63,10 +75,11 @@ impl From<SubmitError> for ApiError {
     fn from(inner: SubmitError) -> Self {
         let internal_code = SumbitErrorCode::from_err(&inner).as_code();
 
-        if let SubmitError::Internal(err) = &inner {
-            ApiError::internal(err)
-        } else {
-            ApiError::bad_request(inner)
+        match &inner {
+            SubmitError::Internal(err) => ApiError::internal(err),
+            SubmitError::RateLimited { .. } => ApiError::too_many_requests(inner),
+            SubmitError::MaintenanceMode => ApiError::service_unavailable(inner),
+            _ => ApiError::bad_request(inner),
         }
         .code(internal_code)
     }
@@ -179,6 +192,18 @@ impl ApiTransactionsData {
             storage.chain().mempool_schema().get_tx(tx_hash).await
         }
     }
+
+    /// Returns the state of the L1 transactions of the block containing the transaction,
+    /// or `None` if the transaction is not yet included into a block.
+    async fn tx_finality(&self, tx_hash: TxHash) -> QueryResult<Option<BlockFinality>> {
+        let mut storage = self.tx_sender.pool.access_storage().await?;
+
+        if let Some(tx_receipt) = Self::tx_receipt(&mut storage, tx_hash).await? {
+            block_finality(&mut storage, tx_receipt.block_number as BlockNumber).await
+        } else {
+            Ok(None)
+        }
+    }
 }
 
 // Data transfer objects.
@@ -209,7 +234,8 @@ pub enum TxReceipt {
     /// The transaction is awaiting execution in the memorypool.
     Pending,
     /// The transaction has been executed, but the block containing this transaction has not
-    /// yet been committed.
+    /// yet been committed. This includes transactions of the pending block, which is persisted
+    /// by the server and survives restarts.
     Executed,
     /// The block which contains this transaction has been committed.
     Committed { block: BlockNumber },
@@ -263,6 +289,13 @@ impl Client {
             .await
     }
 
+    /// Gets the state of the L1 transactions of the block containing the transaction.
+    pub async fn tx_finality(&self, tx_hash: TxHash) -> Result<Option<BlockFinality>, ClientError> {
+        self.get(&format!("transactions/{}/finality", tx_hash.to_string()))
+            .send()
+            .await
+    }
+
     /// Gets transaction receipt by ID.
     pub async fn tx_receipt_by_id(
         &self,
@@ -312,6 +345,18 @@ async fn tx_data(
     Ok(Json(tx_data))
 }
 
+async fn tx_finality(
+    data: web::Data<ApiTransactionsData>,
+    web::Path(tx_hash): web::Path<TxHash>,
+) -> JsonResult<Option<BlockFinality>> {
+    let finality = data
+        .tx_finality(tx_hash)
+        .await
+        .map_err(ApiError::internal)?;
+
+    Ok(Json(finality))
+}
+
 async fn tx_receipt_by_id(
     data: web::Data<ApiTransactionsData>,
     web::Path((tx_hash, receipt_id)): web::Path<(TxHash, u32)>,
@@ -348,14 +393,29 @@ async fn tx_receipts(
     }
 }
 
+/// Returns the IP address of the request source. The forwarding headers are taken
+/// into account only for the requests sent by the trusted proxies.
+pub(super) fn source_ip(req: &HttpRequest, trusted_proxies: &[IpAddr]) -> Option<IpAddr> {
+    let peer = req.peer_addr()?.ip();
+    let header = |name: &str| {
+        req.headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+    };
+
+    Some(client_ip(peer, header, trusted_proxies))
+}
+
 async fn submit_tx(
     data: web::Data<ApiTransactionsData>,
+    req: HttpRequest,
     Json(body): Json<IncomingTx>,
     web::Query(query): web::Query<FastProcessingQuery>,
 ) -> JsonResult<TxHash> {
+    let client_ip = source_ip(&req, &data.tx_sender.trusted_proxies);
     let tx_hash = data
         .tx_sender
-        .submit_tx(body.tx, body.signature, query.fast_processing)
+        .submit_tx(body.tx, body.signature, query.fast_processing, client_ip)
         .await
         .map_err(ApiError::from)?;
 
@@ -364,8 +424,10 @@ async fn submit_tx(
 
 async fn submit_tx_batch(
     data: web::Data<ApiTransactionsData>,
+    req: HttpRequest,
     Json(body): Json<IncomingTxBatch>,
 ) -> JsonResult<Vec<TxHash>> {
+    let client_ip = source_ip(&req, &data.tx_sender.trusted_proxies);
     let txs = body
         .txs
         .into_iter()
@@ -377,7 +439,7 @@ async fn submit_tx_batch(
 
     let tx_hashes = data
         .tx_sender
-        .submit_txs_batch(txs, body.signatures)
+        .submit_txs_batch(txs, body.signatures, client_ip)
         .await
         .map_err(ApiError::from)?;
 
@@ -391,6 +453,7 @@ pub fn api_scope(tx_sender: TxSender) -> Scope {
         .data(data)
         .route("{tx_hash}", web::get().to(tx_status))
         .route("{tx_hash}/data", web::get().to(tx_data))
+        .route("{tx_hash}/finality", web::get().to(tx_finality))
         .route(
             "{tx_hash}/receipts/{receipt_id}",
             web::get().to(tx_receipt_by_id),
@@ -414,6 +477,7 @@ mod tests {
     use super::{super::test_utils::TestServerConfig, *};
     use crate::{
         // api_server::helpers::try_parse_tx_hash,
+        api_server::rate_limiter::SubmissionLimiter,
         core_api_client::CoreApiClient,
         fee_ticker::{Fee, OutputFeeType::Withdraw, TickerRequest},
         signature_checker::{VerifiedTx, VerifyTxSignatureRequest},
@@ -518,6 +582,7 @@ mod tests {
                     cfg.pool.clone(),
                     sign_verifier.clone(),
                     fee_ticker.clone(),
+                    SubmissionLimiter::new(&cfg.api_server_options),
                     &cfg.api_server_options,
                 ))
             });
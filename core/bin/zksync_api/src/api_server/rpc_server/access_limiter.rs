@@ -0,0 +1,192 @@
+//! Middleware enforcing the API keys access tiers of the JSON-RPC requests.
+//!
+//! API key is passed in the `X-Api-Key` HTTP header. Clients without an API key are
+//! identified by their IP address (see `client_addr`).
+
+// Built-in uses
+use std::{cell::Cell, net::IpAddr, sync::Arc};
+
+// External uses
+use jsonrpc_core::{
+    futures::future::{self, Either, Future},
+    middleware::NoopFuture,
+    Call, FutureOutput, Metadata, Middleware, Output,
+};
+use jsonrpc_http_server::hyper::{header::HeaderMap, Body, Request};
+use jsonrpc_pubsub::{PubSubMetadata, Session};
+use jsonrpc_ws_server::{ws, RequestContext};
+
+// Local uses
+use crate::api_server::{
+    client_addr::{client_ip, PEER_ADDR_HEADER},
+    rate_limiter::RequestLimiter,
+};
+
+const API_KEY_HEADER: &str = "x-api-key";
+
+thread_local! {
+    /// Client address of the WebSocket handshake processed by the server event loop thread.
+    static WS_HANDSHAKE_CLIENT_IP: Cell<Option<IpAddr>> = Cell::new(None);
+}
+
+/// Metadata of the JSON-RPC request, shared by the HTTP and WebSocket servers.
+#[derive(Clone, Default)]
+pub struct RequestMeta {
+    pub api_key: Option<String>,
+    pub client_ip: Option<IpAddr>,
+    /// Session of the WebSocket connection, used by the subscriptions.
+    pub session: Option<Arc<Session>>,
+}
+
+impl Metadata for RequestMeta {}
+
+impl PubSubMetadata for RequestMeta {
+    fn session(&self) -> Option<Arc<Session>> {
+        self.session.clone()
+    }
+}
+
+impl RequestMeta {
+    pub fn from_request(request: &Request<Body>, trusted_proxies: &[IpAddr]) -> Self {
+        Self::from_headers(request.headers(), trusted_proxies)
+    }
+
+    fn from_headers(headers: &HeaderMap, trusted_proxies: &[IpAddr]) -> Self {
+        let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+
+        let api_key = header(API_KEY_HEADER).map(String::from);
+        // Peer address is set by the proxy the server is only reachable through.
+        let client_ip = header(PEER_ADDR_HEADER)
+            .and_then(|peer| peer.parse().ok())
+            .map(|peer| client_ip(peer, header, trusted_proxies));
+
+        Self {
+            api_key,
+            client_ip,
+            session: None,
+        }
+    }
+
+    /// Request middleware of the WebSocket server, which remembers the client address of the handshake.
+    ///
+    /// WebSocket server doesn't pass the handshake request to the metadata extractor, but both
+    /// are invoked one after another by the event loop thread, so the address is passed through
+    /// the thread local and taken by `from_ws_context`.
+    pub fn ws_request_middleware(
+        request: &ws::Request,
+        trusted_proxies: &[IpAddr],
+    ) -> Option<ws::Response> {
+        let header = |name: &str| {
+            request
+                .header(name)
+                .and_then(|value| std::str::from_utf8(value).ok())
+        };
+        // Peer address is set by the proxy the server is only reachable through.
+        let client_ip = header(PEER_ADDR_HEADER)
+            .and_then(|peer| peer.parse().ok())
+            .map(|peer| client_ip(peer, header, trusted_proxies));
+        WS_HANDSHAKE_CLIENT_IP.with(|handshake_ip| handshake_ip.set(client_ip));
+
+        None
+    }
+
+    pub fn from_ws_context(context: &RequestContext) -> Self {
+        Self {
+            api_key: None,
+            client_ip: WS_HANDSHAKE_CLIENT_IP.with(Cell::take),
+            session: Some(Arc::new(Session::new(context.sender()))),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AccessLimiter {
+    limiter: RequestLimiter,
+}
+
+impl AccessLimiter {
+    pub fn new(limiter: RequestLimiter) -> Self {
+        Self { limiter }
+    }
+}
+
+impl Middleware<RequestMeta> for AccessLimiter {
+    type Future = NoopFuture;
+    type CallFuture = FutureOutput;
+
+    fn on_call<F, X>(&self, call: Call, meta: RequestMeta, next: F) -> Either<Self::CallFuture, X>
+    where
+        F: FnOnce(Call, RequestMeta) -> X + Send,
+        X: Future<Item = Option<Output>, Error = ()> + Send + 'static,
+    {
+        // Every call of the batch is checked separately, so the rejected
+        // calls don't affect the rest of the batch.
+        let request = match &call {
+            Call::MethodCall(call) => {
+                Some((call.method.as_str(), Some(call.id.clone()), call.jsonrpc))
+            }
+            Call::Notification(notification) => {
+                Some((notification.method.as_str(), None, notification.jsonrpc))
+            }
+            Call::Invalid { .. } => None,
+        };
+
+        if let Some((method, id, version)) = request {
+            if let Err(rejection) =
+                self.limiter
+                    .check(meta.api_key.as_deref(), meta.client_ip, method)
+            {
+                metrics::counter!("api.rpc.rejected_requests", 1, "reason" => rejection.as_str());
+
+                let output = id.map(|id| Output::from(Err(rejection.into()), id, version));
+                return Either::A(Box::new(future::ok(output)));
+            }
+        }
+
+        Either::B(next(call, meta))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonrpc_http_server::hyper::header::HeaderValue;
+
+    #[test]
+    fn request_meta_from_ws_handshake() {
+        let trusted_proxies = [IpAddr::from([10, 0, 0, 1])];
+        let mut request = ws::Request::default();
+        request
+            .headers_mut()
+            .push((PEER_ADDR_HEADER.into(), b"10.0.0.2".to_vec()));
+
+        RequestMeta::ws_request_middleware(&request, &trusted_proxies);
+        assert_eq!(
+            WS_HANDSHAKE_CLIENT_IP.with(Cell::take),
+            Some([10, 0, 0, 2].into())
+        );
+        // Address is taken by the extractor of the same handshake only.
+        assert!(WS_HANDSHAKE_CLIENT_IP.with(Cell::take).is_none());
+    }
+
+    #[test]
+    fn request_meta_from_headers() {
+        let trusted_proxies = [IpAddr::from([10, 0, 0, 1])];
+        let mut headers = HeaderMap::new();
+        assert!(RequestMeta::from_headers(&headers, &trusted_proxies)
+            .client_ip
+            .is_none());
+
+        // Forwarding headers of the untrusted peer are ignored.
+        headers.insert(PEER_ADDR_HEADER, HeaderValue::from_static("10.0.0.2"));
+        headers.insert("x-forwarded-for", HeaderValue::from_static("10.0.0.3"));
+        headers.insert(API_KEY_HEADER, HeaderValue::from_static("partner"));
+        let meta = RequestMeta::from_headers(&headers, &trusted_proxies);
+        assert_eq!(meta.api_key.as_deref(), Some("partner"));
+        assert_eq!(meta.client_ip, Some([10, 0, 0, 2].into()));
+
+        headers.insert(PEER_ADDR_HEADER, HeaderValue::from_static("10.0.0.1"));
+        let meta = RequestMeta::from_headers(&headers, &trusted_proxies);
+        assert_eq!(meta.client_ip, Some([10, 0, 0, 3].into()));
+    }
+}
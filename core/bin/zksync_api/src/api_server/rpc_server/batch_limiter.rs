@@ -0,0 +1,116 @@
+//! Middleware limiting the size of JSON-RPC batch requests.
+//!
+//! Requests within a batch are processed independently by `jsonrpc_core`, so a failure of
+//! one request is reported in its own response item without affecting the others.
+//! Batches exceeding the limit are rejected as a whole with a single error response.
+
+// External uses
+use jsonrpc_core::{
+    futures::future::{self, Either},
+    middleware::NoopCallFuture,
+    Error, ErrorCode, FutureResponse, Metadata, Middleware, Request, Response, Version,
+};
+
+#[derive(Debug, Clone, Copy)]
+pub struct BatchSizeLimiter {
+    max_batch_size: usize,
+}
+
+impl BatchSizeLimiter {
+    pub fn new(max_batch_size: usize) -> Self {
+        Self { max_batch_size }
+    }
+}
+
+impl<M: Metadata> Middleware<M> for BatchSizeLimiter {
+    type Future = FutureResponse;
+    type CallFuture = NoopCallFuture;
+
+    fn on_request<F, X>(&self, request: Request, meta: M, next: F) -> Either<Self::Future, X>
+    where
+        F: FnOnce(Request, M) -> X + Send,
+        X: future::Future<Item = Option<Response>, Error = ()> + Send + 'static,
+    {
+        if let Request::Batch(calls) = &request {
+            metrics::histogram!("api.rpc.batch_size", calls.len() as u64);
+
+            if calls.len() > self.max_batch_size {
+                let error = Error {
+                    code: ErrorCode::InvalidRequest,
+                    message: format!(
+                        "Batch contains {} requests, while the limit is {}",
+                        calls.len(),
+                        self.max_batch_size
+                    ),
+                    data: None,
+                };
+                let response = Response::from(error, Some(Version::V2));
+                return Either::A(Box::new(future::ok(Some(response))));
+            }
+        }
+
+        Either::B(next(request, meta))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonrpc_core::{MetaIoHandler, Params, Value};
+    use serde_json::json;
+
+    fn io_handler(max_batch_size: usize) -> MetaIoHandler<(), BatchSizeLimiter> {
+        let mut io = MetaIoHandler::with_middleware(BatchSizeLimiter::new(max_batch_size));
+        io.add_method("ping", |_: Params| Ok(Value::String("pong".into())));
+        io
+    }
+
+    fn batch(size: usize) -> String {
+        let calls: Vec<_> = (0..size)
+            .map(|id| json!({ "jsonrpc": "2.0", "method": "ping", "params": [], "id": id }))
+            .collect();
+        serde_json::to_string(&calls).unwrap()
+    }
+
+    /// Checks that the batches within the limit are processed.
+    #[test]
+    fn batch_within_limit() {
+        let io = io_handler(2);
+
+        let response = io.handle_request_sync(&batch(2), ()).unwrap();
+        let response: Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(response.as_array().unwrap().len(), 2);
+    }
+
+    /// Checks that the oversized batch is rejected with a single error.
+    #[test]
+    fn batch_exceeding_limit() {
+        let io = io_handler(2);
+
+        let response = io.handle_request_sync(&batch(3), ()).unwrap();
+        let response: Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(
+            response["error"]["code"],
+            json!(ErrorCode::InvalidRequest.code())
+        );
+    }
+
+    /// Checks that a failed request doesn't affect other requests of the batch.
+    #[test]
+    fn batch_items_are_isolated() {
+        let io = io_handler(2);
+
+        let request = json!([
+            { "jsonrpc": "2.0", "method": "ping", "params": [], "id": 0 },
+            { "jsonrpc": "2.0", "method": "unknown", "params": [], "id": 1 },
+        ]);
+        let response = io.handle_request_sync(&request.to_string(), ()).unwrap();
+        let response: Value = serde_json::from_str(&response).unwrap();
+
+        assert_eq!(response[0]["result"], json!("pong"));
+        assert_eq!(
+            response[1]["error"]["code"],
+            json!(ErrorCode::MethodNotFound.code())
+        );
+    }
+}
@@ -2,7 +2,10 @@
 use jsonrpc_core::ErrorCode;
 // Workspace uses
 // Local uses
-use crate::{api_server::tx_sender::SubmitError, tx_error::TxAddError};
+use crate::{
+    api_server::{rate_limiter::RequestRejection, tx_sender::SubmitError},
+    tx_error::TxAddError,
+};
 
 #[derive(Debug, Clone, Copy)]
 pub enum RpcErrorCodes {
@@ -15,11 +18,18 @@ pub enum RpcErrorCodes {
     EIP1271SignatureVerificationFail = 201,
     IncorrectEthSignature = 202,
     ChangePkNotAuthorized = 203,
+    EIP712SignaturesDisabled = 204,
 
     Other = 300,
     AccountCloseDisabled = 301,
     OperationsLimitReached = 302,
     UnsupportedFastProcessing = 303,
+    RateLimited = 304,
+    Unauthorized = 305,
+    MethodNotAllowed = 306,
+    TxAcceptancePaused = 307,
+    TokenDisabled = 308,
+    MaintenanceMode = 309,
 }
 
 impl From<TxAddError> for RpcErrorCodes {
@@ -33,12 +43,16 @@ impl From<TxAddError> for RpcErrorCodes {
             TxAddError::EIP1271SignatureVerificationFail => Self::EIP1271SignatureVerificationFail,
             TxAddError::IncorrectEthSignature => Self::IncorrectEthSignature,
             TxAddError::ChangePkNotAuthorized => Self::ChangePkNotAuthorized,
+            TxAddError::EIP712SignaturesDisabled => Self::EIP712SignaturesDisabled,
             TxAddError::Other => Self::Other,
             TxAddError::DbError => Self::Other,
             TxAddError::EmptyBatch => Self::Other,
             TxAddError::BatchTooBig => Self::Other,
             TxAddError::BatchWithdrawalsOverload => Self::Other,
             TxAddError::EthSignaturesLimitExceeded => Self::Other,
+            TxAddError::MempoolFull => Self::OperationsLimitReached,
+            TxAddError::AccountTxsLimitExceeded => Self::OperationsLimitReached,
+            TxAddError::ShuttingDown => Self::TxAcceptancePaused,
         }
     }
 }
@@ -80,6 +94,26 @@ impl From<SubmitError> for jsonrpc_core::Error {
                 message: inner.to_string(),
                 data: None,
             },
+            SubmitError::RateLimited { retry_after } => Self {
+                code: RpcErrorCodes::RateLimited.into(),
+                message: inner.to_string(),
+                data: Some(serde_json::json!({ "retryAfter": retry_after })),
+            },
+            SubmitError::TxAcceptancePaused => Self {
+                code: RpcErrorCodes::TxAcceptancePaused.into(),
+                message: inner.to_string(),
+                data: None,
+            },
+            SubmitError::MaintenanceMode => Self {
+                code: RpcErrorCodes::MaintenanceMode.into(),
+                message: inner.to_string(),
+                data: None,
+            },
+            SubmitError::TokenDisabled(_) => Self {
+                code: RpcErrorCodes::TokenDisabled.into(),
+                message: inner.to_string(),
+                data: None,
+            },
             SubmitError::CommunicationCoreServer(reason) => Self {
                 code: RpcErrorCodes::Other.into(),
                 message: "Error communicating core server".to_string(),
@@ -98,3 +132,27 @@ impl From<SubmitError> for jsonrpc_core::Error {
         }
     }
 }
+
+impl From<RequestRejection> for jsonrpc_core::Error {
+    fn from(inner: RequestRejection) -> Self {
+        let code = match inner {
+            RequestRejection::UnknownApiKey | RequestRejection::ApiKeyRequired => {
+                RpcErrorCodes::Unauthorized
+            }
+            RequestRejection::MethodNotAllowed => RpcErrorCodes::MethodNotAllowed,
+            RequestRejection::RateLimited { .. } => RpcErrorCodes::RateLimited,
+        };
+        let data = match inner {
+            RequestRejection::RateLimited { retry_after } => {
+                Some(serde_json::json!({ "retryAfter": retry_after }))
+            }
+            _ => None,
+        };
+
+        Self {
+            code: code.into(),
+            message: inner.to_string(),
+            data,
+        }
+    }
+}
@@ -6,8 +6,12 @@ use futures::{
     channel::{mpsc, oneshot},
     SinkExt,
 };
-use jsonrpc_core::{Error, IoHandler, MetaIoHandler, Metadata, Middleware, Result};
-use jsonrpc_http_server::ServerBuilder;
+use jsonrpc_core::{Error, MetaIoHandler, Middleware, Result};
+use jsonrpc_http_server::{
+    hyper::{Body, Request},
+    AccessControlAllowOrigin, DomainsValidation, ServerBuilder,
+};
+use web3::{transports::Http, Web3};
 
 // Workspace uses
 use zksync_config::{ApiServerOptions, ConfigurationOptions};
@@ -18,10 +22,14 @@ use zksync_storage::{
     },
     ConnectionPool, StorageProcessor,
 };
-use zksync_types::{tx::TxHash, Address, TokenLike, TxFeeTypes};
+use zksync_types::{
+    tx::{TxFailureTrace, TxHash},
+    Address, TokenLike, TxFeeTypes, H256,
+};
 
 // Local uses
 use crate::{
+    eth_checker::EthereumChecker,
     fee_ticker::{Fee, TickerRequest, TokenPriceRequestType},
     signature_checker::VerifyTxSignatureRequest,
     utils::shared_lru_cache::SharedLruCache,
@@ -29,14 +37,24 @@ use crate::{
 use bigdecimal::BigDecimal;
 use zksync_utils::panic_notify::ThreadPanicNotify;
 
+mod access_limiter;
+mod batch_limiter;
 pub mod error;
 mod rpc_impl;
 mod rpc_trait;
 pub mod types;
 
+pub use self::access_limiter::{AccessLimiter, RequestMeta};
+pub use self::batch_limiter::BatchSizeLimiter;
 pub use self::rpc_trait::Rpc;
 use self::types::*;
-use super::tx_sender::TxSender;
+use super::{
+    client_addr::{backend_address, start_rpc_proxy},
+    event_notify::EventNotifierRequest,
+    rate_limiter::{RequestLimiter, SubmissionLimiter},
+    tls::TlsConfig,
+    tx_sender::TxSender,
+};
 
 #[derive(Clone)]
 pub struct RpcApp {
@@ -48,8 +66,13 @@ pub struct RpcApp {
     cache_of_complete_withdrawal_tx_hashes: SharedLruCache<TxHash, String>,
 
     pub confirmations_for_eth_event: u64,
+    /// Whether the failure details of the transactions are served.
+    tx_trace_enabled: bool,
 
     tx_sender: TxSender,
+    event_sub_sender: mpsc::Sender<EventNotifierRequest>,
+    /// Looks up the priority operations not processed by the Ethereum watcher yet.
+    eth_checker: EthereumChecker<Http>,
 }
 
 impl RpcApp {
@@ -57,6 +80,8 @@ impl RpcApp {
         connection_pool: ConnectionPool,
         sign_verify_request_sender: mpsc::Sender<VerifyTxSignatureRequest>,
         ticker_request_sender: mpsc::Sender<TickerRequest>,
+        submission_limiter: SubmissionLimiter,
+        event_sub_sender: mpsc::Sender<EventNotifierRequest>,
         config_options: &ConfigurationOptions,
         api_server_options: &ApiServerOptions,
     ) -> Self {
@@ -70,9 +95,18 @@ impl RpcApp {
             connection_pool,
             sign_verify_request_sender,
             ticker_request_sender,
+            submission_limiter,
             api_server_options,
         );
 
+        let transport =
+            Http::new(&config_options.web3_url).expect("Failed to create web3 transport");
+        let eth_checker = EthereumChecker::new(
+            Web3::new(transport),
+            config_options.contract_eth_addr,
+            config_options.confirmations_for_eth_event,
+        );
+
         RpcApp {
             runtime_handle,
 
@@ -82,12 +116,15 @@ impl RpcApp {
             cache_of_complete_withdrawal_tx_hashes: SharedLruCache::new(api_requests_caches_size),
 
             confirmations_for_eth_event,
+            tx_trace_enabled: config_options.tx_trace_enabled,
 
             tx_sender,
+            event_sub_sender,
+            eth_checker,
         }
     }
 
-    pub fn extend<T: Metadata, S: Middleware<T>>(self, io: &mut MetaIoHandler<T, S>) {
+    pub fn extend<S: Middleware<RequestMeta>>(self, io: &mut MetaIoHandler<RequestMeta, S>) {
         io.extend_with(self.to_delegate())
     }
 }
@@ -179,6 +216,27 @@ impl RpcApp {
         Ok(res)
     }
 
+    /// Loads the acknowledgment of the deposit which is not executed yet.
+    async fn get_deposit_acknowledgment(
+        &self,
+        serial_id: u32,
+    ) -> Result<Option<DepositAcknowledgment>> {
+        let start = Instant::now();
+        let mut storage = self.access_storage().await?;
+        let acknowledgment = storage
+            .chain()
+            .operations_schema()
+            .get_priority_op_acknowledgment(u64::from(serial_id))
+            .await
+            .map_err(|err| {
+                vlog::warn!("Internal Server Error: '{}'; input: {}", err, serial_id);
+                Error::internal_error()
+            })?;
+
+        metrics::histogram!("api.rpc.get_deposit_acknowledgment", start.elapsed());
+        Ok(acknowledgment.map(DepositAcknowledgment::from))
+    }
+
     async fn get_block_info(&self, block_number: i64) -> Result<Option<BlockDetails>> {
         let start = Instant::now();
         let res = if let Some(block) = self.cache_of_blocks_info.get(&block_number) {
@@ -375,6 +433,128 @@ impl RpcApp {
         };
         Ok(res)
     }
+
+    async fn withdrawal_status(
+        &self,
+        withdrawal_hash: TxHash,
+    ) -> Result<Option<WithdrawalStatusResp>> {
+        let internal_error = |err: anyhow::Error| {
+            vlog::warn!(
+                "Internal Server Error: '{}'; input: {:?}",
+                err,
+                withdrawal_hash,
+            );
+            Error::internal_error()
+        };
+
+        let mut storage = self.access_storage().await?;
+        let withdrawal = storage
+            .withdrawals_schema()
+            .load_withdrawal(&withdrawal_hash)
+            .await
+            .map_err(internal_error)?;
+        let withdrawal = match withdrawal {
+            Some(withdrawal) => withdrawal,
+            None => return Ok(None),
+        };
+        let last_verified_block = storage
+            .chain()
+            .block_schema()
+            .get_last_verified_confirmed_block()
+            .await
+            .map_err(internal_error)?;
+
+        Ok(Some(WithdrawalStatusResp::new(
+            withdrawal,
+            last_verified_block,
+        )))
+    }
+
+    async fn tx_trace(&self, tx_hash: TxHash) -> Result<Option<TxFailureTrace>> {
+        // Deployments not recording the traces don't expose the method at all.
+        if !self.tx_trace_enabled {
+            return Err(Error::method_not_found());
+        }
+
+        let mut storage = self.access_storage().await?;
+        storage
+            .tx_traces_schema()
+            .load_failure_trace(&tx_hash)
+            .await
+            .map_err(|err| {
+                vlog::warn!("Internal Server Error: '{}'; input: {:?}", err, tx_hash);
+                Error::internal_error()
+            })
+    }
+
+    async fn claim_priority_ops(&self, eth_tx_hash: H256) -> Result<Vec<PriorityOpClaimResp>> {
+        let internal_error = |err: anyhow::Error| {
+            vlog::warn!("Internal Server Error: '{}'; input: {:?}", err, eth_tx_hash);
+            Error::internal_error()
+        };
+
+        let executed_ops = self
+            .access_storage()
+            .await?
+            .chain()
+            .operations_schema()
+            .get_executed_priority_operations_by_hash(eth_tx_hash.as_bytes())
+            .await
+            .map_err(internal_error)?;
+        let mut claims = Vec::with_capacity(executed_ops.len());
+        for executed_op in executed_ops {
+            let block = self.get_block_info(executed_op.block_number).await?;
+            claims.push(PriorityOpClaimResp {
+                serial_id: executed_op.priority_op_serialid as u64,
+                executed: true,
+                block: Some(BlockInfo {
+                    block_number: executed_op.block_number,
+                    committed: true,
+                    verified: block.map(|b| b.verified_at.is_some()).unwrap_or_default(),
+                }),
+                claim: None,
+            });
+        }
+
+        // Core checks the pending operations against the view of the Ethereum watcher.
+        let mut pending_claims = self
+            .tx_sender
+            .core_api_client
+            .claim_priority_ops(eth_tx_hash)
+            .await
+            .map_err(internal_error)?;
+        if claims.is_empty() && pending_claims.is_empty() {
+            // Operations the watcher hasn't processed yet are looked up on Ethereum here,
+            // so the requests don't make the watcher wait for the Ethereum node.
+            let ops = self
+                .eth_checker
+                .get_priority_ops_by_tx_hash(eth_tx_hash)
+                .await
+                .map_err(internal_error)?;
+            if !ops.is_empty() {
+                pending_claims = self
+                    .tx_sender
+                    .core_api_client
+                    .estimate_priority_ops(ops)
+                    .await
+                    .map_err(internal_error)?;
+            }
+        }
+
+        // Operation may be executed before it's removed from the queue of the watcher.
+        pending_claims.retain(|claim| {
+            claims
+                .iter()
+                .all(|executed| executed.serial_id != claim.op.serial_id)
+        });
+        claims.extend(pending_claims.into_iter().map(|claim| PriorityOpClaimResp {
+            serial_id: claim.op.serial_id,
+            executed: false,
+            block: None,
+            claim: Some(claim),
+        }));
+        Ok(claims)
+    }
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -382,29 +562,56 @@ pub fn start_rpc_server(
     connection_pool: ConnectionPool,
     sign_verify_request_sender: mpsc::Sender<VerifyTxSignatureRequest>,
     ticker_request_sender: mpsc::Sender<TickerRequest>,
+    submission_limiter: SubmissionLimiter,
+    event_sub_sender: mpsc::Sender<EventNotifierRequest>,
     panic_notify: mpsc::Sender<bool>,
     config_options: ConfigurationOptions,
     api_server_options: ApiServerOptions,
+    tls: Option<TlsConfig>,
 ) {
     let addr = api_server_options.json_rpc_http_server_address;
+    let cors_allowed_origins = api_server_options.cors_allowed_origins.clone();
+    let batch_limiter = BatchSizeLimiter::new(api_server_options.max_rpc_batch_size);
+    let access_limiter = AccessLimiter::new(RequestLimiter::new(&api_server_options));
+    let trusted_proxies = api_server_options.trusted_proxies.clone();
 
     let rpc_app = RpcApp::new(
         connection_pool,
         sign_verify_request_sender,
         ticker_request_sender,
+        submission_limiter,
+        event_sub_sender,
         &config_options,
         &api_server_options,
     );
     std::thread::spawn(move || {
-        let _panic_sentinel = ThreadPanicNotify(panic_notify);
-        let mut io = IoHandler::new();
+        let _panic_sentinel = ThreadPanicNotify(panic_notify.clone());
+        let mut io = MetaIoHandler::with_middleware((batch_limiter, access_limiter));
         rpc_app.extend(&mut io);
 
-        let server = ServerBuilder::new(io)
+        let meta_extractor =
+            move |request: &Request<Body>| RequestMeta::from_request(request, &trusted_proxies);
+        let mut builder = ServerBuilder::with_meta_extractor(io, meta_extractor)
             .request_middleware(super::loggers::http_rpc::request_middleware)
-            .threads(super::THREADS_PER_SERVER)
-            .start_http(&addr)
-            .unwrap();
+            .threads(super::THREADS_PER_SERVER);
+        if !cors_allowed_origins.is_empty() {
+            builder = builder.cors(DomainsValidation::AllowOnly(
+                cors_allowed_origins
+                    .into_iter()
+                    .map(AccessControlAllowOrigin::from)
+                    .collect(),
+            ));
+        }
+
+        // The server is proxied, so the addresses of the clients are known.
+        let server = builder.start_http(&backend_address()).unwrap();
+        start_rpc_proxy(
+            "rpc-proxy",
+            tls.as_ref(),
+            addr,
+            *server.address(),
+            panic_notify,
+        );
         server.wait();
     });
 }
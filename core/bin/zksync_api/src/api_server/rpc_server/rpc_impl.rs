@@ -1,24 +1,35 @@
 use std::collections::HashMap;
-use std::time::Instant;
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
 // External uses
+use futures::{channel::oneshot, SinkExt};
 use jsonrpc_core::{Error, Result};
 use num::BigUint;
 // Workspace uses
 use zksync_types::{
     helpers::closest_packable_fee_amount,
-    tx::{TxEthSignature, TxHash},
-    Address, Token, TokenLike, TxFeeTypes, ZkSyncTx,
+    tx::{TxEthSignature, TxFailureTrace, TxHash},
+    ActionType, Address, Token, TokenLike, TxFeeTypes, ZkSyncTx, H256,
 };
 
 // Local uses
 use crate::{
-    api_server::tx_sender::SubmitError,
-    fee_ticker::{BatchFee, Fee, TokenPriceRequestType},
+    api_server::{
+        event_notify::{EventNotifierRequest, EventSubscribeRequest},
+        tx_sender::SubmitError,
+        v1::accounts::BalanceBreakdown,
+    },
+    fee_ticker::{BatchFee, BatchFeeQuote, Fee, TokenPriceRequestType},
 };
 use bigdecimal::BigDecimal;
 
 use super::{error::*, types::*, RpcApp};
 
+/// Time to wait for the transaction if the timeout isn't specified in the request.
+const DEFAULT_WAIT_TIMEOUT: Duration = Duration::from_secs(30);
+/// Maximum time the request may wait for the transaction.
+const MAX_WAIT_TIMEOUT: Duration = Duration::from_secs(60);
+
 impl RpcApp {
     pub async fn _impl_account_info(self, address: Address) -> Result<AccountInfoResp> {
         let start = Instant::now();
@@ -30,18 +41,26 @@ impl RpcApp {
             DepositingAccountBalances::from_pending_ops(depositing_ops, &self.tx_sender.tokens)
                 .await?;
 
-        log::trace!(
+        tracing::trace!(
             "account_info: address {}, total request processing {}ms",
             &address,
             start.elapsed().as_millis()
         );
 
+        let balances = BalanceBreakdown::from_balances(
+            &account_state.committed.balances,
+            &account_state.verified.balances,
+        )
+        .into_iter()
+        .collect();
+
         metrics::histogram!("api.rpc.account_info", start.elapsed());
         Ok(AccountInfoResp {
             address,
             id: account_state.account_id,
             committed: account_state.committed,
             verified: account_state.verified,
+            balances,
             depositing,
         })
     }
@@ -58,11 +77,13 @@ impl RpcApp {
                     committed: true,
                     verified: block.map(|b| b.verified_at.is_some()).unwrap_or_default(),
                 }),
+                acknowledgment: None,
             }
         } else {
             ETHOpInfoResp {
                 executed: false,
                 block: None,
+                acknowledgment: self.get_deposit_acknowledgment(serial_id).await?,
             }
         };
 
@@ -99,16 +120,52 @@ impl RpcApp {
         })
     }
 
+    pub async fn _impl_wait_for_tx(
+        self,
+        hash: TxHash,
+        action: ActionType,
+        timeout_ms: Option<u64>,
+    ) -> Result<TransactionInfoResp> {
+        let start = Instant::now();
+        let timeout = timeout_ms
+            .map(Duration::from_millis)
+            .unwrap_or(DEFAULT_WAIT_TIMEOUT)
+            .min(MAX_WAIT_TIMEOUT);
+
+        let (response, receiver) = oneshot::channel();
+        self.event_sub_sender
+            .clone()
+            .send(EventNotifierRequest::Sub(
+                EventSubscribeRequest::WaitTransaction {
+                    hash,
+                    action,
+                    response,
+                },
+            ))
+            .await
+            .map_err(|_| Error::internal_error())?;
+
+        let result = match tokio::time::timeout(timeout, receiver).await {
+            Ok(Ok(tx_info)) => Ok(tx_info),
+            // Transaction didn't reach the requested state in time, so its current state is reported.
+            _ => self._impl_tx_info(hash).await,
+        };
+
+        metrics::histogram!("api.rpc.wait_for_tx", start.elapsed());
+        result
+    }
+
     pub async fn _impl_tx_submit(
         self,
         tx: Box<ZkSyncTx>,
         signature: Box<Option<TxEthSignature>>,
         fast_processing: Option<bool>,
+        client_ip: Option<IpAddr>,
     ) -> Result<TxHash> {
         let start = Instant::now();
         let result = self
             .tx_sender
-            .submit_tx(*tx, *signature, fast_processing)
+            .submit_tx(*tx, *signature, fast_processing, client_ip)
             .await
             .map_err(Error::from);
         metrics::histogram!("api.rpc.tx_submit", start.elapsed());
@@ -119,11 +176,12 @@ impl RpcApp {
         self,
         txs: Vec<TxWithSignature>,
         eth_signatures: Vec<TxEthSignature>,
+        client_ip: Option<IpAddr>,
     ) -> Result<Vec<TxHash>> {
         let start = Instant::now();
         let result = self
             .tx_sender
-            .submit_txs_batch(txs, eth_signatures)
+            .submit_txs_batch(txs, eth_signatures, client_ip)
             .await
             .map_err(Error::from);
         metrics::histogram!("api.rpc.submit_txs_batch", start.elapsed());
@@ -134,7 +192,7 @@ impl RpcApp {
         let start = Instant::now();
         let mut storage = self.access_storage().await?;
         let config = storage.config_schema().load_config().await.map_err(|err| {
-            log::warn!(
+            tracing::warn!(
                 "[{}:{}:{}] Internal Server Error: '{}'; input: N/A",
                 file!(),
                 line!(),
@@ -164,7 +222,7 @@ impl RpcApp {
         let start = Instant::now();
         let mut storage = self.access_storage().await?;
         let mut tokens = storage.tokens_schema().load_tokens().await.map_err(|err| {
-            log::warn!("Internal Server Error: '{}'; input: N/A", err);
+            tracing::warn!("Internal Server Error: '{}'; input: N/A", err);
             Error::internal_error()
         })?;
 
@@ -241,17 +299,34 @@ impl RpcApp {
         }
 
         let mut total_fee = BigUint::from(0u32);
+        let mut is_price_stale = false;
 
         for (tx_type, address) in tx_types.iter().zip(addresses.iter()) {
             let ticker = ticker.clone();
             let fee = Self::ticker_request(ticker, *tx_type, *address, token.clone()).await?;
             total_fee += fee.total_fee;
+            is_price_stale |= fee.is_price_stale;
         }
         // Sum of transactions can be unpackable
         total_fee = closest_packable_fee_amount(&total_fee);
 
         metrics::histogram!("api.rpc.get_txs_batch_fee_in_wei", start.elapsed());
-        Ok(BatchFee { total_fee })
+        Ok(BatchFee {
+            total_fee,
+            is_price_stale,
+        })
+    }
+
+    pub async fn _impl_get_batch_fee_quote(self, txs: Vec<BatchFeeTx>) -> Result<BatchFeeQuote> {
+        let start = Instant::now();
+        let txs: Vec<_> = txs
+            .into_iter()
+            .map(|tx| (tx.tx_type, tx.token, tx.address))
+            .collect();
+
+        let result = self.tx_sender.batch_fee_quote(&txs).await;
+        metrics::histogram!("api.rpc.get_batch_fee_quote", start.elapsed());
+        result.map_err(Error::from)
     }
 
     pub async fn _impl_get_token_price(self, token: TokenLike) -> Result<BigDecimal> {
@@ -275,4 +350,31 @@ impl RpcApp {
         metrics::histogram!("api.rpc.get_eth_tx_for_withdrawal", start.elapsed());
         result
     }
+
+    pub async fn _impl_withdrawal_status(
+        self,
+        withdrawal_hash: TxHash,
+    ) -> Result<Option<WithdrawalStatusResp>> {
+        let start = Instant::now();
+        let result = self.withdrawal_status(withdrawal_hash).await;
+        metrics::histogram!("api.rpc.withdrawal_status", start.elapsed());
+        result
+    }
+
+    pub async fn _impl_tx_trace(self, tx_hash: TxHash) -> Result<Option<TxFailureTrace>> {
+        let start = Instant::now();
+        let result = self.tx_trace(tx_hash).await;
+        metrics::histogram!("api.rpc.tx_trace", start.elapsed());
+        result
+    }
+
+    pub async fn _impl_claim_priority_op(
+        self,
+        eth_tx_hash: H256,
+    ) -> Result<Vec<PriorityOpClaimResp>> {
+        let start = Instant::now();
+        let result = self.claim_priority_ops(eth_tx_hash).await;
+        metrics::histogram!("api.rpc.claim_priority_op", start.elapsed());
+        result
+    }
 }
@@ -5,20 +5,22 @@ use jsonrpc_core::Error;
 use jsonrpc_derive::rpc;
 // Workspace uses
 use zksync_types::{
-    tx::{TxEthSignature, TxHash},
-    Address, Token, TokenLike, TxFeeTypes, ZkSyncTx,
+    tx::{TxEthSignature, TxFailureTrace, TxHash},
+    ActionType, Address, Token, TokenLike, TxFeeTypes, ZkSyncTx, H256,
 };
 
 // Local uses
-use crate::fee_ticker::{BatchFee, Fee};
+use crate::fee_ticker::{BatchFee, BatchFeeQuote, Fee};
 use bigdecimal::BigDecimal;
 
-use super::{types::*, RpcApp};
+use super::{types::*, RequestMeta, RpcApp};
 
 pub type FutureResp<T> = Box<dyn futures01::Future<Item = T, Error = Error> + Send>;
 
 #[rpc]
 pub trait Rpc {
+    type Metadata;
+
     #[rpc(name = "account_info", returns = "AccountInfoResp")]
     fn account_info(&self, addr: Address) -> FutureResp<AccountInfoResp>;
 
@@ -28,17 +30,29 @@ pub trait Rpc {
     #[rpc(name = "tx_info", returns = "ETHOpInfoResp")]
     fn tx_info(&self, hash: TxHash) -> FutureResp<TransactionInfoResp>;
 
-    #[rpc(name = "tx_submit", returns = "TxHash")]
+    /// Waits until the transaction reaches the given state or the timeout expires,
+    /// then returns the transaction info.
+    #[rpc(name = "wait_for_tx", returns = "TransactionInfoResp")]
+    fn wait_for_tx(
+        &self,
+        hash: TxHash,
+        action_type: ActionType,
+        timeout_ms: Option<u64>,
+    ) -> FutureResp<TransactionInfoResp>;
+
+    #[rpc(meta, name = "tx_submit", returns = "TxHash")]
     fn tx_submit(
         &self,
+        meta: Self::Metadata,
         tx: Box<ZkSyncTx>,
         signature: Box<Option<TxEthSignature>>,
         fast_processing: Option<bool>,
     ) -> FutureResp<TxHash>;
 
-    #[rpc(name = "submit_txs_batch", returns = "Vec<TxHash>")]
+    #[rpc(meta, name = "submit_txs_batch", returns = "Vec<TxHash>")]
     fn submit_txs_batch(
         &self,
+        meta: Self::Metadata,
         txs: Vec<TxWithSignature>,
         eth_signatures: Vec<TxEthSignature>,
     ) -> FutureResp<Vec<TxHash>>;
@@ -66,6 +80,9 @@ pub trait Rpc {
         token_like: TokenLike,
     ) -> FutureResp<BatchFee>;
 
+    #[rpc(name = "get_batch_fee_quote", returns = "BatchFeeQuote")]
+    fn get_batch_fee_quote(&self, txs: Vec<BatchFeeTx>) -> FutureResp<BatchFeeQuote>;
+
     #[rpc(name = "get_token_price", returns = "BigDecimal")]
     fn get_token_price(&self, token_like: TokenLike) -> FutureResp<BigDecimal>;
 
@@ -74,9 +91,30 @@ pub trait Rpc {
 
     #[rpc(name = "get_eth_tx_for_withdrawal", returns = "Option<String>")]
     fn get_eth_tx_for_withdrawal(&self, withdrawal_hash: TxHash) -> FutureResp<Option<String>>;
+
+    /// Returns the stage of the withdrawal, along with the hash of the Ethereum transaction
+    /// transferring the funds once the withdrawal is completed.
+    #[rpc(name = "withdrawal_status", returns = "Option<WithdrawalStatusResp>")]
+    fn withdrawal_status(
+        &self,
+        withdrawal_hash: TxHash,
+    ) -> FutureResp<Option<WithdrawalStatusResp>>;
+
+    /// Returns the details of the transaction failure: the failed check along with the checked
+    /// nonce, balance and signature. Available only if the transaction traces are enabled.
+    #[rpc(name = "tx_trace", returns = "Option<TxFailureTrace>")]
+    fn tx_trace(&self, tx_hash: TxHash) -> FutureResp<Option<TxFailureTrace>>;
+
+    /// Claims all the priority operations requested by the Ethereum transaction, returning their
+    /// positions in the priority queue and the expected time of their inclusion. Operations not
+    /// processed by the server yet are verified against Ethereum directly.
+    #[rpc(name = "claim_priority_op", returns = "Vec<PriorityOpClaimResp>")]
+    fn claim_priority_op(&self, eth_tx_hash: H256) -> FutureResp<Vec<PriorityOpClaimResp>>;
 }
 
 impl Rpc for RpcApp {
+    type Metadata = RequestMeta;
+
     fn account_info(&self, addr: Address) -> FutureResp<AccountInfoResp> {
         let handle = self.runtime_handle.clone();
         let self_ = self.clone();
@@ -103,8 +141,26 @@ impl Rpc for RpcApp {
         Box::new(resp.boxed().compat())
     }
 
+    fn wait_for_tx(
+        &self,
+        hash: TxHash,
+        action_type: ActionType,
+        timeout_ms: Option<u64>,
+    ) -> FutureResp<TransactionInfoResp> {
+        let handle = self.runtime_handle.clone();
+        let self_ = self.clone();
+        let resp = async move {
+            handle
+                .spawn(self_._impl_wait_for_tx(hash, action_type, timeout_ms))
+                .await
+                .unwrap()
+        };
+        Box::new(resp.boxed().compat())
+    }
+
     fn tx_submit(
         &self,
+        meta: RequestMeta,
         tx: Box<ZkSyncTx>,
         signature: Box<Option<TxEthSignature>>,
         fast_processing: Option<bool>,
@@ -113,7 +169,7 @@ impl Rpc for RpcApp {
         let self_ = self.clone();
         let resp = async move {
             handle
-                .spawn(self_._impl_tx_submit(tx, signature, fast_processing))
+                .spawn(self_._impl_tx_submit(tx, signature, fast_processing, meta.client_ip))
                 .await
                 .unwrap()
         };
@@ -122,6 +178,7 @@ impl Rpc for RpcApp {
 
     fn submit_txs_batch(
         &self,
+        meta: RequestMeta,
         txs: Vec<TxWithSignature>,
         eth_signatures: Vec<TxEthSignature>,
     ) -> FutureResp<Vec<TxHash>> {
@@ -129,7 +186,7 @@ impl Rpc for RpcApp {
         let self_ = self.clone();
         let resp = async move {
             handle
-                .spawn(self_._impl_submit_txs_batch(txs, eth_signatures))
+                .spawn(self_._impl_submit_txs_batch(txs, eth_signatures, meta.client_ip))
                 .await
                 .unwrap()
         };
@@ -184,6 +241,18 @@ impl Rpc for RpcApp {
         Box::new(resp.boxed().compat())
     }
 
+    fn get_batch_fee_quote(&self, txs: Vec<BatchFeeTx>) -> FutureResp<BatchFeeQuote> {
+        let handle = self.runtime_handle.clone();
+        let self_ = self.clone();
+        let resp = async move {
+            handle
+                .spawn(self_._impl_get_batch_fee_quote(txs))
+                .await
+                .unwrap()
+        };
+        Box::new(resp.boxed().compat())
+    }
+
     fn get_token_price(&self, token_like: TokenLike) -> FutureResp<BigDecimal> {
         let handle = self.runtime_handle.clone();
         let self_ = self.clone();
@@ -219,4 +288,38 @@ impl Rpc for RpcApp {
         };
         Box::new(resp.boxed().compat())
     }
+
+    fn withdrawal_status(
+        &self,
+        withdrawal_hash: TxHash,
+    ) -> FutureResp<Option<WithdrawalStatusResp>> {
+        let handle = self.runtime_handle.clone();
+        let self_ = self.clone();
+        let resp = async move {
+            handle
+                .spawn(self_._impl_withdrawal_status(withdrawal_hash))
+                .await
+                .unwrap()
+        };
+        Box::new(resp.boxed().compat())
+    }
+
+    fn tx_trace(&self, tx_hash: TxHash) -> FutureResp<Option<TxFailureTrace>> {
+        let handle = self.runtime_handle.clone();
+        let self_ = self.clone();
+        let resp = async move { handle.spawn(self_._impl_tx_trace(tx_hash)).await.unwrap() };
+        Box::new(resp.boxed().compat())
+    }
+
+    fn claim_priority_op(&self, eth_tx_hash: H256) -> FutureResp<Vec<PriorityOpClaimResp>> {
+        let handle = self.runtime_handle.clone();
+        let self_ = self.clone();
+        let resp = async move {
+            handle
+                .spawn(self_._impl_claim_priority_op(eth_tx_hash))
+                .await
+                .unwrap()
+        };
+        Box::new(resp.boxed().compat())
+    }
 }
@@ -1,16 +1,25 @@
 use std::collections::HashMap;
 // External uses
+use chrono::{DateTime, Utc};
 use jsonrpc_core::{Error, Result};
 use num::{BigUint, ToPrimitive};
 use serde::{Deserialize, Serialize};
 // Workspace uses
+use zksync_storage::{
+    chain::operations::records::StoredPriorityOpAcknowledgment,
+    withdrawals::records::StoredWithdrawal,
+};
 use zksync_types::{
-    tx::TxEthSignature, Account, AccountId, Address, Nonce, PriorityOp, PubKeyHash,
-    ZkSyncPriorityOp, ZkSyncTx,
+    tx::{TxEthSignature, TxHash},
+    Account, AccountId, Address, BlockNumber, Nonce, PriorityOp, PriorityOpClaim, PubKeyHash,
+    TokenId, TokenLike, TxFeeTypes, ZkSyncPriorityOp, ZkSyncTx,
 };
-use zksync_utils::{BigUintSerdeAsRadix10Str, BigUintSerdeWrapper};
+use zksync_utils::{big_decimal_to_ratio, BigUintSerdeAsRadix10Str, BigUintSerdeWrapper};
 // Local uses
-use crate::{api_server::v1::accounts::AccountState, utils::token_db_cache::TokenDBCache};
+use crate::{
+    api_server::v1::accounts::{AccountState, BalanceBreakdown},
+    utils::token_db_cache::TokenDBCache,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -19,6 +28,15 @@ pub struct TxWithSignature {
     pub signature: Option<TxEthSignature>,
 }
 
+/// Transaction of the batch to quote the fee for.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchFeeTx {
+    pub tx_type: TxFeeTypes,
+    pub token: TokenLike,
+    pub address: Address,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct ResponseAccountState {
@@ -113,6 +131,8 @@ pub struct AccountInfoResp {
     pub depositing: DepositingAccountBalances,
     pub committed: ResponseAccountState,
     pub verified: ResponseAccountState,
+    /// Balances of the tokens present in either of the states, split by finality.
+    pub balances: HashMap<String, BalanceBreakdown>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -137,6 +157,165 @@ pub struct TransactionInfoResp {
 pub struct ETHOpInfoResp {
     pub executed: bool,
     pub block: Option<BlockInfo>,
+    /// Acknowledgment of the deposit which is not executed yet.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acknowledgment: Option<DepositAcknowledgment>,
+}
+
+/// Priority operation claimed by the hash of its Ethereum transaction.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PriorityOpClaimResp {
+    pub serial_id: u64,
+    pub executed: bool,
+    pub block: Option<BlockInfo>,
+    /// Position in the priority queue and the expected inclusion of the operation
+    /// which is not executed yet.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub claim: Option<PriorityOpClaim>,
+}
+
+/// Deposit seen on Ethereum but not executed yet, along with the estimated time
+/// when the deposited funds become usable.
+///
+/// The estimation is made once the deposit is seen, and is based on the amount
+/// of confirmations left, the cadence of Ethereum blocks and the queue of priority operations.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DepositAcknowledgment {
+    pub serial_id: u64,
+    pub eth_tx_hash: String,
+    pub eth_block: u64,
+    pub token: TokenId,
+    pub amount: BigUintSerdeWrapper,
+    pub estimated_usable_at: DateTime<Utc>,
+}
+
+impl From<StoredPriorityOpAcknowledgment> for DepositAcknowledgment {
+    fn from(stored: StoredPriorityOpAcknowledgment) -> Self {
+        let amount = big_decimal_to_ratio(&stored.amount)
+            .expect("Stored deposit amount is not an integer")
+            .to_integer();
+
+        Self {
+            serial_id: stored.serial_id as u64,
+            eth_tx_hash: hex::encode(&stored.eth_hash),
+            eth_block: stored.eth_block as u64,
+            token: stored.token as TokenId,
+            amount: amount.into(),
+            estimated_usable_at: stored.estimated_usable_at,
+        }
+    }
+}
+
+/// Stage of the withdrawal processing.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub enum WithdrawalStage {
+    /// Withdrawal is executed in L2 and included into a block.
+    Committed,
+    /// Block containing the withdrawal is verified.
+    Verified,
+    /// Block is executed on Ethereum, so the funds are transferred to the recipient in L1.
+    Completed,
+    /// Block is executed on Ethereum, but the transfer to the recipient failed, so the funds
+    /// are stored in the pending balance of the recipient in the contract and have to be
+    /// withdrawn manually.
+    PendingBalance,
+}
+
+/// Progress of the withdrawal from its L2 block until the transfer of the funds in L1.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WithdrawalStatusResp {
+    pub tx_hash: TxHash,
+    pub block_number: BlockNumber,
+    pub stage: WithdrawalStage,
+    pub to: Address,
+    pub token: TokenId,
+    pub amount: BigUintSerdeWrapper,
+    /// Hash of the Ethereum transaction executing the block, once the withdrawal is completed
+    /// or stored in the pending balance.
+    pub eth_tx_hash: Option<String>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl WithdrawalStatusResp {
+    pub fn new(stored: StoredWithdrawal, last_verified_block: BlockNumber) -> Self {
+        let block_number = stored.block_number as BlockNumber;
+        let stage = if stored.pending_balance {
+            WithdrawalStage::PendingBalance
+        } else if stored.execute_tx_hash.is_some() {
+            WithdrawalStage::Completed
+        } else if block_number <= last_verified_block {
+            WithdrawalStage::Verified
+        } else {
+            WithdrawalStage::Committed
+        };
+        let amount = big_decimal_to_ratio(&stored.amount)
+            .expect("Stored withdrawal amount is not an integer")
+            .to_integer();
+
+        Self {
+            tx_hash: TxHash::from_slice(&stored.tx_hash).expect("Incorrect stored tx hash"),
+            block_number,
+            stage,
+            to: Address::from_slice(&stored.to_address),
+            token: stored.token as TokenId,
+            amount: amount.into(),
+            eth_tx_hash: stored
+                .execute_tx_hash
+                .map(|hash| format!("0x{}", hex::encode(&hash))),
+            completed_at: stored.executed_at,
+        }
+    }
+}
+
+/// Stage of the transaction processing, reported to the `tx_status` subscribers.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub enum TxStatus {
+    /// Transaction is accepted and waits in the mempool.
+    Queued,
+    /// Transaction is executed and included into a block.
+    Committed,
+    /// Block containing the transaction is verified.
+    Verified,
+    /// Transaction execution failed.
+    Rejected,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TxStatusEvent {
+    pub status: TxStatus,
+    pub block_number: Option<BlockNumber>,
+    pub fail_reason: Option<String>,
+}
+
+/// Event reported to the `account_events` subscribers.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum AccountEvent {
+    /// Account state was changed by the committed (or verified) block.
+    #[serde(rename_all = "camelCase")]
+    BalanceChanged {
+        block_number: BlockNumber,
+        verified: bool,
+        state: ResponseAccountState,
+    },
+    /// Deposit to the account was seen on Ethereum, but is not executed yet.
+    DepositAcknowledged(DepositAcknowledgment),
+    /// Deposit to the account was executed.
+    #[serde(rename_all = "camelCase")]
+    DepositReceived {
+        serial_id: u64,
+        block_number: BlockNumber,
+        token: TokenId,
+        amount: BigUintSerdeWrapper,
+    },
+    /// Withdrawal from the account was completed, i.e. the funds are transferred in L1.
+    WithdrawalCompleted(WithdrawalStatusResp),
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -1,13 +1,11 @@
 #![allow(clippy::needless_return)]
 
-// Built-in deps
-use std::sync::Arc;
 // External uses
 use futures::channel::mpsc;
 use jsonrpc_core::{MetaIoHandler, Result};
 use jsonrpc_derive::rpc;
-use jsonrpc_pubsub::{typed::Subscriber, PubSubHandler, Session, SubscriptionId};
-use jsonrpc_ws_server::RequestContext;
+use jsonrpc_pubsub::{typed::Subscriber, PubSubHandler, SubscriptionId};
+use jsonrpc_ws_server::{ws, DomainsValidation, Origin, RequestContext};
 // Workspace uses
 use zksync_config::{ApiServerOptions, ConfigurationOptions};
 use zksync_storage::ConnectionPool;
@@ -15,8 +13,14 @@ use zksync_types::{tx::TxHash, ActionType, Address};
 // Local uses
 use crate::fee_ticker::TickerRequest;
 use crate::{
-    api_server::event_notify::{start_sub_notifier, EventNotifierRequest, EventSubscribeRequest},
-    api_server::rpc_server::types::{ETHOpInfoResp, ResponseAccountState, TransactionInfoResp},
+    api_server::client_addr::{backend_address, start_rpc_proxy},
+    api_server::event_notify::{EventNotifierRequest, EventSubscribeRequest},
+    api_server::rate_limiter::SubmissionLimiter,
+    api_server::rpc_server::types::{
+        AccountEvent, ETHOpInfoResp, ResponseAccountState, TransactionInfoResp, TxStatusEvent,
+    },
+    api_server::rpc_server::{BatchSizeLimiter, RequestMeta},
+    api_server::tls::TlsConfig,
     signature_checker::VerifyTxSignatureRequest,
 };
 use zksync_utils::panic_notify::ThreadPanicNotify;
@@ -79,10 +83,56 @@ pub trait RpcPubSub {
         meta: Option<Self::Metadata>,
         subscription: SubscriptionId,
     ) -> Result<bool>;
+
+    #[pubsub(
+        subscription = "tx_status",
+        subscribe,
+        name = "tx_status_subscribe",
+        alias("tx_status_sub")
+    )]
+    fn subscribe_tx_status(
+        &self,
+        meta: Self::Metadata,
+        subscriber: Subscriber<TxStatusEvent>,
+        hash: TxHash,
+    );
+    #[pubsub(
+        subscription = "tx_status",
+        unsubscribe,
+        name = "tx_status_unsubscribe"
+    )]
+    fn unsubscribe_tx_status(
+        &self,
+        meta: Option<Self::Metadata>,
+        subscription: SubscriptionId,
+    ) -> Result<bool>;
+
+    #[pubsub(
+        subscription = "account_events",
+        subscribe,
+        name = "account_events_subscribe",
+        alias("account_events_sub")
+    )]
+    fn subscribe_account_events(
+        &self,
+        meta: Self::Metadata,
+        subscriber: Subscriber<AccountEvent>,
+        addr: Address,
+    );
+    #[pubsub(
+        subscription = "account_events",
+        unsubscribe,
+        name = "account_events_unsubscribe"
+    )]
+    fn unsubscribe_account_events(
+        &self,
+        meta: Option<Self::Metadata>,
+        subscription: SubscriptionId,
+    ) -> Result<bool>;
 }
 
 impl RpcPubSub for RpcSubApp {
-    type Metadata = Arc<Session>;
+    type Metadata = RequestMeta;
 
     // subscribe - sub id, sink
     // unsub - sub id
@@ -167,6 +217,62 @@ impl RpcPubSub for RpcSubApp {
             .unwrap_or_default();
         Ok(true)
     }
+
+    fn subscribe_tx_status(
+        &self,
+        _meta: Self::Metadata,
+        subscriber: Subscriber<TxStatusEvent>,
+        hash: TxHash,
+    ) {
+        self.event_sub_sender
+            .clone()
+            .try_send(EventNotifierRequest::Sub(EventSubscribeRequest::TxStatus {
+                hash,
+                subscriber,
+            }))
+            .unwrap_or_default();
+    }
+
+    fn unsubscribe_tx_status(
+        &self,
+        _meta: Option<Self::Metadata>,
+        id: SubscriptionId,
+    ) -> Result<bool> {
+        self.event_sub_sender
+            .clone()
+            .try_send(EventNotifierRequest::Unsub(id))
+            .unwrap_or_default();
+        Ok(true)
+    }
+
+    fn subscribe_account_events(
+        &self,
+        _meta: Self::Metadata,
+        subscriber: Subscriber<AccountEvent>,
+        address: Address,
+    ) {
+        self.event_sub_sender
+            .clone()
+            .try_send(EventNotifierRequest::Sub(
+                EventSubscribeRequest::AccountEvents {
+                    address,
+                    subscriber,
+                },
+            ))
+            .unwrap_or_default();
+    }
+
+    fn unsubscribe_account_events(
+        &self,
+        _meta: Option<Self::Metadata>,
+        id: SubscriptionId,
+    ) -> Result<bool> {
+        self.event_sub_sender
+            .clone()
+            .try_send(EventNotifierRequest::Unsub(id))
+            .unwrap_or_default();
+        Ok(true)
+    }
 }
 
 struct RpcSubApp {
@@ -178,36 +284,32 @@ pub fn start_ws_server(
     db_pool: ConnectionPool,
     sign_verify_request_sender: mpsc::Sender<VerifyTxSignatureRequest>,
     ticker_request_sender: mpsc::Sender<TickerRequest>,
+    submission_limiter: SubmissionLimiter,
+    event_sub_sender: mpsc::Sender<EventNotifierRequest>,
     panic_notify: mpsc::Sender<bool>,
     config_options: ConfigurationOptions,
     api_server_options: ApiServerOptions,
+    tls: Option<TlsConfig>,
 ) {
-    let api_caches_size = api_server_options.api_requests_caches_size;
     let addr = api_server_options.json_rpc_ws_server_address;
-
-    let (event_sub_sender, event_sub_receiver) = mpsc::channel(2048);
-
-    start_sub_notifier(
-        db_pool.clone(),
-        event_sub_receiver,
-        api_caches_size,
-        config_options
-            .miniblock_timings
-            .miniblock_iteration_interval,
-    );
+    let cors_allowed_origins = api_server_options.cors_allowed_origins.clone();
+    let batch_limiter = BatchSizeLimiter::new(api_server_options.max_rpc_batch_size);
+    let trusted_proxies = api_server_options.trusted_proxies.clone();
 
     let req_rpc_app = super::rpc_server::RpcApp::new(
         db_pool,
         sign_verify_request_sender,
         ticker_request_sender,
+        submission_limiter,
+        event_sub_sender.clone(),
         &config_options,
         &api_server_options,
     );
 
     std::thread::spawn(move || {
-        let _panic_sentinel = ThreadPanicNotify(panic_notify);
+        let _panic_sentinel = ThreadPanicNotify(panic_notify.clone());
 
-        let mut io = PubSubHandler::new(MetaIoHandler::default());
+        let mut io = PubSubHandler::new(MetaIoHandler::with_middleware(batch_limiter));
 
         req_rpc_app.extend(&mut io);
 
@@ -221,15 +323,27 @@ pub fn start_ws_server(
             .build()
             .expect("failed to build ws executor");
 
-        let server = jsonrpc_ws_server::ServerBuilder::with_meta_extractor(
+        let mut builder = jsonrpc_ws_server::ServerBuilder::with_meta_extractor(
             io,
-            |context: &RequestContext| Arc::new(Session::new(context.sender())),
+            |context: &RequestContext| RequestMeta::from_ws_context(context),
         )
-        .request_middleware(super::loggers::ws_rpc::request_middleware)
+        .request_middleware(move |request: &ws::Request| {
+            RequestMeta::ws_request_middleware(request, &trusted_proxies)
+                .or_else(|| super::loggers::ws_rpc::request_middleware(request))
+        })
         .max_connections(1000)
-        .event_loop_executor(task_executor.executor())
-        .start(&addr)
-        .expect("Unable to start RPC ws server");
+        .event_loop_executor(task_executor.executor());
+        if !cors_allowed_origins.is_empty() {
+            builder = builder.allowed_origins(DomainsValidation::AllowOnly(
+                cors_allowed_origins.into_iter().map(Origin::from).collect(),
+            ));
+        }
+
+        // The server is proxied, so the addresses of the clients are known.
+        let server = builder
+            .start(&backend_address())
+            .expect("Unable to start RPC ws server");
+        start_rpc_proxy("ws-proxy", tls.as_ref(), addr, *server.addr(), panic_notify);
 
         server.wait().expect("rpc ws server start");
     });
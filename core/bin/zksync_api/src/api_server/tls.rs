@@ -0,0 +1,106 @@
+//! Native TLS support of the API servers.
+//!
+//! REST API server terminates TLS by itself. JSON-RPC servers have no TLS support, so
+//! TLS is terminated by the proxy forwarding the decrypted connections to them
+//! (see `client_addr`).
+//!
+//! Certificate and private key are reloaded on `SIGHUP`, so they can be renewed without restart.
+
+// Built-in uses
+use std::sync::{Arc, RwLock};
+// External uses
+use anyhow::anyhow;
+use futures::channel::mpsc;
+use rustls::{
+    sign::{any_supported_type, CertifiedKey},
+    ClientHello, NoClientAuth, ResolvesServerCert, ServerConfig,
+};
+use tokio::signal::unix::{signal, SignalKind};
+// Workspace uses
+use zksync_config::TlsOptions;
+use zksync_utils::{panic_notify::ThreadPanicNotify, tls::load_pem};
+
+/// Provides the current certificate for every TLS handshake.
+struct CertificateResolver {
+    options: TlsOptions,
+    key: RwLock<CertifiedKey>,
+}
+
+impl ResolvesServerCert for CertificateResolver {
+    fn resolve(&self, _client_hello: ClientHello) -> Option<CertifiedKey> {
+        Some(self.key.read().unwrap().clone())
+    }
+}
+
+fn load_certified_key(options: &TlsOptions) -> anyhow::Result<CertifiedKey> {
+    let (cert_chain, key) = load_pem(&options.cert_path, &options.key_path)?;
+    let key = any_supported_type(&key).map_err(|_| anyhow!("Unsupported private key type"))?;
+
+    Ok(CertifiedKey::new(cert_chain, Arc::new(key)))
+}
+
+/// TLS configuration shared by all the API servers.
+#[derive(Clone)]
+pub struct TlsConfig {
+    resolver: Arc<CertificateResolver>,
+}
+
+impl TlsConfig {
+    pub fn load(options: TlsOptions) -> anyhow::Result<Self> {
+        let key = load_certified_key(&options)?;
+        let resolver = CertificateResolver {
+            options,
+            key: RwLock::new(key),
+        };
+
+        Ok(Self {
+            resolver: Arc::new(resolver),
+        })
+    }
+
+    /// Reloads the certificate, the current one is kept if the new one can't be loaded.
+    pub fn reload(&self) -> anyhow::Result<()> {
+        let key = load_certified_key(&self.resolver.options)?;
+        *self.resolver.key.write().unwrap() = key;
+        Ok(())
+    }
+
+    pub fn server_config(&self) -> ServerConfig {
+        let mut config = ServerConfig::new(NoClientAuth::new());
+        config.cert_resolver = self.resolver.clone();
+        config
+    }
+}
+
+pub(super) fn run_in_thread<F>(name: &str, panic_notify: mpsc::Sender<bool>, task: F)
+where
+    F: std::future::Future<Output = ()> + Send + 'static,
+{
+    std::thread::Builder::new()
+        .name(name.to_string())
+        .spawn(move || {
+            let _panic_sentinel = ThreadPanicNotify(panic_notify);
+
+            let mut runtime = tokio::runtime::Builder::new()
+                .threaded_scheduler()
+                .enable_all()
+                .build()
+                .expect("Unable to build TLS runtime");
+            runtime.block_on(task);
+        })
+        .expect("Unable to start TLS thread");
+}
+
+/// Reloads the certificate every time the server receives `SIGHUP`.
+pub fn start_certificate_reloader(tls: TlsConfig, panic_notify: mpsc::Sender<bool>) {
+    run_in_thread("tls-reloader", panic_notify, async move {
+        let mut hangup = signal(SignalKind::hangup()).expect("Unable to listen to SIGHUP");
+
+        while hangup.recv().await.is_some() {
+            match tls.reload() {
+                Ok(()) => tracing::info!("TLS certificate reloaded"),
+                Err(err) => vlog::error!("Unable to reload TLS certificate: {}", err),
+            }
+        }
+    });
+}
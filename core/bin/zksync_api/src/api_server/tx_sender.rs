@@ -1,7 +1,7 @@
 //! Helper module to submit transactions into the zkSync Network.
 
 // Built-in uses
-use std::{fmt::Display, str::FromStr};
+use std::{collections::HashMap, fmt::Display, net::IpAddr, str::FromStr, time::Duration};
 
 // External uses
 use bigdecimal::BigDecimal;
@@ -19,17 +19,25 @@ use zksync_config::ApiServerOptions;
 use zksync_storage::ConnectionPool;
 use zksync_types::{
     tx::{BatchSignData, EthSignData, SignedZkSyncTx, TxEthSignature, TxHash},
-    Address, Token, TokenId, TokenLike, TxFeeTypes, ZkSyncTx,
+    Address, Nonce, Token, TokenId, TokenLike, TxFeeTypes, ZkSyncTx,
 };
 
 // Local uses
-use crate::api_server::rpc_server::types::TxWithSignature;
+use crate::api_server::{
+    fee_subsidy::{self, FeeCheck, FeeSubsidy},
+    is_standby,
+    rate_limiter::{retry_after_secs, SubmissionLimiter},
+    rpc_server::types::TxWithSignature,
+};
 use crate::{
     core_api_client::CoreApiClient,
-    fee_ticker::{Fee, TickerRequest, TokenPriceRequestType},
+    fee_ticker::{BatchFeeQuote, BatchItemFee, Fee, TickerRequest, TokenPriceRequestType},
     signature_checker::{TxVariant, VerifiedTx, VerifyTxSignatureRequest},
     tx_error::TxAddError,
-    utils::token_db_cache::TokenDBCache,
+    utils::{
+        runtime_settings::{RuntimeSettings, RuntimeSettingsCache},
+        token_db_cache::TokenDBCache,
+    },
 };
 
 #[derive(Clone)]
@@ -40,6 +48,10 @@ pub struct TxSender {
 
     pub pool: ConnectionPool,
     pub tokens: TokenDBCache,
+    pub submission_limiter: SubmissionLimiter,
+    /// Reverse proxies allowed to report the client IP address.
+    pub trusted_proxies: Vec<IpAddr>,
+    pub runtime_settings: RuntimeSettingsCache,
     /// Mimimum age of the account for `ForcedExit` operations to be allowed.
     pub forced_exit_minimum_account_age: chrono::Duration,
     pub enforce_pubkey_change_fee: bool,
@@ -62,6 +74,14 @@ pub enum SubmitError {
     TxAdd(TxAddError),
     #[error("Chosen token is not suitable for paying fees.")]
     InappropriateFeeToken,
+    #[error("Too many transactions submitted, retry after {retry_after} seconds.")]
+    RateLimited { retry_after: u64 },
+    #[error("Transactions acceptance is temporarily paused.")]
+    TxAcceptancePaused,
+    #[error("Transactions are temporarily not accepted due to the maintenance.")]
+    MaintenanceMode,
+    #[error("Token {0} is disabled.")]
+    TokenDisabled(TokenId),
 
     #[error("Communication error with the core server: {0}.")]
     CommunicationCoreServer(String),
@@ -87,6 +107,12 @@ impl SubmitError {
     fn invalid_params(msg: impl Display) -> Self {
         Self::InvalidParams(msg.to_string())
     }
+
+    pub(crate) fn rate_limited(retry_after: Duration) -> Self {
+        Self::RateLimited {
+            retry_after: retry_after_secs(retry_after),
+        }
+    }
 }
 
 macro_rules! internal_error {
@@ -105,6 +131,7 @@ impl TxSender {
         connection_pool: ConnectionPool,
         sign_verify_request_sender: mpsc::Sender<VerifyTxSignatureRequest>,
         ticker_request_sender: mpsc::Sender<TickerRequest>,
+        submission_limiter: SubmissionLimiter,
         api_server_options: &ApiServerOptions,
     ) -> Self {
         let core_api_client = CoreApiClient::new(api_server_options.core_server_url.clone());
@@ -114,6 +141,7 @@ impl TxSender {
             connection_pool,
             sign_verify_request_sender,
             ticker_request_sender,
+            submission_limiter,
             api_server_options,
         )
     }
@@ -123,6 +151,7 @@ impl TxSender {
         connection_pool: ConnectionPool,
         sign_verify_request_sender: mpsc::Sender<VerifyTxSignatureRequest>,
         ticker_request_sender: mpsc::Sender<TickerRequest>,
+        submission_limiter: SubmissionLimiter,
         api_server_options: &ApiServerOptions,
     ) -> Self {
         let enforce_pubkey_change_fee = api_server_options.enforce_pubkey_change_fee;
@@ -139,7 +168,10 @@ impl TxSender {
             pool: connection_pool.clone(),
             sign_verify_requests: sign_verify_request_sender,
             ticker_requests: ticker_request_sender,
-            tokens: TokenDBCache::new(connection_pool),
+            tokens: TokenDBCache::new(connection_pool.clone()),
+            submission_limiter,
+            trusted_proxies: api_server_options.trusted_proxies.clone(),
+            runtime_settings: RuntimeSettingsCache::new(connection_pool),
 
             enforce_pubkey_change_fee,
             forced_exit_minimum_account_age,
@@ -148,6 +180,21 @@ impl TxSender {
         }
     }
 
+    /// Checks the submission limit of the client IP address, if the transport was able to determine it.
+    ///
+    /// IP limit is checked before the signatures verification, so the verification
+    /// of the spam transactions is limited as well.
+    fn check_ip_limit(
+        &self,
+        client_ip: Option<IpAddr>,
+        txs_count: usize,
+    ) -> Result<(), SubmitError> {
+        match client_ip {
+            Some(ip) => self.submission_limiter.check_ip(ip, txs_count),
+            None => Ok(()),
+        }
+    }
+
     /// If `ForcedExit` has Ethereum siganture (e.g. it's a part of a batch), an actual signer
     /// is initiator, not the target, thus, this function will perform a database query to acquire
     /// the corresponding address.
@@ -166,15 +213,24 @@ impl TxSender {
         }
     }
 
+    #[tracing::instrument(
+        name = "submit_tx",
+        skip(self, tx, signature, fast_processing, client_ip),
+        fields(tx_hash = %tx.hash().to_string())
+    )]
     pub async fn submit_tx(
         &self,
         mut tx: ZkSyncTx,
         signature: Option<TxEthSignature>,
         fast_processing: Option<bool>,
+        client_ip: Option<IpAddr>,
     ) -> Result<TxHash, SubmitError> {
         if tx.is_close() {
             return Err(SubmitError::AccountCloseDisabled);
         }
+        self.check_ip_limit(client_ip, 1)?;
+        self.check_runtime_settings(std::slice::from_ref(&tx))
+            .await?;
 
         if let ZkSyncTx::ForcedExit(forced_exit) = &tx {
             self.check_forced_exit(forced_exit).await?;
@@ -201,43 +257,11 @@ impl TxSender {
 
         let msg_to_sign = self.tx_message_to_sign(&tx).await?;
 
-        let tx_fee_info = tx.get_fee_info();
+        let fee_check = self.check_tx_fee(&tx).await?;
+        self.check_committed_nonces(std::slice::from_ref(&tx))
+            .await?;
 
         let sign_verify_channel = self.sign_verify_requests.clone();
-        let ticker_request_sender = self.ticker_requests.clone();
-
-        if let Some((tx_type, token, address, provided_fee)) = tx_fee_info {
-            let should_enforce_fee =
-                !matches!(tx_type, TxFeeTypes::ChangePubKey{..}) || self.enforce_pubkey_change_fee;
-
-            let fee_allowed =
-                Self::token_allowed_for_fees(ticker_request_sender.clone(), token.clone()).await?;
-
-            if !fee_allowed {
-                return Err(SubmitError::InappropriateFeeToken);
-            }
-
-            let required_fee =
-                Self::ticker_request(ticker_request_sender, tx_type, address, token.clone())
-                    .await?;
-            // Converting `BitUint` to `BigInt` is safe.
-            let required_fee: BigDecimal = required_fee.total_fee.to_bigint().unwrap().into();
-            let provided_fee: BigDecimal = provided_fee.to_bigint().unwrap().into();
-            // Scaling the fee required since the price may change between signing the transaction and sending it to the server.
-            let scaled_provided_fee = scale_user_fee_up(provided_fee.clone());
-            if required_fee >= scaled_provided_fee && should_enforce_fee {
-                log::error!(
-                    "User provided fee is too low, required: {}, provided: {} (scaled: {}); difference {}, token: {:?}",
-                    required_fee.to_string(),
-                    provided_fee.to_string(),
-                    scaled_provided_fee.to_string(),
-                    (required_fee - scaled_provided_fee).to_string(),
-                    token
-                );
-
-                return Err(SubmitError::TxAdd(TxAddError::TxFeeTooLow));
-            }
-        }
 
         let tx_sender = self
             .get_tx_sender(&tx)
@@ -254,20 +278,52 @@ impl TxSender {
         .await?
         .unwrap_tx();
 
+        // Limits are checked only for the correctly signed transactions, so nobody
+        // is able to exhaust the limit of another account.
+        self.submission_limiter.check_accounts(&[tx_sender])?;
+
+        if let FeeCheck::Subsidized(subsidy) = &fee_check {
+            self.reserve_fee_subsidy(tx.hash(), subsidy).await?;
+        }
+
         // Send verified transactions to the mempool.
-        self.core_api_client
+        let add_result = self
+            .core_api_client
             .send_tx(verified_tx)
             .await
-            .map_err(SubmitError::communication_core_server)?
-            .map_err(SubmitError::TxAdd)?;
+            .map_err(SubmitError::communication_core_server)?;
+        if let Err(err) = add_result {
+            // Transaction is rejected anyway, so the failure is only reported.
+            // The reservation is kept if the mempool is unreachable, since the transaction
+            // may have been accepted.
+            if let FeeCheck::Subsidized(_) = fee_check {
+                if let Err(err) = self.release_fee_subsidy(tx.hash()).await {
+                    vlog::warn!(
+                        "Unable to release the fee subsidy of {}: {}",
+                        tx.hash().to_string(),
+                        err
+                    );
+                }
+            }
+            return Err(SubmitError::TxAdd(err));
+        }
+        tracing::info!(fast_processing, "Transaction accepted by the API");
+        metrics::counter!("api.accepted_txs", 1);
+
         // if everything is OK, return the transactions hashes.
         Ok(tx.hash())
     }
 
+    #[tracing::instrument(
+        name = "submit_txs_batch",
+        skip(self, txs, eth_signatures, client_ip),
+        fields(batch_size = txs.len())
+    )]
     pub async fn submit_txs_batch(
         &self,
         txs: Vec<TxWithSignature>,
         eth_signatures: Vec<TxEthSignature>,
+        client_ip: Option<IpAddr>,
     ) -> Result<Vec<TxHash>, SubmitError> {
         if txs.is_empty() {
             return Err(SubmitError::TxAdd(TxAddError::EmptyBatch));
@@ -286,67 +342,12 @@ impl TxSender {
         if txs.iter().any(|tx| tx.tx.is_close()) {
             return Err(SubmitError::AccountCloseDisabled);
         }
+        self.check_ip_limit(client_ip, txs.len())?;
 
-        // Checking fees data
-        let mut required_total_usd_fee = BigDecimal::from(0);
-        let mut provided_total_usd_fee = BigDecimal::from(0);
-        for tx in &txs {
-            let tx_fee_info = tx.tx.get_fee_info();
-
-            if let Some((tx_type, token, address, provided_fee)) = tx_fee_info {
-                let fee_allowed =
-                    Self::token_allowed_for_fees(self.ticker_requests.clone(), token.clone())
-                        .await?;
-
-                // In batches, transactions with non-popular token are allowed to be included, but should not
-                // used to pay fees. Fees must be covered by some more common token.
-                if !fee_allowed && provided_fee != 0u64.into() {
-                    return Err(SubmitError::InappropriateFeeToken);
-                }
-
-                let check_token = if fee_allowed {
-                    // For allowed tokens, we perform check in the transaction token (as expected).
-                    token.clone()
-                } else {
-                    // For non-popular tokens we've already checked that the provided fee is 0,
-                    // and the USD price will be checked in ETH.
-                    TokenLike::Id(0)
-                };
-
-                let required_fee = Self::ticker_request(
-                    self.ticker_requests.clone(),
-                    tx_type,
-                    address,
-                    check_token.clone(),
-                )
-                .await?;
-                let token_price_in_usd = Self::ticker_price_request(
-                    self.ticker_requests.clone(),
-                    check_token.clone(),
-                    TokenPriceRequestType::USDForOneWei,
-                )
-                .await?;
-
-                required_total_usd_fee +=
-                    BigDecimal::from(required_fee.total_fee.to_bigint().unwrap())
-                        * &token_price_in_usd;
-                provided_total_usd_fee +=
-                    BigDecimal::from(provided_fee.clone().to_bigint().unwrap())
-                        * &token_price_in_usd;
-            }
-        }
-        // Scaling the fee required since the price may change between signing the transaction and sending it to the server.
-        let scaled_provided_fee_in_usd = scale_user_fee_up(provided_total_usd_fee.clone());
-        if required_total_usd_fee >= scaled_provided_fee_in_usd {
-            log::error!(
-                "User provided batch fee is too low, required: {}, provided: {} (scaled: {}); difference {}",
-                required_total_usd_fee.to_string(),
-                provided_total_usd_fee.to_string(),
-                scaled_provided_fee_in_usd.to_string(),
-                (required_total_usd_fee - scaled_provided_fee_in_usd).to_string(),
-            );
-            return Err(SubmitError::TxAdd(TxAddError::TxBatchFeeTooLow));
-        }
+        let batch_txs = txs.iter().map(|tx| tx.tx.clone()).collect::<Vec<_>>();
+        self.check_runtime_settings(&batch_txs).await?;
+        self.check_batch_fee(&batch_txs).await?;
+        self.check_committed_nonces(&batch_txs).await?;
 
         let mut verified_txs = Vec::with_capacity(txs.len());
         let mut verified_signatures = Vec::new();
@@ -361,16 +362,13 @@ impl TxSender {
                     .or(Err(SubmitError::TxAdd(TxAddError::DbError)))?,
             );
         }
+        let batch_senders = tx_senders.clone();
 
         if !eth_signatures.is_empty() {
             // User provided at least one signature for the whole batch.
-            let _txs = txs
-                .iter()
-                .map(|tx| tx.tx.clone())
-                .collect::<Vec<ZkSyncTx>>();
             // Create batch signature data.
             let batch_sign_data =
-                BatchSignData::new(&_txs, eth_signatures).map_err(SubmitError::other)?;
+                BatchSignData::new(&batch_txs, eth_signatures).map_err(SubmitError::other)?;
             let (verified_batch, sign_data) = verify_txs_batch_signature(
                 txs,
                 tx_senders,
@@ -399,6 +397,10 @@ impl TxSender {
                 verified_txs.push(verified_tx);
             }
         }
+        // Limits are checked only for the correctly signed transactions, so nobody
+        // is able to exhaust the limit of another account.
+        self.submission_limiter.check_accounts(&batch_senders)?;
+
         let tx_hashes: Vec<TxHash> = verified_txs.iter().map(|tx| tx.tx.hash()).collect();
         // Send verified transactions to the mempool.
         self.core_api_client
@@ -406,10 +408,296 @@ impl TxSender {
             .await
             .map_err(SubmitError::communication_core_server)?
             .map_err(SubmitError::TxAdd)?;
+        for tx_hash in &tx_hashes {
+            tracing::info!(
+                tx_hash = %tx_hash.to_string(),
+                batch_size = tx_hashes.len(),
+                "Transaction accepted by the API as a part of a batch"
+            );
+        }
+        metrics::counter!("api.accepted_txs", tx_hashes.len() as u64);
 
         Ok(tx_hashes)
     }
 
+    /// Checks the transactions against the settings changed through the admin API.
+    async fn check_runtime_settings(&self, txs: &[ZkSyncTx]) -> Result<(), SubmitError> {
+        let settings = self
+            .runtime_settings
+            .get()
+            .await
+            .map_err(|err| internal_error!(err))?;
+
+        check_settings(&settings, txs, is_standby())
+    }
+
+    /// Checks that the transaction fee covers the fee quoted by the ticker, or the missing
+    /// part of it may be subsidized.
+    ///
+    /// Used both upon the transaction submission and upon the revalidation of the pending transactions.
+    pub(crate) async fn check_tx_fee(&self, tx: &ZkSyncTx) -> Result<FeeCheck, SubmitError> {
+        let (tx_type, token, address, provided_fee) = match tx.get_fee_info() {
+            Some(fee_info) => fee_info,
+            None => return Ok(FeeCheck::Paid),
+        };
+
+        let should_enforce_fee =
+            !matches!(tx_type, TxFeeTypes::ChangePubKey { .. }) || self.enforce_pubkey_change_fee;
+
+        let fee_allowed =
+            Self::token_allowed_for_fees(self.ticker_requests.clone(), token.clone()).await?;
+
+        if !fee_allowed {
+            return Err(SubmitError::InappropriateFeeToken);
+        }
+
+        let required_fee = Self::ticker_request(
+            self.ticker_requests.clone(),
+            tx_type,
+            address,
+            token.clone(),
+        )
+        .await?;
+        // Converting `BitUint` to `BigInt` is safe.
+        let required_fee: BigDecimal = required_fee.total_fee.to_bigint().unwrap().into();
+        let provided_fee: BigDecimal = provided_fee.to_bigint().unwrap().into();
+        // Scaling the fee required since the price may change between signing the transaction and sending it to the server.
+        let scaled_provided_fee = scale_user_fee_up(provided_fee.clone());
+        if required_fee >= scaled_provided_fee && should_enforce_fee {
+            let fee_check = self
+                .find_fee_subsidy(tx, tx_type, token.clone(), &required_fee, &provided_fee)
+                .await?;
+            if let Some(fee_check) = fee_check {
+                return Ok(fee_check);
+            }
+
+            tracing::error!(
+                "User provided fee is too low, required: {}, provided: {} (scaled: {}); difference {}, token: {:?}",
+                required_fee.to_string(),
+                provided_fee.to_string(),
+                scaled_provided_fee.to_string(),
+                (required_fee - scaled_provided_fee).to_string(),
+                token
+            );
+
+            return Err(SubmitError::TxAdd(TxAddError::TxFeeTooLow));
+        }
+
+        Ok(FeeCheck::Paid)
+    }
+
+    /// Looks for the subsidy covering the missing part of the transaction fee.
+    /// Returns `None` if the missing part can't be subsidized.
+    async fn find_fee_subsidy(
+        &self,
+        tx: &ZkSyncTx,
+        tx_type: TxFeeTypes,
+        token: TokenLike,
+        required_fee: &BigDecimal,
+        provided_fee: &BigDecimal,
+    ) -> Result<Option<FeeCheck>, SubmitError> {
+        let mut storage = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(SubmitError::internal)?;
+        // Pending transactions keep the subsidy once their fees are revalidated.
+        if storage
+            .admin_schema()
+            .is_tx_subsidized(&tx.hash())
+            .await
+            .map_err(SubmitError::internal)?
+        {
+            return Ok(Some(FeeCheck::SubsidyReserved));
+        }
+
+        let token_id = match self
+            .tokens
+            .get_token(token.clone())
+            .await
+            .map_err(SubmitError::internal)?
+        {
+            Some(token) => token.id,
+            None => return Ok(None),
+        };
+        let token_price_usd = Self::ticker_price_request(
+            self.ticker_requests.clone(),
+            token,
+            TokenPriceRequestType::USDForOneWei,
+        )
+        .await?;
+
+        let subsidy = fee_subsidy::find_subsidy(
+            &mut storage,
+            tx_type,
+            token_id,
+            required_fee,
+            provided_fee,
+            &token_price_usd,
+        )
+        .await
+        .map_err(SubmitError::internal)?;
+        Ok(subsidy.map(FeeCheck::Subsidized))
+    }
+
+    /// Reserves the fee subsidy of the transaction about to be accepted.
+    /// Fails if the daily budget of the rule was exhausted since the subsidy was found.
+    pub(crate) async fn reserve_fee_subsidy(
+        &self,
+        tx_hash: TxHash,
+        subsidy: &FeeSubsidy,
+    ) -> Result<(), SubmitError> {
+        let mut storage = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(SubmitError::internal)?;
+        let reserved = storage
+            .admin_schema()
+            .reserve_subsidized_tx(
+                &tx_hash,
+                &subsidy.rule,
+                subsidy.token_id,
+                subsidy.amount.clone(),
+                subsidy.amount_usd.clone(),
+                fee_subsidy::start_of_day(Utc::now()),
+            )
+            .await
+            .map_err(SubmitError::internal)?;
+        if !reserved {
+            return Err(SubmitError::TxAdd(TxAddError::TxFeeTooLow));
+        }
+
+        tracing::info!(
+            "Fee of the transaction {} subsidized under the rule {}: {} of token {} (${})",
+            tx_hash.to_string(),
+            subsidy.rule.id,
+            subsidy.amount,
+            subsidy.token_id,
+            subsidy.amount_usd
+        );
+        Ok(())
+    }
+
+    /// Releases the fee subsidy reserved for the transaction rejected by the mempool.
+    async fn release_fee_subsidy(&self, tx_hash: TxHash) -> anyhow::Result<()> {
+        self.pool
+            .access_storage()
+            .await?
+            .admin_schema()
+            .release_subsidized_txs(&[tx_hash])
+            .await
+    }
+
+    /// Quotes the fee of the transactions batch in the same way as it's charged on submission.
+    pub async fn batch_fee_quote(
+        &self,
+        txs: &[(TxFeeTypes, TokenLike, Address)],
+    ) -> Result<BatchFeeQuote, SubmitError> {
+        if txs.is_empty() {
+            return Err(SubmitError::TxAdd(TxAddError::EmptyBatch));
+        }
+        if txs.len() > self.max_number_of_transactions_per_batch {
+            return Err(SubmitError::TxAdd(TxAddError::BatchTooBig));
+        }
+
+        let mut items = Vec::with_capacity(txs.len());
+        for (tx_type, token, address) in txs {
+            items.push(
+                self.batch_item_fee(*tx_type, token.clone(), *address)
+                    .await?,
+            );
+        }
+
+        Ok(BatchFeeQuote::new(items))
+    }
+
+    /// Returns the fee currently required by the ticker for the transaction.
+    pub(crate) async fn tx_fee(
+        &self,
+        tx_type: TxFeeTypes,
+        address: Address,
+        token: TokenLike,
+    ) -> Result<Fee, SubmitError> {
+        Self::ticker_request(self.ticker_requests.clone(), tx_type, address, token).await
+    }
+
+    /// Quotes the fee required for a single transaction of the batch.
+    async fn batch_item_fee(
+        &self,
+        tx_type: TxFeeTypes,
+        token: TokenLike,
+        address: Address,
+    ) -> Result<BatchItemFee, SubmitError> {
+        let fee_allowed =
+            Self::token_allowed_for_fees(self.ticker_requests.clone(), token.clone()).await?;
+
+        let fee_token = if fee_allowed {
+            // For allowed tokens, we perform check in the transaction token (as expected).
+            token
+        } else {
+            // Non-popular tokens can't be used to pay fees, so the USD price is checked in ETH.
+            TokenLike::Id(0)
+        };
+
+        let required_fee = Self::ticker_request(
+            self.ticker_requests.clone(),
+            tx_type,
+            address,
+            fee_token.clone(),
+        )
+        .await?;
+        let token_price_in_usd = Self::ticker_price_request(
+            self.ticker_requests.clone(),
+            fee_token.clone(),
+            TokenPriceRequestType::USDForOneWei,
+        )
+        .await?;
+
+        Ok(BatchItemFee::new(
+            fee_token,
+            required_fee,
+            token_price_in_usd,
+        ))
+    }
+
+    /// Checks that the total fee provided by the batch transactions covers the total fee
+    /// currently quoted by the ticker. Fees are compared in USD.
+    pub(crate) async fn check_batch_fee(&self, txs: &[ZkSyncTx]) -> Result<(), SubmitError> {
+        let mut required_total_usd_fee = BigDecimal::from(0);
+        let mut provided_total_usd_fee = BigDecimal::from(0);
+        for tx in txs {
+            let tx_fee_info = tx.get_fee_info();
+
+            if let Some((tx_type, token, address, provided_fee)) = tx_fee_info {
+                let item_fee = self.batch_item_fee(tx_type, token.clone(), address).await?;
+
+                // In batches, transactions with non-popular token are allowed to be included, but should not
+                // used to pay fees. Fees must be covered by some more common token.
+                if item_fee.fee_token != token && provided_fee != 0u64.into() {
+                    return Err(SubmitError::InappropriateFeeToken);
+                }
+
+                provided_total_usd_fee += item_fee.amount_to_usd(&provided_fee);
+                required_total_usd_fee += item_fee.total_fee_usd;
+            }
+        }
+        // Scaling the fee required since the price may change between signing the transaction and sending it to the server.
+        let scaled_provided_fee_in_usd = scale_user_fee_up(provided_total_usd_fee.clone());
+        if required_total_usd_fee >= scaled_provided_fee_in_usd {
+            tracing::error!(
+                "User provided batch fee is too low, required: {}, provided: {} (scaled: {}); difference {}",
+                required_total_usd_fee.to_string(),
+                provided_total_usd_fee.to_string(),
+                scaled_provided_fee_in_usd.to_string(),
+                (required_total_usd_fee - scaled_provided_fee_in_usd).to_string(),
+            );
+            return Err(SubmitError::TxAdd(TxAddError::TxBatchFeeTooLow));
+        }
+
+        Ok(())
+    }
+
     /// For forced exits, we must check that target account exists for more
     /// than 24 hours in order to give new account owners give an opportunity
     /// to set the signing key. While `ForcedExit` operation doesn't do anything
@@ -449,6 +737,46 @@ impl TxSender {
         }
     }
 
+    /// Rejects transactions with a nonce that is already used in the committed state
+    /// (including the pending block), since they can never be executed.
+    ///
+    /// Balances are not checked: the funds may be received by the pending transactions
+    /// which are not executed yet (e.g. in the chained transfers), so the insufficient
+    /// balance is only reported once the transaction is executed.
+    async fn check_committed_nonces(&self, txs: &[ZkSyncTx]) -> Result<(), SubmitError> {
+        let mut storage = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(SubmitError::internal)?;
+
+        let mut nonces: HashMap<Address, Nonce> = HashMap::new();
+        for tx in txs {
+            let address = tx.account();
+            let committed_nonce = match nonces.get(&address) {
+                Some(nonce) => *nonce,
+                None => {
+                    let nonce = storage
+                        .chain()
+                        .account_schema()
+                        .account_state_by_address(address)
+                        .await
+                        .map_err(|err| internal_error!(err, tx))?
+                        .committed
+                        .map(|(_, account)| account.nonce)
+                        .unwrap_or_default();
+                    nonces.insert(address, nonce);
+                    nonce
+                }
+            };
+            if tx.nonce() < committed_nonce {
+                return Err(SubmitError::TxAdd(TxAddError::NonceMismatch));
+            }
+        }
+
+        Ok(())
+    }
+
     /// Returns a message that user has to sign to send the transaction.
     /// If the transaction doesn't need a message signature, returns `None`.
     /// If any error is encountered during the message generation, returns `jsonrpc_core::Error`.
@@ -630,6 +958,28 @@ async fn verify_txs_batch_signature(
     send_verify_request_and_recv(request, req_channel, receiver).await
 }
 
+fn check_settings(
+    settings: &RuntimeSettings,
+    txs: &[ZkSyncTx],
+    standby: bool,
+) -> Result<(), SubmitError> {
+    if settings.maintenance_mode {
+        return Err(SubmitError::MaintenanceMode);
+    }
+    if settings.tx_acceptance_paused || standby {
+        return Err(SubmitError::TxAcceptancePaused);
+    }
+    for tx in txs {
+        if let Some((_, TokenLike::Id(token), _, _)) = tx.get_fee_info() {
+            if settings.token_disabled(token) {
+                return Err(SubmitError::TokenDisabled(token));
+            }
+        }
+    }
+
+    Ok(())
+}
+
 /// Scales the fee provided by user up to check whether the provided fee is enough to cover our expenses for
 /// maintaining the protocol.
 ///
@@ -650,3 +1000,59 @@ fn scale_user_fee_up(provided_total_usd_fee: BigDecimal) -> BigDecimal {
         scaled_one_cent_provided_fee_in_usd,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num::BigUint;
+    use zksync_types::Transfer;
+
+    fn transfer(token: TokenId) -> ZkSyncTx {
+        Transfer::new(
+            0,
+            Address::random(),
+            Address::random(),
+            token,
+            BigUint::from(1u32),
+            BigUint::from(1u32),
+            0,
+            None,
+        )
+        .into()
+    }
+
+    /// Checks that the transactions are rejected according to the settings changed through the admin API.
+    #[test]
+    fn runtime_settings_checks() {
+        let txs = vec![transfer(0), transfer(1)];
+        let mut settings = RuntimeSettings::default();
+        assert!(check_settings(&settings, &txs, false).is_ok());
+        assert!(matches!(
+            check_settings(&settings, &txs, true),
+            Err(SubmitError::TxAcceptancePaused)
+        ));
+
+        settings.disabled_tokens.insert(1);
+        assert!(matches!(
+            check_settings(&settings, &txs, false),
+            Err(SubmitError::TokenDisabled(1))
+        ));
+
+        settings.tx_acceptance_paused = true;
+        assert!(matches!(
+            check_settings(&settings, &txs, false),
+            Err(SubmitError::TxAcceptancePaused)
+        ));
+
+        // Maintenance mode takes precedence, so clients know the reason of the rejection.
+        settings.maintenance_mode = true;
+        assert!(matches!(
+            check_settings(&settings, &txs, false),
+            Err(SubmitError::MaintenanceMode)
+        ));
+        assert!(matches!(
+            check_settings(&settings, &[], true),
+            Err(SubmitError::MaintenanceMode)
+        ));
+    }
+}
@@ -44,7 +44,7 @@ async fn handle_coinmarketcap_token_price_query(
             }
         }
     });
-    log::info!("1.0 {} = {} USD", query.symbol, price);
+    tracing::info!("1.0 {} = {} USD", query.symbol, price);
     Ok(HttpResponse::Ok().json(resp))
 }
 
@@ -77,7 +77,7 @@ async fn handle_coingecko_token_price_query(req: HttpRequest) -> Result<HttpResp
             [last_updated, price],
         ]
     });
-    log::info!("1.0 {:?} = {} USD", coin_id, price);
+    tracing::info!("1.0 {:?} = {} USD", coin_id, price);
     Ok(HttpResponse::Ok().json(resp))
 }
 
@@ -1,5 +1,8 @@
 use crate::tx_error::TxAddError;
-use zksync_types::{tx::TxEthSignature, Address, PriorityOp, SignedZkSyncTx, H256};
+use zksync_types::{
+    tx::{TxEthSignature, TxHash},
+    Address, PriorityOp, PriorityOpClaim, SignedZkSyncTx, H256,
+};
 
 /// `CoreApiClient` is capable of interacting with a private zkSync Core API.
 #[derive(Debug, Clone)]
@@ -36,6 +39,12 @@ impl CoreApiClient {
         self.post(&endpoint, data).await
     }
 
+    /// Asks the Core mempool to remove the pending transactions with the given hashes.
+    pub async fn remove_txs(&self, tx_hashes: Vec<TxHash>) -> anyhow::Result<()> {
+        let endpoint = format!("{}/remove_txs", self.addr);
+        self.post(&endpoint, tx_hashes).await
+    }
+
     /// Queries information about unconfirmed deposit operations for a certain address from a Core.
     pub async fn get_unconfirmed_deposits(
         &self,
@@ -62,6 +71,30 @@ impl CoreApiClient {
         self.get(&endpoint).await
     }
 
+    /// Claims the pending priority operations known to the Ethereum watcher by the hash
+    /// of their Ethereum transaction.
+    pub async fn claim_priority_ops(
+        &self,
+        eth_tx_hash: H256,
+    ) -> anyhow::Result<Vec<PriorityOpClaim>> {
+        let endpoint = format!(
+            "{}/priority_op_claims/0x{}",
+            self.addr,
+            hex::encode(eth_tx_hash)
+        );
+        self.get(&endpoint).await
+    }
+
+    /// Estimates the inclusion of the priority operations found on Ethereum, which are
+    /// not processed by the Ethereum watcher yet.
+    pub async fn estimate_priority_ops(
+        &self,
+        ops: Vec<PriorityOp>,
+    ) -> anyhow::Result<Vec<PriorityOpClaim>> {
+        let endpoint = format!("{}/priority_op_estimates", self.addr);
+        self.post(&endpoint, ops).await
+    }
+
     async fn get<T: serde::de::DeserializeOwned>(&self, url: &str) -> anyhow::Result<T> {
         let response = self.client.get(url).send().await?.json().await?;
 
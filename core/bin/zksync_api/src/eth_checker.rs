@@ -1,30 +1,57 @@
 //! Module capable of checking the onchain operations, such as
-//! onchain `ChangePubKey` authorization or EIP1271 signature
-//! verification.
+//! onchain `ChangePubKey` authorization, EIP1271 signature
+//! verification or the priority operations requested by the
+//! Ethereum transaction.
+
+use std::convert::TryFrom;
 
 use web3::{
     contract::{Contract, Options},
-    types::{Address, H160},
+    types::{Address, Log, H160, H256},
     Transport, Web3,
 };
 use zksync_contracts::{eip1271_contract, zksync_contract};
 use zksync_types::{
     tx::EIP1271Signature,
-    {Nonce, PubKeyHash},
+    {Nonce, PriorityOp, PubKeyHash},
 };
 
+use crate::utils::shared_lru_cache::SharedLruCache;
+
 /// isValidSignature return value according to EIP1271 standard
 /// bytes4(keccak256("isValidSignature(bytes32,bytes)")
 pub const EIP1271_SUCCESS_RETURN_VALUE: [u8; 4] = [0x16, 0x26, 0xba, 0x7e];
 
+/// Maximum number of the verified EIP1271 signatures kept in cache.
+const EIP1271_CACHE_SIZE: usize = 10_000;
+
+/// Maximum number of the Ethereum transactions with the looked up priority operations kept in cache.
+const PRIORITY_OPS_CACHE_SIZE: usize = 10_000;
+
+/// Smart wallet address, signed message and the signature.
+type EIP1271CacheKey = (Address, [u8; 32], Vec<u8>);
+
 #[derive(Clone)]
 pub struct EthereumChecker<T: Transport> {
     web3: Web3<T>,
     zksync_contract: (ethabi::Contract, Contract<T>),
+    /// Signatures confirmed by the smart wallets. Only positive results are cached,
+    /// since the wallet can start accepting the signature later (e.g. once the owner is added).
+    eip1271_cache: SharedLruCache<EIP1271CacheKey, ()>,
+    /// Topic of the `NewPriorityRequest` event of the zkSync contract.
+    new_priority_request_topic: H256,
+    /// Priority operations requested by the Ethereum transactions with enough confirmations.
+    priority_ops_cache: SharedLruCache<H256, Vec<PriorityOp>>,
+    /// Number of confirmations after which the transaction receipt is not expected to change.
+    confirmations_for_eth_event: u64,
 }
 
 impl<T: Transport> EthereumChecker<T> {
-    pub fn new(web3: Web3<T>, zksync_contract_addr: H160) -> Self {
+    pub fn new(
+        web3: Web3<T>,
+        zksync_contract_addr: H160,
+        confirmations_for_eth_event: u64,
+    ) -> Self {
         let zksync_contract = {
             (
                 zksync_contract(),
@@ -32,9 +59,19 @@ impl<T: Transport> EthereumChecker<T> {
             )
         };
 
+        let new_priority_request_topic = zksync_contract
+            .0
+            .event("NewPriorityRequest")
+            .expect("zkSync contract abi error")
+            .signature();
+
         Self {
             zksync_contract,
             web3,
+            eip1271_cache: SharedLruCache::new(EIP1271_CACHE_SIZE),
+            new_priority_request_topic,
+            priority_ops_cache: SharedLruCache::new(PRIORITY_OPS_CACHE_SIZE),
+            confirmations_for_eth_event,
         }
     }
 
@@ -60,11 +97,17 @@ impl<T: Transport> EthereumChecker<T> {
     ) -> Result<bool, anyhow::Error> {
         let sign_message = Self::get_sign_message(message);
 
+        let cache_key = (address, sign_message, signature.0);
+        if self.eip1271_cache.get(&cache_key).is_some() {
+            metrics::counter!("eth_checker.eip1271_cache_hits", 1);
+            return Ok(true);
+        }
+
         let call_result = self
             .get_eip1271_contract(address)
             .query(
                 "isValidSignature",
-                (sign_message, signature.0),
+                (sign_message, cache_key.2.clone()),
                 Some(address),
                 Options::default(),
                 None,
@@ -76,12 +119,16 @@ impl<T: Transport> EthereumChecker<T> {
             Err(error) => {
                 // One error of this kind will mean that user provided incorrect signature.
                 // Many errors will likely mean that something is wrong with our implementation.
-                log::warn!("EIP1271 signature check failed: {:#?}", error);
+                tracing::warn!("EIP1271 signature check failed: {:#?}", error);
                 return Ok(false);
             }
         };
 
-        Ok(received == EIP1271_SUCCESS_RETURN_VALUE)
+        let signature_correct = received == EIP1271_SUCCESS_RETURN_VALUE;
+        if signature_correct {
+            self.eip1271_cache.insert(cache_key, ());
+        }
+        Ok(signature_correct)
     }
 
     pub async fn is_new_pubkey_hash_authorized(
@@ -104,16 +151,85 @@ impl<T: Transport> EthereumChecker<T> {
             .map_err(|e| anyhow::format_err!("Failed to query contract authFacts: {}", e))?;
         Ok(auth_fact.as_slice() == tiny_keccak::keccak256(&pub_key_hash.data[..]))
     }
+
+    /// Loads all the priority operations requested by the Ethereum transaction, i.e. the ones
+    /// reported by the `NewPriorityRequest` events of the zkSync contract. Returns an empty list
+    /// if the transaction is not mined yet.
+    ///
+    /// Until the transaction has `confirmations_for_eth_event` confirmations, a reorg may move it
+    /// to another block (changing the operations serial IDs) or drop it, so the result is cached
+    /// only once the transaction is confirmed.
+    pub async fn get_priority_ops_by_tx_hash(
+        &self,
+        eth_hash: H256,
+    ) -> Result<Vec<PriorityOp>, anyhow::Error> {
+        if let Some(ops) = self.priority_ops_cache.get(&eth_hash) {
+            metrics::counter!("eth_checker.priority_ops_cache_hits", 1);
+            return Ok(ops);
+        }
+
+        let receipt = match self.web3.eth().transaction_receipt(eth_hash).await? {
+            Some(receipt) => receipt,
+            None => return Ok(Vec::new()),
+        };
+        let receipt_block = receipt.block_number.map(|block| block.as_u64());
+        let ops = priority_ops_from_logs(
+            receipt.logs,
+            self.zksync_contract.1.address(),
+            self.new_priority_request_topic,
+        )?;
+
+        let current_block = self.web3.eth().block_number().await?.as_u64();
+        if is_receipt_confirmed(
+            receipt_block,
+            current_block,
+            self.confirmations_for_eth_event,
+        ) {
+            self.priority_ops_cache.insert(eth_hash, ops.clone());
+        }
+        Ok(ops)
+    }
+}
+
+/// Checks whether the transaction mined in `receipt_block` has enough confirmations,
+/// so its receipt is not expected to change anymore.
+fn is_receipt_confirmed(
+    receipt_block: Option<u64>,
+    current_block: u64,
+    confirmations: u64,
+) -> bool {
+    match receipt_block {
+        Some(block) => current_block.saturating_sub(block) >= confirmations,
+        None => false,
+    }
+}
+
+/// Decodes the priority operations from the `NewPriorityRequest` events emitted
+/// by the zkSync contract, skipping the rest of the logs.
+fn priority_ops_from_logs(
+    logs: Vec<Log>,
+    contract_address: Address,
+    new_priority_request_topic: H256,
+) -> Result<Vec<PriorityOp>, anyhow::Error> {
+    logs.into_iter()
+        .filter(|log| {
+            log.address == contract_address
+                && log.topics.first() == Some(&new_priority_request_topic)
+        })
+        .map(PriorityOp::try_from)
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
-    use super::EthereumChecker;
+    use super::{is_receipt_confirmed, priority_ops_from_logs, EthereumChecker};
     use std::str::FromStr;
+    use web3::types::{Bytes, Log, H256};
     use zksync_config::test_config::TestConfig;
     use zksync_types::{
+        operations::FullExitOp,
         tx::{EIP1271Signature, PackedEthSignature},
-        Address,
+        Address, ZkSyncPriorityOp,
     };
 
     #[tokio::test]
@@ -129,7 +245,7 @@ mod tests {
         let transport = web3::transports::Http::new(&config.eth.web3_url).unwrap();
         let web3 = web3::Web3::new(transport);
 
-        let eth_checker = EthereumChecker::new(web3, Default::default());
+        let eth_checker = EthereumChecker::new(web3, Default::default(), 0);
         let result = eth_checker
             .is_eip1271_signature_correct(
                 config.eip1271.contract_address,
@@ -142,6 +258,43 @@ mod tests {
         assert_eq!(result, true, "Signature is incorrect");
     }
 
+    /// Checks that the confirmed signatures are taken from cache without accessing the L1.
+    #[tokio::test]
+    async fn eip1271_cache() {
+        let message = "hello-world";
+        let address = Address::repeat_byte(0x11);
+        let signature = EIP1271Signature(vec![1, 2, 3]);
+
+        // There's no Ethereum node on this address, so the contract call always fails.
+        let transport = web3::transports::Http::new("http://127.0.0.1:1").unwrap();
+        let eth_checker = EthereumChecker::new(web3::Web3::new(transport), Default::default(), 0);
+
+        let result = eth_checker
+            .is_eip1271_signature_correct(address, message.as_bytes(), signature.clone())
+            .await
+            .unwrap();
+        assert!(!result, "Unconfirmed signature must be rejected");
+
+        let sign_message =
+            EthereumChecker::<web3::transports::Http>::get_sign_message(message.as_bytes());
+        eth_checker
+            .eip1271_cache
+            .insert((address, sign_message, signature.0.clone()), ());
+
+        let result = eth_checker
+            .is_eip1271_signature_correct(address, message.as_bytes(), signature.clone())
+            .await
+            .unwrap();
+        assert!(result, "Cached signature must be accepted");
+
+        // Cache entry is bound to the message.
+        let result = eth_checker
+            .is_eip1271_signature_correct(address, b"other-message", signature)
+            .await
+            .unwrap();
+        assert!(!result);
+    }
+
     /// This test checks that the actual signature data taken from
     /// mainnet / Argent smart wallet is valid in our codebase.
     #[test]
@@ -174,4 +327,65 @@ mod tests {
             "Restored address is incorrect"
         );
     }
+
+    /// Checks that all the `NewPriorityRequest` events of the transaction are decoded,
+    /// and the logs of other events and contracts are skipped.
+    #[test]
+    fn priority_ops_are_decoded_from_logs() {
+        let contract_address = Address::repeat_byte(0x01);
+        let topic = H256::repeat_byte(0x02);
+        let full_exit_log = |serial_id: u64, address, topic| {
+            let owner = Address::repeat_byte(0x03);
+            let mut pubdata = vec![FullExitOp::OP_CODE];
+            pubdata.extend_from_slice(&1u32.to_be_bytes());
+            pubdata.extend_from_slice(owner.as_bytes());
+            pubdata.extend_from_slice(&0u16.to_be_bytes());
+            pubdata.extend_from_slice(&[0; 16]);
+            let data = ethabi::encode(&[
+                ethabi::Token::Address(owner),
+                ethabi::Token::Uint(serial_id.into()),
+                ethabi::Token::Uint(FullExitOp::OP_CODE.into()),
+                ethabi::Token::Bytes(pubdata),
+                ethabi::Token::Uint(100.into()),
+            ]);
+            Log {
+                address,
+                topics: vec![topic],
+                data: Bytes(data),
+                block_hash: None,
+                block_number: Some(10.into()),
+                transaction_hash: Some(H256::repeat_byte(0x04)),
+                transaction_index: None,
+                log_index: None,
+                transaction_log_index: None,
+                log_type: None,
+                removed: None,
+            }
+        };
+
+        let logs = vec![
+            full_exit_log(5, contract_address, topic),
+            full_exit_log(6, Address::repeat_byte(0xff), topic),
+            full_exit_log(7, contract_address, H256::repeat_byte(0xff)),
+            full_exit_log(8, contract_address, topic),
+        ];
+        let ops = priority_ops_from_logs(logs, contract_address, topic).unwrap();
+        let serial_ids: Vec<_> = ops.iter().map(|op| op.serial_id).collect();
+        assert_eq!(serial_ids, vec![5, 8]);
+        assert!(matches!(ops[0].data, ZkSyncPriorityOp::FullExit(_)));
+        assert_eq!(ops[1].eth_block, 10);
+        assert_eq!(ops[1].eth_hash, H256::repeat_byte(0x04).as_bytes().to_vec());
+    }
+
+    /// Checks that only the receipts with enough confirmations are considered final.
+    #[test]
+    fn receipt_confirmations() {
+        // Pending transaction.
+        assert!(!is_receipt_confirmed(None, 100, 0));
+        assert!(!is_receipt_confirmed(Some(95), 100, 10));
+        assert!(is_receipt_confirmed(Some(90), 100, 10));
+        assert!(is_receipt_confirmed(Some(100), 100, 0));
+        // Node lagging behind the block of the receipt.
+        assert!(!is_receipt_confirmed(Some(101), 100, 1));
+    }
 }
@@ -1,9 +1,13 @@
 // Built-in deps
 // External deps
-use num::{rational::Ratio, BigUint};
+use bigdecimal::BigDecimal;
+use num::{rational::Ratio, BigInt, BigUint, Zero};
 use serde::{Deserialize, Serialize};
 // Workspace deps
-use zksync_types::helpers::{pack_fee_amount, unpack_fee_amount};
+use zksync_types::{
+    helpers::{pack_fee_amount, unpack_fee_amount},
+    ChangePubKeyOp, TokenLike, TransferOp, TransferToNewOp, WithdrawOp,
+};
 use zksync_utils::{round_precision, BigUintSerdeAsRadix10Str};
 // Local deps
 
@@ -25,6 +29,29 @@ pub enum OutputFeeType {
     },
 }
 
+impl OutputFeeType {
+    /// Returns the name the quoted fee is recorded with in the fee history.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Transfer => "Transfer",
+            Self::TransferToNew => "TransferToNew",
+            Self::Withdraw => "Withdraw",
+            Self::FastWithdraw => "FastWithdraw",
+            Self::ChangePubKey { .. } => "ChangePubKey",
+        }
+    }
+
+    /// Returns the amount of block chunks taken by the operation the fee is paid for.
+    pub fn chunks(self) -> usize {
+        match self {
+            Self::Transfer => TransferOp::CHUNKS,
+            Self::TransferToNew => TransferToNewOp::CHUNKS,
+            Self::Withdraw | Self::FastWithdraw => WithdrawOp::CHUNKS,
+            Self::ChangePubKey { .. } => ChangePubKeyOp::CHUNKS,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Fee {
@@ -39,6 +66,10 @@ pub struct Fee {
     pub zkp_fee: BigUint,
     #[serde(with = "BigUintSerdeAsRadix10Str")]
     pub total_fee: BigUint,
+    /// Set if the fee is quoted at the token price that may be outdated, since the price
+    /// API is unavailable. Such a fee may change significantly once the price is updated.
+    #[serde(default)]
+    pub is_price_stale: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -46,6 +77,9 @@ pub struct Fee {
 pub struct BatchFee {
     #[serde(with = "BigUintSerdeAsRadix10Str")]
     pub total_fee: BigUint,
+    /// Set if the fee of any transaction is quoted at the outdated price.
+    #[serde(default)]
+    pub is_price_stale: bool,
 }
 
 impl Fee {
@@ -70,6 +104,95 @@ impl Fee {
             gas_fee,
             zkp_fee,
             total_fee,
+            is_price_stale: false,
         }
     }
 }
+
+/// Fee required for a single transaction of the batch.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchItemFee {
+    /// Token in which the fee is quoted. Transactions in tokens which are not allowed
+    /// for paying fees are quoted in ETH, since their fee must be covered by the other
+    /// transactions of the batch.
+    pub fee_token: TokenLike,
+    pub fee: Fee,
+    /// Price of the smallest unit of the fee token in USD.
+    pub token_price_usd: BigDecimal,
+    pub total_fee_usd: BigDecimal,
+}
+
+impl BatchItemFee {
+    pub fn new(fee_token: TokenLike, fee: Fee, token_price_usd: BigDecimal) -> Self {
+        let total_fee_usd = biguint_to_usd(&fee.total_fee, &token_price_usd);
+        Self {
+            fee_token,
+            fee,
+            token_price_usd,
+            total_fee_usd,
+        }
+    }
+
+    /// Converts the amount of the fee token into USD.
+    pub fn amount_to_usd(&self, amount: &BigUint) -> BigDecimal {
+        biguint_to_usd(amount, &self.token_price_usd)
+    }
+}
+
+/// Aggregate fee of the transactions batch.
+///
+/// Batch is charged in USD: it's accepted if the total fee provided by its transactions
+/// covers the `total_fee_usd`, regardless of the tokens the fee is paid in.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchFeeQuote {
+    pub items: Vec<BatchItemFee>,
+    /// Total amount of the L1 gas required for the batch.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub gas_tx_amount: BigUint,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub gas_price_wei: BigUint,
+    pub gas_fee_usd: BigDecimal,
+    pub zkp_fee_usd: BigDecimal,
+    pub total_fee_usd: BigDecimal,
+    /// Set if the fee of any transaction is quoted at the outdated price.
+    #[serde(default)]
+    pub is_price_stale: bool,
+}
+
+impl BatchFeeQuote {
+    pub fn new(items: Vec<BatchItemFee>) -> Self {
+        let mut gas_tx_amount = BigUint::zero();
+        let mut gas_fee_usd = BigDecimal::zero();
+        let mut zkp_fee_usd = BigDecimal::zero();
+        let mut total_fee_usd = BigDecimal::zero();
+        let mut is_price_stale = false;
+        for item in &items {
+            is_price_stale |= item.fee.is_price_stale;
+            gas_tx_amount += &item.fee.gas_tx_amount;
+            gas_fee_usd += item.amount_to_usd(&item.fee.gas_fee);
+            zkp_fee_usd += item.amount_to_usd(&item.fee.zkp_fee);
+            total_fee_usd += item.total_fee_usd.clone();
+        }
+        // Gas price is the same for every transaction quoted at once.
+        let gas_price_wei = items
+            .first()
+            .map(|item| item.fee.gas_price_wei.clone())
+            .unwrap_or_default();
+
+        Self {
+            items,
+            gas_tx_amount,
+            gas_price_wei,
+            gas_fee_usd,
+            zkp_fee_usd,
+            total_fee_usd,
+            is_price_stale,
+        }
+    }
+}
+
+fn biguint_to_usd(amount: &BigUint, token_price_usd: &BigDecimal) -> BigDecimal {
+    BigDecimal::from(BigInt::from(amount.clone())) * token_price_usd
+}
@@ -0,0 +1,109 @@
+//! Downsampling of the quoted fees recorded into the fee history.
+//!
+//! Fees are quoted on every user request, so only the first quote for the token and
+//! fee type within the configured interval is recorded. Quotes older than the retention
+//! period are removed from time to time.
+
+// Built-in deps
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+// External deps
+use chrono::{DateTime, Utc};
+// Workspace deps
+use zksync_types::TokenId;
+// Local deps
+use super::OutputFeeType;
+
+/// Interval between the removals of the outdated quotes.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(3600);
+
+#[derive(Debug)]
+pub(super) struct FeeHistoryRecorder {
+    /// Minimum interval between the recorded quotes of the same token and fee type.
+    interval: Duration,
+    /// Time the recorded quotes are kept for.
+    retention: Duration,
+    last_recorded: HashMap<(TokenId, OutputFeeType), Instant>,
+    last_pruned: Option<Instant>,
+}
+
+impl FeeHistoryRecorder {
+    pub fn new(interval: Duration, retention: Duration) -> Self {
+        Self {
+            interval,
+            retention,
+            last_recorded: HashMap::new(),
+            last_pruned: None,
+        }
+    }
+
+    /// Returns `true` if the quote has to be recorded, i.e. no quote of the same token
+    /// and fee type was recorded within the interval.
+    pub fn should_record(
+        &mut self,
+        token_id: TokenId,
+        fee_type: OutputFeeType,
+        now: Instant,
+    ) -> bool {
+        match self.last_recorded.get(&(token_id, fee_type)) {
+            Some(recorded_at) if now.saturating_duration_since(*recorded_at) < self.interval => {
+                false
+            }
+            _ => {
+                self.last_recorded.insert((token_id, fee_type), now);
+                true
+            }
+        }
+    }
+
+    /// Returns the time the quotes recorded before have to be removed,
+    /// or `None` if they were removed recently.
+    pub fn prune_before(&mut self, now: Instant) -> Option<DateTime<Utc>> {
+        if let Some(pruned_at) = self.last_pruned {
+            if now.saturating_duration_since(pruned_at) < PRUNE_INTERVAL {
+                return None;
+            }
+        }
+        self.last_pruned = Some(now);
+
+        let retention = chrono::Duration::from_std(self.retention).ok()?;
+        Some(Utc::now() - retention)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quotes_downsampling() {
+        let mut recorder =
+            FeeHistoryRecorder::new(Duration::from_secs(60), Duration::from_secs(3600));
+        let start = Instant::now();
+
+        assert!(recorder.should_record(0, OutputFeeType::Transfer, start));
+        assert!(!recorder.should_record(0, OutputFeeType::Transfer, start));
+        // Other tokens and fee types are recorded independently.
+        assert!(recorder.should_record(1, OutputFeeType::Transfer, start));
+        assert!(recorder.should_record(0, OutputFeeType::Withdraw, start));
+
+        let later = start + Duration::from_secs(30);
+        assert!(!recorder.should_record(0, OutputFeeType::Transfer, later));
+        let later = start + Duration::from_secs(60);
+        assert!(recorder.should_record(0, OutputFeeType::Transfer, later));
+    }
+
+    #[test]
+    fn history_pruning() {
+        let mut recorder =
+            FeeHistoryRecorder::new(Duration::from_secs(60), Duration::from_secs(3600));
+        let start = Instant::now();
+
+        let before = recorder.prune_before(start).unwrap();
+        assert!(before < Utc::now() - chrono::Duration::minutes(59));
+        assert!(recorder
+            .prune_before(start + Duration::from_secs(60))
+            .is_none());
+        assert!(recorder.prune_before(start + PRUNE_INTERVAL).is_some());
+    }
+}
@@ -6,10 +6,13 @@ use std::collections::{HashMap, HashSet};
 // Workspace uses
 use zksync_types::{
     tokens::{Token, TokenLike},
-    Address,
+    Address, TokenId,
 };
 // Local uses
-use crate::utils::token_db_cache::TokenDBCache;
+use crate::utils::{
+    runtime_settings::{LiquidityTier, RuntimeSettingsCache},
+    token_db_cache::TokenDBCache,
+};
 
 /// Fee token validator decides whether certain ERC20 token is suitable for paying fees.
 #[derive(Debug, Clone)]
@@ -17,6 +20,10 @@ pub(crate) struct FeeTokenValidator {
     tokens_cache: TokenCacheWrapper,
     /// List of tokens that aren't accepted to pay fees in.
     disabled_tokens: HashSet<Address>,
+    /// If set, only these tokens and ETH are accepted to pay fees in.
+    allowed_tokens: Option<HashSet<Address>>,
+    /// Settings changed through the admin API, they take precedence over `disabled_tokens`.
+    runtime_settings: Option<RuntimeSettingsCache>,
 }
 
 impl FeeTokenValidator {
@@ -27,9 +34,21 @@ impl FeeTokenValidator {
         Self {
             tokens_cache: cache.into(),
             disabled_tokens,
+            allowed_tokens: None,
+            runtime_settings: None,
         }
     }
 
+    pub(crate) fn with_allowed_tokens(mut self, allowed_tokens: Option<HashSet<Address>>) -> Self {
+        self.allowed_tokens = allowed_tokens;
+        self
+    }
+
+    pub(crate) fn with_runtime_settings(mut self, runtime_settings: RuntimeSettingsCache) -> Self {
+        self.runtime_settings = Some(runtime_settings);
+        self
+    }
+
     /// Returns `true` if token can be used to pay fees.
     pub(crate) async fn token_allowed(&self, token: TokenLike) -> anyhow::Result<bool> {
         let token = self.resolve_token(token).await?;
@@ -37,6 +56,17 @@ impl FeeTokenValidator {
         self.check_token(token).await
     }
 
+    /// Returns the market liquidity tier of the token if it was assessed through the admin API.
+    pub(crate) async fn liquidity_tier(
+        &self,
+        token_id: TokenId,
+    ) -> anyhow::Result<Option<LiquidityTier>> {
+        match &self.runtime_settings {
+            Some(runtime_settings) => Ok(runtime_settings.get().await?.liquidity_tier(token_id)),
+            None => Ok(None),
+        }
+    }
+
     async fn resolve_token(&self, token: TokenLike) -> anyhow::Result<Option<Token>> {
         self.tokens_cache.get_token(token).await
     }
@@ -47,8 +77,18 @@ impl FeeTokenValidator {
         // though it's not really `async` at this moment.
 
         if let Some(token) = token {
+            if let Some(runtime_settings) = &self.runtime_settings {
+                if let Some(allowed) = runtime_settings.get().await?.fee_allowed(token.id) {
+                    return Ok(allowed);
+                }
+            }
+
             let not_acceptable = self.disabled_tokens.contains(&token.address);
-            Ok(!not_acceptable)
+            let not_listed = match &self.allowed_tokens {
+                Some(allowed_tokens) => token.id != 0 && !allowed_tokens.contains(&token.address),
+                None => false,
+            };
+            Ok(!not_acceptable && !not_listed)
         } else {
             // Unknown tokens aren't suitable for our needs, obviously.
             Ok(false)
@@ -118,4 +158,37 @@ mod tests {
         assert_eq!(dai_allowed, true);
         assert_eq!(phnx_allowed, false);
     }
+
+    #[tokio::test]
+    async fn check_allowed_tokens() {
+        let eth_token = Token::new(0, Address::zero(), "ETH", 18);
+        let dai_token_address =
+            Address::from_str("6b175474e89094c44da98b954eedeac495271d0f").unwrap();
+        let dai_token = Token::new(1, dai_token_address, "DAI", 18);
+        let usdc_token_address =
+            Address::from_str("a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap();
+        let usdc_token = Token::new(2, usdc_token_address, "USDC", 6);
+
+        let mut tokens = HashMap::new();
+        tokens.insert(TokenLike::Id(0), eth_token);
+        tokens.insert(TokenLike::Address(dai_token_address), dai_token);
+        tokens.insert(TokenLike::Address(usdc_token_address), usdc_token);
+
+        let allowed_tokens = vec![dai_token_address].into_iter().collect();
+        let validator = FeeTokenValidator::new(tokens, HashSet::new())
+            .with_allowed_tokens(Some(allowed_tokens));
+
+        let eth_allowed = validator.token_allowed(TokenLike::Id(0)).await.unwrap();
+        let dai_allowed = validator
+            .token_allowed(TokenLike::Address(dai_token_address))
+            .await
+            .unwrap();
+        let usdc_allowed = validator
+            .token_allowed(TokenLike::Address(usdc_token_address))
+            .await
+            .unwrap();
+        assert_eq!(eth_allowed, true);
+        assert_eq!(dai_allowed, true);
+        assert_eq!(usdc_allowed, false);
+    }
 }
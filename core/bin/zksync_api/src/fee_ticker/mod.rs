@@ -5,6 +5,7 @@
 
 // Built-in deps
 use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
 // External deps
 use bigdecimal::BigDecimal;
 use futures::{
@@ -14,33 +15,34 @@ use futures::{
 use num::{
     rational::Ratio,
     traits::{Inv, Pow},
-    BigUint,
+    BigInt, BigUint,
 };
 use serde::{Deserialize, Serialize};
 use tokio::task::JoinHandle;
 // Workspace deps
-use zksync_config::{FeeTickerOptions, TokenPriceSource};
-use zksync_storage::ConnectionPool;
-use zksync_types::{
-    Address, ChangePubKeyOp, Token, TokenId, TokenLike, TransferOp, TransferToNewOp, TxFeeTypes,
-    WithdrawOp,
-};
+use zksync_config::{loader::config_generation, FeeTickerOptions, TokenPriceSource};
+use zksync_prometheus_exporter::{register_metrics, Metric};
+use zksync_storage::{fee_history::records::NewFeeQuote, ConnectionPool};
+use zksync_types::{Address, Token, TokenId, TokenLike, TxFeeTypes};
 use zksync_utils::ratio_to_big_decimal;
 // Local deps
 use crate::fee_ticker::{
+    fee_history::FeeHistoryRecorder,
     fee_token_validator::FeeTokenValidator,
     ticker_api::{
-        coingecko::CoinGeckoAPI, coinmarkercap::CoinMarketCapAPI, FeeTickerAPI, TickerApi,
-        CONNECTION_TIMEOUT,
+        coingecko::CoinGeckoAPI, coinmarkercap::CoinMarketCapAPI,
+        multi_source::MultiSourcePriceAPI, static_prices::StaticPriceAPI, uniswap::UniswapTwapAPI,
+        FeeTickerAPI, TickerApi, TokenPriceAPI, CONNECTION_TIMEOUT,
     },
     ticker_info::{FeeTickerInfo, TickerInfo},
 };
-use crate::utils::token_db_cache::TokenDBCache;
+use crate::utils::{runtime_settings::RuntimeSettingsCache, token_db_cache::TokenDBCache};
 
 pub use self::fee::*;
 
 mod constants;
 mod fee;
+mod fee_history;
 mod fee_token_validator;
 mod ticker_api;
 mod ticker_info;
@@ -48,6 +50,44 @@ mod ticker_info;
 #[cfg(test)]
 mod tests;
 
+/// Metrics reported by the fee ticker.
+const METRICS: &[Metric] = &[
+    Metric::histogram(
+        "ticker.get_last_quote",
+        "Time spent on loading a token price",
+    ),
+    Metric::histogram(
+        "ticker.get_gas_price_wei",
+        "Time spent on loading the gas price",
+    ),
+    Metric::gauge("ticker.gas_price_wei", "Gas price used for the fee quotes"),
+    Metric::gauge(
+        "ticker.observed_gas_price_wei",
+        "Gas price observed on the Ethereum node",
+    ),
+    Metric::counter(
+        "ticker.price_cache_hits",
+        "Number of the token prices served from the cache",
+    ),
+    Metric::counter(
+        "ticker.price_cache_misses",
+        "Number of the token prices requested from the price API",
+    ),
+    Metric::histogram("ticker.price_age", "Age of the served token prices"),
+    Metric::counter(
+        "ticker.stale_prices_served",
+        "Number of the stale token prices served while the price API is unavailable",
+    ),
+    Metric::counter(
+        "ticker.price_anomalies",
+        "Number of the token prices rejected as anomalous",
+    ),
+    Metric::counter(
+        "ticker.fee_anomalies",
+        "Number of the fee quotes rejected as exceeding the bound",
+    ),
+];
+
 /// Contains cost of zkSync operations in Wei.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct GasOperationsCost {
@@ -142,7 +182,11 @@ pub struct TickerConfig {
     zkp_cost_chunk_usd: Ratio<BigUint>,
     gas_cost_tx: GasOperationsCost,
     tokens_risk_factors: HashMap<TokenId, Ratio<BigUint>>,
+    /// Multiplier of the fee paid in any token other than ETH.
+    token_fee_markup: Ratio<BigUint>,
     not_subsidized_tokens: HashSet<Address>,
+    /// Maximum fee in USD per fee type name, quotes exceeding it are rejected.
+    max_fee_usd: HashMap<String, Ratio<BigUint>>,
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -176,6 +220,14 @@ struct FeeTicker<API, INFO> {
     requests: Receiver<TickerRequest>,
     config: TickerConfig,
     validator: FeeTokenValidator,
+    /// Recorder of the quoted fees, `None` if the fee history is disabled.
+    fee_history: Option<FeeHistoryRecorder>,
+    /// Configuration generation the fee coefficients were read at.
+    config_generation: u64,
+}
+
+fn token_fee_markup(markup_percent: u32) -> Ratio<BigUint> {
+    Ratio::new(BigUint::from(100 + markup_percent), BigUint::from(100u32))
 }
 
 #[must_use]
@@ -183,56 +235,88 @@ pub fn run_ticker_task(
     db_pool: ConnectionPool,
     tricker_requests: Receiver<TickerRequest>,
 ) -> JoinHandle<()> {
+    register_metrics("fee ticker", METRICS);
+
     let config = FeeTickerOptions::from_env();
 
     let ticker_config = TickerConfig {
         zkp_cost_chunk_usd: Ratio::from_integer(BigUint::from(10u32).pow(3u32)).inv(),
         gas_cost_tx: GasOperationsCost::from_constants(config.fast_processing_coeff),
         tokens_risk_factors: HashMap::new(),
+        token_fee_markup: token_fee_markup(config.token_fee_markup_percent),
         not_subsidized_tokens: config.not_subsidized_tokens,
+        max_fee_usd: config.max_fee_usd,
     };
 
     let cache = TokenDBCache::new(db_pool.clone());
-    let validator = FeeTokenValidator::new(cache, config.disabled_tokens);
+    let validator = FeeTokenValidator::new(cache, config.disabled_tokens)
+        .with_allowed_tokens(config.allowed_tokens)
+        .with_runtime_settings(RuntimeSettingsCache::new(db_pool.clone()));
 
     let client = reqwest::ClientBuilder::new()
         .timeout(CONNECTION_TIMEOUT)
         .connect_timeout(CONNECTION_TIMEOUT)
         .build()
         .expect("Failed to build reqwest::Client");
-    match config.token_price_source {
-        TokenPriceSource::CoinMarketCap { base_url } => {
-            let token_price_api = CoinMarketCapAPI::new(client, base_url);
-
-            let ticker_api = TickerApi::new(db_pool.clone(), token_price_api);
-            let ticker_info = TickerInfo::new(db_pool);
-            let fee_ticker = FeeTicker::new(
-                ticker_api,
-                ticker_info,
-                tricker_requests,
-                ticker_config,
-                validator,
-            );
-
-            tokio::spawn(fee_ticker.run())
-        }
-        TokenPriceSource::CoinGecko { base_url } => {
-            let token_price_api =
-                CoinGeckoAPI::new(client, base_url).expect("failed to init CoinGecko client");
-
-            let ticker_api = TickerApi::new(db_pool.clone(), token_price_api);
-            let ticker_info = TickerInfo::new(db_pool);
-            let fee_ticker = FeeTicker::new(
-                ticker_api,
-                ticker_info,
-                tricker_requests,
-                ticker_config,
-                validator,
-            );
-
-            tokio::spawn(fee_ticker.run())
-        }
+    let token_price_sources = config
+        .token_price_sources
+        .into_iter()
+        .map(|source| -> Box<dyn TokenPriceAPI + Send + Sync> {
+            match source {
+                TokenPriceSource::CoinMarketCap { base_url } => {
+                    Box::new(CoinMarketCapAPI::new(client.clone(), base_url))
+                }
+                TokenPriceSource::CoinGecko { base_url } => Box::new(
+                    CoinGeckoAPI::new(client.clone(), base_url)
+                        .expect("failed to init CoinGecko client"),
+                ),
+                TokenPriceSource::UniswapTwap {
+                    web3_url,
+                    pairs,
+                    quote_token,
+                    window,
+                } => {
+                    let transport = web3::transports::Http::new(&web3_url).unwrap();
+                    Box::new(UniswapTwapAPI::new(
+                        web3::Web3::new(transport),
+                        pairs,
+                        quote_token,
+                        window,
+                    ))
+                }
+                TokenPriceSource::Static { prices } => Box::new(StaticPriceAPI::new(prices)),
+            }
+        })
+        .collect();
+    let token_price_api = MultiSourcePriceAPI::new(token_price_sources, config.token_price_policy);
+
+    let ticker_api = TickerApi::new(
+        db_pool.clone(),
+        token_price_api,
+        config.price_cache_ttl,
+        config.stale_price_grace_period,
+    )
+    .with_gas_price_smoothing(
+        config.gas_price_refresh_interval,
+        config.gas_price_smoothing_factor,
+    );
+    let ticker_api = match config.max_price_change_percent {
+        Some(percent) => ticker_api.with_max_price_change(percent),
+        None => ticker_api,
+    };
+    let ticker_info = TickerInfo::new(db_pool);
+    let mut fee_ticker = FeeTicker::new(
+        ticker_api,
+        ticker_info,
+        tricker_requests,
+        ticker_config,
+        validator,
+    );
+    if let Some(interval) = config.fee_history_interval {
+        fee_ticker = fee_ticker.with_fee_history(interval, config.fee_history_retention);
     }
+
+    tokio::spawn(fee_ticker.run())
 }
 
 impl<API: FeeTickerAPI, INFO: FeeTickerInfo> FeeTicker<API, INFO> {
@@ -249,6 +333,73 @@ impl<API: FeeTickerAPI, INFO: FeeTickerInfo> FeeTicker<API, INFO> {
             requests,
             config,
             validator,
+            fee_history: None,
+            config_generation: config_generation(),
+        }
+    }
+
+    /// Applies the fee coefficients changed by the configuration reload.
+    fn reload_config(&mut self) {
+        let generation = config_generation();
+        if generation == self.config_generation {
+            return;
+        }
+        self.config_generation = generation;
+
+        let options = FeeTickerOptions::from_env();
+        self.config.gas_cost_tx = GasOperationsCost::from_constants(options.fast_processing_coeff);
+        self.config.token_fee_markup = token_fee_markup(options.token_fee_markup_percent);
+        tracing::info!(
+            "Fee coefficients reloaded: fast processing {}, token fee markup {}%",
+            options.fast_processing_coeff,
+            options.token_fee_markup_percent
+        );
+    }
+
+    /// Enables the recording of the quoted fees, at most one quote for the token and fee type
+    /// is recorded within the interval.
+    fn with_fee_history(mut self, interval: Duration, retention: Duration) -> Self {
+        self.fee_history = Some(FeeHistoryRecorder::new(interval, retention));
+        self
+    }
+
+    /// Records the quoted fee along with the USD prices of the whole token and ETH
+    /// it was calculated with, unless a quote for the same token and fee type was recorded recently.
+    async fn record_fee_quote(
+        &mut self,
+        token: &Token,
+        fee: &Fee,
+        token_price_usd: &Ratio<BigUint>,
+        eth_price_usd: &Ratio<BigUint>,
+    ) {
+        let now = Instant::now();
+        let prune_before = match &mut self.fee_history {
+            Some(fee_history) if fee_history.should_record(token.id, fee.fee_type, now) => {
+                fee_history.prune_before(now)
+            }
+            _ => return,
+        };
+
+        let to_decimal = |value: &BigUint| BigDecimal::from(BigInt::from(value.clone()));
+        let quote = NewFeeQuote {
+            token_id: i32::from(token.id),
+            fee_type: fee.fee_type.name().to_string(),
+            gas_tx_amount: to_decimal(&fee.gas_tx_amount),
+            gas_price_wei: to_decimal(&fee.gas_price_wei),
+            gas_fee: to_decimal(&fee.gas_fee),
+            zkp_fee: to_decimal(&fee.zkp_fee),
+            total_fee: to_decimal(&fee.total_fee),
+            token_price_usd: ratio_to_big_decimal(token_price_usd, 18),
+            eth_price_usd: ratio_to_big_decimal(eth_price_usd, 18),
+        };
+        if let Err(err) = self.info.store_fee_quote(quote).await {
+            tracing::warn!("Failed to record the fee quote: {}", err);
+        }
+
+        if let Some(before) = prune_before {
+            if let Err(err) = self.info.remove_fee_history_before(before).await {
+                tracing::warn!("Failed to remove the outdated fee quotes: {}", err);
+            }
         }
     }
 
@@ -298,7 +449,7 @@ impl<API: FeeTickerAPI, INFO: FeeTickerInfo> FeeTicker<API, INFO> {
         self.api
             .get_last_quote(token)
             .await
-            .map(|price| ratio_to_big_decimal(&(price.usd_price / factor), 100))
+            .map(|quote| ratio_to_big_decimal(&(quote.price.usd_price / factor), 100))
     }
 
     /// Returns `true` if account does not yet exist in the zkSync network.
@@ -311,42 +462,77 @@ impl<API: FeeTickerAPI, INFO: FeeTickerInfo> FeeTicker<API, INFO> {
         !self.config.not_subsidized_tokens.contains(&token.address)
     }
 
+    /// Ensures that the fee doesn't exceed the maximum fee in USD configured for its type,
+    /// since such a fee is most likely the result of the wrong price data.
+    fn check_fee_bound(
+        &self,
+        token: &Token,
+        fee: &Fee,
+        token_price_usd: &Ratio<BigUint>,
+    ) -> Result<(), anyhow::Error> {
+        let max_fee_usd = match self.config.max_fee_usd.get(fee.fee_type.name()) {
+            Some(max_fee_usd) => max_fee_usd,
+            None => return Ok(()),
+        };
+        let fee_usd = Ratio::from_integer(fee.total_fee.clone()) * token_price_usd;
+        if &fee_usd <= max_fee_usd {
+            return Ok(());
+        }
+
+        vlog::critical!(
+            "{} fee in {} is {} USD, which exceeds the maximum of {} USD",
+            fee.fee_type.name(),
+            token.symbol,
+            ratio_to_big_decimal(&fee_usd, 6),
+            ratio_to_big_decimal(max_fee_usd, 6)
+        );
+        metrics::counter!("ticker.fee_anomalies", 1);
+        anyhow::bail!("Fee can't be quoted right now")
+    }
+
     async fn get_fee_from_ticker_in_wei(
         &mut self,
         tx_type: TxFeeTypes,
         token: TokenLike,
         recipient: Address,
     ) -> Result<Fee, anyhow::Error> {
+        self.reload_config();
         let zkp_cost_chunk = self.config.zkp_cost_chunk_usd.clone();
         let token = self.api.get_token(token).await?;
-        let token_risk_factor = self
+        let mut token_risk_factor = self
             .config
             .tokens_risk_factors
             .get(&token.id)
             .cloned()
             .unwrap_or_else(|| Ratio::from_integer(1u32.into()));
+        // Fee paid in tokens other than ETH is converted at the ticker rate, which may change
+        // before the fee is collected, so the markup is added.
+        if token.id != 0 {
+            token_risk_factor *= self.config.token_fee_markup.clone();
+        }
+        // Fees collected in the less liquid tokens are sold with a higher slippage.
+        if let Some(tier) = self.validator.liquidity_tier(token.id).await? {
+            token_risk_factor *= tier.fee_risk_factor();
+        }
 
-        let (fee_type, op_chunks) = match tx_type {
-            TxFeeTypes::Withdraw => (OutputFeeType::Withdraw, WithdrawOp::CHUNKS),
-            TxFeeTypes::FastWithdraw => (OutputFeeType::FastWithdraw, WithdrawOp::CHUNKS),
+        let fee_type = match tx_type {
+            TxFeeTypes::Withdraw => OutputFeeType::Withdraw,
+            TxFeeTypes::FastWithdraw => OutputFeeType::FastWithdraw,
             TxFeeTypes::Transfer => {
                 if self.is_account_new(recipient).await {
-                    (OutputFeeType::TransferToNew, TransferToNewOp::CHUNKS)
+                    OutputFeeType::TransferToNew
                 } else {
-                    (OutputFeeType::Transfer, TransferOp::CHUNKS)
+                    OutputFeeType::Transfer
                 }
             }
             TxFeeTypes::ChangePubKey {
                 onchain_pubkey_auth,
-            } => (
-                OutputFeeType::ChangePubKey {
-                    onchain_pubkey_auth,
-                },
-                ChangePubKeyOp::CHUNKS,
-            ),
+            } => OutputFeeType::ChangePubKey {
+                onchain_pubkey_auth,
+            },
         };
         // Convert chunks amount to `BigUint`.
-        let op_chunks = BigUint::from(op_chunks);
+        let op_chunks = BigUint::from(fee_type.chunks());
         let gas_tx_amount = {
             let is_token_subsidized = self.is_token_subsidized(token.clone()).await;
             if is_token_subsidized {
@@ -366,28 +552,26 @@ impl<API: FeeTickerAPI, INFO: FeeTickerInfo> FeeTicker<API, INFO> {
             }
         };
         let gas_price_wei = self.api.get_gas_price_wei().await?;
-        let wei_price_usd = self.api.get_last_quote(TokenLike::Id(0)).await?.usd_price
-            / BigUint::from(10u32).pow(18u32);
+        let eth_quote = self.api.get_last_quote(TokenLike::Id(0)).await?;
+        let eth_price_usd = eth_quote.price.usd_price;
+        let wei_price_usd = eth_price_usd.clone() / BigUint::from(10u32).pow(18u32);
 
-        let token_price_usd = self
-            .api
-            .get_last_quote(TokenLike::Id(token.id))
-            .await?
-            .usd_price
-            / BigUint::from(10u32).pow(u32::from(token.decimals));
+        let token_quote = self.api.get_last_quote(TokenLike::Id(token.id)).await?;
+        let whole_token_price_usd = token_quote.price.usd_price;
+        let token_price_usd =
+            whole_token_price_usd.clone() / BigUint::from(10u32).pow(u32::from(token.decimals));
 
         let zkp_fee =
             (zkp_cost_chunk * op_chunks) * token_risk_factor.clone() / token_price_usd.clone();
         let gas_fee = (wei_price_usd * gas_tx_amount.clone() * gas_price_wei.clone())
             * token_risk_factor
-            / token_price_usd;
-
-        Ok(Fee::new(
-            fee_type,
-            zkp_fee,
-            gas_fee,
-            gas_tx_amount,
-            gas_price_wei,
-        ))
+            / token_price_usd.clone();
+
+        let mut fee = Fee::new(fee_type, zkp_fee, gas_fee, gas_tx_amount, gas_price_wei);
+        fee.is_price_stale = eth_quote.is_stale || token_quote.is_stale;
+        self.check_fee_bound(&token, &fee, &token_price_usd)?;
+        self.record_fee_quote(&token, &fee, &whole_token_price_usd, &eth_price_usd)
+            .await;
+        Ok(fee)
     }
 }
@@ -1,10 +1,12 @@
+use super::ticker_api::TokenQuote;
 use super::*;
 use async_trait::async_trait;
 use bigdecimal::BigDecimal;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use futures::channel::mpsc;
 use futures::executor::block_on;
 use std::str::FromStr;
+use zksync_storage::fee_history::records::NewFeeQuote;
 use zksync_types::{Address, Token, TokenId, TokenPrice};
 use zksync_utils::{ratio_to_big_decimal, UnsignedRatioSerializeAsDecimal};
 
@@ -87,25 +89,27 @@ fn get_test_ticker_config() -> TickerConfig {
                 t.risk_factor.map(|risk| (id, risk))
             })
             .collect(),
+        token_fee_markup: Ratio::from_integer(1u32.into()),
         not_subsidized_tokens: vec![
             Address::from_str("34083bbd70d394110487feaa087da875a54624ec").unwrap(),
         ]
         .into_iter()
         .collect(),
+        max_fee_usd: HashMap::new(),
     }
 }
 
 struct MockApiProvider;
 #[async_trait]
 impl FeeTickerAPI for MockApiProvider {
-    async fn get_last_quote(&self, token: TokenLike) -> Result<TokenPrice, anyhow::Error> {
+    async fn get_last_quote(&self, token: TokenLike) -> Result<TokenQuote, anyhow::Error> {
         for test_token in TestToken::all_tokens() {
             if TokenLike::Id(test_token.id) == token {
                 let token_price = TokenPrice {
                     usd_price: test_token.price_usd,
                     last_updated: Utc::now(),
                 };
-                return Ok(token_price);
+                return Ok(TokenQuote::new(token_price, false));
             }
         }
         unreachable!("incorrect token input")
@@ -141,6 +145,25 @@ impl FeeTickerAPI for MockApiProvider {
     }
 }
 
+/// Serves the prices of `MockApiProvider`, but the prices of the tokens other than ETH are stale.
+struct StalePriceApiProvider;
+#[async_trait]
+impl FeeTickerAPI for StalePriceApiProvider {
+    async fn get_last_quote(&self, token: TokenLike) -> Result<TokenQuote, anyhow::Error> {
+        let mut quote = MockApiProvider.get_last_quote(token.clone()).await?;
+        quote.is_stale = token != TokenLike::Id(0);
+        Ok(quote)
+    }
+
+    async fn get_gas_price_wei(&self) -> Result<BigUint, anyhow::Error> {
+        MockApiProvider.get_gas_price_wei().await
+    }
+
+    async fn get_token(&self, token: TokenLike) -> Result<Token, anyhow::Error> {
+        MockApiProvider.get_token(token).await
+    }
+}
+
 struct MockTickerInfo;
 
 #[async_trait]
@@ -149,6 +172,14 @@ impl FeeTickerInfo for MockTickerInfo {
         // Always false for simplicity.
         false
     }
+
+    async fn store_fee_quote(&mut self, _quote: NewFeeQuote) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn remove_fee_history_before(&mut self, _before: DateTime<Utc>) -> anyhow::Result<()> {
+        Ok(())
+    }
 }
 
 fn format_with_dot(num: &Ratio<BigUint>, precision: usize) -> String {
@@ -180,6 +211,7 @@ fn test_ticker_formula() {
             // Fee in usd
             (block_on(MockApiProvider.get_last_quote(token))
                 .expect("failed to get fee in usd")
+                .price
                 .usd_price
                 / BigUint::from(10u32).pow(u32::from(token_precision)))
                 * fee_in_token.total_fee
@@ -313,3 +345,255 @@ fn test_fee_for_unsubsidized_tokens() {
         }
     }
 }
+
+#[test]
+fn test_token_fee_markup() {
+    let get_transfer_fee = |token_fee_markup: Ratio<BigUint>, token: TokenLike| {
+        let config = TickerConfig {
+            token_fee_markup,
+            ..get_test_ticker_config()
+        };
+        let mut ticker = FeeTicker::new(
+            MockApiProvider,
+            MockTickerInfo,
+            mpsc::channel(1).1,
+            config,
+            FeeTokenValidator::new(HashMap::new(), Default::default()),
+        );
+        block_on(ticker.get_fee_from_ticker_in_wei(TxFeeTypes::Transfer, token, Address::default()))
+            .expect("failed to get fee in token")
+    };
+    let no_markup = || Ratio::from_integer(1u32.into());
+    let markup = || Ratio::new(BigUint::from(110u32), BigUint::from(100u32));
+
+    // Fee paid in ETH isn't affected by the markup.
+    let eth_fee = get_transfer_fee(no_markup(), 0.into());
+    let eth_fee_with_markup = get_transfer_fee(markup(), 0.into());
+    assert_eq!(eth_fee.total_fee, eth_fee_with_markup.total_fee);
+
+    // Fee paid in other tokens is increased by the markup.
+    let token_fee = get_transfer_fee(no_markup(), TestToken::cheap().id.into());
+    let token_fee_with_markup = get_transfer_fee(markup(), TestToken::cheap().id.into());
+    let expected_fee = Ratio::from_integer(token_fee.total_fee) * markup();
+    let actual_fee = Ratio::from_integer(token_fee_with_markup.total_fee);
+    let max = std::cmp::max(&expected_fee, &actual_fee);
+    let min = std::cmp::min(&expected_fee, &actual_fee);
+    // Total fee is packed, so it's compared up to the packing precision.
+    let diff = ratio_to_big_decimal(&((max - min) / min), 6);
+    assert!(diff <= BigDecimal::from_str("0.01").unwrap());
+}
+
+#[test]
+fn test_fee_bound() {
+    let get_transfer_fee = |max_fee_usd: &str| {
+        let config = TickerConfig {
+            max_fee_usd: vec![(
+                "Transfer".to_string(),
+                UnsignedRatioSerializeAsDecimal::deserialize_from_str_with_dot(max_fee_usd)
+                    .unwrap(),
+            )]
+            .into_iter()
+            .collect(),
+            ..get_test_ticker_config()
+        };
+        let mut ticker = FeeTicker::new(
+            MockApiProvider,
+            MockTickerInfo,
+            mpsc::channel(1).1,
+            config,
+            FeeTokenValidator::new(HashMap::new(), Default::default()),
+        );
+        block_on(ticker.get_fee_from_ticker_in_wei(
+            TxFeeTypes::Transfer,
+            TestToken::eth().id.into(),
+            Address::default(),
+        ))
+    };
+
+    assert!(get_transfer_fee("1000").is_ok());
+    assert!(get_transfer_fee("0.000001").is_err());
+}
+
+#[test]
+fn test_price_change_bound() {
+    use super::ticker_api::is_price_change_allowed;
+
+    let price = |value: &str| {
+        UnsignedRatioSerializeAsDecimal::deserialize_from_str_with_dot(value).unwrap()
+    };
+    let max_change = Ratio::new(BigUint::from(50u32), BigUint::from(100u32));
+
+    assert!(is_price_change_allowed(
+        &price("100"),
+        &price("140"),
+        &max_change
+    ));
+    assert!(is_price_change_allowed(
+        &price("100"),
+        &price("50"),
+        &max_change
+    ));
+    assert!(!is_price_change_allowed(
+        &price("100"),
+        &price("151"),
+        &max_change
+    ));
+    assert!(!is_price_change_allowed(
+        &price("100"),
+        &price("0.01"),
+        &max_change
+    ));
+    // There is nothing to compare with if the previous price is zero.
+    assert!(is_price_change_allowed(
+        &price("0"),
+        &price("100"),
+        &max_change
+    ));
+}
+
+#[test]
+fn test_batch_fee_quote() {
+    let item = |token: TokenLike, gas_fee: u64, zkp_fee: u64, price_usd: &str| {
+        let fee = Fee::new(
+            OutputFeeType::Transfer,
+            Ratio::from_integer(zkp_fee.into()),
+            Ratio::from_integer(gas_fee.into()),
+            100u64.into(),
+            10u64.into(),
+        );
+        BatchItemFee::new(token, fee, BigDecimal::from_str(price_usd).unwrap())
+    };
+
+    let quote = BatchFeeQuote::new(vec![
+        item(TokenLike::Id(0), 1000, 500, "0.001"),
+        item(TokenLike::Id(1), 2000, 1000, "0.002"),
+    ]);
+
+    assert_eq!(quote.items.len(), 2);
+    assert_eq!(quote.gas_tx_amount, BigUint::from(200u32));
+    assert_eq!(quote.gas_price_wei, BigUint::from(10u32));
+    assert_eq!(quote.gas_fee_usd, BigDecimal::from_str("5").unwrap());
+    assert_eq!(quote.zkp_fee_usd, BigDecimal::from_str("2.5").unwrap());
+    // Total fee of each item is packed, so it's compared to the sum of the items.
+    let expected_total: BigDecimal = quote
+        .items
+        .iter()
+        .map(|item| item.total_fee_usd.clone())
+        .fold(BigDecimal::from(0), |acc, fee| acc + fee);
+    assert_eq!(quote.total_fee_usd, expected_total);
+    assert_eq!(quote.total_fee_usd, BigDecimal::from_str("7.5").unwrap());
+}
+
+#[test]
+fn test_gas_price_smoothing() {
+    use super::ticker_api::smooth_gas_price;
+
+    // The first observed price is used as is.
+    assert_eq!(smooth_gas_price(None, 100, 0.5), 100);
+    // Spike is only partially reflected in the quoted price.
+    assert_eq!(smooth_gas_price(Some(100), 300, 0.5), 200);
+    assert_eq!(smooth_gas_price(Some(200), 300, 0.5), 250);
+    // Factor of 1 disables the smoothing.
+    assert_eq!(smooth_gas_price(Some(100), 300, 1.0), 300);
+    // Out of range factor is clamped.
+    assert_eq!(smooth_gas_price(Some(100), 300, 2.0), 300);
+}
+
+fn price_updated_ago(secs: i64) -> TokenPrice {
+    TokenPrice {
+        usd_price: Ratio::from_integer(1u32.into()),
+        last_updated: Utc::now() - chrono::Duration::seconds(secs),
+    }
+}
+
+#[test]
+fn test_price_ttl() {
+    use super::ticker_api::{is_cache_entry_actual, TokenCacheEntry};
+
+    let ttl = Duration::from_secs(60);
+    let grace_period = Duration::from_secs(600);
+    let entry = |updated_ago: i64| {
+        TokenCacheEntry::new(price_updated_ago(updated_ago), Instant::now(), false)
+    };
+
+    // The up to date price is served from the cache during its TTL.
+    assert!(is_cache_entry_actual(&entry(10), ttl, grace_period));
+    assert!(is_cache_entry_actual(&entry(59), ttl, grace_period));
+    // The expired price is fetched again, even though it's within the grace period.
+    assert!(!is_cache_entry_actual(&entry(61), ttl, grace_period));
+    assert!(!is_cache_entry_actual(&entry(300), ttl, grace_period));
+}
+
+#[test]
+fn test_stale_price_grace_period() {
+    use super::ticker_api::{is_cache_entry_actual, is_price_servable, TokenCacheEntry};
+
+    let ttl = Duration::from_secs(60);
+    let grace_period = Duration::from_secs(120);
+
+    // The expired price is served while the price API is unavailable until the grace period ends.
+    assert!(is_price_servable(&price_updated_ago(30), ttl, grace_period));
+    assert!(is_price_servable(
+        &price_updated_ago(170),
+        ttl,
+        grace_period
+    ));
+    assert!(!is_price_servable(
+        &price_updated_ago(190),
+        ttl,
+        grace_period
+    ));
+
+    // The stale price is served from the cache until the next attempt to fetch the price.
+    let stale_entry = |updated_ago: i64, cached_ago: u64| {
+        let creation_time = Instant::now()
+            .checked_sub(Duration::from_secs(cached_ago))
+            .unwrap();
+        TokenCacheEntry::new(price_updated_ago(updated_ago), creation_time, true)
+    };
+    assert!(is_cache_entry_actual(
+        &stale_entry(100, 0),
+        ttl,
+        grace_period
+    ));
+    assert!(!is_cache_entry_actual(
+        &stale_entry(100, 61),
+        ttl,
+        grace_period
+    ));
+    // The stale price is not served beyond the grace period, even if it was cached recently.
+    assert!(!is_cache_entry_actual(
+        &stale_entry(190, 0),
+        ttl,
+        grace_period
+    ));
+}
+
+#[test]
+fn test_stale_price_flag() {
+    let get_transfer_fee = |token: TokenLike| {
+        let mut ticker = FeeTicker::new(
+            StalePriceApiProvider,
+            MockTickerInfo,
+            mpsc::channel(1).1,
+            get_test_ticker_config(),
+            FeeTokenValidator::new(HashMap::new(), Default::default()),
+        );
+        block_on(ticker.get_fee_from_ticker_in_wei(TxFeeTypes::Transfer, token, Address::default()))
+            .unwrap()
+    };
+
+    // ETH price is up to date.
+    assert!(!get_transfer_fee(TestToken::eth().id.into()).is_price_stale);
+    // Fee in the token with the stale price is marked.
+    let fee = get_transfer_fee(TestToken::hex().id.into());
+    assert!(fee.is_price_stale);
+
+    // Batch quote is marked if any of its items is.
+    let quote = BatchFeeQuote::new(vec![BatchItemFee::new(
+        TestToken::hex().id.into(),
+        fee,
+        BigDecimal::from(1),
+    )]);
+    assert!(quote.is_price_stale);
+}
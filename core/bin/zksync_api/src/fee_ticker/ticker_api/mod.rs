@@ -9,12 +9,16 @@ use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
 use zksync_storage::ConnectionPool;
 use zksync_types::{Token, TokenId, TokenLike, TokenPrice};
+use zksync_utils::ratio_to_big_decimal;
 
 pub mod coingecko;
 pub mod coinmarkercap;
+pub mod multi_source;
+pub mod static_prices;
+pub mod uniswap;
 
-const API_PRICE_EXPIRATION_TIME_SECS: i64 = 300; // 5 mins
-const HISTORICAL_PRICE_EXPIRATION_TIME: Duration = Duration::from_secs(60);
+/// Interval between the attempts to fetch the price if the stale one is served.
+const STALE_PRICE_RETRY_INTERVAL: Duration = Duration::from_secs(60);
 
 /// The limit of time we are willing to wait for response.
 pub const REQUEST_TIMEOUT: Duration = Duration::from_millis(700);
@@ -26,11 +30,26 @@ pub trait TokenPriceAPI {
     async fn get_price(&self, token_symbol: &str) -> Result<TokenPrice, anyhow::Error>;
 }
 
+/// Price of the token quoted by the ticker.
+#[derive(Debug, Clone)]
+pub struct TokenQuote {
+    pub price: TokenPrice,
+    /// Set if the price is served while the price API is unavailable (or while the fetched
+    /// price is rejected as an outlier), so the price may be outdated.
+    pub is_stale: bool,
+}
+
+impl TokenQuote {
+    pub fn new(price: TokenPrice, is_stale: bool) -> Self {
+        Self { price, is_stale }
+    }
+}
+
 /// Api responsible for querying for TokenPrices
 #[async_trait]
 pub trait FeeTickerAPI {
     /// Get last price from ticker
-    async fn get_last_quote(&self, token: TokenLike) -> Result<TokenPrice, anyhow::Error>;
+    async fn get_last_quote(&self, token: TokenLike) -> Result<TokenQuote, anyhow::Error>;
 
     /// Get current gas price in ETH
     async fn get_gas_price_wei(&self) -> Result<BigUint, anyhow::Error>;
@@ -39,35 +58,83 @@ pub trait FeeTickerAPI {
 }
 
 #[derive(Debug, Clone)]
-struct TokenCacheEntry {
+pub(super) struct TokenCacheEntry {
     price: TokenPrice,
     creation_time: Instant,
-    is_price_historical: bool,
+    /// Set if the price is served while the price API is unavailable.
+    is_price_stale: bool,
 }
 
 impl TokenCacheEntry {
-    fn new(price: TokenPrice, creation_time: Instant, is_price_historical: bool) -> Self {
+    pub(super) fn new(price: TokenPrice, creation_time: Instant, is_price_stale: bool) -> Self {
         Self {
             price,
             creation_time,
-            is_price_historical,
+            is_price_stale,
         }
     }
+}
 
-    fn is_price_expired(&self) -> bool {
-        Utc::now()
-            .signed_duration_since(self.price.last_updated)
-            .num_seconds()
-            > API_PRICE_EXPIRATION_TIME_SECS
+/// Blends the newly observed gas price into the previously quoted one, so the fees follow
+/// the L1 gas price without jumping on its short spikes. The factor is the weight
+/// (from 0 to 1) of the observed price.
+pub(super) fn smooth_gas_price(previous: Option<u64>, observed: u64, factor: f64) -> u64 {
+    let previous = match previous {
+        Some(previous) => previous,
+        None => return observed,
+    };
+    let factor = factor.max(0.0).min(1.0);
+    (observed as f64 * factor + previous as f64 * (1.0 - factor)).round() as u64
+}
+
+/// Checks that the price moved from the previous one by no more than the allowed
+/// relative change (e.g. `1/2` for 50%).
+pub(super) fn is_price_change_allowed(
+    previous: &Ratio<BigUint>,
+    price: &Ratio<BigUint>,
+    max_change: &Ratio<BigUint>,
+) -> bool {
+    if previous == &Ratio::from_integer(BigUint::from(0u32)) {
+        return true;
     }
+    let change = if price > previous {
+        price - previous
+    } else {
+        previous - price
+    };
+    change / previous <= *max_change
+}
 
-    fn is_cache_entry_expired(&self) -> bool {
-        if self.is_price_historical {
-            // We try update historical price faster then fresh prices, since they are outdated
-            Instant::now().duration_since(self.creation_time) >= HISTORICAL_PRICE_EXPIRATION_TIME
-        } else {
-            self.is_price_expired()
-        }
+/// Returns the time passed since the price was updated.
+fn price_age(price: &TokenPrice) -> Duration {
+    Utc::now()
+        .signed_duration_since(price.last_updated)
+        .to_std()
+        .unwrap_or_default()
+}
+
+/// Checks that the price is not older than the grace period allows to serve it.
+pub(super) fn is_price_servable(
+    price: &TokenPrice,
+    price_ttl: Duration,
+    stale_price_grace_period: Duration,
+) -> bool {
+    price_age(price) <= price_ttl + stale_price_grace_period
+}
+
+/// Checks whether the cached price is served without requesting the price API. The up to date
+/// price is served during its TTL. The stale price is served until the next attempt to fetch
+/// the price, so the unavailable price API is not requested on every quote.
+pub(super) fn is_cache_entry_actual(
+    entry: &TokenCacheEntry,
+    price_ttl: Duration,
+    stale_price_grace_period: Duration,
+) -> bool {
+    if entry.is_price_stale {
+        entry.creation_time.elapsed() < STALE_PRICE_RETRY_INTERVAL
+            && is_price_servable(&entry.price, price_ttl, stale_price_grace_period)
+    } else {
+        price_age(&entry.price) <= price_ttl
     }
 }
 
@@ -77,13 +144,29 @@ pub(super) struct TickerApi<T: TokenPriceAPI> {
 
     token_db_cache: TokenDBCache,
     price_cache: Mutex<HashMap<TokenId, TokenCacheEntry>>,
-    gas_price_cache: Mutex<Option<(BigUint, Instant)>>,
+    /// Smoothed gas price and the time it was updated at.
+    gas_price_cache: Mutex<Option<(u64, Instant)>>,
 
     token_price_api: T,
+    /// Time the price is considered up to date after its update.
+    price_ttl: Duration,
+    /// Time the expired price may still be served for while the price API is unavailable.
+    stale_price_grace_period: Duration,
+    /// Interval between updates of the gas price.
+    gas_price_refresh_interval: Duration,
+    /// Weight of the newly observed gas price in the smoothed one.
+    gas_price_smoothing_factor: f64,
+    /// Maximum relative price change between the price updates, `None` if not limited.
+    max_price_change: Option<Ratio<BigUint>>,
 }
 
 impl<T: TokenPriceAPI> TickerApi<T> {
-    pub fn new(db_pool: ConnectionPool, token_price_api: T) -> Self {
+    pub fn new(
+        db_pool: ConnectionPool,
+        token_price_api: T,
+        price_ttl: Duration,
+        stale_price_grace_period: Duration,
+    ) -> Self {
         let token_db_cache = TokenDBCache::new(db_pool.clone());
         Self {
             db_pool,
@@ -91,9 +174,50 @@ impl<T: TokenPriceAPI> TickerApi<T> {
             price_cache: Mutex::new(HashMap::new()),
             gas_price_cache: Mutex::new(None),
             token_price_api,
+            price_ttl,
+            stale_price_grace_period,
+            gas_price_refresh_interval: Duration::from_secs(60),
+            gas_price_smoothing_factor: 1.0,
+            max_price_change: None,
         }
     }
 
+    /// Sets how often the gas price observed by `eth_sender` is loaded, and how much
+    /// the newly observed price affects the quoted one.
+    pub fn with_gas_price_smoothing(mut self, refresh_interval: Duration, factor: f64) -> Self {
+        self.gas_price_refresh_interval = refresh_interval;
+        self.gas_price_smoothing_factor = factor;
+        self
+    }
+
+    /// Limits the token price change between the price updates. The fetched price moving
+    /// further is considered an outlier, and the previous price is served instead while
+    /// it's not too old.
+    pub fn with_max_price_change(mut self, percent: u32) -> Self {
+        self.max_price_change = Some(Ratio::new(BigUint::from(percent), BigUint::from(100u32)));
+        self
+    }
+
+    /// Loads the average gas price observed by `eth_sender`. If it's not known yet,
+    /// the gas price limit is used, so the L1 gas cost is rather overestimated than ignored.
+    async fn load_observed_gas_price(&self) -> Result<u64, anyhow::Error> {
+        let mut storage = self
+            .db_pool
+            .access_storage()
+            .await
+            .map_err(|e| format_err!("Can't access storage: {}", e))?;
+        let mut ethereum_schema = storage.ethereum_schema();
+
+        let gas_price = match ethereum_schema.load_average_gas_price().await? {
+            Some(average_gas_price) => average_gas_price,
+            None => {
+                tracing::warn!("Average gas price is not observed yet, using the gas price limit");
+                ethereum_schema.load_gas_price_limit().await?
+            }
+        };
+        Ok(gas_price.as_u64())
+    }
+
     // Version of `update_stored_value` which returns a `Result` for convenient error handling.
     async fn _update_stored_value(
         &self,
@@ -123,34 +247,92 @@ impl<T: TokenPriceAPI> TickerApi<T> {
         &self,
         token_id: TokenId,
         price: TokenPrice,
-        is_price_historical: bool,
+        is_price_stale: bool,
     ) {
         self.price_cache.lock().await.insert(
             token_id,
-            TokenCacheEntry::new(price.clone(), Instant::now(), is_price_historical),
+            TokenCacheEntry::new(price.clone(), Instant::now(), is_price_stale),
         );
 
-        if !is_price_historical {
+        if !is_price_stale {
             self._update_stored_value(token_id, price)
                 .await
-                .map_err(|e| log::warn!("Failed to update historical ticker price: {}", e))
+                .map_err(|e| tracing::warn!("Failed to update historical ticker price: {}", e))
                 .unwrap_or_default();
         }
     }
 
-    async fn get_stored_value(&self, token_id: TokenId) -> Option<TokenPrice> {
-        let mut price_cache = self.price_cache.lock().await;
+    /// Returns the cached price if it's actual (see `is_cache_entry_actual`).
+    async fn get_stored_value(&self, token_id: TokenId) -> Option<TokenQuote> {
+        let price_cache = self.price_cache.lock().await;
+        let cached_entry = price_cache.get(&token_id)?;
 
-        if let Some(cached_entry) = price_cache.remove(&token_id) {
-            if !cached_entry.is_cache_entry_expired() {
-                price_cache.insert(token_id, cached_entry.clone());
-                if cached_entry.is_price_historical {
-                    log::warn!("Using historical price for token_id: {}", token_id);
-                }
-                return Some(cached_entry.price);
+        if !is_cache_entry_actual(cached_entry, self.price_ttl, self.stale_price_grace_period) {
+            return None;
+        }
+        if cached_entry.is_price_stale {
+            tracing::warn!("Using stale price for token_id: {}", token_id);
+        }
+        Some(TokenQuote::new(
+            cached_entry.price.clone(),
+            cached_entry.is_price_stale,
+        ))
+    }
+
+    /// Returns the latest known price of the token (either cached or stored in the database)
+    /// to be served while the price API is unavailable.
+    async fn get_stale_price(&self, token_id: TokenId) -> Option<TokenPrice> {
+        let cached_price = self
+            .price_cache
+            .lock()
+            .await
+            .get(&token_id)
+            .map(|entry| entry.price.clone());
+        let historical_price = self
+            .get_historical_ticker_price(token_id)
+            .await
+            .map_err(|e| tracing::warn!("Failed to get historical ticker price: {}", e))
+            .ok()
+            .flatten();
+
+        let latest_price = match (cached_price, historical_price) {
+            (Some(cached), Some(historical)) if historical.last_updated > cached.last_updated => {
+                historical
             }
+            (cached, historical) => cached.or(historical)?,
+        };
+        if is_price_servable(&latest_price, self.price_ttl, self.stale_price_grace_period) {
+            Some(latest_price)
+        } else {
+            None
         }
-        None
+    }
+
+    /// Returns the previous price of the token if the fetched one moved away from it further
+    /// than allowed, so the outlier isn't used to quote fees.
+    async fn previous_price_if_anomalous(
+        &self,
+        token: &Token,
+        price: &TokenPrice,
+    ) -> Option<TokenPrice> {
+        let max_price_change = self.max_price_change.as_ref()?;
+        let previous_price = self.get_stale_price(token.id).await?;
+        if is_price_change_allowed(
+            &previous_price.usd_price,
+            &price.usd_price,
+            max_price_change,
+        ) {
+            return None;
+        }
+
+        vlog::critical!(
+            "Price of {} moved from {} to {} USD, the new price is rejected",
+            token.symbol,
+            ratio_to_big_decimal(&previous_price.usd_price, 6),
+            ratio_to_big_decimal(&price.usd_price, 6)
+        );
+        metrics::counter!("ticker.price_anomalies", 1);
+        Some(previous_price)
     }
 
     async fn get_historical_ticker_price(
@@ -175,10 +357,16 @@ impl<T: TokenPriceAPI> TickerApi<T> {
     }
 }
 
+/// Reports the age of the served price. Token symbols are set by the token contracts, so they
+/// are not used as the metric label.
+fn report_price_age(price: &TokenPrice) {
+    metrics::histogram!("ticker.price_age", price_age(price));
+}
+
 #[async_trait]
 impl<T: TokenPriceAPI + Send + Sync> FeeTickerAPI for TickerApi<T> {
     /// Get last price from ticker
-    async fn get_last_quote(&self, token: TokenLike) -> Result<TokenPrice, anyhow::Error> {
+    async fn get_last_quote(&self, token: TokenLike) -> Result<TokenQuote, anyhow::Error> {
         let start = Instant::now();
         let token = self
             .token_db_cache
@@ -188,36 +376,46 @@ impl<T: TokenPriceAPI + Send + Sync> FeeTickerAPI for TickerApi<T> {
 
         // TODO: remove hardcode for Matter Labs Trial Token (ZKS-63).
         if token.symbol == "MLTT" {
-            return Ok(TokenPrice {
+            let price = TokenPrice {
                 usd_price: Ratio::from_integer(1u32.into()),
                 last_updated: Utc::now(),
-            });
+            };
+            return Ok(TokenQuote::new(price, false));
         }
 
         if let Some(cached_value) = self.get_stored_value(token.id).await {
+            metrics::counter!("ticker.price_cache_hits", 1);
+            report_price_age(&cached_value.price);
             return Ok(cached_value);
         }
+        metrics::counter!("ticker.price_cache_misses", 1);
 
         let api_price = self
             .token_price_api
             .get_price(&token.symbol)
             .await
-            .map_err(|e| log::warn!("Failed to get price: {}", e));
+            .map_err(|e| tracing::warn!("Failed to get price: {}", e));
         if let Ok(api_price) = api_price {
+            if let Some(previous_price) = self.previous_price_if_anomalous(&token, &api_price).await
+            {
+                self.update_stored_value(token.id, previous_price.clone(), true)
+                    .await;
+                report_price_age(&previous_price);
+                return Ok(TokenQuote::new(previous_price, true));
+            }
             self.update_stored_value(token.id, api_price.clone(), false)
                 .await;
-            return Ok(api_price);
+            report_price_age(&api_price);
+            return Ok(TokenQuote::new(api_price, false));
         }
 
-        let historical_price = self
-            .get_historical_ticker_price(token.id)
-            .await
-            .map_err(|e| log::warn!("Failed to get historical ticker price: {}", e));
-
-        if let Ok(Some(historical_price)) = historical_price {
-            self.update_stored_value(token.id, historical_price.clone(), true)
+        if let Some(stale_price) = self.get_stale_price(token.id).await {
+            tracing::warn!("Using stale price for token_id: {}", token.id);
+            metrics::counter!("ticker.stale_prices_served", 1);
+            self.update_stored_value(token.id, stale_price.clone(), true)
                 .await;
-            return Ok(historical_price);
+            report_price_age(&stale_price);
+            return Ok(TokenQuote::new(stale_price, true));
         }
 
         metrics::histogram!("ticker.get_last_quote", start.elapsed());
@@ -229,30 +427,24 @@ impl<T: TokenPriceAPI + Send + Sync> FeeTickerAPI for TickerApi<T> {
         let start = Instant::now();
         let mut cached_value = self.gas_price_cache.lock().await;
 
-        if let Some((cached_gas_price, cache_time)) = cached_value.take() {
-            if cache_time.elapsed() < Duration::from_secs(API_PRICE_EXPIRATION_TIME_SECS as u64) {
-                *cached_value = Some((cached_gas_price.clone(), cache_time));
-                return Ok(cached_gas_price);
+        if let Some((cached_gas_price, cache_time)) = *cached_value {
+            if cache_time.elapsed() < self.gas_price_refresh_interval {
+                return Ok(cached_gas_price.into());
             }
         }
 
-        let mut storage = self
-            .db_pool
-            .access_storage()
-            .await
-            .map_err(|e| format_err!("Can't access storage: {}", e))?;
-        let average_gas_price = storage
-            .ethereum_schema()
-            .load_average_gas_price()
-            .await?
-            .unwrap_or_default()
-            .as_u64();
-        let average_gas_price = BigUint::from(average_gas_price);
-
-        *cached_value = Some((average_gas_price.clone(), Instant::now()));
+        let observed_gas_price = self.load_observed_gas_price().await?;
+        let gas_price = smooth_gas_price(
+            cached_value.map(|(gas_price, _)| gas_price),
+            observed_gas_price,
+            self.gas_price_smoothing_factor,
+        );
+        *cached_value = Some((gas_price, Instant::now()));
 
+        metrics::gauge!("ticker.observed_gas_price_wei", observed_gas_price as f64);
+        metrics::gauge!("ticker.gas_price_wei", gas_price as f64);
         metrics::histogram!("ticker.get_gas_price_wei", start.elapsed());
-        Ok(average_gas_price)
+        Ok(gas_price.into())
     }
 
     async fn get_token(&self, token: TokenLike) -> Result<Token, anyhow::Error> {
@@ -0,0 +1,173 @@
+//! Combination of several token price sources, so the ticker doesn't depend on a single API.
+
+// External deps
+use anyhow::format_err;
+use async_trait::async_trait;
+use futures::future::join_all;
+use num::{rational::Ratio, BigUint};
+// Workspace deps
+use zksync_config::TokenPricePolicy;
+use zksync_types::TokenPrice;
+// Local deps
+use super::TokenPriceAPI;
+
+pub struct MultiSourcePriceAPI {
+    sources: Vec<Box<dyn TokenPriceAPI + Send + Sync>>,
+    policy: TokenPricePolicy,
+}
+
+impl std::fmt::Debug for MultiSourcePriceAPI {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MultiSourcePriceAPI")
+            .field("sources", &self.sources.len())
+            .field("policy", &self.policy)
+            .finish()
+    }
+}
+
+impl MultiSourcePriceAPI {
+    /// Creates the API querying the sources according to the policy.
+    /// In case of `Fallback` policy, sources are queried in the provided order.
+    pub fn new(
+        sources: Vec<Box<dyn TokenPriceAPI + Send + Sync>>,
+        policy: TokenPricePolicy,
+    ) -> Self {
+        assert!(
+            !sources.is_empty(),
+            "At least one token price source is required"
+        );
+        Self { sources, policy }
+    }
+
+    async fn fallback_price(&self, token_symbol: &str) -> Result<TokenPrice, anyhow::Error> {
+        for (index, source) in self.sources.iter().enumerate() {
+            match source.get_price(token_symbol).await {
+                Ok(price) => return Ok(price),
+                Err(err) => tracing::warn!("Price source #{} failed: {}", index, err),
+            }
+        }
+        Err(format_err!(
+            "No price source is available for '{}'",
+            token_symbol
+        ))
+    }
+
+    async fn median_price(&self, token_symbol: &str) -> Result<TokenPrice, anyhow::Error> {
+        let results = join_all(
+            self.sources
+                .iter()
+                .map(|source| source.get_price(token_symbol)),
+        )
+        .await;
+
+        let prices = results
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, result)| {
+                result
+                    .map_err(|err| tracing::warn!("Price source #{} failed: {}", index, err))
+                    .ok()
+            })
+            .collect();
+        median(prices)
+            .ok_or_else(|| format_err!("No price source is available for '{}'", token_symbol))
+    }
+}
+
+/// Returns the median of the prices. If the number of prices is even, the average
+/// of the middle ones is used. The price is considered as old as the oldest price it's based on.
+fn median(mut prices: Vec<TokenPrice>) -> Option<TokenPrice> {
+    prices.sort_by(|a, b| a.usd_price.cmp(&b.usd_price));
+    let middle = prices.len() / 2;
+
+    if prices.len() % 2 == 1 {
+        return Some(prices.swap_remove(middle));
+    }
+    let upper = prices.get(middle)?;
+    let lower = &prices[middle - 1];
+    Some(TokenPrice {
+        usd_price: (&upper.usd_price + &lower.usd_price) / Ratio::from_integer(BigUint::from(2u32)),
+        last_updated: upper.last_updated.min(lower.last_updated),
+    })
+}
+
+#[async_trait]
+impl TokenPriceAPI for MultiSourcePriceAPI {
+    async fn get_price(&self, token_symbol: &str) -> Result<TokenPrice, anyhow::Error> {
+        match self.policy {
+            TokenPricePolicy::Fallback => self.fallback_price(token_symbol).await,
+            TokenPricePolicy::Median => self.median_price(token_symbol).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::static_prices::StaticPriceAPI, *};
+    use chrono::Utc;
+    use futures::executor::block_on;
+    use std::collections::HashMap;
+
+    fn price(value: u32) -> Ratio<BigUint> {
+        Ratio::from_integer(BigUint::from(value))
+    }
+
+    fn source(prices: &[(&str, u32)]) -> Box<dyn TokenPriceAPI + Send + Sync> {
+        let prices: HashMap<_, _> = prices
+            .iter()
+            .map(|(symbol, value)| (symbol.to_string(), price(*value)))
+            .collect();
+        Box::new(StaticPriceAPI::new(prices))
+    }
+
+    fn sources() -> Vec<Box<dyn TokenPriceAPI + Send + Sync>> {
+        vec![
+            source(&[("ETH", 300), ("DAI", 1)]),
+            source(&[("ETH", 100)]),
+            source(&[("ETH", 200), ("DAI", 3)]),
+        ]
+    }
+
+    #[test]
+    fn fallback_policy() {
+        let api = MultiSourcePriceAPI::new(sources(), TokenPricePolicy::Fallback);
+
+        assert_eq!(
+            block_on(api.get_price("ETH")).unwrap().usd_price,
+            price(300)
+        );
+        assert_eq!(block_on(api.get_price("DAI")).unwrap().usd_price, price(1));
+        assert!(block_on(api.get_price("MLTT")).is_err());
+    }
+
+    #[test]
+    fn median_policy() {
+        let api = MultiSourcePriceAPI::new(sources(), TokenPricePolicy::Median);
+
+        assert_eq!(
+            block_on(api.get_price("ETH")).unwrap().usd_price,
+            price(200)
+        );
+        assert_eq!(block_on(api.get_price("DAI")).unwrap().usd_price, price(2));
+        assert!(block_on(api.get_price("MLTT")).is_err());
+    }
+
+    #[test]
+    fn median_is_as_old_as_its_prices() {
+        let now = Utc::now();
+        let old = now - chrono::Duration::minutes(5);
+        let prices = vec![
+            TokenPrice {
+                usd_price: price(1),
+                last_updated: now,
+            },
+            TokenPrice {
+                usd_price: price(2),
+                last_updated: old,
+            },
+        ];
+
+        assert_eq!(median(prices).unwrap().last_updated, old);
+        assert!(median(Vec::new()).is_none());
+    }
+}
@@ -0,0 +1,320 @@
+//! Token prices obtained from the Uniswap V2 pairs of the tokens with the USD stablecoin.
+//!
+//! Pair contracts accumulate the price multiplied by the time it was held, so the time-weighted
+//! average price (TWAP) over the period is the difference of the accumulated values divided by
+//! the period duration. Since the price API is requested on demand, the accumulated values are
+//! observed on every request and the TWAP is calculated against the latest observation made
+//! at least `window` ago. Until such an observation exists, the price is not available.
+
+// Built-in deps
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+// External deps
+use anyhow::format_err;
+use async_trait::async_trait;
+use chrono::Utc;
+use num::{rational::Ratio, traits::Pow, BigUint};
+use web3::{
+    contract::{tokens::Detokenize, Contract, Options},
+    transports::Http,
+    types::{Address, BlockId, BlockNumber, U256},
+    Web3,
+};
+// Workspace deps
+use zksync_types::TokenPrice;
+// Local deps
+use super::{TokenPriceAPI, REQUEST_TIMEOUT};
+
+/// Functions of the Uniswap V2 pair and ERC20 contracts used to calculate the price.
+const UNISWAP_PAIR_ABI: &str = r#"[
+    {"type":"function","name":"token0","inputs":[],"outputs":[{"name":"","type":"address"}],"stateMutability":"view","constant":true},
+    {"type":"function","name":"token1","inputs":[],"outputs":[{"name":"","type":"address"}],"stateMutability":"view","constant":true},
+    {"type":"function","name":"getReserves","inputs":[],"outputs":[{"name":"reserve0","type":"uint112"},{"name":"reserve1","type":"uint112"},{"name":"blockTimestampLast","type":"uint32"}],"stateMutability":"view","constant":true},
+    {"type":"function","name":"price0CumulativeLast","inputs":[],"outputs":[{"name":"","type":"uint256"}],"stateMutability":"view","constant":true},
+    {"type":"function","name":"price1CumulativeLast","inputs":[],"outputs":[{"name":"","type":"uint256"}],"stateMutability":"view","constant":true},
+    {"type":"function","name":"decimals","inputs":[],"outputs":[{"name":"","type":"uint8"}],"stateMutability":"view","constant":true}
+]"#;
+
+/// Accumulated price is stored as UQ112x112 fixed point number.
+const PRICE_RESOLUTION_BITS: usize = 112;
+
+/// Properties of the pair which don't change over time.
+#[derive(Debug, Clone, Copy)]
+struct PairInfo {
+    /// Whether the priced token is `token0` of the pair.
+    token_is_first: bool,
+    token_decimals: u8,
+    quote_decimals: u8,
+}
+
+/// Accumulated price of the token in the quote token units at the moment.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Observation {
+    timestamp: u64,
+    price_cumulative: U256,
+}
+
+#[derive(Debug, Default)]
+struct PairState {
+    info: Option<PairInfo>,
+    /// Observations in the ascending order of timestamps.
+    observations: Vec<Observation>,
+}
+
+#[derive(Debug)]
+pub struct UniswapTwapAPI {
+    web3: Web3<Http>,
+    abi: ethabi::Contract,
+    pairs: HashMap<String, Address>,
+    quote_token: Address,
+    window: Duration,
+    /// Never locked across the web3 requests, so the slow RPC doesn't delay the other tokens.
+    state: Mutex<HashMap<String, PairState>>,
+}
+
+impl UniswapTwapAPI {
+    pub fn new(
+        web3: Web3<Http>,
+        pairs: HashMap<String, Address>,
+        quote_token: Address,
+        window: Duration,
+    ) -> Self {
+        Self {
+            web3,
+            abi: ethabi::Contract::load(UNISWAP_PAIR_ABI.as_bytes()).expect("uniswap pair abi"),
+            pairs,
+            quote_token,
+            window,
+            state: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn contract(&self, address: Address) -> Contract<Http> {
+        Contract::new(self.web3.eth(), address, self.abi.clone())
+    }
+
+    async fn query<R: Detokenize>(
+        &self,
+        address: Address,
+        function: &str,
+    ) -> Result<R, anyhow::Error> {
+        let contract = self.contract(address);
+        let query = contract.query(function, (), None, Options::default(), None);
+        tokio::time::timeout(REQUEST_TIMEOUT, query)
+            .await
+            .map_err(|_| format_err!("Uniswap {} request timeout", function))?
+            .map_err(|err| format_err!("Uniswap {} request failed: {}", function, err))
+    }
+
+    async fn load_pair_info(&self, pair: Address) -> Result<PairInfo, anyhow::Error> {
+        let token0: Address = self.query(pair, "token0").await?;
+        let token_is_first = token0 != self.quote_token;
+        let token = if token_is_first {
+            token0
+        } else {
+            self.query(pair, "token1").await?
+        };
+
+        let token_decimals: U256 = self.query(token, "decimals").await?;
+        let quote_decimals: U256 = self.query(self.quote_token, "decimals").await?;
+        Ok(PairInfo {
+            token_is_first,
+            token_decimals: token_decimals.as_u32() as u8,
+            quote_decimals: quote_decimals.as_u32() as u8,
+        })
+    }
+
+    /// Restores the price accumulated by the pair up to the latest block.
+    async fn observe(&self, pair: Address, info: PairInfo) -> Result<Observation, anyhow::Error> {
+        let block = self
+            .web3
+            .eth()
+            .block(BlockId::Number(BlockNumber::Latest))
+            .await?
+            .ok_or_else(|| format_err!("Latest block is not available"))?;
+        let timestamp = block.timestamp.as_u64();
+
+        let (reserve0, reserve1, last_update): (U256, U256, U256) =
+            self.query(pair, "getReserves").await?;
+        let price_cumulative: U256 = if info.token_is_first {
+            self.query(pair, "price0CumulativeLast").await?
+        } else {
+            self.query(pair, "price1CumulativeLast").await?
+        };
+        let (reserve, quote_reserve) = if info.token_is_first {
+            (reserve0, reserve1)
+        } else {
+            (reserve1, reserve0)
+        };
+        if reserve.is_zero() {
+            anyhow::bail!("Uniswap pair {:?} has no liquidity", pair);
+        }
+
+        // The accumulated value is only updated by the pair on the first trade in the block,
+        // so the price held since then is added.
+        let elapsed = (timestamp as u32).wrapping_sub(last_update.as_u32());
+        let current_price = (quote_reserve << PRICE_RESOLUTION_BITS) / reserve;
+        let price_cumulative = price_cumulative
+            .overflowing_add(current_price.overflowing_mul(elapsed.into()).0)
+            .0;
+
+        Ok(Observation {
+            timestamp,
+            price_cumulative,
+        })
+    }
+}
+
+/// Calculates the average price of the whole token in the whole quote token units between observations.
+fn average_price(from: &Observation, to: &Observation, info: &PairInfo) -> Option<Ratio<BigUint>> {
+    if to.timestamp <= from.timestamp {
+        return None;
+    }
+    // Accumulated values are expected to overflow.
+    let price_diff = to.price_cumulative.overflowing_sub(from.price_cumulative).0;
+    let mut price_diff_bytes = [0u8; 32];
+    price_diff.to_big_endian(&mut price_diff_bytes);
+
+    let numerator =
+        BigUint::from_bytes_be(&price_diff_bytes) * BigUint::from(10u32).pow(info.token_decimals);
+    let denominator = (BigUint::from(to.timestamp - from.timestamp) << PRICE_RESOLUTION_BITS)
+        * BigUint::from(10u32).pow(info.quote_decimals);
+    Some(Ratio::new(numerator, denominator))
+}
+
+/// Adds the new observation and returns the latest observation made at least `window` before it.
+/// Observations older than the returned one are no longer needed and removed.
+fn record_observation(
+    observations: &mut Vec<Observation>,
+    observation: Observation,
+    window: Duration,
+) -> Option<Observation> {
+    let window_start = observation.timestamp.checked_sub(window.as_secs());
+    let start_index = window_start.and_then(|window_start| {
+        observations
+            .iter()
+            .rposition(|old| old.timestamp <= window_start)
+    });
+    if let Some(start_index) = start_index {
+        observations.drain(..start_index);
+    }
+    let start = start_index.map(|_| observations[0]);
+
+    // Concurrent requests may observe the blocks out of order, the older ones are not stored.
+    if observations
+        .last()
+        .map_or(true, |last| last.timestamp < observation.timestamp)
+    {
+        observations.push(observation);
+    }
+    start
+}
+
+#[async_trait]
+impl TokenPriceAPI for UniswapTwapAPI {
+    async fn get_price(&self, token_symbol: &str) -> Result<TokenPrice, anyhow::Error> {
+        let pair = *self
+            .pairs
+            .get(token_symbol)
+            .ok_or_else(|| format_err!("Token '{}' has no Uniswap pair", token_symbol))?;
+
+        let cached_info = self
+            .state
+            .lock()
+            .unwrap()
+            .get(token_symbol)
+            .and_then(|pair_state| pair_state.info);
+        let info = match cached_info {
+            Some(info) => info,
+            None => self.load_pair_info(pair).await?,
+        };
+
+        let observation = self.observe(pair, info).await?;
+
+        let start = {
+            let mut state = self.state.lock().unwrap();
+            let pair_state = state.entry(token_symbol.to_string()).or_default();
+            pair_state.info = Some(info);
+            record_observation(&mut pair_state.observations, observation, self.window)
+        }
+        .ok_or_else(|| {
+            format_err!(
+                "Uniswap price of '{}' is not observed long enough",
+                token_symbol
+            )
+        })?;
+        let usd_price = average_price(&start, &observation, &info)
+            .ok_or_else(|| format_err!("Uniswap price of '{}' is not available", token_symbol))?;
+
+        Ok(TokenPrice {
+            usd_price,
+            last_updated: Utc::now(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn observation(timestamp: u64, price_cumulative: U256) -> Observation {
+        Observation {
+            timestamp,
+            price_cumulative,
+        }
+    }
+
+    #[test]
+    fn observations_window() {
+        let window = Duration::from_secs(100);
+        let mut observations = Vec::new();
+
+        assert_eq!(
+            record_observation(&mut observations, observation(1000, 0.into()), window),
+            None
+        );
+        assert_eq!(
+            record_observation(&mut observations, observation(1050, 0.into()), window),
+            None
+        );
+        assert_eq!(
+            record_observation(&mut observations, observation(1120, 0.into()), window),
+            Some(observation(1000, 0.into()))
+        );
+        // The latest observation made at least the window ago is used.
+        assert_eq!(
+            record_observation(&mut observations, observation(1160, 0.into()), window),
+            Some(observation(1050, 0.into()))
+        );
+        assert_eq!(observations.len(), 3);
+
+        // Observation of the older block made by a concurrent request is used, but not stored.
+        assert_eq!(
+            record_observation(&mut observations, observation(1155, 0.into()), window),
+            Some(observation(1050, 0.into()))
+        );
+        assert_eq!(observations.last(), Some(&observation(1160, 0.into())));
+        assert_eq!(observations.len(), 3);
+    }
+
+    #[test]
+    fn twap_calculation() {
+        let info = PairInfo {
+            token_is_first: true,
+            token_decimals: 18,
+            quote_decimals: 6,
+        };
+        // 1 ETH (10^18 units) is worth 500 USDC (500 * 10^6 units) during 10 seconds,
+        // accumulated value overflows in between.
+        let price = (U256::from(500u32) << PRICE_RESOLUTION_BITS) / U256::exp10(12);
+        let from = observation(100, U256::zero().overflowing_sub(price).0);
+        let to = observation(110, price * U256::from(9));
+
+        let average = average_price(&from, &to, &info).unwrap();
+        // Price is slightly less than 500 because of the fixed point precision.
+        assert_eq!(average.to_integer(), BigUint::from(499u32));
+        assert!(average > Ratio::new(BigUint::from(4999u32), BigUint::from(10u32)));
+        assert_eq!(average_price(&to, &from, &info), None);
+    }
+}
@@ -3,8 +3,9 @@
 
 // External deps
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 // Workspace deps
-use zksync_storage::ConnectionPool;
+use zksync_storage::{fee_history::records::NewFeeQuote, ConnectionPool};
 use zksync_types::Address;
 // Local deps
 
@@ -14,6 +15,12 @@ pub trait FeeTickerInfo {
     /// Check whether account exists in the zkSync network or not.
     /// Returns `true` if account does not yet exist in the zkSync network.
     async fn is_account_new(&mut self, address: Address) -> bool;
+
+    /// Records the quoted fee into the fee history.
+    async fn store_fee_quote(&mut self, quote: NewFeeQuote) -> anyhow::Result<()>;
+
+    /// Removes the fee quotes recorded before the specified time.
+    async fn remove_fee_history_before(&mut self, before: DateTime<Utc>) -> anyhow::Result<()>;
 }
 
 pub struct TickerInfo {
@@ -45,4 +52,20 @@ impl FeeTickerInfo for TickerInfo {
         // If account is `Some(_)` then it's not new.
         account_state.committed.is_none()
     }
+
+    async fn store_fee_quote(&mut self, quote: NewFeeQuote) -> anyhow::Result<()> {
+        let mut storage = self.db.access_storage().await?;
+        storage.fee_history_schema().store_fee_quote(quote).await?;
+        Ok(())
+    }
+
+    async fn remove_fee_history_before(&mut self, before: DateTime<Utc>) -> anyhow::Result<()> {
+        let mut storage = self.db.access_storage().await?;
+        let removed = storage
+            .fee_history_schema()
+            .remove_fee_history_before(before)
+            .await?;
+        tracing::info!("Removed {} outdated fee quotes", removed);
+        Ok(())
+    }
 }
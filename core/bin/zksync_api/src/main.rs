@@ -1,11 +1,13 @@
 use futures::{channel::mpsc, executor::block_on, SinkExt, StreamExt};
 use std::cell::RefCell;
 use zksync_api::run_api;
+use zksync_config::ConfigurationOptions;
+use zksync_prometheus_exporter::install_prometheus_exporter;
 use zksync_storage::ConnectionPool;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    env_logger::init();
+    vlog::init();
     // handle ctrl+c
     let (stop_signal_sender, mut stop_signal_receiver) = mpsc::channel(256);
     {
@@ -17,6 +19,8 @@ async fn main() -> anyhow::Result<()> {
         .expect("Error setting Ctrl+C handler");
     }
     let connection_pool = ConnectionPool::new(None);
+    let prometheus_handle =
+        install_prometheus_exporter(ConfigurationOptions::from_env().prometheus_export_port);
 
     let task_handle = run_api(connection_pool, stop_signal_sender);
 
@@ -24,8 +28,11 @@ async fn main() -> anyhow::Result<()> {
         _ = async { task_handle.await } => {
             panic!("API server actors aren't supposed to finish their execution")
         },
+        _ = async { prometheus_handle.await } => {
+            panic!("Prometheus exporter actors aren't supposed to finish their execution")
+        },
         _ = async { stop_signal_receiver.next().await } => {
-            log::warn!("Stop signal received, shutting down");
+            tracing::warn!("Stop signal received, shutting down");
         }
     };
 
@@ -16,7 +16,7 @@ use futures::{
 use tokio::runtime::{Builder, Handle};
 // Workspace uses
 use zksync_types::{
-    tx::{BatchSignData, TxEthSignature},
+    tx::{BatchSignData, Eip712Domain, TxEthSignature},
     Address, SignedZkSyncTx, ZkSyncTx,
 };
 // Local uses
@@ -44,11 +44,14 @@ pub struct VerifiedTx(TxVariant);
 impl VerifiedTx {
     /// Checks the (batch of) transaction(s) correctness by verifying its
     /// Ethereum signature (if required) and `ZKSync` signature.
+    ///
+    /// EIP-712 signatures are only accepted if the domain they're created in is provided.
     pub async fn verify(
         request: &mut VerifyTxSignatureRequest,
         eth_checker: &EthereumChecker<web3::transports::Http>,
+        eip712_domain: Option<&Eip712Domain>,
     ) -> Result<Self, TxAddError> {
-        verify_eth_signature(request, eth_checker).await?;
+        verify_eth_signature(request, eth_checker, eip712_domain).await?;
         verify_tx_correctness(&mut request.tx)?;
 
         Ok(Self(request.tx.clone()))
@@ -81,6 +84,7 @@ impl VerifiedTx {
 async fn verify_eth_signature(
     request: &VerifyTxSignatureRequest,
     eth_checker: &EthereumChecker<web3::transports::Http>,
+    eip712_domain: Option<&Eip712Domain>,
 ) -> Result<(), TxAddError> {
     let accounts = &request.senders;
 
@@ -89,7 +93,7 @@ async fn verify_eth_signature(
             if accounts.len() != 1 {
                 return Err(TxAddError::Other);
             }
-            verify_eth_signature_single_tx(tx, accounts[0], eth_checker).await?;
+            verify_eth_signature_single_tx(tx, accounts[0], eth_checker, eip712_domain).await?;
         }
         TxVariant::Batch(txs, batch_sign_data) => {
             if accounts.len() != txs.len() {
@@ -99,7 +103,7 @@ async fn verify_eth_signature(
             // In case there're signatures provided for some of transactions
             // we still verify them.
             for (tx, &account) in txs.iter().zip(accounts.iter()) {
-                verify_eth_signature_single_tx(tx, account, eth_checker).await?;
+                verify_eth_signature_single_tx(tx, account, eth_checker, eip712_domain).await?;
             }
         }
     }
@@ -111,6 +115,7 @@ async fn verify_eth_signature_single_tx(
     tx: &SignedZkSyncTx,
     sender_address: Address,
     eth_checker: &EthereumChecker<web3::transports::Http>,
+    eip712_domain: Option<&Eip712Domain>,
 ) -> Result<(), TxAddError> {
     let start = Instant::now();
     // Check if the tx is a `ChangePubKey` operation without an Ethereum signature.
@@ -124,7 +129,10 @@ async fn verify_eth_signature_single_tx(
                     &change_pk.new_pk_hash,
                 )
                 .await
-                .expect("Unable to check onchain ChangePubKey Authorization");
+                .map_err(|e| {
+                    vlog::warn!("Unable to check onchain ChangePubKey authorization: {}", e);
+                    TxAddError::Other
+                })?;
 
             if !is_authorized {
                 return Err(TxAddError::ChangePkNotAuthorized);
@@ -155,7 +163,21 @@ async fn verify_eth_signature_single_tx(
                     .expect("Unable to check EIP1271 signature");
 
                 if !signature_correct {
-                    return Err(TxAddError::IncorrectTx);
+                    return Err(TxAddError::EIP1271SignatureVerificationFail);
+                }
+            }
+            TxEthSignature::EIP712Signature(packed_signature) => {
+                // Typed data is signed instead of the message, so the hash is restored from the transaction.
+                let domain = eip712_domain.ok_or(TxAddError::EIP712SignaturesDisabled)?;
+                let hash = domain
+                    .tx_hash_to_sign(&tx.tx)
+                    .ok_or(TxAddError::IncorrectEthSignature)?;
+                let signer_account = packed_signature
+                    .signature_recover_signer_from_hash(&hash)
+                    .or(Err(TxAddError::IncorrectEthSignature))?;
+
+                if signer_account != sender_address {
+                    return Err(TxAddError::IncorrectEthSignature);
                 }
             }
         };
@@ -215,6 +237,10 @@ async fn verify_eth_signature_txs_batch(
                         break;
                     }
                 }
+                // Batches have no typed data definition, only the batch message can be signed.
+                TxEthSignature::EIP712Signature(_) => {
+                    return Err(TxAddError::IncorrectEthSignature);
+                }
             }
         }
         // No signature for this transaction found, return error.
@@ -259,6 +285,19 @@ pub struct VerifyTxSignatureRequest {
     pub response: oneshot::Sender<Result<VerifiedTx, TxAddError>>,
 }
 
+/// Returns the EIP-712 domain of the transactions signatures, or `None`
+/// if EIP-712 signatures are disabled.
+pub(crate) fn eip712_domain(config_options: &ConfigurationOptions) -> Option<Eip712Domain> {
+    if config_options.eip712_signatures_enabled {
+        Some(Eip712Domain::new(
+            config_options.chain_id.into(),
+            config_options.contract_eth_addr,
+        ))
+    } else {
+        None
+    }
+}
+
 /// Main routine of the concurrent signature checker.
 /// See the module documentation for details.
 pub fn start_sign_checker_detached(
@@ -269,7 +308,12 @@ pub fn start_sign_checker_detached(
     let transport = web3::transports::Http::new(&config_options.web3_url).unwrap();
     let web3 = web3::Web3::new(transport);
 
-    let eth_checker = EthereumChecker::new(web3, config_options.contract_eth_addr);
+    let eth_checker = EthereumChecker::new(
+        web3,
+        config_options.contract_eth_addr,
+        config_options.confirmations_for_eth_event,
+    );
+    let eip712_domain = eip712_domain(&config_options);
 
     /// Main signature check requests handler.
     /// Basically it receives the requests through the channel and verifies signatures,
@@ -278,11 +322,14 @@ pub fn start_sign_checker_detached(
         handle: Handle,
         mut input: mpsc::Receiver<VerifyTxSignatureRequest>,
         eth_checker: EthereumChecker<web3::transports::Http>,
+        eip712_domain: Option<Eip712Domain>,
     ) {
         while let Some(mut request) = input.next().await {
             let eth_checker = eth_checker.clone();
+            let eip712_domain = eip712_domain.clone();
             handle.spawn(async move {
-                let resp = VerifiedTx::verify(&mut request, &eth_checker).await;
+                let resp =
+                    VerifiedTx::verify(&mut request, &eth_checker, eip712_domain.as_ref()).await;
 
                 request.response.send(resp).unwrap_or_default();
             });
@@ -300,7 +347,7 @@ pub fn start_sign_checker_detached(
                 .build()
                 .expect("failed to build runtime for signature processor");
             let handle = runtime.handle().clone();
-            runtime.block_on(checker_routine(handle, input, eth_checker));
+            runtime.block_on(checker_routine(handle, input, eth_checker, eip712_domain));
         })
         .expect("failed to start signature checker thread");
 }
@@ -24,6 +24,9 @@ pub enum TxAddError {
     #[error("Eth signature is incorrect")]
     IncorrectEthSignature,
 
+    #[error("EIP712 signatures are not supported")]
+    EIP712SignaturesDisabled,
+
     #[error("Change pubkey tx is not authorized onchain")]
     ChangePkNotAuthorized,
 
@@ -44,4 +47,13 @@ pub enum TxAddError {
 
     #[error("Too many Ethereum signatures provided")]
     EthSignaturesLimitExceeded,
+
+    #[error("Mempool is full and the transaction fee is too low to replace pending transactions")]
+    MempoolFull,
+
+    #[error("Too many pending transactions from the account")]
+    AccountTxsLimitExceeded,
+
+    #[error("Server is shutting down")]
+    ShuttingDown,
 }
@@ -0,0 +1,120 @@
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use lru_cache::LruCache;
+use tokio::sync::Mutex;
+
+use zksync_storage::{ConnectionPool, QueryResult};
+use zksync_types::{block::ExecutedOperations, AccountId, Address, ZkSyncOp};
+
+/// Time the address resolution is cached for, so the account IDs reassigned
+/// by the block reverts are eventually picked up.
+const ACCOUNT_ID_CACHE_TTL: Duration = Duration::from_secs(300);
+
+/// Cache of the address to account ID resolution shared by the API servers.
+///
+/// Addresses without an account are cached as well. Once the account is created,
+/// the event fetcher reports the executed operations to the cache, which replaces
+/// the cached absence of the account with its ID.
+#[derive(Debug, Clone)]
+pub struct AccountIdCache {
+    pub pool: ConnectionPool,
+    cache: Arc<Mutex<LruCache<Address, (Option<AccountId>, Instant)>>>,
+}
+
+impl AccountIdCache {
+    pub fn new(pool: ConnectionPool, capacity: usize) -> Self {
+        Self {
+            pool,
+            cache: Arc::new(Mutex::new(LruCache::new(capacity))),
+        }
+    }
+
+    pub async fn get_account_id(&self, address: Address) -> QueryResult<Option<AccountId>> {
+        let account_ids = self.get_account_ids(&[address]).await?;
+        Ok(account_ids.get(&address).copied())
+    }
+
+    /// Resolves the addresses to the account IDs, the addresses without an account
+    /// are absent in the result. Addresses missing in the cache are resolved with a single query.
+    pub async fn get_account_ids(
+        &self,
+        addresses: &[Address],
+    ) -> QueryResult<HashMap<Address, AccountId>> {
+        let mut account_ids = HashMap::new();
+        let mut missing = Vec::new();
+        {
+            let mut cache = self.cache.lock().await;
+            for address in addresses {
+                match cache.get_mut(address) {
+                    Some((id, cached_at)) if cached_at.elapsed() < ACCOUNT_ID_CACHE_TTL => {
+                        if let Some(id) = id {
+                            account_ids.insert(*address, *id);
+                        }
+                    }
+                    _ => missing.push(*address),
+                }
+            }
+        }
+        metrics::counter!(
+            "api.account_id_cache.hits",
+            (addresses.len() - missing.len()) as u64
+        );
+
+        missing.sort();
+        missing.dedup();
+        if missing.is_empty() {
+            return Ok(account_ids);
+        }
+
+        let loaded = {
+            let mut storage = self.pool.access_storage().await?;
+            storage
+                .chain()
+                .account_schema()
+                .account_ids_by_addresses(&missing)
+                .await?
+        };
+
+        let mut cache = self.cache.lock().await;
+        let now = Instant::now();
+        for address in missing {
+            let account_id = loaded.get(&address).copied();
+            cache.insert(address, (account_id, now));
+            if let Some(account_id) = account_id {
+                account_ids.insert(address, account_id);
+            }
+        }
+
+        Ok(account_ids)
+    }
+
+    /// Caches the accounts created by the executed operations.
+    pub async fn handle_executed_ops(&self, ops: &[ExecutedOperations]) {
+        let created = created_accounts(ops);
+        if created.is_empty() {
+            return;
+        }
+
+        let mut cache = self.cache.lock().await;
+        let now = Instant::now();
+        for (address, account_id) in created {
+            cache.insert(address, (Some(account_id), now));
+        }
+    }
+}
+
+/// Returns the addresses and IDs of the accounts created by the executed operations.
+/// Deposits to the existing accounts are included as well, since they don't change the IDs.
+fn created_accounts(ops: &[ExecutedOperations]) -> Vec<(Address, AccountId)> {
+    ops.iter()
+        .filter_map(|op| match op.get_executed_op()? {
+            ZkSyncOp::TransferToNew(op) => Some((op.tx.to, op.to)),
+            ZkSyncOp::Deposit(op) => Some((op.priority_op.to, op.account_id)),
+            _ => None,
+        })
+        .collect()
+}
@@ -114,7 +114,7 @@ impl MetricsCounter {
             if tps > self.noise_threshold {
                 self.stats.add_sample(tps);
 
-                log::info!(
+                tracing::info!(
                     "Throughput: {} el/s; min: {}, max: {}, avg: {}",
                     tps,
                     self.stats.min(),
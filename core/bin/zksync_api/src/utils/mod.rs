@@ -1,3 +1,5 @@
+pub mod account_id_cache;
 pub mod metrics_counter;
+pub mod runtime_settings;
 pub mod shared_lru_cache;
 pub mod token_db_cache;
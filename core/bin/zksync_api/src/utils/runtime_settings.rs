@@ -0,0 +1,256 @@
+//! Settings changed at runtime through the admin API.
+
+// Built-in uses
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+// External uses
+use num::{rational::Ratio, BigUint};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+// Workspace uses
+use zksync_storage::{
+    admin::{MAINTENANCE_MODE_FLAG, TX_ACCEPTANCE_PAUSED_FLAG},
+    ConnectionPool, StorageProcessor,
+};
+use zksync_types::TokenId;
+
+/// Settings are reloaded from the database once they're older than this interval,
+/// thus changes made through the admin API of other servers are applied within it.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Incremented upon the settings changes made through the admin API of this server,
+/// so the caches reload the settings immediately.
+static SETTINGS_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Makes all the settings caches of the server reload the settings upon the next access.
+pub fn invalidate_runtime_settings() {
+    SETTINGS_GENERATION.fetch_add(1, Ordering::SeqCst);
+}
+
+/// Market liquidity of the token, i.e. how easily the collected fees can be sold
+/// to cover the L1 gas costs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LiquidityTier {
+    High,
+    Medium,
+    Low,
+    /// Fees can't be sold at a predictable price, so the token isn't accepted to pay them.
+    Illiquid,
+}
+
+impl LiquidityTier {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::High => "high",
+            Self::Medium => "medium",
+            Self::Low => "low",
+            Self::Illiquid => "illiquid",
+        }
+    }
+
+    /// Returns the multiplier of the fee paid in the token of this tier,
+    /// which covers the slippage of selling the collected fees.
+    pub fn fee_risk_factor(self) -> Ratio<BigUint> {
+        let percent: u32 = match self {
+            Self::High => 100,
+            Self::Medium => 110,
+            Self::Low | Self::Illiquid => 150,
+        };
+        Ratio::new(BigUint::from(percent), BigUint::from(100u32))
+    }
+
+    pub fn fee_allowed(self) -> bool {
+        self != Self::Illiquid
+    }
+}
+
+impl FromStr for LiquidityTier {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "high" => Ok(Self::High),
+            "medium" => Ok(Self::Medium),
+            "low" => Ok(Self::Low),
+            "illiquid" => Ok(Self::Illiquid),
+            other => Err(anyhow::format_err!("Unknown liquidity tier: '{}'", other)),
+        }
+    }
+}
+
+impl fmt::Display for LiquidityTier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RuntimeSettings {
+    /// Whether the incoming transactions are rejected.
+    pub tx_acceptance_paused: bool,
+    /// Whether the server is in the maintenance mode, i.e. the incoming transactions
+    /// are rejected and no new blocks are created.
+    pub maintenance_mode: bool,
+    /// Tokens which cannot be used in the transactions.
+    pub disabled_tokens: HashSet<TokenId>,
+    /// Tokens for which the fee acceptance is decided by the admin rather than the server config.
+    pub fee_tokens: HashMap<TokenId, bool>,
+    /// Assessed market liquidity of the tokens.
+    pub liquidity_tiers: HashMap<TokenId, LiquidityTier>,
+}
+
+impl RuntimeSettings {
+    pub async fn load(storage: &mut StorageProcessor<'_>) -> anyhow::Result<Self> {
+        let mut settings = Self {
+            tx_acceptance_paused: storage
+                .admin_schema()
+                .is_flag_enabled(TX_ACCEPTANCE_PAUSED_FLAG)
+                .await?,
+            maintenance_mode: storage
+                .admin_schema()
+                .is_flag_enabled(MAINTENANCE_MODE_FLAG)
+                .await?,
+            ..Self::default()
+        };
+
+        for token in storage.admin_schema().load_token_settings().await? {
+            let token_id = token.token_id as TokenId;
+            if token.disabled {
+                settings.disabled_tokens.insert(token_id);
+            }
+            if let Some(fee_allowed) = token.fee_allowed {
+                settings.fee_tokens.insert(token_id, fee_allowed);
+            }
+            if let Some(tier) = token.liquidity_tier {
+                match tier.parse() {
+                    Ok(tier) => {
+                        settings.liquidity_tiers.insert(token_id, tier);
+                    }
+                    Err(err) => {
+                        tracing::warn!("Token {} has invalid liquidity tier: {}", token_id, err)
+                    }
+                }
+            }
+        }
+
+        Ok(settings)
+    }
+
+    pub fn token_disabled(&self, token_id: TokenId) -> bool {
+        self.disabled_tokens.contains(&token_id)
+    }
+
+    /// Returns whether the token can be used to pay fees, or `None` if it's up to the server config.
+    /// Disabled and illiquid tokens are never accepted.
+    pub fn fee_allowed(&self, token_id: TokenId) -> Option<bool> {
+        let illiquid = self
+            .liquidity_tier(token_id)
+            .map_or(false, |tier| !tier.fee_allowed());
+        if self.token_disabled(token_id) || illiquid {
+            Some(false)
+        } else {
+            self.fee_tokens.get(&token_id).copied()
+        }
+    }
+
+    pub fn liquidity_tier(&self, token_id: TokenId) -> Option<LiquidityTier> {
+        self.liquidity_tiers.get(&token_id).copied()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RuntimeSettingsCache {
+    pool: ConnectionPool,
+    cache: Arc<RwLock<Option<(Instant, u64, RuntimeSettings)>>>,
+}
+
+impl RuntimeSettingsCache {
+    pub fn new(pool: ConnectionPool) -> Self {
+        Self {
+            pool,
+            cache: Arc::default(),
+        }
+    }
+
+    pub async fn get(&self) -> anyhow::Result<RuntimeSettings> {
+        // Generation is read before loading, so a change made during the load isn't missed.
+        let generation = SETTINGS_GENERATION.load(Ordering::SeqCst);
+        if let Some((loaded_at, loaded_generation, settings)) = self.cache.read().await.as_ref() {
+            if loaded_at.elapsed() < REFRESH_INTERVAL && *loaded_generation == generation {
+                return Ok(settings.clone());
+            }
+        }
+
+        let settings = {
+            let mut storage = self.pool.access_storage().await?;
+            RuntimeSettings::load(&mut storage).await?
+        };
+        *self.cache.write().await = Some((Instant::now(), generation, settings.clone()));
+
+        Ok(settings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIERS: [LiquidityTier; 4] = [
+        LiquidityTier::High,
+        LiquidityTier::Medium,
+        LiquidityTier::Low,
+        LiquidityTier::Illiquid,
+    ];
+
+    /// Checks that the tiers stored in the database are parsed back.
+    #[test]
+    fn liquidity_tier_str() {
+        for &tier in &TIERS {
+            assert_eq!(tier.as_str().parse::<LiquidityTier>().unwrap(), tier);
+        }
+        assert!("unknown".parse::<LiquidityTier>().is_err());
+    }
+
+    /// Checks that the fees in the less liquid tokens are never cheaper.
+    #[test]
+    fn liquidity_tier_fee_risk_factor() {
+        assert_eq!(
+            LiquidityTier::High.fee_risk_factor(),
+            Ratio::from_integer(BigUint::from(1u32))
+        );
+        for tiers in TIERS.windows(2) {
+            assert!(tiers[0].fee_risk_factor() <= tiers[1].fee_risk_factor());
+        }
+    }
+
+    /// Checks that disabled and illiquid tokens can't be used to pay fees regardless of the override.
+    #[test]
+    fn fee_allowed() {
+        let mut settings = RuntimeSettings::default();
+        assert_eq!(settings.fee_allowed(1), None);
+
+        settings.fee_tokens.insert(1, true);
+        settings.liquidity_tiers.insert(1, LiquidityTier::Low);
+        assert_eq!(settings.fee_allowed(1), Some(true));
+
+        settings.liquidity_tiers.insert(1, LiquidityTier::Illiquid);
+        assert_eq!(settings.fee_allowed(1), Some(false));
+
+        settings.liquidity_tiers.remove(&1);
+        settings.disabled_tokens.insert(1);
+        assert_eq!(settings.fee_allowed(1), Some(false));
+
+        // Tier of the other token doesn't matter.
+        settings.liquidity_tiers.insert(2, LiquidityTier::Illiquid);
+        assert_eq!(settings.fee_allowed(0), None);
+    }
+}
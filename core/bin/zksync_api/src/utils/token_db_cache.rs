@@ -1,15 +1,22 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use tokio::sync::RwLock;
 
 use zksync_storage::ConnectionPool;
 use zksync_types::{Token, TokenId, TokenLike};
 
+/// Time the token is cached for, so the updates of the token metadata
+/// (e.g. when the token is renamed) are eventually picked up.
+const TOKEN_CACHE_TTL: Duration = Duration::from_secs(300);
+
 #[derive(Debug, Clone)]
 pub struct TokenDBCache {
     pub pool: ConnectionPool,
-    // TODO: handle stale entries, edge case when we rename token after adding it (ZKS-97)
-    cache: Arc<RwLock<HashMap<TokenLike, Token>>>,
+    cache: Arc<RwLock<HashMap<TokenLike, (Token, Instant)>>>,
 }
 
 impl TokenDBCache {
@@ -48,9 +55,11 @@ impl TokenDBCache {
     }
 
     async fn get_token_impl(&self, token_query: TokenLike) -> anyhow::Result<Option<Token>> {
-        // Just return token from cache.
-        if let Some(token) = self.cache.read().await.get(&token_query) {
-            return Ok(Some(token.clone()));
+        // Just return token from cache if it's not expired.
+        if let Some((token, cached_at)) = self.cache.read().await.get(&token_query) {
+            if cached_at.elapsed() < TOKEN_CACHE_TTL {
+                return Ok(Some(token.clone()));
+            }
         }
         // Tries to fetch token from the underlying database.
         let token = {
@@ -62,7 +71,10 @@ impl TokenDBCache {
         };
         // Stores received token into the local cache.
         if let Some(token) = &token {
-            self.cache.write().await.insert(token_query, token.clone());
+            self.cache
+                .write()
+                .await
+                .insert(token_query, (token.clone(), Instant::now()));
         }
 
         Ok(token)
@@ -8,23 +8,27 @@ fn main() {
     let mut main_runtime = Runtime::new().expect("main runtime start");
 
     env_logger::init();
-    log::info!("ETH watcher started");
+    tracing::info!("ETH watcher started");
     let web3_url = std::env::var("WEB3_URL").expect("WEB3_URL env var not found");
     let contract_address = std::env::var("CONTRACT_ADDR").expect("CONTRACT_ADDR env var not found")
         [2..]
         .parse()
         .expect("Failed to parse CONTRACT_ADDR");
+    let upgrade_gatekeeper_address = std::env::var("UPGRADE_GATEKEEPER_ADDR")
+        .expect("UPGRADE_GATEKEEPER_ADDR env var not found")[2..]
+        .parse()
+        .expect("Failed to parse UPGRADE_GATEKEEPER_ADDR");
     let transport = web3::transports::Http::new(&web3_url).unwrap();
     let web3 = web3::Web3::new(transport);
 
     let (eth_req_sender, eth_req_receiver) = mpsc::channel(256);
 
     let db_pool = ConnectionPool::new(None);
-    let eth_client = EthHttpClient::new(web3, contract_address);
+    let eth_client = EthHttpClient::new(web3, contract_address, upgrade_gatekeeper_address);
 
     let storage = DBStorage::new(db_pool);
 
-    let watcher = EthWatch::new(eth_client, storage, 0);
+    let watcher = EthWatch::new(eth_client, storage, 0, Default::default());
 
     main_runtime.spawn(watcher.run(eth_req_receiver));
     main_runtime.block_on(async move {
@@ -3,22 +3,39 @@
 //!
 //! It does it in small batches, called here `miniblocks`, which are smaller that full blocks.
 //!
+//! Before being sent to the `StateKeeper`, signatures of the proposed transactions are verified
+//! in parallel on a dedicated thread pool. Verification results are cached within transactions,
+//! so the `StateKeeper` doesn't have to check signatures on its critical path.
+//!
+//! Once the maintenance mode is enabled through the admin API, the block proposer keeps proposing
+//! the transactions already accepted to the mempool until it's drained, then requests the `StateKeeper`
+//! to seal the pending block and stops proposing new operations until the maintenance mode is disabled.
+//!
 //! Right now logic of this actor is simple, but in future consensus will replace it using the same API.
 
+// Built-in deps
+use std::time::{Duration, Instant};
 // External deps
 use futures::{
     channel::{mpsc, oneshot},
     SinkExt,
 };
+use rayon::prelude::*;
 use tokio::{task::JoinHandle, time};
 // Workspace deps
 use zksync_config::ConfigurationOptions;
+use zksync_storage::{admin::MAINTENANCE_MODE_FLAG, ConnectionPool};
+use zksync_types::mempool::SignedTxVariant;
+use zksync_utils::shutdown::ShutdownSignal;
 // Local deps
 use crate::{
     mempool::{GetBlockRequest, MempoolRequest, ProposedBlock},
     state_keeper::StateKeeperRequest,
 };
 
+/// Interval between the checks of the maintenance mode flag.
+const MAINTENANCE_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
 fn create_mempool_req(
     last_priority_op_number: u64,
 ) -> (MempoolRequest, oneshot::Receiver<ProposedBlock>) {
@@ -34,6 +51,10 @@ fn create_mempool_req(
 
 struct BlockProposer {
     current_priority_op_number: u64,
+    maintenance_mode: bool,
+    maintenance_checked_at: Option<Instant>,
+    /// Whether the pending block is sealed after the mempool was drained in the maintenance mode.
+    maintenance_block_sealed: bool,
 
     mempool_requests: mpsc::Sender<MempoolRequest>,
     statekeeper_requests: mpsc::Sender<StateKeeperRequest>,
@@ -52,6 +73,11 @@ impl BlockProposer {
 
     async fn commit_new_tx_mini_batch(&mut self) {
         let proposed_block = self.propose_new_block().await;
+        self.execute_mini_batch(proposed_block).await;
+    }
+
+    async fn execute_mini_batch(&mut self, proposed_block: ProposedBlock) {
+        let proposed_block = verify_signatures(proposed_block).await;
 
         self.current_priority_op_number += proposed_block.priority_ops.len() as u64;
         self.statekeeper_requests
@@ -59,14 +85,112 @@ impl BlockProposer {
             .await
             .expect("state keeper receiver dropped");
     }
+
+    /// Checks whether the maintenance mode is enabled. The flag is reloaded from the database
+    /// once per `MAINTENANCE_CHECK_INTERVAL`, and the last known value is used in between.
+    ///
+    /// Once the maintenance mode is enabled, the mempool is drained by `drain_mempool`.
+    async fn check_maintenance_mode(&mut self, connection_pool: &ConnectionPool) -> bool {
+        let check_due = self
+            .maintenance_checked_at
+            .map(|checked_at| checked_at.elapsed() >= MAINTENANCE_CHECK_INTERVAL)
+            .unwrap_or(true);
+        if !check_due {
+            return self.maintenance_mode;
+        }
+        self.maintenance_checked_at = Some(Instant::now());
+
+        let enabled = match load_maintenance_flag(connection_pool).await {
+            Ok(enabled) => enabled,
+            Err(err) => {
+                tracing::warn!("Unable to check the maintenance mode: {}", err);
+                return self.maintenance_mode;
+            }
+        };
+        if enabled == self.maintenance_mode {
+            return enabled;
+        }
+
+        self.maintenance_mode = enabled;
+        if enabled {
+            tracing::info!("Maintenance mode is enabled, draining the mempool");
+            self.maintenance_block_sealed = false;
+        } else {
+            tracing::info!("Maintenance mode is disabled, proposing new operations");
+        }
+        enabled
+    }
+
+    /// Proposes the transactions left in the mempool in the maintenance mode. The transactions
+    /// may still be accepted by the API until it reloads the maintenance flag, so once the mempool
+    /// is drained the state keeper is requested to seal the pending block.
+    ///
+    /// New priority operations are not proposed unless there are transactions to execute.
+    async fn drain_mempool(&mut self) {
+        let proposed_block = self.propose_new_block().await;
+        if !proposed_block.txs.is_empty() {
+            self.maintenance_block_sealed = false;
+            self.execute_mini_batch(proposed_block).await;
+        } else if !self.maintenance_block_sealed {
+            tracing::info!("Mempool is drained, sealing the pending block before the maintenance");
+            self.maintenance_block_sealed = true;
+            self.statekeeper_requests
+                .send(StateKeeperRequest::Maintenance)
+                .await
+                .expect("state keeper receiver dropped");
+        }
+    }
+}
+
+async fn load_maintenance_flag(connection_pool: &ConnectionPool) -> anyhow::Result<bool> {
+    let mut storage = connection_pool.access_storage().await?;
+    storage
+        .admin_schema()
+        .is_flag_enabled(MAINTENANCE_MODE_FLAG)
+        .await
+}
+
+/// Verifies signatures of the proposed transactions in parallel, caching the results within transactions.
+///
+/// Transactions are expected to be checked by the API already, so the verification result is not
+/// interpreted here: incorrect transactions will be rejected by the `StateKeeper` as usual.
+async fn verify_signatures(mut proposed_block: ProposedBlock) -> ProposedBlock {
+    if proposed_block.txs.is_empty() {
+        return proposed_block;
+    }
+
+    let start = Instant::now();
+    let proposed_block = tokio::task::spawn_blocking(move || {
+        proposed_block
+            .txs
+            .par_iter_mut()
+            .for_each(|variant| match variant {
+                SignedTxVariant::Tx(tx) => {
+                    tx.tx.check_correctness();
+                }
+                SignedTxVariant::Batch(batch) => {
+                    batch.txs.par_iter_mut().for_each(|tx| {
+                        tx.tx.check_correctness();
+                    });
+                }
+            });
+        proposed_block
+    })
+    .await
+    .expect("Signatures verification task failed");
+
+    metrics::histogram!("block_proposer.verify_signatures", start.elapsed());
+    proposed_block
 }
 
 // driving engine of the application
 #[must_use]
 pub fn run_block_proposer_task(
     config_options: &ConfigurationOptions,
+    connection_pool: ConnectionPool,
     mempool_requests: mpsc::Sender<MempoolRequest>,
     mut statekeeper_requests: mpsc::Sender<StateKeeperRequest>,
+    shutdown: ShutdownSignal,
 ) -> JoinHandle<()> {
     let miniblock_interval = config_options
         .miniblock_timings
@@ -88,6 +212,9 @@ pub fn run_block_proposer_task(
 
         let mut block_proposer = BlockProposer {
             current_priority_op_number,
+            maintenance_mode: false,
+            maintenance_checked_at: None,
+            maintenance_block_sealed: false,
             mempool_requests,
             statekeeper_requests,
         };
@@ -95,7 +222,122 @@ pub fn run_block_proposer_task(
         loop {
             timer.tick().await;
 
+            if shutdown.is_requested() {
+                // No new transactions are proposed, so the state keeper can seal
+                // the pending block and stop.
+                tracing::info!("Block proposer stopped, shutting down the state keeper");
+                block_proposer
+                    .statekeeper_requests
+                    .send(StateKeeperRequest::Shutdown)
+                    .await
+                    .expect("state keeper receiver dropped");
+                return;
+            }
+            if block_proposer
+                .check_maintenance_mode(&connection_pool)
+                .await
+            {
+                block_proposer.drain_mempool().await;
+                continue;
+            }
             block_proposer.commit_new_tx_mini_batch().await;
         }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{stream::StreamExt, FutureExt};
+    use num::BigUint;
+    use zksync_types::{Address, SignedZkSyncTx, Transfer, ZkSyncTx};
+
+    fn proposed_block(txs_count: usize) -> ProposedBlock {
+        let txs = (0..txs_count)
+            .map(|nonce| {
+                let transfer = Transfer::new(
+                    0,
+                    Address::random(),
+                    Address::random(),
+                    0,
+                    BigUint::from(1u32),
+                    BigUint::from(1u32),
+                    nonce as u32,
+                    None,
+                );
+                SignedZkSyncTx::from(ZkSyncTx::from(transfer)).into()
+            })
+            .collect();
+
+        ProposedBlock {
+            priority_ops: Vec::new(),
+            txs,
+        }
+    }
+
+    /// Responds to the block requests of the proposer with the provided blocks.
+    fn spawn_mempool(blocks: Vec<ProposedBlock>) -> mpsc::Sender<MempoolRequest> {
+        let (sender, mut receiver) = mpsc::channel(1);
+        tokio::spawn(async move {
+            for block in blocks {
+                match receiver.next().await {
+                    Some(MempoolRequest::GetBlock(request)) => {
+                        request.response_sender.send(block).unwrap_or_default();
+                    }
+                    _ => return,
+                }
+            }
+        });
+        sender
+    }
+
+    /// Checks that in the maintenance mode the transactions left in the mempool are executed,
+    /// and the pending block is sealed once after the mempool is drained.
+    #[tokio::test]
+    async fn maintenance_drains_mempool() {
+        let blocks = vec![
+            proposed_block(2),
+            proposed_block(0),
+            proposed_block(0),
+            proposed_block(1),
+            proposed_block(0),
+        ];
+        let (statekeeper_requests, mut statekeeper_receiver) = mpsc::channel(16);
+        let mut block_proposer = BlockProposer {
+            current_priority_op_number: 0,
+            maintenance_mode: true,
+            maintenance_checked_at: Some(Instant::now()),
+            maintenance_block_sealed: false,
+            mempool_requests: spawn_mempool(blocks),
+            statekeeper_requests,
+        };
+
+        block_proposer.drain_mempool().await;
+        match statekeeper_receiver.next().await {
+            Some(StateKeeperRequest::ExecuteMiniBlock(block)) => assert_eq!(block.txs.len(), 2),
+            _ => panic!("Mini block is not executed"),
+        }
+
+        block_proposer.drain_mempool().await;
+        assert!(matches!(
+            statekeeper_receiver.next().await,
+            Some(StateKeeperRequest::Maintenance)
+        ));
+
+        // The block is sealed only once while the mempool stays empty.
+        block_proposer.drain_mempool().await;
+        assert!(statekeeper_receiver.next().now_or_never().is_none());
+
+        // Transactions accepted by the API before it reloaded the flag are executed as well.
+        block_proposer.drain_mempool().await;
+        match statekeeper_receiver.next().await {
+            Some(StateKeeperRequest::ExecuteMiniBlock(block)) => assert_eq!(block.txs.len(), 1),
+            _ => panic!("Mini block is not executed"),
+        }
+        block_proposer.drain_mempool().await;
+        assert!(matches!(
+            statekeeper_receiver.next().await,
+            Some(StateKeeperRequest::Maintenance)
+        ));
+    }
+}
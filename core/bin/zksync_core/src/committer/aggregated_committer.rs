@@ -1,7 +1,8 @@
 use chrono::{DateTime, Utc};
-use std::cmp::max;
+use std::cmp::{max, min};
 use std::time::Duration;
 use zksync_crypto::proof::AggregatedProof;
+use zksync_prover_utils::aggregated_proofs::verify_aggregated_proof;
 use zksync_storage::chain::block::BlockSchema;
 use zksync_storage::chain::operations::OperationsSchema;
 use zksync_storage::prover::ProverSchema;
@@ -108,11 +109,11 @@ fn create_new_create_proof_operation(
         return None;
     }
 
-    // get max possible aggregate size
+    // get the smallest aggregate size that fits all the blocks, up to the max one
+    let blocks_to_aggregate = min(new_blocks_with_proofs.len(), max_aggregate_size);
     let aggregate_proof_size = available_aggregate_proof_sizes
         .iter()
-        .rev()
-        .find(|aggregate_size| *aggregate_size >= &new_blocks_with_proofs.len())
+        .find(|aggregate_size| **aggregate_size >= blocks_to_aggregate)
         .cloned()
         .expect("failed to find correct aggregate proof size");
 
@@ -142,6 +143,51 @@ fn create_publish_proof_operation(
     }
 }
 
+/// Checks the aggregated proof before it is sent to the contract, so a corrupted or
+/// mismatched proof does not end up in a reverted `proveBlocks` transaction.
+///
+/// Returns the reason if the proof is invalid, or an error if the proof can't be verified
+/// (e.g. the verification key is not available).
+fn check_aggregated_proof(
+    blocks: &[Block],
+    aggregated_proof: &AggregatedProof,
+) -> anyhow::Result<Option<String>> {
+    let (first_block, last_block) = (
+        blocks.first().map(|b| b.block_number).unwrap_or_default(),
+        blocks.last().map(|b| b.block_number).unwrap_or_default(),
+    );
+
+    let encoded_proof = aggregated_proof.serialize_aggregated_proof();
+    if encoded_proof.individual_vk_inputs.len() != blocks.len() {
+        return Ok(Some(format!(
+            "Aggregated proof for blocks [{},{}] contains {} block commitments, expected {}",
+            first_block,
+            last_block,
+            encoded_proof.individual_vk_inputs.len(),
+            blocks.len()
+        )));
+    }
+    // Contract checks only the lower bits of the commitment, since the input must fit the field.
+    let input_mask = U256::MAX >> 3;
+    for (block, input) in blocks.iter().zip(&encoded_proof.individual_vk_inputs) {
+        let commitment = U256::from_big_endian(block.block_commitment.as_bytes());
+        if commitment & input_mask != *input & input_mask {
+            return Ok(Some(format!(
+                "Aggregated proof for blocks [{},{}] has incorrect commitment of the block {}",
+                first_block, last_block, block.block_number
+            )));
+        }
+    }
+
+    if !verify_aggregated_proof(aggregated_proof)? {
+        return Ok(Some(format!(
+            "Aggregated proof for blocks [{},{}] is invalid",
+            first_block, last_block
+        )));
+    }
+    Ok(None)
+}
+
 fn create_execute_blocks_operation(
     proven_non_executed_block: &[Block],
     current_time: DateTime<Utc>,
@@ -199,7 +245,6 @@ fn create_execute_blocks_operation(
 const MAX_BLOCK_TO_COMMIT: usize = 5;
 const BLOCK_COMMIT_DEADLINE: Duration = Duration::from_secs(10);
 const MAX_GAS_TX: u64 = 2_000_000;
-const AVAILABLE_AGGREGATE_PROOFS: &[usize] = &[1, 5];
 
 async fn create_aggregated_commits_storage(
     storage: &mut StorageProcessor<'_>,
@@ -246,8 +291,11 @@ async fn create_aggregated_commits_storage(
     }
 }
 
+/// Creates the operation aggregating the proofs of the next blocks into one recursive proof,
+/// `aggregated_proof_sizes` are the numbers of blocks a single proof may aggregate, in ascending order.
 async fn create_aggregated_prover_task_storage(
     storage: &mut StorageProcessor<'_>,
+    aggregated_proof_sizes: &[usize],
 ) -> anyhow::Result<bool> {
     let last_committed_block = BlockSchema(storage).get_last_committed_block().await?;
     let last_aggregate_create_proof_block = OperationsSchema(storage)
@@ -276,7 +324,7 @@ async fn create_aggregated_prover_task_storage(
 
     let create_proof_operation = create_new_create_proof_operation(
         &blocks_with_proofs,
-        AVAILABLE_AGGREGATE_PROOFS,
+        aggregated_proof_sizes,
         Utc::now(),
         BLOCK_COMMIT_DEADLINE,
         MAX_GAS_TX.into(),
@@ -293,8 +341,13 @@ async fn create_aggregated_prover_task_storage(
     }
 }
 
+/// Creates the operation publishing the aggregated proof once it's generated.
+///
+/// Proof is checked locally first, unless the dummy verifier is used: placeholder
+/// proofs of the dummy prover are accepted by the dummy verifier only.
 async fn create_aggregated_publish_proof_operation_storage(
     storage: &mut StorageProcessor<'_>,
+    dummy_verifier: bool,
 ) -> anyhow::Result<bool> {
     let last_aggregate_create_proof_block = OperationsSchema(storage)
         .get_last_affected_block_by_aggregated_action(AggregatedActionType::CreateProofBlocks)
@@ -345,6 +398,23 @@ async fn create_aggregated_publish_proof_operation_storage(
     };
 
     if let Some(proof) = aggregated_proof {
+        if !dummy_verifier {
+            let blocks = &last_unpublished_create_proof_operation.blocks;
+            if let Some(reason) = check_aggregated_proof(blocks, &proof)? {
+                // The proof is generated again, so the pipeline is not stalled by the invalid one.
+                metrics::counter!("committer.invalid_aggregated_proof", 1);
+                tracing::error!("{}, the proof job is returned to the queue", reason);
+                storage
+                    .prover_schema()
+                    .requeue_aggregated_proof_job(
+                        blocks.first().unwrap().block_number,
+                        blocks.last().unwrap().block_number,
+                    )
+                    .await?;
+                return Ok(false);
+            }
+        }
+
         let operation =
             create_publish_proof_operation(&last_unpublished_create_proof_operation, &proof);
         let aggregated_op = operation.into();
@@ -403,20 +473,57 @@ async fn create_aggregated_execute_operation_storage(
     }
 }
 
+/// Checks whether the amount of the aggregated operations waiting to be sent to Ethereum
+/// reached `capacity`, so no more operations to be sent should be created.
+async fn eth_sender_queue_is_full(
+    storage: &mut StorageProcessor<'_>,
+    capacity: usize,
+) -> anyhow::Result<bool> {
+    let unsent_operations = OperationsSchema(storage)
+        .count_unsent_aggregated_operations()
+        .await?;
+    metrics::gauge!(
+        "committer.unsent_aggregated_operations",
+        unsent_operations as f64
+    );
+
+    let is_full = unsent_operations >= capacity as i64;
+    if is_full {
+        tracing::debug!(
+            "Ethereum sender queue is full ({} operations), aggregated operations are not created",
+            unsent_operations
+        );
+    }
+    Ok(is_full)
+}
+
+/// Creates the aggregated operations for the new blocks and proofs. Operations sent to Ethereum
+/// are not created while `eth_sender_queue_capacity` of them are waiting to be sent, so they
+/// don't pile up if the Ethereum sender falls behind.
 pub async fn create_aggregated_operations_storage(
     storage: &mut StorageProcessor<'_>,
+    aggregated_proof_sizes: &[usize],
+    dummy_verifier: bool,
+    eth_sender_queue_capacity: usize,
 ) -> anyhow::Result<()> {
-    while create_aggregated_commits_storage(storage).await? {}
-    while create_aggregated_prover_task_storage(storage).await? {}
-    while create_aggregated_publish_proof_operation_storage(storage).await? {}
-    while create_aggregated_execute_operation_storage(storage).await? {}
+    while !eth_sender_queue_is_full(storage, eth_sender_queue_capacity).await?
+        && create_aggregated_commits_storage(storage).await?
+    {}
+    // Proofs are created off-chain, so the prover tasks are not limited.
+    while create_aggregated_prover_task_storage(storage, aggregated_proof_sizes).await? {}
+    while !eth_sender_queue_is_full(storage, eth_sender_queue_capacity).await?
+        && create_aggregated_publish_proof_operation_storage(storage, dummy_verifier).await?
+    {}
+    while !eth_sender_queue_is_full(storage, eth_sender_queue_capacity).await?
+        && create_aggregated_execute_operation_storage(storage).await?
+    {}
 
     Ok(())
 }
 
 fn log_aggregated_op_creation(aggregated_op: &AggregatedOperation) {
     let (first, last) = aggregated_op.get_block_range();
-    log::info!(
+    tracing::info!(
         "Created aggregated operation: {}, blocks: [{},{}]",
         aggregated_op.get_action_type().to_string(),
         first,
@@ -1,4 +1,8 @@
 // Built-in uses
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
 use std::time::{Duration, Instant};
 // External uses
 use futures::channel::mpsc::{Receiver, Sender};
@@ -7,11 +11,13 @@ use serde::{Deserialize, Serialize};
 use tokio::{task::JoinHandle, time};
 // Workspace uses
 use crate::mempool::MempoolRequest;
+use zksync_prometheus_exporter::{register_metrics, Metric};
 use zksync_storage::ConnectionPool;
 use zksync_types::{
     block::{Block, ExecutedOperations, PendingBlock},
     AccountUpdates, Action, BlockNumber, Operation,
 };
+use zksync_utils::shutdown::ShutdownSignal;
 
 mod aggregated_committer;
 
@@ -38,14 +44,69 @@ pub struct ExecutedOpsNotify {
     pub block_number: BlockNumber,
 }
 
+/// Amount of the requests sent by the state keeper which are not handled by the committer yet.
+///
+/// The requests are sent through a bounded channel which doesn't expose its length,
+/// so the state keeper increments the depth before sending a request, and the committer
+/// decrements it once the request is received.
+#[derive(Debug, Clone, Default)]
+pub struct CommitQueueDepth(Arc<AtomicUsize>);
+
+impl CommitQueueDepth {
+    pub fn get(&self) -> usize {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    pub fn increment(&self) {
+        let depth = self.0.fetch_add(1, Ordering::SeqCst) + 1;
+        metrics::gauge!("committer.queue_depth", depth as f64);
+    }
+
+    fn decrement(&self) {
+        let depth = self.0.fetch_sub(1, Ordering::SeqCst) - 1;
+        metrics::gauge!("committer.queue_depth", depth as f64);
+    }
+}
+
 const PROOF_POLL_INTERVAL: Duration = Duration::from_secs(1);
 
+/// Metrics reported by the committer.
+const METRICS: &[Metric] = &[
+    Metric::histogram(
+        "committer.commit_block",
+        "Time spent on storing a sealed block",
+    ),
+    Metric::histogram(
+        "committer.save_pending_block",
+        "Time spent on storing the pending block",
+    ),
+    Metric::gauge(
+        "committer.queue_depth",
+        "Number of the requests sent by the state keeper and not handled by the committer yet",
+    ),
+    Metric::gauge(
+        "committer.last_committed_block",
+        "Number of the latest block stored by the committer",
+    ),
+    Metric::counter(
+        "committer.invalid_aggregated_proof",
+        "Number of the aggregated proofs failed to be verified",
+    ),
+    Metric::gauge(
+        "committer.unsent_aggregated_operations",
+        "Number of the aggregated operations not sent to Ethereum yet",
+    ),
+];
+
 async fn handle_new_commit_task(
     mut rx_for_ops: Receiver<CommitRequest>,
     mut mempool_req_sender: Sender<MempoolRequest>,
     pool: ConnectionPool,
+    queue_depth: CommitQueueDepth,
+    shutdown: ShutdownSignal,
 ) {
     while let Some(request) = rx_for_ops.next().await {
+        queue_depth.decrement();
         match request {
             CommitRequest::Block((block_commit_request, applied_updates_req)) => {
                 commit_block(
@@ -69,8 +130,17 @@ async fn handle_new_commit_task(
             }
         }
     }
+
+    // The state keeper has stopped, and all the blocks it has sealed are stored.
+    tracing::info!("Committer stopped");
+    shutdown.mark_stopped();
 }
 
+#[tracing::instrument(
+    name = "save_pending_block",
+    skip(pending_block, applied_updates_request, pool),
+    fields(block = pending_block.number)
+)]
 async fn save_pending_block(
     pending_block: PendingBlock,
     applied_updates_request: AppliedUpdatesRequest,
@@ -89,7 +159,7 @@ async fn save_pending_block(
 
     let block_number = pending_block.number;
 
-    log::trace!("persist pending block #{}", block_number);
+    tracing::trace!("Persisting pending block");
 
     transaction
         .chain()
@@ -117,6 +187,11 @@ async fn save_pending_block(
     metrics::histogram!("committer.save_pending_block", start.elapsed());
 }
 
+#[tracing::instrument(
+    name = "commit_block",
+    skip(block_commit_request, applied_updates_request, pool, mempool_req_sender),
+    fields(block = block_commit_request.block.block_number)
+)]
 async fn commit_block(
     block_commit_request: BlockCommitRequest,
     applied_updates_request: AppliedUpdatesRequest,
@@ -155,7 +230,7 @@ async fn commit_block(
         block,
         id: None,
     };
-    log::info!("commit block #{}", op.block.block_number);
+    tracing::info!("Committing block");
     transaction
         .chain()
         .block_schema()
@@ -166,7 +241,7 @@ async fn commit_block(
     mempool_req_sender
         .send(MempoolRequest::UpdateNonces(accounts_updated))
         .await
-        .map_err(|e| log::warn!("Failed notify mempool about account updates: {}", e))
+        .map_err(|e| tracing::warn!("Failed notify mempool about account updates: {}", e))
         .unwrap_or_default();
 
     transaction
@@ -174,10 +249,32 @@ async fn commit_block(
         .await
         .expect("Unable to commit DB transaction");
 
+    for executed_tx in op
+        .block
+        .block_transactions
+        .iter()
+        .filter_map(ExecutedOperations::get_executed_tx)
+    {
+        tracing::debug!(
+            tx_hash = %executed_tx.signed_tx.hash().to_string(),
+            success = executed_tx.success,
+            "Transaction committed"
+        );
+    }
+
+    metrics::gauge!(
+        "committer.last_committed_block",
+        op.block.block_number as f64
+    );
     metrics::histogram!("committer.commit_block", start.elapsed());
 }
 
-async fn poll_for_new_proofs_task(pool: ConnectionPool) {
+async fn poll_for_new_proofs_task(
+    pool: ConnectionPool,
+    aggregated_proof_sizes: Vec<usize>,
+    dummy_verifier: bool,
+    eth_sender_queue_capacity: usize,
+) {
     let mut timer = time::interval(PROOF_POLL_INTERVAL);
     loop {
         timer.tick().await;
@@ -187,10 +284,15 @@ async fn poll_for_new_proofs_task(pool: ConnectionPool) {
             .await
             .expect("db connection failed for committer");
 
-        aggregated_committer::create_aggregated_operations_storage(&mut storage)
-            .await
-            .map_err(|e| log::error!("Failed to create aggregated operation: {}", e))
-            .unwrap_or_default();
+        aggregated_committer::create_aggregated_operations_storage(
+            &mut storage,
+            &aggregated_proof_sizes,
+            dummy_verifier,
+            eth_sender_queue_capacity,
+        )
+        .await
+        .map_err(|e| vlog::critical!("Failed to create aggregated operation: {}", e))
+        .unwrap_or_default();
     }
 }
 
@@ -199,11 +301,24 @@ pub fn run_committer(
     rx_for_ops: Receiver<CommitRequest>,
     mempool_req_sender: Sender<MempoolRequest>,
     pool: ConnectionPool,
+    aggregated_proof_sizes: Vec<usize>,
+    dummy_verifier: bool,
+    eth_sender_queue_capacity: usize,
+    queue_depth: CommitQueueDepth,
+    shutdown: ShutdownSignal,
 ) -> JoinHandle<()> {
+    register_metrics("committer", METRICS);
     tokio::spawn(handle_new_commit_task(
         rx_for_ops,
         mempool_req_sender,
         pool.clone(),
+        queue_depth,
+        shutdown,
     ));
-    tokio::spawn(poll_for_new_proofs_task(pool))
+    tokio::spawn(poll_for_new_proofs_task(
+        pool,
+        aggregated_proof_sizes,
+        dummy_verifier,
+        eth_sender_queue_capacity,
+    ))
 }
@@ -10,8 +10,11 @@ use web3::{
     Web3,
 };
 
-use zksync_contracts::zksync_contract;
-use zksync_types::{ethereum::CompleteWithdrawalsTx, Address, Nonce, PriorityOp, H160};
+use zksync_contracts::{upgrade_gatekeeper_contract, zksync_contract};
+use zksync_types::{
+    ethereum::{CompleteWithdrawalsTx, ContractUpgradeEvent, ContractUpgradeStage},
+    Address, Nonce, PriorityOp, H160,
+};
 
 struct ContractTopics {
     new_priority_request: Hash,
@@ -34,6 +37,38 @@ impl ContractTopics {
     }
 }
 
+/// Signatures of the upgrade gatekeeper events along with the upgrade stages they start.
+struct UpgradeTopics(Vec<(Hash, ContractUpgradeStage)>);
+
+impl UpgradeTopics {
+    fn new(upgrade_gatekeeper_contract: &ethabi::Contract) -> Self {
+        let topics = [
+            ("NoticePeriodStart", ContractUpgradeStage::NoticePeriod),
+            ("PreparationStart", ContractUpgradeStage::Preparation),
+            ("UpgradeCancel", ContractUpgradeStage::Cancelled),
+            ("UpgradeComplete", ContractUpgradeStage::Completed),
+        ]
+        .iter()
+        .map(|(event, stage)| {
+            let signature = upgrade_gatekeeper_contract
+                .event(event)
+                .expect("upgrade gatekeeper contract abi error")
+                .signature();
+            (signature, *stage)
+        })
+        .collect();
+
+        Self(topics)
+    }
+
+    fn stage(&self, topic: &Hash) -> Option<ContractUpgradeStage> {
+        self.0
+            .iter()
+            .find(|(signature, _)| signature == topic)
+            .map(|(_, stage)| *stage)
+    }
+}
+
 #[async_trait::async_trait]
 pub trait EthClient {
     async fn get_priority_op_events(
@@ -46,6 +81,12 @@ pub trait EthClient {
         from: BlockNumber,
         to: BlockNumber,
     ) -> anyhow::Result<Vec<CompleteWithdrawalsTx>>;
+    /// Loads the events of the upgrade gatekeeper changing the stage of the contract upgrades.
+    async fn get_contract_upgrade_events(
+        &self,
+        from: BlockNumber,
+        to: BlockNumber,
+    ) -> anyhow::Result<Vec<ContractUpgradeEvent>>;
     async fn block_number(&self) -> anyhow::Result<u64>;
     async fn get_auth_fact(&self, address: Address, nonce: Nonce) -> anyhow::Result<Vec<u8>>;
     async fn get_first_pending_withdrawal_index(&self) -> anyhow::Result<u32>;
@@ -56,17 +97,26 @@ pub struct EthHttpClient {
     web3: Web3<Http>,
     zksync_contract: Contract<Http>,
     topics: ContractTopics,
+    upgrade_gatekeeper_addr: H160,
+    upgrade_topics: UpgradeTopics,
 }
 
 impl EthHttpClient {
-    pub fn new(web3: Web3<Http>, zksync_contract_addr: H160) -> Self {
+    pub fn new(
+        web3: Web3<Http>,
+        zksync_contract_addr: H160,
+        upgrade_gatekeeper_addr: H160,
+    ) -> Self {
         let zksync_contract = Contract::new(web3.eth(), zksync_contract_addr, zksync_contract());
 
         let topics = ContractTopics::new(zksync_contract.abi());
+        let upgrade_topics = UpgradeTopics::new(&upgrade_gatekeeper_contract());
         Self {
             zksync_contract,
             web3,
             topics,
+            upgrade_gatekeeper_addr,
+            upgrade_topics,
         }
     }
 
@@ -134,6 +184,45 @@ impl EthClient for EthHttpClient {
         result
     }
 
+    async fn get_contract_upgrade_events(
+        &self,
+        from: BlockNumber,
+        to: BlockNumber,
+    ) -> anyhow::Result<Vec<ContractUpgradeEvent>> {
+        let filter = FilterBuilder::default()
+            .address(vec![self.upgrade_gatekeeper_addr])
+            .from_block(from)
+            .to_block(to)
+            .topics(
+                Some(
+                    self.upgrade_topics
+                        .0
+                        .iter()
+                        .map(|(topic, _)| *topic)
+                        .collect(),
+                ),
+                None,
+                None,
+                None,
+            )
+            .build();
+
+        self.web3
+            .eth()
+            .logs(filter)
+            .await?
+            .iter()
+            .map(|event| {
+                let stage = event
+                    .topics
+                    .first()
+                    .and_then(|topic| self.upgrade_topics.stage(topic))
+                    .ok_or_else(|| format_err!("Unexpected upgrade gatekeeper event"))?;
+                ContractUpgradeEvent::from_log(stage, event)
+            })
+            .collect()
+    }
+
     async fn block_number(&self) -> anyhow::Result<u64> {
         Ok(self.web3.eth().block_number().await?.as_u64())
     }
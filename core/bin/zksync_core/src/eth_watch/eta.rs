@@ -0,0 +1,155 @@
+//! Estimation of the time until the deposited funds become usable.
+//!
+//! Once the Ethereum watcher sees a new deposit, it acknowledges it: the deposit is stored
+//! along with the estimated time of its execution, so wallets can show a countdown
+//! long before the deposit has enough confirmations to be processed.
+
+// Built-in deps
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+// External uses
+use chrono::{DateTime, Utc};
+// Workspace deps
+use zksync_config::ConfigurationOptions;
+use zksync_types::PriorityOp;
+
+/// Interval between the Ethereum blocks assumed until the watcher observes enough blocks.
+pub const DEFAULT_ETH_BLOCK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Amount of the latest observations used to estimate the block interval.
+const MAX_OBSERVATIONS: usize = 32;
+
+/// Deposit seen by the Ethereum watcher before it has enough confirmations,
+/// along with the estimated time when the deposited funds become usable.
+#[derive(Debug, Clone)]
+pub struct DepositAcknowledgment {
+    pub op: PriorityOp,
+    pub estimated_usable_at: DateTime<Utc>,
+}
+
+/// Tracks the cadence of the Ethereum blocks as observed by the watcher.
+#[derive(Debug, Default)]
+pub struct BlockCadence {
+    /// Numbers of the observed blocks along with the time of their observation.
+    observations: VecDeque<(u64, Instant)>,
+}
+
+impl BlockCadence {
+    pub fn observe(&mut self, block_number: u64, observed_at: Instant) {
+        if let Some((last_block, _)) = self.observations.back() {
+            if *last_block >= block_number {
+                return;
+            }
+        }
+
+        self.observations.push_back((block_number, observed_at));
+        if self.observations.len() > MAX_OBSERVATIONS {
+            self.observations.pop_front();
+        }
+    }
+
+    /// Returns the average interval between the observed blocks, or the default one
+    /// if the watcher didn't observe enough blocks yet.
+    pub fn block_interval(&self) -> Duration {
+        match (self.observations.front(), self.observations.back()) {
+            (Some((first_block, first_seen)), Some((last_block, last_seen)))
+                if last_block > first_block =>
+            {
+                (*last_seen - *first_seen) / (last_block - first_block) as u32
+            }
+            _ => DEFAULT_ETH_BLOCK_INTERVAL,
+        }
+    }
+}
+
+/// Timings of the deposit processing after it gets enough confirmations.
+#[derive(Debug, Clone, Default)]
+pub struct ProcessingTimings {
+    /// Interval of the Ethereum watcher polls, the confirmed deposit is noticed on the next one.
+    pub poll_interval: Duration,
+    /// Interval of the state keeper miniblocks, each of them executes the queued priority operations.
+    pub miniblock_interval: Duration,
+    /// Maximum amount of chunks of the priority operations executed in one miniblock.
+    pub max_block_chunks: usize,
+}
+
+impl ProcessingTimings {
+    pub fn from_config(config: &ConfigurationOptions) -> Self {
+        Self {
+            poll_interval: config.eth_watch_poll_interval,
+            miniblock_interval: config.miniblock_timings.miniblock_iteration_interval,
+            max_block_chunks: *config
+                .available_block_chunk_sizes
+                .iter()
+                .max()
+                .expect("failed to find max block chunks size"),
+        }
+    }
+
+    /// Estimates the time until the deposit is executed by the state keeper:
+    ///
+    /// - the deposit waits for `blocks_left` confirmations;
+    /// - the watcher notices it on the next poll;
+    /// - the state keeper executes the `queued_chunks` of the priority operations (including
+    ///   the deposit itself), taking no more than one block of them per miniblock.
+    pub fn time_until_usable(
+        &self,
+        blocks_left: u64,
+        queued_chunks: usize,
+        block_interval: Duration,
+    ) -> Duration {
+        let max_block_chunks = self.max_block_chunks.max(1);
+        let miniblocks = (queued_chunks + max_block_chunks - 1) / max_block_chunks;
+
+        block_interval * blocks_left as u32
+            + self.poll_interval
+            + self.miniblock_interval * miniblocks as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn block_interval() {
+        let mut cadence = BlockCadence::default();
+        assert_eq!(cadence.block_interval(), DEFAULT_ETH_BLOCK_INTERVAL);
+
+        let start = Instant::now();
+        cadence.observe(10, start);
+        assert_eq!(cadence.block_interval(), DEFAULT_ETH_BLOCK_INTERVAL);
+
+        cadence.observe(12, start + Duration::from_secs(20));
+        // Outdated observations are ignored.
+        cadence.observe(11, start + Duration::from_secs(25));
+        cadence.observe(14, start + Duration::from_secs(40));
+        assert_eq!(cadence.block_interval(), Duration::from_secs(10));
+
+        for block in 15..100 {
+            cadence.observe(block, start + Duration::from_secs(40 + (block - 14) * 2));
+        }
+        assert_eq!(cadence.block_interval(), Duration::from_secs(2));
+    }
+
+    #[test]
+    fn time_until_usable() {
+        let timings = ProcessingTimings {
+            poll_interval: Duration::from_secs(5),
+            miniblock_interval: Duration::from_millis(200),
+            max_block_chunks: 10,
+        };
+        let block_interval = Duration::from_secs(10);
+
+        assert_eq!(
+            timings.time_until_usable(0, 6, block_interval),
+            Duration::from_millis(5200)
+        );
+        assert_eq!(
+            timings.time_until_usable(3, 25, block_interval),
+            Duration::from_millis(35600)
+        );
+    }
+}
@@ -4,6 +4,19 @@
 //!
 //! Poll interval is configured using the `ETH_POLL_INTERVAL` constant.
 //! Number of confirmations is configured using the `CONFIRMATIONS_FOR_ETH_EVENT` environment variable.
+//!
+//! Deposits are acknowledged as soon as they're seen: the acknowledgment with the estimated time
+//! until the deposited funds are usable is stored in the database for the API to report it.
+//! The acknowledgment is updated once the estimate changes noticeably, and removed once
+//! the deposit is executed by the state keeper.
+//!
+//! Pending priority operations can be claimed by the hash of their Ethereum transaction: the watcher
+//! reports the position of the operations in the queue and the expected time of their inclusion.
+//! Operations not processed by the watcher yet are looked up on Ethereum by the API server, and
+//! the watcher only estimates them.
+//!
+//! Confirmed events of the upgrade gatekeeper are stored in the database as well, so `eth_sender`
+//! doesn't send transactions against the outdated interface once the contract is upgraded.
 
 // Built-in deps
 use std::{
@@ -17,27 +30,35 @@ use futures::{
     SinkExt, StreamExt,
 };
 
+use chrono::{DateTime, Utc};
 use tokio::{task::JoinHandle, time};
 use web3::types::{Address, BlockNumber};
 
 // Workspace deps
 use zksync_config::ConfigurationOptions;
 use zksync_crypto::params::PRIORITY_EXPIRATION;
+use zksync_prometheus_exporter::{register_metrics, Metric};
 use zksync_storage::ConnectionPool;
-use zksync_types::{Nonce, PriorityOp, PubKeyHash, ZkSyncPriorityOp};
+use zksync_types::{
+    ethereum::ContractUpgradeEvent, Nonce, PriorityOp, PriorityOpClaim, PriorityOpClaimSource,
+    PubKeyHash, SerialId, ZkSyncPriorityOp, H256,
+};
 
 // Local deps
 use self::{
     client::EthClient,
+    eta::{BlockCadence, DepositAcknowledgment},
     eth_state::ETHState,
     received_ops::{sift_outdated_ops, ReceivedPriorityOp},
     storage::Storage,
 };
 
 pub use client::EthHttpClient;
+pub use eta::ProcessingTimings;
 pub use storage::DBStorage;
 
 mod client;
+mod eta;
 mod eth_state;
 mod received_ops;
 mod storage;
@@ -48,6 +69,32 @@ mod tests;
 /// As `infura` may limit the requests, upon error we need to wait for a while
 /// before repeating the request.
 const RATE_LIMIT_DELAY: Duration = Duration::from_secs(30);
+/// Stored acknowledgment of the deposit is updated once its estimate changes by this interval.
+const ETA_UPDATE_THRESHOLD: Duration = Duration::from_secs(30);
+
+/// Metrics reported by the Ethereum watcher.
+const METRICS: &[Metric] = &[
+    Metric::histogram(
+        "eth_watcher.poll_eth_node",
+        "Time spent on processing the new Ethereum blocks",
+    ),
+    Metric::histogram(
+        "eth_watcher.get_priority_op_events",
+        "Time spent on loading the priority operation events",
+    ),
+    Metric::gauge(
+        "eth_watcher.last_processed_block",
+        "Number of the latest Ethereum block processed by the watcher",
+    ),
+    Metric::counter(
+        "eth_watcher.deposit_acknowledgments",
+        "Number of the deposits acknowledged before being confirmed",
+    ),
+    Metric::counter(
+        "eth_watcher.contract_upgrade_events",
+        "Number of the confirmed events of the upgrade gatekeeper",
+    ),
+];
 
 /// Ethereum Watcher operating mode.
 ///
@@ -86,6 +133,14 @@ pub enum EthWatchRequest {
         eth_hash: Vec<u8>,
         resp: oneshot::Sender<Option<PriorityOp>>,
     },
+    ClaimPriorityOps {
+        eth_hash: H256,
+        resp: oneshot::Sender<Vec<PriorityOpClaim>>,
+    },
+    EstimatePriorityOps {
+        ops: Vec<PriorityOp>,
+        resp: oneshot::Sender<Vec<PriorityOpClaim>>,
+    },
 }
 
 pub struct EthWatch<W: EthClient, S: Storage> {
@@ -95,16 +150,40 @@ pub struct EthWatch<W: EthClient, S: Storage> {
     /// All ethereum events are accepted after sufficient confirmations to eliminate risk of block reorg.
     number_of_confirmations_for_event: u64,
     mode: WatcherMode,
+    /// Timings of the deposit processing used to estimate the time until the deposit is usable.
+    processing_timings: ProcessingTimings,
+    block_cadence: BlockCadence,
+    /// Serial ID of the first priority operation not processed by the state keeper yet,
+    /// unknown until the state keeper requests the priority operations.
+    next_priority_op_id: Option<SerialId>,
+    /// Ethereum transaction hashes of the acknowledged deposits which are not executed yet,
+    /// along with the stored estimates.
+    acknowledged_deposits: HashMap<SerialId, (Vec<u8>, DateTime<Utc>)>,
+    /// Acknowledgments of the priority operations before this one are removed from the database.
+    acknowledgments_removed_before: SerialId,
+    /// Last Ethereum block checked for the events of the upgrade gatekeeper.
+    last_upgrade_events_block: Option<u64>,
 }
 
 impl<W: EthClient, S: Storage> EthWatch<W, S> {
-    pub fn new(client: W, storage: S, number_of_confirmations_for_event: u64) -> Self {
+    pub fn new(
+        client: W,
+        storage: S,
+        number_of_confirmations_for_event: u64,
+        processing_timings: ProcessingTimings,
+    ) -> Self {
         Self {
             client,
             storage,
             eth_state: ETHState::default(),
             mode: WatcherMode::Working,
             number_of_confirmations_for_event,
+            processing_timings,
+            block_cadence: BlockCadence::default(),
+            next_priority_op_id: None,
+            acknowledged_deposits: HashMap::new(),
+            acknowledgments_removed_before: 0,
+            last_upgrade_events_block: None,
         }
     }
 
@@ -158,7 +237,7 @@ impl<W: EthClient, S: Storage> EthWatch<W, S> {
         let new_state = ETHState::new(last_ethereum_block, unconfirmed_queue, priority_queue);
 
         self.set_new_state(new_state);
-        log::trace!("ETH state: {:#?}", self.eth_state);
+        tracing::trace!("ETH state: {:#?}", self.eth_state);
         Ok(())
     }
 
@@ -172,6 +251,12 @@ impl<W: EthClient, S: Storage> EthWatch<W, S> {
         let previous_block_with_accepted_events =
             new_block_with_accepted_events.saturating_sub(depth_of_last_approved_block);
 
+        self.process_contract_upgrades(
+            previous_block_with_accepted_events,
+            new_block_with_accepted_events,
+        )
+        .await?;
+
         let unconfirmed_queue = self.get_unconfirmed_ops(current_ethereum_block).await?;
         let priority_queue = self
             .client
@@ -187,6 +272,45 @@ impl<W: EthClient, S: Storage> EthWatch<W, S> {
         Ok((unconfirmed_queue, priority_queue))
     }
 
+    /// Stores the confirmed events of the upgrade gatekeeper from the blocks not checked yet.
+    async fn process_contract_upgrades(&mut self, from: u64, to: u64) -> anyhow::Result<()> {
+        let from = match self.last_upgrade_events_block {
+            Some(last_block) => from.max(last_block + 1),
+            None => from,
+        };
+        if from > to {
+            return Ok(());
+        }
+
+        let events = self
+            .client
+            .get_contract_upgrade_events(
+                BlockNumber::Number(from.into()),
+                BlockNumber::Number(to.into()),
+            )
+            .await?;
+        if !events.is_empty() {
+            for ContractUpgradeEvent {
+                version_id,
+                stage,
+                eth_block,
+            } in &events
+            {
+                tracing::info!(
+                    "Contract upgrade to version {} entered the {} stage in Ethereum block {}",
+                    version_id,
+                    stage,
+                    eth_block
+                );
+            }
+            metrics::counter!("eth_watcher.contract_upgrade_events", events.len() as u64);
+            self.storage.store_contract_upgrade_events(events).await?;
+        }
+
+        self.last_upgrade_events_block = Some(to);
+        Ok(())
+    }
+
     fn get_priority_requests(&self, first_serial_id: u64, max_chunks: usize) -> Vec<PriorityOp> {
         let mut result = Vec::new();
 
@@ -239,14 +363,222 @@ impl<W: EthClient, S: Storage> EthWatch<W, S> {
             .collect()
     }
 
+    /// Returns the amount of chunks of the priority operations which are to be executed
+    /// before the given one.
+    fn queued_chunks_before(&self, next_serial_id: SerialId, serial_id: SerialId) -> usize {
+        let queued = self
+            .eth_state
+            .priority_queue()
+            .values()
+            .map(AsRef::as_ref)
+            .chain(self.eth_state.unconfirmed_queue());
+
+        queued
+            .filter(|op| (next_serial_id..serial_id).contains(&op.serial_id))
+            .map(|op| op.data.chunks())
+            .sum()
+    }
+
+    /// Finds all the pending priority operations requested by the Ethereum transaction
+    /// and estimates their inclusion.
+    ///
+    /// Operations not processed by the watcher yet are looked up on Ethereum by the API server
+    /// and estimated via `estimate_priority_ops`, so the requests of the users don't make
+    /// the watcher wait for the Ethereum node.
+    fn claim_priority_ops(&self, eth_hash: H256) -> Vec<PriorityOpClaim> {
+        let mut claims: Vec<_> = self
+            .eth_state
+            .unconfirmed_queue()
+            .iter()
+            .chain(self.eth_state.priority_queue().values().map(AsRef::as_ref))
+            .filter(|op| op.eth_hash.as_slice() == eth_hash.as_bytes())
+            .map(|op| self.claim_priority_op(op.clone(), PriorityOpClaimSource::EthWatch, 0))
+            .collect();
+        claims.sort_by_key(|claim| claim.op.serial_id);
+        claims
+    }
+
+    /// Estimates the inclusion of the priority operations found on Ethereum. Operations already
+    /// known to the watcher are estimated as usual.
+    fn estimate_priority_ops(&self, mut ops: Vec<PriorityOp>) -> Vec<PriorityOpClaim> {
+        ops.sort_by_key(|op| op.serial_id);
+
+        let mut unknown_chunks = 0;
+        let mut claims = Vec::with_capacity(ops.len());
+        for op in ops {
+            let is_known = self.eth_state.priority_queue().contains_key(&op.serial_id)
+                || self
+                    .eth_state
+                    .unconfirmed_queue()
+                    .iter()
+                    .any(|known_op| known_op.serial_id == op.serial_id);
+            if is_known {
+                claims.push(self.claim_priority_op(op, PriorityOpClaimSource::EthWatch, 0));
+            } else {
+                let chunks = op.data.chunks();
+                claims.push(self.claim_priority_op(
+                    op,
+                    PriorityOpClaimSource::Ethereum,
+                    unknown_chunks,
+                ));
+                unknown_chunks += chunks;
+            }
+        }
+        claims
+    }
+
+    /// Estimates the inclusion of the priority operation. `unknown_chunks` is the amount of
+    /// chunks of the preceding operations which are not processed by the watcher yet.
+    fn claim_priority_op(
+        &self,
+        op: PriorityOp,
+        source: PriorityOpClaimSource,
+        unknown_chunks: usize,
+    ) -> PriorityOpClaim {
+        let confirmations_left = (op.eth_block + self.number_of_confirmations_for_event)
+            .saturating_sub(self.eth_state.last_ethereum_block());
+        let (queue_position, estimated_inclusion_at) = match self.next_priority_op_id {
+            Some(next_serial_id) => {
+                let queued_chunks = self.queued_chunks_before(next_serial_id, op.serial_id)
+                    + unknown_chunks
+                    + op.data.chunks();
+                let time_until_inclusion = self.processing_timings.time_until_usable(
+                    confirmations_left,
+                    queued_chunks,
+                    self.block_cadence.block_interval(),
+                );
+                let estimated_inclusion_at = Utc::now()
+                    + chrono::Duration::from_std(time_until_inclusion)
+                        .expect("Estimated time is out of range");
+
+                (
+                    Some(op.serial_id.saturating_sub(next_serial_id)),
+                    Some(estimated_inclusion_at),
+                )
+            }
+            // Queue of the state keeper is unknown yet.
+            None => (None, None),
+        };
+
+        PriorityOpClaim {
+            op,
+            source,
+            confirmations_left,
+            queue_position,
+            estimated_inclusion_at,
+        }
+    }
+
+    /// Stores the acknowledgments of the unconfirmed deposits seen for the first time, updates
+    /// the ones which estimate has changed by more than `ETA_UPDATE_THRESHOLD` until the deposits
+    /// are executed, and removes the ones of the executed deposits.
+    async fn acknowledge_deposits(&mut self) -> anyhow::Result<()> {
+        // Queue of the state keeper is unknown yet, so the deposits are acknowledged later.
+        let next_serial_id = match self.next_priority_op_id {
+            Some(serial_id) => serial_id,
+            None => return Ok(()),
+        };
+
+        if next_serial_id > self.acknowledgments_removed_before {
+            self.storage
+                .remove_deposit_acknowledgments(next_serial_id)
+                .await?;
+            self.acknowledgments_removed_before = next_serial_id;
+        }
+
+        // Deposits waiting for the confirmations or for the execution by the state keeper.
+        let pending_deposits: Vec<&PriorityOp> = self
+            .eth_state
+            .priority_queue()
+            .values()
+            .map(AsRef::as_ref)
+            .filter(|op| op.serial_id >= next_serial_id)
+            .chain(self.eth_state.unconfirmed_queue())
+            .filter(|op| matches!(op.data, ZkSyncPriorityOp::Deposit(_)))
+            .collect();
+        // Executed and reverted deposits don't need to be tracked anymore.
+        self.acknowledged_deposits
+            .retain(|serial_id, (eth_hash, _)| {
+                pending_deposits
+                    .iter()
+                    .any(|op| op.serial_id == *serial_id && op.eth_hash == *eth_hash)
+            });
+
+        let now = Utc::now();
+        let update_threshold = chrono::Duration::from_std(ETA_UPDATE_THRESHOLD)
+            .expect("Update threshold is out of range");
+        let block_interval = self.block_cadence.block_interval();
+        let last_block = self.eth_state.last_ethereum_block();
+        let mut new_acknowledgments = 0;
+        let acknowledgments: Vec<_> = pending_deposits
+            .iter()
+            .filter_map(|op| {
+                let blocks_left = (op.eth_block + self.number_of_confirmations_for_event)
+                    .saturating_sub(last_block);
+                let queued_chunks =
+                    self.queued_chunks_before(next_serial_id, op.serial_id) + op.data.chunks();
+                let time_until_usable = self.processing_timings.time_until_usable(
+                    blocks_left,
+                    queued_chunks,
+                    block_interval,
+                );
+                let estimated_usable_at = now
+                    + chrono::Duration::from_std(time_until_usable)
+                        .expect("Estimated time is out of range");
+
+                if let Some((_, stored)) = self.acknowledged_deposits.get(&op.serial_id) {
+                    let change = (estimated_usable_at - *stored).num_milliseconds().abs();
+                    if change < update_threshold.num_milliseconds() {
+                        return None;
+                    }
+                } else if op.eth_block + self.number_of_confirmations_for_event <= last_block {
+                    // Deposits confirmed before the watcher has seen them are not acknowledged.
+                    return None;
+                } else {
+                    new_acknowledgments += 1;
+                }
+                Some(DepositAcknowledgment {
+                    op: (*op).clone(),
+                    estimated_usable_at,
+                })
+            })
+            .collect();
+        if acknowledgments.is_empty() {
+            return Ok(());
+        }
+
+        let acknowledged: Vec<_> = acknowledgments
+            .iter()
+            .map(|ack| {
+                (
+                    ack.op.serial_id,
+                    (ack.op.eth_hash.clone(), ack.estimated_usable_at),
+                )
+            })
+            .collect();
+        metrics::counter!("eth_watcher.deposit_acknowledgments", new_acknowledgments);
+        self.storage
+            .store_deposit_acknowledgments(acknowledgments)
+            .await?;
+        self.acknowledged_deposits.extend(acknowledged);
+        Ok(())
+    }
+
     async fn poll_eth_node(&mut self) -> anyhow::Result<()> {
         let start = Instant::now();
         let last_block_number = self.client.block_number().await?;
+        self.block_cadence
+            .observe(last_block_number, Instant::now());
 
         if last_block_number > self.eth_state.last_ethereum_block() {
             self.process_new_blocks(last_block_number).await?;
         }
+        self.acknowledge_deposits().await?;
 
+        metrics::gauge!(
+            "eth_watcher.last_processed_block",
+            self.eth_state.last_ethereum_block() as f64
+        );
         metrics::histogram!("eth_watcher.poll_eth_node", start.elapsed());
         Ok(())
     }
@@ -266,7 +598,7 @@ impl<W: EthClient, S: Storage> EthWatch<W, S> {
             WatcherMode::Working => true,
             WatcherMode::Backoff(delay_until) => {
                 if Instant::now() >= delay_until {
-                    log::info!("Exiting the backoff mode");
+                    tracing::info!("Exiting the backoff mode");
                     self.mode = WatcherMode::Working;
                     true
                 } else {
@@ -289,7 +621,7 @@ impl<W: EthClient, S: Storage> EthWatch<W, S> {
                     break block;
                 }
                 Err(error) => {
-                    log::warn!(
+                    tracing::warn!(
                         "Unable to fetch last block number: '{}'. Retrying again in {} seconds",
                         error,
                         RATE_LIMIT_DELAY.as_secs()
@@ -320,7 +652,7 @@ impl<W: EthClient, S: Storage> EthWatch<W, S> {
 
                     if let Err(error) = poll_result {
                         if self.is_backoff_requested(&error) {
-                            log::warn!(
+                            tracing::warn!(
                                 "Rate limit was reached, as reported by Ethereum node. \
                                 Entering the backoff mode"
                             );
@@ -328,7 +660,7 @@ impl<W: EthClient, S: Storage> EthWatch<W, S> {
                         } else {
                             // Some unexpected kind of error, we won't shutdown the node because of it,
                             // but rather expect node administrators to handle the situation.
-                            log::error!("Failed to process new blocks {}", error);
+                            vlog::critical!("Failed to process new blocks {}", error);
                         }
                     }
                 }
@@ -337,6 +669,8 @@ impl<W: EthClient, S: Storage> EthWatch<W, S> {
                     max_chunks,
                     resp,
                 } => {
+                    // State keeper requests the operations starting from the first unprocessed one.
+                    self.next_priority_op_id = Some(op_start_id);
                     resp.send(self.get_priority_requests(op_start_id, max_chunks))
                         .unwrap_or_default();
                 }
@@ -348,6 +682,14 @@ impl<W: EthClient, S: Storage> EthWatch<W, S> {
                     let unconfirmed_op = self.find_ongoing_op_by_hash(&eth_hash);
                     resp.send(unconfirmed_op).unwrap_or_default();
                 }
+                EthWatchRequest::ClaimPriorityOps { eth_hash, resp } => {
+                    let claims = self.claim_priority_ops(eth_hash);
+                    resp.send(claims).unwrap_or_default();
+                }
+                EthWatchRequest::EstimatePriorityOps { ops, resp } => {
+                    let claims = self.estimate_priority_ops(ops);
+                    resp.send(claims).unwrap_or_default();
+                }
                 EthWatchRequest::IsPubkeyChangeAuthorized {
                     address,
                     nonce,
@@ -372,9 +714,15 @@ pub fn start_eth_watch(
     eth_req_receiver: mpsc::Receiver<EthWatchRequest>,
     db_pool: ConnectionPool,
 ) -> JoinHandle<()> {
+    register_metrics("Ethereum watcher", METRICS);
+
     let transport = web3::transports::Http::new(&config_options.web3_url).unwrap();
     let web3 = web3::Web3::new(transport);
-    let eth_client = EthHttpClient::new(web3, config_options.contract_eth_addr);
+    let eth_client = EthHttpClient::new(
+        web3,
+        config_options.contract_eth_addr,
+        config_options.upgrade_gatekeeper_eth_addr,
+    );
 
     let storage = DBStorage::new(db_pool);
 
@@ -382,6 +730,7 @@ pub fn start_eth_watch(
         eth_client,
         storage,
         config_options.confirmations_for_eth_event,
+        ProcessingTimings::from_config(&config_options),
     );
 
     tokio::spawn(eth_watch.run(eth_req_receiver));
@@ -1,5 +1,7 @@
 use zksync_storage::ConnectionPool;
-use zksync_types::ethereum::CompleteWithdrawalsTx;
+use zksync_types::ethereum::{CompleteWithdrawalsTx, ContractUpgradeEvent};
+
+use super::eta::DepositAcknowledgment;
 
 #[async_trait::async_trait]
 pub trait Storage {
@@ -7,6 +9,20 @@ pub trait Storage {
         &mut self,
         complete_withdrawals_txs: Vec<CompleteWithdrawalsTx>,
     ) -> anyhow::Result<()>;
+
+    async fn store_deposit_acknowledgments(
+        &mut self,
+        acknowledgments: Vec<DepositAcknowledgment>,
+    ) -> anyhow::Result<()>;
+
+    /// Removes the acknowledgments of the deposits executed by the state keeper.
+    async fn remove_deposit_acknowledgments(&mut self, before_serial_id: u64)
+        -> anyhow::Result<()>;
+
+    async fn store_contract_upgrade_events(
+        &mut self,
+        events: Vec<ContractUpgradeEvent>,
+    ) -> anyhow::Result<()>;
 }
 
 pub struct DBStorage {
@@ -27,4 +43,52 @@ impl Storage for DBStorage {
     ) -> anyhow::Result<()> {
         unreachable!()
     }
+
+    async fn store_deposit_acknowledgments(
+        &mut self,
+        acknowledgments: Vec<DepositAcknowledgment>,
+    ) -> anyhow::Result<()> {
+        let mut storage = self.db_pool.access_storage().await?;
+        let mut transaction = storage.start_transaction().await?;
+        for acknowledgment in acknowledgments {
+            transaction
+                .chain()
+                .operations_schema()
+                .store_priority_op_acknowledgment(
+                    &acknowledgment.op,
+                    acknowledgment.estimated_usable_at,
+                )
+                .await?;
+        }
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    async fn remove_deposit_acknowledgments(
+        &mut self,
+        before_serial_id: u64,
+    ) -> anyhow::Result<()> {
+        let mut storage = self.db_pool.access_storage().await?;
+        storage
+            .chain()
+            .operations_schema()
+            .remove_priority_op_acknowledgments(before_serial_id)
+            .await
+    }
+
+    async fn store_contract_upgrade_events(
+        &mut self,
+        events: Vec<ContractUpgradeEvent>,
+    ) -> anyhow::Result<()> {
+        let mut storage = self.db_pool.access_storage().await?;
+        let mut transaction = storage.start_transaction().await?;
+        for event in &events {
+            transaction
+                .ethereum_schema()
+                .store_contract_upgrade_event(event)
+                .await?;
+        }
+        transaction.commit().await?;
+        Ok(())
+    }
 }
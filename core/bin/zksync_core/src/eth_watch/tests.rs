@@ -3,20 +3,32 @@ use std::collections::HashMap;
 
 use web3::types::{Address, BlockNumber};
 
-use zksync_types::{ethereum::CompleteWithdrawalsTx, Deposit, PriorityOp, ZkSyncPriorityOp};
+use zksync_types::{
+    ethereum::{CompleteWithdrawalsTx, ContractUpgradeEvent, ContractUpgradeStage},
+    Deposit, PriorityOp, PriorityOpClaimSource, ZkSyncPriorityOp, H256,
+};
 
-use crate::eth_watch::{client::EthClient, storage::Storage, EthWatch};
+use crate::eth_watch::{
+    client::EthClient, eta::DepositAcknowledgment, storage::Storage, EthWatch, ProcessingTimings,
+};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 
 struct FakeStorage {
     withdrawal_txs: Vec<CompleteWithdrawalsTx>,
+    acknowledgments: Vec<DepositAcknowledgment>,
+    acknowledgments_removed_before: u64,
+    upgrade_events: Vec<ContractUpgradeEvent>,
 }
 
 impl FakeStorage {
     fn new() -> Self {
         Self {
             withdrawal_txs: vec![],
+            acknowledgments: vec![],
+            acknowledgments_removed_before: 0,
+            upgrade_events: vec![],
         }
     }
 }
@@ -30,11 +42,36 @@ impl Storage for FakeStorage {
         self.withdrawal_txs.extend(complete_withdrawals_txs);
         Ok(())
     }
+
+    async fn store_deposit_acknowledgments(
+        &mut self,
+        acknowledgments: Vec<DepositAcknowledgment>,
+    ) -> anyhow::Result<()> {
+        self.acknowledgments.extend(acknowledgments);
+        Ok(())
+    }
+
+    async fn remove_deposit_acknowledgments(
+        &mut self,
+        before_serial_id: u64,
+    ) -> anyhow::Result<()> {
+        self.acknowledgments_removed_before = before_serial_id;
+        Ok(())
+    }
+
+    async fn store_contract_upgrade_events(
+        &mut self,
+        events: Vec<ContractUpgradeEvent>,
+    ) -> anyhow::Result<()> {
+        self.upgrade_events.extend(events);
+        Ok(())
+    }
 }
 
 struct FakeEthClientData {
     priority_ops: HashMap<u64, Vec<PriorityOp>>,
     withdrawals: HashMap<u64, Vec<CompleteWithdrawalsTx>>,
+    upgrade_events: Vec<ContractUpgradeEvent>,
     last_block_number: u64,
 }
 
@@ -43,6 +80,7 @@ impl FakeEthClientData {
         Self {
             priority_ops: Default::default(),
             withdrawals: Default::default(),
+            upgrade_events: Default::default(),
             last_block_number: 0,
         }
     }
@@ -74,6 +112,14 @@ impl FakeEthClient {
         self.inner.write().await.add_operations(ops);
     }
 
+    async fn add_upgrade_events(&mut self, events: &[ContractUpgradeEvent]) {
+        let mut inner = self.inner.write().await;
+        for event in events {
+            inner.last_block_number = max(event.eth_block, inner.last_block_number);
+            inner.upgrade_events.push(*event);
+        }
+    }
+
     async fn block_to_number(&self, block: &BlockNumber) -> u64 {
         match block {
             BlockNumber::Latest => self.inner.read().await.last_block_number,
@@ -118,6 +164,24 @@ impl EthClient for FakeEthClient {
         Ok(withdrawals)
     }
 
+    async fn get_contract_upgrade_events(
+        &self,
+        from: BlockNumber,
+        to: BlockNumber,
+    ) -> Result<Vec<ContractUpgradeEvent>, anyhow::Error> {
+        let from = self.block_to_number(&from).await;
+        let to = self.block_to_number(&to).await;
+        Ok(self
+            .inner
+            .read()
+            .await
+            .upgrade_events
+            .iter()
+            .filter(|event| (from..=to).contains(&event.eth_block))
+            .copied()
+            .collect())
+    }
+
     async fn block_number(&self) -> Result<u64, anyhow::Error> {
         Ok(self.inner.read().await.last_block_number)
     }
@@ -141,7 +205,7 @@ impl EthClient for FakeEthClient {
 
 fn create_watcher<T: EthClient>(client: T) -> EthWatch<T, FakeStorage> {
     let storage = FakeStorage::new();
-    EthWatch::new(client, storage, 1)
+    EthWatch::new(client, storage, 1, Default::default())
 }
 
 #[tokio::test]
@@ -263,3 +327,208 @@ async fn test_restore_and_poll() {
     let deposits = watcher.get_ongoing_deposits_for([2u8; 20].into());
     assert_eq!(deposits.len(), 1);
 }
+
+#[tokio::test]
+async fn deposit_acknowledgments() {
+    let deposit = |serial_id, eth_block| PriorityOp {
+        serial_id,
+        data: ZkSyncPriorityOp::Deposit(Deposit {
+            from: Default::default(),
+            token: 0,
+            amount: Default::default(),
+            to: [2u8; 20].into(),
+        }),
+        deadline_block: 0,
+        eth_hash: [serial_id as u8; 32].to_vec(),
+        eth_block,
+    };
+
+    let mut client = FakeEthClient::new();
+    client
+        .add_operations(&[deposit(0, 3), deposit(1, 4), deposit(2, 4)])
+        .await;
+    let timings = ProcessingTimings {
+        poll_interval: Duration::from_secs(1),
+        miniblock_interval: Duration::from_secs(1),
+        max_block_chunks: 6,
+    };
+    let mut watcher = EthWatch::new(client.clone(), FakeStorage::new(), 1, timings);
+
+    // Deposits are not acknowledged until the queue of the state keeper is known.
+    watcher.poll_eth_node().await.unwrap();
+    assert!(watcher.storage.acknowledgments.is_empty());
+
+    watcher.next_priority_op_id = Some(0);
+    watcher.poll_eth_node().await.unwrap();
+    let acknowledgments = &watcher.storage.acknowledgments;
+    assert_eq!(
+        acknowledgments
+            .iter()
+            .map(|ack| ack.op.serial_id)
+            .collect::<Vec<_>>(),
+        vec![1, 2]
+    );
+    // The second deposit waits for the first one to be executed.
+    let difference =
+        acknowledgments[1].estimated_usable_at - acknowledgments[0].estimated_usable_at;
+    assert!(difference >= chrono::Duration::seconds(1));
+
+    // Deposits are acknowledged only once.
+    client.add_operations(&[deposit(3, 5)]).await;
+    watcher.poll_eth_node().await.unwrap();
+    assert_eq!(
+        watcher
+            .storage
+            .acknowledgments
+            .iter()
+            .map(|ack| ack.op.serial_id)
+            .collect::<Vec<_>>(),
+        vec![1, 2, 3]
+    );
+}
+
+#[tokio::test]
+async fn deposit_acknowledgments_update() {
+    let deposit = |serial_id, eth_block| PriorityOp {
+        serial_id,
+        data: ZkSyncPriorityOp::Deposit(Deposit {
+            from: Default::default(),
+            token: 0,
+            amount: Default::default(),
+            to: [2u8; 20].into(),
+        }),
+        deadline_block: 0,
+        eth_hash: [serial_id as u8; 32].to_vec(),
+        eth_block,
+    };
+
+    let mut client = FakeEthClient::new();
+    client.add_operations(&[deposit(0, 3), deposit(1, 4)]).await;
+    let timings = ProcessingTimings {
+        poll_interval: Duration::from_secs(1),
+        miniblock_interval: Duration::from_secs(60),
+        max_block_chunks: 6,
+    };
+    let mut watcher = EthWatch::new(client.clone(), FakeStorage::new(), 1, timings);
+    watcher.next_priority_op_id = Some(0);
+    watcher.poll_eth_node().await.unwrap();
+    assert_eq!(watcher.storage.acknowledgments.len(), 1);
+    let first_estimate = watcher.storage.acknowledgments[0].estimated_usable_at;
+
+    // Estimate barely changes, so the acknowledgment is not updated.
+    watcher.poll_eth_node().await.unwrap();
+    assert_eq!(watcher.storage.acknowledgments.len(), 1);
+
+    // Once the first deposit is executed, the second one waits for one miniblock less,
+    // and the acknowledgment of the executed deposit is removed.
+    watcher.next_priority_op_id = Some(1);
+    client.add_operations(&[deposit(2, 5)]).await;
+    watcher.poll_eth_node().await.unwrap();
+    assert_eq!(watcher.storage.acknowledgments_removed_before, 1);
+    let acknowledgments = &watcher.storage.acknowledgments;
+    assert_eq!(
+        acknowledgments
+            .iter()
+            .map(|ack| ack.op.serial_id)
+            .collect::<Vec<_>>(),
+        vec![1, 1, 2]
+    );
+    assert!(
+        first_estimate - acknowledgments[1].estimated_usable_at >= chrono::Duration::seconds(60)
+    );
+}
+
+#[tokio::test]
+async fn priority_op_claims() {
+    let deposit = |serial_id, eth_hash: u8, eth_block| PriorityOp {
+        serial_id,
+        data: ZkSyncPriorityOp::Deposit(Deposit {
+            from: Default::default(),
+            token: 0,
+            amount: Default::default(),
+            to: [2u8; 20].into(),
+        }),
+        deadline_block: 0,
+        eth_hash: [eth_hash; 32].to_vec(),
+        eth_block,
+    };
+
+    let mut client = FakeEthClient::new();
+    // Single transaction may request several priority operations.
+    client
+        .add_operations(&[deposit(0, 1, 3), deposit(1, 2, 4), deposit(2, 2, 4)])
+        .await;
+    let mut watcher = create_watcher(client.clone());
+    watcher.poll_eth_node().await.unwrap();
+
+    // Position in the queue is unknown until the state keeper requests the operations.
+    let claims = watcher.claim_priority_ops(H256::repeat_byte(2));
+    assert_eq!(claims.len(), 2);
+    let claim = &claims[0];
+    assert_eq!(claim.op.serial_id, 1);
+    assert_eq!(claim.source, PriorityOpClaimSource::EthWatch);
+    assert_eq!(claim.confirmations_left, 1);
+    assert!(claim.queue_position.is_none());
+    assert!(claim.estimated_inclusion_at.is_none());
+
+    watcher.next_priority_op_id = Some(0);
+    let claims = watcher.claim_priority_ops(H256::repeat_byte(2));
+    let queue_positions: Vec<_> = claims.iter().map(|claim| claim.queue_position).collect();
+    assert_eq!(queue_positions, vec![Some(1), Some(2)]);
+    assert!(claims[0].estimated_inclusion_at <= claims[1].estimated_inclusion_at);
+
+    // Transactions unknown to the watcher can't be claimed.
+    assert!(watcher.claim_priority_ops(H256::repeat_byte(3)).is_empty());
+
+    // Operations found on Ethereum are estimated after the known ones.
+    let claims = watcher.estimate_priority_ops(vec![deposit(4, 3, 7), deposit(3, 3, 7)]);
+    let serial_ids: Vec<_> = claims.iter().map(|claim| claim.op.serial_id).collect();
+    assert_eq!(serial_ids, vec![3, 4]);
+    assert!(claims
+        .iter()
+        .all(|claim| claim.source == PriorityOpClaimSource::Ethereum));
+    assert_eq!(claims[0].confirmations_left, 4);
+    assert_eq!(claims[1].queue_position, Some(4));
+    assert!(claims[0].estimated_inclusion_at <= claims[1].estimated_inclusion_at);
+
+    let claims = watcher.estimate_priority_ops(vec![deposit(0, 1, 3)]);
+    assert_eq!(claims[0].source, PriorityOpClaimSource::EthWatch);
+}
+
+#[tokio::test]
+async fn contract_upgrade_events() {
+    let event = |stage, eth_block| ContractUpgradeEvent {
+        version_id: 2,
+        stage,
+        eth_block,
+    };
+
+    let mut client = FakeEthClient::new();
+    client
+        .add_upgrade_events(&[event(ContractUpgradeStage::NoticePeriod, 2)])
+        .await;
+    let mut watcher = create_watcher(client.clone());
+    watcher.restore_state_from_eth(2).await.unwrap();
+    // The event is not confirmed yet.
+    assert!(watcher.storage.upgrade_events.is_empty());
+
+    client
+        .add_upgrade_events(&[event(ContractUpgradeStage::Completed, 4)])
+        .await;
+    watcher.poll_eth_node().await.unwrap();
+    assert_eq!(
+        watcher.storage.upgrade_events,
+        vec![event(ContractUpgradeStage::NoticePeriod, 2)]
+    );
+
+    // Every event is stored once it's confirmed, and only once.
+    client.inner.write().await.last_block_number = 6;
+    watcher.poll_eth_node().await.unwrap();
+    assert_eq!(
+        watcher.storage.upgrade_events,
+        vec![
+            event(ContractUpgradeStage::NoticePeriod, 2),
+            event(ContractUpgradeStage::Completed, 4)
+        ]
+    );
+}
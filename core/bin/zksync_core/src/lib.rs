@@ -2,16 +2,20 @@ use zksync_types::{tokens::get_genesis_token_list, Token, TokenId};
 
 use crate::{
     block_proposer::run_block_proposer_task,
-    committer::run_committer,
+    committer::{run_committer, CommitQueueDepth},
     eth_watch::start_eth_watch,
     mempool::run_mempool_task,
     private_api::start_private_core_api,
     state_keeper::{start_state_keeper, ZkSyncStateInitParams, ZkSyncStateKeeper},
+    token_metadata_updater::start_token_metadata_updater,
 };
 use futures::{channel::mpsc, future};
 use tokio::task::JoinHandle;
-use zksync_config::{ApiServerOptions, ConfigurationOptions};
+use zksync_config::{
+    ApiServerOptions, AvailableBlockSizesConfig, ConfigurationOptions, MempoolOptions,
+};
 use zksync_storage::ConnectionPool;
+use zksync_utils::shutdown::ShutdownSignal;
 
 const DEFAULT_CHANNEL_CAPACITY: usize = 32_768;
 
@@ -21,6 +25,7 @@ pub mod eth_watch;
 pub mod mempool;
 pub mod private_api;
 pub mod state_keeper;
+pub mod token_metadata_updater;
 
 /// Waits for *any* of the tokio tasks to be finished.
 /// Since the main tokio tasks are used as actors which should live as long
@@ -32,7 +37,7 @@ pub async fn wait_for_tasks(task_futures: Vec<JoinHandle<()>>) {
             panic!("One of the actors finished its run, while it wasn't expected to do it");
         }
         (Err(error), _, _) => {
-            log::warn!("One of the tokio actors unexpectedly finished, shutting down");
+            tracing::warn!("One of the tokio actors unexpectedly finished, shutting down");
             if error.is_panic() {
                 // Resume the panic on the main task
                 std::panic::resume_unwind(error.into_panic());
@@ -46,14 +51,14 @@ pub async fn genesis_init() {
     let pool = ConnectionPool::new(Some(1));
     let config_options = ConfigurationOptions::from_env();
 
-    log::info!("Generating genesis block.");
+    tracing::info!("Generating genesis block.");
     ZkSyncStateKeeper::create_genesis_block(pool.clone(), &config_options.operator_fee_eth_addr)
         .await;
-    log::info!("Adding initial tokens to db");
+    tracing::info!("Adding initial tokens to db");
     let genesis_tokens =
         get_genesis_token_list(&config_options.eth_network).expect("Initial token list not found");
     for (id, token) in (1..).zip(genesis_tokens) {
-        log::info!(
+        tracing::info!(
             "Adding token: {}, id:{}, address: {}, decimals: {}",
             token.symbol,
             id,
@@ -85,17 +90,32 @@ pub async fn genesis_init() {
 /// - block proposer, module to create block proposals for state keeper.
 /// - committer, module to store pending and completed blocks into the database.
 /// - private Core API server.
+/// - token metadata updater, module to update the token symbols and decimals from L1.
+///
+/// Once the `shutdown` is requested, the mempool rejects new transactions, the block proposer
+/// stops proposing them, and the state keeper seals the pending block and stops. The `shutdown`
+/// is marked as stopped once the committer stores that block.
 pub async fn run_core(
     connection_pool: ConnectionPool,
     panic_notify: mpsc::Sender<bool>,
+    shutdown: ShutdownSignal,
 ) -> anyhow::Result<Vec<JoinHandle<()>>> {
     let config_opts = ConfigurationOptions::from_env();
     let api_server_options = ApiServerOptions::from_env();
+    let mempool_options = MempoolOptions::from_env();
 
+    AvailableBlockSizesConfig::from_env()
+        .check_block_chunk_sizes(&config_opts.available_block_chunk_sizes)
+        .map_err(anyhow::Error::msg)?;
+
+    // Queues between the block proposer, the state keeper and the committer are kept short,
+    // so the backpressure of the lagging committer propagates to the block proposer instead
+    // of piling up the blocks in memory.
     let (proposed_blocks_sender, proposed_blocks_receiver) =
-        mpsc::channel(DEFAULT_CHANNEL_CAPACITY);
+        mpsc::channel(config_opts.committer_queue_capacity);
     let (state_keeper_req_sender, state_keeper_req_receiver) =
-        mpsc::channel(DEFAULT_CHANNEL_CAPACITY);
+        mpsc::channel(config_opts.state_keeper_queue_capacity);
+    let commit_queue_depth = CommitQueueDepth::default();
     let (eth_watch_req_sender, eth_watch_req_receiver) = mpsc::channel(DEFAULT_CHANNEL_CAPACITY);
     let (mempool_request_sender, mempool_request_receiver) =
         mpsc::channel(DEFAULT_CHANNEL_CAPACITY);
@@ -122,10 +142,13 @@ pub async fn run_core(
         config_opts.operator_fee_eth_addr,
         state_keeper_req_receiver,
         proposed_blocks_sender,
+        commit_queue_depth.clone(),
         config_opts.available_block_chunk_sizes.clone(),
         config_opts.miniblock_timings.max_miniblock_iterations,
         config_opts.miniblock_timings.fast_miniblock_iterations,
+        config_opts.miniblock_timings.block_commit_deadline,
         config_opts.max_number_of_withdrawals_per_block,
+        config_opts.tx_trace_enabled,
     );
     let state_keeper_task = start_state_keeper(state_keeper, pending_block);
 
@@ -134,6 +157,11 @@ pub async fn run_core(
         proposed_blocks_receiver,
         mempool_request_sender.clone(),
         connection_pool.clone(),
+        config_opts.aggregated_proof_sizes.clone(),
+        config_opts.dummy_verifier,
+        config_opts.eth_sender_queue_capacity,
+        commit_queue_depth,
+        shutdown.clone(),
     );
 
     // Start mempool.
@@ -142,13 +170,17 @@ pub async fn run_core(
         mempool_request_receiver,
         eth_watch_req_sender.clone(),
         &config_opts,
+        mempool_options,
+        shutdown.clone(),
     );
 
     // Start block proposer.
     let proposer_task = run_block_proposer_task(
         &config_opts,
+        connection_pool.clone(),
         mempool_request_sender.clone(),
         state_keeper_req_sender.clone(),
+        shutdown,
     );
 
     // Start private API.
@@ -159,7 +191,7 @@ pub async fn run_core(
         api_server_options,
     );
 
-    let task_futures = vec![
+    let mut task_futures = vec![
         eth_watch_task,
         state_keeper_task,
         committer_task,
@@ -167,5 +199,14 @@ pub async fn run_core(
         proposer_task,
     ];
 
+    // Start token metadata updater.
+    if let Some(interval) = config_opts.token_metadata_refresh_interval {
+        task_futures.push(start_token_metadata_updater(
+            &config_opts.web3_url,
+            connection_pool,
+            interval,
+        ));
+    }
+
     Ok(task_futures)
 }
@@ -1,11 +1,14 @@
 use futures::{channel::mpsc, executor::block_on, SinkExt, StreamExt};
 use std::cell::RefCell;
+use zksync_config::ConfigurationOptions;
 use zksync_core::{run_core, wait_for_tasks};
+use zksync_prometheus_exporter::install_prometheus_exporter;
 use zksync_storage::ConnectionPool;
+use zksync_utils::shutdown::ShutdownSignal;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    env_logger::init();
+    vlog::init();
     // handle ctrl+c
     let (stop_signal_sender, mut stop_signal_receiver) = mpsc::channel(256);
     {
@@ -17,8 +20,12 @@ async fn main() -> anyhow::Result<()> {
         .expect("Error setting Ctrl+C handler");
     }
     let connection_pool = ConnectionPool::new(None);
+    let config_options = ConfigurationOptions::from_env();
+    let shutdown_timeout = config_options.shutdown_timeout;
+    let shutdown = ShutdownSignal::new();
+    let prometheus_handle = install_prometheus_exporter(config_options.prometheus_export_port);
 
-    let task_handles = run_core(connection_pool, stop_signal_sender)
+    let task_handles = run_core(connection_pool, stop_signal_sender, shutdown.clone())
         .await
         .expect("Unable to start Core actors");
 
@@ -26,10 +33,24 @@ async fn main() -> anyhow::Result<()> {
         _ = async { wait_for_tasks(task_handles).await } => {
             // We don't need to do anything here, since actors will panic upon future resolving.
         },
+        _ = async { prometheus_handle.await } => {
+            panic!("Prometheus exporter actors aren't supposed to finish their execution")
+        },
         _ = async { stop_signal_receiver.next().await } => {
-            log::warn!("Stop signal received, shutting down");
+            tracing::warn!("Stop signal received, shutting down");
         }
     };
 
+    shutdown.request();
+    if tokio::time::timeout(shutdown_timeout, shutdown.stopped())
+        .await
+        .is_err()
+    {
+        tracing::warn!(
+            "Core actors didn't stop in {:?}, exiting anyway",
+            shutdown_timeout
+        );
+    }
+
     Ok(())
 }
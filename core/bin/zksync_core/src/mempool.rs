@@ -6,35 +6,74 @@
 //! when new block is committed.
 //! 2) When polled return vector of the transactions in the queue.
 //!
-//! Mempool is not persisted on disc, all transactions will be lost on node shutdown.
+//! Mempool is persisted in the database: every accepted transaction is stored before the
+//! response is sent, so the pending transactions survive the node restart. On startup
+//! the stored transactions are revalidated against the current state: the executed ones
+//! and the ones with outdated nonces are removed from the database.
+//!
+//! Mempool size is limited: once it's full, transactions paying the lowest fee per block chunk
+//! are evicted in favor of the better paying ones. Fees paid in different tokens are compared
+//! by their USD value, using the token prices stored by the fee ticker. Transactions are evicted
+//! starting from the highest nonces of the account, so the remaining ones have no nonce gaps.
+//! Also every account can only have a limited amount of pending transactions, so a single account
+//! can't occupy the whole pool.
+//!
+//! When a new block is proposed, priority operations are always included first. The transactions
+//! are then selected according to the configured ordering: either the best paying ones (by the fee
+//! per chunk) first, or in the order they were received. In both cases transactions of the same
+//! account are included in the order they were received, so their nonces are never reordered,
+//! and after the preceding transfers to the account, which may create or fund it.
 //!
 //! Communication channel with other actors:
 //! Mempool does not push information to other actors, only accepts requests. (see `MempoolRequest`)
 //!
 //! Communication with db:
-//! on restart mempool restores nonces of the accounts that are stored in the account tree.
+//! on restart mempool restores nonces of the accounts that are stored in the account tree
+//! and the pending transactions.
 
 // Built-in deps
-use std::collections::{HashMap, VecDeque};
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::Entry, BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet},
+    time::{Duration, Instant},
+};
 // External uses
 use futures::{
     channel::{mpsc, oneshot},
     SinkExt, StreamExt,
 };
+use num::{rational::Ratio, traits::Pow, BigUint, Zero};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use tokio::task::JoinHandle;
 // Workspace uses
+use zksync_prometheus_exporter::{register_metrics, Metric};
 use zksync_storage::ConnectionPool;
 use zksync_types::{
     mempool::{SignedTxVariant, SignedTxsBatch},
-    tx::TxEthSignature,
-    AccountId, AccountUpdate, AccountUpdates, Address, Nonce, PriorityOp, SignedZkSyncTx,
-    TransferOp, TransferToNewOp, ZkSyncTx,
+    tx::{TxEthSignature, TxHash},
+    AccountId, AccountUpdate, AccountUpdates, Address, Nonce, PriorityOp, SignedZkSyncTx, TokenId,
+    TokenLike, ZkSyncTx,
 };
+use zksync_utils::shutdown::ShutdownSignal;
 // Local uses
 use crate::eth_watch::EthWatchRequest;
-use zksync_config::ConfigurationOptions;
+use zksync_config::{ConfigurationOptions, MempoolOptions, TxOrdering};
+
+/// Interval between reloads of the token prices used to compare fees of transactions.
+const TOKEN_PRICES_UPDATE_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Metrics reported by the mempool.
+const METRICS: &[Metric] = &[
+    Metric::gauge(
+        "mempool.ready_txs",
+        "Number of transactions and batches waiting for the inclusion into a block",
+    ),
+    Metric::histogram(
+        "mempool.propose_new_block",
+        "Time spent on proposing the transactions for a new block",
+    ),
+];
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, Error)]
 pub enum TxAddError {
@@ -76,6 +115,15 @@ pub enum TxAddError {
 
     #[error("The number of withdrawals in the batch is too big")]
     BatchWithdrawalsOverload,
+
+    #[error("Mempool is full and the transaction fee is too low to replace pending transactions")]
+    MempoolFull,
+
+    #[error("Too many pending transactions from the account")]
+    AccountTxsLimitExceeded,
+
+    #[error("Server is shutting down")]
+    ShuttingDown,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -113,29 +161,80 @@ pub enum MempoolRequest {
     ),
     /// When block is committed, nonces of the account tree should be updated too.
     UpdateNonces(AccountUpdates),
+    /// Remove pending transactions with the given hashes from the mempool (e.g. once their fee
+    /// no longer covers the current cost of processing). If any of the batched transactions is
+    /// removed, the whole batch is removed as well.
+    RemoveTxs(Vec<TxHash>),
     /// Get transactions from the mempool.
     GetBlock(GetBlockRequest),
 }
 
+/// Returns the transactions contained in the mempool element.
+fn element_txs(element: &SignedTxVariant) -> &[SignedZkSyncTx] {
+    match element {
+        SignedTxVariant::Tx(tx) => std::slice::from_ref(tx),
+        SignedTxVariant::Batch(batch) => &batch.txs,
+    }
+}
+
+/// Returns the accounts affected by the mempool element: the senders and the transfer recipients.
+fn element_accounts(element: &SignedTxVariant) -> HashSet<Address> {
+    let mut accounts = HashSet::new();
+    for tx in element_txs(element) {
+        accounts.insert(tx.account());
+        if let ZkSyncTx::Transfer(transfer) = &tx.tx {
+            accounts.insert(transfer.to);
+        }
+    }
+    accounts
+}
+
+/// Pending mempool element along with its dependencies.
+///
+/// Element depends on the preceding pending elements affecting the same accounts: the transactions
+/// of its senders (so the nonces are not reordered), and the transfers to them (which may create
+/// the account or provide the funds to be spent). Transfers to the same recipient keep their order
+/// as well, so depending on the last element affecting every account is enough.
+#[derive(Debug)]
+struct PendingElement {
+    element: SignedTxVariant,
+    /// Fee per chunk paid by the element, see `MempoolState::fee_per_chunk`.
+    fee_per_chunk: Ratio<BigUint>,
+    /// Preceding pending elements which must be included into a block before this one.
+    blocked_by: BTreeSet<u64>,
+    /// Following pending elements which depend on this one.
+    dependents: BTreeSet<u64>,
+}
+
+/// Key of the fee index: elements are ordered by the fee per chunk, and the equally paying
+/// ones in the reversed order they were received.
+type FeeKey = (Ratio<BigUint>, Reverse<u64>);
+
 struct MempoolState {
     // account and last committed nonce
     account_nonces: HashMap<Address, Nonce>,
     account_ids: HashMap<AccountId, Address>,
-    ready_txs: VecDeque<SignedTxVariant>,
+    /// Pending elements by their sequence numbers, i.e. in the order they were received.
+    ready_txs: BTreeMap<u64, PendingElement>,
+    /// Sequence number of the next element added to the mempool.
+    next_seq: u64,
+    /// Sequence numbers of the pending elements affecting each account.
+    account_elements: HashMap<Address, BTreeSet<u64>>,
+    /// Elements which don't depend on any other ones, i.e. can be included into a block.
+    head_index: BTreeSet<FeeKey>,
+    /// Elements no other ones depend on, i.e. can be evicted without breaking the nonce order.
+    tail_index: BTreeSet<FeeKey>,
+    /// Amount of transactions in the `ready_txs` queue, with every transaction of a batch counted separately.
+    txs_count: usize,
+    /// Amount of pending transactions for each account.
+    account_txs_count: HashMap<Address, usize>,
+    /// USD price of the smallest unit of each token (e.g. wei for ETH).
+    token_prices: HashMap<TokenId, Ratio<BigUint>>,
 }
 
 impl MempoolState {
     fn chunks_for_tx(&self, tx: &ZkSyncTx) -> usize {
-        match tx {
-            ZkSyncTx::Transfer(tx) => {
-                if self.account_nonces.contains_key(&tx.to) {
-                    TransferOp::CHUNKS
-                } else {
-                    TransferToNewOp::CHUNKS
-                }
-            }
-            _ => tx.min_chunks(),
-        }
+        tx.chunks(|address| self.account_nonces.contains_key(address))
     }
 
     fn chunks_for_batch(&self, batch: &SignedTxsBatch) -> usize {
@@ -149,6 +248,28 @@ impl MempoolState {
         }
     }
 
+    /// Returns the USD value of the fee paid for every chunk required by the element.
+    /// Fees paid in tokens with an unknown price are considered worthless.
+    fn fee_per_chunk(&self, element: &SignedTxVariant) -> Ratio<BigUint> {
+        let fee_in_usd = element_txs(element)
+            .iter()
+            .filter_map(|tx| tx.get_fee_info())
+            .fold(Ratio::zero(), |total, (_, token, _, fee)| {
+                let token_price = match token {
+                    TokenLike::Id(token_id) => self.token_prices.get(&token_id),
+                    _ => None,
+                };
+
+                match token_price {
+                    Some(price) => total + price.clone() * Ratio::from_integer(fee),
+                    None => total,
+                }
+            });
+
+        let chunks = self.required_chunks(element).max(1);
+        fee_in_usd / Ratio::from_integer(BigUint::from(chunks))
+    }
+
     async fn restore_from_db(db_pool: &ConnectionPool) -> Self {
         let mut storage = db_pool.access_storage().await.expect("mempool db restore");
         let mut transaction = storage
@@ -182,58 +303,369 @@ impl MempoolState {
 
         // Load transactions that were not yet processed and are awaiting in the
         // mempool.
-        let ready_txs: VecDeque<_> = transaction
+        let restored_txs = transaction
             .chain()
             .mempool_schema()
             .load_txs()
             .await
             .expect("Attempt to restore mempool txs from DB failed");
 
+        let mut state = Self {
+            account_nonces,
+            account_ids,
+            ready_txs: BTreeMap::new(),
+            next_seq: 0,
+            account_elements: HashMap::new(),
+            head_index: BTreeSet::new(),
+            tail_index: BTreeSet::new(),
+            txs_count: 0,
+            account_txs_count: HashMap::new(),
+            token_prices: HashMap::new(),
+        };
+        let outdated_txs = state.restore_txs(restored_txs);
+
+        // Transactions may become outdated while the server is down (e.g. if the
+        // state was reverted), so they should not be kept in the database.
+        transaction
+            .chain()
+            .mempool_schema()
+            .remove_txs(&outdated_txs)
+            .await
+            .expect("Attempt to remove outdated mempool txs from DB failed");
+
         transaction
             .commit()
             .await
             .expect("mempool db transaction commit");
 
-        log::info!(
-            "{} transactions were restored from the persistent mempool storage",
-            ready_txs.len()
+        tracing::info!(
+            "{} transactions were restored from the persistent mempool storage, {} outdated transactions were removed",
+            state.txs_count,
+            outdated_txs.len()
         );
 
-        Self {
-            account_nonces,
-            account_ids,
-            ready_txs,
+        state
+    }
+
+    /// Revalidates the transactions restored from the database against the current state,
+    /// adding the valid ones to the `ready_txs` queue.
+    /// Returns hashes of the outdated transactions, which have nonces lower than the
+    /// committed ones. If any of the batched transactions is outdated, the whole batch is.
+    fn restore_txs(&mut self, txs: impl IntoIterator<Item = SignedTxVariant>) -> Vec<TxHash> {
+        let mut outdated_txs = Vec::new();
+        for element in txs {
+            if self.check_nonces(element_txs(&element)).is_ok() {
+                self.push_ready(element);
+            } else {
+                outdated_txs.extend(element.hashes());
+            }
         }
+
+        outdated_txs
     }
 
     fn nonce(&self, address: &Address) -> Nonce {
         *self.account_nonces.get(address).unwrap_or(&0)
     }
 
-    fn add_tx(&mut self, tx: SignedZkSyncTx) -> Result<(), TxAddError> {
+    fn check_nonces(&self, txs: &[SignedZkSyncTx]) -> Result<(), TxAddError> {
         // Correctness should be checked by `signature_checker`, thus
         // `tx.check_correctness()` is not invoked here.
 
-        if tx.nonce() >= self.nonce(&tx.account()) {
-            self.ready_txs.push_back(tx.into());
-            Ok(())
-        } else {
-            Err(TxAddError::NonceMismatch)
+        for tx in txs {
+            if tx.nonce() < self.nonce(&tx.account()) {
+                return Err(TxAddError::NonceMismatch);
+            }
         }
+
+        Ok(())
+    }
+
+    /// Updates the token prices and the fees of the pending elements.
+    fn set_token_prices(&mut self, token_prices: HashMap<TokenId, Ratio<BigUint>>) {
+        self.token_prices = token_prices;
+        self.reindex();
     }
 
-    fn add_batch(&mut self, batch: SignedTxsBatch) -> Result<(), TxAddError> {
-        assert_ne!(batch.batch_id, 0, "Batch ID was not set");
+    /// Recalculates the fees of the pending elements and rebuilds the fee indices.
+    /// Must be called once the token prices or the set of existing accounts are changed.
+    fn reindex(&mut self) {
+        let fees: Vec<_> = self
+            .ready_txs
+            .iter()
+            .map(|(&seq, pending)| (seq, self.fee_per_chunk(&pending.element)))
+            .collect();
 
-        for tx in batch.txs.iter() {
-            if tx.nonce() < self.nonce(&tx.account()) {
-                return Err(TxAddError::NonceMismatch);
+        self.head_index.clear();
+        self.tail_index.clear();
+        for (seq, fee_per_chunk) in fees {
+            let pending = self.ready_txs.get_mut(&seq).unwrap();
+            pending.fee_per_chunk = fee_per_chunk.clone();
+            if pending.blocked_by.is_empty() {
+                self.head_index
+                    .insert((fee_per_chunk.clone(), Reverse(seq)));
+            }
+            if pending.dependents.is_empty() {
+                self.tail_index.insert((fee_per_chunk, Reverse(seq)));
             }
         }
+    }
 
-        self.ready_txs.push_back(SignedTxVariant::Batch(batch));
+    fn fee_key(&self, seq: u64) -> FeeKey {
+        (self.ready_txs[&seq].fee_per_chunk.clone(), Reverse(seq))
+    }
 
-        Ok(())
+    /// Checks whether the element can be added to the mempool without exceeding the limits.
+    ///
+    /// If the mempool is full, selects the pending elements with the fee per chunk lower
+    /// than the new element has, so that evicting them frees enough space for the new element.
+    /// Only the elements no other ones depend on are evicted (e.g. the transactions with the
+    /// highest nonces of the account), cheapest first. Once all the dependents of an element
+    /// are evicted, it can be evicted as well. Returns the sequence numbers of the elements in the `ready_txs` queue to be evicted.
+    fn select_evicted(
+        &self,
+        element: &SignedTxVariant,
+        limits: &MempoolOptions,
+    ) -> Result<Vec<u64>, TxAddError> {
+        let txs = element_txs(element);
+
+        let mut new_txs_per_account = HashMap::new();
+        for tx in txs {
+            *new_txs_per_account.entry(tx.account()).or_insert(0) += 1;
+        }
+        for (address, new_txs) in new_txs_per_account {
+            let pending_txs = self.account_txs_count.get(&address).copied().unwrap_or(0);
+            if pending_txs + new_txs > limits.max_txs_per_account {
+                return Err(TxAddError::AccountTxsLimitExceeded);
+            }
+        }
+
+        if self.txs_count + txs.len() <= limits.max_size {
+            return Ok(Vec::new());
+        }
+
+        // Elements the new one would depend on can't be evicted.
+        let fee_per_chunk = self.fee_per_chunk(element);
+        let protected: HashSet<u64> = element_accounts(element)
+            .iter()
+            .filter_map(|account| self.account_elements.get(account)?.iter().next_back())
+            .copied()
+            .collect();
+
+        // Tails which pay less than the new element, cheapest first, merged with the
+        // elements which become tails once their dependents are evicted.
+        let mut tails = self
+            .tail_index
+            .range(..(fee_per_chunk.clone(), Reverse(u64::MAX)))
+            .peekable();
+        let mut exposed_tails = BinaryHeap::new();
+        let mut evicted_dependents = HashMap::new();
+
+        let mut txs_to_free = self.txs_count + txs.len() - limits.max_size;
+        let mut evicted = Vec::new();
+        while txs_to_free > 0 {
+            let take_exposed = match (tails.peek(), exposed_tails.peek()) {
+                (Some(tail), Some(Reverse(exposed))) => exposed < *tail,
+                (Some(_), None) => false,
+                (None, _) => true,
+            };
+            let next = if take_exposed {
+                exposed_tails.pop().map(|Reverse(key)| key)
+            } else {
+                tails.next().cloned()
+            };
+            let seq = match next {
+                Some((_, Reverse(seq))) => seq,
+                None => break,
+            };
+            if protected.contains(&seq) {
+                continue;
+            }
+
+            let pending = &self.ready_txs[&seq];
+            txs_to_free = txs_to_free.saturating_sub(element_txs(&pending.element).len());
+            evicted.push(seq);
+
+            for &blocker in &pending.blocked_by {
+                let count = evicted_dependents.entry(blocker).or_insert(0);
+                *count += 1;
+                let key = self.fee_key(blocker);
+                if *count == self.ready_txs[&blocker].dependents.len() && key.0 < fee_per_chunk {
+                    exposed_tails.push(Reverse(key));
+                }
+            }
+        }
+
+        if txs_to_free > 0 {
+            return Err(TxAddError::MempoolFull);
+        }
+
+        Ok(evicted)
+    }
+
+    /// Removes elements with the provided sequence numbers from the `ready_txs` queue.
+    fn evict(&mut self, seqs: Vec<u64>) {
+        for seq in seqs {
+            if let Some(element) = self.remove(seq) {
+                tracing::debug!(
+                    "Evicting transactions from the mempool: {:?}",
+                    element.hashes()
+                );
+            }
+        }
+    }
+
+    fn push_ready(&mut self, element: SignedTxVariant) {
+        for tx in element_txs(&element) {
+            *self.account_txs_count.entry(tx.account()).or_insert(0) += 1;
+        }
+        self.txs_count += element_txs(&element).len();
+
+        let seq = self.next_seq;
+        self.next_seq += 1;
+
+        // It's enough to depend on the last element affecting every account, since it
+        // depends on the previous ones itself.
+        let accounts = element_accounts(&element);
+        let blocked_by: BTreeSet<u64> = accounts
+            .iter()
+            .filter_map(|account| self.account_elements.get(account)?.iter().next_back())
+            .copied()
+            .collect();
+        for &blocker in &blocked_by {
+            let key = self.fee_key(blocker);
+            self.tail_index.remove(&key);
+            if let Some(pending) = self.ready_txs.get_mut(&blocker) {
+                pending.dependents.insert(seq);
+            }
+        }
+        for account in accounts {
+            self.account_elements
+                .entry(account)
+                .or_default()
+                .insert(seq);
+        }
+
+        let fee_per_chunk = self.fee_per_chunk(&element);
+        if blocked_by.is_empty() {
+            self.head_index
+                .insert((fee_per_chunk.clone(), Reverse(seq)));
+        }
+        self.tail_index
+            .insert((fee_per_chunk.clone(), Reverse(seq)));
+        self.ready_txs.insert(
+            seq,
+            PendingElement {
+                element,
+                fee_per_chunk,
+                blocked_by,
+                dependents: BTreeSet::new(),
+            },
+        );
+    }
+
+    /// Removes the element from the `ready_txs` queue. Dependents of the element inherit its
+    /// dependencies, so the order of the remaining elements is preserved.
+    fn remove(&mut self, seq: u64) -> Option<SignedTxVariant> {
+        let pending = self.ready_txs.remove(&seq)?;
+        let key = (pending.fee_per_chunk.clone(), Reverse(seq));
+        self.head_index.remove(&key);
+        self.tail_index.remove(&key);
+
+        for &blocker_seq in &pending.blocked_by {
+            if let Some(blocker) = self.ready_txs.get_mut(&blocker_seq) {
+                blocker.dependents.remove(&seq);
+                blocker.dependents.extend(&pending.dependents);
+                if blocker.dependents.is_empty() {
+                    self.tail_index
+                        .insert((blocker.fee_per_chunk.clone(), Reverse(blocker_seq)));
+                }
+            }
+        }
+        for &dependent_seq in &pending.dependents {
+            if let Some(dependent) = self.ready_txs.get_mut(&dependent_seq) {
+                dependent.blocked_by.remove(&seq);
+                dependent.blocked_by.extend(&pending.blocked_by);
+                if dependent.blocked_by.is_empty() {
+                    self.head_index
+                        .insert((dependent.fee_per_chunk.clone(), Reverse(dependent_seq)));
+                }
+            }
+        }
+        for account in element_accounts(&pending.element) {
+            if let Entry::Occupied(mut entry) = self.account_elements.entry(account) {
+                entry.get_mut().remove(&seq);
+                if entry.get().is_empty() {
+                    entry.remove();
+                }
+            }
+        }
+
+        self.untrack(&pending.element);
+        Some(pending.element)
+    }
+
+    /// Takes the elements fitting into `chunks_left` chunks from the front of the `ready_txs` queue.
+    /// Returns the amount of chunks left and the taken elements.
+    fn take_fifo(&mut self, mut chunks_left: usize) -> (usize, Vec<SignedTxVariant>) {
+        let mut taken = Vec::new();
+
+        while let Some((&seq, pending)) = self.ready_txs.iter().next() {
+            let chunks_for_element = self.required_chunks(&pending.element);
+            if chunks_left < chunks_for_element {
+                break;
+            }
+
+            chunks_left -= chunks_for_element;
+            taken.extend(self.remove(seq));
+        }
+
+        (chunks_left, taken)
+    }
+
+    /// Takes the elements fitting into `chunks_left` chunks from the `ready_txs` queue,
+    /// the ones with the highest fee per chunk first. Element can only be taken after all
+    /// the elements it depends on, so nonces are not reordered and the accounts are funded
+    /// before spending. Returns the amount of chunks left and the taken elements.
+    fn take_by_fee_per_chunk(&mut self, mut chunks_left: usize) -> (usize, Vec<SignedTxVariant>) {
+        let mut taken = Vec::new();
+        let mut skipped = Vec::new();
+
+        // Equally paying elements are taken in the order they were received. Once the element
+        // is taken, its dependents may be added to the index.
+        while chunks_left > 0 {
+            let key = match self.head_index.iter().next_back() {
+                Some(key) => key.clone(),
+                None => break,
+            };
+            self.head_index.remove(&key);
+
+            let (_, Reverse(seq)) = key;
+            let chunks_for_element = self.required_chunks(&self.ready_txs[&seq].element);
+            if chunks_for_element > chunks_left {
+                // Element does not fit, so its dependents can't be taken either.
+                skipped.push(key);
+                continue;
+            }
+            chunks_left -= chunks_for_element;
+            taken.extend(self.remove(seq));
+        }
+        self.head_index.extend(skipped);
+
+        (chunks_left, taken)
+    }
+
+    /// Updates the transactions counters once the element leaves the mempool.
+    fn untrack(&mut self, element: &SignedTxVariant) {
+        for tx in element_txs(element) {
+            if let Entry::Occupied(mut entry) = self.account_txs_count.entry(tx.account()) {
+                *entry.get_mut() -= 1;
+                if *entry.get() == 0 {
+                    entry.remove();
+                }
+            }
+        }
+        self.txs_count -= element_txs(element).len();
     }
 }
 
@@ -244,17 +676,32 @@ struct Mempool {
     eth_watch_req: mpsc::Sender<EthWatchRequest>,
     max_block_size_chunks: usize,
     max_number_of_withdrawals_per_block: usize,
+    limits: MempoolOptions,
+    token_prices_updated_at: Option<Instant>,
+    /// Once the shutdown is requested, new transactions are rejected.
+    shutdown: ShutdownSignal,
 }
 
 impl Mempool {
+    #[tracing::instrument(name = "mempool_add_tx", skip(self, tx), fields(tx_hash = %tx.hash().to_string()))]
     async fn add_tx(&mut self, tx: SignedZkSyncTx) -> Result<(), TxAddError> {
+        if self.shutdown.is_requested() {
+            return Err(TxAddError::ShuttingDown);
+        }
+        self.mempool_state.check_nonces(std::slice::from_ref(&tx))?;
+
+        self.update_token_prices().await;
+        let element = SignedTxVariant::from(tx.clone());
+        let evicted = self.mempool_state.select_evicted(&element, &self.limits)?;
+        let evicted_hashes = self.hashes_of(&evicted);
+
         let mut storage = self.db_pool.access_storage().await.map_err(|err| {
-            log::warn!("Mempool storage access error: {}", err);
+            tracing::warn!("Mempool storage access error: {}", err);
             TxAddError::DbError
         })?;
 
         let mut transaction = storage.start_transaction().await.map_err(|err| {
-            log::warn!("Mempool storage access error: {}", err);
+            tracing::warn!("Mempool storage access error: {}", err);
             TxAddError::DbError
         })?;
         transaction
@@ -263,29 +710,49 @@ impl Mempool {
             .insert_tx(&tx)
             .await
             .map_err(|err| {
-                log::warn!("Mempool storage access error: {}", err);
+                tracing::warn!("Mempool storage access error: {}", err);
+                TxAddError::DbError
+            })?;
+        transaction
+            .chain()
+            .mempool_schema()
+            .remove_txs(&evicted_hashes)
+            .await
+            .map_err(|err| {
+                tracing::warn!("Mempool storage access error: {}", err);
                 TxAddError::DbError
             })?;
 
         transaction.commit().await.map_err(|err| {
-            log::warn!("Mempool storage access error: {}", err);
+            tracing::warn!("Mempool storage access error: {}", err);
             TxAddError::DbError
         })?;
 
-        self.mempool_state.add_tx(tx)
+        self.mempool_state.evict(evicted);
+        self.mempool_state.push_ready(element);
+        tracing::debug!(
+            evicted = evicted_hashes.len(),
+            "Transaction added to the mempool"
+        );
+        Ok(())
     }
 
+    #[tracing::instrument(
+        name = "mempool_add_batch",
+        skip(self, txs, eth_signatures),
+        fields(batch_size = txs.len())
+    )]
     async fn add_batch(
         &mut self,
         txs: Vec<SignedZkSyncTx>,
         eth_signatures: Vec<TxEthSignature>,
     ) -> Result<(), TxAddError> {
-        let mut storage = self.db_pool.access_storage().await.map_err(|err| {
-            log::warn!("Mempool storage access error: {}", err);
-            TxAddError::DbError
-        })?;
+        if self.shutdown.is_requested() {
+            return Err(TxAddError::ShuttingDown);
+        }
+        self.mempool_state.check_nonces(&txs)?;
 
-        let mut batch: SignedTxsBatch = SignedTxsBatch {
+        let batch: SignedTxsBatch = SignedTxsBatch {
             txs: txs.clone(),
             batch_id: 0, // Will be determined after inserting to the database
             eth_signatures: eth_signatures.clone(),
@@ -296,7 +763,7 @@ impl Mempool {
         }
 
         let mut number_of_withdrawals = 0;
-        for tx in txs {
+        for tx in &txs {
             if tx.tx.is_withdraw() {
                 number_of_withdrawals += 1;
             }
@@ -305,27 +772,147 @@ impl Mempool {
             return Err(TxAddError::BatchWithdrawalsOverload);
         }
 
+        self.update_token_prices().await;
+        let mut element = SignedTxVariant::Batch(batch);
+        let evicted = self.mempool_state.select_evicted(&element, &self.limits)?;
+        let evicted_hashes = self.hashes_of(&evicted);
+
+        let mut storage = self.db_pool.access_storage().await.map_err(|err| {
+            tracing::warn!("Mempool storage access error: {}", err);
+            TxAddError::DbError
+        })?;
+
         let mut transaction = storage.start_transaction().await.map_err(|err| {
-            log::warn!("Mempool storage access error: {}", err);
+            tracing::warn!("Mempool storage access error: {}", err);
             TxAddError::DbError
         })?;
         let batch_id = transaction
             .chain()
             .mempool_schema()
-            .insert_batch(&batch.txs, eth_signatures)
+            .insert_batch(&txs, eth_signatures)
+            .await
+            .map_err(|err| {
+                tracing::warn!("Mempool storage access error: {}", err);
+                TxAddError::DbError
+            })?;
+        transaction
+            .chain()
+            .mempool_schema()
+            .remove_txs(&evicted_hashes)
             .await
             .map_err(|err| {
-                log::warn!("Mempool storage access error: {}", err);
+                tracing::warn!("Mempool storage access error: {}", err);
                 TxAddError::DbError
             })?;
         transaction.commit().await.map_err(|err| {
-            log::warn!("Mempool storage access error: {}", err);
+            tracing::warn!("Mempool storage access error: {}", err);
             TxAddError::DbError
         })?;
 
-        batch.batch_id = batch_id;
+        assert_ne!(batch_id, 0, "Batch ID was not set");
+        if let SignedTxVariant::Batch(batch) = &mut element {
+            batch.batch_id = batch_id;
+        }
+
+        self.mempool_state.evict(evicted);
+        self.mempool_state.push_ready(element);
+        for tx in &txs {
+            tracing::debug!(
+                tx_hash = %tx.hash().to_string(),
+                batch_id,
+                evicted = evicted_hashes.len(),
+                "Transaction added to the mempool as a part of a batch"
+            );
+        }
+        Ok(())
+    }
+
+    /// Removes the elements containing any of the provided transactions from the mempool.
+    async fn remove_txs(&mut self, tx_hashes: Vec<TxHash>) {
+        let tx_hashes: HashSet<TxHash> = tx_hashes.into_iter().collect();
+        let removed: Vec<u64> = self
+            .mempool_state
+            .ready_txs
+            .iter()
+            .filter(|(_, pending)| {
+                pending
+                    .element
+                    .hashes()
+                    .iter()
+                    .any(|hash| tx_hashes.contains(hash))
+            })
+            .map(|(&seq, _)| seq)
+            .collect();
+        if removed.is_empty() {
+            return;
+        }
+
+        let removed_hashes = self.hashes_of(&removed);
+        let db_result: anyhow::Result<()> = async {
+            let mut storage = self.db_pool.access_storage().await?;
+            storage
+                .chain()
+                .mempool_schema()
+                .remove_txs(&removed_hashes)
+                .await
+        }
+        .await;
+        if let Err(err) = db_result {
+            tracing::warn!(
+                "Unable to remove transactions from the mempool storage: {}",
+                err
+            );
+            return;
+        }
+
+        tracing::info!(
+            "Removed {} transactions from the mempool: {:?}",
+            removed_hashes.len(),
+            removed_hashes
+        );
+        self.mempool_state.evict(removed);
+    }
+
+    /// Returns hashes of all the transactions from the `ready_txs` elements with the given sequence numbers.
+    fn hashes_of(&self, seqs: &[u64]) -> Vec<TxHash> {
+        seqs.iter()
+            .flat_map(|seq| self.mempool_state.ready_txs[seq].element.hashes())
+            .collect()
+    }
+
+    /// Reloads the token prices from the database if they're outdated.
+    async fn update_token_prices(&mut self) {
+        if let Some(updated_at) = self.token_prices_updated_at {
+            if updated_at.elapsed() < TOKEN_PRICES_UPDATE_INTERVAL {
+                return;
+            }
+        }
+
+        match self.load_token_prices().await {
+            Ok(token_prices) => self.mempool_state.set_token_prices(token_prices),
+            Err(err) => tracing::warn!("Unable to load token prices for the mempool: {}", err),
+        }
+        self.token_prices_updated_at = Some(Instant::now());
+    }
+
+    async fn load_token_prices(&self) -> anyhow::Result<HashMap<TokenId, Ratio<BigUint>>> {
+        let mut storage = self.db_pool.access_storage().await?;
+        let tokens = storage.tokens_schema().load_tokens().await?;
+
+        let mut token_prices = HashMap::new();
+        for (token_id, token) in tokens {
+            let price = storage
+                .tokens_schema()
+                .get_historical_ticker_price(token_id)
+                .await?;
+
+            if let Some(price) = price {
+                let token_unit = BigUint::from(10u32).pow(u32::from(token.decimals));
+                token_prices.insert(token_id, price.usd_price / token_unit);
+            }
+        }
 
-        self.mempool_state.add_batch(batch)
+        Ok(token_prices)
     }
 
     async fn run(mut self) {
@@ -339,6 +926,9 @@ impl Mempool {
                     let tx_add_result = self.add_batch(txs, eth_signatures).await;
                     resp.send(tx_add_result).unwrap_or_default();
                 }
+                MempoolRequest::RemoveTxs(tx_hashes) => {
+                    self.remove_txs(tx_hashes).await;
+                }
                 MempoolRequest::GetBlock(block) => {
                     // Generate proposed block.
                     let proposed_block =
@@ -351,15 +941,19 @@ impl Mempool {
                         .expect("mempool proposed block response send failed");
                 }
                 MempoolRequest::UpdateNonces(updates) => {
+                    // Transfers to the created accounts require less chunks, so the fees per chunk change.
+                    let mut accounts_changed = false;
                     for (id, update) in updates {
                         match update {
                             AccountUpdate::Create { address, nonce } => {
                                 self.mempool_state.account_ids.insert(id, address);
                                 self.mempool_state.account_nonces.insert(address, nonce);
+                                accounts_changed = true;
                             }
                             AccountUpdate::Delete { address, .. } => {
                                 self.mempool_state.account_ids.remove(&id);
                                 self.mempool_state.account_nonces.remove(&address);
+                                accounts_changed = true;
                             }
                             AccountUpdate::UpdateBalance { new_nonce, .. } => {
                                 if let Some(address) = self.mempool_state.account_ids.get(&id) {
@@ -381,8 +975,16 @@ impl Mempool {
                             }
                         }
                     }
+                    if accounts_changed {
+                        self.mempool_state.reindex();
+                    }
                 }
             }
+
+            metrics::gauge!(
+                "mempool.ready_txs",
+                self.mempool_state.ready_txs.len() as f64
+            );
         }
     }
 
@@ -393,8 +995,8 @@ impl Mempool {
             .await;
         let (_chunks_left, txs) = self.prepare_tx_for_block(chunks_left);
 
-        log::trace!("Proposed priority ops for block: {:#?}", priority_ops);
-        log::trace!("Proposed txs for block: {:#?}", txs);
+        tracing::trace!("Proposed priority ops for block: {:#?}", priority_ops);
+        tracing::trace!("Proposed txs for block: {:#?}", txs);
         metrics::histogram!("mempool.propose_new_block", start.elapsed());
         ProposedBlock { priority_ops, txs }
     }
@@ -427,22 +1029,11 @@ impl Mempool {
         )
     }
 
-    fn prepare_tx_for_block(&mut self, mut chunks_left: usize) -> (usize, Vec<SignedTxVariant>) {
-        let mut txs_for_commit = Vec::new();
-
-        while let Some(tx) = self.mempool_state.ready_txs.pop_front() {
-            let chunks_for_tx = self.mempool_state.required_chunks(&tx);
-            if chunks_left >= chunks_for_tx {
-                txs_for_commit.push(tx);
-                chunks_left -= chunks_for_tx;
-            } else {
-                // Push the taken tx back, it does not fit.
-                self.mempool_state.ready_txs.push_front(tx);
-                break;
-            }
+    fn prepare_tx_for_block(&mut self, chunks_left: usize) -> (usize, Vec<SignedTxVariant>) {
+        match self.limits.tx_ordering {
+            TxOrdering::FeePerChunk => self.mempool_state.take_by_fee_per_chunk(chunks_left),
+            TxOrdering::Fifo => self.mempool_state.take_fifo(chunks_left),
         }
-
-        (chunks_left, txs_for_commit)
     }
 }
 
@@ -452,7 +1043,11 @@ pub fn run_mempool_task(
     requests: mpsc::Receiver<MempoolRequest>,
     eth_watch_req: mpsc::Sender<EthWatchRequest>,
     config: &ConfigurationOptions,
+    limits: MempoolOptions,
+    shutdown: ShutdownSignal,
 ) -> JoinHandle<()> {
+    register_metrics("mempool", METRICS);
+
     let config = config.clone();
     tokio::spawn(async move {
         let mempool_state = MempoolState::restore_from_db(&db_pool).await;
@@ -468,8 +1063,300 @@ pub fn run_mempool_task(
                 .max()
                 .expect("failed to find max block chunks size"),
             max_number_of_withdrawals_per_block: config.max_number_of_withdrawals_per_block,
+            limits,
+            token_prices_updated_at: None,
+            shutdown,
         };
 
         mempool.run().await
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_types::{Transfer, TransferToNewOp};
+
+    fn mempool_limits(max_size: usize, max_txs_per_account: usize) -> MempoolOptions {
+        MempoolOptions {
+            max_size,
+            max_txs_per_account,
+            tx_ordering: TxOrdering::FeePerChunk,
+        }
+    }
+
+    fn empty_state() -> MempoolState {
+        let mut token_prices = HashMap::new();
+        token_prices.insert(0, Ratio::from_integer(BigUint::from(1u32)));
+
+        MempoolState {
+            account_nonces: HashMap::new(),
+            account_ids: HashMap::new(),
+            ready_txs: BTreeMap::new(),
+            next_seq: 0,
+            account_elements: HashMap::new(),
+            head_index: BTreeSet::new(),
+            tail_index: BTreeSet::new(),
+            txs_count: 0,
+            account_txs_count: HashMap::new(),
+            token_prices,
+        }
+    }
+
+    fn transfer(from: Address, nonce: Nonce, fee: u32) -> SignedTxVariant {
+        transfer_to(from, Address::random(), nonce, fee)
+    }
+
+    fn transfer_to(from: Address, to: Address, nonce: Nonce, fee: u32) -> SignedTxVariant {
+        let transfer = Transfer::new(
+            0,
+            from,
+            to,
+            0,
+            BigUint::from(1u32),
+            BigUint::from(fee),
+            nonce,
+            None,
+        );
+
+        SignedZkSyncTx::from(ZkSyncTx::from(transfer)).into()
+    }
+
+    /// Returns hashes of the pending elements in the order they were received.
+    fn pending_hashes(state: &MempoolState) -> Vec<Vec<TxHash>> {
+        state
+            .ready_txs
+            .values()
+            .map(|pending| pending.element.hashes())
+            .collect()
+    }
+
+    /// Checks that a single account can't exceed the limit of pending transactions.
+    #[test]
+    fn account_txs_limit() {
+        let limits = mempool_limits(10, 1);
+        let mut state = empty_state();
+        let account = Address::random();
+        state.push_ready(transfer(account, 0, 10));
+
+        let result = state.select_evicted(&transfer(account, 1, 10), &limits);
+        assert!(matches!(result, Err(TxAddError::AccountTxsLimitExceeded)));
+
+        // Other accounts must not be affected.
+        let evicted = state
+            .select_evicted(&transfer(Address::random(), 0, 10), &limits)
+            .unwrap();
+        assert!(evicted.is_empty());
+    }
+
+    /// Checks that once the mempool is full, the cheapest transactions are evicted first.
+    #[test]
+    fn cheapest_txs_are_evicted() {
+        let limits = mempool_limits(2, 10);
+        let mut state = empty_state();
+        state.push_ready(transfer(Address::random(), 0, 20));
+        state.push_ready(transfer(Address::random(), 0, 10));
+
+        let evicted = state
+            .select_evicted(&transfer(Address::random(), 0, 30), &limits)
+            .unwrap();
+        assert_eq!(evicted, vec![1]);
+
+        state.evict(evicted);
+        assert_eq!(state.txs_count, 1);
+        assert_eq!(state.account_txs_count.len(), 1);
+    }
+
+    /// Checks that only the transactions with the highest nonces of the account are evicted,
+    /// and the preceding ones are evicted only after them.
+    #[test]
+    fn txs_evicted_from_account_tail() {
+        let limits = mempool_limits(3, 10);
+        let mut state = empty_state();
+        let account = Address::random();
+        state.push_ready(transfer(account, 0, 5));
+        state.push_ready(transfer(account, 1, 50));
+        state.push_ready(transfer(Address::random(), 0, 20));
+
+        // The cheapest transaction is not evicted, since the following one depends on it.
+        let evicted = state
+            .select_evicted(&transfer(Address::random(), 0, 30), &limits)
+            .unwrap();
+        assert_eq!(evicted, vec![2]);
+
+        let batch = SignedTxVariant::Batch(SignedTxsBatch {
+            txs: element_txs(&transfer(Address::random(), 0, 60))
+                .iter()
+                .chain(element_txs(&transfer(Address::random(), 0, 60)))
+                .cloned()
+                .collect(),
+            batch_id: 1,
+            eth_signatures: Vec::new(),
+        });
+        let evicted = state.select_evicted(&batch, &limits).unwrap();
+        assert_eq!(evicted, vec![2, 1]);
+
+        // Pending transactions of the account can't be evicted in favor of its new transactions.
+        let batch = SignedTxVariant::Batch(SignedTxsBatch {
+            txs: element_txs(&transfer(account, 2, 100))
+                .iter()
+                .chain(element_txs(&transfer(account, 3, 100)))
+                .cloned()
+                .collect(),
+            batch_id: 2,
+            eth_signatures: Vec::new(),
+        });
+        let result = state.select_evicted(&batch, &limits);
+        assert!(matches!(result, Err(TxAddError::MempoolFull)));
+    }
+
+    /// Checks that a transaction which doesn't pay more than pending ones is rejected if mempool is full.
+    #[test]
+    fn underpaying_tx_rejected_when_full() {
+        let limits = mempool_limits(2, 10);
+        let mut state = empty_state();
+        state.push_ready(transfer(Address::random(), 0, 20));
+        state.push_ready(transfer(Address::random(), 0, 10));
+
+        let result = state.select_evicted(&transfer(Address::random(), 0, 10), &limits);
+        assert!(matches!(result, Err(TxAddError::MempoolFull)));
+    }
+
+    /// Checks that the restored transactions with outdated nonces are not added to the queue.
+    #[test]
+    fn outdated_txs_not_restored() {
+        let mut state = empty_state();
+        let account = Address::random();
+        state.account_nonces.insert(account, 1);
+
+        let outdated = transfer(account, 0, 10);
+        let actual = transfer(account, 1, 10);
+        let batch = SignedTxVariant::Batch(SignedTxsBatch {
+            txs: element_txs(&transfer(Address::random(), 0, 10))
+                .iter()
+                .chain(element_txs(&outdated))
+                .cloned()
+                .collect(),
+            batch_id: 1,
+            eth_signatures: Vec::new(),
+        });
+
+        let mut expected_outdated = outdated.hashes();
+        expected_outdated.extend(batch.hashes());
+
+        let outdated_txs = state.restore_txs(vec![outdated, actual.clone(), batch]);
+        assert_eq!(outdated_txs, expected_outdated);
+        assert_eq!(state.txs_count, 1);
+        assert_eq!(pending_hashes(&state), vec![actual.hashes()]);
+    }
+
+    /// Checks that the best paying transactions are taken first, without reordering
+    /// the transactions of the same account.
+    #[test]
+    fn txs_taken_by_fee_per_chunk() {
+        let mut state = empty_state();
+        let account = Address::random();
+        let cheap = transfer(Address::random(), 0, 10);
+        let first = transfer(account, 0, 5);
+        let second = transfer(account, 1, 50);
+        let expensive = transfer(Address::random(), 0, 30);
+        state.push_ready(cheap.clone());
+        state.push_ready(first.clone());
+        state.push_ready(second.clone());
+        state.push_ready(expensive.clone());
+
+        // Only three transfers to the new accounts fit into the block.
+        let (chunks_left, taken) = state.take_by_fee_per_chunk(3 * TransferToNewOp::CHUNKS);
+        assert_eq!(chunks_left, 0);
+        let taken: Vec<_> = taken.iter().map(SignedTxVariant::hashes).collect();
+        assert_eq!(
+            taken,
+            vec![expensive.hashes(), cheap.hashes(), first.hashes()]
+        );
+
+        assert_eq!(state.txs_count, 1);
+        assert_eq!(pending_hashes(&state), vec![second.hashes()]);
+    }
+
+    /// Checks that the transactions of an account are not taken before the preceding
+    /// transfers to it, and that the removal of a pending element keeps this order.
+    #[test]
+    fn txs_taken_after_transfers_to_sender() {
+        let mut state = empty_state();
+        let funder = Address::random();
+        let account = Address::random();
+        let funding = transfer_to(funder, account, 0, 5);
+        let top_up = transfer_to(Address::random(), account, 0, 10);
+        let spending = transfer(account, 0, 50);
+        state.push_ready(funding.clone());
+        state.push_ready(top_up.clone());
+        state.push_ready(spending.clone());
+
+        // Top up is removed, but the spending still waits for the funding.
+        state.evict(vec![1]);
+        let (_, taken) = state.take_by_fee_per_chunk(10 * TransferToNewOp::CHUNKS);
+        let taken: Vec<_> = taken.iter().map(SignedTxVariant::hashes).collect();
+        assert_eq!(taken, vec![funding.hashes(), spending.hashes()]);
+
+        assert_eq!(state.txs_count, 0);
+        assert!(state.account_elements.is_empty());
+    }
+
+    /// Checks that in the FIFO mode transactions are taken in the order they were received.
+    #[test]
+    fn txs_taken_fifo() {
+        let mut state = empty_state();
+        let cheap = transfer(Address::random(), 0, 10);
+        let expensive = transfer(Address::random(), 0, 30);
+        state.push_ready(cheap.clone());
+        state.push_ready(expensive.clone());
+
+        let (_, taken) = state.take_fifo(TransferToNewOp::CHUNKS);
+        assert_eq!(taken.len(), 1);
+        assert_eq!(taken[0].hashes(), cheap.hashes());
+        assert_eq!(pending_hashes(&state), vec![expensive.hashes()]);
+    }
+
+    /// Checks that transactions and batches are rejected once the shutdown is requested.
+    #[tokio::test]
+    async fn txs_rejected_on_shutdown() {
+        let (_, requests) = mpsc::channel(1);
+        let (eth_watch_req, _) = mpsc::channel(1);
+        let shutdown = ShutdownSignal::new();
+        let mut mempool = Mempool {
+            db_pool: ConnectionPool::new(Some(1)),
+            mempool_state: empty_state(),
+            requests,
+            eth_watch_req,
+            max_block_size_chunks: 10 * TransferToNewOp::CHUNKS,
+            max_number_of_withdrawals_per_block: 1,
+            limits: mempool_limits(10, 10),
+            token_prices_updated_at: None,
+            shutdown: shutdown.clone(),
+        };
+        let tx = |nonce| {
+            let transfer = Transfer::new(
+                0,
+                Address::random(),
+                Address::random(),
+                0,
+                BigUint::from(1u32),
+                BigUint::from(10u32),
+                nonce,
+                None,
+            );
+            SignedZkSyncTx::from(ZkSyncTx::from(transfer))
+        };
+
+        shutdown.request();
+        assert!(matches!(
+            mempool.add_tx(tx(0)).await,
+            Err(TxAddError::ShuttingDown)
+        ));
+        assert!(matches!(
+            mempool.add_batch(vec![tx(0), tx(1)], Vec::new()).await,
+            Err(TxAddError::ShuttingDown)
+        ));
+        assert_eq!(mempool.mempool_state.txs_count, 0);
+    }
+}
@@ -15,7 +15,10 @@ use futures::{
 };
 use std::thread;
 use zksync_config::ApiServerOptions;
-use zksync_types::{tx::TxEthSignature, Address, SignedZkSyncTx, H256};
+use zksync_types::{
+    tx::{TxEthSignature, TxHash},
+    Address, PriorityOp, SignedZkSyncTx, H256,
+};
 use zksync_utils::panic_notify::ThreadPanicNotify;
 
 #[derive(Debug, Clone)]
@@ -70,6 +73,23 @@ async fn new_txs_batch(
     Ok(HttpResponse::Ok().json(response))
 }
 
+/// Removes transactions with the given hashes from the mempool.
+/// Expects the hashes to be selected on the API side (e.g. during the fee revalidation).
+#[actix_web::post("/remove_txs")]
+async fn remove_txs(
+    data: web::Data<AppState>,
+    web::Json(tx_hashes): web::Json<Vec<TxHash>>,
+) -> actix_web::Result<HttpResponse> {
+    let item = MempoolRequest::RemoveTxs(tx_hashes);
+    let mut mempool_sender = data.mempool_tx_sender.clone();
+    mempool_sender
+        .send(item)
+        .await
+        .map_err(|_err| HttpResponse::InternalServerError().finish())?;
+
+    Ok(HttpResponse::Ok().json(()))
+}
+
 /// Obtains information about unconfirmed deposits known for a certain address.
 #[actix_web::get("/unconfirmed_deposits/{address}")]
 async fn unconfirmed_deposits(
@@ -118,6 +138,55 @@ async fn unconfirmed_op(
     Ok(HttpResponse::Ok().json(response))
 }
 
+/// Claims the pending priority operations known to the Ethereum watcher by the hash
+/// of their Ethereum transaction.
+/// Returns a JSON representation of `Vec<PriorityOpClaim>`.
+#[actix_web::get("/priority_op_claims/{tx_hash}")]
+async fn priority_op_claims(
+    data: web::Data<AppState>,
+    web::Path(eth_hash): web::Path<H256>,
+) -> actix_web::Result<HttpResponse> {
+    let (sender, receiver) = oneshot::channel();
+    let item = EthWatchRequest::ClaimPriorityOps {
+        eth_hash,
+        resp: sender,
+    };
+    let mut eth_watch_sender = data.eth_watch_req_sender.clone();
+    eth_watch_sender
+        .send(item)
+        .await
+        .map_err(|_err| HttpResponse::InternalServerError().finish())?;
+
+    let response = receiver
+        .await
+        .map_err(|_err| HttpResponse::InternalServerError().finish())?;
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Estimates the inclusion of the priority operations found on Ethereum by the API server,
+/// which are not processed by the Ethereum watcher yet.
+/// Returns a JSON representation of `Vec<PriorityOpClaim>`.
+#[actix_web::post("/priority_op_estimates")]
+async fn priority_op_estimates(
+    data: web::Data<AppState>,
+    web::Json(ops): web::Json<Vec<PriorityOp>>,
+) -> actix_web::Result<HttpResponse> {
+    let (sender, receiver) = oneshot::channel();
+    let item = EthWatchRequest::EstimatePriorityOps { ops, resp: sender };
+    let mut eth_watch_sender = data.eth_watch_req_sender.clone();
+    eth_watch_sender
+        .send(item)
+        .await
+        .map_err(|_err| HttpResponse::InternalServerError().finish())?;
+
+    let response = receiver
+        .await
+        .map_err(|_err| HttpResponse::InternalServerError().finish())?;
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn start_private_core_api(
     panic_notify: mpsc::Sender<bool>,
@@ -146,8 +215,11 @@ pub fn start_private_core_api(
                         .app_data(web::Data::new(app_state))
                         .service(new_tx)
                         .service(new_txs_batch)
+                        .service(remove_txs)
                         .service(unconfirmed_op)
                         .service(unconfirmed_deposits)
+                        .service(priority_op_claims)
+                        .service(priority_op_estimates)
                 })
                 .bind(&api_server_options.core_server_address)
                 .expect("failed to bind")
@@ -1,5 +1,5 @@
 use std::collections::{HashMap, VecDeque};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 // External uses
 use futures::{
     channel::{mpsc, oneshot},
@@ -9,8 +9,10 @@ use futures::{
 use itertools::Itertools;
 use tokio::task::JoinHandle;
 // Workspace uses
+use zksync_config::{loader::config_generation, MiniblockTimings};
 use zksync_crypto::ff;
 use zksync_crypto::ff::{PrimeField, PrimeFieldRepr};
+use zksync_prometheus_exporter::{register_metrics, Metric};
 use zksync_state::state::{CollectedFee, OpSuccess, ZkSyncState};
 use zksync_storage::ConnectionPool;
 use zksync_types::{
@@ -20,13 +22,13 @@ use zksync_types::{
     },
     gas_counter::GasCounter,
     mempool::SignedTxVariant,
-    tx::{TxHash, ZkSyncTx},
+    tx::{TxFailureTrace, TxHash, ZkSyncTx},
     Account, AccountId, AccountTree, AccountUpdate, AccountUpdates, ActionType, Address,
-    BlockNumber, PriorityOp, SignedZkSyncTx, H256,
+    BlockNumber, PriorityOp, SignedZkSyncTx, ZkSyncPriorityOp, H256,
 };
 // Local uses
 use crate::{
-    committer::{AppliedUpdatesRequest, BlockCommitRequest, CommitRequest},
+    committer::{AppliedUpdatesRequest, BlockCommitRequest, CommitQueueDepth, CommitRequest},
     mempool::ProposedBlock,
 };
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -34,6 +36,67 @@ use std::time::{SystemTime, UNIX_EPOCH};
 #[cfg(test)]
 mod tests;
 
+/// Metrics reported by the state keeper.
+const METRICS: &[Metric] = &[
+    Metric::histogram(
+        "state_keeper.execute_proposed_block",
+        "Time spent on executing the block proposed by the mempool",
+    ),
+    Metric::histogram(
+        "state_keeper.apply_tx",
+        "Time spent on executing a transaction",
+    ),
+    Metric::histogram(
+        "state_keeper.apply_batch",
+        "Time spent on executing a batch",
+    ),
+    Metric::histogram(
+        "state_keeper.apply_priority_op",
+        "Time spent on executing a priority operation",
+    ),
+    Metric::histogram(
+        "state_keeper.seal_pending_block",
+        "Time spent on sealing a block",
+    ),
+    Metric::histogram(
+        "state_keeper.store_pending_block",
+        "Time spent on persisting the pending block",
+    ),
+    Metric::histogram(
+        "state_keeper.commit_queue_wait",
+        "Time the state keeper was paused waiting for the committer queue to free up",
+    ),
+    Metric::counter(
+        "state_keeper.commit_queue_full",
+        "Number of the times the committer queue was full",
+    ),
+    Metric::counter(
+        "state_keeper.executed_ops",
+        "Number of the executed operations",
+    ),
+    Metric::counter(
+        "state_keeper.sealed_blocks",
+        "Number of the sealed blocks by reason",
+    ),
+    Metric::gauge(
+        "state_keeper.last_sealed_block",
+        "Number of the latest block sealed by the state keeper",
+    ),
+    Metric::gauge(
+        "state_keeper.pending_block_chunks_used",
+        "Number of chunks used in the pending block",
+    ),
+    Metric::gauge(
+        "state_keeper.pending_block_occupancy",
+        "Share of the pending block chunks in use",
+    ),
+    Metric::gauge(
+        "state_keeper.pending_block_ops",
+        "Number of operations in the pending block",
+    ),
+    Metric::gauge("tx_batch_size", "Size of the latest executed batch"),
+];
+
 pub enum ExecutedOpId {
     Transaction(TxHash),
     PriorityOp(u64),
@@ -44,6 +107,52 @@ pub enum StateKeeperRequest {
     GetLastUnprocessedPriorityOp(oneshot::Sender<u64>),
     ExecuteMiniBlock(ProposedBlock),
     SealBlock,
+    /// Seals the pending block (if there are any operations in it) before the maintenance,
+    /// no new operations are proposed until the maintenance is over.
+    Maintenance,
+    /// Seals the pending block (if there are any operations in it) and stops the state keeper.
+    Shutdown,
+}
+
+/// Reason of the pending block sealing, reported to the metrics.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SealReason {
+    /// There is no space left in the block, or the next operation exceeds the gas or withdrawals limit.
+    BlockFull,
+    /// Block reached the maximum amount of the miniblock iterations.
+    MiniblockIterations,
+    /// Block commit deadline has elapsed.
+    CommitDeadline,
+    /// Sealing was requested explicitly.
+    Requested,
+    /// Maintenance mode was enabled.
+    Maintenance,
+    /// Server is shutting down.
+    Shutdown,
+}
+
+impl SealReason {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::BlockFull => "block_full",
+            Self::MiniblockIterations => "miniblock_iterations",
+            Self::CommitDeadline => "commit_deadline",
+            Self::Requested => "requested",
+            Self::Maintenance => "maintenance",
+            Self::Shutdown => "shutdown",
+        }
+    }
+}
+
+/// Name of the transaction type, used as a metrics label.
+fn tx_type_label(tx: &ZkSyncTx) -> &'static str {
+    match tx {
+        ZkSyncTx::Transfer(_) => "transfer",
+        ZkSyncTx::Withdraw(_) => "withdraw",
+        ZkSyncTx::Close(_) => "close",
+        ZkSyncTx::ChangePubKey(_) => "change_pubkey",
+        ZkSyncTx::ForcedExit(_) => "forced_exit",
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -65,6 +174,8 @@ struct PendingBlock {
     stored_account_updates: usize,
     previous_block_root_hash: H256,
     timestamp: u64,
+    /// Moment when the first operation was included into the block.
+    first_op_executed_at: Option<Instant>,
 }
 
 impl PendingBlock {
@@ -89,6 +200,7 @@ impl PendingBlock {
             stored_account_updates: 0,
             previous_block_root_hash,
             timestamp,
+            first_op_executed_at: None,
         }
     }
 }
@@ -105,11 +217,18 @@ pub struct ZkSyncStateKeeper {
 
     rx_for_blocks: mpsc::Receiver<StateKeeperRequest>,
     tx_for_commitments: mpsc::Sender<CommitRequest>,
+    commit_queue_depth: CommitQueueDepth,
 
     available_block_chunk_sizes: Vec<usize>,
     max_miniblock_iterations: usize,
     fast_miniblock_iterations: usize,
+    /// Time after which a non-empty pending block is sealed regardless of its fill level.
+    block_commit_deadline: Duration,
     max_number_of_withdrawals_per_block: usize,
+    /// Whether the details of the failed transactions are recorded along with the failure reason.
+    tx_trace_enabled: bool,
+    /// Configuration generation the block sealing timings were read at.
+    config_generation: u64,
 
     // Two fields below are for optimization: we don't want to overwrite all the block contents over and over.
     // With these fields we'll be able save the diff between two pending block states only.
@@ -276,7 +395,7 @@ impl ZkSyncStateInitParams {
         self.unprocessed_priority_op =
             Self::unprocessed_priority_op_id(storage, block_number).await?;
 
-        log::info!(
+        tracing::info!(
             "Loaded committed state: last block number: {}, unprocessed priority op: {}",
             self.last_block_number,
             self.unprocessed_priority_op
@@ -353,10 +472,13 @@ impl ZkSyncStateKeeper {
         fee_account_address: Address,
         rx_for_blocks: mpsc::Receiver<StateKeeperRequest>,
         tx_for_commitments: mpsc::Sender<CommitRequest>,
+        commit_queue_depth: CommitQueueDepth,
         available_block_chunk_sizes: Vec<usize>,
         max_miniblock_iterations: usize,
         fast_miniblock_iterations: usize,
+        block_commit_deadline: Duration,
         max_number_of_withdrawals_per_block: usize,
+        tx_trace_enabled: bool,
     ) -> Self {
         assert!(!available_block_chunk_sizes.is_empty());
 
@@ -390,6 +512,7 @@ impl ZkSyncStateKeeper {
             current_unprocessed_priority_op: initial_state.unprocessed_priority_op,
             rx_for_blocks,
             tx_for_commitments,
+            commit_queue_depth,
             pending_block: PendingBlock::new(
                 initial_state.unprocessed_priority_op,
                 max_block_size,
@@ -402,14 +525,17 @@ impl ZkSyncStateKeeper {
             available_block_chunk_sizes,
             max_miniblock_iterations,
             fast_miniblock_iterations,
+            block_commit_deadline,
             max_number_of_withdrawals_per_block,
+            tx_trace_enabled,
+            config_generation: config_generation(),
 
             success_txs_pending_len: 0,
             failed_txs_pending_len: 0,
         };
 
         let root = keeper.state.root_hash();
-        log::info!("created state keeper, root hash = {}", root);
+        tracing::info!("created state keeper, root hash = {}", root);
 
         keeper
     }
@@ -442,16 +568,32 @@ impl ZkSyncStateKeeper {
                 }
             }
             self.pending_block.stored_account_updates = self.pending_block.account_updates.len();
+            // Time of the restored operations execution is unknown, so the deadline is counted from the restart.
+            if !self.pending_block.success_operations.is_empty() {
+                self.pending_block.first_op_executed_at = Some(Instant::now());
+            }
 
-            log::info!(
+            tracing::info!(
                 "Executed restored proposed block: {} transactions, {} priority operations, {} failed transactions",
                 txs_count,
                 priority_op_count,
                 pending_block.failed_txs.len()
             );
             self.pending_block.failed_txs = pending_block.failed_txs;
+
+            // Resume the block from the point it was persisted at, so it will be sealed at the same moment
+            // as if there was no restart.
+            self.pending_block.pending_block_iteration = pending_block.pending_block_iteration;
+            self.pending_block.previous_block_root_hash = pending_block.previous_block_root_hash;
+            // Blocks persisted before the timestamp was introduced don't have it.
+            if pending_block.timestamp != 0 {
+                self.pending_block.timestamp = pending_block.timestamp;
+            }
+            // Restored operations are already stored in the database.
+            self.success_txs_pending_len = self.pending_block.success_operations.len();
+            self.failed_txs_pending_len = self.pending_block.failed_txs.len();
         } else {
-            log::info!("There is no pending block to restore");
+            tracing::info!("There is no pending block to restore");
         }
 
         metrics::histogram!("state_keeper.initialize", start.elapsed());
@@ -512,7 +654,7 @@ impl ZkSyncStateKeeper {
             .commit()
             .await
             .expect("Unable to commit transaction in statekeeper");
-        log::info!("Genesis block created, state: {}", state.root_hash());
+        tracing::info!("Genesis block created, state: {}", state.root_hash());
         println!("GENESIS_ROOT=0x{}", ff::to_hex(&root_hash));
         metrics::histogram!("state_keeper.create_genesis_block", start.elapsed());
     }
@@ -531,15 +673,57 @@ impl ZkSyncStateKeeper {
                         .unwrap_or_default();
                 }
                 StateKeeperRequest::ExecuteMiniBlock(proposed_block) => {
+                    self.reload_timings();
                     self.execute_proposed_block(proposed_block).await;
                 }
                 StateKeeperRequest::SealBlock => {
-                    self.seal_pending_block().await;
+                    self.seal_pending_block(SealReason::Requested).await;
+                }
+                StateKeeperRequest::Maintenance => {
+                    self.seal_for_maintenance().await;
+                }
+                StateKeeperRequest::Shutdown => {
+                    if self.pending_block_has_operations() {
+                        self.seal_pending_block(SealReason::Shutdown).await;
+                    }
+                    // Returning drops the sender of the commit requests, so the committer
+                    // stops once it stores all the sealed blocks.
+                    tracing::info!("State keeper stopped");
+                    return;
                 }
             }
         }
     }
 
+    /// Seals the pending block once the block proposer has drained the mempool
+    /// in the maintenance mode.
+    async fn seal_for_maintenance(&mut self) {
+        if self.pending_block_has_operations() {
+            self.seal_pending_block(SealReason::Maintenance).await;
+        }
+        tracing::info!("Pending block is sealed before the maintenance");
+    }
+
+    fn pending_block_has_operations(&self) -> bool {
+        !self.pending_block.success_operations.is_empty()
+            || !self.pending_block.failed_txs.is_empty()
+    }
+
+    /// Applies the block sealing timings changed by the configuration reload.
+    fn reload_timings(&mut self) {
+        let generation = config_generation();
+        if generation == self.config_generation {
+            return;
+        }
+        self.config_generation = generation;
+
+        let timings = MiniblockTimings::from_env();
+        self.max_miniblock_iterations = timings.max_miniblock_iterations;
+        self.fast_miniblock_iterations = timings.fast_miniblock_iterations;
+        self.block_commit_deadline = timings.block_commit_deadline;
+        tracing::info!("Block sealing timings reloaded: {:?}", timings);
+    }
+
     async fn execute_proposed_block(&mut self, proposed_block: ProposedBlock) {
         let start = Instant::now();
         let mut executed_ops = Vec::new();
@@ -557,7 +741,7 @@ impl ZkSyncStateKeeper {
                     executed_ops.push(exec_op);
                 }
                 Err(priority_op) => {
-                    self.seal_pending_block().await;
+                    self.seal_pending_block(SealReason::BlockFull).await;
 
                     priority_op_queue.push_front(priority_op);
                 }
@@ -576,7 +760,7 @@ impl ZkSyncStateKeeper {
                             // We could not execute the tx due to either of block size limit
                             // or the withdraw operations limit, so we seal this block and
                             // the last transaction will go to the next block instead.
-                            self.seal_pending_block().await;
+                            self.seal_pending_block(SealReason::BlockFull).await;
 
                             tx_queue.push_front(variant);
                         }
@@ -591,7 +775,7 @@ impl ZkSyncStateKeeper {
                             // We could not execute the batch tx due to either of block size limit
                             // or the withdraw operations limit, so we seal this block and
                             // the last transaction will go to the next block instead.
-                            self.seal_pending_block().await;
+                            self.seal_pending_block(SealReason::BlockFull).await;
 
                             tx_queue.push_front(variant);
                         }
@@ -602,18 +786,36 @@ impl ZkSyncStateKeeper {
 
         if !self.pending_block.success_operations.is_empty() {
             self.pending_block.pending_block_iteration += 1;
+            self.pending_block
+                .first_op_executed_at
+                .get_or_insert_with(Instant::now);
         }
 
+        // Block should not wait for the transactions for too long, even if it's not full yet.
+        let commit_deadline_reached = self
+            .pending_block
+            .first_op_executed_at
+            .map_or(false, |executed_at| {
+                executed_at.elapsed() >= self.block_commit_deadline
+            });
+
         // If pending block contains withdrawals we seal it faster
         let max_miniblock_iterations = if self.pending_block.fast_processing_required {
             self.fast_miniblock_iterations
         } else {
             self.max_miniblock_iterations
         };
-        if self.pending_block.chunks_left == 0
-            || self.pending_block.pending_block_iteration > max_miniblock_iterations
-        {
-            self.seal_pending_block().await;
+        let seal_reason = if self.pending_block.chunks_left == 0 {
+            Some(SealReason::BlockFull)
+        } else if self.pending_block.pending_block_iteration > max_miniblock_iterations {
+            Some(SealReason::MiniblockIterations)
+        } else if commit_deadline_reached {
+            Some(SealReason::CommitDeadline)
+        } else {
+            None
+        };
+        if let Some(seal_reason) = seal_reason {
+            self.seal_pending_block(seal_reason).await;
         } else {
             // We've already incremented the pending block iteration, so this iteration will count towards
             // reaching the block commitment timeout.
@@ -623,15 +825,42 @@ impl ZkSyncStateKeeper {
             }
         }
 
+        // Throughput is measured as the rate of this counter.
+        metrics::counter!("state_keeper.executed_ops", executed_ops.len() as u64);
+        self.report_pending_block_occupancy();
         metrics::histogram!("state_keeper.execute_proposed_block", start.elapsed());
     }
 
+    /// Reports the amount of operations and used chunks in the current pending block.
+    fn report_pending_block_occupancy(&self) {
+        let max_block_size = *self
+            .available_block_chunk_sizes
+            .last()
+            .expect("failed to get max block size");
+        let chunks_used = max_block_size.saturating_sub(self.pending_block.chunks_left);
+
+        metrics::gauge!("state_keeper.pending_block_chunks_used", chunks_used as f64);
+        metrics::gauge!(
+            "state_keeper.pending_block_occupancy",
+            chunks_used as f64 / max_block_size as f64
+        );
+        metrics::gauge!(
+            "state_keeper.pending_block_ops",
+            (self.pending_block.success_operations.len() + self.pending_block.failed_txs.len())
+                as f64
+        );
+    }
+
     // Err if there is no space in current block
     fn apply_priority_op(
         &mut self,
         priority_op: PriorityOp,
     ) -> Result<ExecutedOperations, PriorityOp> {
         let start = Instant::now();
+        let op_type = match &priority_op.data {
+            ZkSyncPriorityOp::Deposit(_) => "deposit",
+            ZkSyncPriorityOp::FullExit(_) => "full_exit",
+        };
         let chunks_needed = priority_op.data.chunks();
         if self.pending_block.chunks_left < chunks_needed {
             return Err(priority_op);
@@ -678,7 +907,7 @@ impl ZkSyncStateKeeper {
             .push(exec_result.clone());
         self.current_unprocessed_priority_op += 1;
 
-        metrics::histogram!("state_keeper.apply_priority_op", start.elapsed());
+        metrics::histogram!("state_keeper.apply_priority_op", start.elapsed(), "type" => op_type);
         Ok(exec_result)
     }
 
@@ -734,6 +963,13 @@ impl ZkSyncStateKeeper {
         let mut executed_operations = Vec::new();
 
         for (tx, tx_updates) in txs.iter().zip(all_updates) {
+            let span = tracing::info_span!(
+                "tx",
+                tx_hash = %tx.hash().to_string(),
+                block = self.state.block_number,
+                batch_id
+            );
+            let _guard = span.enter();
             match tx_updates {
                 Ok(OpSuccess {
                     fee,
@@ -753,6 +989,7 @@ impl ZkSyncStateKeeper {
                         success: true,
                         op: Some(executed_op),
                         fail_reason: None,
+                        fail_trace: None,
                         block_index: Some(block_index),
                         created_at: chrono::Utc::now(),
                         batch_id: Some(batch_id),
@@ -761,14 +998,16 @@ impl ZkSyncStateKeeper {
                         .success_operations
                         .push(exec_result.clone());
                     executed_operations.push(exec_result);
+                    tracing::info!(block_index, "Transaction executed");
                 }
                 Err(e) => {
-                    log::warn!("Failed to execute transaction: {:?}, {}", tx, e);
+                    tracing::warn!(error = %e, "Transaction failed");
                     let failed_tx = ExecutedTx {
                         signed_tx: tx.clone(),
                         success: false,
                         op: None,
                         fail_reason: Some(e.to_string()),
+                        fail_trace: self.trace_tx_failure(&e),
                         block_index: None,
                         created_at: chrono::Utc::now(),
                         batch_id: Some(batch_id),
@@ -785,6 +1024,12 @@ impl ZkSyncStateKeeper {
     }
 
     fn apply_tx(&mut self, tx: &SignedZkSyncTx) -> Result<ExecutedOperations, ()> {
+        let span = tracing::info_span!(
+            "tx",
+            tx_hash = %tx.hash().to_string(),
+            block = self.state.block_number
+        );
+        let _guard = span.enter();
         let start = Instant::now();
         let chunks_needed = self.state.chunks_for_tx(&tx);
 
@@ -849,6 +1094,7 @@ impl ZkSyncStateKeeper {
                     success: true,
                     op: Some(executed_op),
                     fail_reason: None,
+                    fail_trace: None,
                     block_index: Some(block_index),
                     created_at: chrono::Utc::now(),
                     batch_id: None,
@@ -856,15 +1102,17 @@ impl ZkSyncStateKeeper {
                 self.pending_block
                     .success_operations
                     .push(exec_result.clone());
+                tracing::info!(block_index, "Transaction executed");
                 exec_result
             }
             Err(e) => {
-                log::warn!("Failed to execute transaction: {:?}, {}", tx, e);
+                tracing::warn!(error = %e, "Transaction failed");
                 let failed_tx = ExecutedTx {
                     signed_tx: tx.clone(),
                     success: false,
                     op: None,
                     fail_reason: Some(e.to_string()),
+                    fail_trace: self.trace_tx_failure(&e),
                     block_index: None,
                     created_at: chrono::Utc::now(),
                     batch_id: None,
@@ -874,12 +1122,25 @@ impl ZkSyncStateKeeper {
             }
         };
 
-        metrics::histogram!("state_keeper.apply_tx", start.elapsed());
+        metrics::histogram!("state_keeper.apply_tx", start.elapsed(), "type" => tx_type_label(&tx.tx));
         Ok(exec_result)
     }
 
+    /// Returns the details of the transaction failure, if the transaction traces are enabled.
+    fn trace_tx_failure(&self, error: &anyhow::Error) -> Option<TxFailureTrace> {
+        if !self.tx_trace_enabled {
+            return None;
+        }
+        Some(zksync_state::error::trace_tx_failure(error))
+    }
+
     /// Finalizes the pending block, transforming it into a full block.
-    async fn seal_pending_block(&mut self) {
+    #[tracing::instrument(
+        name = "seal_block",
+        skip(self, reason),
+        fields(block = self.state.block_number, reason = reason.as_str())
+    )]
+    async fn seal_pending_block(&mut self, reason: SealReason) {
         let start = Instant::now();
         let mut pending_block = std::mem::replace(
             &mut self.pending_block,
@@ -951,20 +1212,21 @@ impl ZkSyncStateKeeper {
         pending_block.stored_account_updates = pending_block.account_updates.len();
         self.state.block_number += 1;
 
-        log::info!(
-            "Creating full block: {}, operations: {}, chunks_left: {}, miniblock iterations: {}",
-            block_commit_request.block.block_number,
-            block_commit_request.block.block_transactions.len(),
-            pending_block.chunks_left,
-            pending_block.pending_block_iteration
+        tracing::info!(
+            operations = block_commit_request.block.block_transactions.len(),
+            chunks_left = pending_block.chunks_left,
+            miniblock_iterations = pending_block.pending_block_iteration,
+            "Creating full block"
         );
 
         let commit_request = CommitRequest::Block((block_commit_request, applied_updates_request));
-        self.tx_for_commitments
-            .send(commit_request)
-            .await
-            .expect("committer receiver dropped");
+        self.send_commit_request(commit_request).await;
 
+        metrics::counter!("state_keeper.sealed_blocks", 1, "reason" => reason.as_str());
+        metrics::gauge!(
+            "state_keeper.last_sealed_block",
+            (self.state.block_number - 1) as f64
+        );
         metrics::histogram!("state_keeper.seal_pending_block", start.elapsed());
     }
 
@@ -1007,7 +1269,7 @@ impl ZkSyncStateKeeper {
         };
         self.pending_block.stored_account_updates = self.pending_block.account_updates.len();
 
-        log::trace!(
+        tracing::trace!(
             "Persisting mini block: {}, operations: {}, failed_txs: {}, chunks_left: {}, miniblock iterations: {}",
             pending_block.number,
             pending_block.success_operations.len(),
@@ -1017,11 +1279,31 @@ impl ZkSyncStateKeeper {
         );
 
         let commit_request = CommitRequest::PendingBlock((pending_block, applied_updates_request));
+        self.send_commit_request(commit_request).await;
+        metrics::histogram!("state_keeper.store_pending_block", start.elapsed());
+    }
+
+    /// Sends the request to the committer. Once the committer queue is full, the state keeper
+    /// neither seals blocks nor executes new operations until the committer catches up.
+    async fn send_commit_request(&mut self, request: CommitRequest) {
+        self.commit_queue_depth.increment();
+        let request = match self.tx_for_commitments.try_send(request) {
+            Ok(()) => return,
+            Err(err) if err.is_full() => err.into_inner(),
+            Err(_) => panic!("committer receiver dropped"),
+        };
+
+        tracing::warn!(
+            "Committer queue is full ({} requests), waiting for the committer to catch up",
+            self.commit_queue_depth.get()
+        );
+        metrics::counter!("state_keeper.commit_queue_full", 1);
+        let start = Instant::now();
         self.tx_for_commitments
-            .send(commit_request)
+            .send(request)
             .await
             .expect("committer receiver dropped");
-        metrics::histogram!("state_keeper.store_pending_block", start.elapsed());
+        metrics::histogram!("state_keeper.commit_queue_wait", start.elapsed());
     }
 
     fn account(&self, address: &Address) -> Option<(AccountId, Account)> {
@@ -1034,5 +1316,6 @@ pub fn start_state_keeper(
     sk: ZkSyncStateKeeper,
     pending_block: Option<SendablePendingBlock>,
 ) -> JoinHandle<()> {
+    register_metrics("state keeper", METRICS);
     tokio::spawn(sk.run(pending_block))
 }
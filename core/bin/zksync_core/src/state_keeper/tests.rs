@@ -1,7 +1,10 @@
-use super::{CommitRequest, ZkSyncStateInitParams, ZkSyncStateKeeper};
+use super::{
+    CommitRequest, SealReason, StateKeeperRequest, ZkSyncStateInitParams, ZkSyncStateKeeper,
+};
 use crate::mempool::ProposedBlock;
-use futures::{channel::mpsc, stream::StreamExt};
+use futures::{channel::mpsc, stream::StreamExt, SinkExt};
 use num::BigUint;
+use std::time::{Duration, Instant};
 use zksync_crypto::{
     priv_key_from_fs,
     rand::{Rng, SeedableRng, XorShiftRng},
@@ -11,6 +14,11 @@ use zksync_types::{
     mempool::SignedTxVariant, mempool::SignedTxsBatch, tx::PackedEthSignature, AccountId, H160, *,
 };
 
+mod soak;
+
+/// Commit deadline which is never reached during the tests.
+const BLOCK_COMMIT_DEADLINE: Duration = Duration::from_secs(3600);
+
 struct StateKeeperTester {
     state_keeper: ZkSyncStateKeeper,
     response_rx: mpsc::Receiver<CommitRequest>,
@@ -39,10 +47,13 @@ impl StateKeeperTester {
             fee_collector.address,
             request_rx,
             response_tx,
+            Default::default(),
             vec![available_chunk_size],
             max_iterations,
             fast_iterations,
+            BLOCK_COMMIT_DEADLINE,
             number_of_withdrawals,
+            true,
         );
 
         Self {
@@ -241,10 +252,13 @@ fn test_create_incorrect_state_keeper() {
         fee_collector.address,
         request_rx,
         response_tx,
+        Default::default(),
         vec![1, 2, 2], // `available_block_chunk_sizes` must be strictly increasing.
         MAX_ITERATIONS,
         FAST_ITERATIONS,
+        BLOCK_COMMIT_DEADLINE,
         NUMBER_OF_WITHDRAWALS,
+        false,
     );
 }
 
@@ -339,6 +353,15 @@ mod apply_tx {
         assert!(!pending_block.failed_txs.is_empty());
         assert!(pending_block.collected_fees.is_empty());
         assert_eq!(pending_block.withdrawals_amount, 1);
+
+        let trace = pending_block.failed_txs[0]
+            .fail_trace
+            .clone()
+            .expect("failure must be traced");
+        assert_eq!(trace.account_id, Some(1));
+        let balance = trace.balance.expect("balance must be traced");
+        assert_eq!(balance.available, BigUint::from(100u32));
+        assert_eq!(balance.required, BigUint::from(146u32));
     }
 
     /// Checks if processing withdrawal fails because of
@@ -395,7 +418,10 @@ async fn seal_pending_block() {
     assert!(tester.state_keeper.apply_priority_op(deposit).is_ok());
 
     let old_updates_len = tester.state_keeper.pending_block.account_updates.len();
-    tester.state_keeper.seal_pending_block().await;
+    tester
+        .state_keeper
+        .seal_pending_block(SealReason::Requested)
+        .await;
 
     assert!(tester.state_keeper.pending_block.failed_txs.is_empty());
     assert!(tester
@@ -431,6 +457,56 @@ async fn seal_pending_block() {
     }
 }
 
+/// Checks that the pending block is sealed before the maintenance,
+/// and no empty block is created if there are no pending operations.
+#[tokio::test]
+async fn seal_for_maintenance() {
+    let mut tester = StateKeeperTester::new(20, 3, 3, 2);
+
+    tester.state_keeper.seal_for_maintenance().await;
+    assert!(tester.response_rx.try_next().is_err());
+
+    let withdraw = create_account_and_withdrawal(&mut tester, 0, 1, 200u32, 145u32);
+    assert!(tester.state_keeper.apply_tx(&withdraw).is_ok());
+    let block_number = tester.state_keeper.state.block_number;
+    tester.state_keeper.seal_for_maintenance().await;
+
+    assert!(!tester.state_keeper.pending_block_has_operations());
+    if let Some(CommitRequest::Block((block, _))) = tester.response_rx.next().await {
+        assert_eq!(block.block.block_number, block_number);
+        assert_eq!(block.block.block_transactions.len(), 1);
+    } else {
+        panic!("Block is not received!");
+    }
+}
+
+/// Checks that on shutdown the state keeper seals the pending block and stops,
+/// closing the channel to the committer.
+#[tokio::test]
+async fn seal_on_shutdown() {
+    let mut tester = StateKeeperTester::new(20, 3, 3, 2);
+    let (mut request_tx, request_rx) = mpsc::channel(1);
+    tester.state_keeper.rx_for_blocks = request_rx;
+
+    let withdraw = create_account_and_withdrawal(&mut tester, 0, 1, 200u32, 145u32);
+    assert!(tester.state_keeper.apply_tx(&withdraw).is_ok());
+    let block_number = tester.state_keeper.state.block_number;
+
+    request_tx
+        .send(StateKeeperRequest::Shutdown)
+        .await
+        .expect("state keeper receiver dropped");
+    tester.state_keeper.run(None).await;
+
+    if let Some(CommitRequest::Block((block, _))) = tester.response_rx.next().await {
+        assert_eq!(block.block.block_number, block_number);
+        assert_eq!(block.block.block_transactions.len(), 1);
+    } else {
+        panic!("Block is not received!");
+    }
+    assert!(tester.response_rx.next().await.is_none());
+}
+
 /// Checks if block storing is done correctly by storing a block
 /// with 1 priority_op, 1 succeeded tx, 1 failed tx
 #[tokio::test]
@@ -476,6 +552,84 @@ async fn store_pending_block() {
     }
 }
 
+/// Checks that the state keeper waits for the committer once the commit queue is full.
+#[tokio::test]
+async fn commit_queue_backpressure() {
+    let mut tester = StateKeeperTester::new(20, 3, 3, 2);
+    // Channel without a buffer holds a single request of its only sender.
+    let (response_tx, mut response_rx) = mpsc::channel(0);
+    tester.state_keeper.tx_for_commitments = response_tx;
+
+    tester.state_keeper.store_pending_block().await;
+    assert_eq!(tester.state_keeper.commit_queue_depth.get(), 1);
+
+    // The next request is sent only once the committer takes the queued one.
+    let committer_delay = Duration::from_millis(100);
+    let start = Instant::now();
+    let store = async {
+        tester.state_keeper.store_pending_block().await;
+        Instant::now()
+    };
+    let commit = async {
+        tokio::time::delay_for(committer_delay).await;
+        response_rx.next().await
+    };
+    let (stored_at, received) = tokio::join!(store, commit);
+
+    assert!(stored_at.duration_since(start) >= committer_delay);
+    assert!(matches!(received, Some(CommitRequest::PendingBlock(_))));
+    assert_eq!(tester.state_keeper.commit_queue_depth.get(), 2);
+}
+
+/// Checks that the state keeper resumes the stored pending block after restart.
+#[tokio::test]
+async fn restore_pending_block() {
+    let mut tester = StateKeeperTester::new(20, 5, 5, 2);
+    let transfer = create_account_and_transfer(&mut tester, 0, 1, 200u32, 100u32);
+    let account = tester.state_keeper.state.get_account(1).unwrap();
+    let proposed_block = ProposedBlock {
+        txs: vec![SignedTxVariant::Tx(transfer)],
+        priority_ops: Vec::new(),
+    };
+    tester
+        .state_keeper
+        .execute_proposed_block(proposed_block)
+        .await;
+
+    let pending_block = match tester.response_rx.next().await {
+        Some(CommitRequest::PendingBlock((block, _))) => block,
+        _ => panic!("Pending block is not received!"),
+    };
+
+    let mut restored = StateKeeperTester::new(20, 5, 5, 2);
+    restored.state_keeper.state.insert_account(1, account);
+    restored
+        .state_keeper
+        .initialize(Some(pending_block.clone()))
+        .await;
+
+    let restored_block = &restored.state_keeper.pending_block;
+    assert_eq!(restored_block.success_operations.len(), 1);
+    assert_eq!(restored_block.chunks_left, pending_block.chunks_left);
+    assert_eq!(
+        restored_block.pending_block_iteration,
+        pending_block.pending_block_iteration
+    );
+    assert_eq!(restored_block.timestamp, pending_block.timestamp);
+
+    // Restored operations must not be persisted again.
+    restored.state_keeper.store_pending_block().await;
+    if let Some(CommitRequest::PendingBlock((block, _))) = restored.response_rx.next().await {
+        assert!(block.success_operations.is_empty());
+        assert_eq!(
+            block.pending_block_iteration,
+            pending_block.pending_block_iteration
+        );
+    } else {
+        panic!("Pending block is not received!");
+    }
+}
+
 mod execute_proposed_block {
     use super::*;
 
@@ -730,6 +884,50 @@ mod execute_proposed_block {
         ));
     }
 
+    /// Checks that non-empty block is sealed once the commit deadline is reached,
+    /// while the empty one keeps waiting for transactions.
+    #[tokio::test]
+    async fn commit_deadline() {
+        let mut tester = StateKeeperTester::new(20, 100, 100, 2);
+        tester.state_keeper.block_commit_deadline = Duration::from_secs(0);
+
+        // Empty pending block is not sealed.
+        tester
+            .state_keeper
+            .execute_proposed_block(ProposedBlock::default())
+            .await;
+        assert!(tester
+            .state_keeper
+            .pending_block
+            .first_op_executed_at
+            .is_none());
+        assert!(tester.response_rx.try_next().is_err());
+
+        tester.state_keeper.block_commit_deadline = BLOCK_COMMIT_DEADLINE;
+        apply_single_transfer(&mut tester).await;
+        assert!(matches!(
+            tester.response_rx.next().await,
+            Some(CommitRequest::PendingBlock(_))
+        ));
+
+        // Deadline is checked even if there are no new transactions.
+        tester.state_keeper.block_commit_deadline = Duration::from_secs(0);
+        tester
+            .state_keeper
+            .execute_proposed_block(ProposedBlock::default())
+            .await;
+        if let Some(CommitRequest::Block((block, _))) = tester.response_rx.next().await {
+            assert_eq!(block.block.block_transactions.len(), 1);
+        } else {
+            panic!("Block is not received!");
+        }
+        assert!(tester
+            .state_keeper
+            .pending_block
+            .first_op_executed_at
+            .is_none());
+    }
+
     /// Checks the following things:
     /// 1. if proposed block is empty, no pending block is yielded from the state keeper.
     /// 2. if there were no successful operations in the block, pending block iteration is not incremented after empty or rejected-only updates.
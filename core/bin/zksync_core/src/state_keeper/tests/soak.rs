@@ -0,0 +1,627 @@
+//! Randomized tests for the state transitions performed by the state keeper.
+//!
+//! The harness generates random sequences of valid and invalid operations, executes them
+//! through the state keeper and compares the outcome with the reference implementation
+//! (`Reference`) that applies the same operations to a plain map of accounts.
+//! After every proposed block the following invariants are checked:
+//!
+//! - state of every account matches the reference;
+//! - total amount of every token equals the deposited amount minus the withdrawn one;
+//! - balances never go below zero (balances are unsigned, so the underflow would either panic
+//!   in the state or break the conservation check).
+//!
+//! Once a block is sealed, its root hash is also compared with the root hash of the reference state.
+//!
+//! Sequences are generated from the seed, so any failure can be reproduced by the seed
+//! printed in the test output. The long-running version of the test is ignored by default
+//! and configured through the environment:
+//!
+//! ```text
+//! SOAK_ITERATIONS=100000 SOAK_SEED=42 cargo test --release -p zksync_core soak -- --ignored --nocapture
+//! ```
+
+// Built-in deps
+use std::collections::{BTreeMap, HashMap};
+// External uses
+use num::ToPrimitive;
+// Workspace uses
+use zksync_crypto::Fr;
+use zksync_state::state::ZkSyncState;
+use zksync_types::{
+    block::ExecutedOperations,
+    helpers::{closest_packable_fee_amount, closest_packable_token_amount},
+};
+// Local uses
+use super::*;
+
+/// ID of the fee account created by the `StateKeeperTester`.
+const FEE_ACCOUNT_ID: AccountId = 0;
+
+/// Amount of tokens used in the generated operations.
+const TOKENS: TokenId = 3;
+/// Amount of accounts with the signing keys created before the test.
+const INITIAL_ACCOUNTS: u32 = 8;
+/// Maximum initial balance of the account in every token.
+const MAX_INITIAL_BALANCE: u64 = 10_000;
+/// Maximum amount of the single deposit.
+const MAX_DEPOSIT: u64 = 1_000;
+/// Maximum fee of the single transaction.
+const MAX_FEE: u64 = 5;
+/// Block size, big enough to fit any single operation.
+const BLOCK_CHUNK_SIZE: usize = 50;
+
+/// Reference implementation of the state transitions.
+///
+/// Only the balances, nonces and account creation are modelled, and the fees are credited
+/// to the fee account immediately rather than when the block is sealed.
+#[derive(Debug, Clone, Default)]
+struct Reference {
+    accounts: BTreeMap<AccountId, Account>,
+    account_ids: HashMap<Address, AccountId>,
+    /// Deposited amount minus withdrawn amount for every token.
+    supply: HashMap<TokenId, BigUint>,
+}
+
+impl Reference {
+    fn insert_account(&mut self, account_id: AccountId, account: Account) {
+        self.account_ids.insert(account.address, account_id);
+        self.accounts.insert(account_id, account);
+    }
+
+    fn account(&self, account_id: AccountId) -> &Account {
+        &self.accounts[&account_id]
+    }
+
+    fn balance(&self, account_id: AccountId, token: TokenId) -> BigUint {
+        self.account(account_id).get_balance(token)
+    }
+
+    fn supply(&self, token: TokenId) -> BigUint {
+        self.supply.get(&token).cloned().unwrap_or_default()
+    }
+
+    /// Returns the ID of the account with the given address, creating the account if it doesn't exist.
+    fn get_or_create_account(&mut self, address: Address) -> AccountId {
+        if let Some(account_id) = self.account_ids.get(&address) {
+            return *account_id;
+        }
+
+        let account_id = self.accounts.len() as AccountId;
+        self.insert_account(account_id, Account::default_with_address(&address));
+        account_id
+    }
+
+    fn root_hash(&self) -> Fr {
+        let accounts = self
+            .accounts
+            .iter()
+            .map(|(id, account)| (*id, account.clone()))
+            .collect();
+        ZkSyncState::from_acc_map(accounts, 0).root_hash()
+    }
+
+    /// Checks that the transaction can be executed by the account and charges the fee.
+    fn charge(
+        &mut self,
+        account_id: AccountId,
+        signer: Option<PubKeyHash>,
+        nonce: Nonce,
+        token: TokenId,
+        amount: &BigUint,
+        fee: &BigUint,
+    ) -> bool {
+        let account = match self.accounts.get_mut(&account_id) {
+            Some(account) => account,
+            None => return false,
+        };
+        if account.pub_key_hash == PubKeyHash::default()
+            || signer != Some(account.pub_key_hash)
+            || account.nonce != nonce
+            || account.get_balance(token) < amount + fee
+        {
+            return false;
+        }
+
+        account.sub_balance(token, &(amount + fee));
+        account.nonce += 1;
+        self.accounts
+            .get_mut(&FEE_ACCOUNT_ID)
+            .unwrap()
+            .add_balance(token, fee);
+        true
+    }
+
+    /// Applies the transaction and returns whether it succeeded.
+    fn apply_tx(&mut self, tx: &ZkSyncTx) -> bool {
+        match tx {
+            ZkSyncTx::Transfer(tx) => {
+                if self.account_ids.get(&tx.from) != Some(&tx.account_id) {
+                    return false;
+                }
+                if !self.charge(
+                    tx.account_id,
+                    tx.verify_signature(),
+                    tx.nonce,
+                    tx.token,
+                    &tx.amount,
+                    &tx.fee,
+                ) {
+                    return false;
+                }
+                let to = self.get_or_create_account(tx.to);
+                self.accounts
+                    .get_mut(&to)
+                    .unwrap()
+                    .add_balance(tx.token, &tx.amount);
+                true
+            }
+            ZkSyncTx::Withdraw(tx) => {
+                if self.account_ids.get(&tx.from) != Some(&tx.account_id) {
+                    return false;
+                }
+                if !self.charge(
+                    tx.account_id,
+                    tx.verify_signature(),
+                    tx.nonce,
+                    tx.token,
+                    &tx.amount,
+                    &tx.fee,
+                ) {
+                    return false;
+                }
+                let supply = self.supply.entry(tx.token).or_default();
+                *supply = &*supply - &tx.amount;
+                true
+            }
+            _ => unreachable!("Only transfers and withdrawals are generated"),
+        }
+    }
+
+    /// Applies the batch and returns whether it succeeded. Failed batch leaves the state untouched.
+    fn apply_batch(&mut self, txs: &[SignedZkSyncTx]) -> bool {
+        let mut updated = self.clone();
+        if txs.iter().all(|tx| updated.apply_tx(&tx.tx)) {
+            *self = updated;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn apply_deposit(&mut self, deposit: &Deposit) {
+        let to = self.get_or_create_account(deposit.to);
+        self.accounts
+            .get_mut(&to)
+            .unwrap()
+            .add_balance(deposit.token, &deposit.amount);
+        *self.supply.entry(deposit.token).or_default() += &deposit.amount;
+    }
+}
+
+/// Amount of the operations of each kind.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct OperationsCount {
+    successful_txs: usize,
+    failed_txs: usize,
+    priority_ops: usize,
+}
+
+/// Generator of the random operations.
+struct Generator {
+    rng: XorShiftRng,
+    keys: HashMap<AccountId, PrivateKey>,
+    next_serial_id: u64,
+}
+
+impl Generator {
+    fn new(seed: u32) -> Self {
+        Self {
+            rng: XorShiftRng::from_seed([
+                seed,
+                seed.wrapping_add(1),
+                seed.wrapping_add(2),
+                seed.wrapping_add(3),
+            ]),
+            keys: HashMap::new(),
+            next_serial_id: 0,
+        }
+    }
+
+    fn random_address(&mut self) -> Address {
+        // Zero address is not allowed as a recipient.
+        H160::from_low_u64_be(self.rng.gen::<u64>() | 1)
+    }
+
+    fn random_account_id(&mut self, reference: &Reference) -> AccountId {
+        self.rng.gen_range(0, reference.accounts.len() as AccountId)
+    }
+
+    /// Creates an account with the signing key and random balances.
+    fn random_account(&mut self, account_id: AccountId) -> Account {
+        let sk = priv_key_from_fs(self.rng.gen());
+        let mut account = Account::default_with_address(&self.random_address());
+        account.pub_key_hash = PubKeyHash::from_privkey(&sk);
+        for token in 0..TOKENS {
+            let balance = self.rng.gen_range(0, MAX_INITIAL_BALANCE);
+            account.set_balance(token, BigUint::from(balance));
+        }
+        self.keys.insert(account_id, sk);
+        account
+    }
+
+    /// Generates a transfer or a withdrawal, which is invalid with some probability.
+    fn random_tx(&mut self, reference: &Reference) -> SignedZkSyncTx {
+        let account_id = self.random_account_id(reference);
+        let account = reference.account(account_id);
+        let token = self.rng.gen_range(0, TOKENS);
+
+        let balance = account.get_balance(token).to_u64().unwrap();
+        let amount = if self.rng.gen_weighted_bool(5) {
+            // Not enough balance.
+            balance + self.rng.gen_range(1, 100)
+        } else {
+            self.rng.gen_range(0, balance / 2 + 1)
+        };
+        let amount = closest_packable_token_amount(&BigUint::from(amount));
+        let fee = closest_packable_fee_amount(&BigUint::from(self.rng.gen_range(0, MAX_FEE + 1)));
+
+        let nonce = if self.rng.gen_weighted_bool(10) {
+            // Nonce mismatch.
+            if self.rng.gen() {
+                account.nonce + 1
+            } else {
+                account.nonce.wrapping_sub(1)
+            }
+        } else {
+            account.nonce
+        };
+
+        // Accounts without the key and the incorrect signatures are signed by the random key.
+        let random_sk;
+        let sk = match self.keys.get(&account_id) {
+            Some(sk) if !self.rng.gen_weighted_bool(10) => sk,
+            _ => {
+                random_sk = priv_key_from_fs(self.rng.gen());
+                &random_sk
+            }
+        };
+
+        let tx = if self.rng.gen_weighted_bool(4) {
+            let withdraw = Withdraw::new_signed(
+                account_id,
+                account.address,
+                account.address,
+                token,
+                amount,
+                fee,
+                nonce,
+                sk,
+            )
+            .unwrap();
+            ZkSyncTx::Withdraw(Box::new(withdraw))
+        } else {
+            let to = if self.rng.gen_weighted_bool(10) {
+                self.random_address()
+            } else {
+                let to = self.random_account_id(reference);
+                reference.account(to).address
+            };
+            let transfer = Transfer::new_signed(
+                account_id,
+                account.address,
+                to,
+                token,
+                amount,
+                fee,
+                nonce,
+                sk,
+            )
+            .unwrap();
+            ZkSyncTx::Transfer(Box::new(transfer))
+        };
+
+        SignedZkSyncTx {
+            tx,
+            eth_sign_data: None,
+        }
+    }
+
+    /// Generates a deposit either to the existing or to the new account.
+    fn random_deposit(&mut self, reference: &Reference) -> PriorityOp {
+        let to = if self.rng.gen_weighted_bool(5) {
+            self.random_address()
+        } else {
+            let to = self.random_account_id(reference);
+            reference.account(to).address
+        };
+        let deposit = Deposit {
+            from: self.random_address(),
+            to,
+            amount: BigUint::from(self.rng.gen_range(1, MAX_DEPOSIT)),
+            token: self.rng.gen_range(0, TOKENS),
+        };
+
+        let serial_id = self.next_serial_id;
+        self.next_serial_id += 1;
+        PriorityOp {
+            data: ZkSyncPriorityOp::Deposit(deposit),
+            serial_id,
+            deadline_block: 0,
+            eth_hash: vec![],
+            eth_block: 0,
+        }
+    }
+}
+
+struct SoakTester {
+    tester: StateKeeperTester,
+    generator: Generator,
+    reference: Reference,
+    /// Operations that are expected to be included into the blocks by the reference.
+    expected: OperationsCount,
+    /// Operations that are included into the sealed blocks.
+    sealed: OperationsCount,
+    last_sealed_root_hash: Option<Fr>,
+}
+
+impl SoakTester {
+    fn new(seed: u32) -> Self {
+        let mut tester = StateKeeperTester::new(BLOCK_CHUNK_SIZE, 4, 2, 4);
+        let mut generator = Generator::new(seed);
+        let mut reference = Reference::default();
+
+        let fee_account = tester
+            .state_keeper
+            .state
+            .get_account(tester.fee_collector)
+            .expect("fee account doesn't exist");
+        reference.insert_account(FEE_ACCOUNT_ID, fee_account);
+
+        for account_id in 1..=INITIAL_ACCOUNTS {
+            let account = generator.random_account(account_id);
+            for token in 0..TOKENS {
+                *reference.supply.entry(token).or_default() += account.get_balance(token);
+            }
+
+            tester
+                .state_keeper
+                .state
+                .insert_account(account_id, account.clone());
+            reference.insert_account(account_id, account);
+        }
+
+        Self {
+            tester,
+            generator,
+            reference,
+            expected: OperationsCount::default(),
+            sealed: OperationsCount::default(),
+            last_sealed_root_hash: None,
+        }
+    }
+
+    /// Generates a proposed block and applies it to the reference.
+    ///
+    /// Transactions are generated one by one against the updated reference state,
+    /// so the most of them are valid.
+    fn random_proposed_block(&mut self) -> ProposedBlock {
+        let mut priority_ops = Vec::new();
+        for _ in 0..self.generator.rng.gen_range(0, 3) {
+            let priority_op = self.generator.random_deposit(&self.reference);
+            if let ZkSyncPriorityOp::Deposit(deposit) = &priority_op.data {
+                self.reference.apply_deposit(deposit);
+            }
+            self.expected.priority_ops += 1;
+            priority_ops.push(priority_op);
+        }
+
+        let mut txs = Vec::new();
+        for batch_id in 0..self.generator.rng.gen_range(0i64, 8) {
+            if self.generator.rng.gen_weighted_bool(10) {
+                // Next transactions of the batch are generated against the state changed by the previous ones.
+                let mut batch_state = self.reference.clone();
+                let mut batch = Vec::new();
+                for _ in 0..self.generator.rng.gen_range(2, 5) {
+                    let tx = self.generator.random_tx(&batch_state);
+                    batch_state.apply_tx(&tx.tx);
+                    batch.push(tx);
+                }
+
+                if self.reference.apply_batch(&batch) {
+                    self.expected.successful_txs += batch.len();
+                } else {
+                    self.expected.failed_txs += batch.len();
+                }
+                txs.push(SignedTxVariant::Batch(SignedTxsBatch {
+                    txs: batch,
+                    batch_id,
+                    eth_signatures: Vec::new(),
+                }));
+            } else {
+                let tx = self.generator.random_tx(&self.reference);
+                if self.reference.apply_tx(&tx.tx) {
+                    self.expected.successful_txs += 1;
+                } else {
+                    self.expected.failed_txs += 1;
+                }
+                txs.push(SignedTxVariant::Tx(tx));
+            }
+        }
+
+        ProposedBlock { txs, priority_ops }
+    }
+
+    /// Receives the blocks sealed by the state keeper.
+    fn receive_sealed_blocks(&mut self) {
+        while let Ok(Some(request)) = self.tester.response_rx.try_next() {
+            let block = match request {
+                CommitRequest::Block((request, _)) => request.block,
+                CommitRequest::PendingBlock(_) => continue,
+            };
+
+            for op in &block.block_transactions {
+                match op {
+                    ExecutedOperations::Tx(tx) if tx.success => self.sealed.successful_txs += 1,
+                    ExecutedOperations::Tx(_) => self.sealed.failed_txs += 1,
+                    ExecutedOperations::PriorityOp(_) => self.sealed.priority_ops += 1,
+                }
+            }
+            self.last_sealed_root_hash = Some(block.new_root_hash);
+        }
+    }
+
+    /// Checks that the state keeper state matches the reference.
+    fn check_state(&self, seed: u32, iteration: usize) {
+        let state = &self.tester.state_keeper.state;
+        let pending_fees = &self.tester.state_keeper.pending_block.collected_fees;
+
+        assert_eq!(
+            state.get_accounts().len(),
+            self.reference.accounts.len(),
+            "Seed {}, iteration {}: amount of accounts doesn't match the reference",
+            seed,
+            iteration
+        );
+
+        for token in 0..TOKENS {
+            let fees = pending_fees
+                .iter()
+                .filter(|fee| fee.token == token)
+                .fold(BigUint::from(0u32), |sum, fee| sum + &fee.amount);
+            let balances = state
+                .get_accounts()
+                .into_iter()
+                .fold(BigUint::from(0u32), |sum, (_, account)| {
+                    sum + account.get_balance(token)
+                });
+            assert_eq!(
+                balances + &fees,
+                self.reference.supply(token),
+                "Seed {}, iteration {}: total amount of token {} is not conserved",
+                seed,
+                iteration,
+                token
+            );
+
+            for (account_id, expected) in &self.reference.accounts {
+                let account = state.get_account(*account_id).unwrap_or_else(|| {
+                    panic!(
+                        "Seed {}, iteration {}: account {} doesn't exist",
+                        seed, iteration, account_id
+                    )
+                });
+                assert_eq!(account.address, expected.address);
+                assert_eq!(account.pub_key_hash, expected.pub_key_hash);
+                assert_eq!(
+                    account.nonce, expected.nonce,
+                    "Seed {}, iteration {}: nonce of account {} doesn't match the reference",
+                    seed, iteration, account_id
+                );
+
+                let mut balance = account.get_balance(token);
+                if *account_id == FEE_ACCOUNT_ID {
+                    // Fees are not collected until the block is sealed.
+                    balance += &fees;
+                }
+                assert_eq!(
+                    balance,
+                    self.reference.balance(*account_id, token),
+                    "Seed {}, iteration {}: balance of account {} in token {} doesn't match the reference",
+                    seed,
+                    iteration,
+                    account_id,
+                    token
+                );
+            }
+        }
+    }
+
+    /// Seals the pending block and checks that it matches the reference.
+    async fn seal_and_check(&mut self, seed: u32, iteration: usize) {
+        self.tester
+            .state_keeper
+            .seal_pending_block(SealReason::Requested)
+            .await;
+        self.receive_sealed_blocks();
+        self.check_state(seed, iteration);
+
+        assert_eq!(
+            self.sealed, self.expected,
+            "Seed {}, iteration {}: sealed operations don't match the reference",
+            seed, iteration
+        );
+        let root_hash = self.reference.root_hash();
+        assert_eq!(
+            self.tester.state_keeper.state.root_hash(),
+            root_hash,
+            "Seed {}, iteration {}: state root hash doesn't match the reference",
+            seed,
+            iteration
+        );
+        assert_eq!(
+            self.last_sealed_root_hash,
+            Some(root_hash),
+            "Seed {}, iteration {}: block root hash doesn't match the reference",
+            seed,
+            iteration
+        );
+    }
+}
+
+/// Applies `iterations` random proposed blocks generated from the `seed`, checking the invariants
+/// after each of them.
+async fn run_random_operations(seed: u32, iterations: usize) {
+    let mut tester = SoakTester::new(seed);
+
+    for iteration in 0..iterations {
+        let proposed_block = tester.random_proposed_block();
+        tester
+            .tester
+            .state_keeper
+            .execute_proposed_block(proposed_block)
+            .await;
+        tester.receive_sealed_blocks();
+        tester.check_state(seed, iteration);
+
+        if tester.generator.rng.gen_weighted_bool(10) {
+            tester.seal_and_check(seed, iteration).await;
+        }
+    }
+    tester.seal_and_check(seed, iterations).await;
+
+    println!(
+        "Seed {}: {} proposed blocks, {:?}",
+        seed, iterations, tester.sealed
+    );
+}
+
+/// Reads the numeric parameter of the soak test from the environment.
+fn soak_param<T: std::str::FromStr>(name: &str) -> Option<T> {
+    std::env::var(name).ok().map(|value| {
+        value
+            .parse()
+            .unwrap_or_else(|_| panic!("{} must be a number", name))
+    })
+}
+
+/// Checks the invariants on the short random sequences of operations.
+#[tokio::test]
+async fn random_operations() {
+    for seed in 0..4 {
+        run_random_operations(seed, 50).await;
+    }
+}
+
+/// Long-running version of `random_operations`.
+///
+/// Amount of the proposed blocks and the seed are set by the `SOAK_ITERATIONS`
+/// and `SOAK_SEED` variables. If the seed is not set, the random one is used.
+#[tokio::test]
+#[ignore]
+async fn soak() {
+    let iterations = soak_param("SOAK_ITERATIONS").unwrap_or(10_000);
+    let seed = soak_param("SOAK_SEED").unwrap_or_else(zksync_crypto::rand::random);
+    println!("Running soak test with seed {}", seed);
+
+    run_random_operations(seed, iterations).await;
+}
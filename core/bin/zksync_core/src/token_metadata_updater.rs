@@ -0,0 +1,235 @@
+//! Token metadata updater periodically reads the symbols and decimals of the listed tokens
+//! from their ERC20 contracts and updates the stored symbols, so the renamed tokens don't keep
+//! the symbols they had at the moment of listing.
+//!
+//! Decimals define the token amounts shown to the users and signed by them, so they are never
+//! changed automatically: a mismatch is only reported to be resolved through the admin API.
+//! Symbols used by other tokens are not taken either, since the tokens are looked up by symbol.
+//! Tokens with the metadata set manually through the admin API are not updated.
+
+// Built-in deps
+use std::time::Duration;
+// External deps
+use anyhow::format_err;
+use ethabi::{ParamType, Token as AbiToken};
+use tokio::{task::JoinHandle, time};
+use web3::{
+    transports::Http,
+    types::{Address, Bytes, CallRequest},
+    Web3,
+};
+// Workspace deps
+use zksync_prometheus_exporter::{register_metrics, Metric};
+use zksync_storage::ConnectionPool;
+use zksync_types::{Token, TokenLike};
+
+/// Optional metadata functions of the ERC20 contract.
+const ERC20_METADATA_ABI: &str = r#"[
+    {"type":"function","name":"symbol","inputs":[],"outputs":[{"name":"","type":"string"}],"stateMutability":"view","constant":true},
+    {"type":"function","name":"decimals","inputs":[],"outputs":[{"name":"","type":"uint8"}],"stateMutability":"view","constant":true}
+]"#;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Metrics reported by the token metadata updater.
+const METRICS: &[Metric] = &[
+    Metric::counter(
+        "token_metadata_updater.updated_tokens",
+        "Number of the tokens with the symbol updated from L1",
+    ),
+    Metric::counter(
+        "token_metadata_updater.decimals_mismatches",
+        "Number of the checks finding the token decimals in L1 different from the stored ones",
+    ),
+    Metric::counter(
+        "token_metadata_updater.symbol_collisions",
+        "Number of the checks finding the token symbol in L1 used by another token",
+    ),
+];
+
+/// Symbol and decimals of the token declared by its contract.
+#[derive(Debug, Clone, PartialEq)]
+struct TokenMetadata {
+    symbol: String,
+    decimals: u8,
+}
+
+struct TokenMetadataUpdater {
+    web3: Web3<Http>,
+    abi: ethabi::Contract,
+    db_pool: ConnectionPool,
+}
+
+impl TokenMetadataUpdater {
+    fn new(web3: Web3<Http>, db_pool: ConnectionPool) -> Self {
+        Self {
+            web3,
+            abi: ethabi::Contract::load(ERC20_METADATA_ABI.as_bytes()).expect("erc20 metadata abi"),
+            db_pool,
+        }
+    }
+
+    async fn call(&self, address: Address, function: &str) -> anyhow::Result<Vec<u8>> {
+        let data = self.abi.function(function)?.encode_input(&[])?;
+        let request = CallRequest {
+            from: None,
+            to: Some(address),
+            gas: None,
+            gas_price: None,
+            value: None,
+            data: Some(Bytes(data)),
+        };
+        let output = time::timeout(REQUEST_TIMEOUT, self.web3.eth().call(request, None))
+            .await
+            .map_err(|_| format_err!("Token {} request timeout", function))?
+            .map_err(|err| format_err!("Token {} request failed: {}", function, err))?;
+        Ok(output.0)
+    }
+
+    async fn load_token_metadata(&self, address: Address) -> anyhow::Result<TokenMetadata> {
+        let symbol = decode_symbol(&self.call(address, "symbol").await?)?;
+        let decimals = match self
+            .abi
+            .function("decimals")?
+            .decode_output(&self.call(address, "decimals").await?)?
+            .pop()
+        {
+            Some(AbiToken::Uint(decimals)) if decimals <= u8::max_value().into() => {
+                decimals.as_u32() as u8
+            }
+            _ => anyhow::bail!("Token decimals are not valid"),
+        };
+        Ok(TokenMetadata { symbol, decimals })
+    }
+
+    async fn update_token(&self, token: &Token) -> anyhow::Result<()> {
+        let metadata = self.load_token_metadata(token.address).await?;
+        if metadata.decimals != token.decimals {
+            tracing::warn!(
+                "Token {} decimals in L1 ({}) differ from the stored ones ({}), \
+                 the metadata must be checked manually",
+                token.id,
+                metadata.decimals,
+                token.decimals
+            );
+            metrics::counter!("token_metadata_updater.decimals_mismatches", 1);
+        }
+        if metadata.symbol == token.symbol {
+            return Ok(());
+        }
+
+        let mut storage = self.db_pool.access_storage().await?;
+        let other_token = storage
+            .tokens_schema()
+            .get_token(TokenLike::Symbol(metadata.symbol.clone()))
+            .await?
+            .filter(|other_token| other_token.id != token.id);
+        if let Some(other_token) = other_token {
+            tracing::warn!(
+                "Token {} symbol in L1 ({}) is already used by token {}, \
+                 the metadata must be checked manually",
+                token.id,
+                metadata.symbol,
+                other_token.id
+            );
+            metrics::counter!("token_metadata_updater.symbol_collisions", 1);
+            return Ok(());
+        }
+
+        let updated = storage
+            .tokens_schema()
+            .update_token_symbol(token.id, &metadata.symbol)
+            .await?;
+        if updated {
+            tracing::info!(
+                "Token {} symbol updated: {} -> {}",
+                token.id,
+                token.symbol,
+                metadata.symbol
+            );
+            metrics::counter!("token_metadata_updater.updated_tokens", 1);
+        }
+        Ok(())
+    }
+
+    async fn update_tokens(&self) -> anyhow::Result<()> {
+        let tokens = self
+            .db_pool
+            .access_storage()
+            .await?
+            .tokens_schema()
+            .load_tokens_for_metadata_update()
+            .await?;
+
+        for token in &tokens {
+            if let Err(err) = self.update_token(token).await {
+                tracing::warn!("Failed to update token {} metadata: {}", token.id, err);
+            }
+        }
+        Ok(())
+    }
+
+    async fn run(self, interval: Duration) {
+        let mut timer = time::interval(interval);
+        loop {
+            timer.tick().await;
+
+            self.update_tokens()
+                .await
+                .map_err(|err| tracing::error!("Failed to update token metadata: {}", err))
+                .unwrap_or_default();
+        }
+    }
+}
+
+/// Decodes the token symbol, which is either a `string` or a `bytes32` value padded
+/// with zeros for the early tokens (e.g. MKR).
+fn decode_symbol(output: &[u8]) -> anyhow::Result<String> {
+    let symbol = match ethabi::decode(&[ParamType::String], output) {
+        Ok(mut tokens) => match tokens.pop() {
+            Some(AbiToken::String(symbol)) => symbol,
+            _ => anyhow::bail!("Token symbol is not a string"),
+        },
+        Err(_) => match ethabi::decode(&[ParamType::FixedBytes(32)], output)?.pop() {
+            Some(AbiToken::FixedBytes(bytes)) => String::from_utf8(bytes)?,
+            _ => anyhow::bail!("Token symbol is not a string"),
+        },
+    };
+
+    let symbol = symbol.trim_end_matches('\0').trim();
+    if symbol.is_empty() {
+        anyhow::bail!("Token symbol is empty");
+    }
+    Ok(symbol.to_string())
+}
+
+#[must_use]
+pub fn start_token_metadata_updater(
+    web3_url: &str,
+    db_pool: ConnectionPool,
+    interval: Duration,
+) -> JoinHandle<()> {
+    register_metrics("token metadata updater", METRICS);
+
+    let transport = web3::transports::Http::new(web3_url).unwrap();
+    let updater = TokenMetadataUpdater::new(Web3::new(transport), db_pool);
+    tokio::spawn(updater.run(interval))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn symbol_decoding() {
+        let string_symbol = ethabi::encode(&[AbiToken::String("DAI".to_string())]);
+        assert_eq!(decode_symbol(&string_symbol).unwrap(), "DAI");
+
+        let mut bytes32_symbol = b"MKR".to_vec();
+        bytes32_symbol.resize(32, 0);
+        assert_eq!(decode_symbol(&bytes32_symbol).unwrap(), "MKR");
+
+        assert!(decode_symbol(&[0u8; 32]).is_err());
+        assert!(decode_symbol(&[]).is_err());
+    }
+}
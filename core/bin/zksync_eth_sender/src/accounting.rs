@@ -0,0 +1,164 @@
+//! Accounting of the fees collected by the blocks against the ETH spent on the Ethereum
+//! transactions for these blocks.
+//!
+//! Fees are collected in different tokens, so they are converted to wei with the ticker
+//! prices of the tokens. Fees in the tokens without a known price are not taken into account.
+
+// Built-in deps
+use std::collections::HashMap;
+// External uses
+use num::{rational::Ratio, traits::Pow, BigUint, Zero};
+// Workspace uses
+use zksync_types::{
+    block::{Block, ExecutedOperations},
+    Token, TokenId, TokenLike,
+};
+
+/// Decimals of ETH, i.e. the amount of wei in one ETH is `10^18`.
+const ETH_DECIMALS: u8 = 18;
+
+/// Returns the fees collected by the successful transactions of the block, per token.
+pub fn collected_fees(block: &Block) -> HashMap<TokenId, BigUint> {
+    let mut fees = HashMap::new();
+    for op in &block.block_transactions {
+        let tx = match op {
+            ExecutedOperations::Tx(tx) if tx.success => tx,
+            _ => continue,
+        };
+        if let Some((_, TokenLike::Id(token_id), _, fee)) = tx.signed_tx.tx.get_fee_info() {
+            *fees.entry(token_id).or_insert_with(BigUint::zero) += fee;
+        }
+    }
+    fees
+}
+
+/// Converts the fees to wei. Prices are the USD prices of the tokens, including ETH.
+pub fn fees_in_wei(
+    fees: &HashMap<TokenId, BigUint>,
+    tokens: &HashMap<TokenId, Token>,
+    prices: &HashMap<TokenId, Ratio<BigUint>>,
+) -> BigUint {
+    let eth_price = prices.get(&0).filter(|price| !price.is_zero());
+    let mut total = BigUint::zero();
+    for (token_id, fee) in fees {
+        if *token_id == 0 {
+            total += fee;
+            continue;
+        }
+
+        let (token, token_price, eth_price) =
+            match (tokens.get(token_id), prices.get(token_id), eth_price) {
+                (Some(token), Some(token_price), Some(eth_price)) => {
+                    (token, token_price, eth_price)
+                }
+                _ => continue,
+            };
+        let fee_usd = Ratio::from_integer(fee.clone()) * token_price
+            / BigUint::from(10u32).pow(u32::from(token.decimals));
+        let fee_wei = fee_usd / eth_price * BigUint::from(10u32).pow(u32::from(ETH_DECIMALS));
+        total += fee_wei.to_integer();
+    }
+    total
+}
+
+/// Splits the ETH spent on the transaction between the blocks it was sent for, proportionally
+/// to the chunk sizes of the blocks, since the transaction cost mostly depends on the amount
+/// of the block data. The remainder of the division goes to the first block.
+pub fn split_cost(cost: &BigUint, chunk_sizes: &[usize]) -> Vec<BigUint> {
+    if chunk_sizes.is_empty() {
+        return Vec::new();
+    }
+
+    let total_chunks: usize = chunk_sizes.iter().sum();
+    let mut costs: Vec<BigUint> = if total_chunks == 0 {
+        let share = cost / BigUint::from(chunk_sizes.len());
+        vec![share; chunk_sizes.len()]
+    } else {
+        chunk_sizes
+            .iter()
+            .map(|chunks| cost * BigUint::from(*chunks) / BigUint::from(total_chunks))
+            .collect()
+    };
+    let distributed = costs.iter().fold(BigUint::zero(), |sum, share| sum + share);
+    costs[0] += cost - distributed;
+    costs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_types::Address;
+
+    fn token(id: TokenId, decimals: u8) -> Token {
+        Token::new(id, Address::repeat_byte(id as u8), "TKN", decimals)
+    }
+
+    /// Checks that the fees are converted to wei with the token prices.
+    #[test]
+    fn fees_conversion() {
+        let tokens = vec![(0, token(0, 18)), (1, token(1, 6)), (2, token(2, 18))]
+            .into_iter()
+            .collect();
+        // ETH costs 500 USD and token 1 costs 1 USD, token 2 has no price.
+        let prices = vec![
+            (0, Ratio::from_integer(BigUint::from(500u32))),
+            (1, Ratio::from_integer(BigUint::from(1u32))),
+        ]
+        .into_iter()
+        .collect();
+        // 0.001 ETH, 5 of token 1 and some token 2.
+        let fees = vec![
+            (0, BigUint::from(1_000_000_000_000_000u64)),
+            (1, BigUint::from(5_000_000u64)),
+            (2, BigUint::from(1000u32)),
+        ]
+        .into_iter()
+        .collect();
+
+        // 5 USD is 0.01 ETH.
+        assert_eq!(
+            fees_in_wei(&fees, &tokens, &prices),
+            BigUint::from(11_000_000_000_000_000u64)
+        );
+
+        // Without the ETH price only the fees in ETH are known.
+        let prices = vec![(1, Ratio::from_integer(BigUint::from(1u32)))]
+            .into_iter()
+            .collect();
+        assert_eq!(
+            fees_in_wei(&fees, &tokens, &prices),
+            BigUint::from(1_000_000_000_000_000u64)
+        );
+    }
+
+    /// Checks that the cost is split between the blocks by their chunk sizes without losing
+    /// the remainder.
+    #[test]
+    fn cost_split() {
+        let costs = split_cost(&BigUint::from(100u32), &[10, 30, 60]);
+        assert_eq!(
+            costs,
+            vec![
+                BigUint::from(10u32),
+                BigUint::from(30u32),
+                BigUint::from(60u32)
+            ]
+        );
+
+        let costs = split_cost(&BigUint::from(10u32), &[10, 10, 10]);
+        assert_eq!(
+            costs,
+            vec![
+                BigUint::from(4u32),
+                BigUint::from(3u32),
+                BigUint::from(3u32)
+            ]
+        );
+
+        // Blocks without the chunks share the cost equally.
+        let costs = split_cost(&BigUint::from(10u32), &[0, 0]);
+        assert_eq!(costs, vec![BigUint::from(5u32), BigUint::from(5u32)]);
+
+        assert!(split_cost(&BigUint::from(10u32), &[]).is_empty());
+    }
+}
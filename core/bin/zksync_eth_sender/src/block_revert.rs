@@ -0,0 +1,117 @@
+//! Revert of the blocks which were committed, but not verified yet.
+//!
+//! Reverting is an operator action performed while the server is stopped: the blocks
+//! are reverted on the zkSync contract first, and then the database is rolled back to the
+//! last verified block, returning the transactions of the reverted blocks to the mempool.
+
+// External uses
+use ethabi::Token;
+use web3::{contract::Options, types::H256};
+// Workspace uses
+use zksync_config::{EthClientOptions, EthSenderOptions};
+use zksync_storage::ConnectionPool;
+use zksync_types::{aggregated_operations::stored_block_info, ActionType, BlockNumber};
+// Local uses
+use crate::ethereum_interface::{EthereumHttpClient, EthereumInterface};
+
+/// Gas limit for the `revertBlocks` transaction.
+const REVERT_BLOCKS_GAS_LIMIT: u64 = 1_000_000;
+
+/// Reverts all the blocks after the last verified one, both on the contract and in the database.
+///
+/// Must be invoked only when the server (and `eth_sender` in particular) is stopped.
+/// Returns the number of the last remaining block.
+pub async fn revert_unverified_blocks(
+    pool: ConnectionPool,
+    eth_client_options: EthClientOptions,
+    eth_sender_options: EthSenderOptions,
+) -> anyhow::Result<BlockNumber> {
+    let ethereum = EthereumHttpClient::new(&eth_client_options)?;
+    let mut storage = pool.access_storage().await?;
+
+    let unconfirmed_operations = storage
+        .ethereum_schema()
+        .load_unconfirmed_operations()
+        .await?;
+    anyhow::ensure!(
+        unconfirmed_operations.is_empty(),
+        "There are {} unconfirmed Ethereum operations, wait for them to be confirmed first",
+        unconfirmed_operations.len()
+    );
+
+    let last_verified_block = storage
+        .chain()
+        .block_schema()
+        .get_last_verified_confirmed_block()
+        .await?;
+    let last_committed_block = storage
+        .chain()
+        .operations_schema()
+        .get_last_block_by_action(ActionType::COMMIT, Some(true))
+        .await?;
+
+    if last_committed_block > last_verified_block {
+        // Contract expects blocks to be reverted starting from the latest one.
+        let mut blocks = Vec::new();
+        for block_number in (last_verified_block + 1..=last_committed_block).rev() {
+            let block = storage
+                .chain()
+                .block_schema()
+                .get_block(block_number)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("Block {} is not stored", block_number))?;
+            blocks.push(stored_block_info(&block));
+        }
+
+        let args = vec![Token::Array(blocks)];
+        let data = ethereum.encode_tx_data("revertBlocks", args.as_slice());
+        let nonce = storage.ethereum_schema().get_next_nonce().await?;
+        let options = Options::with(move |opt| {
+            opt.nonce = Some(nonce.into());
+            opt.gas = Some(REVERT_BLOCKS_GAS_LIMIT.into());
+        });
+        let signed_tx = ethereum.sign_prepared_tx(data, options).await?;
+        ethereum.send_tx(&signed_tx).await?;
+        tracing::info!(
+            "Sent transaction {:#x} reverting blocks {}..={}",
+            signed_tx.hash,
+            last_verified_block + 1,
+            last_committed_block
+        );
+
+        wait_for_confirmation(&ethereum, &signed_tx.hash, &eth_sender_options).await?;
+    }
+
+    storage
+        .chain()
+        .block_schema()
+        .revert_blocks(last_verified_block)
+        .await?;
+    tracing::info!(
+        "Database is reverted to the block {}, transactions of the reverted blocks are returned to the mempool",
+        last_verified_block
+    );
+
+    Ok(last_verified_block)
+}
+
+async fn wait_for_confirmation(
+    ethereum: &EthereumHttpClient,
+    hash: &H256,
+    options: &EthSenderOptions,
+) -> anyhow::Result<()> {
+    loop {
+        match ethereum.get_tx_status(hash).await? {
+            Some(status) if !status.success => {
+                let reason = ethereum
+                    .failure_reason(*hash)
+                    .await
+                    .map(|info| info.revert_reason)
+                    .unwrap_or_else(|| "unknown".to_string());
+                anyhow::bail!("Revert transaction {:#x} failed: {}", hash, reason);
+            }
+            Some(status) if status.confirmations >= options.wait_confirmations => return Ok(()),
+            _ => tokio::time::delay_for(options.tx_poll_period).await,
+        }
+    }
+}
@@ -4,24 +4,72 @@
 //! database to run, which is required for tests.
 
 // Built-in deps
-use std::collections::VecDeque;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::str::FromStr;
 // External uses
 use num::BigUint;
 use zksync_basic_types::{H256, U256};
 // Workspace uses
-use zksync_storage::{ConnectionPool, StorageProcessor};
+use zksync_storage::{
+    admin::{
+        ETH_OPERATION_DROP, ETH_OPERATION_REQUEUE, ETH_SENDER_RELOAD_FLAG, ETH_SENDER_RESUBMIT_FLAG,
+    },
+    ConnectionPool, StorageProcessor,
+};
 use zksync_types::{
-    ethereum::{ETHOperation, EthOpId, InsertedOperationResponse, OperationType},
-    Action, ActionType, Operation,
+    ethereum::{
+        ContractUpgradeStage, ETHOperation, EthOpId, InsertedOperationResponse, OnchainWithdrawal,
+        OperationType,
+    },
+    Action, ActionType, Operation, TokenId,
 };
 // Local uses
-use super::transactions::ETHStats;
+use super::{accounting, transactions::ETHStats};
 use zksync_types::aggregated_operations::{AggregatedActionType, AggregatedOperation};
 
+/// Request made through the admin API for `ETHSender` to handle the ongoing operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperationRequest {
+    /// Cancel the sent transactions and send the aggregated operation again.
+    Requeue,
+    /// Cancel the sent transactions and stop tracking the operation.
+    Drop,
+}
+
+impl OperationRequest {
+    /// Name of the action stored in the database.
+    pub fn as_action(self) -> &'static str {
+        match self {
+            OperationRequest::Requeue => ETH_OPERATION_REQUEUE,
+            OperationRequest::Drop => ETH_OPERATION_DROP,
+        }
+    }
+
+    /// Parses the action stored in the database.
+    pub fn from_action(action: &str) -> Option<Self> {
+        match action {
+            ETH_OPERATION_REQUEUE => Some(OperationRequest::Requeue),
+            ETH_OPERATION_DROP => Some(OperationRequest::Drop),
+            _ => None,
+        }
+    }
+}
+
+/// Upgrade of the zkSync contract observed by the Ethereum watcher, which is either
+/// being prepared or completed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContractUpgrade {
+    /// Version of the contract the upgrade results in.
+    pub version_id: u64,
+    /// Whether the upgrade is completed, otherwise it's in the preparation stage.
+    pub completed: bool,
+    /// Whether the operator confirmed that the ABI in use matches the upgraded contract.
+    pub confirmed: bool,
+}
+
 /// Abstract database access trait, optimized for the needs of `ETHSender`.
 #[async_trait::async_trait]
-pub(super) trait DatabaseInterface {
+pub trait DatabaseInterface {
     /// Returns connection to the database.
     async fn acquire_connection(&self) -> anyhow::Result<StorageProcessor<'_>>;
 
@@ -68,12 +116,31 @@ pub(super) trait DatabaseInterface {
         new_gas_value: U256,
     ) -> anyhow::Result<()>;
 
-    /// Marks an operation as completed in the database.
+    /// Replaces the tx payload of the Ethereum operation, e.g. re-encoded for the upgraded
+    /// contract.
+    async fn update_eth_tx_data(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        eth_op_id: EthOpId,
+        encoded_tx_data: &[u8],
+    ) -> anyhow::Result<()>;
+
+    /// Marks an operation as completed in the database. For the `ExecuteBlocks` operation,
+    /// `onchain_withdrawals` are the withdrawals transferred by the confirmed transaction.
     async fn confirm_operation(
         &self,
         connection: &mut StorageProcessor<'_>,
         hash: &H256,
         op: &ETHOperation,
+        onchain_withdrawals: &[OnchainWithdrawal],
+    ) -> anyhow::Result<()>;
+
+    /// Adds the ETH spent on the transactions of the operation to the accounting of its blocks.
+    async fn record_operation_cost(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        op: &ETHOperation,
+        eth_spent: U256,
     ) -> anyhow::Result<()>;
 
     /// Loads the stored Ethereum operations stats.
@@ -98,6 +165,87 @@ pub(super) trait DatabaseInterface {
         connection: &mut StorageProcessor<'_>,
         op: &ETHOperation,
     ) -> anyhow::Result<bool>;
+
+    /// Loads the withdrawal gas limits of the tokens which require more gas than the default one.
+    async fn load_withdrawal_gas_limits(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<HashMap<TokenId, u64>>;
+
+    /// Checks whether the resubmission of the ongoing operations was requested
+    /// through the admin API. The request is reset once taken.
+    async fn take_resubmission_request(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<bool>;
+
+    /// Checks whether the reload of the unconfirmed operations was requested
+    /// through the admin API. The request is reset once taken.
+    async fn take_reload_request(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<bool>;
+
+    /// Loads the requests for the ongoing operations made through the admin API,
+    /// in the order they were made. The requests are removed once taken.
+    async fn take_operation_requests(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<(EthOpId, OperationRequest)>>;
+
+    /// Loads the Ethereum operations that were started, but not confirmed yet.
+    async fn load_unconfirmed_operations(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<VecDeque<ETHOperation>>;
+
+    /// Removes the transactions of the cancelled operation, so its aggregated operation
+    /// is sent again with a new nonce.
+    async fn requeue_operation(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        eth_op_id: EthOpId,
+    ) -> anyhow::Result<()>;
+
+    /// Marks the cancelled operation as dropped.
+    async fn drop_operation(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        eth_op_id: EthOpId,
+    ) -> anyhow::Result<()>;
+
+    /// Stores the transaction sent to cancel the operation before handling the operator request.
+    async fn save_cancellation(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        eth_op_id: EthOpId,
+        request: OperationRequest,
+        tx_hash: H256,
+    ) -> anyhow::Result<()>;
+
+    /// Loads the cancellations which are not completed yet.
+    async fn load_cancellations(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<(EthOpId, OperationRequest, H256)>>;
+
+    /// Removes the cancellation which won't happen.
+    async fn remove_cancellation(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        eth_op_id: EthOpId,
+    ) -> anyhow::Result<()>;
+
+    /// Loads the latest contract upgrade which is being prepared or completed, if any.
+    async fn load_last_contract_upgrade(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Option<ContractUpgrade>>;
+
+    /// Checks that this server replica is still the leader. Must be called within the
+    /// transaction which is committed only after the Ethereum transaction is sent, so that
+    /// the new leader can't take over until the sent transaction is stored.
+    async fn check_leadership(&self, connection: &mut StorageProcessor<'_>) -> anyhow::Result<()>;
 }
 
 /// The actual database wrapper.
@@ -106,11 +254,16 @@ pub(super) trait DatabaseInterface {
 pub struct Database {
     /// Connection to the database.
     db_pool: ConnectionPool,
+    /// Leader epoch of the server replica, `None` if the leader election is disabled.
+    leader_epoch: Option<i64>,
 }
 
 impl Database {
-    pub fn new(db_pool: ConnectionPool) -> Self {
-        Self { db_pool }
+    pub fn new(db_pool: ConnectionPool, leader_epoch: Option<i64>) -> Self {
+        Self {
+            db_pool,
+            leader_epoch,
+        }
     }
 }
 
@@ -200,6 +353,18 @@ impl DatabaseInterface for Database {
             .await?)
     }
 
+    async fn update_eth_tx_data(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        eth_op_id: EthOpId,
+        encoded_tx_data: &[u8],
+    ) -> anyhow::Result<()> {
+        Ok(connection
+            .ethereum_schema()
+            .update_eth_tx_data(eth_op_id, encoded_tx_data)
+            .await?)
+    }
+
     async fn is_previous_operation_confirmed(
         &self,
         _connection: &mut StorageProcessor<'_>,
@@ -223,6 +388,7 @@ impl DatabaseInterface for Database {
         connection: &mut StorageProcessor<'_>,
         hash: &H256,
         op: &ETHOperation,
+        onchain_withdrawals: &[OnchainWithdrawal],
     ) -> anyhow::Result<()> {
         let mut transaction = connection.start_transaction().await?;
 
@@ -261,6 +427,10 @@ impl DatabaseInterface for Database {
                     .operations_schema()
                     .confirm_operations(first_block, last_block, ActionType::VERIFY)
                     .await?;
+                transaction
+                    .withdrawals_schema()
+                    .complete_withdrawals(first_block, last_block, hash, onchain_withdrawals)
+                    .await?;
             }
             _ => {}
         }
@@ -271,6 +441,63 @@ impl DatabaseInterface for Database {
         Ok(())
     }
 
+    async fn record_operation_cost(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        op: &ETHOperation,
+        eth_spent: U256,
+    ) -> anyhow::Result<()> {
+        let (action_type, blocks) = match &op.op {
+            Some((_, AggregatedOperation::CommitBlocks(op))) => {
+                (AggregatedActionType::CommitBlocks, &op.blocks)
+            }
+            Some((_, AggregatedOperation::PublishProofBlocksOnchain(op))) => {
+                (AggregatedActionType::PublishProofBlocksOnchain, &op.blocks)
+            }
+            Some((_, AggregatedOperation::ExecuteBlocks(op))) => {
+                (AggregatedActionType::ExecuteBlocks, &op.blocks)
+            }
+            _ => return Ok(()),
+        };
+
+        let block_fees: Vec<_> = blocks.iter().map(accounting::collected_fees).collect();
+        let tokens = connection.tokens_schema().load_tokens().await?;
+        let mut prices = HashMap::new();
+        let fee_tokens: HashSet<TokenId> = block_fees
+            .iter()
+            .flat_map(|fees| fees.keys().copied())
+            .chain(std::iter::once(0))
+            .collect();
+        for token_id in fee_tokens {
+            if let Some(price) = connection
+                .tokens_schema()
+                .get_historical_ticker_price(token_id)
+                .await?
+            {
+                prices.insert(token_id, price.usd_price);
+            }
+        }
+
+        let eth_spent = BigUint::from_str(&eth_spent.to_string()).unwrap();
+        let chunk_sizes: Vec<_> = blocks.iter().map(|block| block.block_chunks_size).collect();
+        let costs = accounting::split_cost(&eth_spent, &chunk_sizes);
+        let mut transaction = connection.start_transaction().await?;
+        for ((block, fees), cost) in blocks.iter().zip(block_fees).zip(costs) {
+            transaction
+                .accounting_schema()
+                .record_block_cost(
+                    block.block_number,
+                    action_type,
+                    accounting::fees_in_wei(&fees, &tokens, &prices),
+                    cost,
+                )
+                .await?;
+        }
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
     async fn load_stats(&self, connection: &mut StorageProcessor<'_>) -> anyhow::Result<ETHStats> {
         let stats = connection.ethereum_schema().load_stats().await?;
         Ok(stats.into())
@@ -296,4 +523,169 @@ impl DatabaseInterface for Database {
             .await?;
         Ok(())
     }
+    async fn load_withdrawal_gas_limits(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<HashMap<TokenId, u64>> {
+        let gas_limits = connection
+            .tokens_schema()
+            .load_withdrawal_gas_limits()
+            .await?;
+        Ok(gas_limits)
+    }
+
+    async fn take_resubmission_request(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<bool> {
+        let requested = connection
+            .admin_schema()
+            .take_flag(ETH_SENDER_RESUBMIT_FLAG)
+            .await?;
+        Ok(requested)
+    }
+
+    async fn take_reload_request(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<bool> {
+        let requested = connection
+            .admin_schema()
+            .take_flag(ETH_SENDER_RELOAD_FLAG)
+            .await?;
+        Ok(requested)
+    }
+
+    async fn take_operation_requests(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<(EthOpId, OperationRequest)>> {
+        let requests = connection
+            .admin_schema()
+            .take_eth_operation_requests()
+            .await?
+            .into_iter()
+            .filter_map(|request| {
+                let action = OperationRequest::from_action(&request.action);
+                if action.is_none() {
+                    tracing::warn!(
+                        "Unknown action '{}' requested for Ethereum operation {}",
+                        request.action,
+                        request.eth_op_id
+                    );
+                }
+                action.map(|action| (request.eth_op_id, action))
+            })
+            .collect();
+        Ok(requests)
+    }
+
+    async fn load_unconfirmed_operations(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<VecDeque<ETHOperation>> {
+        let unconfirmed_ops = connection
+            .ethereum_schema()
+            .load_unconfirmed_operations()
+            .await?;
+        Ok(unconfirmed_ops)
+    }
+
+    async fn requeue_operation(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        eth_op_id: EthOpId,
+    ) -> anyhow::Result<()> {
+        connection
+            .ethereum_schema()
+            .requeue_eth_operation(eth_op_id)
+            .await?;
+        Ok(())
+    }
+
+    async fn drop_operation(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        eth_op_id: EthOpId,
+    ) -> anyhow::Result<()> {
+        connection
+            .ethereum_schema()
+            .drop_eth_operation(eth_op_id)
+            .await?;
+        Ok(())
+    }
+
+    async fn save_cancellation(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        eth_op_id: EthOpId,
+        request: OperationRequest,
+        tx_hash: H256,
+    ) -> anyhow::Result<()> {
+        connection
+            .ethereum_schema()
+            .save_eth_cancellation(eth_op_id, request.as_action(), &tx_hash)
+            .await?;
+        Ok(())
+    }
+
+    async fn load_cancellations(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<(EthOpId, OperationRequest, H256)>> {
+        let cancellations = connection
+            .ethereum_schema()
+            .load_eth_cancellations()
+            .await?
+            .into_iter()
+            .filter_map(|cancellation| {
+                OperationRequest::from_action(&cancellation.action).map(|request| {
+                    (
+                        cancellation.eth_op_id,
+                        request,
+                        H256::from_slice(&cancellation.tx_hash),
+                    )
+                })
+            })
+            .collect();
+        Ok(cancellations)
+    }
+
+    async fn remove_cancellation(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        eth_op_id: EthOpId,
+    ) -> anyhow::Result<()> {
+        connection
+            .ethereum_schema()
+            .remove_eth_cancellation(eth_op_id)
+            .await?;
+        Ok(())
+    }
+
+    async fn load_last_contract_upgrade(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Option<ContractUpgrade>> {
+        let upgrade = connection
+            .ethereum_schema()
+            .load_last_started_contract_upgrade()
+            .await?
+            .map(|upgrade| ContractUpgrade {
+                version_id: upgrade.version_id as u64,
+                completed: upgrade.stage == ContractUpgradeStage::Completed.to_string(),
+                confirmed: upgrade.confirmed_at.is_some(),
+            });
+        Ok(upgrade)
+    }
+
+    async fn check_leadership(&self, connection: &mut StorageProcessor<'_>) -> anyhow::Result<()> {
+        if let Some(epoch) = self.leader_epoch {
+            connection
+                .leader_election_schema()
+                .check_leader_epoch(epoch)
+                .await?;
+        }
+        Ok(())
+    }
 }
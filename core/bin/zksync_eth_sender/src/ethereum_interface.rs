@@ -1,4 +1,5 @@
 // Built-in deps
+use std::convert::TryFrom;
 // External uses
 
 use anyhow::ensure;
@@ -11,8 +12,12 @@ use zksync_eth_signer::PrivateKeySigner;
 use super::ExecutedTxStatus;
 use std::time::Duration;
 use zksync_config::EthClientOptions;
-use zksync_contracts::zksync_contract;
+use zksync_contracts::{versioned_zksync_contract, zksync_contract};
 use zksync_eth_client::{ETHClient, SignedCallResult};
+use zksync_types::ethereum::OnchainWithdrawal;
+
+/// Gas limit of the plain Ether transfer used to cancel a sent transaction.
+pub const CANCEL_TX_GAS_LIMIT: u64 = 21_000;
 
 /// Sleep time between consecutive requests.
 const SLEEP_DURATION: Duration = Duration::from_millis(250);
@@ -36,7 +41,7 @@ pub struct FailureInfo {
 /// The provided interface is not as rich as the actual `ETHClient`
 /// structure, but it is instead optimized for the needs of `ETHSender`.
 #[async_trait::async_trait]
-pub(super) trait EthereumInterface {
+pub trait EthereumInterface {
     /// Obtains a transaction status from the Ethereum blockchain.
     /// The resulting information is reduced to the following minimum:
     ///
@@ -45,6 +50,15 @@ pub(super) trait EthereumInterface {
     ///   of confirmations is returned.
     async fn get_tx_status(&self, hash: &H256) -> anyhow::Result<Option<ExecutedTxStatus>>;
 
+    /// Obtains the ETH paid for the transaction, i.e. the gas used by the transaction
+    /// multiplied by its gas price. Reverted transactions are paid for as well, while
+    /// for the transactions which are not mined `None` is returned.
+    async fn get_tx_fee(&self, hash: &H256) -> anyhow::Result<Option<U256>>;
+
+    /// Obtains the withdrawals transferred to their recipients by the executed transaction
+    /// (`OnchainWithdrawal` events of the zkSync contract).
+    async fn get_onchain_withdrawals(&self, hash: &H256) -> anyhow::Result<Vec<OnchainWithdrawal>>;
+
     /// Gets the actual block number.
     async fn block_number(&self) -> anyhow::Result<u64>;
 
@@ -58,6 +72,10 @@ pub(super) trait EthereumInterface {
     /// without creating an actual transaction.
     fn encode_tx_data<P: Tokenize>(&self, func: &str, params: P) -> Vec<u8>;
 
+    /// Switches the ABI used to encode the transaction data to the ABI of the given
+    /// contract version. Returns an error if the ABI of the version is not available.
+    fn switch_contract_version(&mut self, version_id: u64) -> anyhow::Result<()>;
+
     /// Signs the transaction given the previously encoded data.
     /// Fills in gas/nonce if not supplied inside options.
     async fn sign_prepared_tx(
@@ -66,6 +84,14 @@ pub(super) trait EthereumInterface {
         options: Options,
     ) -> anyhow::Result<SignedCallResult>;
 
+    /// Signs the empty transfer of the operator to itself, which takes the given nonce
+    /// and so cancels the pending transactions with the same nonce.
+    async fn sign_cancel_tx(
+        &self,
+        nonce: U256,
+        gas_price: U256,
+    ) -> anyhow::Result<SignedCallResult>;
+
     /// Returns the information about transaction failure reason.
     async fn failure_reason(&self, tx_hash: H256) -> Option<FailureInfo>;
 }
@@ -145,6 +171,58 @@ impl EthereumInterface for EthereumHttpClient {
         }
     }
 
+    async fn get_tx_fee(&self, hash: &H256) -> anyhow::Result<Option<U256>> {
+        self.sleep();
+        let gas_used = match self
+            .eth_client
+            .web3
+            .eth()
+            .transaction_receipt(*hash)
+            .await?
+            .and_then(|receipt| receipt.gas_used)
+        {
+            Some(gas_used) => gas_used,
+            None => return Ok(None),
+        };
+        self.sleep();
+        let transaction = self
+            .eth_client
+            .web3
+            .eth()
+            .transaction((*hash).into())
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Transaction {:#x} is not found", hash))?;
+
+        Ok(Some(gas_used * transaction.gas_price))
+    }
+
+    async fn get_onchain_withdrawals(&self, hash: &H256) -> anyhow::Result<Vec<OnchainWithdrawal>> {
+        self.sleep();
+        let receipt = self
+            .eth_client
+            .web3
+            .eth()
+            .transaction_receipt(*hash)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Transaction {:#x} is not executed", hash))?;
+        let event_signature = self
+            .eth_client
+            .contract
+            .event("OnchainWithdrawal")
+            .expect("zkSync contract ABI has no OnchainWithdrawal event")
+            .signature();
+
+        receipt
+            .logs
+            .into_iter()
+            .filter(|log| {
+                log.address == self.eth_client.contract_addr
+                    && log.topics.first() == Some(&event_signature)
+            })
+            .map(OnchainWithdrawal::try_from)
+            .collect()
+    }
+
     async fn block_number(&self) -> anyhow::Result<u64> {
         self.sleep();
         let block_number = self.eth_client.web3.eth().block_number().await?;
@@ -173,6 +251,18 @@ impl EthereumInterface for EthereumHttpClient {
         self.eth_client.encode_tx_data(func, params)
     }
 
+    fn switch_contract_version(&mut self, version_id: u64) -> anyhow::Result<()> {
+        let contract = versioned_zksync_contract(version_id).map_err(|err| {
+            anyhow::format_err!(
+                "ABI of the contract version {} is not available: {}",
+                version_id,
+                err
+            )
+        })?;
+        self.eth_client.set_contract(version_id, contract);
+        Ok(())
+    }
+
     async fn sign_prepared_tx(
         &self,
         data: Vec<u8>,
@@ -182,6 +272,23 @@ impl EthereumInterface for EthereumHttpClient {
         self.eth_client.sign_prepared_tx(data, options).await
     }
 
+    async fn sign_cancel_tx(
+        &self,
+        nonce: U256,
+        gas_price: U256,
+    ) -> anyhow::Result<SignedCallResult> {
+        self.sleep();
+        let options = Options {
+            nonce: Some(nonce),
+            gas_price: Some(gas_price),
+            gas: Some(CANCEL_TX_GAS_LIMIT.into()),
+            ..Default::default()
+        };
+        self.eth_client
+            .sign_prepared_tx_for_addr(Vec::new(), self.eth_client.sender_account, options)
+            .await
+    }
+
     async fn failure_reason(&self, tx_hash: H256) -> Option<FailureInfo> {
         let transaction = self
             .eth_client
@@ -86,7 +86,7 @@ impl<ETH: EthereumInterface, DB: DatabaseInterface> GasAdjuster<ETH, DB> {
         if price == self.get_current_max_price() {
             // We're suggesting the max price, so we must notify the log
             // entry about it.
-            log::warn!("Maximum possible gas price will be used: <{}>", price);
+            tracing::warn!("Maximum possible gas price will be used: <{}>", price);
         }
 
         // Report used price to be gathered by the statistics module.
@@ -108,7 +108,7 @@ impl<ETH: EthereumInterface, DB: DatabaseInterface> GasAdjuster<ETH, DB> {
                     self.last_sample_added = Instant::now();
                 }
                 Err(err) => {
-                    log::warn!("Cannot add the sample gas price: {}", err);
+                    tracing::warn!("Cannot add the sample gas price: {}", err);
                 }
             }
         }
@@ -123,7 +123,7 @@ impl<ETH: EthereumInterface, DB: DatabaseInterface> GasAdjuster<ETH, DB> {
             let mut connection = match db.acquire_connection().await {
                 Ok(connection) => connection,
                 Err(err) => {
-                    log::warn!("Cannot update the gas limit value in the database: {}", err);
+                    tracing::warn!("Cannot update the gas limit value in the database: {}", err);
                     return;
                 }
             };
@@ -138,7 +138,7 @@ impl<ETH: EthereumInterface, DB: DatabaseInterface> GasAdjuster<ETH, DB> {
             if let Err(err) = result {
                 // Inability of update the value in the DB is not critical as it's not
                 // an essential logic part, so just report the error to the log.
-                log::warn!("Cannot update the gas limit value in the database: {}", err);
+                tracing::warn!("Cannot update the gas limit value in the database: {}", err);
             }
         }
     }
@@ -159,8 +159,14 @@ impl<ETH: EthereumInterface, DB: DatabaseInterface> GasAdjuster<ETH, DB> {
         self.statistics.get_limit()
     }
 
-    /// Get estimate of the average gas prices used for past transactions based on the current gas_limit.
+    /// Get the average of the recently observed gas prices, which is used by the fee ticker
+    /// to quote the L1 gas component of the fees. Until enough samples are collected,
+    /// the estimate based on the current gas_limit is returned.
     pub fn get_average_gas_price(&self) -> U256 {
+        if let Some(average_price) = self.statistics.get_average_price() {
+            return average_price;
+        }
+
         let scale_factor = parameters::limit_scale_factor();
         let divider = U256::from((scale_factor * 100.0f64).round() as u64);
         let multiplier = U256::from(100);
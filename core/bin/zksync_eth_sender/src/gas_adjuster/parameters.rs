@@ -8,8 +8,9 @@
 //!
 //! The module uses a child module `parameters_impl` which contains two implementations
 //! for functions declared in module: one for the actual usage, and one for tests.
-//! While the actual implementation obtains the values from the environment variables,
-//! the test one uses hard-coded values for better test behavior predictability.
+//! While the actual implementation obtains the values from the configuration (which may be
+//! reloaded at runtime), the test one uses hard-coded values for better test behavior
+//! predictability.
 
 // Built-in deps.
 use std::time::Duration;
@@ -47,7 +48,7 @@ mod parameters_impl {
     // Built-in deps.
     use std::time::Duration;
     // Workspace deps
-    use zksync_utils::parse_env;
+    use zksync_config::loader::parse_reloadable_var;
 
     /// Name of the environment variable responsible for the `gas_price_limit` renewing interval.
     const GAS_PRICE_LIMIT_UPDATE_INTERVAL: &str = "ETH_GAS_PRICE_LIMIT_UPDATE_INTERVAL";
@@ -67,7 +68,8 @@ mod parameters_impl {
     /// server by an administrator. This may be required if existing settings aren't flexible
     /// enough to match the current network price.
     pub fn limit_update_interval() -> Duration {
-        let renew_interval: u64 = parse_env(GAS_PRICE_LIMIT_UPDATE_INTERVAL);
+        let renew_interval: u64 = parse_reloadable_var(GAS_PRICE_LIMIT_UPDATE_INTERVAL)
+            .expect("ETH_GAS_PRICE_LIMIT_UPDATE_INTERVAL is not set");
 
         Duration::from_secs(renew_interval)
     }
@@ -78,7 +80,8 @@ mod parameters_impl {
     /// server by an administrator. This may be required if existing settings aren't flexible
     /// enough to match the current network price.
     pub fn limit_scale_factor() -> f64 {
-        parse_env(GAS_PRICE_LIMIT_SCALE_FACTOR)
+        parse_reloadable_var(GAS_PRICE_LIMIT_SCALE_FACTOR)
+            .expect("ETH_GAS_PRICE_LIMIT_SCALE_FACTOR is not set")
     }
 
     /// Obtains the interval for the gas price samples to be added into `gas_adjuster`.
@@ -87,18 +90,17 @@ mod parameters_impl {
     /// server by an administrator. This may be required if existing settings aren't flexible
     /// enough to match the current network price.
     pub fn sample_adding_interval() -> Duration {
-        if std::env::var(GAS_PRICE_LIMIT_SAMPLE_INTERVAL).is_err() {
-            log::trace!(
-                "No value provided for `ETH_GAS_PRICE_LIMIT_SAMPLE_INTERVAL` env variable, \
-                 using the default: {} seconds",
-                DEFAULT_GAS_PRICE_LIMIT_SAMPLE_INTERVAL.as_secs()
-            );
-            return DEFAULT_GAS_PRICE_LIMIT_SAMPLE_INTERVAL;
+        match parse_reloadable_var::<u64>(GAS_PRICE_LIMIT_SAMPLE_INTERVAL) {
+            Some(renew_interval) => Duration::from_secs(renew_interval),
+            None => {
+                tracing::trace!(
+                    "No value provided for `ETH_GAS_PRICE_LIMIT_SAMPLE_INTERVAL` env variable, \
+                     using the default: {} seconds",
+                    DEFAULT_GAS_PRICE_LIMIT_SAMPLE_INTERVAL.as_secs()
+                );
+                DEFAULT_GAS_PRICE_LIMIT_SAMPLE_INTERVAL
+            }
         }
-
-        let renew_interval: u64 = parse_env(GAS_PRICE_LIMIT_SAMPLE_INTERVAL);
-
-        Duration::from_secs(renew_interval)
     }
 }
 
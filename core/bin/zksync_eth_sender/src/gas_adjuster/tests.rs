@@ -3,7 +3,7 @@ use zksync_basic_types::U256;
 // Local uses
 use crate::{
     gas_adjuster::{parameters::limit_scale_factor, GasStatistics},
-    tests::mock::{default_eth_sender, MockDatabase, MockEthereum},
+    testkit::{default_eth_sender, MockDatabase, MockEthereum},
     DatabaseInterface, GasAdjuster,
 };
 
@@ -289,3 +289,30 @@ async fn gas_price_limit_preservation() {
         assert_eq!(new_limit, price_limit.into());
     }
 }
+
+/// Checks that the average gas price reported to the fee ticker is based on the observed prices
+/// once enough samples are collected, rather than on the gas price limit.
+#[tokio::test]
+async fn average_gas_price_follows_samples() {
+    // Amount of samples to gather statistics.
+    const N_SAMPLES: usize = GasStatistics::GAS_PRICE_SAMPLES_AMOUNT;
+    // Price suggested by Ethereum client;
+    const SUGGESTED_PRICE: u64 = 10;
+    // Initial price limit to set.
+    const PRICE_LIMIT: u64 = 10000;
+
+    let (mut ethereum, db) = eth_and_db_clients().await;
+    db.update_gas_price_limit(scale_gas_limit(PRICE_LIMIT).into())
+        .await
+        .unwrap();
+    let mut gas_adjuster: GasAdjuster<MockEthereum, MockDatabase> = GasAdjuster::new(&db).await;
+    ethereum.gas_price = SUGGESTED_PRICE.into();
+
+    // Not enough samples, the estimate is based on the limit.
+    assert_eq!(gas_adjuster.get_average_gas_price(), PRICE_LIMIT.into());
+
+    for _ in 0..N_SAMPLES {
+        gas_adjuster.get_gas_price(&ethereum, None).await.unwrap();
+    }
+    assert_eq!(gas_adjuster.get_average_gas_price(), SUGGESTED_PRICE.into());
+}
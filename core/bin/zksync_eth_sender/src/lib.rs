@@ -4,7 +4,7 @@
 //! every transaction is executed successfully and confirmed.
 
 // Built-in deps
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::time::{Duration, Instant};
 // External uses
 use tokio::{task::JoinHandle, time};
@@ -15,29 +15,43 @@ use web3::{
 // Workspace uses
 use zksync_config::{EthClientOptions, EthSenderOptions};
 use zksync_eth_client::SignedCallResult;
+use zksync_prometheus_exporter::{register_metrics, Metric};
 use zksync_storage::ConnectionPool;
 use zksync_types::{
+    block::Block,
     config,
-    ethereum::{ETHOperation, OperationType},
+    ethereum::{ETHOperation, EthOpId, OperationType},
     gas_counter::GasCounter,
-    Action, Operation,
+    Action, Operation, TokenId,
 };
 // Local uses
 use self::{
-    database::{Database, DatabaseInterface},
-    ethereum_interface::{EthereumHttpClient, EthereumInterface},
+    database::Database,
+    ethereum_interface::EthereumHttpClient,
     gas_adjuster::GasAdjuster,
     transactions::*,
     tx_queue::{TxData, TxQueue, TxQueueBuilder},
 };
 use zksync_types::aggregated_operations::AggregatedOperation;
+use zksync_utils::shutdown::ShutdownSignal;
 
+pub use self::{
+    block_revert::revert_unverified_blocks,
+    database::{ContractUpgrade, DatabaseInterface, OperationRequest},
+    ethereum_interface::{EthereumInterface, FailureInfo},
+    transactions::{ETHStats, ExecutedTxStatus},
+};
+
+mod accounting;
+mod block_revert;
 mod database;
 mod ethereum_interface;
 mod gas_adjuster;
 mod transactions;
 mod tx_queue;
 
+#[cfg(any(test, feature = "testkit"))]
+pub mod testkit;
 #[cfg(test)]
 mod tests;
 
@@ -46,6 +60,38 @@ const RATE_LIMIT_BACKOFF_PERIOD: Duration = Duration::from_secs(30);
 /// Rate limit error will contain this response code
 const RATE_LIMIT_HTTP_CODE: &str = "429";
 
+/// Metrics reported by the Ethereum sender.
+const METRICS: &[Metric] = &[
+    Metric::histogram(
+        "eth_sender.load_new_operations",
+        "Time spent on loading the new operations to send",
+    ),
+    Metric::histogram(
+        "eth_sender.proceed_next_operations",
+        "Time spent on a single round of the operations processing",
+    ),
+    Metric::histogram(
+        "eth_sender.perform_commitment_step",
+        "Time spent on checking the state of an ongoing operation",
+    ),
+    Metric::gauge(
+        "eth_sender.queued_operations",
+        "Number of the operations created by the committer and not sent to Ethereum yet",
+    ),
+    Metric::gauge(
+        "eth_sender.ongoing_operations",
+        "Number of the operations sent to Ethereum and not confirmed yet",
+    ),
+    Metric::counter(
+        "eth_sender.confirmed_operations",
+        "Number of the operations confirmed on Ethereum",
+    ),
+    Metric::gauge(
+        "eth_sender.paused_by_contract_upgrade",
+        "Whether sending new transactions is paused until the contract upgrade is confirmed",
+    ),
+];
+
 /// `TxCheckMode` enum determines the policy on the obtaining the tx status.
 /// The latest sent transaction can be pending (we're still waiting for it),
 /// but if there is more than one tx for some Ethereum operation, it means that we
@@ -111,7 +157,17 @@ enum TxCheckMode {
 /// report the incident to the log and then panic to prevent continue working in a probably
 /// erroneous conditions. Failure handling policy is determined by a corresponding callback,
 /// which can be changed if needed.
-struct ETHSender<ETH: EthereumInterface, DB: DatabaseInterface> {
+///
+/// # Contract upgrades
+///
+/// The upgraded zkSync contract may have a different interface, so once the upgrade preparation
+/// is started, `ETHSender` doesn't send new transactions. After the upgrade is completed, sending
+/// is resumed once either the operator confirms the upgrade, or (if `auto_switch_abi` is enabled)
+/// the ABI of the new contract version is used. The ongoing operations are still tracked while
+/// sending is paused. Once the ABI is switched, they are re-encoded for the new interface and
+/// their transactions are replaced right away, unless the ones sent against the outdated
+/// interface get mined first.
+pub struct ETHSender<ETH: EthereumInterface, DB: DatabaseInterface> {
     /// Ongoing operations queue.
     ongoing_ops: VecDeque<ETHOperation>,
     /// Connection to the database.
@@ -124,6 +180,16 @@ struct ETHSender<ETH: EthereumInterface, DB: DatabaseInterface> {
     gas_adjuster: GasAdjuster<ETH, DB>,
     /// Settings for the `ETHSender`.
     options: EthSenderOptions,
+    /// Version of the contract upgrade the ABI in use corresponds to,
+    /// `None` until the completed upgrades are checked.
+    contract_version: Option<u64>,
+    /// Contract upgrade which is being prepared or is completed but not handled yet,
+    /// new transactions are not sent until it's completed and confirmed.
+    pending_contract_upgrade: Option<ContractUpgrade>,
+    /// Operations requested to be requeued or dropped, mapped to the request and the hash
+    /// of the transaction cancelling them. New transactions are not sent until every
+    /// cancellation is completed.
+    cancellations: HashMap<EthOpId, (OperationRequest, H256)>,
 }
 
 impl<ETH: EthereumInterface, DB: DatabaseInterface> ETHSender<ETH, DB> {
@@ -148,6 +214,14 @@ impl<ETH: EthereumInterface, DB: DatabaseInterface> ETHSender<ETH, DB> {
             .with_aggregated_ops_count(stats.commit_ops)
             .build();
 
+        let cancellations = db
+            .load_cancellations(&mut connection)
+            .await
+            .expect("Failed loading the operation cancellations")
+            .into_iter()
+            .map(|(eth_op_id, request, tx_hash)| (eth_op_id, (request, tx_hash)))
+            .collect();
+
         let gas_adjuster = GasAdjuster::new(&db).await;
 
         drop(connection);
@@ -158,11 +232,14 @@ impl<ETH: EthereumInterface, DB: DatabaseInterface> ETHSender<ETH, DB> {
             tx_queue,
             gas_adjuster,
             options,
+            contract_version: None,
+            pending_contract_upgrade: None,
+            cancellations,
         };
 
         // Add all the unprocessed operations to the queue.
         for operation in unprocessed_ops {
-            log::info!(
+            tracing::info!(
                 "Adding unprocessed ZKSync operation <id -; action: {}; blocks: {}-{}> to queue",
                 // operation.id.expect("ID must be set"),
                 operation.1.get_action_type().to_string(),
@@ -175,14 +252,54 @@ impl<ETH: EthereumInterface, DB: DatabaseInterface> ETHSender<ETH, DB> {
         sender
     }
 
+    /// Returns the Ethereum intermediator used by the sender.
+    pub fn ethereum(&self) -> &ETH {
+        &self.ethereum
+    }
+
+    /// Returns the mutable reference to the Ethereum intermediator used by the sender.
+    pub fn ethereum_mut(&mut self) -> &mut ETH {
+        &mut self.ethereum
+    }
+
+    /// Returns the database used by the sender.
+    pub fn db(&self) -> &DB {
+        &self.db
+    }
+
+    /// Returns the mutable reference to the database used by the sender.
+    pub fn db_mut(&mut self) -> &mut DB {
+        &mut self.db
+    }
+
+    /// Returns the Ethereum operations sent but not confirmed yet.
+    pub fn ongoing_operations(&self) -> &VecDeque<ETHOperation> {
+        &self.ongoing_ops
+    }
+
     /// Main routine of `ETHSender`.
-    pub async fn run(mut self) {
+    ///
+    /// Once the shutdown is requested, the loop is left between the iterations: every operation
+    /// (as well as every sent transaction) is already persisted in the database, so the queue
+    /// will be restored on the next start.
+    pub async fn run(mut self, shutdown: ShutdownSignal) {
         loop {
+            if shutdown.is_requested() {
+                tracing::info!(
+                    "Ethereum sender stopped, {} operations are pending confirmation",
+                    self.ongoing_ops.len()
+                );
+                shutdown.mark_stopped();
+                return;
+            }
+
             time::timeout(self.options.tx_poll_period, self.load_new_operations())
                 .await
                 .unwrap_or_default();
 
             if self.options.is_enabled {
+                self.handle_admin_requests().await;
+                self.handle_contract_upgrade().await;
                 // ...and proceed them.
                 self.proceed_next_operations().await;
                 // Update the gas adjuster to maintain the up-to-date max gas price limit.
@@ -195,12 +312,12 @@ impl<ETH: EthereumInterface, DB: DatabaseInterface> ETHSender<ETH, DB> {
 
     /// Gets the incoming operations from the database and adds them to the
     /// transactions queue.
-    async fn load_new_operations(&mut self) {
+    pub async fn load_new_operations(&mut self) {
         let start = Instant::now();
         let mut connection = match self.db.acquire_connection().await {
             Ok(connection) => connection,
             Err(err) => {
-                log::warn!("Unable to connect to the database: {}", err);
+                tracing::warn!("Unable to connect to the database: {}", err);
                 return;
             }
         };
@@ -210,7 +327,7 @@ impl<ETH: EthereumInterface, DB: DatabaseInterface> ETHSender<ETH, DB> {
             .load_new_operations(&mut connection)
             .await
             .unwrap_or_else(|err| {
-                log::warn!("Unable to load new operations from the database: {}", err);
+                tracing::warn!("Unable to load new operations from the database: {}", err);
                 Vec::new()
             });
         drop(connection);
@@ -219,22 +336,449 @@ impl<ETH: EthereumInterface, DB: DatabaseInterface> ETHSender<ETH, DB> {
             self.add_operation_to_queue(operation);
         }
 
+        metrics::gauge!(
+            "eth_sender.queued_operations",
+            self.tx_queue.queued_operations() as f64
+        );
         metrics::histogram!("eth_sender.load_new_operations", start.elapsed());
     }
 
+    /// Handles the requests made through the admin API for the ongoing operations.
+    pub async fn handle_admin_requests(&mut self) {
+        self.handle_reload_request().await;
+        self.handle_operation_requests().await;
+        self.check_cancellations().await;
+        self.handle_resubmission_request().await;
+    }
+
+    /// Checks whether the reload of the unconfirmed operations was requested, and if so,
+    /// replaces the ongoing operations with the ones stored in the database, so the
+    /// confirmations of every one of them are checked from scratch.
+    async fn handle_reload_request(&mut self) {
+        let reloaded = match self.db.acquire_connection().await {
+            Ok(mut connection) => match self.db.take_reload_request(&mut connection).await {
+                Ok(true) => self
+                    .db
+                    .load_unconfirmed_operations(&mut connection)
+                    .await
+                    .map(Some),
+                Ok(false) => Ok(None),
+                Err(err) => Err(err),
+            },
+            Err(err) => Err(err),
+        };
+
+        match reloaded {
+            Ok(Some(ongoing_ops)) => {
+                tracing::info!(
+                    "Ongoing operations are reloaded: {} in memory, {} in the database",
+                    self.ongoing_ops.len(),
+                    ongoing_ops.len()
+                );
+                self.tx_queue.set_sent_pending_txs(ongoing_ops.len());
+                self.ongoing_ops = ongoing_ops;
+            }
+            Ok(None) => {}
+            Err(err) => tracing::warn!("Unable to reload the ongoing operations: {}", err),
+        }
+    }
+
+    /// Requeues or drops the ongoing operations as requested.
+    async fn handle_operation_requests(&mut self) {
+        let requests = match self.db.acquire_connection().await {
+            Ok(mut connection) => self.db.take_operation_requests(&mut connection).await,
+            Err(err) => Err(err),
+        };
+        let requests = requests.unwrap_or_else(|err| {
+            tracing::warn!("Unable to load the operation requests: {}", err);
+            Vec::new()
+        });
+
+        for (eth_op_id, request) in requests {
+            match self.handle_operation_request(eth_op_id, request).await {
+                Ok(()) => tracing::info!(
+                    "Request {:?} for Ethereum operation {} is accepted, the operation is being cancelled",
+                    request,
+                    eth_op_id
+                ),
+                Err(err) => tracing::warn!(
+                    "Request {:?} for Ethereum operation {} is rejected: {}",
+                    request,
+                    eth_op_id,
+                    err
+                ),
+            }
+        }
+    }
+
+    /// Starts the cancellation of the ongoing operation to requeue or drop it.
+    ///
+    /// Only the operation with the latest nonce can be handled, and only if none of its
+    /// transactions is mined. Since the sent transactions can still be mined, the operation
+    /// is not requeued or dropped right away: an empty transfer with the same nonce and
+    /// an increased gas price is sent instead, and the request is completed once this
+    /// transfer is confirmed (see `check_cancellations`).
+    async fn handle_operation_request(
+        &mut self,
+        eth_op_id: EthOpId,
+        request: OperationRequest,
+    ) -> anyhow::Result<()> {
+        let op = self
+            .ongoing_ops
+            .iter()
+            .find(|op| op.id == eth_op_id)
+            .ok_or_else(|| anyhow::anyhow!("operation is not ongoing"))?;
+        anyhow::ensure!(
+            !self.cancellations.contains_key(&eth_op_id),
+            "operation is already being cancelled"
+        );
+        anyhow::ensure!(
+            self.ongoing_ops.iter().all(|other| other.nonce <= op.nonce),
+            "operation doesn't have the latest nonce, the later operations must be handled first"
+        );
+        anyhow::ensure!(
+            request == OperationRequest::Drop || op.op.is_some(),
+            "operation has no aggregated operation"
+        );
+        for hash in &op.used_tx_hashes {
+            anyhow::ensure!(
+                self.ethereum.get_tx_status(hash).await?.is_none(),
+                "transaction {:#x} is already mined",
+                hash
+            );
+        }
+
+        let nonce = op.nonce;
+        let gas_price = self
+            .gas_adjuster
+            .get_gas_price(&self.ethereum, Some(op.last_used_gas_price))
+            .await?;
+        let signed_tx = self.ethereum.sign_cancel_tx(nonce, gas_price).await?;
+
+        // The cancellation is stored before sending, so it's not forgotten after the restart.
+        // If sending fails, the transaction is rolled back along with the cancellation.
+        let mut connection = self.db.acquire_connection().await?;
+        let mut transaction = connection.start_transaction().await?;
+        self.db
+            .save_cancellation(&mut transaction, eth_op_id, request, signed_tx.hash)
+            .await?;
+        self.db.check_leadership(&mut transaction).await?;
+        tracing::info!(
+            "Sending tx cancelling Ethereum operation {}: {}",
+            eth_op_id,
+            self.eth_tx_description(&signed_tx)
+        );
+        self.ethereum.send_tx(&signed_tx).await?;
+        transaction.commit().await?;
+        self.cancellations
+            .insert(eth_op_id, (request, signed_tx.hash));
+
+        Ok(())
+    }
+
+    /// Completes the requests for the cancelled operations: once the cancelling transaction
+    /// is confirmed, the operation is either requeued or dropped. If one of the operation
+    /// transactions is mined instead, the request is abandoned and the operation is tracked
+    /// as usual.
+    async fn check_cancellations(&mut self) {
+        let cancellations: Vec<_> = self
+            .cancellations
+            .iter()
+            .map(|(eth_op_id, (request, tx_hash))| (*eth_op_id, *request, *tx_hash))
+            .collect();
+
+        for (eth_op_id, request, tx_hash) in cancellations {
+            match self.check_cancellation(eth_op_id, request, tx_hash).await {
+                Ok(true) => {
+                    self.cancellations.remove(&eth_op_id);
+                }
+                Ok(false) => {}
+                Err(err) => tracing::warn!(
+                    "Unable to check the cancellation of Ethereum operation {}: {}",
+                    eth_op_id,
+                    err
+                ),
+            }
+        }
+    }
+
+    /// Checks the cancellation of the operation, returns `true` if it's not pending anymore.
+    async fn check_cancellation(
+        &mut self,
+        eth_op_id: EthOpId,
+        request: OperationRequest,
+        tx_hash: H256,
+    ) -> anyhow::Result<bool> {
+        let mut connection = self.db.acquire_connection().await?;
+        let position = match self.ongoing_ops.iter().position(|op| op.id == eth_op_id) {
+            Some(position) => position,
+            None => {
+                // Ongoing operations were reloaded without the operation.
+                self.db
+                    .remove_cancellation(&mut connection, eth_op_id)
+                    .await?;
+                return Ok(true);
+            }
+        };
+
+        for hash in &self.ongoing_ops[position].used_tx_hashes {
+            if self.ethereum.get_tx_status(hash).await?.is_some() {
+                tracing::warn!(
+                    "Request {:?} for Ethereum operation {} is abandoned: transaction {:#x} is mined",
+                    request,
+                    eth_op_id,
+                    hash
+                );
+                self.db
+                    .remove_cancellation(&mut connection, eth_op_id)
+                    .await?;
+                return Ok(true);
+            }
+        }
+
+        match self.ethereum.get_tx_status(&tx_hash).await? {
+            Some(status) if status.confirmations >= self.options.wait_confirmations => {}
+            _ => return Ok(false),
+        }
+
+        match request {
+            OperationRequest::Requeue => {
+                let operation = self.ongoing_ops[position]
+                    .op
+                    .clone()
+                    .ok_or_else(|| anyhow::anyhow!("operation has no aggregated operation"))?;
+                self.db
+                    .requeue_operation(&mut connection, eth_op_id)
+                    .await?;
+
+                // The operation is returned to the queue as if it was never sent,
+                // it will be sent with a new nonce.
+                let raw_tx = self.operation_to_raw_tx(&operation.1);
+                self.tx_queue
+                    .return_popped(TxData::from_operation(operation, raw_tx));
+            }
+            OperationRequest::Drop => {
+                self.db.drop_operation(&mut connection, eth_op_id).await?;
+                self.tx_queue.report_commitment();
+            }
+        }
+        let op = self
+            .ongoing_ops
+            .remove(position)
+            .expect("position is valid");
+        // The requeued operation is paid for once again when it's confirmed.
+        self.record_operation_cost(&op, &[tx_hash]).await;
+        tracing::info!(
+            "Ethereum operation {} is cancelled by tx {:#x}, request {:?} is completed",
+            eth_op_id,
+            tx_hash,
+            request
+        );
+
+        Ok(true)
+    }
+
+    /// Records the ETH spent on the mined transactions of the operation in the accounting
+    /// of its blocks. Accounting is best-effort, so the failures don't affect the processing
+    /// of the operation and are only reported to the log.
+    async fn record_operation_cost(&self, op: &ETHOperation, tx_hashes: &[H256]) {
+        if let Err(err) = self.try_record_operation_cost(op, tx_hashes).await {
+            tracing::warn!(
+                "Unable to record the cost of Ethereum operation {}: {}",
+                op.id,
+                err
+            );
+        }
+    }
+
+    async fn try_record_operation_cost(
+        &self,
+        op: &ETHOperation,
+        tx_hashes: &[H256],
+    ) -> anyhow::Result<()> {
+        let mut eth_spent = U256::zero();
+        for hash in tx_hashes {
+            if let Some(fee) = self.ethereum.get_tx_fee(hash).await? {
+                eth_spent += fee;
+            }
+        }
+        if eth_spent.is_zero() {
+            return Ok(());
+        }
+
+        let mut connection = self.db.acquire_connection().await?;
+        self.db
+            .record_operation_cost(&mut connection, op, eth_spent)
+            .await
+    }
+
+    /// Checks whether the resubmission of the ongoing operations was requested, and if so,
+    /// marks all of them as stuck, so the supplement transactions with the increased gas price
+    /// are sent on the next `proceed_next_operations` call.
+    async fn handle_resubmission_request(&mut self) {
+        let requested = match self.db.acquire_connection().await {
+            Ok(mut connection) => self.db.take_resubmission_request(&mut connection).await,
+            Err(err) => Err(err),
+        };
+
+        match requested {
+            Ok(true) => {
+                tracing::info!(
+                    "Resubmission of {} ongoing operations was requested",
+                    self.ongoing_ops.len()
+                );
+                for op in self.ongoing_ops.iter_mut() {
+                    op.last_deadline_block = 0;
+                }
+            }
+            Ok(false) => {}
+            Err(err) => tracing::warn!("Unable to check the resubmission request: {}", err),
+        }
+    }
+
+    /// Checks whether the contract was upgraded since the ABI in use was set, and if so,
+    /// either switches to the ABI of the new contract version or pauses sending new transactions.
+    ///
+    /// Sending is paused as soon as the upgrade preparation is started, since the transactions
+    /// may be mined after the contract is upgraded. The confirmed upgrade means that the ABI
+    /// in use matches the upgraded contract, unless the ABI of the new version is available.
+    /// Otherwise, the ABI is switched only if `auto_switch_abi` is enabled and the ABI of the new
+    /// version is available.
+    pub async fn handle_contract_upgrade(&mut self) {
+        let upgrade = match self.db.acquire_connection().await {
+            Ok(mut connection) => self.db.load_last_contract_upgrade(&mut connection).await,
+            Err(err) => Err(err),
+        };
+        let upgrade = match upgrade {
+            Ok(Some(upgrade)) if Some(upgrade.version_id) > self.contract_version => upgrade,
+            Ok(_) => {
+                // The upgrade which paused sending was cancelled during the preparation.
+                if let Some(upgrade) = self.pending_contract_upgrade.take() {
+                    tracing::info!(
+                        "Contract upgrade to version {} is cancelled, sending transactions",
+                        upgrade.version_id
+                    );
+                    metrics::gauge!("eth_sender.paused_by_contract_upgrade", 0.0);
+                }
+                return;
+            }
+            Err(err) => {
+                tracing::warn!("Unable to load the contract upgrades: {}", err);
+                return;
+            }
+        };
+
+        let switched = if !upgrade.completed {
+            false
+        } else if upgrade.confirmed || self.options.auto_switch_abi {
+            match self.ethereum.switch_contract_version(upgrade.version_id) {
+                Ok(()) => true,
+                Err(err) if upgrade.confirmed => {
+                    tracing::info!("{}, the ABI in use is confirmed by the operator", err);
+                    true
+                }
+                Err(err) => {
+                    if self.pending_contract_upgrade != Some(upgrade) {
+                        tracing::warn!("Unable to switch the ABI automatically: {}", err);
+                    }
+                    false
+                }
+            }
+        } else {
+            false
+        };
+
+        if switched {
+            // Operations in the queue were encoded with the outdated ABI.
+            let ethereum = &self.ethereum;
+            self.tx_queue
+                .reencode_operations(|op| Self::encode_operation(ethereum, op));
+            // So were the ongoing ones. If re-encoding fails, the switch is retried later.
+            if let Err(err) = self.reencode_ongoing_operations().await {
+                tracing::warn!("Unable to re-encode the ongoing operations: {}", err);
+                return;
+            }
+            self.contract_version = Some(upgrade.version_id);
+            self.pending_contract_upgrade = None;
+            tracing::info!(
+                "Contract upgrade to version {} is handled, sending transactions",
+                upgrade.version_id
+            );
+        } else if self.pending_contract_upgrade != Some(upgrade) {
+            self.pending_contract_upgrade = Some(upgrade);
+            if upgrade.completed {
+                vlog::critical!(
+                    "Contract is upgraded to version {}, new transactions are not sent \
+                     until the upgrade is confirmed by the operator",
+                    upgrade.version_id
+                );
+            } else {
+                tracing::warn!(
+                    "Contract upgrade to version {} is being prepared, new transactions \
+                     are not sent until the upgrade is completed",
+                    upgrade.version_id
+                );
+            }
+        }
+
+        let paused = if self.pending_contract_upgrade.is_some() {
+            1.0
+        } else {
+            0.0
+        };
+        metrics::gauge!("eth_sender.paused_by_contract_upgrade", paused);
+    }
+
+    /// Re-encodes the ongoing operations with the ABI in use and marks the ones with the changed
+    /// payload as stuck, so their transactions are replaced on the next `proceed_next_operations`
+    /// call with the same nonce. If one of the outdated transactions is mined anyway, it's
+    /// handled as usual.
+    async fn reencode_ongoing_operations(&mut self) -> anyhow::Result<()> {
+        let mut connection = self.db.acquire_connection().await?;
+        for op in self.ongoing_ops.iter_mut() {
+            let encoded_tx_data = match &op.op {
+                Some((_, operation)) => Self::encode_operation(&self.ethereum, operation),
+                None => continue,
+            };
+            if encoded_tx_data == op.encoded_tx_data {
+                continue;
+            }
+
+            self.db
+                .update_eth_tx_data(&mut connection, op.id, &encoded_tx_data)
+                .await?;
+            tracing::info!(
+                "Ethereum operation {} is re-encoded for the upgraded contract",
+                op.id
+            );
+            op.encoded_tx_data = encoded_tx_data;
+            op.last_deadline_block = 0;
+        }
+        Ok(())
+    }
+
+    /// Pops the next transaction to send from the queue, unless sending is paused until
+    /// the contract upgrade is confirmed or the ongoing operations are cancelled.
+    fn pop_next_tx(&mut self) -> Option<TxData> {
+        if self.pending_contract_upgrade.is_some() || !self.cancellations.is_empty() {
+            return None;
+        }
+        self.tx_queue.pop_front()
+    }
+
     /// This method does two main things:
     ///
     /// 1. Pops all the available transactions from the `TxQueue` and sends them.
     /// 2. Sifts all the ongoing operations, filtering the completed ones and
     ///   managing the rest (e.g. by sending a supplement txs for stuck operations).
-    async fn proceed_next_operations(&mut self) {
+    pub async fn proceed_next_operations(&mut self) {
         let start = Instant::now();
         // Queue for storing all the operations that were not finished at this iteration.
         let mut new_ongoing_ops = VecDeque::new();
 
-        while let Some(tx) = self.tx_queue.pop_front() {
+        while let Some(tx) = self.pop_next_tx() {
             if let Err(e) = self.initialize_operation(tx.clone()).await {
-                log::warn!(
+                tracing::warn!(
                     "[{}:{}:{}] Error while trying to complete uncommitted op: {}",
                     file!(),
                     line!(),
@@ -242,7 +786,7 @@ impl<ETH: EthereumInterface, DB: DatabaseInterface> ETHSender<ETH, DB> {
                     e
                 );
                 if e.to_string().contains(RATE_LIMIT_HTTP_CODE) {
-                    log::warn!(
+                    tracing::warn!(
                         "Received rate limit response, waiting for {}s",
                         RATE_LIMIT_BACKOFF_PERIOD.as_secs()
                     );
@@ -254,9 +798,19 @@ impl<ETH: EthereumInterface, DB: DatabaseInterface> ETHSender<ETH, DB> {
                 self.tx_queue.return_popped(tx);
             }
         }
+        metrics::gauge!(
+            "eth_sender.queued_operations",
+            self.tx_queue.queued_operations() as f64
+        );
 
         // Commit the next operations (if any).
         while let Some(mut current_op) = self.ongoing_ops.pop_front() {
+            // Operations being cancelled must not be resent.
+            if self.cancellations.contains_key(&current_op.id) {
+                new_ongoing_ops.push_back(current_op);
+                continue;
+            }
+
             // We perform a commitment step here. In case of error, we suppose that this is some
             // network issue which won't appear the next time, so we report the situation to the
             // log and consider the operation pending (meaning that we won't process it on this
@@ -264,9 +818,9 @@ impl<ETH: EthereumInterface, DB: DatabaseInterface> ETHSender<ETH, DB> {
             let commitment = match self.perform_commitment_step(&mut current_op).await {
                 Ok(commitment) => commitment,
                 Err(e) => {
-                    log::warn!("Error while trying to complete uncommitted op: {}", e);
+                    tracing::warn!("Error while trying to complete uncommitted op: {}", e);
                     if e.to_string().contains(RATE_LIMIT_HTTP_CODE) {
-                        log::warn!(
+                        tracing::warn!(
                             "Received rate limit response, waiting for {}s",
                             RATE_LIMIT_BACKOFF_PERIOD.as_secs()
                         );
@@ -280,6 +834,7 @@ impl<ETH: EthereumInterface, DB: DatabaseInterface> ETHSender<ETH, DB> {
                 OperationCommitment::Committed => {
                     // Free a slot for the next tx in the queue.
                     self.tx_queue.report_commitment();
+                    metrics::counter!("eth_sender.confirmed_operations", 1);
                 }
                 OperationCommitment::Pending => {
                     // Poll this operation on the next iteration.
@@ -295,10 +850,23 @@ impl<ETH: EthereumInterface, DB: DatabaseInterface> ETHSender<ETH, DB> {
 
         // Store the ongoing operations for the next round.
         self.ongoing_ops = new_ongoing_ops;
+        metrics::gauge!(
+            "eth_sender.ongoing_operations",
+            self.ongoing_ops.len() as f64
+        );
         metrics::histogram!("eth_sender.proceed_next_operations", start.elapsed());
     }
 
     /// Stores the new operation in the database and sends the corresponding transaction.
+    #[tracing::instrument(
+        name = "eth_operation",
+        skip(self, tx),
+        fields(
+            action = %tx.op_type.to_string(),
+            first_block = tx.operation.1.get_block_range().0,
+            last_block = tx.operation.1.get_block_range().1
+        )
+    )]
     async fn initialize_operation(&mut self, tx: TxData) -> anyhow::Result<()> {
         let current_block = self.ethereum.block_number().await?;
         let deadline_block = self.get_deadline_block(current_block);
@@ -340,7 +908,10 @@ impl<ETH: EthereumInterface, DB: DatabaseInterface> ETHSender<ETH, DB> {
             };
 
             // Sign the transaction.
-            let signed_tx = Self::sign_new_tx(&self.ethereum, &new_op).await?;
+            let withdrawal_gas_limits =
+                self.db.load_withdrawal_gas_limits(&mut transaction).await?;
+            let signed_tx =
+                Self::sign_new_tx(&self.ethereum, &new_op, &withdrawal_gas_limits).await?;
 
             // With signed tx, update the hash in the operation entry and in the db.
             new_op.used_tx_hashes.push(signed_tx.hash);
@@ -351,21 +922,26 @@ impl<ETH: EthereumInterface, DB: DatabaseInterface> ETHSender<ETH, DB> {
             (new_op, signed_tx)
         };
 
+        // The former leader must not send the transactions with the nonces that may be
+        // already used by the new one.
+        self.db.check_leadership(&mut transaction).await?;
+
         // We should store the operation as `ongoing` **before** sending it as well,
         // so if sending will fail, we won't forget about it.
         self.ongoing_ops.push_back(new_op.clone());
 
         // After storing all the tx data in the database, we can finally send the tx.
-        log::info!(
+        tracing::info!(
             "Sending new tx: [ETH Operation <id: {}, type: {:?}>. ETH tx: {}. ZKSync operation: {}]",
             new_op.id, new_op.op_type, self.eth_tx_description(&signed_tx), self.zksync_operation_description(&new_op),
         );
+        Self::trace_zksync_blocks(&new_op, &signed_tx.hash, "Block operation sent to Ethereum");
         self.ethereum.send_tx(&signed_tx).await.unwrap_or_else(|e| {
             // Sending tx error is not critical: this will result in transaction being considered stuck,
             // and resent. We can't do anything about this failure either, since it's most probably is not
             // related to the node logic, so we just log this error and pretend to have this operation
             // processed.
-            log::warn!("Error while sending the operation: {}", e);
+            tracing::warn!("Error while sending the operation: {}", e);
         });
 
         transaction.commit().await?;
@@ -400,12 +976,31 @@ impl<ETH: EthereumInterface, DB: DatabaseInterface> ETHSender<ETH, DB> {
         }
     }
 
+    /// Reports the event for every zkSync block affected by the operation, so the blocks
+    /// can be traced from the state keeper to the Ethereum.
+    fn trace_zksync_blocks(operation: &ETHOperation, eth_tx_hash: &H256, event: &str) {
+        if let Some((_, op)) = &operation.op {
+            let (first_block, last_block) = op.get_block_range();
+            for block in first_block..=last_block {
+                tracing::info!(
+                    block,
+                    action = %op.get_action_type().to_string(),
+                    eth_op_id = operation.id,
+                    eth_tx_hash = %format!("{:#x}", eth_tx_hash),
+                    "{}",
+                    event
+                );
+            }
+        }
+    }
+
     /// Handles the ongoing operation by checking its state and doing the following:
     /// - If the transaction is either pending or completed, stops the execution (as
     ///   there is nothing to do with the operation yet).
     /// - If the transaction is stuck, sends a supplement transaction for it.
     /// - If the transaction is failed, handles the failure according to the failure
     ///   processing policy.
+    #[tracing::instrument(name = "eth_operation", skip(self, op), fields(eth_op_id = op.id))]
     async fn perform_commitment_step(
         &mut self,
         op: &mut ETHOperation,
@@ -437,6 +1032,12 @@ impl<ETH: EthereumInterface, DB: DatabaseInterface> ETHSender<ETH, DB> {
                     return Ok(OperationCommitment::Pending);
                 }
                 TxCheckOutcome::Committed => {
+                    let onchain_withdrawals = match &op.op {
+                        Some((_, AggregatedOperation::ExecuteBlocks(_))) => {
+                            self.ethereum.get_onchain_withdrawals(tx_hash).await?
+                        }
+                        _ => Vec::new(),
+                    };
                     let mut connection = self.db.acquire_connection().await?;
                     let mut transaction = connection.start_transaction().await?;
 
@@ -461,18 +1062,23 @@ impl<ETH: EthereumInterface, DB: DatabaseInterface> ETHSender<ETH, DB> {
                         .is_previous_operation_confirmed(&mut transaction, &op)
                         .await?
                     {
-                        log::info!("ETH Operation <id: {}> is confirmed ahead of time, considering it pending for now", op.id);
+                        tracing::info!("ETH Operation <id: {}> is confirmed ahead of time, considering it pending for now", op.id);
                         return Ok(OperationCommitment::Pending);
                     }
 
-                    log::info!(
+                    tracing::info!(
                         "Confirmed: [ETH Operation <id: {}, type: {:?}>. Tx hash: <{:#x}>. ZKSync operation: {}]",
                         op.id, op.op_type, tx_hash, self.zksync_operation_description(op),
                     );
+                    Self::trace_zksync_blocks(op, tx_hash, "Block operation confirmed on Ethereum");
                     self.db
-                        .confirm_operation(&mut transaction, tx_hash, op)
+                        .confirm_operation(&mut transaction, tx_hash, op, &onchain_withdrawals)
                         .await?;
                     transaction.commit().await?;
+
+                    // Transactions replaced by the mined one are not mined, while the reverted
+                    // ones are paid for as well.
+                    self.record_operation_cost(op, &op.used_tx_hashes).await;
                     return Ok(OperationCommitment::Committed);
                 }
                 TxCheckOutcome::Stuck => {
@@ -480,7 +1086,7 @@ impl<ETH: EthereumInterface, DB: DatabaseInterface> ETHSender<ETH, DB> {
                     // the last entry of the list, a new tx will be sent.
                 }
                 TxCheckOutcome::Failed(receipt) => {
-                    log::warn!(
+                    tracing::warn!(
                         "ETH transaction failed: tx: {:#x}, op_type: {:?}, op: {:?}; tx_receipt: {:#?} ",
                         tx_hash,
                         op.op_type,
@@ -514,8 +1120,9 @@ impl<ETH: EthereumInterface, DB: DatabaseInterface> ETHSender<ETH, DB> {
         self.db
             .add_hash_entry(&mut transaction, op.id, &new_tx.hash)
             .await?;
+        self.db.check_leadership(&mut transaction).await?;
 
-        log::info!(
+        tracing::info!(
             "Stuck tx processing: sending tx for op, eth_op_id: {}; ETH tx: {}",
             op.id,
             self.eth_tx_description(&new_tx),
@@ -530,14 +1137,18 @@ impl<ETH: EthereumInterface, DB: DatabaseInterface> ETHSender<ETH, DB> {
     /// Handles a transaction execution failure by reporting the issue to the log
     /// and terminating the node.
     async fn failure_handler(&self, receipt: &TransactionReceipt) -> ! {
-        log::error!(
+        vlog::critical!(
+            "Ethereum transaction {:#x} unexpectedly failed, the Ethereum sender is stopped",
+            receipt.transaction_hash
+        );
+        tracing::error!(
             "Ethereum transaction unexpectedly failed. Receipt: {:#?}",
             receipt
         );
         if let Some(reason) = self.ethereum.failure_reason(receipt.transaction_hash).await {
-            log::error!("Failure reason for Ethereum tx: {:#?}", reason);
+            tracing::error!("Failure reason for Ethereum tx: {:#?}", reason);
         } else {
-            log::error!("Unable to receive failure reason for Ethereum tx");
+            tracing::error!("Unable to receive failure reason for Ethereum tx");
         }
         panic!("Cannot operate after unexpected TX failure");
     }
@@ -596,7 +1207,11 @@ impl<ETH: EthereumInterface, DB: DatabaseInterface> ETHSender<ETH, DB> {
     }
 
     /// Creates a new Ethereum operation.
-    async fn sign_new_tx(ethereum: &ETH, op: &ETHOperation) -> anyhow::Result<SignedCallResult> {
+    async fn sign_new_tx(
+        ethereum: &ETH,
+        op: &ETHOperation,
+        withdrawal_gas_limits: &HashMap<TokenId, u64>,
+    ) -> anyhow::Result<SignedCallResult> {
         let tx_options = {
             let mut options = Options::default();
             options.nonce = Some(op.nonce);
@@ -604,7 +1219,7 @@ impl<ETH: EthereumInterface, DB: DatabaseInterface> ETHSender<ETH, DB> {
 
             // We set the gas limit for commit / verify operations as pre-calculated estimation.
             // This estimation is a higher bound based on a pre-calculated cost of every operation in the block.
-            let gas_limit = Self::gas_limit_for_op(op);
+            let gas_limit = Self::gas_limit_for_op(op, withdrawal_gas_limits);
 
             assert!(
                 gas_limit > 0.into(),
@@ -612,7 +1227,7 @@ impl<ETH: EthereumInterface, DB: DatabaseInterface> ETHSender<ETH, DB> {
                 op
             );
 
-            log::info!(
+            tracing::info!(
                 "Gas limit for <ETH Operation id: {}> is {}",
                 op.id,
                 gas_limit
@@ -631,9 +1246,25 @@ impl<ETH: EthereumInterface, DB: DatabaseInterface> ETHSender<ETH, DB> {
     }
 
     /// Calculates the gas limit for transaction to be send, depending on the type of operation.
-    fn gas_limit_for_op(op: &ETHOperation) -> U256 {
+    ///
+    /// Withdrawals are completed along with the blocks execution, so the withdrawal gas limits
+    /// of the tokens requiring more gas than the default one are added on top for every withdrawal.
+    /// These limits only keep the transaction from running out of gas: every ERC20 transfer is
+    /// still capped by the contract at `ERC20_WITHDRAWAL_GAS_LIMIT`, so the withdrawals of tokens
+    /// requiring more gas are stored as the pending balances.
+    fn gas_limit_for_op(op: &ETHOperation, withdrawal_gas_limits: &HashMap<TokenId, u64>) -> U256 {
         // TODO:
-        U256::from(5_000_000)
+        let mut gas_limit = U256::from(5_000_000);
+        if let Some((_, AggregatedOperation::ExecuteBlocks(operation))) = &op.op {
+            let withdrawals_gas: u64 = operation
+                .blocks
+                .iter()
+                .flat_map(Block::withdrawal_tokens)
+                .filter_map(|token| withdrawal_gas_limits.get(&token))
+                .sum();
+            gas_limit += U256::from(withdrawals_gas);
+        }
+        gas_limit
         // match op.op_type {
         //     OperationType::Commit => {
         //         op.op
@@ -685,7 +1316,11 @@ impl<ETH: EthereumInterface, DB: DatabaseInterface> ETHSender<ETH, DB> {
             .get_gas_price(&self.ethereum, Some(old_tx_gas_price))
             .await?;
         let nonce = stuck_tx.nonce;
-        let gas_limit = Self::gas_limit_for_op(stuck_tx);
+        let withdrawal_gas_limits = {
+            let mut connection = self.db.acquire_connection().await?;
+            self.db.load_withdrawal_gas_limits(&mut connection).await?
+        };
+        let gas_limit = Self::gas_limit_for_op(stuck_tx, &withdrawal_gas_limits);
 
         assert!(
             gas_limit > 0.into(),
@@ -693,7 +1328,7 @@ impl<ETH: EthereumInterface, DB: DatabaseInterface> ETHSender<ETH, DB> {
             stuck_tx
         );
 
-        log::info!(
+        tracing::info!(
             "Replacing tx: hash: {:#x}, old_gas: {}, new_gas: {}, used nonce: {}, gas limit: {}",
             stuck_tx.used_tx_hashes.last().unwrap(),
             old_tx_gas_price,
@@ -711,23 +1346,25 @@ impl<ETH: EthereumInterface, DB: DatabaseInterface> ETHSender<ETH, DB> {
 
     /// Encodes the operation data to the Ethereum tx payload (not signs it!).
     fn operation_to_raw_tx(&self, op: &AggregatedOperation) -> Vec<u8> {
+        Self::encode_operation(&self.ethereum, op)
+    }
+
+    fn encode_operation(ethereum: &ETH, op: &AggregatedOperation) -> Vec<u8> {
         match op {
             AggregatedOperation::CommitBlocks(operation) => {
                 let args = operation.get_eth_tx_args();
-                self.ethereum
-                    .encode_tx_data("commitBlocks", args.as_slice())
+                ethereum.encode_tx_data("commitBlocks", args.as_slice())
             }
             AggregatedOperation::CreateProofBlocks(..) => {
                 panic!("Eth sender should ignore CreateProofBlocks");
             } // not for eth sender
             AggregatedOperation::PublishProofBlocksOnchain(operation) => {
                 let args = operation.get_eth_tx_args();
-                self.ethereum.encode_tx_data("proveBlocks", args.as_slice())
+                ethereum.encode_tx_data("proveBlocks", args.as_slice())
             }
             AggregatedOperation::ExecuteBlocks(operation) => {
                 let args = operation.get_eth_tx_args();
-                self.ethereum
-                    .encode_tx_data("executeBlocks", args.as_slice())
+                ethereum.encode_tx_data("executeBlocks", args.as_slice())
             }
         }
     }
@@ -746,15 +1383,19 @@ pub fn run_eth_sender(
     pool: ConnectionPool,
     eth_client_options: EthClientOptions,
     eth_sender_options: EthSenderOptions,
+    leader_epoch: Option<i64>,
+    shutdown: ShutdownSignal,
 ) -> JoinHandle<()> {
+    register_metrics("Ethereum sender", METRICS);
+
     let ethereum =
         EthereumHttpClient::new(&eth_client_options).expect("Ethereum client creation failed");
 
-    let db = Database::new(pool);
+    let db = Database::new(pool, leader_epoch);
 
     tokio::spawn(async move {
         let eth_sender = ETHSender::new(eth_sender_options, db, ethereum).await;
 
-        eth_sender.run().await
+        eth_sender.run(shutdown).await
     })
 }
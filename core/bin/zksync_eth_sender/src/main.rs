@@ -1,15 +1,17 @@
 use futures::{channel::mpsc, executor::block_on, SinkExt, StreamExt};
 use std::cell::RefCell;
-use zksync_config::{EthClientOptions, EthSenderOptions};
+use zksync_config::{ConfigurationOptions, EthClientOptions, EthSenderOptions};
 use zksync_eth_sender::run_eth_sender;
+use zksync_prometheus_exporter::install_prometheus_exporter;
 use zksync_storage::ConnectionPool;
+use zksync_utils::shutdown::ShutdownSignal;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     // `eth_sender` doesn't require many connections to the database.
     const ETH_SENDER_CONNECTION_POOL_SIZE: u32 = 2;
 
-    env_logger::init();
+    vlog::init();
 
     // handle ctrl+c
     let (stop_signal_sender, mut stop_signal_receiver) = mpsc::channel(256);
@@ -25,17 +27,41 @@ async fn main() -> anyhow::Result<()> {
     let pool = ConnectionPool::new(Some(ETH_SENDER_CONNECTION_POOL_SIZE));
     let eth_client_options = EthClientOptions::from_env();
     let eth_sender_options = EthSenderOptions::from_env();
+    let config_options = ConfigurationOptions::from_env();
+    let shutdown_timeout = config_options.shutdown_timeout;
+    let shutdown = ShutdownSignal::new();
+    let prometheus_handle = install_prometheus_exporter(config_options.prometheus_export_port);
 
-    let task_handle = run_eth_sender(pool, eth_client_options, eth_sender_options);
+    let task_handle = run_eth_sender(
+        pool,
+        eth_client_options,
+        eth_sender_options,
+        None,
+        shutdown.clone(),
+    );
 
     tokio::select! {
         _ = async { task_handle.await } => {
             panic!("Ethereum sender actors aren't supposed to finish their execution")
         },
+        _ = async { prometheus_handle.await } => {
+            panic!("Prometheus exporter actors aren't supposed to finish their execution")
+        },
         _ = async { stop_signal_receiver.next().await } => {
-            log::warn!("Stop signal received, shutting down");
+            tracing::warn!("Stop signal received, shutting down");
         }
     };
 
+    shutdown.request();
+    if tokio::time::timeout(shutdown_timeout, shutdown.stopped())
+        .await
+        .is_err()
+    {
+        tracing::warn!(
+            "Ethereum sender didn't stop in {:?}, exiting anyway",
+            shutdown_timeout
+        );
+    }
+
     Ok(())
 }
@@ -0,0 +1,598 @@
+//! Mock database for `ETHSender`.
+
+// Built-in deps
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+// External uses
+use tokio::sync::RwLock;
+use zksync_basic_types::{H256, U256};
+// Workspace uses
+use zksync_storage::StorageProcessor;
+use zksync_types::{
+    aggregated_operations::{AggregatedActionType, AggregatedOperation},
+    ethereum::{ETHOperation, EthOpId, InsertedOperationResponse, OnchainWithdrawal},
+    TokenId,
+};
+// Local uses
+use crate::database::{ContractUpgrade, DatabaseInterface, OperationRequest};
+use crate::transactions::ETHStats;
+
+/// Mock database is capable of recording all the incoming requests for the further analysis.
+///
+/// Besides the recording, the following failures can be injected:
+///
+/// - Write failures (`fail_next_writes`): the next writes to the database return an error.
+///   Note that unlike the actual database, the mock doesn't roll back the writes made earlier
+///   within the same transaction.
+/// - Nonce races (`advance_nonce`): nonces are taken by another sender, so the next
+///   operation is stored with a nonce that skips them.
+#[derive(Debug, Default)]
+pub struct MockDatabase {
+    restore_state: VecDeque<ETHOperation>,
+    unconfirmed_operations: RwLock<BTreeMap<i64, ETHOperation>>,
+    unprocessed_operations: RwLock<BTreeMap<i64, AggregatedOperation>>,
+    confirmed_operations: RwLock<BTreeMap<i64, ETHOperation>>,
+    dropped_operations: RwLock<BTreeMap<i64, ETHOperation>>,
+    nonce: RwLock<i64>,
+    gas_price_limit: RwLock<U256>,
+    pending_op_id: RwLock<EthOpId>,
+    stats: RwLock<ETHStats>,
+    resubmission_requested: RwLock<bool>,
+    reload_requested: RwLock<bool>,
+    operation_requests: RwLock<Vec<(EthOpId, OperationRequest)>>,
+    cancellations: RwLock<BTreeMap<EthOpId, (OperationRequest, H256)>>,
+    contract_upgrade: RwLock<Option<ContractUpgrade>>,
+    operation_costs: RwLock<HashMap<EthOpId, U256>>,
+    failing_writes: AtomicUsize,
+    leadership_lost: AtomicBool,
+}
+
+impl MockDatabase {
+    /// Creates a database with emulation of previously stored uncommitted requests.
+    pub fn with_restorable_state(
+        restore_state: impl IntoIterator<Item = ETHOperation>,
+        stats: ETHStats,
+    ) -> Self {
+        let restore_state: VecDeque<_> = restore_state.into_iter().collect();
+        let nonce = restore_state
+            .iter()
+            .fold(0, |acc, op| acc + op.used_tx_hashes.len());
+        let pending_op_id = restore_state.len();
+
+        let unconfirmed_operations: BTreeMap<i64, ETHOperation> =
+            restore_state.iter().map(|op| (op.id, op.clone())).collect();
+
+        let gas_price_limit: u64 = zksync_utils::parse_env("ETH_GAS_PRICE_DEFAULT_LIMIT");
+
+        Self {
+            restore_state,
+            nonce: RwLock::new(nonce as i64),
+            gas_price_limit: RwLock::new(gas_price_limit.into()),
+            pending_op_id: RwLock::new(pending_op_id as EthOpId),
+            stats: RwLock::new(stats),
+            unconfirmed_operations: RwLock::new(unconfirmed_operations),
+            ..Default::default()
+        }
+    }
+
+    pub async fn update_gas_price_limit(&self, value: U256) -> anyhow::Result<()> {
+        let mut gas_price_limit = self.gas_price_limit.write().await;
+        (*gas_price_limit) = value;
+
+        Ok(())
+    }
+
+    /// Simulates the resubmission request made through the admin API.
+    pub async fn request_resubmission(&self) {
+        *self.resubmission_requested.write().await = true;
+    }
+
+    /// Simulates the reload request made through the admin API.
+    pub async fn request_reload(&self) {
+        *self.reload_requested.write().await = true;
+    }
+
+    /// Simulates the request to requeue or drop the operation made through the admin API.
+    pub async fn request_operation_action(&self, eth_op_id: EthOpId, request: OperationRequest) {
+        self.operation_requests
+            .write()
+            .await
+            .push((eth_op_id, request));
+    }
+
+    /// Simulates the contract upgrade preparation started on Ethereum and stored by
+    /// the Ethereum watcher.
+    pub async fn prepare_contract_upgrade(&self, version_id: u64) {
+        *self.contract_upgrade.write().await = Some(ContractUpgrade {
+            version_id,
+            completed: false,
+            confirmed: false,
+        });
+    }
+
+    /// Simulates the contract upgrade cancelled on Ethereum during the preparation.
+    pub async fn cancel_contract_upgrade(&self) {
+        *self.contract_upgrade.write().await = None;
+    }
+
+    /// Simulates the contract upgrade completed on Ethereum and stored by the Ethereum watcher.
+    pub async fn complete_contract_upgrade(&self, version_id: u64) {
+        *self.contract_upgrade.write().await = Some(ContractUpgrade {
+            version_id,
+            completed: true,
+            confirmed: false,
+        });
+    }
+
+    /// Simulates the confirmation of the completed contract upgrade made by the operator.
+    pub async fn confirm_contract_upgrade(&self) {
+        if let Some(upgrade) = self.contract_upgrade.write().await.as_mut() {
+            upgrade.confirmed = true;
+        }
+    }
+
+    /// Simulates the operation of OperationsSchema, creates a new operation in the database.
+    pub async fn send_operation(&mut self, op: (i64, AggregatedOperation)) -> anyhow::Result<()> {
+        let (id, op) = op;
+
+        self.unprocessed_operations.write().await.insert(id, op);
+
+        Ok(())
+    }
+
+    /// Makes the next `count` writes to the database fail.
+    pub fn fail_next_writes(&self, count: usize) {
+        self.failing_writes.fetch_add(count, Ordering::SeqCst);
+    }
+
+    /// Simulates another server replica taking over the leadership.
+    pub fn lose_leadership(&self) {
+        self.leadership_lost.store(true, Ordering::SeqCst);
+    }
+
+    /// Simulates another sender taking the next `count` nonces.
+    pub async fn advance_nonce(&self, count: i64) {
+        *self.nonce.write().await += count;
+    }
+
+    /// Ensures that the provided transaction is stored in the database and not confirmed yet.
+    pub async fn assert_stored(&self, tx: &ETHOperation) {
+        assert_eq!(
+            self.unconfirmed_operations.read().await.get(&tx.id),
+            Some(tx)
+        );
+
+        assert!(self.confirmed_operations.read().await.get(&tx.id).is_none());
+    }
+
+    /// Ensures that the provided transaction is stored as confirmed.
+    pub async fn assert_confirmed(&self, tx: &ETHOperation) {
+        assert_eq!(self.confirmed_operations.read().await.get(&tx.id), Some(tx));
+
+        assert!(self
+            .unconfirmed_operations
+            .read()
+            .await
+            .get(&tx.id)
+            .is_none());
+    }
+
+    /// Ensures that the ETH spent on the provided operation is recorded.
+    pub async fn assert_cost_recorded(&self, tx: &ETHOperation, eth_spent: U256) {
+        assert_eq!(
+            self.operation_costs.read().await.get(&tx.id),
+            Some(&eth_spent)
+        );
+    }
+
+    /// Returns all the stored operations that are not confirmed yet.
+    pub async fn unconfirmed_operations(&self) -> Vec<ETHOperation> {
+        self.unconfirmed_operations
+            .read()
+            .await
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    /// Returns all the operations dropped through the admin API.
+    pub async fn dropped_operations(&self) -> Vec<ETHOperation> {
+        self.dropped_operations
+            .read()
+            .await
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the stored cancellations of the operations.
+    pub async fn cancellations(&self) -> Vec<(EthOpId, OperationRequest, H256)> {
+        self.cancellations
+            .read()
+            .await
+            .iter()
+            .map(|(eth_op_id, (request, tx_hash))| (*eth_op_id, *request, *tx_hash))
+            .collect()
+    }
+
+    /// Returns all the confirmed operations.
+    pub async fn confirmed_operations(&self) -> Vec<ETHOperation> {
+        self.confirmed_operations
+            .read()
+            .await
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    async fn next_nonce(&self) -> anyhow::Result<i64> {
+        let mut nonce = self.nonce.write().await;
+        let old_value = *nonce;
+        *nonce = old_value + 1;
+
+        Ok(old_value)
+    }
+
+    /// Removes the latest unconfirmed operation along with its cancellation.
+    /// The nonce is not released, since it's taken by the cancelling transaction.
+    async fn remove_latest_unconfirmed(&self, eth_op_id: EthOpId) -> anyhow::Result<ETHOperation> {
+        let mut ops = self.unconfirmed_operations.write().await;
+        let op = ops
+            .get(&eth_op_id)
+            .ok_or_else(|| anyhow::anyhow!("Ethereum operation {} is not stored", eth_op_id))?;
+        anyhow::ensure!(
+            ops.values().all(|other| other.nonce <= op.nonce),
+            "Ethereum operation {} isn't the latest one",
+            eth_op_id
+        );
+
+        self.cancellations.write().await.remove(&eth_op_id);
+        Ok(ops.remove(&eth_op_id).unwrap())
+    }
+
+    /// Returns an error if the write is chosen to fail.
+    fn check_write(&self) -> anyhow::Result<()> {
+        let injected = self
+            .failing_writes
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |count| {
+                count.checked_sub(1)
+            })
+            .is_ok();
+        if injected {
+            anyhow::bail!("Injected database write failure");
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl DatabaseInterface for MockDatabase {
+    /// Creates a new database connection, used as a stub
+    /// and nothing will be sent through this connection.
+    async fn acquire_connection(&self) -> anyhow::Result<StorageProcessor<'_>> {
+        StorageProcessor::establish_connection().await
+    }
+
+    /// Returns all unprocessed operations and then deletes them.
+    async fn load_new_operations(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<(i64, AggregatedOperation)>> {
+        let mut unprocessed_operations = self.unprocessed_operations.write().await;
+
+        Ok(std::mem::take(&mut *unprocessed_operations)
+            .into_iter()
+            .collect())
+    }
+
+    async fn update_gas_price_params(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        gas_price_limit: U256,
+        _average_gas_price: U256,
+    ) -> anyhow::Result<()> {
+        self.check_write()?;
+        let mut new_gas_price_limit = self.gas_price_limit.write().await;
+        *new_gas_price_limit = gas_price_limit;
+
+        Ok(())
+    }
+
+    async fn restore_state(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<(VecDeque<ETHOperation>, Vec<(i64, AggregatedOperation)>)> {
+        Ok((
+            self.restore_state.clone(),
+            self.load_new_operations(connection).await?,
+        ))
+    }
+
+    async fn save_new_eth_tx(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        op_type: AggregatedActionType,
+        op: Option<(i64, AggregatedOperation)>,
+        deadline_block: i64,
+        used_gas_price: U256,
+        raw_tx: Vec<u8>,
+    ) -> anyhow::Result<InsertedOperationResponse> {
+        self.check_write()?;
+
+        let id = {
+            let mut pending_op_id = self.pending_op_id.write().await;
+            let id = *pending_op_id;
+            *pending_op_id = id + 1;
+            id
+        };
+        let nonce = self.next_nonce().await?;
+
+        // Store with the assigned ID.
+        let state = ETHOperation {
+            id,
+            op_type,
+            op,
+            nonce: nonce.into(),
+            last_deadline_block: deadline_block as u64,
+            last_used_gas_price: used_gas_price,
+            used_tx_hashes: vec![],
+            encoded_tx_data: raw_tx,
+            confirmed: false,
+            final_hash: None,
+        };
+
+        self.unconfirmed_operations.write().await.insert(id, state);
+
+        Ok(InsertedOperationResponse {
+            id,
+            nonce: nonce.into(),
+        })
+    }
+
+    /// Adds a tx hash entry associated with some Ethereum operation to the database.
+    async fn add_hash_entry(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        eth_op_id: i64,
+        hash: &H256,
+    ) -> anyhow::Result<()> {
+        self.check_write()?;
+
+        let mut ops = self.unconfirmed_operations.write().await;
+        let op = ops
+            .get_mut(&eth_op_id)
+            .expect("Attempt to update tx that is not unconfirmed");
+        op.used_tx_hashes.push(*hash);
+
+        Ok(())
+    }
+
+    async fn update_eth_tx(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        eth_op_id: EthOpId,
+        new_deadline_block: i64,
+        new_gas_value: U256,
+    ) -> anyhow::Result<()> {
+        self.check_write()?;
+
+        let mut ops = self.unconfirmed_operations.write().await;
+        let op = ops
+            .get_mut(&eth_op_id)
+            .expect("Attempt to update tx that is not unconfirmed");
+        op.last_deadline_block = new_deadline_block as u64;
+        op.last_used_gas_price = new_gas_value;
+
+        Ok(())
+    }
+
+    async fn update_eth_tx_data(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        eth_op_id: EthOpId,
+        encoded_tx_data: &[u8],
+    ) -> anyhow::Result<()> {
+        self.check_write()?;
+
+        let mut ops = self.unconfirmed_operations.write().await;
+        let op = ops
+            .get_mut(&eth_op_id)
+            .expect("Attempt to update tx that is not unconfirmed");
+        op.encoded_tx_data = encoded_tx_data.to_vec();
+
+        Ok(())
+    }
+
+    async fn confirm_operation(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        hash: &H256,
+        _op: &ETHOperation,
+        _onchain_withdrawals: &[OnchainWithdrawal],
+    ) -> anyhow::Result<()> {
+        self.check_write()?;
+
+        let mut unconfirmed_operations = self.unconfirmed_operations.write().await;
+        let op_idx = unconfirmed_operations
+            .values()
+            .find(|operation| operation.used_tx_hashes.contains(hash))
+            .map(|operation| operation.id)
+            .expect("Request to confirm operation that was not stored");
+
+        let mut operation = unconfirmed_operations.remove(&op_idx).unwrap();
+        operation.confirmed = true;
+        operation.final_hash = Some(*hash);
+        self.confirmed_operations
+            .write()
+            .await
+            .insert(op_idx, operation);
+
+        Ok(())
+    }
+
+    async fn record_operation_cost(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        op: &ETHOperation,
+        eth_spent: U256,
+    ) -> anyhow::Result<()> {
+        self.check_write()?;
+
+        self.operation_costs.write().await.insert(op.id, eth_spent);
+        Ok(())
+    }
+
+    async fn load_gas_price_limit(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<U256> {
+        Ok(*self.gas_price_limit.read().await)
+    }
+
+    async fn load_stats(&self, _connection: &mut StorageProcessor<'_>) -> anyhow::Result<ETHStats> {
+        Ok(self.stats.read().await.clone())
+    }
+
+    async fn load_withdrawal_gas_limits(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<HashMap<TokenId, u64>> {
+        Ok(HashMap::new())
+    }
+
+    async fn take_resubmission_request(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<bool> {
+        let mut requested = self.resubmission_requested.write().await;
+        Ok(std::mem::take(&mut *requested))
+    }
+
+    async fn take_reload_request(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<bool> {
+        let mut requested = self.reload_requested.write().await;
+        Ok(std::mem::take(&mut *requested))
+    }
+
+    async fn take_operation_requests(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<(EthOpId, OperationRequest)>> {
+        let mut requests = self.operation_requests.write().await;
+        Ok(std::mem::take(&mut *requests))
+    }
+
+    async fn load_unconfirmed_operations(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<VecDeque<ETHOperation>> {
+        Ok(self.unconfirmed_operations().await.into())
+    }
+
+    async fn requeue_operation(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        eth_op_id: EthOpId,
+    ) -> anyhow::Result<()> {
+        self.check_write()?;
+        self.remove_latest_unconfirmed(eth_op_id).await?;
+
+        Ok(())
+    }
+
+    async fn drop_operation(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        eth_op_id: EthOpId,
+    ) -> anyhow::Result<()> {
+        self.check_write()?;
+        let op = self.remove_latest_unconfirmed(eth_op_id).await?;
+        self.dropped_operations.write().await.insert(eth_op_id, op);
+
+        Ok(())
+    }
+
+    async fn save_cancellation(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        eth_op_id: EthOpId,
+        request: OperationRequest,
+        tx_hash: H256,
+    ) -> anyhow::Result<()> {
+        self.check_write()?;
+        self.cancellations
+            .write()
+            .await
+            .insert(eth_op_id, (request, tx_hash));
+
+        Ok(())
+    }
+
+    async fn load_cancellations(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<(EthOpId, OperationRequest, H256)>> {
+        Ok(self.cancellations().await)
+    }
+
+    async fn remove_cancellation(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        eth_op_id: EthOpId,
+    ) -> anyhow::Result<()> {
+        self.check_write()?;
+        self.cancellations.write().await.remove(&eth_op_id);
+
+        Ok(())
+    }
+
+    async fn is_previous_operation_confirmed(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        op: &ETHOperation,
+    ) -> anyhow::Result<bool> {
+        let (first_block, _) = match &op.op {
+            Some((_, operation)) => operation.get_block_range(),
+            None => return Ok(true),
+        };
+        // We're checking previous block, so for the edge case of first block we can say that it was confirmed.
+        if first_block <= 1 {
+            return Ok(true);
+        }
+        let block_to_check = first_block - 1;
+
+        let confirmed = self
+            .confirmed_operations
+            .read()
+            .await
+            .values()
+            .any(|confirmed_op| match &confirmed_op.op {
+                Some((_, operation)) => {
+                    let (from, to) = operation.get_block_range();
+                    confirmed_op.op_type.to_string() == op.op_type.to_string()
+                        && from <= block_to_check
+                        && block_to_check <= to
+                }
+                None => false,
+            });
+
+        Ok(confirmed)
+    }
+
+    async fn load_last_contract_upgrade(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Option<ContractUpgrade>> {
+        Ok(*self.contract_upgrade.read().await)
+    }
+
+    async fn check_leadership(&self, _connection: &mut StorageProcessor<'_>) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            !self.leadership_lost.load(Ordering::SeqCst),
+            "Leadership is taken over by another replica"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,329 @@
+//! Mock Ethereum node for `ETHSender`.
+
+// Built-in deps
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+// External uses
+use tokio::sync::RwLock;
+use web3::contract::{tokens::Tokenize, Options};
+use zksync_basic_types::{H256, U256};
+// Workspace uses
+use zksync_eth_client::SignedCallResult;
+use zksync_types::ethereum::OnchainWithdrawal;
+// Local uses
+use crate::ethereum_interface::{EthereumInterface, FailureInfo};
+use crate::transactions::ExecutedTxStatus;
+
+/// Gas used by every transaction executed by the mock node.
+pub const MOCK_GAS_USED: u64 = 100_000;
+
+/// Mock Ethereum client is capable of recording all the incoming requests for the further analysis.
+///
+/// Besides the recording, the following failures can be injected:
+///
+/// - RPC errors (`fail_next_calls`): the next calls to the node return an error.
+/// - Dropped transactions (`drop_next_txs`): the transaction is accepted by the node, but never mined.
+/// - Chain reorganizations (`reorg`): the latest blocks are reverted along with the transactions in them.
+///
+/// The encoded transaction data is prefixed with the contract version once the ABI is switched,
+/// so the data encoded with the different ABI versions can be told apart.
+#[derive(Debug)]
+pub struct MockEthereum {
+    pub block_number: u64,
+    pub gas_price: U256,
+    pub tx_statuses: RwLock<HashMap<H256, ExecutedTxStatus>>,
+    pub sent_txs: RwLock<HashMap<H256, SignedCallResult>>,
+    /// Transactions accepted by the node, but dropped from its mempool.
+    pub dropped_txs: RwLock<HashMap<H256, SignedCallResult>>,
+    /// Contract versions with the available ABI.
+    pub contract_versions: Vec<u64>,
+    /// Contract version of the ABI in use, `None` for the default one.
+    pub contract_version: Option<u64>,
+    /// Withdrawals transferred by the executed transactions.
+    pub onchain_withdrawals: RwLock<HashMap<H256, Vec<OnchainWithdrawal>>>,
+    /// Numbers of the blocks the executed transactions were included in.
+    included_in: RwLock<HashMap<H256, u64>>,
+    failing_calls: AtomicUsize,
+    dropping_txs: AtomicUsize,
+}
+
+impl Default for MockEthereum {
+    fn default() -> Self {
+        Self {
+            block_number: 1,
+            gas_price: 100.into(),
+            tx_statuses: Default::default(),
+            sent_txs: Default::default(),
+            dropped_txs: Default::default(),
+            contract_versions: Default::default(),
+            contract_version: None,
+            onchain_withdrawals: Default::default(),
+            included_in: Default::default(),
+            failing_calls: Default::default(),
+            dropping_txs: Default::default(),
+        }
+    }
+}
+
+impl MockEthereum {
+    /// A fake `sha256` hasher, which calculates an `std::hash` instead.
+    /// This is done for simplicity and it's also much faster.
+    pub fn fake_sha256(data: &[u8]) -> H256 {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::Hasher;
+
+        let mut hasher = DefaultHasher::new();
+        hasher.write(data);
+
+        let result = hasher.finish();
+
+        H256::from_low_u64_ne(result)
+    }
+
+    /// Makes the next `count` requests to the node fail.
+    pub fn fail_next_calls(&self, count: usize) {
+        self.failing_calls.fetch_add(count, Ordering::SeqCst);
+    }
+
+    /// Makes the next `count` sent transactions to be dropped: sending succeeds,
+    /// but the transactions are never executed.
+    pub fn drop_next_txs(&self, count: usize) {
+        self.dropping_txs.fetch_add(count, Ordering::SeqCst);
+    }
+
+    /// Reverts the latest `depth` blocks. Transactions executed in these blocks
+    /// become pending again, and the rest lose `depth` confirmations.
+    pub async fn reorg(&mut self, depth: u64) {
+        assert!(
+            depth < self.block_number,
+            "Reorg of depth {} is deeper than the chain",
+            depth
+        );
+        self.block_number -= depth;
+
+        let mut included_in = self.included_in.write().await;
+        let mut tx_statuses = self.tx_statuses.write().await;
+        let reverted: Vec<_> = included_in
+            .iter()
+            .filter(|(_, &block)| block > self.block_number)
+            .map(|(hash, _)| *hash)
+            .collect();
+        for hash in reverted {
+            included_in.remove(&hash);
+            tx_statuses.remove(&hash);
+        }
+        for (hash, status) in tx_statuses.iter_mut() {
+            if let Some(block) = included_in.get(hash) {
+                status.confirmations = self.block_number + 1 - block;
+            }
+        }
+    }
+
+    /// Checks that there was a request to send the provided transaction.
+    pub async fn assert_sent(&self, hash: &H256) {
+        assert!(
+            self.sent_txs.read().await.get(hash).is_some(),
+            format!("Transaction with hash {:?} was not sent", hash),
+        );
+    }
+
+    /// Adds an response for the sent transaction for `ETHSender` to receive.
+    pub async fn add_execution(&mut self, hash: &H256, status: &ExecutedTxStatus) {
+        self.included_in.write().await.insert(
+            *hash,
+            (self.block_number + 1).saturating_sub(status.confirmations),
+        );
+        self.tx_statuses.write().await.insert(*hash, status.clone());
+    }
+
+    /// Increments the blocks by a provided `confirmations` and marks the sent transaction
+    /// as a success.
+    pub async fn add_successfull_execution(&mut self, tx_hash: H256, confirmations: u64) {
+        self.block_number += confirmations;
+
+        let status = ExecutedTxStatus {
+            confirmations,
+            success: true,
+            receipt: None,
+        };
+        self.add_execution(&tx_hash, &status).await;
+    }
+
+    /// Same as `add_successfull_execution`, but marks the transaction as a failure.
+    pub async fn add_failed_execution(&mut self, hash: &H256, confirmations: u64) {
+        self.block_number += confirmations;
+
+        let status = ExecutedTxStatus {
+            confirmations,
+            success: false,
+            receipt: Some(Default::default()),
+        };
+        self.add_execution(hash, &status).await;
+    }
+
+    /// Increments the blocks by a provided `confirmations` and marks all the sent
+    /// transactions which are not executed yet as a success.
+    pub async fn execute_pending_txs(&mut self, confirmations: u64) {
+        let pending: Vec<_> = {
+            let tx_statuses = self.tx_statuses.read().await;
+            self.sent_txs
+                .read()
+                .await
+                .keys()
+                .filter(|hash| !tx_statuses.contains_key(hash))
+                .copied()
+                .collect()
+        };
+
+        self.block_number += confirmations;
+        let status = ExecutedTxStatus {
+            confirmations,
+            success: true,
+            receipt: None,
+        };
+        for hash in pending {
+            self.add_execution(&hash, &status).await;
+        }
+    }
+
+    /// Returns an error if the request is chosen to fail.
+    fn check_call(&self) -> anyhow::Result<()> {
+        let injected = self
+            .failing_calls
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |count| {
+                count.checked_sub(1)
+            })
+            .is_ok();
+        if injected {
+            anyhow::bail!("Injected Ethereum RPC failure");
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl EthereumInterface for MockEthereum {
+    async fn get_tx_status(&self, hash: &H256) -> anyhow::Result<Option<ExecutedTxStatus>> {
+        self.check_call()?;
+        Ok(self.tx_statuses.read().await.get(hash).cloned())
+    }
+
+    async fn get_tx_fee(&self, hash: &H256) -> anyhow::Result<Option<U256>> {
+        self.check_call()?;
+        if !self.tx_statuses.read().await.contains_key(hash) {
+            return Ok(None);
+        }
+        let gas_price = self
+            .sent_txs
+            .read()
+            .await
+            .get(hash)
+            .map(|tx| tx.gas_price)
+            .unwrap_or(self.gas_price);
+        Ok(Some(gas_price * MOCK_GAS_USED))
+    }
+
+    async fn get_onchain_withdrawals(&self, hash: &H256) -> anyhow::Result<Vec<OnchainWithdrawal>> {
+        self.check_call()?;
+        Ok(self
+            .onchain_withdrawals
+            .read()
+            .await
+            .get(hash)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn block_number(&self) -> anyhow::Result<u64> {
+        self.check_call()?;
+        Ok(self.block_number)
+    }
+
+    async fn gas_price(&self) -> anyhow::Result<U256> {
+        self.check_call()?;
+        Ok(self.gas_price)
+    }
+
+    async fn send_tx(&self, signed_tx: &SignedCallResult) -> anyhow::Result<()> {
+        self.check_call()?;
+
+        let dropped = self
+            .dropping_txs
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |count| {
+                count.checked_sub(1)
+            })
+            .is_ok();
+        let txs = if dropped {
+            &self.dropped_txs
+        } else {
+            &self.sent_txs
+        };
+        txs.write().await.insert(signed_tx.hash, signed_tx.clone());
+
+        Ok(())
+    }
+
+    fn encode_tx_data<P: Tokenize>(&self, _func: &str, params: P) -> Vec<u8> {
+        let mut data = self
+            .contract_version
+            .map(|version| version.to_be_bytes().to_vec())
+            .unwrap_or_default();
+        data.extend(ethabi::encode(params.into_tokens().as_ref()));
+        data
+    }
+
+    fn switch_contract_version(&mut self, version_id: u64) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            self.contract_versions.contains(&version_id),
+            "ABI of the contract version {} is not available",
+            version_id
+        );
+        self.contract_version = Some(version_id);
+        Ok(())
+    }
+
+    async fn sign_prepared_tx(
+        &self,
+        raw_tx: Vec<u8>,
+        options: Options,
+    ) -> anyhow::Result<SignedCallResult> {
+        let gas_price = options.gas_price.unwrap_or(self.gas_price);
+        let nonce = options.nonce.expect("Nonce must be set for every tx");
+
+        // Nonce and gas_price are appended to distinguish the same transactions
+        // with different gas by their hash in tests.
+        let mut data_for_hash = raw_tx.clone();
+        data_for_hash.append(&mut ethabi::encode(gas_price.into_tokens().as_ref()));
+        data_for_hash.append(&mut ethabi::encode(nonce.into_tokens().as_ref()));
+        let hash = Self::fake_sha256(data_for_hash.as_ref()); // Okay for test purposes.
+
+        Ok(SignedCallResult {
+            raw_tx,
+            gas_price,
+            nonce,
+            hash,
+        })
+    }
+
+    async fn sign_cancel_tx(
+        &self,
+        nonce: U256,
+        gas_price: U256,
+    ) -> anyhow::Result<SignedCallResult> {
+        let mut data_for_hash = b"cancel".to_vec();
+        data_for_hash.append(&mut ethabi::encode(gas_price.into_tokens().as_ref()));
+        data_for_hash.append(&mut ethabi::encode(nonce.into_tokens().as_ref()));
+        let hash = Self::fake_sha256(data_for_hash.as_ref());
+
+        Ok(SignedCallResult {
+            raw_tx: Vec::new(),
+            gas_price,
+            nonce,
+            hash,
+        })
+    }
+
+    async fn failure_reason(&self, _tx_hash: H256) -> Option<FailureInfo> {
+        None
+    }
+}
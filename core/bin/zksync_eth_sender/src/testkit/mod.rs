@@ -0,0 +1,198 @@
+//! Test kit for `ETHSender`.
+//!
+//! Contains the mock Ethereum node and database, which record all the incoming
+//! requests and can be configured to fail, and the `Scenario` to script the
+//! `ETHSender` behavior step by step.
+//!
+//! The kit is available for the crate tests and, with the `testkit` feature enabled,
+//! for the tests of other crates.
+
+// Built-in deps
+// External uses
+use zksync_types::{aggregated_operations::AggregatedOperation, ethereum::ETHOperation};
+// Workspace uses
+use zksync_config::EthSenderOptions;
+// Local uses
+use crate::{transactions::ETHStats, ETHSender};
+
+pub use self::{
+    database::MockDatabase,
+    ethereum::{MockEthereum, MOCK_GAS_USED},
+};
+
+mod database;
+mod ethereum;
+
+/// Amount of blocks `ETHSender` created by the kit waits before considering a transaction stuck.
+pub const EXPECTED_WAIT_TIME_BLOCKS: u64 = 30;
+/// Amount of confirmations `ETHSender` created by the kit waits for.
+pub const WAIT_CONFIRMATIONS: u64 = 3;
+
+/// `ETHSender` working with the mock Ethereum node and database.
+pub type MockETHSender = ETHSender<MockEthereum, MockDatabase>;
+
+/// Creates a default `ETHSender` with mock Ethereum connection/database and no operations in DB.
+pub async fn default_eth_sender() -> MockETHSender {
+    build_eth_sender(1, Vec::new(), Default::default()).await
+}
+
+/// Creates an `ETHSender` with mock Ethereum connection/database and no operations in DB
+/// which supports multiple transactions in flight.
+pub async fn concurrent_eth_sender(max_txs_in_flight: u64) -> MockETHSender {
+    build_eth_sender(max_txs_in_flight, Vec::new(), Default::default()).await
+}
+
+/// Creates an `ETHSender` with mock Ethereum connection/database and restores its state "from DB".
+pub async fn restored_eth_sender(
+    restore_state: impl IntoIterator<Item = ETHOperation>,
+    stats: ETHStats,
+) -> MockETHSender {
+    const MAX_TXS_IN_FLIGHT: u64 = 1;
+
+    build_eth_sender(MAX_TXS_IN_FLIGHT, restore_state, stats).await
+}
+
+/// Helper method for configurable creation of `ETHSender`.
+async fn build_eth_sender(
+    max_txs_in_flight: u64,
+    restore_state: impl IntoIterator<Item = ETHOperation>,
+    stats: ETHStats,
+) -> MockETHSender {
+    let ethereum = MockEthereum::default();
+    let db = MockDatabase::with_restorable_state(restore_state, stats);
+
+    let options = EthSenderOptions {
+        max_txs_in_flight,
+        expected_wait_time_block: EXPECTED_WAIT_TIME_BLOCKS,
+        wait_confirmations: WAIT_CONFIRMATIONS,
+        tx_poll_period: Default::default(),
+        is_enabled: true,
+        auto_switch_abi: false,
+    };
+
+    ETHSender::new(options, db, ethereum).await
+}
+
+/// Single step of the `Scenario`.
+#[derive(Debug, Clone)]
+pub enum ScenarioStep {
+    /// Stores a new zkSync operation in the database.
+    AddOperation((i64, AggregatedOperation)),
+    /// Makes the next requests to the Ethereum node fail.
+    FailEthereumCalls(usize),
+    /// Makes the next sent transactions to be dropped.
+    DropTxs(usize),
+    /// Makes the next writes to the database fail.
+    FailDatabaseWrites(usize),
+    /// Makes another sender take the next nonces.
+    AdvanceNonce(i64),
+    /// Creates new Ethereum blocks.
+    MineBlocks(u64),
+    /// Executes all the sent transactions, creating the given amount of blocks.
+    ExecutePendingTxs(u64),
+    /// Reverts the latest Ethereum blocks.
+    Reorg(u64),
+    /// Loads the new operations and processes the ongoing ones.
+    Process,
+}
+
+/// Sequence of the events to apply to the `ETHSender` working with the mocks.
+///
+/// ```ignore
+/// Scenario::new()
+///     .add_operation(commit_op)
+///     .process()
+///     .fail_ethereum_calls(1)
+///     .process()
+///     .execute_pending_txs(WAIT_CONFIRMATIONS)
+///     .process()
+///     .run(&mut eth_sender)
+///     .await;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Scenario {
+    steps: Vec<ScenarioStep>,
+}
+
+impl Scenario {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn step(mut self, step: ScenarioStep) -> Self {
+        self.steps.push(step);
+        self
+    }
+
+    pub fn add_operation(self, op: (i64, AggregatedOperation)) -> Self {
+        self.step(ScenarioStep::AddOperation(op))
+    }
+
+    pub fn fail_ethereum_calls(self, count: usize) -> Self {
+        self.step(ScenarioStep::FailEthereumCalls(count))
+    }
+
+    pub fn drop_txs(self, count: usize) -> Self {
+        self.step(ScenarioStep::DropTxs(count))
+    }
+
+    pub fn fail_database_writes(self, count: usize) -> Self {
+        self.step(ScenarioStep::FailDatabaseWrites(count))
+    }
+
+    pub fn advance_nonce(self, count: i64) -> Self {
+        self.step(ScenarioStep::AdvanceNonce(count))
+    }
+
+    pub fn mine_blocks(self, count: u64) -> Self {
+        self.step(ScenarioStep::MineBlocks(count))
+    }
+
+    pub fn execute_pending_txs(self, confirmations: u64) -> Self {
+        self.step(ScenarioStep::ExecutePendingTxs(confirmations))
+    }
+
+    pub fn reorg(self, depth: u64) -> Self {
+        self.step(ScenarioStep::Reorg(depth))
+    }
+
+    pub fn process(self) -> Self {
+        self.step(ScenarioStep::Process)
+    }
+
+    /// Applies the steps to the `ETHSender` one by one.
+    pub async fn run(self, eth_sender: &mut MockETHSender) {
+        for step in self.steps {
+            match step {
+                ScenarioStep::AddOperation(op) => {
+                    eth_sender.db.send_operation(op).await.unwrap();
+                }
+                ScenarioStep::FailEthereumCalls(count) => {
+                    eth_sender.ethereum.fail_next_calls(count);
+                }
+                ScenarioStep::DropTxs(count) => {
+                    eth_sender.ethereum.drop_next_txs(count);
+                }
+                ScenarioStep::FailDatabaseWrites(count) => {
+                    eth_sender.db.fail_next_writes(count);
+                }
+                ScenarioStep::AdvanceNonce(count) => {
+                    eth_sender.db.advance_nonce(count).await;
+                }
+                ScenarioStep::MineBlocks(count) => {
+                    eth_sender.ethereum.block_number += count;
+                }
+                ScenarioStep::ExecutePendingTxs(confirmations) => {
+                    eth_sender.ethereum.execute_pending_txs(confirmations).await;
+                }
+                ScenarioStep::Reorg(depth) => {
+                    eth_sender.ethereum.reorg(depth).await;
+                }
+                ScenarioStep::Process => {
+                    eth_sender.load_new_operations().await;
+                    eth_sender.proceed_next_operations().await;
+                }
+            }
+        }
+    }
+}
@@ -1,19 +1,65 @@
-// Local uses
-use self::mock::{
-    concurrent_eth_sender, create_signed_tx, create_signed_withdraw_tx, default_eth_sender,
-    restored_eth_sender,
+// Built-in uses
+use std::collections::HashMap;
+// External uses
+use web3::{
+    contract::Options,
+    types::{H256, U256},
+};
+// Workspace uses
+use zksync_types::{
+    aggregated_operations::AggregatedOperation,
+    ethereum::{ETHOperation, EthOpId},
+    TokenId,
 };
+use zksync_utils::shutdown::ShutdownSignal;
+// Local uses
 use super::{
+    database::OperationRequest,
+    ethereum_interface::EthereumInterface,
+    testkit::{
+        concurrent_eth_sender, default_eth_sender, restored_eth_sender, MockETHSender, Scenario,
+        EXPECTED_WAIT_TIME_BLOCKS, MOCK_GAS_USED, WAIT_CONFIRMATIONS,
+    },
     transactions::{ETHStats, ExecutedTxStatus, TxCheckOutcome},
-    ETHSender, TxCheckMode,
+    TxCheckMode,
 };
 
-const EXPECTED_WAIT_TIME_BLOCKS: u64 = 30;
-const WAIT_CONFIRMATIONS: u64 = 3;
-
-pub mod mock;
 mod test_data;
 
+/// Behaves the same as `ETHSender::sign_new_tx`, but does not affect nonce.
+/// This method should be used to create expected tx copies which won't affect
+/// the internal `ETHSender` state.
+async fn create_signed_tx(
+    id: i64,
+    eth_sender: &MockETHSender,
+    operation: &(i64, AggregatedOperation),
+    deadline_block: u64,
+    nonce: i64,
+) -> ETHOperation {
+    let mut options = Options::default();
+    options.nonce = Some(nonce.into());
+
+    let raw_tx = eth_sender.operation_to_raw_tx(&operation.1);
+    let signed_tx = eth_sender
+        .ethereum
+        .sign_prepared_tx(raw_tx.clone(), options)
+        .await
+        .unwrap();
+
+    ETHOperation {
+        id,
+        op_type: operation.1.get_action_type(),
+        op: Some(operation.clone()),
+        nonce: signed_tx.nonce,
+        last_deadline_block: deadline_block,
+        last_used_gas_price: signed_tx.gas_price,
+        used_tx_hashes: vec![signed_tx.hash],
+        encoded_tx_data: raw_tx,
+        confirmed: false,
+        final_hash: None,
+    }
+}
+
 /// Basic test that `ETHSender` creation does not panic and initializes correctly.
 #[tokio::test]
 async fn basic_test() {
@@ -23,6 +69,18 @@ async fn basic_test() {
     assert!(eth_sender.ongoing_ops.is_empty());
 }
 
+/// Checks that `ETHSender` leaves the main loop and reports being stopped once
+/// the shutdown is requested.
+#[tokio::test]
+async fn stops_on_shutdown() {
+    let eth_sender = default_eth_sender().await;
+    let shutdown = ShutdownSignal::new();
+
+    shutdown.request();
+    eth_sender.run(shutdown.clone()).await;
+    assert!(shutdown.is_stopped());
+}
+
 /// Checks that deadline block is chosen according to the expected policy.
 #[tokio::test]
 async fn deadline_block() {
@@ -214,10 +272,11 @@ async fn transaction_state() {
 }
 
 /// Test for a normal `ETHSender` workflow:
-/// - we send the two sequential operations (commit and verify);
-/// - they are successfully committed to the Ethereum;
-/// - `completeWithdrawals` tx is sent to the Ethereum;
-/// - notification is sent after `verify` operation is committed.
+/// - we send the two sequential operations (commit and execute);
+/// - they are successfully committed to the Ethereum.
+///
+/// Withdrawals are completed along with the blocks execution, so there are no separate
+/// transactions for them.
 #[tokio::test]
 async fn operation_commitment_workflow() {
     let mut eth_sender = default_eth_sender().await;
@@ -277,32 +336,10 @@ async fn operation_commitment_workflow() {
         expected_tx.final_hash = Some(expected_tx.used_tx_hashes[0]);
         eth_sender.db.assert_confirmed(&expected_tx).await;
     }
-    // Process the next operation and check that `completeWithdrawals` transaction is stored and sent.
-    eth_sender.proceed_next_operations().await;
-
-    let eth_op_idx = operations.len() as i64;
-    let nonce = eth_op_idx;
-    let deadline_block = eth_sender.get_deadline_block(eth_sender.ethereum.block_number);
-    let mut withdraw_op_tx =
-        create_signed_withdraw_tx(eth_op_idx, &eth_sender, deadline_block, nonce).await;
 
-    eth_sender.db.assert_stored(&withdraw_op_tx).await;
-    eth_sender
-        .ethereum
-        .assert_sent(&withdraw_op_tx.used_tx_hashes[0])
-        .await;
-
-    // Mark `completeWithdrawals` as completed.
-    eth_sender
-        .ethereum
-        .add_successfull_execution(withdraw_op_tx.used_tx_hashes[0], WAIT_CONFIRMATIONS)
-        .await;
+    // There are no more transactions to send.
     eth_sender.proceed_next_operations().await;
-
-    // Check that `completeWithdrawals` is completed in the DB.
-    withdraw_op_tx.confirmed = true;
-    withdraw_op_tx.final_hash = Some(withdraw_op_tx.used_tx_hashes[0]);
-    eth_sender.db.assert_confirmed(&withdraw_op_tx).await;
+    assert!(eth_sender.ongoing_ops.is_empty());
 }
 
 /// A simple scenario for a stuck transaction:
@@ -362,13 +399,64 @@ async fn stuck_transaction() {
     stuck_tx.confirmed = true;
     stuck_tx.final_hash = Some(stuck_tx.used_tx_hashes[1]);
     eth_sender.db.assert_confirmed(&stuck_tx).await;
+
+    // Only the executed transaction is paid for, at its increased gas price.
+    eth_sender
+        .db
+        .assert_cost_recorded(&stuck_tx, expected_sent_tx.gas_price * MOCK_GAS_USED)
+        .await;
+}
+
+/// Checks that requested resubmission makes `ETHSender` send a supplement transaction
+/// for the ongoing operation without waiting for its deadline block.
+#[tokio::test]
+async fn resubmission_request() {
+    let mut eth_sender = default_eth_sender().await;
+
+    let operation = test_data::commit_operation(0);
+    eth_sender
+        .db
+        .send_operation(operation.clone())
+        .await
+        .unwrap();
+
+    eth_sender.load_new_operations().await;
+    eth_sender.proceed_next_operations().await;
+
+    let deadline_block = eth_sender.get_deadline_block(eth_sender.ethereum.block_number);
+    let mut pending_tx = create_signed_tx(0, &eth_sender, &operation, deadline_block, 0).await;
+
+    // Nothing is resubmitted until it's requested.
+    eth_sender.handle_resubmission_request().await;
+    eth_sender.proceed_next_operations().await;
+    assert_eq!(eth_sender.ongoing_ops[0].used_tx_hashes.len(), 1);
+
+    // Resubmission is requested within the same block.
+    eth_sender.db.request_resubmission().await;
+    eth_sender.handle_resubmission_request().await;
+    eth_sender.proceed_next_operations().await;
+
+    let expected_sent_tx = eth_sender
+        .create_supplement_tx(deadline_block, &mut pending_tx)
+        .await
+        .unwrap();
+    eth_sender.db.assert_stored(&pending_tx).await;
+    eth_sender
+        .ethereum
+        .assert_sent(&expected_sent_tx.hash)
+        .await;
+
+    // The request is handled only once.
+    eth_sender.handle_resubmission_request().await;
+    eth_sender.proceed_next_operations().await;
+    assert_eq!(eth_sender.ongoing_ops[0].used_tx_hashes.len(), 2);
 }
 
 /// This test verifies that with multiple operations received all-together,
 /// their order is respected and no processing of the next operation is started until
 /// the previous one is committed.
 ///
-/// This test includes all three operation types (commit, verify and withdraw).
+/// This test includes both operation types (commit and execute).
 #[tokio::test]
 async fn operations_order() {
     let mut eth_sender = default_eth_sender().await;
@@ -385,17 +473,17 @@ async fn operations_order() {
     let mut expected_txs = Vec::new();
 
     // Create expected txs from all the operations.
-    // Since we create 3 operations at each cycle iteration,
-    // the logic of ID calculating is (i * 3), (i * 3 + 1), (i * 3 + 2).
-    // On the first iteration the indices 0, 1 and 2 will be taken, then it
-    // will be 3, 4 and 5, etc.
+    // Since we create 2 operations at each cycle iteration,
+    // the logic of ID calculating is (i * 2), (i * 2 + 1).
+    // On the first iteration the indices 0 and 1 will be taken, then it
+    // will be 2 and 3, etc.
     for (idx, (commit_operation, verify_operation)) in
         commit_operations.iter().zip(verify_operations).enumerate()
     {
         // Create the commit operation.
-        let start_block = 1 + WAIT_CONFIRMATIONS * (idx * 3) as u64;
+        let start_block = 1 + WAIT_CONFIRMATIONS * (idx * 2) as u64;
         let deadline_block = eth_sender.get_deadline_block(start_block);
-        let eth_op_idx = (idx * 3) as i64;
+        let eth_op_idx = (idx * 2) as i64;
         let nonce = eth_op_idx;
 
         let commit_op_tx = create_signed_tx(
@@ -410,9 +498,9 @@ async fn operations_order() {
         expected_txs.push(commit_op_tx);
 
         // Create the verify operation, as by priority it will be processed right after `commit`.
-        let start_block = 1 + WAIT_CONFIRMATIONS * (idx * 3 + 1) as u64;
+        let start_block = 1 + WAIT_CONFIRMATIONS * (idx * 2 + 1) as u64;
         let deadline_block = eth_sender.get_deadline_block(start_block);
-        let eth_op_idx = (idx * 3 + 1) as i64;
+        let eth_op_idx = (idx * 2 + 1) as i64;
         let nonce = eth_op_idx;
 
         let verify_op_tx = create_signed_tx(
@@ -425,17 +513,6 @@ async fn operations_order() {
         .await;
 
         expected_txs.push(verify_op_tx);
-
-        // Create the withdraw operation.
-        let start_block = 1 + WAIT_CONFIRMATIONS * (idx * 3 + 2) as u64;
-        let deadline_block = eth_sender.get_deadline_block(start_block);
-        let eth_op_idx = (idx * 3 + 2) as i64;
-        let nonce = eth_op_idx;
-
-        let withdraw_op_tx =
-            create_signed_withdraw_tx(eth_op_idx, &eth_sender, deadline_block, nonce).await;
-
-        expected_txs.push(withdraw_op_tx);
     }
 
     for operation in operations.iter() {
@@ -471,6 +548,35 @@ async fn operations_order() {
     }
 }
 
+/// Checks that the gas limit of the transaction executing the blocks is increased
+/// by the withdrawal gas limits of the withdrawn tokens.
+#[tokio::test]
+async fn withdrawal_gas_limits() {
+    let eth_sender = default_eth_sender().await;
+    let deadline_block = eth_sender.get_deadline_block(1);
+    let commit_op = test_data::commit_operation(0);
+    let commit_tx = create_signed_tx(0, &eth_sender, &commit_op, deadline_block, 0).await;
+    let execute_op = test_data::verify_operation(0);
+    let execute_tx = create_signed_tx(1, &eth_sender, &execute_op, deadline_block, 1).await;
+
+    let base_gas_limit = MockETHSender::gas_limit_for_op(&commit_tx, &HashMap::new());
+    assert_eq!(
+        MockETHSender::gas_limit_for_op(&execute_tx, &HashMap::new()),
+        base_gas_limit
+    );
+
+    // Every block of the test data contains a single full exit of the token 0.
+    let gas_limits: HashMap<TokenId, u64> = vec![(0, 30_000), (1, 40_000)].into_iter().collect();
+    assert_eq!(
+        MockETHSender::gas_limit_for_op(&commit_tx, &gas_limits),
+        base_gas_limit
+    );
+    assert_eq!(
+        MockETHSender::gas_limit_for_op(&execute_tx, &gas_limits),
+        base_gas_limit + U256::from(30_000)
+    );
+}
+
 /// Check that upon a transaction failure the incident causes a panic by default.
 #[tokio::test]
 #[should_panic(expected = "Cannot operate after unexpected TX failure")]
@@ -632,23 +738,21 @@ async fn concurrent_operations_order() {
     let mut expected_txs = Vec::new();
 
     // Create expected txs from all the operations.
-    // Since we create 3 operations at each cycle iteration,
-    // the logic of ID calculating is (i * 3), (i * 3 + 1), (i * 3 + 2).
-    // On the first iteration the indices 0, 1 and 2 will be taken, then it
-    // will be 3, 4 and 5, etc.
+    // Since we create 2 operations at each cycle iteration,
+    // the logic of ID calculating is (i * 2), (i * 2 + 1).
+    // On the first iteration the indices 0 and 1 will be taken, then it
+    // will be 2 and 3, etc.
 
     for (idx, (commit_operation, verify_operation)) in
         commit_operations.iter().zip(verify_operations).enumerate()
     {
         // Commit/verify transactions from one iteration will be sent concurrently,
         // thus the deadline block is the same for them.
-        // However, withdraw operation will be sent after these txs are confirmed,
-        // so it will have a different deadline block,
-        let start_block = 1 + WAIT_CONFIRMATIONS * (idx * 3) as u64;
+        let start_block = 1 + WAIT_CONFIRMATIONS * (idx * 2) as u64;
         let deadline_block = eth_sender.get_deadline_block(start_block);
 
         // Create the commit operation.
-        let eth_op_idx = (idx * 3) as i64;
+        let eth_op_idx = (idx * 2) as i64;
         let nonce = eth_op_idx;
 
         let commit_op_tx = create_signed_tx(
@@ -663,7 +767,7 @@ async fn concurrent_operations_order() {
         expected_txs.push(commit_op_tx);
 
         // Create the verify operation, as by priority it will be processed right after `commit`.
-        let eth_op_idx = (idx * 3 + 1) as i64;
+        let eth_op_idx = (idx * 2 + 1) as i64;
         let nonce = eth_op_idx;
 
         let verify_op_tx = create_signed_tx(
@@ -676,27 +780,14 @@ async fn concurrent_operations_order() {
         .await;
 
         expected_txs.push(verify_op_tx);
-
-        // Create the withdraw operation.
-        let start_block = 1 + WAIT_CONFIRMATIONS * (idx * 3 + 2) as u64;
-        let deadline_block = eth_sender.get_deadline_block(start_block);
-        let eth_op_idx = (idx * 3 + 2) as i64;
-        let nonce = eth_op_idx;
-
-        let withdraw_op_tx =
-            create_signed_withdraw_tx(eth_op_idx, &eth_sender, deadline_block, nonce).await;
-
-        expected_txs.push(withdraw_op_tx);
     }
     // Pair commit/verify operations.
     let mut operations_iter = commit_operations.iter().zip(verify_operations);
 
     // Then we go through the operations and check that the order of operations is preserved.
-    // Here we take N txs at each interaction.
-    for txs in expected_txs.chunks(MAX_TXS_IN_FLIGHT as usize) {
-        // We send operations by two, so the order will be "commit-verify-withdraw".
-        // If we'll send all the operations together, the order will be "commit-verify-commit-verify-withdraw",
-        // since withdraw is only sent after verify operation is confirmed.
+    // Here we take both txs of the iteration at once.
+    for txs in expected_txs.chunks(2) {
+        // We send operations by two, so the order will be "commit-verify".
         let (commit_op, verify_op) = operations_iter.next().unwrap();
 
         eth_sender
@@ -717,7 +808,6 @@ async fn concurrent_operations_order() {
 
         let commit_tx = &txs[0];
         let verify_tx = &txs[1];
-        let mut withdraw_tx = txs[2].clone();
 
         // Check that commit/verify txs are sent and add the successful execution for them.
         for tx in &[commit_tx, verify_tx] {
@@ -746,26 +836,509 @@ async fn concurrent_operations_order() {
             tx.final_hash = Some(current_tx_hash);
             eth_sender.db.assert_confirmed(&tx).await;
         }
+    }
+}
 
-        // Now, the withdraw operation should be taken from the queue, and
-        // sent to the Ethereum.
-        eth_sender.proceed_next_operations().await;
+/// Checks that failed requests to the Ethereum node are retried, and the operation
+/// is processed as usual once the node is available again.
+#[tokio::test]
+async fn ethereum_rpc_failures() {
+    let mut eth_sender = default_eth_sender().await;
+
+    // Failure upon the operation initialization makes it return to the queue.
+    Scenario::new()
+        .add_operation(test_data::commit_operation(0))
+        .fail_ethereum_calls(2)
+        .process()
+        .run(&mut eth_sender)
+        .await;
+    assert_eq!(eth_sender.ongoing_ops.len(), 1);
+    eth_sender
+        .ethereum
+        .assert_sent(&eth_sender.ongoing_ops[0].used_tx_hashes[0])
+        .await;
+
+    // Failure upon the commitment check leaves the operation pending.
+    Scenario::new()
+        .execute_pending_txs(WAIT_CONFIRMATIONS)
+        .fail_ethereum_calls(1)
+        .process()
+        .run(&mut eth_sender)
+        .await;
+    assert_eq!(eth_sender.ongoing_ops.len(), 1);
 
-        let withdraw_tx_hash = withdraw_tx.used_tx_hashes[0];
-        eth_sender.db.assert_stored(&withdraw_tx).await;
-        eth_sender.ethereum.assert_sent(&withdraw_tx_hash).await;
+    Scenario::new().process().run(&mut eth_sender).await;
+    assert!(eth_sender.ongoing_ops.is_empty());
+    assert_eq!(eth_sender.db.confirmed_operations().await.len(), 1);
+}
 
-        // Mark the tx as successfully
+/// Checks that the transaction dropped by the Ethereum node is considered stuck
+/// and the supplement transaction is sent for it.
+#[tokio::test]
+async fn dropped_transaction() {
+    let mut eth_sender = default_eth_sender().await;
+
+    Scenario::new()
+        .add_operation(test_data::commit_operation(0))
+        .drop_txs(1)
+        .process()
+        .execute_pending_txs(WAIT_CONFIRMATIONS)
+        .process()
+        .run(&mut eth_sender)
+        .await;
+    // Dropped transaction is never executed.
+    assert_eq!(eth_sender.ongoing_ops.len(), 1);
+    assert!(eth_sender.ethereum.sent_txs.read().await.is_empty());
+
+    Scenario::new()
+        .mine_blocks(EXPECTED_WAIT_TIME_BLOCKS)
+        .process()
+        .execute_pending_txs(WAIT_CONFIRMATIONS)
+        .process()
+        .run(&mut eth_sender)
+        .await;
+
+    let confirmed = eth_sender.db.confirmed_operations().await;
+    assert_eq!(confirmed.len(), 1);
+    assert_eq!(confirmed[0].used_tx_hashes.len(), 2);
+    assert_eq!(
+        confirmed[0].final_hash,
+        Some(confirmed[0].used_tx_hashes[1])
+    );
+}
+
+/// Checks that no transactions are sent once the leadership is taken over by another
+/// server replica.
+#[tokio::test]
+async fn leadership_loss() {
+    let mut eth_sender = default_eth_sender().await;
+
+    Scenario::new()
+        .add_operation(test_data::commit_operation(0))
+        .process()
+        .run(&mut eth_sender)
+        .await;
+    assert_eq!(eth_sender.ethereum.sent_txs.read().await.len(), 1);
+
+    eth_sender.db.lose_leadership();
+    // Neither the new operation nor the supplement transaction for the stuck one is sent.
+    Scenario::new()
+        .add_operation(test_data::commit_operation(1))
+        .mine_blocks(EXPECTED_WAIT_TIME_BLOCKS)
+        .process()
+        .run(&mut eth_sender)
+        .await;
+    assert_eq!(eth_sender.ethereum.sent_txs.read().await.len(), 1);
+    assert_eq!(eth_sender.ongoing_ops.len(), 1);
+}
+
+/// Checks that the transaction reverted by the chain reorganization before getting
+/// enough confirmations is not considered committed.
+#[tokio::test]
+async fn chain_reorganization() {
+    let mut eth_sender = default_eth_sender().await;
+
+    Scenario::new()
+        .add_operation(test_data::commit_operation(0))
+        .process()
+        .execute_pending_txs(WAIT_CONFIRMATIONS - 1)
+        .process()
+        .reorg(WAIT_CONFIRMATIONS - 1)
+        .mine_blocks(1)
+        .process()
+        .run(&mut eth_sender)
+        .await;
+    // The transaction is pending again.
+    assert!(eth_sender.ethereum.tx_statuses.read().await.is_empty());
+    assert_eq!(eth_sender.ongoing_ops.len(), 1);
+
+    Scenario::new()
+        .execute_pending_txs(WAIT_CONFIRMATIONS)
+        .process()
+        .run(&mut eth_sender)
+        .await;
+    assert!(eth_sender.ongoing_ops.is_empty());
+    assert_eq!(eth_sender.db.confirmed_operations().await.len(), 1);
+}
+
+/// Checks that failed writes to the database don't lead to the operation being lost.
+#[tokio::test]
+async fn database_write_failures() {
+    let mut eth_sender = default_eth_sender().await;
+
+    Scenario::new()
+        .add_operation(test_data::commit_operation(0))
+        .fail_database_writes(1)
+        .process()
+        .run(&mut eth_sender)
+        .await;
+    // The operation wasn't stored on the first attempt, so it was initialized once again.
+    assert_eq!(eth_sender.db.unconfirmed_operations().await.len(), 1);
+    assert_eq!(eth_sender.ongoing_ops.len(), 1);
+
+    // Confirmation is retried on the next round.
+    Scenario::new()
+        .execute_pending_txs(WAIT_CONFIRMATIONS)
+        .fail_database_writes(1)
+        .process()
+        .run(&mut eth_sender)
+        .await;
+    assert_eq!(eth_sender.ongoing_ops.len(), 1);
+
+    Scenario::new().process().run(&mut eth_sender).await;
+    assert!(eth_sender.ongoing_ops.is_empty());
+    assert_eq!(eth_sender.db.confirmed_operations().await.len(), 1);
+}
+
+/// Checks that the operation uses the nonce assigned by the database, even if
+/// some nonces were taken by another sender.
+#[tokio::test]
+async fn nonce_race() {
+    let mut eth_sender = default_eth_sender().await;
+
+    Scenario::new()
+        .add_operation(test_data::commit_operation(0))
+        .advance_nonce(2)
+        .process()
+        .run(&mut eth_sender)
+        .await;
+
+    let stored = eth_sender.db.unconfirmed_operations().await;
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[0].nonce, 2.into());
+    let sent_txs = eth_sender.ethereum.sent_txs.read().await;
+    assert_eq!(sent_txs[&stored[0].used_tx_hashes[0]].nonce, 2.into());
+}
+
+/// Returns the hash of the transaction cancelling the operation.
+async fn cancel_tx_hash(eth_sender: &MockETHSender, eth_op_id: EthOpId) -> H256 {
+    eth_sender
+        .db
+        .cancellations()
+        .await
+        .into_iter()
+        .find(|(id, _, _)| *id == eth_op_id)
+        .map(|(_, _, tx_hash)| tx_hash)
+        .expect("Operation is not being cancelled")
+}
+
+/// Checks that only the operation with the latest nonce can be requeued or dropped, that
+/// the request is completed only after the cancelling transaction is confirmed, and that
+/// the requeued operation is sent again with a new nonce.
+#[tokio::test]
+async fn operation_requests() {
+    let mut eth_sender = concurrent_eth_sender(2).await;
+
+    Scenario::new()
+        .add_operation(test_data::commit_operation(0))
+        .add_operation(test_data::commit_operation(1))
+        .drop_txs(2)
+        .process()
+        .run(&mut eth_sender)
+        .await;
+    assert_eq!(eth_sender.ongoing_ops.len(), 2);
+    let (first_op, second_op) = (
+        eth_sender.ongoing_ops[0].clone(),
+        eth_sender.ongoing_ops[1].clone(),
+    );
+
+    // The first operation doesn't have the latest nonce.
+    eth_sender
+        .db
+        .request_operation_action(first_op.id, OperationRequest::Requeue)
+        .await;
+    eth_sender.handle_admin_requests().await;
+    assert_eq!(eth_sender.ongoing_ops.len(), 2);
+    assert!(eth_sender.db.cancellations().await.is_empty());
+
+    // The operation is cancelled by a transaction with the same nonce and a higher gas price.
+    eth_sender
+        .db
+        .request_operation_action(second_op.id, OperationRequest::Requeue)
+        .await;
+    eth_sender.handle_admin_requests().await;
+    let cancel_hash = cancel_tx_hash(&eth_sender, second_op.id).await;
+    let cancel_tx = eth_sender.ethereum.sent_txs.read().await[&cancel_hash].clone();
+    assert_eq!(cancel_tx.nonce, second_op.nonce);
+    assert!(cancel_tx.gas_price > second_op.last_used_gas_price);
+
+    // Until the cancellation is confirmed, the operation is tracked and no new txs are sent.
+    eth_sender.proceed_next_operations().await;
+    assert_eq!(eth_sender.ongoing_ops.len(), 2);
+    assert_eq!(eth_sender.ethereum.sent_txs.read().await.len(), 1);
+
+    // Requeued operation is sent again as a new one with a new nonce.
+    eth_sender
+        .ethereum
+        .add_successfull_execution(cancel_hash, WAIT_CONFIRMATIONS)
+        .await;
+    eth_sender.handle_admin_requests().await;
+    assert_eq!(eth_sender.ongoing_ops.len(), 1);
+    assert!(eth_sender.db.cancellations().await.is_empty());
+    // The cancelling transaction is paid for by the blocks of the operation.
+    eth_sender
+        .db
+        .assert_cost_recorded(&second_op, cancel_tx.gas_price * MOCK_GAS_USED)
+        .await;
+    eth_sender.proceed_next_operations().await;
+
+    let requeued_op = eth_sender.ongoing_ops[1].clone();
+    assert_ne!(requeued_op.id, second_op.id);
+    assert_eq!(requeued_op.nonce, second_op.nonce + 1);
+    assert_eq!(
+        requeued_op.op.map(|(id, _)| id),
+        second_op.op.map(|(id, _)| id)
+    );
+    eth_sender
+        .ethereum
+        .assert_sent(&requeued_op.used_tx_hashes[0])
+        .await;
+
+    // Dropped operations are not tracked and not sent again.
+    for op in &[&requeued_op, &first_op] {
+        eth_sender
+            .db
+            .request_operation_action(op.id, OperationRequest::Drop)
+            .await;
+        eth_sender.handle_admin_requests().await;
+        let cancel_hash = cancel_tx_hash(&eth_sender, op.id).await;
         eth_sender
             .ethereum
-            .add_successfull_execution(withdraw_tx_hash, WAIT_CONFIRMATIONS)
+            .add_successfull_execution(cancel_hash, WAIT_CONFIRMATIONS)
             .await;
-
-        // Call `proceed_next_operations` again. Withdraw tx should become confirmed.
-        eth_sender.proceed_next_operations().await;
-        // Update the fields in the tx and check if it's confirmed.
-        withdraw_tx.confirmed = true;
-        withdraw_tx.final_hash = Some(withdraw_tx_hash);
-        eth_sender.db.assert_confirmed(&withdraw_tx).await;
+        eth_sender.handle_admin_requests().await;
     }
+    eth_sender.proceed_next_operations().await;
+    assert!(eth_sender.ongoing_ops.is_empty());
+    assert!(eth_sender.db.unconfirmed_operations().await.is_empty());
+    assert_eq!(eth_sender.db.dropped_operations().await.len(), 2);
+    // Three cancelling transactions and the requeued operation.
+    assert_eq!(eth_sender.ethereum.sent_txs.read().await.len(), 4);
+}
+
+/// Checks that the operation with a mined transaction can't be dropped.
+#[tokio::test]
+async fn operation_request_for_mined_tx() {
+    let mut eth_sender = default_eth_sender().await;
+
+    Scenario::new()
+        .add_operation(test_data::commit_operation(0))
+        .process()
+        .execute_pending_txs(1)
+        .run(&mut eth_sender)
+        .await;
+    let op = eth_sender.ongoing_ops[0].clone();
+
+    eth_sender
+        .db
+        .request_operation_action(op.id, OperationRequest::Drop)
+        .await;
+    eth_sender.handle_admin_requests().await;
+    assert_eq!(eth_sender.ongoing_ops.len(), 1);
+    assert!(eth_sender.db.cancellations().await.is_empty());
+    assert!(eth_sender.db.dropped_operations().await.is_empty());
+    eth_sender.db.assert_stored(&op).await;
+}
+
+/// Checks that the request is abandoned if the operation transaction is mined
+/// before the cancelling one, and the operation is confirmed as usual.
+#[tokio::test]
+async fn operation_request_abandoned_for_mined_tx() {
+    let mut eth_sender = default_eth_sender().await;
+
+    Scenario::new()
+        .add_operation(test_data::commit_operation(0))
+        .process()
+        .run(&mut eth_sender)
+        .await;
+    let op = eth_sender.ongoing_ops[0].clone();
+
+    eth_sender
+        .db
+        .request_operation_action(op.id, OperationRequest::Drop)
+        .await;
+    eth_sender.handle_admin_requests().await;
+    assert_eq!(eth_sender.db.cancellations().await.len(), 1);
+
+    eth_sender
+        .ethereum
+        .add_successfull_execution(op.used_tx_hashes[0], WAIT_CONFIRMATIONS)
+        .await;
+    eth_sender.handle_admin_requests().await;
+    assert!(eth_sender.db.cancellations().await.is_empty());
+    assert!(eth_sender.db.dropped_operations().await.is_empty());
+
+    eth_sender.proceed_next_operations().await;
+    assert!(eth_sender.ongoing_ops.is_empty());
+    assert_eq!(eth_sender.db.confirmed_operations().await.len(), 1);
+}
+
+/// Checks that the reload request restores the ongoing operations from the database.
+#[tokio::test]
+async fn reload_request() {
+    let mut eth_sender = default_eth_sender().await;
+
+    Scenario::new()
+        .add_operation(test_data::commit_operation(0))
+        .process()
+        .run(&mut eth_sender)
+        .await;
+    let op = eth_sender.ongoing_ops[0].clone();
+
+    // The operation is lost from the memory, e.g. because of a bug.
+    eth_sender.ongoing_ops.clear();
+    eth_sender.db.request_reload().await;
+    eth_sender.handle_admin_requests().await;
+    assert_eq!(eth_sender.ongoing_ops, vec![op.clone()]);
+
+    Scenario::new()
+        .execute_pending_txs(WAIT_CONFIRMATIONS)
+        .process()
+        .run(&mut eth_sender)
+        .await;
+    assert!(eth_sender.ongoing_ops.is_empty());
+    assert_eq!(eth_sender.db.confirmed_operations().await.len(), 1);
+}
+
+/// Checks that the new transactions are not sent after the contract upgrade
+/// until it's confirmed by the operator.
+#[tokio::test]
+async fn contract_upgrade_confirmation() {
+    let mut eth_sender = default_eth_sender().await;
+
+    eth_sender.db.complete_contract_upgrade(2).await;
+    eth_sender.handle_contract_upgrade().await;
+    Scenario::new()
+        .add_operation(test_data::commit_operation(0))
+        .process()
+        .run(&mut eth_sender)
+        .await;
+    assert!(eth_sender.ongoing_ops.is_empty());
+    assert_eq!(
+        eth_sender
+            .pending_contract_upgrade
+            .map(|upgrade| upgrade.version_id),
+        Some(2)
+    );
+
+    // The ABI of the new version is not available, so the confirmed upgrade
+    // means that the ABI in use matches the upgraded contract.
+    eth_sender.db.confirm_contract_upgrade().await;
+    eth_sender.handle_contract_upgrade().await;
+    eth_sender.proceed_next_operations().await;
+    assert_eq!(eth_sender.ongoing_ops.len(), 1);
+    assert_eq!(eth_sender.contract_version, Some(2));
+    assert_eq!(eth_sender.ethereum.contract_version, None);
+}
+
+/// Checks that the ABI of the upgraded contract is used automatically if it's available,
+/// and the queued operations are encoded with it.
+#[tokio::test]
+async fn contract_upgrade_auto_switch() {
+    let mut eth_sender = default_eth_sender().await;
+    eth_sender.options.auto_switch_abi = true;
+    eth_sender.ethereum.contract_versions = vec![2];
+
+    let operation = test_data::commit_operation(0);
+    Scenario::new()
+        .add_operation(operation.clone())
+        .run(&mut eth_sender)
+        .await;
+    eth_sender.load_new_operations().await;
+
+    eth_sender.db.complete_contract_upgrade(2).await;
+    eth_sender.handle_contract_upgrade().await;
+    eth_sender.proceed_next_operations().await;
+    assert_eq!(eth_sender.ongoing_ops.len(), 1);
+    assert_eq!(eth_sender.ethereum.contract_version, Some(2));
+    assert_eq!(
+        eth_sender.ongoing_ops[0].encoded_tx_data,
+        eth_sender.operation_to_raw_tx(&operation.1)
+    );
+    assert!(eth_sender.ongoing_ops[0]
+        .encoded_tx_data
+        .starts_with(&2u64.to_be_bytes()));
+
+    // ABI of the next version is not available, so the sending is paused.
+    eth_sender.db.complete_contract_upgrade(3).await;
+    eth_sender.handle_contract_upgrade().await;
+    Scenario::new()
+        .add_operation(test_data::commit_operation(1))
+        .process()
+        .run(&mut eth_sender)
+        .await;
+    assert_eq!(eth_sender.ongoing_ops.len(), 1);
+    assert_eq!(
+        eth_sender
+            .pending_contract_upgrade
+            .map(|upgrade| upgrade.version_id),
+        Some(3)
+    );
+}
+
+/// Checks that sending is paused once the contract upgrade preparation is started,
+/// and the ongoing operations sent with the outdated ABI are re-encoded and replaced
+/// after the switch.
+#[tokio::test]
+async fn contract_upgrade_preparation() {
+    let mut eth_sender = default_eth_sender().await;
+    eth_sender.options.auto_switch_abi = true;
+    eth_sender.ethereum.contract_versions = vec![2];
+
+    let operation = test_data::commit_operation(0);
+    Scenario::new()
+        .add_operation(operation.clone())
+        .process()
+        .run(&mut eth_sender)
+        .await;
+    assert_eq!(eth_sender.ongoing_ops.len(), 1);
+
+    eth_sender.db.prepare_contract_upgrade(2).await;
+    eth_sender.handle_contract_upgrade().await;
+    Scenario::new()
+        .add_operation(test_data::commit_operation(1))
+        .process()
+        .run(&mut eth_sender)
+        .await;
+    assert_eq!(eth_sender.ongoing_ops.len(), 1);
+    // ABI isn't switched until the upgrade is completed.
+    assert_eq!(eth_sender.ethereum.contract_version, None);
+    assert!(eth_sender.pending_contract_upgrade.is_some());
+
+    eth_sender.db.complete_contract_upgrade(2).await;
+    eth_sender.handle_contract_upgrade().await;
+    assert!(eth_sender.pending_contract_upgrade.is_none());
+    let encoded_tx_data = eth_sender.operation_to_raw_tx(&operation.1);
+    assert!(encoded_tx_data.starts_with(&2u64.to_be_bytes()));
+    assert_eq!(eth_sender.ongoing_ops[0].encoded_tx_data, encoded_tx_data);
+    assert_eq!(
+        eth_sender.db.unconfirmed_operations().await[0].encoded_tx_data,
+        encoded_tx_data
+    );
+
+    // The outdated transaction is replaced right away, and the queued operation is sent.
+    eth_sender.proceed_next_operations().await;
+    assert_eq!(eth_sender.ongoing_ops.len(), 2);
+    assert_eq!(eth_sender.ongoing_ops[0].used_tx_hashes.len(), 2);
+}
+
+/// Checks that sending is resumed if the contract upgrade is cancelled during the preparation.
+#[tokio::test]
+async fn contract_upgrade_cancellation() {
+    let mut eth_sender = default_eth_sender().await;
+
+    eth_sender.db.prepare_contract_upgrade(2).await;
+    eth_sender.handle_contract_upgrade().await;
+    Scenario::new()
+        .add_operation(test_data::commit_operation(0))
+        .process()
+        .run(&mut eth_sender)
+        .await;
+    assert!(eth_sender.ongoing_ops.is_empty());
+
+    eth_sender.db.cancel_contract_upgrade().await;
+    eth_sender.handle_contract_upgrade().await;
+    eth_sender.proceed_next_operations().await;
+    assert!(eth_sender.pending_contract_upgrade.is_none());
+    assert_eq!(eth_sender.ongoing_ops.len(), 1);
 }
@@ -6,14 +6,16 @@ use chrono::DateTime;
 use lazy_static::lazy_static;
 // Workspace uses
 use zksync_basic_types::H256;
+use zksync_types::aggregated_operations::{
+    AggregatedActionType, AggregatedOperation, BlocksCommitOperation, BlocksExecuteOperation,
+};
 use zksync_types::{
-    block::Block, Address, ExecutedOperations, ExecutedPriorityOp, Fr, FullExit, FullExitOp,
-    PriorityOp, ZkSyncOp, ZkSyncPriorityOp,
+    block::Block, Address, BlockNumber, ExecutedOperations, ExecutedPriorityOp, Fr, FullExit,
+    FullExitOp, PriorityOp, ZkSyncOp, ZkSyncPriorityOp,
 };
-use zksync_types::{Action, Operation};
 
-/// Creates a dummy operation as a test input for `ETHSender` tests.
-fn get_operation(id: i64, block_number: u32, action: Action) -> Operation {
+/// Creates a dummy block with the full exit operation.
+fn get_block(block_number: BlockNumber) -> Block {
     // Create full exit operation for non-zero return data.
     let executed_full_exit_op = {
         let priority_op = FullExit {
@@ -37,40 +39,53 @@ fn get_operation(id: i64, block_number: u32, action: Action) -> Operation {
             created_at: DateTime::from(SystemTime::UNIX_EPOCH),
         }))
     };
-    Operation {
-        id: Some(id),
-        action,
-        block: Block::new(
-            block_number,
-            Fr::default(),
-            0,
-            vec![executed_full_exit_op],
-            (0, 0),
-            50,
-            1_000_000.into(),
-            1_500_000.into(),
-            H256::default(),
-            0,
-        ),
-    }
+    Block::new(
+        block_number,
+        Fr::default(),
+        0,
+        vec![executed_full_exit_op],
+        (0, 0),
+        50,
+        1_000_000.into(),
+        1_500_000.into(),
+        H256::default(),
+        0,
+    )
+}
+
+/// Creates a dummy operation as a test input for `ETHSender` tests.
+fn get_operation(
+    id: i64,
+    block_number: BlockNumber,
+    action: AggregatedActionType,
+) -> (i64, AggregatedOperation) {
+    let operation = match action {
+        AggregatedActionType::CommitBlocks => {
+            AggregatedOperation::CommitBlocks(BlocksCommitOperation {
+                last_committed_block: get_block(block_number - 1),
+                blocks: vec![get_block(block_number)],
+            })
+        }
+        AggregatedActionType::ExecuteBlocks => {
+            AggregatedOperation::ExecuteBlocks(BlocksExecuteOperation {
+                blocks: vec![get_block(block_number)],
+            })
+        }
+        _ => panic!("Unsupported operation type: {}", action.to_string()),
+    };
+    (id, operation)
 }
 
 lazy_static! {
-    pub static ref COMMIT_OPERATIONS: Vec<Operation> = (1..10)
-        .map(|id| get_operation(id, id as u32, Action::Commit))
+    pub static ref COMMIT_OPERATIONS: Vec<(i64, AggregatedOperation)> = (1..10)
+        .map(|id| get_operation(id, id as u32, AggregatedActionType::CommitBlocks))
         .collect();
-    pub static ref VERIFY_OPERATIONS: Vec<Operation> = (11..20)
-        .map(|id| get_operation(
-            id,
-            (id - 10) as u32,
-            Action::Verify {
-                proof: Default::default()
-            }
-        ))
+    pub static ref VERIFY_OPERATIONS: Vec<(i64, AggregatedOperation)> = (11..20)
+        .map(|id| get_operation(id, (id - 10) as u32, AggregatedActionType::ExecuteBlocks))
         .collect();
 }
 
-pub fn commit_operation(idx: usize) -> Operation {
+pub fn commit_operation(idx: usize) -> (i64, AggregatedOperation) {
     assert!(
         idx < COMMIT_OPERATIONS.len(),
         format!("Index {} is out of bounds for commit operations", idx)
@@ -79,7 +94,7 @@ pub fn commit_operation(idx: usize) -> Operation {
     COMMIT_OPERATIONS[idx].clone()
 }
 
-pub fn verify_operation(idx: usize) -> Operation {
+pub fn verify_operation(idx: usize) -> (i64, AggregatedOperation) {
     assert!(
         idx < VERIFY_OPERATIONS.len(),
         format!("Index {} is out of bounds for verify operations", idx)
@@ -35,7 +35,7 @@ impl From<StorageETHStats> for ETHStats {
 
 /// State of the executed Ethereum transaction.
 #[derive(Debug, Clone)]
-pub(super) struct ExecutedTxStatus {
+pub struct ExecutedTxStatus {
     /// Amount of confirmations for a block containing the transaction.
     pub confirmations: u64,
     /// Whether transaction was executed successfully or failed.
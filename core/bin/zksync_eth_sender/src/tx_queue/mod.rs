@@ -123,7 +123,7 @@ impl TxQueue {
 
         self.aggregated_operations.push_back(aggregate_operation);
 
-        log::info!(
+        tracing::info!(
             "Adding operation to the queue. \
             Sent pending txs count: {}, \
             max pending txs count: {}, \
@@ -134,6 +134,11 @@ impl TxQueue {
         );
     }
 
+    /// Returns the amount of the operations waiting to be sent.
+    pub fn queued_operations(&self) -> usize {
+        self.aggregated_operations.len()
+    }
+
     /// Returns a previously popped element to the front of the queue.
     pub fn return_popped(&mut self, element: TxData) {
         assert!(
@@ -172,6 +177,19 @@ impl TxQueue {
         self.aggregated_operations.pop_front()
     }
 
+    /// Encodes the queued operations again, e.g. once the ABI of the contract is changed.
+    pub fn reencode_operations(&mut self, encode: impl Fn(&AggregatedOperation) -> RawTxData) {
+        for tx in self.aggregated_operations.elements.iter_mut() {
+            tx.raw = encode(&tx.operation.1);
+        }
+    }
+
+    /// Sets the amount of transactions "in the fly", e.g. once the ongoing operations
+    /// are reloaded from the database.
+    pub fn set_sent_pending_txs(&mut self, sent_pending_txs: usize) {
+        self.sent_pending_txs = sent_pending_txs;
+    }
+
     /// Notifies the queue about the transaction being confirmed on the Ethereum blockchain.
     /// Decrements the amount of transactions "in the fly".
     pub fn report_commitment(&mut self) {
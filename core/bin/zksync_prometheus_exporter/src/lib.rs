@@ -1,5 +1,12 @@
-//! This module handles metric export to the Prometheus server
+//! This module handles metric export to the Prometheus server.
+//!
+//! Metrics are reported by the subsystems through the `metrics` facade and exported over HTTP
+//! by the recorder installed in `install_prometheus_exporter`. Every binary installs it before
+//! starting its subsystems, and every subsystem describes the metrics it reports with
+//! `register_metrics` upon the start, so the exporter provides the help text for them and
+//! the gauges are exported even before they're set for the first time.
 
+use metrics::{Key, Recorder};
 use metrics_exporter_prometheus::PrometheusBuilder;
 use std::{thread, time::Duration};
 use tokio::task::JoinHandle;
@@ -8,10 +15,77 @@ use zksync_types::ActionType::*;
 
 const QUERY_INTERVAL: Duration = Duration::from_secs(60);
 
-pub fn run_prometheus_exporter(
-    connection_pool: ConnectionPool,
-    port: u16,
-) -> (JoinHandle<()>, JoinHandle<()>) {
+/// Kind of the metric reported by a subsystem.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MetricKind {
+    Counter,
+    Gauge,
+    Histogram,
+}
+
+/// Description of the metric reported by a subsystem.
+#[derive(Debug, Clone, Copy)]
+pub struct Metric {
+    pub name: &'static str,
+    pub kind: MetricKind,
+    pub description: &'static str,
+}
+
+impl Metric {
+    pub const fn counter(name: &'static str, description: &'static str) -> Self {
+        Self {
+            name,
+            kind: MetricKind::Counter,
+            description,
+        }
+    }
+
+    pub const fn gauge(name: &'static str, description: &'static str) -> Self {
+        Self {
+            name,
+            kind: MetricKind::Gauge,
+            description,
+        }
+    }
+
+    pub const fn histogram(name: &'static str, description: &'static str) -> Self {
+        Self {
+            name,
+            kind: MetricKind::Histogram,
+            description,
+        }
+    }
+}
+
+/// Registers the metrics reported by the subsystem in the installed recorder.
+/// Does nothing if the exporter is not installed (e.g. in tests).
+pub fn register_metrics(subsystem: &str, metrics: &[Metric]) {
+    let recorder = match metrics::try_recorder() {
+        Some(recorder) => recorder,
+        None => return,
+    };
+
+    for metric in metrics {
+        let key = Key::from_name(metric.name);
+        let description = Some(metric.description);
+        match metric.kind {
+            MetricKind::Counter => recorder.register_counter(key, None, description),
+            MetricKind::Gauge => recorder.register_gauge(key, None, description),
+            MetricKind::Histogram => recorder.register_histogram(key, None, description),
+        }
+    }
+    tracing::info!("Registered {} metrics of the {}", metrics.len(), subsystem);
+}
+
+/// Metrics reported by the exporter itself.
+const METRICS: &[Metric] = &[Metric::gauge(
+    "count_operations",
+    "Number of the block operations by action and confirmation status",
+)];
+
+/// Installs the metrics recorder and serves the reported metrics over HTTP on the given port.
+/// Must be called before the subsystems of the binary are started, so their metrics are registered.
+pub fn install_prometheus_exporter(port: u16) -> JoinHandle<()> {
     let addr = ([0, 0, 0, 0], port);
     let (recorder, exporter) = PrometheusBuilder::new()
         .listen_address(addr)
@@ -19,14 +93,23 @@ pub fn run_prometheus_exporter(
         .expect("failed to install Prometheus recorder");
     metrics::set_boxed_recorder(Box::new(recorder)).expect("failed to set metrics recorder");
 
-    let prometheus_handle = tokio::spawn(async move {
+    tokio::spawn(async move {
         tokio::pin!(exporter);
         loop {
             tokio::select! {
                 _ = &mut exporter => {}
             }
         }
-    });
+    })
+}
+
+/// Installs the exporter along with the counter of the block operations stored in the database.
+pub fn run_prometheus_exporter(
+    connection_pool: ConnectionPool,
+    port: u16,
+) -> (JoinHandle<()>, JoinHandle<()>) {
+    let prometheus_handle = install_prometheus_exporter(port);
+    register_metrics("Prometheus exporter", METRICS);
 
     let operation_counter_handle = tokio::spawn(async move {
         let mut storage = connection_pool
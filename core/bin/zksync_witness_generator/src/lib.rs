@@ -1,10 +1,11 @@
 // Built-in
+use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
 use std::thread;
 use std::time::Duration;
 // External
 use actix_web::dev::ServiceRequest;
-use actix_web::{web, App, HttpResponse, HttpServer};
+use actix_web::{web, App, HttpMessage, HttpRequest, HttpResponse, HttpServer};
 use actix_web_httpauth::extractors::{
     bearer::{BearerAuth, Config},
     AuthenticationError,
@@ -12,17 +13,19 @@ use actix_web_httpauth::extractors::{
 use actix_web_httpauth::middleware::HttpAuthentication;
 use futures::channel::mpsc;
 use jsonwebtoken::errors::Error as JwtError;
-use jsonwebtoken::{decode, DecodingKey, Validation};
+use jsonwebtoken::{dangerous_unsafe_decode, decode, DecodingKey, Validation};
+use rustls::{NoClientAuth, ServerConfig};
 use serde::{Deserialize, Serialize};
 // Workspace deps
 use zksync_config::ProverOptions;
+use zksync_prometheus_exporter::{register_metrics, Metric};
 use zksync_storage::{ConnectionPool, StorageProcessor};
 // Local deps
 use self::scaler::ScalerOracle;
 use zksync_circuit::serialization::ProverData;
 use zksync_prover_utils::api::{
     JobRequestData, JobResultData, ProverInputRequest, ProverInputResponse, ProverOutputRequest,
-    WorkingOn,
+    ProverStopped, WorkingOn,
 };
 use zksync_types::aggregated_operations::{
     AggregatedActionType, AggregatedOperation, BlocksCreateProofOperation,
@@ -30,11 +33,46 @@ use zksync_types::aggregated_operations::{
 use zksync_types::prover::{
     ProverJobType, AGGREGATED_PROOF_JOB_PRIORITY, SINGLE_PROOF_JOB_PRIORITY,
 };
-use zksync_utils::panic_notify::ThreadPanicNotify;
+use zksync_utils::{panic_notify::ThreadPanicNotify, tls::load_pem};
 
 mod scaler;
 mod witness_generator;
 
+/// Interval between the reloads of the provers secrets from the database.
+const PROVER_SECRETS_RELOAD_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Metrics reported by the prover server and witness generators.
+const METRICS: &[Metric] = &[
+    Metric::counter(
+        "prover_server.jobs_assigned",
+        "Number of the prover jobs assigned to the provers",
+    ),
+    Metric::counter(
+        "prover_server.proofs_received",
+        "Number of the proofs received from the provers by proof kind",
+    ),
+    Metric::gauge(
+        "prover.running",
+        "Number of the provers which have not reported the stop",
+    ),
+    Metric::gauge(
+        "prover.max_seconds_since_heartbeat",
+        "Time since the latest heartbeat of the most lagging prover",
+    ),
+    Metric::gauge(
+        "prover.jobs_completed",
+        "Number of the jobs completed by the running provers",
+    ),
+    Metric::gauge(
+        "prover.average_proving_time_secs",
+        "Average time the running provers spend on a job",
+    ),
+    Metric::histogram(
+        "witness_generator.load_account_tree",
+        "Time spent on restoring the account tree for the witness",
+    ),
+];
+
 #[derive(Debug, Serialize, Deserialize)]
 struct PayloadAuthToken {
     /// Subject (whom auth token refers to).
@@ -43,9 +81,59 @@ struct PayloadAuthToken {
     exp: usize,
 }
 
+/// Secrets the auth tokens of the provers are signed with.
+///
+/// Own secrets of the provers are kept in memory and reloaded from the database periodically,
+/// so the requests are authenticated without accessing the database.
+#[derive(Debug, Clone)]
+struct ProverSecrets {
+    /// Shared secret accepted for the provers having no own secret.
+    shared: String,
+    own: Arc<RwLock<HashMap<String, String>>>,
+}
+
+impl ProverSecrets {
+    fn new(shared: String) -> Self {
+        Self {
+            shared,
+            own: Default::default(),
+        }
+    }
+
+    /// Returns the secret the auth token of the prover must be signed with.
+    fn secret(&self, prover_name: &str) -> String {
+        self.own
+            .read()
+            .unwrap()
+            .get(prover_name)
+            .cloned()
+            .unwrap_or_else(|| self.shared.clone())
+    }
+
+    async fn reload(&self, connection_pool: &ConnectionPool) -> anyhow::Result<()> {
+        let own = connection_pool
+            .access_storage()
+            .await?
+            .prover_schema()
+            .load_prover_secrets()
+            .await?;
+        *self.own.write().unwrap() = own;
+        Ok(())
+    }
+}
+
+async fn reload_prover_secrets_loop(connection_pool: ConnectionPool, secrets: ProverSecrets) {
+    let mut timer = tokio::time::interval(PROVER_SECRETS_RELOAD_INTERVAL);
+    loop {
+        timer.tick().await;
+        if let Err(e) = secrets.reload(&connection_pool).await {
+            vlog::warn!("Failed to reload the provers secrets: {}", e);
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 struct AppState {
-    secret_auth: String,
     connection_pool: zksync_storage::ConnectionPool,
     scaler_oracle: Arc<RwLock<ScalerOracle>>,
     prover_timeout: Duration,
@@ -53,7 +141,6 @@ struct AppState {
 
 impl AppState {
     pub fn new(
-        secret_auth: String,
         connection_pool: ConnectionPool,
         prover_timeout: Duration,
         idle_provers: u32,
@@ -64,7 +151,6 @@ impl AppState {
         )));
 
         Self {
-            secret_auth,
             connection_pool,
             scaler_oracle,
             prover_timeout,
@@ -79,37 +165,74 @@ impl AppState {
     }
 }
 
-/// The structure that stores the secret key for checking JsonWebToken matching.
-struct AuthTokenValidator<'a> {
-    decoding_key: DecodingKey<'a>,
-}
+/// Name of the prover that has sent the request, taken from the subject of its auth token.
+#[derive(Debug, Clone)]
+struct AuthenticatedProver(String);
 
-impl<'a> AuthTokenValidator<'a> {
-    fn new(secret: &'a str) -> Self {
-        Self {
-            decoding_key: DecodingKey::from_secret(secret.as_ref()),
-        }
-    }
+/// Checks whether the secret key and the authorization token match.
+fn validate_auth_token(token: &str, secret: &str) -> Result<(), JwtError> {
+    let decoding_key = DecodingKey::from_secret(secret.as_ref());
+    decode::<PayloadAuthToken>(token, &decoding_key, &Validation::default())?;
 
-    /// Checks whether the secret key and the authorization token match.
-    fn validate_auth_token(&self, token: &str) -> Result<(), JwtError> {
-        decode::<PayloadAuthToken>(token, &self.decoding_key, &Validation::default())?;
+    Ok(())
+}
 
-        Ok(())
+/// Authenticates the prover by the subject of its auth token.
+///
+/// Token of the prover that has its own secret must be signed with it,
+/// the shared secret is accepted for the rest of provers.
+async fn validator(
+    req: ServiceRequest,
+    credentials: BearerAuth,
+) -> actix_web::Result<ServiceRequest> {
+    let config = req.app_data::<Config>().cloned().unwrap_or_default();
+    let secrets = req
+        .app_data::<web::Data<ProverSecrets>>()
+        .expect("failed get ProverSecrets upon receipt of the authentication token");
+    let token = credentials.token();
+
+    // The subject is read before the signature check to select the secret it must be signed with.
+    // Secrets are kept in memory, so nothing is loaded on behalf of the unauthenticated request.
+    let prover_name = dangerous_unsafe_decode::<PayloadAuthToken>(token)
+        .map_err(|_| AuthenticationError::from(config.clone()))?
+        .claims
+        .sub;
+
+    if validate_auth_token(token, &secrets.secret(&prover_name)).is_err() {
+        vlog::warn!(
+            "Rejected the request with invalid auth token of prover '{}'",
+            prover_name
+        );
+        return Err(AuthenticationError::from(config).into());
     }
 
-    async fn validator(
-        &self,
-        req: ServiceRequest,
-        credentials: BearerAuth,
-    ) -> actix_web::Result<ServiceRequest> {
-        let config = req.app_data::<Config>().cloned().unwrap_or_default();
+    req.extensions_mut()
+        .insert(AuthenticatedProver(prover_name));
+    Ok(req)
+}
 
-        self.validate_auth_token(credentials.token())
-            .map_err(|_| AuthenticationError::from(config))?;
+/// Returns the name of the prover that has sent the request.
+fn authenticated_prover(req: &HttpRequest) -> actix_web::Result<String> {
+    req.extensions()
+        .get::<AuthenticatedProver>()
+        .map(|prover| prover.0.clone())
+        .ok_or_else(|| actix_web::error::ErrorUnauthorized("prover is not authenticated"))
+}
 
-        Ok(req)
+/// Ensures that the prover acts on its own behalf.
+fn check_prover_name(req: &HttpRequest, prover_name: &str) -> actix_web::Result<()> {
+    let authenticated_name = authenticated_prover(req)?;
+    if authenticated_name != prover_name {
+        vlog::warn!(
+            "Prover '{}' attempted to act on behalf of prover '{}'",
+            authenticated_name,
+            prover_name
+        );
+        return Err(actix_web::error::ErrorForbidden(
+            "prover name doesn't match the auth token",
+        ));
     }
+    Ok(())
 }
 
 async fn status() -> actix_web::Result<String> {
@@ -117,24 +240,35 @@ async fn status() -> actix_web::Result<String> {
 }
 
 async fn get_job(
+    req: HttpRequest,
     data: web::Data<AppState>,
     r: web::Json<ProverInputRequest>,
 ) -> actix_web::Result<HttpResponse> {
-    log::trace!("request block to prove from worker: {}", r.prover_name);
+    tracing::trace!("request block to prove from worker: {}", r.prover_name);
     if r.prover_name == "" {
         return Err(actix_web::error::ErrorBadRequest("empty name"));
     }
+    check_prover_name(&req, &r.prover_name)?;
     let mut storage = data.access_storage().await?;
+    storage
+        .prover_schema()
+        .record_prover_seen(&r.prover_name)
+        .await
+        .map_err(|e| {
+            vlog::warn!("failed to record prover request: {}", e);
+            actix_web::error::ErrorInternalServerError("storage layer error")
+        })?;
     let ret = storage
         .prover_schema()
-        .get_idle_prover_job_from_job_queue()
+        .get_idle_prover_job_from_job_queue(&r.prover_name)
         .await
         .map_err(|e| {
             vlog::warn!("could not get next unverified commit operation: {}", e);
             actix_web::error::ErrorInternalServerError("storage layer error")
         })?;
     if let Some(prover_job) = ret {
-        log::info!("satisfied request to prove from worker");
+        tracing::info!("satisfied request to prove from worker");
+        metrics::counter!("prover_server.jobs_assigned", 1);
         Ok(HttpResponse::Ok().json(ProverInputResponse {
             job_id: prover_job.job_id,
             first_block: prover_job.first_block,
@@ -155,17 +289,19 @@ async fn get_job(
 }
 
 async fn working_on(
+    req: HttpRequest,
     data: web::Data<AppState>,
     r: web::Json<WorkingOn>,
 ) -> actix_web::Result<HttpResponse> {
     // These heartbeats aren't really important, as they're sent
     // continuously while prover is performing computations.
-    log::trace!("Received heartbeat for prover_run with id: {}", r.job_id);
+    tracing::trace!("Received heartbeat for prover_run with id: {}", r.job_id);
+    check_prover_name(&req, &r.prover_name)?;
     let mut storage = data
         .access_storage()
         .await
         .map_err(actix_web::error::ErrorInternalServerError)?;
-    storage
+    let lease_held = storage
         .prover_schema()
         .record_prover_is_working(r.job_id, &r.prover_name)
         .await
@@ -174,39 +310,78 @@ async fn working_on(
             actix_web::error::ErrorInternalServerError("storage layer error")
         })?;
 
+    if !lease_held {
+        tracing::info!(
+            "Prover '{}' sent a heartbeat for the job {} which is not leased to it",
+            r.prover_name,
+            r.job_id
+        );
+        return Ok(HttpResponse::Conflict().body("job is not leased to the prover"));
+    }
+
     Ok(HttpResponse::Ok().finish())
 }
 
 async fn publish(
+    req: HttpRequest,
     data: web::Data<AppState>,
     r: web::Json<ProverOutputRequest>,
 ) -> actix_web::Result<HttpResponse> {
+    let prover_name = authenticated_prover(&req)?;
     let mut storage = data
         .access_storage()
         .await
         .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    // Only the prover holding the lease may publish the proof, so the proof
+    // can be attributed to the prover that has produced it.
+    let lease_holder = storage
+        .prover_schema()
+        .load_job_lease_holder(r.job_id)
+        .await
+        .map_err(|e| {
+            vlog::warn!("failed to load the prover job: {}", e);
+            actix_web::error::ErrorInternalServerError("storage layer error")
+        })?;
+    if lease_holder.as_deref() != Some(prover_name.as_str()) {
+        vlog::warn!(
+            "Prover '{}' published a proof for the job {} which is not leased to it",
+            prover_name,
+            r.job_id
+        );
+        return Ok(HttpResponse::Conflict().body("job is not leased to the prover"));
+    }
+
     let storage_result = match &r.data {
         JobResultData::BlockProof(single_proof) => {
-            log::info!(
-                "Received a proof for job: {}, single block: {}",
+            tracing::info!(
+                "Received a proof for job: {}, single block: {} from prover '{}'",
                 r.job_id,
-                r.first_block
+                r.first_block,
+                prover_name
             );
             storage
                 .prover_schema()
-                .store_proof(r.job_id, r.first_block, single_proof)
+                .store_proof(r.job_id, r.first_block, single_proof, &prover_name)
                 .await
         }
         JobResultData::AggregatedBlockProof(aggregated_proof) => {
-            log::info!(
-                "Received a proof for job: {}, aggregated blocks: [{},{}]",
+            tracing::info!(
+                "Received a proof for job: {}, aggregated blocks: [{},{}] from prover '{}'",
                 r.job_id,
                 r.first_block,
-                r.last_block
+                r.last_block,
+                prover_name
             );
             storage
                 .prover_schema()
-                .store_aggregated_proof(r.job_id, r.first_block, r.last_block, aggregated_proof)
+                .store_aggregated_proof(
+                    r.job_id,
+                    r.first_block,
+                    r.last_block,
+                    aggregated_proof,
+                    &prover_name,
+                )
                 .await
         }
     };
@@ -220,26 +395,35 @@ async fn publish(
         return Err(actix_web::error::ErrorInternalServerError(message));
     }
 
+    let proof_kind = match &r.data {
+        JobResultData::BlockProof(_) => "single",
+        JobResultData::AggregatedBlockProof(_) => "aggregated",
+    };
+    metrics::counter!("prover_server.proofs_received", 1, "kind" => proof_kind);
+
     Ok(HttpResponse::Ok().finish())
 }
 
 async fn stopped(
+    req: HttpRequest,
     data: web::Data<AppState>,
-    prover_name: web::Json<String>,
+    r: web::Json<ProverStopped>,
 ) -> actix_web::Result<HttpResponse> {
+    let prover_name = &r.prover_id;
+    check_prover_name(&req, prover_name)?;
     let mut storage = data
         .access_storage()
         .await
         .map_err(actix_web::error::ErrorInternalServerError)?;
 
-    log::info!(
+    tracing::info!(
         "Prover instance '{}' send a stopping notification",
-        &prover_name
+        prover_name
     );
 
     storage
         .prover_schema()
-        .record_prover_stop(&prover_name)
+        .record_prover_stop(prover_name)
         .await
         .map_err(|e| {
             vlog::warn!("failed to record prover stop: {}", e);
@@ -280,21 +464,64 @@ async fn required_replicas(
     Ok(HttpResponse::Ok().json(response))
 }
 
-async fn update_prover_job_queue_loop(connection_pool: ConnectionPool) {
+async fn update_prover_job_queue_loop(connection_pool: ConnectionPool, prover_timeout: Duration) {
     let mut interval = tokio::time::interval(Duration::from_secs(5));
     loop {
         interval.tick().await;
 
         if let Ok(mut storage) = connection_pool.access_storage().await {
-            update_prover_job_queue(&mut storage)
+            update_prover_job_queue(&mut storage, prover_timeout)
+                .await
+                .unwrap_or_default();
+            report_provers_metrics(&mut storage)
                 .await
                 .unwrap_or_default();
         }
     }
 }
 
-async fn update_prover_job_queue(storage: &mut StorageProcessor<'_>) -> anyhow::Result<()> {
-    {
+/// Reports the state of the running provers, so the provers that stopped sending heartbeats
+/// without notifying the server can be spotted.
+///
+/// Prover names are chosen by the provers, so they are not used as the metric labels:
+/// the metrics are aggregated over all the provers, and the admin API lists every prover.
+async fn report_provers_metrics(storage: &mut StorageProcessor<'_>) -> anyhow::Result<()> {
+    let now = chrono::Utc::now();
+    let provers = storage.prover_schema().load_provers().await?;
+
+    let max_since_heartbeat = provers
+        .iter()
+        .map(|prover| {
+            now.signed_duration_since(prover.last_heartbeat)
+                .num_milliseconds() as f64
+                / 1000.0
+        })
+        .fold(0.0, f64::max);
+    let jobs_completed: i64 = provers.iter().map(|prover| prover.jobs_completed).sum();
+    let total_proving_time: f64 = provers
+        .iter()
+        .map(|prover| prover.total_proving_time_secs)
+        .sum();
+    let average_proving_time = if jobs_completed > 0 {
+        total_proving_time / jobs_completed as f64
+    } else {
+        0.0
+    };
+
+    metrics::gauge!("prover.running", provers.len() as f64);
+    metrics::gauge!("prover.max_seconds_since_heartbeat", max_since_heartbeat);
+    metrics::gauge!("prover.jobs_completed", jobs_completed as f64);
+    metrics::gauge!("prover.average_proving_time_secs", average_proving_time);
+    Ok(())
+}
+
+async fn update_prover_job_queue(
+    storage: &mut StorageProcessor<'_>,
+    prover_timeout: Duration,
+) -> anyhow::Result<()> {
+    // Witnesses are prepared by several generators in parallel, so all of the
+    // ready ones are added to the queue at once.
+    loop {
         let mut prover_schema = storage.prover_schema();
         let next_single_block_to_add = prover_schema
             .get_last_block_prover_job_queue(ProverJobType::SingleProof)
@@ -318,6 +545,8 @@ async fn update_prover_job_queue(storage: &mut StorageProcessor<'_>) -> anyhow::
                     ProverJobType::SingleProof,
                 )
                 .await?;
+        } else {
+            break;
         }
     }
 
@@ -372,7 +601,10 @@ async fn update_prover_job_queue(storage: &mut StorageProcessor<'_>) -> anyhow::
                 .await?;
         }
     }
-    storage.prover_schema().mark_stale_jobs_as_idle().await?;
+    storage
+        .prover_schema()
+        .mark_stale_jobs_as_idle(prover_timeout)
+        .await?;
     Ok(())
 }
 
@@ -381,6 +613,18 @@ pub fn run_prover_server(
     panic_notify: mpsc::Sender<bool>,
     prover_options: ProverOptions,
 ) {
+    register_metrics("prover server", METRICS);
+
+    let tls_config = prover_options.tls.as_ref().map(|options| {
+        let (cert_chain, key) = load_pem(&options.cert_path, &options.key_path)
+            .expect("Unable to load the TLS certificate");
+        let mut config = ServerConfig::new(NoClientAuth::new());
+        config
+            .set_single_cert(cert_chain, key)
+            .expect("Unable to use the TLS certificate");
+        config
+    });
+
     thread::Builder::new()
         .name("prover_server".to_string())
         .spawn(move || {
@@ -388,7 +632,22 @@ pub fn run_prover_server(
             let mut actix_runtime = actix_rt::System::new("prover-server");
 
             actix_runtime.block_on(async move {
-                tokio::spawn(update_prover_job_queue_loop(connection_pool.clone()));
+                tokio::spawn(update_prover_job_queue_loop(
+                    connection_pool.clone(),
+                    prover_options.gone_timeout,
+                ));
+
+                // Secrets are loaded before the server starts, so the provers
+                // with own secrets aren't rejected meanwhile.
+                let prover_secrets = ProverSecrets::new(prover_options.secret_auth.clone());
+                prover_secrets
+                    .reload(&connection_pool)
+                    .await
+                    .expect("Failed to load the provers secrets");
+                tokio::spawn(reload_prover_secrets_loop(
+                    connection_pool.clone(),
+                    prover_secrets.clone(),
+                ));
 
                 let last_verified_block = {
                     let mut storage = connection_pool
@@ -409,7 +668,7 @@ pub fn run_prover_server(
                 for offset in 0..prover_options.witness_generators {
                     let start_block = (last_verified_block + offset + 1) as u32;
                     let block_step = prover_options.witness_generators as u32;
-                    log::info!(
+                    tracing::info!(
                         "Starting witness generator ({},{})",
                         start_block,
                         block_step
@@ -417,54 +676,159 @@ pub fn run_prover_server(
                     let pool_maintainer = witness_generator::WitnessGenerator::new(
                         connection_pool.clone(),
                         prover_options.prepare_data_interval,
+                        prover_options.witness_generator_max_pending_jobs,
                         start_block,
                         block_step,
                     );
                     pool_maintainer.start(panic_notify.clone());
                 }
                 // Start HTTP server.
-                let secret_auth = prover_options.secret_auth.clone();
                 let gone_timeout = prover_options.gone_timeout;
                 let idle_provers = prover_options.idle_provers;
-                HttpServer::new(move || {
-                    let app_state = AppState::new(
-                        secret_auth.clone(),
-                        connection_pool.clone(),
-                        gone_timeout,
-                        idle_provers,
-                    );
+                let server = HttpServer::new(move || {
+                    let app_state =
+                        AppState::new(connection_pool.clone(), gone_timeout, idle_provers);
 
-                    let _auth = HttpAuthentication::bearer(move |req, credentials| async {
-                        let secret_auth = req
-                            .app_data::<web::Data<AppState>>()
-                            .expect("failed get AppState upon receipt of the authentication token")
-                            .secret_auth
-                            .clone();
-                        AuthTokenValidator::new(&secret_auth)
-                            .validator(req, credentials)
-                            .await
-                    });
+                    let auth = HttpAuthentication::bearer(validator);
 
                     // By calling `register_data` instead of `data` we're avoiding double
                     // `Arc` wrapping of the object.
                     App::new()
-                        // .wrap(auth) // TODO: restore auth
                         .app_data(web::Data::new(app_state))
+                        .app_data(web::Data::new(prover_secrets.clone()))
                         .route("/status", web::get().to(status))
-                        .route("/get_job", web::get().to(get_job))
-                        .route("/working_on", web::post().to(working_on))
-                        .route("/publish", web::post().to(publish))
-                        .route("/stopped", web::post().to(stopped))
-                        .route(
-                            "/api/internal/prover/replicas",
-                            web::post().to(required_replicas),
+                        .service(
+                            web::scope("")
+                                .wrap(auth)
+                                .route("/get_job", web::get().to(get_job))
+                                .route("/working_on", web::post().to(working_on))
+                                .route("/publish", web::post().to(publish))
+                                .route("/stopped", web::post().to(stopped))
+                                .route(
+                                    "/api/internal/prover/replicas",
+                                    web::post().to(required_replicas),
+                                ),
                         )
-                })
-                .bind(&prover_options.prover_server_address)
-                .expect("failed to bind")
-                .run()
-                .await
+                });
+                let server = match tls_config {
+                    Some(tls_config) => {
+                        server.bind_rustls(&prover_options.prover_server_address, tls_config)
+                    }
+                    None => server.bind(&prover_options.prover_server_address),
+                };
+                server.expect("failed to bind").run().await
             })
         })
         .expect("failed to start prover server");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{dev::Service, http::StatusCode, test};
+    use zksync_prover::auth_utils::AuthTokenGenerator;
+
+    const SHARED_SECRET: &str = "shared";
+
+    fn token(secret: &str, prover_name: &str, lifetime: Duration) -> String {
+        AuthTokenGenerator::new(secret.to_string(), prover_name.to_string(), lifetime)
+            .encode()
+            .unwrap()
+    }
+
+    async fn whoami(req: HttpRequest) -> actix_web::Result<String> {
+        authenticated_prover(&req)
+    }
+
+    /// Checks that the provers are authenticated with their own secrets if they have ones,
+    /// and the requests with the missing or invalid tokens are rejected.
+    #[actix_rt::test]
+    async fn prover_authentication() {
+        let secrets = ProverSecrets::new(SHARED_SECRET.to_string());
+        secrets
+            .own
+            .write()
+            .unwrap()
+            .insert("own".to_string(), "own_secret".to_string());
+
+        let mut app = test::init_service(
+            App::new().app_data(web::Data::new(secrets)).service(
+                web::scope("")
+                    .wrap(HttpAuthentication::bearer(validator))
+                    .route("/whoami", web::get().to(whoami)),
+            ),
+        )
+        .await;
+
+        let lifetime = Duration::from_secs(60);
+        let cases = vec![
+            (None, StatusCode::UNAUTHORIZED),
+            (Some("malformed".to_string()), StatusCode::UNAUTHORIZED),
+            (
+                Some(token("wrong", "shared_prover", lifetime)),
+                StatusCode::UNAUTHORIZED,
+            ),
+            // The prover having its own secret can't use the shared one.
+            (
+                Some(token(SHARED_SECRET, "own", lifetime)),
+                StatusCode::UNAUTHORIZED,
+            ),
+            (
+                Some(token("own_secret", "shared_prover", lifetime)),
+                StatusCode::UNAUTHORIZED,
+            ),
+            (
+                Some(token(SHARED_SECRET, "shared_prover", lifetime)),
+                StatusCode::OK,
+            ),
+            (Some(token("own_secret", "own", lifetime)), StatusCode::OK),
+        ];
+
+        for (token, expected_status) in cases {
+            let mut req = test::TestRequest::get().uri("/whoami");
+            if let Some(token) = &token {
+                req = req.header("Authorization", format!("Bearer {}", token));
+            }
+            let status = match app.call(req.to_request()).await {
+                Ok(resp) => resp.status(),
+                Err(err) => err.as_response_error().status_code(),
+            };
+            assert_eq!(status, expected_status, "token: {:?}", token);
+        }
+
+        // The authenticated prover is the subject of the token.
+        let req = test::TestRequest::get()
+            .uri("/whoami")
+            .header(
+                "Authorization",
+                format!("Bearer {}", token("own_secret", "own", lifetime)),
+            )
+            .to_request();
+        let body = test::read_response(&mut app, req).await;
+        assert_eq!(body, "own");
+    }
+
+    /// Checks that the provers can't act on behalf of another ones.
+    #[test]
+    fn prover_name_check() {
+        let req = test::TestRequest::default().to_http_request();
+        assert_eq!(
+            check_prover_name(&req, "prover")
+                .unwrap_err()
+                .as_response_error()
+                .status_code(),
+            StatusCode::UNAUTHORIZED
+        );
+
+        req.extensions_mut()
+            .insert(AuthenticatedProver("prover".to_string()));
+        assert!(check_prover_name(&req, "prover").is_ok());
+        assert_eq!(
+            check_prover_name(&req, "another")
+                .unwrap_err()
+                .as_response_error()
+                .status_code(),
+            StatusCode::FORBIDDEN
+        );
+    }
+}
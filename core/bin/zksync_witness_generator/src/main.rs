@@ -1,6 +1,7 @@
 use futures::{channel::mpsc, executor::block_on, SinkExt, StreamExt};
 use std::cell::RefCell;
-use zksync_config::ProverOptions;
+use zksync_config::{ConfigurationOptions, ProverOptions};
+use zksync_prometheus_exporter::install_prometheus_exporter;
 use zksync_storage::ConnectionPool;
 use zksync_witness_generator::run_prover_server;
 
@@ -9,7 +10,7 @@ async fn main() -> anyhow::Result<()> {
     // `eth_sender` doesn't require many connections to the database.
     const WITNESS_GENERATOR_CONNECTION_POOL_SIZE: u32 = 2;
 
-    env_logger::init();
+    vlog::init();
 
     // handle ctrl+c
     let (stop_signal_sender, mut stop_signal_receiver) = mpsc::channel(256);
@@ -24,10 +25,19 @@ async fn main() -> anyhow::Result<()> {
 
     let connection_pool = ConnectionPool::new(Some(WITNESS_GENERATOR_CONNECTION_POOL_SIZE));
     let prover_options = ProverOptions::from_env();
+    let prometheus_handle =
+        install_prometheus_exporter(ConfigurationOptions::from_env().prometheus_export_port);
 
     run_prover_server(connection_pool, stop_signal_sender, prover_options);
 
-    stop_signal_receiver.next().await;
+    tokio::select! {
+        _ = async { prometheus_handle.await } => {
+            panic!("Prometheus exporter actors aren't supposed to finish their execution")
+        },
+        _ = async { stop_signal_receiver.next().await } => {
+            tracing::warn!("Stop signal received, shutting down");
+        }
+    };
 
     Ok(())
 }
@@ -2,6 +2,7 @@
 use std::{thread, time};
 // External
 use futures::channel::mpsc;
+use tokio::time::delay_for;
 // Workspace deps
 use zksync_circuit::serialization::ProverData;
 use zksync_circuit::witness::utils::build_block_witness;
@@ -17,11 +18,17 @@ use zksync_utils::panic_notify::ThreadPanicNotify;
 ///
 /// This will generate and store in db witnesses for blocks with indexes
 /// start_block, start_block + block_step, start_block + 2*block_step, ...
+///
+/// Every generator runs in its own thread, so the witnesses for the consecutive
+/// blocks are prepared in parallel by the different generators.
 pub struct WitnessGenerator {
     /// Connection to the database.
     conn_pool: zksync_storage::ConnectionPool,
     /// Routine refresh interval.
     rounds_interval: time::Duration,
+    /// Generator waits while there are that many prover jobs in the queue,
+    /// since the witnesses are not needed until the provers can take them.
+    max_pending_jobs: u32,
 
     start_block: BlockNumber,
     block_step: BlockNumber,
@@ -38,12 +45,14 @@ impl WitnessGenerator {
     pub fn new(
         conn_pool: zksync_storage::ConnectionPool,
         rounds_interval: time::Duration,
+        max_pending_jobs: u32,
         start_block: BlockNumber,
         block_step: BlockNumber,
     ) -> Self {
         Self {
             conn_pool,
             rounds_interval,
+            max_pending_jobs,
             start_block,
             block_step,
         }
@@ -97,6 +106,13 @@ impl WitnessGenerator {
         Ok(block_info)
     }
 
+    /// Checks whether the provers are behind, so preparing new witnesses should be paused.
+    async fn prover_queue_is_full(&self) -> Result<bool, anyhow::Error> {
+        let mut storage = self.conn_pool.access_storage().await?;
+        let pending_jobs = storage.prover_schema().pending_jobs_count().await?;
+        Ok(pending_jobs >= self.max_pending_jobs)
+    }
+
     async fn load_account_tree(
         &self,
         block: BlockNumber,
@@ -195,14 +211,14 @@ impl WitnessGenerator {
         let mut circuit_account_tree = self
             .load_account_tree(block.block_number - 1, &mut storage)
             .await?;
-        log::trace!(
+        tracing::trace!(
             "Witness generator loading circuit account tree {}s",
             timer.elapsed().as_secs()
         );
 
         let timer = time::Instant::now();
         let witness: ProverData = build_block_witness(&mut circuit_account_tree, &block)?.into();
-        log::trace!(
+        tracing::trace!(
             "Witness generator witness build {}s",
             timer.elapsed().as_secs()
         );
@@ -234,33 +250,59 @@ impl WitnessGenerator {
         }
     }
 
-    /// Updates witness data in database in an infinite loop,
-    /// awaiting `rounds_interval` time between updates.
+    /// Updates witness data in database in an infinite loop.
+    /// Blocks are processed one after another without pause, `rounds_interval` is awaited
+    /// only when the next block is not ready yet or the prover queue is full.
     async fn maintain(self) {
-        log::info!(
+        tracing::info!(
             "preparing prover data routine started with start_block({}), block_step({})",
             self.start_block,
             self.block_step
         );
         let mut current_block = self.start_block;
         loop {
-            std::thread::sleep(self.rounds_interval);
             let should_work = match self.should_work_on_block(current_block).await {
                 Ok(should_work) => should_work,
                 Err(err) => {
-                    log::warn!("witness for block {} check failed: {}", current_block, err);
+                    tracing::warn!("witness for block {} check failed: {}", current_block, err);
+                    delay_for(self.rounds_interval).await;
                     continue;
                 }
             };
 
             let next_block = Self::next_witness_block(current_block, self.block_step, &should_work);
-            if let BlockInfo::NoWitness(block) = should_work {
-                let block_number = block.block_number;
-                if let Err(err) = self.prepare_witness_and_save_it(block).await {
-                    log::warn!("Witness generator ({},{}) failed to prepare witness for block: {}, err: {}",
-                        self.start_block, self.block_step, block_number, err);
-                    continue; // Retry the same block on the next iteration.
+            match should_work {
+                BlockInfo::NotReadyBlock => {
+                    delay_for(self.rounds_interval).await;
+                    continue;
+                }
+                BlockInfo::NoWitness(block) => {
+                    match self.prover_queue_is_full().await {
+                        Ok(false) => {}
+                        Ok(true) => {
+                            tracing::debug!(
+                                "Prover queue is full, witness for block {} is postponed",
+                                current_block
+                            );
+                            delay_for(self.rounds_interval).await;
+                            continue;
+                        }
+                        Err(err) => {
+                            tracing::warn!("prover queue check failed: {}", err);
+                            delay_for(self.rounds_interval).await;
+                            continue;
+                        }
+                    }
+
+                    let block_number = block.block_number;
+                    if let Err(err) = self.prepare_witness_and_save_it(block).await {
+                        tracing::warn!("Witness generator ({},{}) failed to prepare witness for block: {}, err: {}",
+                            self.start_block, self.block_step, block_number, err);
+                        delay_for(self.rounds_interval).await;
+                        continue; // Retry the same block on the next iteration.
+                    }
                 }
+                BlockInfo::WithWitness => {}
             }
 
             // Update current block.
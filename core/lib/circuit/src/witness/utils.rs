@@ -1,6 +1,7 @@
 // External deps
 use crypto::{digest::Digest, sha2::Sha256};
 use num::ToPrimitive;
+use rayon::prelude::*;
 use zksync_crypto::franklin_crypto::{
     alt_babyjubjub::AltJubjubBn256,
     bellman::pairing::{
@@ -609,6 +610,17 @@ impl SigDataInput {
     }
 }
 
+/// Deferred calculation of the circuit operations for a single zkSync operation.
+type OperationsBuilder = Box<dyn FnOnce() -> Vec<Operation<Bn256>> + Send>;
+
+fn operations_builder<W>(witness: W, input: W::CalculateOpsInput) -> OperationsBuilder
+where
+    W: Witness + Send + 'static,
+    W::CalculateOpsInput: Send + 'static,
+{
+    Box::new(move || witness.calculate_operations(input))
+}
+
 /// Get root hash of the used subtree.
 pub fn get_used_subtree_root_hash(account_tree: &CircuitAccountTree) -> Fr {
     // We take account 0, and hash it with it's Merkle proof.
@@ -651,7 +663,9 @@ pub fn build_block_witness<'a>(
         .iter()
         .filter_map(|tx| tx.get_executed_op().cloned());
 
-    let mut operations = vec![];
+    // Witnesses are built sequentially, since every operation changes the account tree.
+    // Circuit operations depend only on the witness, so they are calculated in parallel.
+    let mut operations_builders = vec![];
     let mut pub_data = vec![];
     let mut offset_commitment = vec![];
     let mut fees = vec![];
@@ -661,25 +675,22 @@ pub fn build_block_witness<'a>(
                 let deposit_witness =
                     DepositWitness::apply_tx(&mut witness_accum.account_tree, &deposit);
 
-                let deposit_operations = deposit_witness.calculate_operations(());
-                operations.extend(deposit_operations);
                 pub_data.extend(deposit_witness.get_pubdata());
-                offset_commitment.extend(deposit_witness.get_offset_commitment_data())
+                offset_commitment.extend(deposit_witness.get_offset_commitment_data());
+                operations_builders.push(operations_builder(deposit_witness, ()));
             }
             ZkSyncOp::Transfer(transfer) => {
                 let transfer_witness =
                     TransferWitness::apply_tx(&mut witness_accum.account_tree, &transfer);
 
                 let input = SigDataInput::from_transfer_op(&transfer)?;
-                let transfer_operations = transfer_witness.calculate_operations(input);
-
-                operations.extend(transfer_operations);
                 fees.push(CollectedFee {
                     token: transfer.tx.token,
                     amount: transfer.tx.fee,
                 });
                 pub_data.extend(transfer_witness.get_pubdata());
-                offset_commitment.extend(transfer_witness.get_offset_commitment_data())
+                offset_commitment.extend(transfer_witness.get_offset_commitment_data());
+                operations_builders.push(operations_builder(transfer_witness, input));
             }
             ZkSyncOp::TransferToNew(transfer_to_new) => {
                 let transfer_to_new_witness = TransferToNewWitness::apply_tx(
@@ -688,42 +699,35 @@ pub fn build_block_witness<'a>(
                 );
 
                 let input = SigDataInput::from_transfer_to_new_op(&transfer_to_new)?;
-                let transfer_to_new_operations =
-                    transfer_to_new_witness.calculate_operations(input);
-
-                operations.extend(transfer_to_new_operations);
                 fees.push(CollectedFee {
                     token: transfer_to_new.tx.token,
                     amount: transfer_to_new.tx.fee,
                 });
                 pub_data.extend(transfer_to_new_witness.get_pubdata());
-                offset_commitment.extend(transfer_to_new_witness.get_offset_commitment_data())
+                offset_commitment.extend(transfer_to_new_witness.get_offset_commitment_data());
+                operations_builders.push(operations_builder(transfer_to_new_witness, input));
             }
             ZkSyncOp::Withdraw(withdraw) => {
                 let withdraw_witness =
                     WithdrawWitness::apply_tx(&mut witness_accum.account_tree, &withdraw);
 
                 let input = SigDataInput::from_withdraw_op(&withdraw)?;
-                let withdraw_operations = withdraw_witness.calculate_operations(input);
-
-                operations.extend(withdraw_operations);
                 fees.push(CollectedFee {
                     token: withdraw.tx.token,
                     amount: withdraw.tx.fee,
                 });
                 pub_data.extend(withdraw_witness.get_pubdata());
-                offset_commitment.extend(withdraw_witness.get_offset_commitment_data())
+                offset_commitment.extend(withdraw_witness.get_offset_commitment_data());
+                operations_builders.push(operations_builder(withdraw_witness, input));
             }
             ZkSyncOp::Close(close) => {
                 let close_account_witness =
                     CloseAccountWitness::apply_tx(&mut witness_accum.account_tree, &close);
 
                 let input = SigDataInput::from_close_op(&close)?;
-                let close_account_operations = close_account_witness.calculate_operations(input);
-
-                operations.extend(close_account_operations);
                 pub_data.extend(close_account_witness.get_pubdata());
-                offset_commitment.extend(close_account_witness.get_offset_commitment_data())
+                offset_commitment.extend(close_account_witness.get_offset_commitment_data());
+                operations_builders.push(operations_builder(close_account_witness, input));
             }
             ZkSyncOp::FullExit(full_exit_op) => {
                 let success = full_exit_op.withdraw_amount.is_some();
@@ -733,11 +737,9 @@ pub fn build_block_witness<'a>(
                     &(*full_exit_op, success),
                 );
 
-                let full_exit_operations = full_exit_witness.calculate_operations(());
-
-                operations.extend(full_exit_operations);
                 pub_data.extend(full_exit_witness.get_pubdata());
-                offset_commitment.extend(full_exit_witness.get_offset_commitment_data())
+                offset_commitment.extend(full_exit_witness.get_offset_commitment_data());
+                operations_builders.push(operations_builder(full_exit_witness, ()));
             }
             ZkSyncOp::ChangePubKeyOffchain(change_pkhash_op) => {
                 let change_pkhash_witness = ChangePubkeyOffChainWitness::apply_tx(
@@ -746,35 +748,38 @@ pub fn build_block_witness<'a>(
                 );
 
                 let input = SigDataInput::from_change_pubkey_op(&change_pkhash_op)?;
-                let change_pkhash_operations = change_pkhash_witness.calculate_operations(input);
-
-                operations.extend(change_pkhash_operations);
                 fees.push(CollectedFee {
                     token: change_pkhash_op.tx.fee_token,
                     amount: change_pkhash_op.tx.fee,
                 });
                 pub_data.extend(change_pkhash_witness.get_pubdata());
-                offset_commitment.extend(change_pkhash_witness.get_offset_commitment_data())
+                offset_commitment.extend(change_pkhash_witness.get_offset_commitment_data());
+                operations_builders.push(operations_builder(change_pkhash_witness, input));
             }
             ZkSyncOp::ForcedExit(forced_exit) => {
                 let forced_exit_witness =
                     ForcedExitWitness::apply_tx(&mut witness_accum.account_tree, &forced_exit);
 
                 let input = SigDataInput::from_forced_exit_op(&forced_exit)?;
-                let forced_exit_operations = forced_exit_witness.calculate_operations(input);
-
-                operations.extend(forced_exit_operations);
                 fees.push(CollectedFee {
                     token: forced_exit.tx.token,
                     amount: forced_exit.tx.fee,
                 });
                 pub_data.extend(forced_exit_witness.get_pubdata());
-                offset_commitment.extend(forced_exit_witness.get_offset_commitment_data())
+                offset_commitment.extend(forced_exit_witness.get_offset_commitment_data());
+                operations_builders.push(operations_builder(forced_exit_witness, input));
             }
             ZkSyncOp::Noop(_) => {} // Noops are handled below
         }
     }
 
+    // `collect` preserves the order of the operations.
+    let operations: Vec<Vec<Operation<Bn256>>> = operations_builders
+        .into_par_iter()
+        .map(|build_operations| build_operations())
+        .collect();
+    let operations = operations.into_iter().flatten().collect();
+
     witness_accum.add_operation_with_pubdata(operations, pub_data, offset_commitment);
     witness_accum.extend_pubdata_with_noops(block_size);
     assert_eq!(witness_accum.pubdata.len(), CHUNK_BIT_WIDTH * block_size);
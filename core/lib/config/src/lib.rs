@@ -1,12 +1,24 @@
 // Built-in deps
-use std::{collections::HashSet, env, net::SocketAddr, str::FromStr, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    env,
+    net::{IpAddr, SocketAddr},
+    path::PathBuf,
+    str::FromStr,
+    time::Duration,
+};
 // External uses
+use num::{rational::Ratio, BigUint};
 use url::Url;
 // Workspace uses
 use zksync_types::{Address, H256};
-use zksync_utils::{get_env, parse_env, parse_env_if_exists, parse_env_with};
+use zksync_utils::{
+    get_env, parse_env, parse_env_if_exists, parse_env_with, UnsignedRatioSerializeAsDecimal,
+};
 // Local uses
+use self::loader::parse_reloadable_var;
 
+pub mod loader;
 pub mod test_config;
 
 /// Makes address for bind from port.
@@ -24,6 +36,10 @@ pub struct EthSenderOptions {
     pub wait_confirmations: u64,
     pub max_txs_in_flight: u64,
     pub is_enabled: bool,
+    /// Whether the ABI of the upgraded contract is used automatically once the contract upgrade
+    /// is completed. Otherwise, sending the transactions is paused until the operator
+    /// confirms the upgrade.
+    pub auto_switch_abi: bool,
 }
 
 impl EthSenderOptions {
@@ -38,6 +54,7 @@ impl EthSenderOptions {
             wait_confirmations: parse_env("ETH_WAIT_CONFIRMATIONS"),
             max_txs_in_flight: parse_env("ETH_MAX_TXS_IN_FLIGHT"),
             is_enabled: parse_env("ETH_IS_ENABLED"),
+            auto_switch_abi: parse_env_if_exists("ETH_AUTO_SWITCH_ABI").unwrap_or(false),
         }
     }
 }
@@ -76,6 +93,10 @@ pub struct ProverOptions {
     pub prover_server_address: SocketAddr,
     pub idle_provers: u32,
     pub witness_generators: usize,
+    /// Witness generators pause once this many prover jobs wait in the queue.
+    pub witness_generator_max_pending_jobs: u32,
+    /// TLS certificate of the prover server, it's served over plain HTTP if not set.
+    pub tls: Option<TlsOptions>,
 }
 
 impl ProverOptions {
@@ -97,7 +118,9 @@ impl ProverOptions {
             gone_timeout: Duration::from_millis(parse_env("PROVER_GONE_TIMEOUT")),
             prover_server_address: addr_from_port(parse_env("PROVER_SERVER_PORT")),
             witness_generators: parse_env("WITNESS_GENERATORS"),
+            witness_generator_max_pending_jobs: parse_env("WITNESS_GENERATOR_MAX_PENDING_JOBS"),
             idle_provers: parse_env("IDLE_PROVERS"),
+            tls: TlsOptions::from_env("PROVER_SERVER"),
             secret_auth,
         }
     }
@@ -133,22 +156,114 @@ impl AdminServerOptions {
 
 #[derive(Clone, Debug)]
 pub enum TokenPriceSource {
-    CoinMarketCap { base_url: Url },
-    CoinGecko { base_url: Url },
+    CoinMarketCap {
+        base_url: Url,
+    },
+    CoinGecko {
+        base_url: Url,
+    },
+    /// Time-weighted average prices of the Uniswap pairs of the tokens with the USD stablecoin.
+    UniswapTwap {
+        web3_url: String,
+        /// Uniswap pair addresses by the token symbols.
+        pairs: HashMap<String, Address>,
+        /// USD stablecoin the prices are quoted in.
+        quote_token: Address,
+        /// Period the price is averaged over.
+        window: Duration,
+    },
+    /// Prices set in the configuration, e.g. for the tokens not listed on any exchange.
+    Static {
+        prices: HashMap<String, Ratio<BigUint>>,
+    },
 }
 
 impl TokenPriceSource {
-    fn from_env() -> Self {
-        match get_env("TOKEN_PRICE_SOURCE").to_lowercase().as_str() {
+    fn from_env(name: &str) -> Self {
+        match name.trim().to_lowercase().as_str() {
             "coinmarketcap" => Self::CoinMarketCap {
                 base_url: parse_env("COINMARKETCAP_BASE_URL"),
             },
             "coingecko" => Self::CoinGecko {
                 base_url: parse_env("COINGECKO_BASE_URL"),
             },
+            "uniswap" => Self::UniswapTwap {
+                web3_url: get_env("WEB3_URL"),
+                pairs: parse_symbol_map("UNISWAP_TWAP_PAIRS", parse_token_address),
+                quote_token: parse_env_with("UNISWAP_TWAP_QUOTE_TOKEN", |s| &s[2..]),
+                window: Duration::from_secs(parse_env("UNISWAP_TWAP_WINDOW_SECS")),
+            },
+            "static" => Self::Static {
+                prices: parse_symbol_map("STATIC_TOKEN_PRICES", parse_decimal),
+            },
             source => panic!("Unknown token price source: {}", source),
         }
     }
+
+    /// Parses the comma-separated list of the price sources.
+    fn list_from_env() -> Vec<Self> {
+        get_env("TOKEN_PRICE_SOURCE")
+            .split(',')
+            .filter(|name| !name.trim().is_empty())
+            .map(Self::from_env)
+            .collect()
+    }
+}
+
+/// Parses the address with an optional `0x` prefix.
+pub(crate) fn parse_token_address(address: &str) -> Option<Address> {
+    address.trim_start_matches("0x").parse().ok()
+}
+
+/// Parses the decimal fraction, e.g. the price or the fee in USD.
+pub(crate) fn parse_decimal(value: &str) -> Option<Ratio<BigUint>> {
+    UnsignedRatioSerializeAsDecimal::deserialize_from_str_with_dot(value).ok()
+}
+
+/// Parses the comma-separated list of `SYMBOL:value` pairs.
+pub(crate) fn parse_symbol_map_value<T>(
+    value: &str,
+    parse: impl Fn(&str) -> Option<T>,
+) -> Result<HashMap<String, T>, String> {
+    value
+        .split(',')
+        .filter(|entry| !entry.trim().is_empty())
+        .map(|entry| {
+            let mut parts = entry.trim().split(':');
+            let symbol = parts.next().unwrap_or_default();
+            let value = parts.next().and_then(|value| parse(value));
+            match (value, parts.next()) {
+                (Some(value), None) if !symbol.is_empty() => Ok((symbol.to_string(), value)),
+                _ => Err(format!("incorrect entry '{}'", entry)),
+            }
+        })
+        .collect()
+}
+
+fn parse_symbol_map<T>(name: &str, parse: impl Fn(&str) -> Option<T>) -> HashMap<String, T> {
+    parse_symbol_map_value(&get_env(name), parse)
+        .unwrap_or_else(|err| panic!("Incorrect {}: {}", name, err))
+}
+
+/// Policy of combining the prices of the multiple token price sources.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TokenPricePolicy {
+    /// Sources are queried in the configured order, the first obtained price is used.
+    Fallback,
+    /// All the sources are queried, the median of the obtained prices is used.
+    Median,
+}
+
+impl FromStr for TokenPricePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "fallback" => Ok(Self::Fallback),
+            "median" => Ok(Self::Median),
+            policy => Err(format!("Unknown token price policy: {}", policy)),
+        }
+    }
 }
 
 /// Configuration options related to generating blocks by state keeper.
@@ -162,22 +277,80 @@ pub struct MiniblockTimings {
     pub max_miniblock_iterations: usize,
     /// Max number of miniblocks for block with fast withdraw operations (defaults to `max_minblock_iterations`).
     pub fast_miniblock_iterations: usize,
+    /// Time after which the block with at least one operation is sealed, even if it's not full yet.
+    pub block_commit_deadline: Duration,
 }
 
 impl MiniblockTimings {
+    /// Reads the timings, which may be changed by the configuration reload.
     pub fn from_env() -> Self {
-        let fast_miniblock_iterations = if env::var("FAST_BLOCK_MINIBLOCKS_ITERATIONS").is_ok() {
-            parse_env("FAST_BLOCK_MINIBLOCKS_ITERATIONS")
-        } else {
-            parse_env("MINIBLOCKS_ITERATIONS")
-        };
+        let max_miniblock_iterations = parse_reloadable_var("MINIBLOCKS_ITERATIONS")
+            .expect("MINIBLOCKS_ITERATIONS is not set");
+        let fast_miniblock_iterations = parse_reloadable_var("FAST_BLOCK_MINIBLOCKS_ITERATIONS")
+            .unwrap_or(max_miniblock_iterations);
+        let block_commit_deadline = parse_reloadable_var("BLOCK_COMMIT_DEADLINE_SECS")
+            .expect("BLOCK_COMMIT_DEADLINE_SECS is not set");
 
         Self {
             miniblock_iteration_interval: Duration::from_millis(parse_env::<u64>(
                 "MINIBLOCK_ITERATION_INTERVAL",
             )),
-            max_miniblock_iterations: parse_env("MINIBLOCKS_ITERATIONS"),
+            max_miniblock_iterations,
             fast_miniblock_iterations,
+            block_commit_deadline: Duration::from_secs(block_commit_deadline),
+        }
+    }
+}
+
+/// Order in which the pending transactions are included into blocks.
+/// Priority operations are always included before any transactions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TxOrdering {
+    /// Best paying transactions (by the fee per block chunk) are included first.
+    /// Transactions of the same account are never reordered.
+    FeePerChunk,
+    /// Transactions are included in the order they were received, without any fee-based reordering.
+    Fifo,
+}
+
+impl FromStr for TxOrdering {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "fee_per_chunk" => Ok(Self::FeePerChunk),
+            "fifo" => Ok(Self::Fifo),
+            ordering => Err(format!("Unknown transactions ordering: {}", ordering)),
+        }
+    }
+}
+
+impl TxOrdering {
+    fn from_env() -> Self {
+        parse_env_if_exists("MEMPOOL_TX_ORDERING").unwrap_or(Self::FeePerChunk)
+    }
+}
+
+/// Configuration options for the mempool.
+#[derive(Debug, Clone)]
+pub struct MempoolOptions {
+    /// Maximum amount of transactions (including the batched ones) that mempool can hold.
+    /// Once this limit is reached, transactions with the lowest fee per chunk are evicted.
+    pub max_size: usize,
+    /// Maximum amount of pending transactions that a single account can have in the mempool.
+    pub max_txs_per_account: usize,
+    /// Order in which the pending transactions are included into blocks.
+    pub tx_ordering: TxOrdering,
+}
+
+impl MempoolOptions {
+    /// Parses the mempool configuration options values from the environment variables.
+    /// Panics if any of options is missing or has inappropriate value.
+    pub fn from_env() -> Self {
+        Self {
+            max_size: parse_env("MEMPOOL_MAX_SIZE"),
+            max_txs_per_account: parse_env("MEMPOOL_MAX_TXS_PER_ACCOUNT"),
+            tx_ordering: TxOrdering::from_env(),
         }
     }
 }
@@ -185,14 +358,46 @@ impl MiniblockTimings {
 /// Configuration options related to fee ticker.
 #[derive(Debug)]
 pub struct FeeTickerOptions {
-    /// Source to fetch token prices from (e.g. CoinGecko or coinmarketcap).
-    pub token_price_source: TokenPriceSource,
+    /// Sources to fetch token prices from (e.g. CoinGecko or coinmarketcap).
+    pub token_price_sources: Vec<TokenPriceSource>,
+    /// Policy of combining the prices if there are several sources.
+    pub token_price_policy: TokenPricePolicy,
+    /// Time the fetched token price is cached for.
+    pub price_cache_ttl: Duration,
+    /// Time the expired token price may still be used for while the price sources are unavailable.
+    pub stale_price_grace_period: Duration,
     /// Fee increase coefficient for fast processing of withdrawal.
     pub fast_processing_coeff: f64,
     /// List of the tokens that aren't acceptable for paying fee in.
     pub disabled_tokens: HashSet<Address>,
+    /// If set, only these tokens (and ETH) are acceptable for paying fee in.
+    pub allowed_tokens: Option<HashSet<Address>>,
+    /// Markup (in percent) added to the fee paid in any token other than ETH
+    /// to cover the risk of the token price change.
+    pub token_fee_markup_percent: u32,
     /// Tokens for which subsidies are disabled.
     pub not_subsidized_tokens: HashSet<Address>,
+    /// Interval between checks of the L1 gas price for the pending transactions fee revalidation.
+    pub fee_revalidation_interval: Duration,
+    /// Relative gas price rise (e.g. `0.3` for 30%) after which fees of the pending transactions
+    /// are revalidated against the current ticker quotes.
+    pub gas_price_rise_threshold: f64,
+    /// Interval between updates of the L1 gas price the fees are quoted with.
+    pub gas_price_refresh_interval: Duration,
+    /// Weight (from 0 to 1) of the newly observed L1 gas price in the quoted one,
+    /// lower values smooth out the short gas price spikes.
+    pub gas_price_smoothing_factor: f64,
+    /// Minimum interval between the recorded quotes of the same token and fee type,
+    /// `None` if the fee history is not recorded.
+    pub fee_history_interval: Option<Duration>,
+    /// Time the recorded fee quotes are kept for.
+    pub fee_history_retention: Duration,
+    /// Maximum fee in USD per fee type (e.g. `Transfer` or `Withdraw`), quotes exceeding it
+    /// are rejected as the result of the wrong price data.
+    pub max_fee_usd: HashMap<String, Ratio<BigUint>>,
+    /// Maximum token price change (in percent) between the consecutive price updates,
+    /// `None` if the price changes are not limited.
+    pub max_price_change_percent: Option<u32>,
 }
 
 impl FeeTickerOptions {
@@ -205,10 +410,177 @@ impl FeeTickerOptions {
 
     pub fn from_env() -> Self {
         Self {
-            token_price_source: TokenPriceSource::from_env(),
-            fast_processing_coeff: parse_env("TICKER_FAST_PROCESSING_COEFF"),
+            token_price_sources: TokenPriceSource::list_from_env(),
+            token_price_policy: parse_env_if_exists("TOKEN_PRICE_POLICY")
+                .unwrap_or(TokenPricePolicy::Fallback),
+            price_cache_ttl: Duration::from_secs(
+                parse_env_if_exists("TICKER_PRICE_CACHE_TTL_SECS").unwrap_or(300),
+            ),
+            stale_price_grace_period: Duration::from_secs(
+                parse_env_if_exists("TICKER_STALE_PRICE_GRACE_PERIOD_SECS").unwrap_or(3600),
+            ),
+            fast_processing_coeff: parse_reloadable_var("TICKER_FAST_PROCESSING_COEFF")
+                .expect("TICKER_FAST_PROCESSING_COEFF is not set"),
             disabled_tokens: Self::comma_separated_addresses("TICKER_DISABLED_TOKENS"),
+            allowed_tokens: parse_env_if_exists::<String>("TICKER_ALLOWED_TOKENS")
+                .filter(|tokens| !tokens.is_empty())
+                .map(|_| Self::comma_separated_addresses("TICKER_ALLOWED_TOKENS")),
+            token_fee_markup_percent: parse_reloadable_var("TICKER_TOKEN_FEE_MARKUP_PERCENT")
+                .unwrap_or(0),
             not_subsidized_tokens: Self::comma_separated_addresses("NOT_SUBSIDIZED_TOKENS"),
+            fee_revalidation_interval: Duration::from_secs(parse_env(
+                "TICKER_FEE_REVALIDATION_INTERVAL_SECS",
+            )),
+            gas_price_rise_threshold: parse_env("TICKER_GAS_PRICE_RISE_THRESHOLD"),
+            gas_price_refresh_interval: Duration::from_secs(
+                parse_env_if_exists("TICKER_GAS_PRICE_REFRESH_INTERVAL_SECS").unwrap_or(60),
+            ),
+            gas_price_smoothing_factor: parse_env_if_exists("TICKER_GAS_PRICE_SMOOTHING_FACTOR")
+                .unwrap_or(0.5),
+            fee_history_interval: Some(
+                parse_env_if_exists("TICKER_FEE_HISTORY_INTERVAL_SECS").unwrap_or(60),
+            )
+            .filter(|&secs| secs > 0)
+            .map(Duration::from_secs),
+            fee_history_retention: Duration::from_secs(
+                parse_env_if_exists::<u64>("TICKER_FEE_HISTORY_RETENTION_DAYS").unwrap_or(30)
+                    * 24
+                    * 3600,
+            ),
+            max_fee_usd: parse_env_if_exists::<String>("TICKER_MAX_FEE_USD")
+                .filter(|bounds| !bounds.is_empty())
+                .map(|_| parse_symbol_map("TICKER_MAX_FEE_USD", parse_decimal))
+                .unwrap_or_default(),
+            max_price_change_percent: Some(
+                parse_env_if_exists("TICKER_MAX_PRICE_CHANGE_PERCENT").unwrap_or(50),
+            )
+            .filter(|&percent| percent > 0),
+        }
+    }
+}
+
+/// Access tier of the JSON-RPC API client identified by the API key.
+///
+/// Parsed from the `key:requests_per_minute[:method1|method2|...]` string,
+/// all the methods are allowed if the list is omitted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiKeyOptions {
+    pub key: String,
+    /// Maximum amount of JSON-RPC requests the client can send per minute.
+    pub requests_per_minute: usize,
+    /// Methods the client is allowed to call, `None` means no restrictions.
+    pub allowed_methods: Option<HashSet<String>>,
+}
+
+impl FromStr for ApiKeyOptions {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.trim().split(':');
+        let key = parts
+            .next()
+            .filter(|key| !key.is_empty())
+            .ok_or_else(|| format!("API key is missing: {}", s))?;
+        let requests_per_minute = parts
+            .next()
+            .ok_or_else(|| format!("Requests limit is missing: {}", s))?
+            .parse()
+            .map_err(|e| format!("Incorrect requests limit in {}: {}", s, e))?;
+        let allowed_methods = parts
+            .next()
+            .map(|methods| methods.split('|').map(String::from).collect());
+        if parts.next().is_some() {
+            return Err(format!("Unexpected API key format: {}", s));
+        }
+
+        Ok(Self {
+            key: key.to_string(),
+            requests_per_minute,
+            allowed_methods,
+        })
+    }
+}
+
+impl ApiKeyOptions {
+    /// Parses the comma-separated list of the API keys.
+    fn parse_list(s: &str) -> Result<Vec<Self>, String> {
+        s.split(',')
+            .filter(|entry| !entry.trim().is_empty())
+            .map(str::parse)
+            .collect()
+    }
+}
+
+/// Liquidity provider paying out the fast withdrawals in L1 in exchange for the transfer in L2.
+///
+/// Parsed from the `address:fee_bps` string, the fee is charged in basis points
+/// of the withdrawn amount.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiquidityProviderOptions {
+    pub address: Address,
+    pub fee_bps: u32,
+}
+
+impl FromStr for LiquidityProviderOptions {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.trim().split(':');
+        let address = parts
+            .next()
+            .ok_or_else(|| format!("Provider address is missing: {}", s))?;
+        let address = address
+            .trim_start_matches("0x")
+            .parse()
+            .map_err(|e| format!("Incorrect provider address in {}: {}", s, e))?;
+        let fee_bps = parts
+            .next()
+            .ok_or_else(|| format!("Provider fee is missing: {}", s))?
+            .parse()
+            .map_err(|e| format!("Incorrect provider fee in {}: {}", s, e))?;
+        if fee_bps >= 10_000 || parts.next().is_some() {
+            return Err(format!("Unexpected liquidity provider format: {}", s));
+        }
+
+        Ok(Self { address, fee_bps })
+    }
+}
+
+impl LiquidityProviderOptions {
+    /// Parses the comma-separated list of the liquidity providers.
+    fn parse_list(s: &str) -> Result<Vec<Self>, String> {
+        s.split(',')
+            .filter(|entry| !entry.trim().is_empty())
+            .map(str::parse)
+            .collect()
+    }
+}
+
+/// Certificate and private key used to serve the API over TLS.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TlsOptions {
+    /// PEM file with the certificate chain.
+    pub cert_path: PathBuf,
+    /// PEM file with the PKCS#8 or RSA private key.
+    pub key_path: PathBuf,
+}
+
+impl TlsOptions {
+    /// Reads the `{prefix}_TLS_CERT_PATH` and `{prefix}_TLS_KEY_PATH` variables,
+    /// TLS is disabled if none of them is set.
+    fn from_env(prefix: &str) -> Option<Self> {
+        let cert_var = format!("{}_TLS_CERT_PATH", prefix);
+        let key_var = format!("{}_TLS_KEY_PATH", prefix);
+        let cert_path = env::var(&cert_var).ok();
+        let key_path = env::var(&key_var).ok();
+
+        match (cert_path, key_path) {
+            (Some(cert_path), Some(key_path)) => Some(Self {
+                cert_path: cert_path.into(),
+                key_path: key_path.into(),
+            }),
+            (None, None) => None,
+            _ => panic!("{} and {} must be set together", cert_var, key_var),
         }
     }
 }
@@ -227,6 +599,32 @@ pub struct ApiServerOptions {
     // Limit the number of both transactions and Ethereum signatures per batch.
     pub max_number_of_transactions_per_batch: usize,
     pub max_number_of_authors_per_batch: usize,
+    /// Maximum amount of transactions a single account can submit per minute.
+    pub max_txs_per_account_per_minute: usize,
+    /// Maximum amount of transactions that can be submitted from a single IP address per minute.
+    pub max_txs_per_ip_per_minute: usize,
+    /// Maximum amount of requests in a single JSON-RPC batch.
+    pub max_rpc_batch_size: usize,
+    /// API keys of the JSON-RPC clients with their own limits.
+    pub api_keys: Vec<ApiKeyOptions>,
+    /// Maximum amount of JSON-RPC requests per minute from a single client without an API key.
+    /// `None` means no limit, zero disables the anonymous access.
+    pub anonymous_rpc_requests_per_minute: Option<usize>,
+    /// Amount of Ethereum blocks the unconfirmed operation may stay past its deadline
+    /// before `eth_sender` is reported as stalled by the readiness check.
+    pub health_eth_sender_stall_blocks: u64,
+    /// Maximum age of the last sealed block for the server to be reported as ready.
+    /// Not checked if `None`.
+    pub health_max_sealed_block_age: Option<Duration>,
+    /// TLS settings of the REST and JSON-RPC servers, the API is served over plain HTTP if `None`.
+    pub tls: Option<TlsOptions>,
+    /// Origins allowed to access the API from the browser, any origin is allowed if empty.
+    pub cors_allowed_origins: Vec<String>,
+    /// Addresses of the reverse proxies trusted to report the client addresses
+    /// in the forwarding headers. The headers are ignored if empty.
+    pub trusted_proxies: Vec<IpAddr>,
+    /// Liquidity providers the fast withdrawals can be handed off to.
+    pub liquidity_providers: Vec<LiquidityProviderOptions>,
 }
 
 impl ApiServerOptions {
@@ -251,6 +649,122 @@ impl ApiServerOptions {
                 .unwrap_or(true),
             max_number_of_transactions_per_batch: parse_env("MAX_TRANSACTIONS_PER_BATCH"),
             max_number_of_authors_per_batch: parse_env("MAX_ETH_SIGNATURES_PER_BATCH"),
+            max_txs_per_account_per_minute: parse_env("MAX_TXS_PER_ACCOUNT_PER_MINUTE"),
+            max_txs_per_ip_per_minute: parse_env("MAX_TXS_PER_IP_PER_MINUTE"),
+            max_rpc_batch_size: parse_env("MAX_RPC_REQUESTS_PER_BATCH"),
+            api_keys: env::var("API_KEYS")
+                .map(|keys| {
+                    ApiKeyOptions::parse_list(&keys).unwrap_or_else(|e| {
+                        panic!("Failed to parse environment variable API_KEYS: {}", e)
+                    })
+                })
+                .unwrap_or_default(),
+            anonymous_rpc_requests_per_minute: parse_env_if_exists(
+                "ANONYMOUS_RPC_REQUESTS_PER_MINUTE",
+            ),
+            health_eth_sender_stall_blocks: parse_env("HEALTH_ETH_SENDER_STALL_BLOCKS"),
+            health_max_sealed_block_age: parse_env_if_exists("HEALTH_MAX_SEALED_BLOCK_AGE_SECS")
+                .map(Duration::from_secs),
+            tls: TlsOptions::from_env("API"),
+            cors_allowed_origins: env::var("API_CORS_ALLOWED_ORIGINS")
+                .map(|origins| {
+                    origins
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|origin| !origin.is_empty())
+                        .map(String::from)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            trusted_proxies: env::var("API_TRUSTED_PROXIES")
+                .map(|proxies| {
+                    proxies
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|proxy| !proxy.is_empty())
+                        .map(|proxy| {
+                            proxy.parse().unwrap_or_else(|e| {
+                                panic!(
+                                    "Failed to parse environment variable API_TRUSTED_PROXIES: {}",
+                                    e
+                                )
+                            })
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+            liquidity_providers: env::var("FAST_WITHDRAW_LIQUIDITY_PROVIDERS")
+                .map(|providers| {
+                    LiquidityProviderOptions::parse_list(&providers).unwrap_or_else(|e| {
+                        panic!(
+                            "Failed to parse environment variable FAST_WITHDRAW_LIQUIDITY_PROVIDERS: {}",
+                            e
+                        )
+                    })
+                })
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Configuration options of the alerts about the critical failures of the server.
+#[derive(Debug, Clone, Default)]
+pub struct AlertOptions {
+    /// Sentry DSN the alerts are reported to.
+    pub sentry_dsn: Option<String>,
+    /// URL the alerts are posted to as JSON.
+    pub webhook_url: Option<Url>,
+    /// Whether the server is shut down once any of its tasks panics.
+    pub shutdown_on_panic: bool,
+}
+
+impl AlertOptions {
+    /// Parses the configuration options values from the environment variables.
+    /// Panics if any of options has inappropriate value.
+    pub fn from_env() -> Self {
+        Self {
+            sentry_dsn: env::var("ALERT_SENTRY_DSN")
+                .ok()
+                .filter(|dsn| !dsn.is_empty()),
+            webhook_url: env::var("ALERT_WEBHOOK_URL")
+                .ok()
+                .filter(|url| !url.is_empty())
+                .map(|url| {
+                    url.parse().unwrap_or_else(|e| {
+                        panic!(
+                            "Failed to parse environment variable ALERT_WEBHOOK_URL: {:?}",
+                            e
+                        )
+                    })
+                }),
+            shutdown_on_panic: parse_env_if_exists("ALERT_SHUTDOWN_ON_PANIC").unwrap_or(false),
+        }
+    }
+}
+
+/// Configuration options of the leader election between the server replicas.
+#[derive(Debug, Clone)]
+pub struct LeaderElectionOptions {
+    /// Whether several server replicas share the database, and only the elected one
+    /// runs the Core actors and the Ethereum sender.
+    pub enabled: bool,
+    /// ID of the Postgres advisory lock held by the leader.
+    pub lock_id: i64,
+    /// Interval between the attempts of the standby replica to become the leader,
+    /// as well as between the checks that the leader still holds the lock.
+    pub check_interval: Duration,
+}
+
+impl LeaderElectionOptions {
+    /// Parses the configuration options values from the environment variables.
+    /// Panics if any of options has inappropriate value.
+    pub fn from_env() -> Self {
+        Self {
+            enabled: parse_env_if_exists("LEADER_ELECTION_ENABLED").unwrap_or(false),
+            lock_id: parse_env_if_exists("LEADER_ELECTION_LOCK_ID").unwrap_or(1),
+            check_interval: Duration::from_secs(
+                parse_env_if_exists("LEADER_ELECTION_CHECK_INTERVAL_SECS").unwrap_or(5),
+            ),
         }
     }
 }
@@ -261,6 +775,8 @@ pub struct ConfigurationOptions {
     pub genesis_tx_hash: H256,
     pub contract_eth_addr: Address,
     pub governance_eth_addr: Address,
+    /// Address of the contract managing the upgrades of the zkSync contract.
+    pub upgrade_gatekeeper_eth_addr: Address,
     pub operator_fee_eth_addr: Address,
     pub confirmations_for_eth_event: u64,
     pub available_block_chunk_sizes: Vec<usize>,
@@ -269,7 +785,30 @@ pub struct ConfigurationOptions {
     pub eth_network: String,
     pub miniblock_timings: MiniblockTimings,
     pub prometheus_export_port: u16,
+    /// Numbers of block proofs that can be aggregated into one recursive proof, in ascending order.
     pub aggregated_proof_sizes: Vec<usize>,
+    /// Whether the contract is deployed with the dummy verifier accepting any block proof,
+    /// so the placeholder proofs of the dummy prover can be used.
+    pub dummy_verifier: bool,
+    /// Ethereum chain ID, part of the EIP-712 domain the transactions are signed in.
+    pub chain_id: u8,
+    /// Whether the transactions signed according to EIP-712 are accepted.
+    pub eip712_signatures_enabled: bool,
+    /// Interval between the token symbol and decimals updates from the token contracts,
+    /// `None` if the token metadata is not updated.
+    pub token_metadata_refresh_interval: Option<Duration>,
+    /// Time given to the actors to finish their work on shutdown before the server exits anyway.
+    pub shutdown_timeout: Duration,
+    /// Whether the details of the failed transactions are recorded and served by the API.
+    pub tx_trace_enabled: bool,
+    /// Capacity of the queue of the miniblocks proposed to the state keeper.
+    pub state_keeper_queue_capacity: usize,
+    /// Capacity of the queue of the blocks sent by the state keeper to the committer.
+    /// Once it's full, the state keeper pauses until the committer catches up.
+    pub committer_queue_capacity: usize,
+    /// Maximum amount of the aggregated operations not sent to Ethereum yet. Once it's reached,
+    /// the committer stops creating the operations to be sent until the Ethereum sender catches up.
+    pub eth_sender_queue_capacity: usize,
 }
 
 impl ConfigurationOptions {
@@ -304,6 +843,7 @@ impl ConfigurationOptions {
             genesis_tx_hash: parse_env_with("GENESIS_TX_HASH", |s| &s[2..]),
             contract_eth_addr: parse_env_with("CONTRACT_ADDR", |s| &s[2..]),
             governance_eth_addr: parse_env_with("GOVERNANCE_ADDR", |s| &s[2..]),
+            upgrade_gatekeeper_eth_addr: parse_env_with("UPGRADE_GATEKEEPER_ADDR", |s| &s[2..]),
             operator_fee_eth_addr: parse_env_with("OPERATOR_FEE_ETH_ADDRESS", |s| &s[2..]),
             confirmations_for_eth_event: parse_env("CONFIRMATIONS_FOR_ETH_EVENT"),
             available_block_chunk_sizes,
@@ -315,6 +855,24 @@ impl ConfigurationOptions {
             miniblock_timings: MiniblockTimings::from_env(),
             prometheus_export_port: parse_env("PROMETHEUS_EXPORT_PORT"),
             aggregated_proof_sizes,
+            dummy_verifier: parse_env("DUMMY_VERIFIER"),
+            chain_id: parse_env("CHAIN_ID"),
+            eip712_signatures_enabled: parse_env_if_exists("EIP712_SIGNATURES_ENABLED")
+                .unwrap_or(false),
+            token_metadata_refresh_interval: Some(
+                parse_env_if_exists("TOKEN_METADATA_REFRESH_INTERVAL_SECS").unwrap_or(3600),
+            )
+            .filter(|&secs| secs > 0)
+            .map(Duration::from_secs),
+            shutdown_timeout: Duration::from_secs(
+                parse_env_if_exists("SHUTDOWN_TIMEOUT_SECS").unwrap_or(30),
+            ),
+            tx_trace_enabled: parse_env_if_exists("TX_TRACE_ENABLED").unwrap_or(false),
+            state_keeper_queue_capacity: parse_env_if_exists("STATE_KEEPER_QUEUE_CAPACITY")
+                .unwrap_or(64),
+            committer_queue_capacity: parse_env_if_exists("COMMITTER_QUEUE_CAPACITY").unwrap_or(16),
+            eth_sender_queue_capacity: parse_env_if_exists("ETH_SENDER_QUEUE_CAPACITY")
+                .unwrap_or(32),
         }
     }
 }
@@ -371,4 +929,49 @@ impl AvailableBlockSizesConfig {
             .zip(self.aggregated_proof_sizes_setup_power2.iter().cloned())
             .collect()
     }
+
+    /// Checks that every block size the state keeper is configured with has a circuit
+    /// supported by the prover, since sealed blocks are padded to the smallest available
+    /// size that fits.
+    pub fn check_block_chunk_sizes(&self, block_chunk_sizes: &[usize]) -> Result<(), String> {
+        match block_chunk_sizes
+            .iter()
+            .find(|block_size| !self.blocks_chunks.contains(block_size))
+        {
+            Some(block_size) => Err(format!(
+                "Block size {} is not supported by the prover, supported sizes: {:?}",
+                block_size, self.blocks_chunks
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block_sizes_config(blocks_chunks: Vec<usize>) -> AvailableBlockSizesConfig {
+        AvailableBlockSizesConfig {
+            blocks_setup_power2: vec![21; blocks_chunks.len()],
+            blocks_chunks,
+            aggregated_proof_sizes: vec![1, 5],
+            aggregated_proof_sizes_setup_power2: vec![22, 24],
+        }
+    }
+
+    /// Checks that only the block sizes supported by the prover are accepted.
+    #[test]
+    fn block_chunk_sizes_check() {
+        let config = block_sizes_config(vec![6, 30, 74]);
+
+        assert!(config.check_block_chunk_sizes(&[6, 30, 74]).is_ok());
+        assert!(config.check_block_chunk_sizes(&[30]).is_ok());
+        assert_eq!(
+            config.check_block_chunk_sizes(&[6, 50]),
+            Err(
+                "Block size 50 is not supported by the prover, supported sizes: [6, 30, 74]".into()
+            )
+        );
+    }
 }
@@ -0,0 +1,1114 @@
+//! Loading of the server configuration from the TOML file and its validation at startup.
+//!
+//! Configuration options are still read from the environment variables by the `from_env`
+//! methods of the options structures. The configuration file provides the values of the
+//! variables which are not set in the environment, so the environment overrides the file.
+//! Variables are grouped by the server components:
+//!
+//! ```toml
+//! [eth_sender]
+//! ETH_WAIT_CONFIRMATIONS = 1
+//!
+//! [ticker]
+//! TICKER_DISABLED_TOKENS = ["38A2fDc11f526Ddd5a607C1F251C065f40fBF2f7"]
+//! ```
+//!
+//! The parameters of the Ethereum network (chain ID, contract addresses, confirmation depths,
+//! gas settings) can be set at once by selecting the network profile, which is the file of the
+//! same format stored as `etc/networks/<name>.toml`. Unlike the configuration file, the profile
+//! overrides the environment.
+//!
+//! Once loaded, the configuration is validated against the known variables of every component,
+//! and all the missing or malformed values are reported at once, so the server doesn't panic
+//! on the first inappropriate value read by one of its actors. Values are checked with the same
+//! parsers the options structures use, and the file can't set unknown variables, so a typo
+//! in the variable name doesn't go unnoticed.
+//!
+//! The subset of the parameters which are safe to change at runtime (gas price limits, fee
+//! coefficients, block sealing timings) can be reloaded from the file while the server is running.
+//! Reloaded values are kept by the loader rather than in the environment, which is read
+//! concurrently by the actors, so such parameters must be read with [`reloadable_var`].
+//! They're either read on every use, or re-read by their actors once the [`config_generation`]
+//! changes.
+
+// Built-in uses
+use std::{
+    collections::HashMap,
+    env, fmt, fs,
+    net::IpAddr,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        RwLock,
+    },
+};
+// External uses
+use lazy_static::lazy_static;
+use url::Url;
+// Workspace uses
+use zksync_types::{Address, H256};
+// Local uses
+use self::{ConfigSection::*, ValueKind::*};
+use crate::{
+    parse_decimal, parse_symbol_map_value, parse_token_address, TokenPricePolicy, TxOrdering,
+};
+
+/// Server component the configuration variable belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigSection {
+    EthSender,
+    StateKeeper,
+    Api,
+    Prover,
+    Ticker,
+}
+
+impl ConfigSection {
+    pub const ALL: [Self; 5] = [
+        Self::EthSender,
+        Self::StateKeeper,
+        Self::Api,
+        Self::Prover,
+        Self::Ticker,
+    ];
+
+    /// Name of the section in the configuration file.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::EthSender => "eth_sender",
+            Self::StateKeeper => "state_keeper",
+            Self::Api => "api",
+            Self::Prover => "prover",
+            Self::Ticker => "ticker",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|section| section.name() == name)
+    }
+}
+
+/// Expected format of the variable value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ValueKind {
+    Integer,
+    Port,
+    Float,
+    Bool,
+    /// Address with the `0x` prefix.
+    EthAddress,
+    /// Comma-separated list of the addresses without the `0x` prefix.
+    AddressList,
+    /// 32-byte hash with the `0x` prefix.
+    EthHash,
+    Endpoint,
+    /// Comma-separated list of the integers.
+    IntegerList,
+    /// Comma-separated list of the IP addresses.
+    IpList,
+    /// Comma-separated list of the `SYMBOL:address` pairs.
+    AddressMap,
+    /// Comma-separated list of the `SYMBOL:decimal` pairs.
+    DecimalMap,
+    /// Order of the transactions in the mempool.
+    TxOrder,
+    /// Policy of combining the token prices.
+    PricePolicy,
+    Text,
+}
+
+impl ValueKind {
+    fn check(self, value: &str) -> Result<(), String> {
+        let is_valid = match self {
+            Self::Integer => u64::from_str(value).is_ok(),
+            Self::Port => u16::from_str(value).is_ok(),
+            Self::Float => f64::from_str(value).is_ok(),
+            Self::Bool => bool::from_str(value).is_ok(),
+            Self::EthAddress => value.starts_with("0x") && Address::from_str(&value[2..]).is_ok(),
+            Self::AddressList => value
+                .split(',')
+                .all(|address| Address::from_str(address.trim()).is_ok()),
+            Self::EthHash => value.starts_with("0x") && H256::from_str(&value[2..]).is_ok(),
+            Self::Endpoint => Url::parse(value).is_ok(),
+            Self::IntegerList => value
+                .split(',')
+                .all(|number| u64::from_str(number.trim()).is_ok()),
+            Self::IpList => value
+                .split(',')
+                .all(|ip| IpAddr::from_str(ip.trim()).is_ok()),
+            Self::AddressMap => {
+                return parse_symbol_map_value(value, parse_token_address)
+                    .map(drop)
+                    .map_err(|err| format!("expected {}, got {}", self.description(), err));
+            }
+            Self::DecimalMap => {
+                return parse_symbol_map_value(value, parse_decimal)
+                    .map(drop)
+                    .map_err(|err| format!("expected {}, got {}", self.description(), err));
+            }
+            Self::TxOrder => TxOrdering::from_str(value).is_ok(),
+            Self::PricePolicy => TokenPricePolicy::from_str(value).is_ok(),
+            Self::Text => !value.trim().is_empty(),
+        };
+        if is_valid {
+            Ok(())
+        } else {
+            Err(format!("expected {}, got '{}'", self.description(), value))
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Self::Integer => "a non-negative integer",
+            Self::Port => "a port number",
+            Self::Float => "a number",
+            Self::Bool => "'true' or 'false'",
+            Self::EthAddress => "a 0x-prefixed address",
+            Self::AddressList => "a comma-separated list of addresses",
+            Self::EthHash => "a 0x-prefixed 32-byte hash",
+            Self::Endpoint => "a URL",
+            Self::IntegerList => "a comma-separated list of integers",
+            Self::IpList => "a comma-separated list of IP addresses",
+            Self::AddressMap => "a comma-separated list of SYMBOL:address pairs",
+            Self::DecimalMap => "a comma-separated list of SYMBOL:decimal pairs",
+            Self::TxOrder => "'fee_per_chunk' or 'fifo'",
+            Self::PricePolicy => "'fallback' or 'median'",
+            Self::Text => "a non-empty value",
+        }
+    }
+}
+
+/// Environment variable the server configuration is read from.
+#[derive(Debug, Clone, Copy)]
+struct ConfigVariable {
+    name: &'static str,
+    section: ConfigSection,
+    kind: ValueKind,
+    /// Whether the variable has no default value.
+    required: bool,
+    /// Whether the value is not printed.
+    secret: bool,
+}
+
+impl ConfigVariable {
+    /// Empty optional lists and texts are treated as not set.
+    fn may_be_empty(&self) -> bool {
+        !self.required
+            && matches!(
+                self.kind,
+                AddressList | AddressMap | DecimalMap | Text | Endpoint
+            )
+    }
+
+    fn check(&self, value: Option<&str>) -> Result<(), FieldError> {
+        let result = match value {
+            Some(value) if value.trim().is_empty() && self.may_be_empty() => Ok(()),
+            Some(value) => self.kind.check(value.trim()),
+            None if self.required => Err("missing value".to_string()),
+            None => Ok(()),
+        };
+        result.map_err(|message| FieldError {
+            section: self.section,
+            variable: self.name.to_string(),
+            message,
+        })
+    }
+}
+
+const fn required(name: &'static str, section: ConfigSection, kind: ValueKind) -> ConfigVariable {
+    ConfigVariable {
+        name,
+        section,
+        kind,
+        required: true,
+        secret: false,
+    }
+}
+
+const fn optional(name: &'static str, section: ConfigSection, kind: ValueKind) -> ConfigVariable {
+    ConfigVariable {
+        name,
+        section,
+        kind,
+        required: false,
+        secret: false,
+    }
+}
+
+const fn secret(variable: ConfigVariable) -> ConfigVariable {
+    ConfigVariable {
+        secret: true,
+        ..variable
+    }
+}
+
+const VARIABLES: &[ConfigVariable] = &[
+    required("WEB3_URL", EthSender, Endpoint),
+    required("CONTRACT_ADDR", EthSender, EthAddress),
+    required("CHAIN_ID", EthSender, Integer),
+    required("GAS_PRICE_FACTOR", EthSender, Float),
+    required("OPERATOR_COMMIT_ETH_ADDRESS", EthSender, EthAddress),
+    secret(optional("OPERATOR_PRIVATE_KEY", EthSender, Text)),
+    required("ETH_EXPECTED_WAIT_TIME_BLOCK", EthSender, Integer),
+    required("ETH_TX_POLL_PERIOD", EthSender, Integer),
+    required("ETH_WAIT_CONFIRMATIONS", EthSender, Integer),
+    required("ETH_MAX_TXS_IN_FLIGHT", EthSender, Integer),
+    required("ETH_IS_ENABLED", EthSender, Bool),
+    optional("ETH_AUTO_SWITCH_ABI", EthSender, Bool),
+    optional("ETH_GAS_PRICE_LIMIT_UPDATE_INTERVAL", EthSender, Integer),
+    optional("ETH_GAS_PRICE_LIMIT_SAMPLE_INTERVAL", EthSender, Integer),
+    optional("ETH_GAS_PRICE_LIMIT_SCALE_FACTOR", EthSender, Float),
+    required("ETH_NETWORK", StateKeeper, Text),
+    required("GENESIS_TX_HASH", StateKeeper, EthHash),
+    required("GOVERNANCE_ADDR", StateKeeper, EthAddress),
+    required("UPGRADE_GATEKEEPER_ADDR", StateKeeper, EthAddress),
+    required("OPERATOR_FEE_ETH_ADDRESS", StateKeeper, EthAddress),
+    required("CONFIRMATIONS_FOR_ETH_EVENT", StateKeeper, Integer),
+    required("ETH_WATCH_POLL_INTERVAL", StateKeeper, Integer),
+    required("BLOCK_CHUNK_SIZES", StateKeeper, IntegerList),
+    required("AGGREGATED_PROOF_SIZES", StateKeeper, IntegerList),
+    required("MAX_NUMBER_OF_WITHDRAWALS_PER_BLOCK", StateKeeper, Integer),
+    required("MINIBLOCK_ITERATION_INTERVAL", StateKeeper, Integer),
+    required("MINIBLOCKS_ITERATIONS", StateKeeper, Integer),
+    optional("FAST_BLOCK_MINIBLOCKS_ITERATIONS", StateKeeper, Integer),
+    required("BLOCK_COMMIT_DEADLINE_SECS", StateKeeper, Integer),
+    required("MEMPOOL_MAX_SIZE", StateKeeper, Integer),
+    required("MEMPOOL_MAX_TXS_PER_ACCOUNT", StateKeeper, Integer),
+    optional("MEMPOOL_TX_ORDERING", StateKeeper, TxOrder),
+    required("DUMMY_VERIFIER", StateKeeper, Bool),
+    optional("EIP712_SIGNATURES_ENABLED", StateKeeper, Bool),
+    optional("TOKEN_METADATA_REFRESH_INTERVAL_SECS", StateKeeper, Integer),
+    required("PROMETHEUS_EXPORT_PORT", StateKeeper, Port),
+    secret(optional("ALERT_SENTRY_DSN", StateKeeper, Text)),
+    optional("ALERT_WEBHOOK_URL", StateKeeper, Endpoint),
+    optional("ALERT_SHUTDOWN_ON_PANIC", StateKeeper, Bool),
+    optional("SHUTDOWN_TIMEOUT_SECS", StateKeeper, Integer),
+    optional("TX_TRACE_ENABLED", StateKeeper, Bool),
+    optional("STATE_KEEPER_QUEUE_CAPACITY", StateKeeper, Integer),
+    optional("COMMITTER_QUEUE_CAPACITY", StateKeeper, Integer),
+    optional("ETH_SENDER_QUEUE_CAPACITY", StateKeeper, Integer),
+    optional("LEADER_ELECTION_ENABLED", StateKeeper, Bool),
+    optional("LEADER_ELECTION_LOCK_ID", StateKeeper, Integer),
+    optional("LEADER_ELECTION_CHECK_INTERVAL_SECS", StateKeeper, Integer),
+    required("REST_API_PORT", Api, Port),
+    required("HTTP_RPC_API_PORT", Api, Port),
+    required("WS_API_PORT", Api, Port),
+    required("PRIVATE_CORE_SERVER_PORT", Api, Port),
+    required("PRIVATE_CORE_SERVER_URL", Api, Endpoint),
+    required("ADMIN_SERVER_API_PORT", Api, Port),
+    required("ADMIN_SERVER_API_URL", Api, Endpoint),
+    secret(required("ADMIN_SERVER_SECRET_AUTH", Api, Text)),
+    required("API_REQUESTS_CACHES_SIZE", Api, Integer),
+    required("FORCED_EXIT_MINIMUM_ACCOUNT_AGE_SECS", Api, Integer),
+    optional("ENFORCE_PUBKEY_CHANGE_FEE", Api, Bool),
+    required("MAX_TRANSACTIONS_PER_BATCH", Api, Integer),
+    required("MAX_ETH_SIGNATURES_PER_BATCH", Api, Integer),
+    required("MAX_TXS_PER_ACCOUNT_PER_MINUTE", Api, Integer),
+    required("MAX_TXS_PER_IP_PER_MINUTE", Api, Integer),
+    required("MAX_RPC_REQUESTS_PER_BATCH", Api, Integer),
+    secret(optional("API_KEYS", Api, Text)),
+    optional("ANONYMOUS_RPC_REQUESTS_PER_MINUTE", Api, Integer),
+    required("HEALTH_ETH_SENDER_STALL_BLOCKS", Api, Integer),
+    optional("HEALTH_MAX_SEALED_BLOCK_AGE_SECS", Api, Integer),
+    optional("API_CORS_ALLOWED_ORIGINS", Api, Text),
+    optional("API_TRUSTED_PROXIES", Api, IpList),
+    optional("API_TLS_CERT_PATH", Api, Text),
+    optional("API_TLS_KEY_PATH", Api, Text),
+    optional("FAST_WITHDRAW_LIQUIDITY_PROVIDERS", Api, Text),
+    secret(required("PROVER_SECRET_AUTH", Prover, Text)),
+    required("PROVER_PREPARE_DATA_INTERVAL", Prover, Integer),
+    required("PROVER_HEARTBEAT_INTERVAL", Prover, Integer),
+    required("PROVER_CYCLE_WAIT", Prover, Integer),
+    required("PROVER_GONE_TIMEOUT", Prover, Integer),
+    required("PROVER_SERVER_PORT", Prover, Port),
+    optional("PROVER_SERVER_TLS_CERT_PATH", Prover, Text),
+    optional("PROVER_SERVER_TLS_KEY_PATH", Prover, Text),
+    required("WITNESS_GENERATORS", Prover, Integer),
+    required("WITNESS_GENERATOR_MAX_PENDING_JOBS", Prover, Integer),
+    required("IDLE_PROVERS", Prover, Integer),
+    required("SUPPORTED_BLOCK_CHUNKS_SIZES", Prover, IntegerList),
+    required(
+        "SUPPORTED_BLOCK_CHUNKS_SIZES_SETUP_POWERS",
+        Prover,
+        IntegerList,
+    ),
+    required("SUPPORTED_AGGREGATED_PROOF_SIZES", Prover, IntegerList),
+    required(
+        "SUPPORTED_AGGREGATED_PROOF_SIZES_SETUP_POWERS",
+        Prover,
+        IntegerList,
+    ),
+    required("TOKEN_PRICE_SOURCE", Ticker, Text),
+    optional("TOKEN_PRICE_POLICY", Ticker, PricePolicy),
+    optional("COINMARKETCAP_BASE_URL", Ticker, Endpoint),
+    optional("COINGECKO_BASE_URL", Ticker, Endpoint),
+    optional("UNISWAP_TWAP_PAIRS", Ticker, AddressMap),
+    optional("UNISWAP_TWAP_QUOTE_TOKEN", Ticker, EthAddress),
+    optional("UNISWAP_TWAP_WINDOW_SECS", Ticker, Integer),
+    optional("STATIC_TOKEN_PRICES", Ticker, DecimalMap),
+    optional("TICKER_PRICE_CACHE_TTL_SECS", Ticker, Integer),
+    optional("TICKER_STALE_PRICE_GRACE_PERIOD_SECS", Ticker, Integer),
+    required("TICKER_FAST_PROCESSING_COEFF", Ticker, Float),
+    required("TICKER_DISABLED_TOKENS", Ticker, AddressList),
+    optional("TICKER_ALLOWED_TOKENS", Ticker, AddressList),
+    optional("TICKER_TOKEN_FEE_MARKUP_PERCENT", Ticker, Integer),
+    required("NOT_SUBSIDIZED_TOKENS", Ticker, AddressList),
+    required("TICKER_FEE_REVALIDATION_INTERVAL_SECS", Ticker, Integer),
+    required("TICKER_GAS_PRICE_RISE_THRESHOLD", Ticker, Float),
+    optional("TICKER_GAS_PRICE_REFRESH_INTERVAL_SECS", Ticker, Integer),
+    optional("TICKER_GAS_PRICE_SMOOTHING_FACTOR", Ticker, Float),
+    optional("TICKER_FEE_HISTORY_INTERVAL_SECS", Ticker, Integer),
+    optional("TICKER_FEE_HISTORY_RETENTION_DAYS", Ticker, Integer),
+    optional("TICKER_MAX_FEE_USD", Ticker, DecimalMap),
+    optional("TICKER_MAX_PRICE_CHANGE_PERCENT", Ticker, Integer),
+];
+
+/// Variables applied by the running server after the configuration reload.
+const RELOADABLE_VARIABLES: &[&str] = &[
+    "ETH_GAS_PRICE_LIMIT_UPDATE_INTERVAL",
+    "ETH_GAS_PRICE_LIMIT_SAMPLE_INTERVAL",
+    "ETH_GAS_PRICE_LIMIT_SCALE_FACTOR",
+    "TICKER_FAST_PROCESSING_COEFF",
+    "TICKER_TOKEN_FEE_MARKUP_PERCENT",
+    "MINIBLOCKS_ITERATIONS",
+    "FAST_BLOCK_MINIBLOCKS_ITERATIONS",
+    "BLOCK_COMMIT_DEADLINE_SECS",
+];
+
+/// Environment variable with the path to the configuration file, set once the file is loaded.
+pub const CONFIG_FILE_VARIABLE: &str = "ZKSYNC_CONFIG_FILE";
+
+/// Variables describing the Ethereum network, which may be set by the network profile.
+const NETWORK_VARIABLES: &[&str] = &[
+    "ETH_NETWORK",
+    "CHAIN_ID",
+    "CONTRACT_ADDR",
+    "GOVERNANCE_ADDR",
+    "UPGRADE_GATEKEEPER_ADDR",
+    "GENESIS_TX_HASH",
+    "CONFIRMATIONS_FOR_ETH_EVENT",
+    "ETH_WAIT_CONFIRMATIONS",
+    "GAS_PRICE_FACTOR",
+    "ETH_GAS_PRICE_LIMIT_SCALE_FACTOR",
+];
+
+/// Variables which must be set by every network profile.
+const REQUIRED_NETWORK_VARIABLES: &[&str] = &["ETH_NETWORK", "CHAIN_ID"];
+
+/// Contract addresses which must be set by the profiles of every network except the local one.
+/// Contracts of the local network are redeployed by `zk init`, so they're taken from `dev.env`.
+const REQUIRED_CONTRACT_VARIABLES: &[&str] = &["CONTRACT_ADDR", "GOVERNANCE_ADDR"];
+
+/// Name of the local development network.
+const LOCAL_NETWORK: &str = "localhost";
+
+/// Environment variable with the name of the network profile, set once the profile is loaded.
+pub const NETWORK_PROFILE_VARIABLE: &str = "ZKSYNC_NETWORK";
+
+/// Number of the configuration reloads which changed any parameters.
+static CONFIG_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+lazy_static! {
+    /// Values of the reloadable variables set by the configuration reloads,
+    /// which override the environment ones.
+    static ref RELOADED_VALUES: RwLock<HashMap<String, String>> = RwLock::new(HashMap::new());
+}
+
+/// Inappropriate value of the configuration variable.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldError {
+    pub section: ConfigSection,
+    pub variable: String,
+    pub message: String,
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{}] {}: {}",
+            self.section.name(),
+            self.variable,
+            self.message
+        )
+    }
+}
+
+/// Changed value of the configuration variable.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigChange {
+    pub variable: String,
+    pub old_value: Option<String>,
+    pub new_value: String,
+}
+
+impl fmt::Display for ConfigChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.old_value {
+            Some(old_value) => write!(
+                f,
+                "{}: '{}' -> '{}'",
+                self.variable, old_value, self.new_value
+            ),
+            None => write!(f, "{}: not set -> '{}'", self.variable, self.new_value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigError {
+    /// Configuration is reloaded, but no configuration file was loaded at startup.
+    NoConfigFile,
+    /// Configuration file can't be read or has an inappropriate structure.
+    File { path: String, message: String },
+    /// Configuration variables are missing or have inappropriate values.
+    Fields(Vec<FieldError>),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoConfigFile => write!(f, "Configuration file is not provided"),
+            Self::File { path, message } => {
+                write!(f, "Invalid configuration file {}: {}", path, message)
+            }
+            Self::Fields(errors) => {
+                write!(f, "Invalid configuration:")?;
+                for error in errors {
+                    write!(f, "\n  {}", error)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Reads the values of the configuration variables from the TOML file content.
+/// Lists are joined into the comma-separated values, as they are expected in the environment.
+/// Every variable must be a known one and belong to its section.
+fn parse_config_file(content: &str) -> Result<Vec<(String, String)>, String> {
+    let file: toml::value::Table = toml::from_str(content).map_err(|err| err.to_string())?;
+
+    let mut values = Vec::new();
+    for (section_name, section) in file {
+        let config_section = ConfigSection::from_name(&section_name)
+            .ok_or_else(|| format!("unknown section [{}]", section_name))?;
+        let section = match section {
+            toml::Value::Table(section) => section,
+            _ => return Err(format!("[{}] must be a table", section_name)),
+        };
+        for (name, value) in section {
+            let name = name.to_uppercase();
+            match VARIABLES.iter().find(|variable| variable.name == name) {
+                Some(variable) if variable.section == config_section => {}
+                Some(variable) => {
+                    return Err(format!(
+                        "{} belongs to the [{}] section",
+                        name,
+                        variable.section.name()
+                    ))
+                }
+                None => return Err(format!("unknown variable {}", name)),
+            }
+            let value = match value {
+                toml::Value::Array(items) => items
+                    .into_iter()
+                    .map(|item| scalar_to_string(item, &name))
+                    .collect::<Result<Vec<_>, _>>()?
+                    .join(","),
+                value => scalar_to_string(value, &name)?,
+            };
+            values.push((name, value));
+        }
+    }
+    Ok(values)
+}
+
+fn scalar_to_string(value: toml::Value, name: &str) -> Result<String, String> {
+    match value {
+        toml::Value::String(value) => Ok(value),
+        toml::Value::Integer(value) => Ok(value.to_string()),
+        toml::Value::Float(value) => Ok(value.to_string()),
+        toml::Value::Boolean(value) => Ok(value.to_string()),
+        _ => Err(format!(
+            "{} must be a string, number, boolean or list",
+            name
+        )),
+    }
+}
+
+fn read_config_file(path: &Path) -> Result<Vec<(String, String)>, ConfigError> {
+    let file_error = |message: String| ConfigError::File {
+        path: path.display().to_string(),
+        message,
+    };
+    let content = fs::read_to_string(path).map_err(|err| file_error(err.to_string()))?;
+    parse_config_file(&content).map_err(file_error)
+}
+
+/// Sets the environment variables from the configuration file, unless they are already set.
+/// Must be called at startup, before the environment is read concurrently.
+pub fn load_config_file(path: &Path) -> Result<(), ConfigError> {
+    for (name, value) in read_config_file(path)? {
+        if env::var_os(&name).is_none() {
+            env::set_var(name, value);
+        }
+    }
+    env::set_var(CONFIG_FILE_VARIABLE, path);
+    Ok(())
+}
+
+fn network_profile_path(name: &str) -> PathBuf {
+    let home = env::var_os("ZKSYNC_HOME").unwrap_or_else(|| ".".into());
+    Path::new(&home)
+        .join("etc/networks")
+        .join(format!("{}.toml", name))
+}
+
+/// Checks that the network profile sets all the required network variables and nothing else.
+/// Profiles of the public networks must set the contract addresses, otherwise the addresses
+/// from the environment would be used with the profile unnoticed.
+fn check_network_profile(values: &[(String, String)]) -> Result<(), String> {
+    if let Some((name, _)) = values
+        .iter()
+        .find(|(name, _)| !NETWORK_VARIABLES.contains(&name.as_str()))
+    {
+        return Err(format!("{} is not a network parameter", name));
+    }
+    let value = |name: &str| {
+        values
+            .iter()
+            .find(|(variable, _)| variable == name)
+            .map(|(_, value)| value.as_str())
+    };
+
+    let is_local = value("ETH_NETWORK") == Some(LOCAL_NETWORK);
+    let required_contracts = if is_local {
+        &[]
+    } else {
+        REQUIRED_CONTRACT_VARIABLES
+    };
+    if let Some(name) = REQUIRED_NETWORK_VARIABLES
+        .iter()
+        .chain(required_contracts)
+        .find(|&name| value(name).is_none())
+    {
+        return Err(format!("{} is not set", name));
+    }
+    Ok(())
+}
+
+/// Sets the environment variables from the network profile `etc/networks/<name>.toml`.
+/// The profile overrides the environment, so the values of another network (e.g. the ones from
+/// `dev.env`) can't be mixed with the profile ones unnoticed.
+pub fn load_network_profile(name: &str) -> Result<(), ConfigError> {
+    let path = network_profile_path(name);
+    let file_error = |message: String| ConfigError::File {
+        path: path.display().to_string(),
+        message,
+    };
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(file_error(format!("invalid network name '{}'", name)));
+    }
+    let values = read_config_file(&path)?;
+    check_network_profile(&values).map_err(file_error)?;
+
+    for variable in NETWORK_VARIABLES {
+        if env::var_os(variable).is_some() && !values.iter().any(|(set, _)| set == variable) {
+            log::warn!(
+                "{} is not set by the '{}' network profile, the value from the environment is used",
+                variable,
+                name
+            );
+        }
+    }
+    for (variable, value) in values {
+        match env::var(&variable) {
+            Ok(old_value) if old_value != value => log::warn!(
+                "{} is overridden by the '{}' network profile: '{}' -> '{}'",
+                variable,
+                name,
+                old_value,
+                value
+            ),
+            _ => {}
+        }
+        env::set_var(variable, value);
+    }
+    env::set_var(NETWORK_PROFILE_VARIABLE, name);
+    Ok(())
+}
+
+fn validate(lookup: impl Fn(&str) -> Option<String>) -> Vec<FieldError> {
+    let mut errors = Vec::new();
+
+    for variable in VARIABLES {
+        if let Err(error) = variable.check(lookup(variable.name).as_deref()) {
+            errors.push(error);
+        }
+    }
+
+    // Options read together are validated together.
+    for prefix in &["API", "PROVER_SERVER"] {
+        let cert_var = format!("{}_TLS_CERT_PATH", prefix);
+        let key_var = format!("{}_TLS_KEY_PATH", prefix);
+        if lookup(&cert_var).is_some() != lookup(&key_var).is_some() {
+            errors.push(FieldError {
+                section: if *prefix == "API" { Api } else { Prover },
+                variable: cert_var.clone(),
+                message: format!("{} and {} must be set together", cert_var, key_var),
+            });
+        }
+    }
+    errors
+}
+
+/// Checks that all the configuration variables of the server are set and have the appropriate
+/// values, reporting every inappropriate variable.
+pub fn validate_config() -> Result<(), ConfigError> {
+    let errors = validate(|name| env::var(name).ok());
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(ConfigError::Fields(errors))
+    }
+}
+
+/// Loads the configuration file (if provided either explicitly or via `ZKSYNC_CONFIG_FILE`)
+/// and validates the resulting configuration.
+pub fn load_config(path: Option<&Path>) -> Result<(), ConfigError> {
+    let path = path
+        .map(Path::to_path_buf)
+        .or_else(|| env::var_os(CONFIG_FILE_VARIABLE).map(PathBuf::from));
+    if let Some(path) = path {
+        load_config_file(&path)?;
+    }
+    validate_config()
+}
+
+/// Returns the number of the configuration reloads which changed any parameters,
+/// so the actors caching the reloadable parameters know when to re-read them.
+pub fn config_generation() -> u64 {
+    CONFIG_GENERATION.load(Ordering::SeqCst)
+}
+
+/// Returns the value of the configuration variable, taking the configuration reloads
+/// into account.
+pub fn reloadable_var(name: &str) -> Option<String> {
+    let reloaded_values = RELOADED_VALUES
+        .read()
+        .expect("Reloaded values lock poisoned");
+    match reloaded_values.get(name) {
+        Some(value) => Some(value.clone()),
+        None => env::var(name).ok(),
+    }
+}
+
+/// Parses the value of the configuration variable, taking the configuration reloads into
+/// account. Panics if the value is malformed, like the other options parsers.
+pub fn parse_reloadable_var<T>(name: &str) -> Option<T>
+where
+    T: FromStr,
+    T::Err: fmt::Debug,
+{
+    reloadable_var(name).map(|value| {
+        value
+            .parse()
+            .unwrap_or_else(|err| panic!("Failed to parse variable {}: {:?}", name, err))
+    })
+}
+
+/// Splits the values of the configuration file which differ from the current ones into
+/// the reloadable changes and the ones requiring a restart.
+fn config_changes(
+    values: Vec<(String, String)>,
+    current: impl Fn(&str) -> Option<String>,
+) -> (Vec<ConfigChange>, Vec<ConfigChange>) {
+    values
+        .into_iter()
+        .filter_map(|(variable, new_value)| {
+            let old_value = current(&variable);
+            if old_value.as_deref() == Some(new_value.as_str()) {
+                return None;
+            }
+            Some(ConfigChange {
+                variable,
+                old_value,
+                new_value,
+            })
+        })
+        .partition(|change| RELOADABLE_VARIABLES.contains(&change.variable.as_str()))
+}
+
+/// Re-reads the configuration file loaded at startup and applies the changed values of the
+/// parameters which are safe to change at runtime. Other changed values are only reported,
+/// since they require a restart. Unlike the startup loading, the file values override the
+/// environment ones.
+pub fn reload_config() -> Result<Vec<ConfigChange>, ConfigError> {
+    let path = env::var_os(CONFIG_FILE_VARIABLE).ok_or(ConfigError::NoConfigFile)?;
+    let values = read_config_file(Path::new(&path))?;
+    let (changes, ignored_changes) = config_changes(values, reloadable_var);
+
+    let errors: Vec<_> = changes
+        .iter()
+        .filter_map(|change| {
+            let variable = VARIABLES.iter().find(|var| var.name == change.variable)?;
+            variable.check(Some(&change.new_value)).err()
+        })
+        .collect();
+    if !errors.is_empty() {
+        return Err(ConfigError::Fields(errors));
+    }
+
+    for change in &ignored_changes {
+        log::warn!(
+            "{} is changed in the configuration file, restart is required to apply it",
+            change.variable
+        );
+    }
+    let mut reloaded_values = RELOADED_VALUES
+        .write()
+        .expect("Reloaded values lock poisoned");
+    for change in &changes {
+        reloaded_values.insert(change.variable.clone(), change.new_value.clone());
+        log::info!("Configuration parameter changed: {}", change);
+    }
+    drop(reloaded_values);
+    if !changes.is_empty() {
+        CONFIG_GENERATION.fetch_add(1, Ordering::SeqCst);
+    }
+    Ok(changes)
+}
+
+/// Returns the current configuration in the configuration file format,
+/// with the secret values hidden.
+pub fn config_report() -> String {
+    let mut report = String::new();
+    for section in ConfigSection::ALL.iter() {
+        report.push_str(&format!("[{}]\n", section.name()));
+        for variable in VARIABLES.iter().filter(|var| var.section == *section) {
+            match reloadable_var(variable.name) {
+                Some(_) if variable.secret => {
+                    report.push_str(&format!("{} = \"<hidden>\"\n", variable.name))
+                }
+                Some(value) => report.push_str(&format!("{} = {:?}\n", variable.name, value)),
+                None => report.push_str(&format!("# {} is not set\n", variable.name)),
+            }
+        }
+        report.push('\n');
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn config_file_parsing() {
+        let values = parse_config_file(
+            r#"
+            [eth_sender]
+            eth_wait_confirmations = 1
+            ETH_IS_ENABLED = true
+
+            [ticker]
+            TICKER_DISABLED_TOKENS = ["38A2fDc11f526Ddd5a607C1F251C065f40fBF2f7", "0000000000000000000000000000000000000001"]
+            TICKER_GAS_PRICE_SMOOTHING_FACTOR = 0.5
+            "#,
+        )
+        .unwrap();
+        let values: HashMap<_, _> = values.into_iter().collect();
+
+        assert_eq!(values["ETH_WAIT_CONFIRMATIONS"], "1");
+        assert_eq!(values["ETH_IS_ENABLED"], "true");
+        assert_eq!(
+            values["TICKER_DISABLED_TOKENS"],
+            "38A2fDc11f526Ddd5a607C1F251C065f40fBF2f7,0000000000000000000000000000000000000001"
+        );
+        assert_eq!(values["TICKER_GAS_PRICE_SMOOTHING_FACTOR"], "0.5");
+
+        assert!(parse_config_file("[unknown]\nA = 1").is_err());
+        assert!(parse_config_file("A = 1").is_err());
+        assert!(parse_config_file("[api]\nREST_API_PORT = { B = 1 }").is_err());
+        assert_eq!(
+            parse_config_file("[api]\nREST_API_PROT = 3001"),
+            Err("unknown variable REST_API_PROT".to_string())
+        );
+        assert_eq!(
+            parse_config_file("[ticker]\nREST_API_PORT = 3001"),
+            Err("REST_API_PORT belongs to the [api] section".to_string())
+        );
+    }
+
+    /// Checks that every variable read by the options structures is validated at startup,
+    /// and every validated variable is read by the server.
+    #[test]
+    fn variables_match_options() {
+        // Variables read by the actors themselves.
+        const READ_BY_ACTORS: &[&str] = &[
+            "ETH_GAS_PRICE_LIMIT_UPDATE_INTERVAL",
+            "ETH_GAS_PRICE_LIMIT_SAMPLE_INTERVAL",
+            "ETH_GAS_PRICE_LIMIT_SCALE_FACTOR",
+        ];
+        // Prefixes of the TLS variables, which names are formatted.
+        const TLS_PREFIXES: &[&str] = &["API", "PROVER_SERVER"];
+
+        let source = include_str!("lib.rs");
+        let read_variables: Vec<_> = source
+            .split('"')
+            .skip(1)
+            .step_by(2)
+            .filter(|literal| {
+                literal.len() > 2
+                    && literal
+                        .chars()
+                        .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+            })
+            .collect();
+
+        for name in &read_variables {
+            if TLS_PREFIXES.contains(name) {
+                for suffix in &["_TLS_CERT_PATH", "_TLS_KEY_PATH"] {
+                    let name = format!("{}{}", name, suffix);
+                    assert!(VARIABLES.iter().any(|var| var.name == name), "{}", name);
+                }
+                continue;
+            }
+            assert!(
+                VARIABLES.iter().any(|var| var.name == *name),
+                "{} is not validated",
+                name
+            );
+        }
+        for variable in VARIABLES {
+            let is_read = read_variables.contains(&variable.name)
+                || READ_BY_ACTORS.contains(&variable.name)
+                || TLS_PREFIXES
+                    .iter()
+                    .any(|prefix| variable.name.starts_with(&format!("{}_TLS_", prefix)));
+            assert!(is_read, "{} is not read by the server", variable.name);
+        }
+    }
+
+    #[test]
+    fn values_are_checked_by_parsers() {
+        let check = |name: &str, value: &str| {
+            VARIABLES
+                .iter()
+                .find(|var| var.name == name)
+                .unwrap()
+                .check(Some(value))
+        };
+
+        assert!(check("TICKER_MAX_FEE_USD", "ETH:100.5,DAI:20").is_ok());
+        assert!(check("TICKER_MAX_FEE_USD", "").is_ok());
+        assert!(check("TICKER_MAX_FEE_USD", "ETH").is_err());
+        assert!(check("TICKER_MAX_FEE_USD", "ETH:abc").is_err());
+        assert!(check(
+            "UNISWAP_TWAP_PAIRS",
+            "ETH:0xB4e16d0168e52d35CaCD2c6185b44281Ec28C9Dc"
+        )
+        .is_ok());
+        assert!(check("UNISWAP_TWAP_PAIRS", "ETH:0x1234").is_err());
+        assert!(check("MEMPOOL_TX_ORDERING", "fifo").is_ok());
+        assert!(check("MEMPOOL_TX_ORDERING", "lifo").is_err());
+        assert!(check("TOKEN_PRICE_POLICY", "median").is_ok());
+        assert!(check("TOKEN_PRICE_POLICY", "mean").is_err());
+    }
+
+    #[test]
+    fn network_profile_check() {
+        let profile = |values: &[(&str, &str)]| {
+            values
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect::<Vec<_>>()
+        };
+
+        const CONTRACT: (&str, &str) = (
+            "CONTRACT_ADDR",
+            "0x38A2fDc11f526Ddd5a607C1F251C065f40fBF2f7",
+        );
+        const GOVERNANCE: (&str, &str) = (
+            "GOVERNANCE_ADDR",
+            "0x0000000000000000000000000000000000000001",
+        );
+
+        let values = profile(&[
+            ("ETH_NETWORK", "goerli"),
+            ("CHAIN_ID", "5"),
+            ("ETH_WAIT_CONFIRMATIONS", "3"),
+            CONTRACT,
+            GOVERNANCE,
+        ]);
+        assert!(check_network_profile(&values).is_ok());
+
+        let values = profile(&[("ETH_NETWORK", "goerli"), CONTRACT, GOVERNANCE]);
+        assert_eq!(
+            check_network_profile(&values),
+            Err("CHAIN_ID is not set".to_string())
+        );
+
+        // Contract addresses are required for every network except the local one.
+        let values = profile(&[("ETH_NETWORK", "goerli"), ("CHAIN_ID", "5"), CONTRACT]);
+        assert_eq!(
+            check_network_profile(&values),
+            Err("GOVERNANCE_ADDR is not set".to_string())
+        );
+        let values = profile(&[("ETH_NETWORK", "localhost"), ("CHAIN_ID", "9")]);
+        assert!(check_network_profile(&values).is_ok());
+
+        let values = profile(&[
+            ("ETH_NETWORK", "goerli"),
+            ("CHAIN_ID", "5"),
+            CONTRACT,
+            GOVERNANCE,
+            ("REST_API_PORT", "3001"),
+        ]);
+        assert_eq!(
+            check_network_profile(&values),
+            Err("REST_API_PORT is not a network parameter".to_string())
+        );
+    }
+
+    /// Checks that the network profiles shipped with the repository are valid.
+    #[test]
+    fn shipped_network_profiles() {
+        // There is no public Goerli deployment, so its addresses are set by the operator.
+        let values = read_config_file(&network_profile_path("goerli")).unwrap();
+        assert_eq!(
+            check_network_profile(&values),
+            Err("CONTRACT_ADDR is not set".to_string())
+        );
+
+        for name in &["localhost", "mainnet"] {
+            let values = read_config_file(&network_profile_path(name)).unwrap();
+            check_network_profile(&values).unwrap();
+
+            let errors: Vec<_> = values
+                .iter()
+                .filter_map(|(name, value)| {
+                    let variable = VARIABLES.iter().find(|var| var.name == name.as_str())?;
+                    variable.check(Some(value)).err()
+                })
+                .collect();
+            assert!(errors.is_empty(), "{}: {:?}", name, errors);
+        }
+    }
+
+    #[test]
+    fn reloadable_changes() {
+        let current = |name: &str| match name {
+            "TICKER_FAST_PROCESSING_COEFF" => Some("10".to_string()),
+            "BLOCK_COMMIT_DEADLINE_SECS" => Some("300".to_string()),
+            "REST_API_PORT" => Some("3001".to_string()),
+            _ => None,
+        };
+        let values = vec![
+            ("TICKER_FAST_PROCESSING_COEFF".to_string(), "10".to_string()),
+            ("BLOCK_COMMIT_DEADLINE_SECS".to_string(), "60".to_string()),
+            ("MINIBLOCKS_ITERATIONS".to_string(), "5".to_string()),
+            ("REST_API_PORT".to_string(), "3002".to_string()),
+        ];
+
+        let (changes, ignored_changes) = config_changes(values, current);
+        assert_eq!(
+            changes,
+            vec![
+                ConfigChange {
+                    variable: "BLOCK_COMMIT_DEADLINE_SECS".to_string(),
+                    old_value: Some("300".to_string()),
+                    new_value: "60".to_string(),
+                },
+                ConfigChange {
+                    variable: "MINIBLOCKS_ITERATIONS".to_string(),
+                    old_value: None,
+                    new_value: "5".to_string(),
+                },
+            ]
+        );
+        assert_eq!(ignored_changes.len(), 1);
+        assert_eq!(ignored_changes[0].variable, "REST_API_PORT");
+        assert_eq!(
+            changes[0].to_string(),
+            "BLOCK_COMMIT_DEADLINE_SECS: '300' -> '60'"
+        );
+    }
+
+    #[test]
+    fn reloaded_values_override_environment() {
+        const VARIABLE: &str = "TICKER_TOKEN_FEE_MARKUP_PERCENT";
+        env::set_var(VARIABLE, "5");
+        assert_eq!(parse_reloadable_var::<u32>(VARIABLE), Some(5));
+
+        RELOADED_VALUES
+            .write()
+            .unwrap()
+            .insert(VARIABLE.to_string(), "10".to_string());
+        assert_eq!(parse_reloadable_var::<u32>(VARIABLE), Some(10));
+        // The environment is not modified at runtime.
+        assert_eq!(env::var(VARIABLE).unwrap(), "5");
+        assert!(config_report().contains("TICKER_TOKEN_FEE_MARKUP_PERCENT = \"10\""));
+    }
+
+    #[test]
+    fn config_validation() {
+        let mut values: HashMap<&str, String> = VARIABLES
+            .iter()
+            .filter(|variable| variable.required)
+            .map(|variable| {
+                let value = match variable.kind {
+                    ValueKind::Integer => "1",
+                    ValueKind::Port => "3000",
+                    ValueKind::Float => "1.5",
+                    ValueKind::Bool => "false",
+                    ValueKind::EthAddress => "0x0000000000000000000000000000000000000001",
+                    ValueKind::AddressList => "0000000000000000000000000000000000000001",
+                    ValueKind::EthHash => {
+                        "0x0000000000000000000000000000000000000000000000000000000000000001"
+                    }
+                    ValueKind::Endpoint => "http://127.0.0.1:3000",
+                    ValueKind::IntegerList => "6,30",
+                    ValueKind::IpList => "127.0.0.1",
+                    ValueKind::AddressMap => "ETH:0x0000000000000000000000000000000000000001",
+                    ValueKind::DecimalMap => "ETH:1.5",
+                    ValueKind::TxOrder => "fifo",
+                    ValueKind::PricePolicy => "fallback",
+                    ValueKind::Text => "localhost",
+                };
+                (variable.name, value.to_string())
+            })
+            .collect();
+        assert!(validate(|name| values.get(name).cloned()).is_empty());
+
+        values.remove("ETH_WAIT_CONFIRMATIONS");
+        values.insert("CONTRACT_ADDR", "0x1234".to_string());
+        values.insert("BLOCK_CHUNK_SIZES", "6,a".to_string());
+        values.insert("API_TLS_CERT_PATH", "cert.pem".to_string());
+        let errors = validate(|name| values.get(name).cloned());
+
+        let invalid_variables: Vec<_> = errors.iter().map(|err| err.variable.as_str()).collect();
+        assert_eq!(
+            invalid_variables,
+            vec![
+                "CONTRACT_ADDR",
+                "ETH_WAIT_CONFIRMATIONS",
+                "BLOCK_CHUNK_SIZES",
+                "API_TLS_CERT_PATH"
+            ]
+        );
+        assert_eq!(errors[1].section, ConfigSection::EthSender);
+        assert_eq!(errors[1].message, "missing value");
+    }
+}
@@ -11,6 +11,13 @@ const IERC20_CONTRACT_FILE: &str =
     "contracts/artifacts/cache/solpp-generated-contracts/IERC20.sol/IERC20.json";
 const IEIP1271_CONTRACT_FILE: &str =
     "contracts/artifacts/cache/solpp-generated-contracts/IEIP1271.sol/IEIP1271.json";
+const VERIFIER_CONTRACT_FILE: &str =
+    "contracts/artifacts/cache/solpp-generated-contracts/Verifier.sol/Verifier.json";
+const UPGRADE_GATEKEEPER_CONTRACT_FILE: &str =
+    "contracts/artifacts/cache/solpp-generated-contracts/UpgradeGatekeeper.sol/UpgradeGatekeeper.json";
+/// Directory with the ABI of the zkSync contract versions, `ZkSync.v<version>.json` file
+/// has the same format as the build artifact.
+const VERSIONED_ZKSYNC_CONTRACTS_DIR: &str = "contracts/abi";
 
 fn read_file_to_json_value(path: &str) -> io::Result<serde_json::Value> {
     let zksync_home = std::env::var("ZKSYNC_HOME").unwrap_or_else(|_| ".".into());
@@ -55,3 +62,38 @@ pub fn eip1271_contract() -> Contract {
         .to_string();
     Contract::load(abi_string.as_bytes()).expect("erc20 contract abi")
 }
+
+pub fn verifier_contract() -> Contract {
+    let abi_string = read_file_to_json_value(VERIFIER_CONTRACT_FILE)
+        .expect("couldn't read VERIFIER_CONTRACT_FILE")
+        .get("abi")
+        .expect("couldn't get abi from VERIFIER_CONTRACT_FILE")
+        .to_string();
+    Contract::load(abi_string.as_bytes()).expect("verifier contract abi")
+}
+
+pub fn upgrade_gatekeeper_contract() -> Contract {
+    let abi_string = read_file_to_json_value(UPGRADE_GATEKEEPER_CONTRACT_FILE)
+        .expect("couldn't read UPGRADE_GATEKEEPER_CONTRACT_FILE")
+        .get("abi")
+        .expect("couldn't get abi from UPGRADE_GATEKEEPER_CONTRACT_FILE")
+        .to_string();
+    Contract::load(abi_string.as_bytes()).expect("upgrade gatekeeper contract abi")
+}
+
+/// Loads the ABI of the given zkSync contract version.
+///
+/// Unlike the ABI from the build artifacts, the versioned ABI is loaded at runtime once
+/// the contract is upgraded, so an error is returned instead of panicking.
+pub fn versioned_zksync_contract(version_id: u64) -> io::Result<Contract> {
+    let path = format!(
+        "{}/ZkSync.v{}.json",
+        VERSIONED_ZKSYNC_CONTRACTS_DIR, version_id
+    );
+    let abi_string = read_file_to_json_value(&path)?
+        .get("abi")
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "no abi in the file"))?
+        .to_string();
+    Contract::load(abi_string.as_bytes())
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))
+}
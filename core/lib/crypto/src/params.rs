@@ -91,6 +91,20 @@ pub const NONCE_BIT_WIDTH: usize = 32;
 pub const CHUNK_BIT_WIDTH: usize = 72;
 pub const CHUNK_BYTES: usize = CHUNK_BIT_WIDTH / 8;
 
+// Amount of chunks taken by each operation in the block. Operation public data is padded
+// to this amount of chunks, so the values must match the circuit. Block capacity, mempool
+// admission and fees are derived from this table, so the circuit change only requires
+// updating the values below.
+pub const NOOP_CHUNKS: usize = 1;
+pub const DEPOSIT_CHUNKS: usize = 6;
+pub const TRANSFER_TO_NEW_CHUNKS: usize = 6;
+pub const WITHDRAW_CHUNKS: usize = 6;
+pub const CLOSE_CHUNKS: usize = 1;
+pub const TRANSFER_CHUNKS: usize = 2;
+pub const FULL_EXIT_CHUNKS: usize = 6;
+pub const CHANGE_PUBKEY_CHUNKS: usize = 6;
+pub const FORCED_EXIT_CHUNKS: usize = 6;
+
 pub const MAX_CIRCUIT_MSG_HASH_BITS: usize = 736;
 
 pub const ETH_ADDRESS_BIT_WIDTH: usize = 160;
@@ -3,7 +3,9 @@ use std::fmt;
 
 // External uses
 use web3::contract::tokens::Tokenize;
-use web3::contract::Options;
+use web3::contract::{Contract, Options};
+use web3::helpers::CallFuture;
+use web3::transports::Http;
 use web3::types::{Address, BlockNumber, Bytes, TransactionReceipt};
 use web3::types::{H160, H256, U256, U64};
 use web3::{Error, Transport, Web3};
@@ -17,12 +19,82 @@ use zksync_eth_signer::{raw_ethereum_tx::RawTransaction, EthereumSigner};
 /// This is an emergency value, which will not be used normally.
 const FALLBACK_GAS_LIMIT: u64 = 3_000_000;
 
+/// Checks that the Ethereum node is connected to the network with the expected chain ID,
+/// and the zkSync contract is deployed there.
+///
+/// The contract is recognized by its getters: it must be governed by the expected governance
+/// contract and use the deployed verifier, so an address of an unrelated contract is rejected.
+pub async fn check_network(
+    web3_url: &str,
+    chain_id: u8,
+    contract_addr: Address,
+    contract_abi: ethabi::Contract,
+    governance_addr: Address,
+) -> Result<(), anyhow::Error> {
+    let transport = Http::new(web3_url)?;
+    let node_chain_id: U64 = CallFuture::new(transport.execute("eth_chainId", vec![])).await?;
+    anyhow::ensure!(
+        node_chain_id == U64::from(chain_id),
+        "Ethereum node {} is connected to the network with chain ID {}, expected {}",
+        web3_url,
+        node_chain_id,
+        chain_id
+    );
+
+    let web3 = Web3::new(transport);
+    let code = web3.eth().code(contract_addr, None).await?;
+    anyhow::ensure!(
+        !code.0.is_empty(),
+        "zkSync contract {:?} is not deployed on the network of the Ethereum node {}",
+        contract_addr,
+        web3_url
+    );
+
+    let contract = Contract::new(web3.eth(), contract_addr, contract_abi);
+    let not_zksync = |getter: &str, err: web3::contract::Error| {
+        anyhow::anyhow!(
+            "Contract {:?} is not the zkSync contract, `{}` call failed: {}",
+            contract_addr,
+            getter,
+            err
+        )
+    };
+    let contract_governance: Address = contract
+        .query("governance", (), None, Options::default(), None)
+        .await
+        .map_err(|err| not_zksync("governance", err))?;
+    anyhow::ensure!(
+        contract_governance == governance_addr,
+        "zkSync contract {:?} is governed by {:?}, expected {:?}",
+        contract_addr,
+        contract_governance,
+        governance_addr
+    );
+    let verifier: Address = contract
+        .query("verifier", (), None, Options::default(), None)
+        .await
+        .map_err(|err| not_zksync("verifier", err))?;
+    for (name, address) in &[("governance", governance_addr), ("verifier", verifier)] {
+        let code = web3.eth().code(*address, None).await?;
+        anyhow::ensure!(
+            !code.0.is_empty(),
+            "zkSync {} contract {:?} is not deployed",
+            name,
+            address
+        );
+    }
+    Ok(())
+}
+
 #[derive(Clone)]
 pub struct ETHClient<T: Transport, S: EthereumSigner> {
     eth_signer: S,
     pub sender_account: Address,
     pub contract_addr: H160,
     pub contract: ethabi::Contract,
+    /// Version of the contract upgrade the ABI corresponds to,
+    /// `None` for the ABI the client was created with.
+    pub contract_version: Option<u64>,
     pub chain_id: u8,
     pub gas_price_factor: f64,
     pub web3: Web3<T>,
@@ -35,6 +107,7 @@ impl<T: Transport, S: EthereumSigner> fmt::Debug for ETHClient<T, S> {
         f.debug_struct("ETHClient")
             .field("sender_account", &self.sender_account)
             .field("contract_addr", &self.contract_addr)
+            .field("contract_version", &self.contract_version)
             .field("chain_id", &self.chain_id)
             .field("gas_price_factor", &self.gas_price_factor)
             .finish()
@@ -65,11 +138,19 @@ impl<T: Transport, S: EthereumSigner> ETHClient<T, S> {
             contract_addr: contract_eth_addr,
             chain_id,
             contract,
+            contract_version: None,
             gas_price_factor,
             web3: Web3::new(transport),
         }
     }
 
+    /// Replaces the ABI used to encode the contract calls with the ABI of the given
+    /// contract version, e.g. once the contract is upgraded.
+    pub fn set_contract(&mut self, version_id: u64, contract: ethabi::Contract) {
+        self.contract = contract;
+        self.contract_version = Some(version_id);
+    }
+
     /// Returns the next *expected* nonce with respect to the transactions
     /// in the mempool.
     ///
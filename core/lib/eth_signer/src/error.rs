@@ -29,6 +29,10 @@ pub enum SignerError {
     DefineAddress,
     #[error("Recover address from signature failed: {0}")]
     RecoverAddress(String),
+    #[error("EIP1271 signature can't be used to sign the operation")]
+    UnsupportedEIP1271Signature,
+    #[error("EIP712 signature can't be used to sign the operation")]
+    UnsupportedEIP712Signature,
     #[error("{0}")]
     CustomError(String),
 }
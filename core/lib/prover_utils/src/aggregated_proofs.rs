@@ -148,3 +148,25 @@ pub fn gen_aggregate_proof(
         aggr_limbs,
     })
 }
+
+/// Verifies the aggregated proof against the verification key of the recursive circuit
+/// for the corresponding number of the aggregated blocks.
+pub fn verify_aggregated_proof(proof: &AggregatedProof) -> anyhow::Result<bool> {
+    let proofs_count = proof.individual_vk_inputs.len();
+    let vk_for_recursive_circuit = VkAggregate::read(
+        File::open(get_recursive_verification_key_path(proofs_count)).map_err(|e| {
+            anyhow::format_err!(
+                "Recursive verification key for {} proofs not found: {}",
+                proofs_count,
+                e
+            )
+        })?,
+    )?;
+
+    let is_valid = verify::<_, _, RollingKeccakTranscript<<Engine as ScalarEngine>::Fr>>(
+        &vk_for_recursive_circuit,
+        &proof.proof,
+        None,
+    )?;
+    Ok(is_valid)
+}
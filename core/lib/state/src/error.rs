@@ -0,0 +1,133 @@
+//! Errors of the transaction execution describing the failed checks.
+
+use num::BigUint;
+use thiserror::Error;
+use zksync_types::{
+    tx::{BalanceTrace, NonceTrace, SignatureTrace, TxFailureTrace},
+    AccountId, Nonce, PubKeyHash, TokenId,
+};
+
+/// Check of the transaction which failed during its execution, along with the checked values.
+///
+/// Returned by `ZkSyncState::execute_tx` within the error, the message is the reason reported to the user.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum TxCheckError {
+    #[error("Nonce mismatch")]
+    Nonce {
+        account_id: AccountId,
+        trace: NonceTrace,
+    },
+    #[error("{message}")]
+    Balance {
+        account_id: AccountId,
+        message: &'static str,
+        trace: BalanceTrace,
+    },
+    #[error("{message}")]
+    Signature {
+        account_id: AccountId,
+        message: &'static str,
+        trace: SignatureTrace,
+    },
+}
+
+/// Error of the transaction of the failed batch which didn't cause the failure:
+/// transactions of the batch are executed all together or not at all.
+#[derive(Debug, Clone, PartialEq, Error)]
+#[error("{0}")]
+pub struct BatchRolledBack(pub String);
+
+pub(crate) fn check_nonce(
+    account_id: AccountId,
+    expected: Nonce,
+    actual: Nonce,
+) -> Result<(), TxCheckError> {
+    if expected == actual {
+        return Ok(());
+    }
+    Err(TxCheckError::Nonce {
+        account_id,
+        trace: NonceTrace { expected, actual },
+    })
+}
+
+pub(crate) fn check_balance(
+    account_id: AccountId,
+    token: TokenId,
+    required: &BigUint,
+    available: &BigUint,
+    message: &'static str,
+) -> Result<(), TxCheckError> {
+    if available >= required {
+        return Ok(());
+    }
+    Err(TxCheckError::Balance {
+        account_id,
+        message,
+        trace: BalanceTrace {
+            token,
+            required: required.clone(),
+            available: available.clone(),
+        },
+    })
+}
+
+pub(crate) fn check_signature(
+    account_id: AccountId,
+    expected_signer: PubKeyHash,
+    actual_signer: Option<PubKeyHash>,
+    message: &'static str,
+) -> Result<(), TxCheckError> {
+    if actual_signer == Some(expected_signer) {
+        return Ok(());
+    }
+    Err(TxCheckError::Signature {
+        account_id,
+        message,
+        trace: SignatureTrace {
+            expected_signer,
+            actual_signer,
+        },
+    })
+}
+
+/// Describes the failure of the transaction rejected with the given error.
+/// Only the values of the failed check are reported, if the failure is caused by one.
+pub fn trace_tx_failure(error: &anyhow::Error) -> TxFailureTrace {
+    let mut trace = TxFailureTrace {
+        reason: error.to_string(),
+        ..TxFailureTrace::default()
+    };
+    if error.is::<BatchRolledBack>() {
+        trace.batch_rolled_back = true;
+        return trace;
+    }
+
+    match error.downcast_ref::<TxCheckError>() {
+        Some(TxCheckError::Nonce {
+            account_id,
+            trace: nonce,
+        }) => {
+            trace.account_id = Some(*account_id);
+            trace.nonce = Some(nonce.clone());
+        }
+        Some(TxCheckError::Balance {
+            account_id,
+            trace: balance,
+            ..
+        }) => {
+            trace.account_id = Some(*account_id);
+            trace.balance = Some(balance.clone());
+        }
+        Some(TxCheckError::Signature {
+            account_id,
+            trace: signature,
+            ..
+        }) => {
+            trace.account_id = Some(*account_id);
+            trace.signature = Some(signature.clone());
+        }
+        None => {}
+    }
+    trace
+}
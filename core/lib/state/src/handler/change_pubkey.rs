@@ -8,6 +8,7 @@ use zksync_types::{
 };
 
 use crate::{
+    error::{check_balance, check_nonce, check_signature},
     handler::TxHandler,
     state::{CollectedFee, OpSuccess, ZkSyncState},
 };
@@ -27,10 +28,13 @@ impl TxHandler<ChangePubKey> for ZkSyncState {
             tx.is_eth_auth_data_valid(),
             "ChangePubKey Ethereum auth data is incorrect"
         );
-        ensure!(
-            tx.verify_signature() == Some(tx.new_pk_hash),
-            "ChangePubKey zkSync signature is incorrect"
-        );
+        // Transaction is signed with the key being set.
+        check_signature(
+            account_id,
+            tx.new_pk_hash,
+            tx.verify_signature(),
+            "ChangePubKey zkSync signature is incorrect",
+        )?;
         ensure!(
             account_id == tx.account_id,
             "ChangePubKey account id is incorrect"
@@ -69,14 +73,20 @@ impl TxHandler<ChangePubKey> for ZkSyncState {
         let old_nonce = account.nonce;
 
         // Update nonce.
-        ensure!(op.tx.nonce == account.nonce, "Nonce mismatch");
+        check_nonce(op.account_id, account.nonce, op.tx.nonce)?;
         account.nonce += 1;
 
         // Update pubkey hash.
         account.pub_key_hash = op.tx.new_pk_hash;
 
         // Subract fees.
-        ensure!(old_balance >= op.tx.fee, "Not enough balance");
+        check_balance(
+            op.account_id,
+            op.tx.fee_token,
+            &op.tx.fee,
+            &old_balance,
+            "Not enough balance",
+        )?;
         account.sub_balance(op.tx.fee_token, &op.tx.fee);
 
         let new_pub_key_hash = account.pub_key_hash;
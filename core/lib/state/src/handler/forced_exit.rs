@@ -5,6 +5,7 @@ use zksync_types::{AccountUpdate, AccountUpdates, ForcedExit, ForcedExitOp, PubK
 use zksync_utils::BigUintSerdeWrapper;
 
 use crate::{
+    error::{check_balance, check_nonce, check_signature},
     handler::TxHandler,
     state::{CollectedFee, OpSuccess, ZkSyncState},
 };
@@ -17,10 +18,12 @@ impl TxHandler<ForcedExit> for ZkSyncState {
         let initiator_account = self
             .get_account(tx.initiator_account_id)
             .ok_or_else(|| format_err!("Initiator account does not exist"))?;
-        ensure!(
-            tx.verify_signature() == Some(initiator_account.pub_key_hash),
-            "ForcedExit signature is incorrect"
-        );
+        check_signature(
+            tx.initiator_account_id,
+            initiator_account.pub_key_hash,
+            tx.verify_signature(),
+            "ForcedExit signature is incorrect",
+        )?;
 
         // Check the token ID correctness.
         ensure!(
@@ -92,11 +95,14 @@ impl TxHandler<ForcedExit> for ZkSyncState {
         let initiator_old_balance = initiator_account.get_balance(op.tx.token);
         let initiator_old_nonce = initiator_account.nonce;
 
-        ensure!(op.tx.nonce == initiator_old_nonce, "Nonce mismatch");
-        ensure!(
-            initiator_old_balance >= op.tx.fee,
-            "Initiator account: Not enough balance to cover fees"
-        );
+        check_nonce(initiator_account_id, initiator_old_nonce, op.tx.nonce)?;
+        check_balance(
+            initiator_account_id,
+            op.tx.token,
+            &op.tx.fee,
+            &initiator_old_balance,
+            "Initiator account: Not enough balance to cover fees",
+        )?;
 
         // Check that target account has required amount of tokens to withdraw.
         // (normally, it should, since we're declaring this amount ourselves, but
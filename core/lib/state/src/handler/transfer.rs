@@ -7,6 +7,7 @@ use zksync_types::{
 };
 
 use crate::{
+    error::{check_balance, check_nonce, check_signature},
     handler::TxHandler,
     state::{CollectedFee, OpSuccess, TransferOutcome, ZkSyncState},
 };
@@ -30,10 +31,12 @@ impl TxHandler<Transfer> for ZkSyncState {
             from_account.pub_key_hash != PubKeyHash::default(),
             "Account is locked"
         );
-        ensure!(
-            tx.verify_signature() == Some(from_account.pub_key_hash),
-            "Transfer signature is incorrect"
-        );
+        check_signature(
+            from,
+            from_account.pub_key_hash,
+            tx.verify_signature(),
+            "Transfer signature is incorrect",
+        )?;
         ensure!(from == tx.account_id, "Transfer account id is incorrect");
 
         let outcome = if let Some((to, _)) = self.get_account_by_address(&tx.to) {
@@ -100,11 +103,14 @@ impl ZkSyncState {
         let from_old_balance = from_account.get_balance(op.tx.token);
         let from_old_nonce = from_account.nonce;
 
-        ensure!(op.tx.nonce == from_old_nonce, "Nonce mismatch");
-        ensure!(
-            from_old_balance >= &op.tx.amount + &op.tx.fee,
-            "Not enough balance"
-        );
+        check_nonce(op.from, from_old_nonce, op.tx.nonce)?;
+        check_balance(
+            op.from,
+            op.tx.token,
+            &(&op.tx.amount + &op.tx.fee),
+            &from_old_balance,
+            "Not enough balance",
+        )?;
 
         from_account.sub_balance(op.tx.token, &(&op.tx.amount + &op.tx.fee));
         from_account.nonce += 1;
@@ -169,11 +175,14 @@ impl ZkSyncState {
         let old_balance = account.get_balance(op.tx.token);
         let old_nonce = account.nonce;
 
-        ensure!(op.tx.nonce == old_nonce, "Nonce mismatch");
-        ensure!(
-            old_balance >= &op.tx.amount + &op.tx.fee,
-            "Not enough balance"
-        );
+        check_nonce(op.from, old_nonce, op.tx.nonce)?;
+        check_balance(
+            op.from,
+            op.tx.token,
+            &(&op.tx.amount + &op.tx.fee),
+            &old_balance,
+            "Not enough balance",
+        )?;
 
         account.sub_balance(op.tx.token, &op.tx.fee);
         account.nonce += 1;
@@ -230,11 +239,14 @@ impl ZkSyncState {
         let mut from_account = self.get_account(op.from).unwrap();
         let from_old_balance = from_account.get_balance(op.tx.token);
         let from_old_nonce = from_account.nonce;
-        ensure!(op.tx.nonce == from_old_nonce, "Nonce mismatch");
-        ensure!(
-            from_old_balance >= &op.tx.amount + &op.tx.fee,
-            "Not enough balance"
-        );
+        check_nonce(op.from, from_old_nonce, op.tx.nonce)?;
+        check_balance(
+            op.from,
+            op.tx.token,
+            &(&op.tx.amount + &op.tx.fee),
+            &from_old_balance,
+            "Not enough balance",
+        )?;
         from_account.sub_balance(op.tx.token, &(&op.tx.amount + &op.tx.fee));
         from_account.nonce += 1;
         let from_new_balance = from_account.get_balance(op.tx.token);
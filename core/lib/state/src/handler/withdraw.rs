@@ -4,6 +4,7 @@ use zksync_crypto::params::{self, max_account_id};
 use zksync_types::{AccountUpdate, AccountUpdates, PubKeyHash, Withdraw, WithdrawOp, ZkSyncOp};
 
 use crate::{
+    error::{check_balance, check_nonce, check_signature},
     handler::TxHandler,
     state::{CollectedFee, OpSuccess, ZkSyncState},
 };
@@ -23,10 +24,12 @@ impl TxHandler<Withdraw> for ZkSyncState {
             account.pub_key_hash != PubKeyHash::default(),
             "Account is locked"
         );
-        ensure!(
-            tx.verify_signature() == Some(account.pub_key_hash),
-            "withdraw signature is incorrect"
-        );
+        check_signature(
+            account_id,
+            account.pub_key_hash,
+            tx.verify_signature(),
+            "withdraw signature is incorrect",
+        )?;
         ensure!(
             account_id == tx.account_id,
             "Withdraw account id is incorrect"
@@ -63,11 +66,14 @@ impl TxHandler<Withdraw> for ZkSyncState {
         let from_old_balance = from_account.get_balance(op.tx.token);
         let from_old_nonce = from_account.nonce;
 
-        ensure!(op.tx.nonce == from_old_nonce, "Nonce mismatch");
-        ensure!(
-            from_old_balance >= &op.tx.amount + &op.tx.fee,
-            "Not enough balance"
-        );
+        check_nonce(op.account_id, from_old_nonce, op.tx.nonce)?;
+        check_balance(
+            op.account_id,
+            op.tx.token,
+            &(&op.tx.amount + &op.tx.fee),
+            &from_old_balance,
+            "Not enough balance",
+        )?;
 
         from_account.sub_balance(op.tx.token, &(&op.tx.amount + &op.tx.fee));
         from_account.nonce += 1;
@@ -9,7 +9,7 @@ use zksync_types::{
     BlockNumber, SignedZkSyncTx, TokenId, ZkSyncPriorityOp, ZkSyncTx,
 };
 
-use crate::handler::TxHandler;
+use crate::{error::BatchRolledBack, handler::TxHandler};
 
 #[derive(Debug)]
 pub struct OpSuccess {
@@ -117,16 +117,7 @@ impl ZkSyncState {
     }
 
     pub fn chunks_for_tx(&self, franklin_tx: &ZkSyncTx) -> usize {
-        match franklin_tx {
-            ZkSyncTx::Transfer(tx) => {
-                if self.get_account_by_address(&tx.to).is_some() {
-                    TransferOp::CHUNKS
-                } else {
-                    TransferToNewOp::CHUNKS
-                }
-            }
-            _ => franklin_tx.min_chunks(),
-        }
+        franklin_tx.chunks(|address| self.get_account_by_address(address).is_some())
     }
 
     /// Priority op execution should not fail.
@@ -224,9 +215,18 @@ impl ZkSyncState {
                         error
                     );
 
-                    // Create the same error for each transaction.
+                    // Create the same error message for each transaction, the error
+                    // of the failed transaction keeps the failed check.
+                    let mut error = Some(error);
                     let errors = (0..txs.len())
-                        .map(|_| Err(anyhow::format_err!("{}", error_msg)))
+                        .map(|tx_id| {
+                            if tx_id == id {
+                                let error = error.take().expect("failed tx is unique");
+                                Err(error.context(error_msg.clone()))
+                            } else {
+                                Err(BatchRolledBack(error_msg.clone()).into())
+                            }
+                        })
                         .collect();
 
                     // Stop execution and return an error.
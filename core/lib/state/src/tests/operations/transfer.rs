@@ -1,7 +1,13 @@
-use crate::tests::{AccountState::*, PlasmaTestBuilder};
+use crate::{
+    error::trace_tx_failure,
+    tests::{AccountState::*, PlasmaTestBuilder},
+};
 use num::{BigUint, Zero};
 use web3::types::H160;
-use zksync_types::{AccountUpdate, Transfer};
+use zksync_types::{
+    tx::{BalanceTrace, TxFailureTrace},
+    AccountUpdate, SignedZkSyncTx, Transfer, ZkSyncTx,
+};
 
 /// Check Transfer operation to existing account
 #[test]
@@ -81,6 +87,123 @@ fn insufficient_funds() {
     tb.test_tx_fail(transfer.into(), "Not enough balance");
 }
 
+/// Check the trace of the Transfer failed because of insufficient funds
+#[test]
+fn insufficient_funds_trace() {
+    let token_id = 0;
+    let amount = BigUint::from(100u32);
+    let fee = BigUint::from(10u32);
+
+    let mut tb = PlasmaTestBuilder::new();
+
+    let (from_account_id, from_account, from_sk) = tb.add_account(Unlocked);
+    tb.set_balance(from_account_id, token_id, amount.clone());
+
+    let (_to_account_id, to_account, _to_sk) = tb.add_account(Locked);
+
+    let transfer = Transfer::new_signed(
+        from_account_id,
+        from_account.address,
+        to_account.address,
+        token_id,
+        amount.clone(),
+        fee.clone(),
+        from_account.nonce,
+        &from_sk,
+    )
+    .unwrap();
+
+    let error = tb
+        .state
+        .execute_tx(transfer.into())
+        .expect_err("transaction didn't fail");
+    assert_eq!(
+        trace_tx_failure(&error),
+        TxFailureTrace {
+            reason: "Not enough balance".to_string(),
+            account_id: Some(from_account_id),
+            balance: Some(BalanceTrace {
+                token: token_id,
+                required: &amount + &fee,
+                available: amount,
+            }),
+            ..TxFailureTrace::default()
+        }
+    );
+}
+
+/// Check the traces of the batch failed because of the second Transfer:
+/// only the failed transaction reports the failed check, the first one is rolled back.
+#[test]
+fn failed_batch_trace() {
+    let token_id = 0;
+    let amount = BigUint::from(100u32);
+    let fee = BigUint::from(10u32);
+
+    let mut tb = PlasmaTestBuilder::new();
+
+    let (from_account_id, from_account, from_sk) = tb.add_account(Unlocked);
+    tb.set_balance(from_account_id, token_id, &amount + &fee);
+
+    let (_to_account_id, to_account, _to_sk) = tb.add_account(Locked);
+
+    let transfers: Vec<SignedZkSyncTx> = (0..2)
+        .map(|i| {
+            let transfer = Transfer::new_signed(
+                from_account_id,
+                from_account.address,
+                to_account.address,
+                token_id,
+                amount.clone(),
+                fee.clone(),
+                from_account.nonce + i,
+                &from_sk,
+            )
+            .unwrap();
+            ZkSyncTx::from(transfer).into()
+        })
+        .collect();
+
+    let errors: Vec<_> = tb
+        .state
+        .execute_txs_batch(&transfers)
+        .into_iter()
+        .map(|result| result.expect_err("batch didn't fail"))
+        .collect();
+    let reason =
+        "Batch execution failed, since tx #2 of batch failed with a reason: Not enough balance";
+
+    assert_eq!(
+        trace_tx_failure(&errors[0]),
+        TxFailureTrace {
+            reason: reason.to_string(),
+            batch_rolled_back: true,
+            ..TxFailureTrace::default()
+        }
+    );
+    assert_eq!(
+        trace_tx_failure(&errors[1]),
+        TxFailureTrace {
+            reason: reason.to_string(),
+            account_id: Some(from_account_id),
+            balance: Some(BalanceTrace {
+                token: token_id,
+                required: &amount + &fee,
+                available: BigUint::zero(),
+            }),
+            ..TxFailureTrace::default()
+        }
+    );
+    // The state of the accounts is restored.
+    assert_eq!(
+        tb.state
+            .get_account(from_account_id)
+            .unwrap()
+            .get_balance(token_id),
+        &amount + &fee
+    );
+}
+
 /// Check Transfer operation to new account
 #[test]
 fn to_new() {
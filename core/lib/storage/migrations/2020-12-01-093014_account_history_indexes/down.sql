@@ -0,0 +1,5 @@
+DROP INDEX executed_transactions_from_account_history_index;
+DROP INDEX executed_transactions_to_account_history_index;
+DROP INDEX executed_transactions_primary_account_history_index;
+DROP INDEX executed_priority_operations_from_account_history_index;
+DROP INDEX executed_priority_operations_to_account_history_index;
@@ -0,0 +1,12 @@
+-- Composite indexes used by the account transactions history queries,
+-- so the paginated requests don't scan every transaction of the account.
+CREATE INDEX executed_transactions_from_account_history_index
+    ON executed_transactions (from_account, block_number, block_index);
+CREATE INDEX executed_transactions_to_account_history_index
+    ON executed_transactions (to_account, block_number, block_index);
+CREATE INDEX executed_transactions_primary_account_history_index
+    ON executed_transactions (primary_account_address, block_number, block_index);
+CREATE INDEX executed_priority_operations_from_account_history_index
+    ON executed_priority_operations (from_account, block_number, block_index);
+CREATE INDEX executed_priority_operations_to_account_history_index
+    ON executed_priority_operations (to_account, block_number, block_index);
@@ -0,0 +1,2 @@
+DROP TABLE server_flags;
+DROP TABLE token_settings;
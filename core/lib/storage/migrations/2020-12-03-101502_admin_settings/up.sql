@@ -0,0 +1,14 @@
+-- Runtime settings of the tokens changed through the admin API.
+-- `fee_allowed` overrides the server configuration if set.
+CREATE TABLE token_settings (
+    token_id INTEGER NOT NULL PRIMARY KEY REFERENCES tokens(id) ON DELETE CASCADE,
+    disabled BOOLEAN NOT NULL DEFAULT false,
+    fee_allowed BOOLEAN
+);
+
+-- Operational flags of the server changed through the admin API.
+CREATE TABLE server_flags (
+    name TEXT NOT NULL PRIMARY KEY,
+    enabled BOOLEAN NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
@@ -0,0 +1 @@
+ALTER TABLE eth_operations ADD COLUMN confirmed_at TIMESTAMP WITH TIME ZONE;
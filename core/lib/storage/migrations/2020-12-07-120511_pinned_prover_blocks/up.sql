@@ -0,0 +1,5 @@
+-- Blocks pinned by the operator, jobs containing them are given to the provers first.
+CREATE TABLE pinned_prover_blocks (
+    block_number BIGINT NOT NULL PRIMARY KEY,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
@@ -0,0 +1,12 @@
+-- Provers that requested jobs from the server, used to monitor the prover fleet.
+CREATE TABLE provers (
+    name TEXT NOT NULL PRIMARY KEY,
+    first_seen_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    last_heartbeat TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    stopped_at TIMESTAMP WITH TIME ZONE,
+    -- Job the prover has been working on since `current_job_started_at`.
+    current_job_id INTEGER,
+    current_job_started_at TIMESTAMP WITH TIME ZONE,
+    jobs_completed BIGINT NOT NULL DEFAULT 0,
+    total_proving_time_secs DOUBLE PRECISION NOT NULL DEFAULT 0
+);
@@ -0,0 +1,4 @@
+ALTER TABLE aggregated_proofs DROP COLUMN created_by;
+ALTER TABLE proofs DROP COLUMN created_by;
+
+DROP TABLE IF EXISTS prover_credentials;
@@ -0,0 +1,10 @@
+-- Secrets of the provers authenticating with their own credentials instead of the shared one.
+CREATE TABLE prover_credentials (
+    prover_name TEXT PRIMARY KEY,
+    secret TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+
+-- Provers that produced the proofs.
+ALTER TABLE proofs ADD COLUMN created_by TEXT;
+ALTER TABLE aggregated_proofs ADD COLUMN created_by TEXT;
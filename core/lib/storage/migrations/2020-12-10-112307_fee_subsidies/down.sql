@@ -0,0 +1,2 @@
+DROP TABLE subsidized_transactions;
+DROP TABLE subsidy_rules;
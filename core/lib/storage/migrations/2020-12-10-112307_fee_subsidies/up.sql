@@ -0,0 +1,24 @@
+-- Rules under which the operator covers the fee of the transactions.
+-- `tx_type` is the fee type name (e.g. `Transfer`), `token_id` of NULL matches any token.
+CREATE TABLE subsidy_rules (
+    id SERIAL PRIMARY KEY,
+    tx_type TEXT NOT NULL,
+    token_id INTEGER REFERENCES tokens(id) ON DELETE CASCADE,
+    subsidy_percent INTEGER NOT NULL,
+    daily_budget_usd NUMERIC NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+
+-- Transactions accepted with the fee partially covered by the subsidy rule.
+-- Rules may be removed, so the records keep the rule details for reporting.
+CREATE TABLE subsidized_transactions (
+    tx_hash BYTEA NOT NULL PRIMARY KEY,
+    rule_id INTEGER NOT NULL,
+    tx_type TEXT NOT NULL,
+    token_id INTEGER NOT NULL,
+    subsidized_amount NUMERIC NOT NULL,
+    subsidized_usd NUMERIC NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+CREATE INDEX subsidized_transactions_rule_id_created_at_idx
+    ON subsidized_transactions (rule_id, created_at);
@@ -0,0 +1 @@
+DROP TABLE lp_withdrawals;
@@ -0,0 +1,26 @@
+-- Withdrawals handed off to the liquidity providers: the user transfers the funds to the
+-- provider in L2, and the provider pays out `payout_amount` to `l1_recipient` in L1
+-- once the transfer is executed.
+CREATE TABLE lp_withdrawals (
+    id BIGSERIAL PRIMARY KEY,
+    tx_hash BYTEA NOT NULL UNIQUE,
+    account_address BYTEA NOT NULL,
+    lp_address BYTEA NOT NULL,
+    l1_recipient BYTEA NOT NULL,
+    token_id INTEGER NOT NULL,
+    amount NUMERIC NOT NULL,
+    payout_amount NUMERIC NOT NULL,
+    -- Ethereum transaction of the provider paying out the withdrawal.
+    l1_tx_hash BYTEA,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    paid_at TIMESTAMP WITH TIME ZONE,
+    -- Reported payout transaction is checked in L1: the withdrawal is considered paid out
+    -- only once the transaction is confirmed.
+    payout_confirmed_at TIMESTAMP WITH TIME ZONE
+);
+CREATE INDEX lp_withdrawals_lp_address_id_idx ON lp_withdrawals (lp_address, id);
+-- A single payout transaction can't be reported for several withdrawals.
+CREATE UNIQUE INDEX lp_withdrawals_l1_tx_hash_idx ON lp_withdrawals (l1_tx_hash)
+    WHERE l1_tx_hash IS NOT NULL;
+CREATE INDEX lp_withdrawals_unconfirmed_payouts_idx ON lp_withdrawals (id)
+    WHERE l1_tx_hash IS NOT NULL AND payout_confirmed_at IS NULL;
@@ -0,0 +1 @@
+ALTER TABLE tokens DROP COLUMN withdrawal_gas_limit;
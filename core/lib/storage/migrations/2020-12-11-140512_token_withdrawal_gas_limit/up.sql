@@ -0,0 +1,2 @@
+-- Gas limit of the token withdrawal in L1, the default one is used if not set.
+ALTER TABLE tokens ADD COLUMN withdrawal_gas_limit BIGINT;
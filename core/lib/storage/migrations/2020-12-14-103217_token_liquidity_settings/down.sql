@@ -0,0 +1 @@
+ALTER TABLE token_settings DROP COLUMN liquidity_tier;
@@ -0,0 +1,2 @@
+-- Market liquidity tier of the token, affects the fees paid in the token.
+ALTER TABLE token_settings ADD COLUMN liquidity_tier TEXT;
@@ -0,0 +1,18 @@
+-- Fees quoted by the ticker along with the prices they were calculated with.
+-- Quotes are downsampled by the server, so only some of them are recorded.
+CREATE TABLE fee_history (
+    id BIGSERIAL PRIMARY KEY,
+    token_id INTEGER NOT NULL,
+    -- Fee type as quoted, e.g. `TransferToNew`.
+    fee_type TEXT NOT NULL,
+    gas_tx_amount NUMERIC NOT NULL,
+    gas_price_wei NUMERIC NOT NULL,
+    gas_fee NUMERIC NOT NULL,
+    zkp_fee NUMERIC NOT NULL,
+    total_fee NUMERIC NOT NULL,
+    -- USD prices of the whole token and ETH used in the quote.
+    token_price_usd NUMERIC NOT NULL,
+    eth_price_usd NUMERIC NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+CREATE INDEX fee_history_token_id_created_at_idx ON fee_history (token_id, created_at);
@@ -0,0 +1,3 @@
+-- Whether the token symbol and decimals were set through the admin API,
+-- such tokens are not updated from the token contract in L1.
+ALTER TABLE tokens ADD COLUMN metadata_overridden BOOLEAN NOT NULL DEFAULT false;
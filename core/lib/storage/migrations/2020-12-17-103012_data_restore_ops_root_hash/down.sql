@@ -0,0 +1 @@
+ALTER TABLE data_restore_rollup_ops DROP COLUMN new_root_hash;
@@ -0,0 +1,3 @@
+-- Root hash committed to the contract for the block of the operation,
+-- used to check the restored state after each block.
+ALTER TABLE data_restore_rollup_ops ADD COLUMN new_root_hash BYTEA;
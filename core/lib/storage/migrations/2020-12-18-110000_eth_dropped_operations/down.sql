@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS eth_operation_requests;
+DROP TABLE IF EXISTS eth_dropped_operations;
@@ -0,0 +1,14 @@
+-- Ethereum operations dropped by the operator: `eth_sender` doesn't track them anymore,
+-- and their aggregated operations are not sent again.
+CREATE TABLE eth_dropped_operations (
+    eth_op_id BIGINT PRIMARY KEY REFERENCES eth_operations(id) ON DELETE CASCADE,
+    dropped_at TIMESTAMP with time zone NOT NULL DEFAULT now()
+);
+
+-- Requests to requeue or drop the Ethereum operation made through the admin API,
+-- `eth_sender` removes the request once it's handled.
+CREATE TABLE eth_operation_requests (
+    eth_op_id BIGINT PRIMARY KEY REFERENCES eth_operations(id) ON DELETE CASCADE,
+    action TEXT NOT NULL,
+    created_at TIMESTAMP with time zone NOT NULL DEFAULT now()
+);
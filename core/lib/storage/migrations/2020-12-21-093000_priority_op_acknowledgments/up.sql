@@ -0,0 +1,15 @@
+-- Deposits seen by `eth_watch` before they have enough confirmations to be processed,
+-- along with the estimated time when the deposited funds become usable.
+CREATE TABLE priority_op_acknowledgments (
+    id BIGSERIAL PRIMARY KEY,
+    serial_id BIGINT NOT NULL UNIQUE,
+    eth_hash BYTEA NOT NULL,
+    eth_block BIGINT NOT NULL,
+    address BYTEA NOT NULL,
+    token INTEGER NOT NULL,
+    amount NUMERIC NOT NULL,
+    estimated_usable_at TIMESTAMP with time zone NOT NULL,
+    created_at TIMESTAMP with time zone NOT NULL DEFAULT now()
+);
+
+CREATE INDEX priority_op_acknowledgments_eth_hash_idx ON priority_op_acknowledgments (eth_hash);
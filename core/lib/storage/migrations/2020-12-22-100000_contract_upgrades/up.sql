@@ -0,0 +1,11 @@
+-- Upgrades of the zkSync contract observed by the Ethereum watcher, along with the stage
+-- of every upgrade. Completed upgrade may change the contract interface, so `eth_sender`
+-- doesn't send new transactions until the upgrade is confirmed by the operator
+-- (or the ABI of the new contract version is used automatically).
+CREATE TABLE contract_upgrades (
+    version_id BIGINT PRIMARY KEY,
+    stage TEXT NOT NULL,
+    eth_block BIGINT NOT NULL,
+    confirmed_at TIMESTAMP with time zone,
+    updated_at TIMESTAMP with time zone NOT NULL DEFAULT now()
+);
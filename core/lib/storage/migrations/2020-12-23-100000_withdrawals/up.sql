@@ -0,0 +1,16 @@
+-- Withdrawals executed in L2, along with the Ethereum transaction executing
+-- their block, which transfers the withdrawn funds in L1.
+CREATE TABLE withdrawals (
+    tx_hash BYTEA PRIMARY KEY,
+    block_number BIGINT NOT NULL,
+    -- Address of the account the funds are withdrawn from.
+    address BYTEA NOT NULL,
+    -- Address of the L1 recipient.
+    to_address BYTEA NOT NULL,
+    token INTEGER NOT NULL,
+    amount NUMERIC NOT NULL,
+    execute_tx_hash BYTEA,
+    executed_at TIMESTAMP with time zone
+);
+
+CREATE INDEX withdrawals_block_number_idx ON withdrawals (block_number);
@@ -0,0 +1,14 @@
+-- Fees collected by the rollup blocks compared to the ETH spent by the Ethereum
+-- transactions committing, proving and executing the blocks.
+-- All the amounts are in wei, the fees are converted with the token prices
+-- known at the time the block is committed.
+CREATE TABLE block_accounting (
+    block_number BIGINT PRIMARY KEY,
+    collected_fees_wei NUMERIC NOT NULL,
+    commit_cost_wei NUMERIC NOT NULL,
+    proof_cost_wei NUMERIC NOT NULL,
+    execute_cost_wei NUMERIC NOT NULL,
+    -- Collected fees minus the ETH spent for the block so far.
+    margin_wei NUMERIC NOT NULL,
+    updated_at TIMESTAMP with time zone NOT NULL DEFAULT now()
+);
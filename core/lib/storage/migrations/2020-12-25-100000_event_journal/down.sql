@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS event_journal_consumers;
+DROP TABLE IF EXISTS event_journal;
@@ -0,0 +1,17 @@
+-- Append-only journal of the rollup events, written in the same database transactions
+-- as the state changes they describe, so external systems can mirror the rollup activity.
+CREATE TABLE event_journal (
+    id BIGSERIAL PRIMARY KEY,
+    event_type TEXT NOT NULL,
+    block_number BIGINT NOT NULL,
+    data JSONB NOT NULL,
+    created_at TIMESTAMP with time zone NOT NULL DEFAULT now()
+);
+
+-- Offsets of the external consumers: every event with the ID up to `acked_id`
+-- is acknowledged by the consumer and won't be delivered to it again.
+CREATE TABLE event_journal_consumers (
+    name TEXT PRIMARY KEY,
+    acked_id BIGINT NOT NULL,
+    updated_at TIMESTAMP with time zone NOT NULL DEFAULT now()
+);
@@ -0,0 +1,7 @@
+-- Details of the transactions failed in the state keeper, recorded if the transaction traces are enabled.
+CREATE TABLE tx_failure_traces (
+    tx_hash BYTEA PRIMARY KEY,
+    block_number BIGINT NOT NULL,
+    trace JSONB NOT NULL,
+    created_at TIMESTAMP with time zone NOT NULL DEFAULT now()
+);
@@ -0,0 +1,9 @@
+-- Transactions sent by `eth_sender` to cancel the requeued or dropped Ethereum operations:
+-- the nonce of the operation is taken by a transaction doing nothing, so the transactions
+-- of the operation can't be mined anymore.
+CREATE TABLE eth_operation_cancellations (
+    eth_op_id BIGINT PRIMARY KEY REFERENCES eth_operations(id) ON DELETE CASCADE,
+    action TEXT NOT NULL,
+    tx_hash BYTEA NOT NULL,
+    created_at TIMESTAMP with time zone NOT NULL DEFAULT now()
+);
@@ -0,0 +1,3 @@
+-- Withdrawals which failed to be transferred during the block execution are stored
+-- in the pending balance of the recipient in the contract and have to be withdrawn manually.
+ALTER TABLE withdrawals ADD COLUMN pending_balance BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,9 @@
+-- Epoch of the current leader replica, incremented by every replica which acquires
+-- the leadership. Writes preceding the Ethereum transactions are fenced by the epoch,
+-- so the former leader can't send them once the leadership is taken over.
+CREATE TABLE leader_epoch (
+    -- enforce single record
+    id bool PRIMARY KEY NOT NULL DEFAULT true,
+    CONSTRAINT single_leader_epoch CHECK (id),
+    epoch BIGINT NOT NULL
+);
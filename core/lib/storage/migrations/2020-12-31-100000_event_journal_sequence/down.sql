@@ -0,0 +1,5 @@
+CREATE SEQUENCE event_journal_id_seq OWNED BY event_journal.id;
+SELECT setval('event_journal_id_seq', GREATEST(last_id, 1), last_id > 0) FROM event_journal_sequence;
+ALTER TABLE event_journal ALTER COLUMN id SET DEFAULT nextval('event_journal_id_seq');
+
+DROP TABLE event_journal_sequence;
@@ -0,0 +1,10 @@
+-- IDs of the journal events are allocated from the single-row counter instead of the sequence.
+-- The counter row stays locked until the appending transaction is committed, so the events
+-- become visible in the order of their IDs without locking the whole journal.
+CREATE TABLE event_journal_sequence (
+    last_id BIGINT NOT NULL
+);
+INSERT INTO event_journal_sequence (last_id) SELECT COALESCE(MAX(id), 0) FROM event_journal;
+
+ALTER TABLE event_journal ALTER COLUMN id DROP DEFAULT;
+DROP SEQUENCE event_journal_id_seq;
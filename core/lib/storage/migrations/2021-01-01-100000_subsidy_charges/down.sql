@@ -0,0 +1,2 @@
+DROP INDEX subsidized_transactions_rule_id_executed_at_idx;
+ALTER TABLE subsidized_transactions DROP COLUMN executed_at;
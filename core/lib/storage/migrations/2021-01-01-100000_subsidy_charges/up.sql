@@ -0,0 +1,6 @@
+-- Subsidies are reserved once the transaction is accepted and charged once it's executed.
+-- Reservations of the transactions which are not executed yet have no `executed_at`.
+ALTER TABLE subsidized_transactions ADD COLUMN executed_at TIMESTAMP WITH TIME ZONE;
+UPDATE subsidized_transactions SET executed_at = created_at;
+CREATE INDEX subsidized_transactions_rule_id_executed_at_idx
+    ON subsidized_transactions (rule_id, executed_at);
@@ -0,0 +1,116 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+use num::{BigInt, BigUint};
+use sqlx::types::BigDecimal;
+// Workspace imports
+use zksync_types::{aggregated_operations::AggregatedActionType, BlockNumber};
+// Local imports
+use self::records::{AccountingTotals, StoredBlockAccounting};
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+/// Accounting schema reconciles the fees collected by the blocks with the ETH spent
+/// by `eth_sender` on the Ethereum transactions for these blocks.
+#[derive(Debug)]
+pub struct AccountingSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> AccountingSchema<'a, 'c> {
+    /// Adds the ETH spent on the Ethereum transaction of the given type to the block costs
+    /// and updates the block margin.
+    ///
+    /// The collected fees are only stored along with the first cost of the block, so the fees
+    /// are converted with the token prices known at the moment the block is committed.
+    pub async fn record_block_cost(
+        &mut self,
+        block_number: BlockNumber,
+        action_type: AggregatedActionType,
+        collected_fees_wei: BigUint,
+        cost_wei: BigUint,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let zero = BigDecimal::from(0);
+        let cost_wei = BigDecimal::from(BigInt::from(cost_wei));
+        let (commit_cost, proof_cost, execute_cost) = match action_type {
+            AggregatedActionType::CommitBlocks => (cost_wei, zero.clone(), zero),
+            AggregatedActionType::PublishProofBlocksOnchain => (zero.clone(), cost_wei, zero),
+            AggregatedActionType::ExecuteBlocks => (zero.clone(), zero, cost_wei),
+            // Proofs are created off-chain, so there's nothing to pay for.
+            AggregatedActionType::CreateProofBlocks => return Ok(()),
+        };
+        sqlx::query!(
+            "INSERT INTO block_accounting (block_number, collected_fees_wei, commit_cost_wei,
+                proof_cost_wei, execute_cost_wei, margin_wei)
+            VALUES ($1, $2, $3, $4, $5, $2 - $3 - $4 - $5)
+            ON CONFLICT (block_number)
+            DO UPDATE SET
+                commit_cost_wei = block_accounting.commit_cost_wei + $3,
+                proof_cost_wei = block_accounting.proof_cost_wei + $4,
+                execute_cost_wei = block_accounting.execute_cost_wei + $5,
+                margin_wei = block_accounting.margin_wei - $3 - $4 - $5,
+                updated_at = now()",
+            i64::from(block_number),
+            BigDecimal::from(BigInt::from(collected_fees_wei)),
+            commit_cost,
+            proof_cost,
+            execute_cost,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.accounting.record_block_cost", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the accounting of the blocks within the range, ordered by the block number.
+    pub async fn load_block_accounting(
+        &mut self,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+    ) -> QueryResult<Vec<StoredBlockAccounting>> {
+        let start = Instant::now();
+        let blocks = sqlx::query_as!(
+            StoredBlockAccounting,
+            "SELECT * FROM block_accounting
+            WHERE block_number >= $1 AND block_number <= $2
+            ORDER BY block_number ASC",
+            i64::from(from_block),
+            i64::from(to_block),
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.accounting.load_block_accounting", start.elapsed());
+        Ok(blocks)
+    }
+
+    /// Sums the collected fees and the spent ETH of the blocks within the range.
+    pub async fn load_accounting_totals(
+        &mut self,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+    ) -> QueryResult<AccountingTotals> {
+        let start = Instant::now();
+        let totals = sqlx::query!(
+            "SELECT count(*) AS blocks_count,
+                sum(collected_fees_wei) AS collected_fees_wei,
+                sum(commit_cost_wei + proof_cost_wei + execute_cost_wei) AS spent_wei,
+                sum(margin_wei) AS margin_wei
+            FROM block_accounting
+            WHERE block_number >= $1 AND block_number <= $2",
+            i64::from(from_block),
+            i64::from(to_block),
+        )
+        .fetch_one(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.accounting.load_accounting_totals", start.elapsed());
+        Ok(AccountingTotals {
+            blocks_count: totals.blocks_count.unwrap_or(0),
+            collected_fees_wei: totals.collected_fees_wei.unwrap_or_default(),
+            spent_wei: totals.spent_wei.unwrap_or_default(),
+            margin_wei: totals.margin_wei.unwrap_or_default(),
+        })
+    }
+}
@@ -0,0 +1,27 @@
+// External imports
+use chrono::{DateTime, Utc};
+use sqlx::{types::BigDecimal, FromRow};
+// Workspace imports
+// Local imports
+
+/// Fees collected by the block and the ETH spent for it, all in wei.
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct StoredBlockAccounting {
+    pub block_number: i64,
+    pub collected_fees_wei: BigDecimal,
+    pub commit_cost_wei: BigDecimal,
+    pub proof_cost_wei: BigDecimal,
+    pub execute_cost_wei: BigDecimal,
+    /// Collected fees minus the ETH spent for the block, negative if the block is unprofitable.
+    pub margin_wei: BigDecimal,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Sums of the block accounting over the range of blocks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountingTotals {
+    pub blocks_count: i64,
+    pub collected_fees_wei: BigDecimal,
+    pub spent_wei: BigDecimal,
+    pub margin_wei: BigDecimal,
+}
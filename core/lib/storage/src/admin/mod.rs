@@ -0,0 +1,454 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+use chrono::{DateTime, Utc};
+use sqlx::types::BigDecimal;
+// Workspace imports
+use zksync_types::{tx::TxHash, TokenId};
+// Local imports
+use self::records::{
+    StorageEthOperationRequest, StorageSubsidyReport, StorageSubsidyRule, StorageTokenSettings,
+};
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+/// Name of the flag that makes the server reject the incoming transactions.
+pub const TX_ACCEPTANCE_PAUSED_FLAG: &str = "tx_acceptance_paused";
+/// Name of the flag that puts the server into the maintenance mode: the incoming transactions
+/// are rejected, and the state keeper seals the pending block and executes no new operations.
+pub const MAINTENANCE_MODE_FLAG: &str = "maintenance_mode";
+/// Name of the flag that requests `eth_sender` to resubmit the pending Ethereum transactions.
+/// The flag is reset once `eth_sender` takes it.
+pub const ETH_SENDER_RESUBMIT_FLAG: &str = "eth_sender_resubmit";
+/// Name of the flag that requests `eth_sender` to reload the unconfirmed operations from
+/// the database and check their confirmations from scratch.
+/// The flag is reset once `eth_sender` takes it.
+pub const ETH_SENDER_RELOAD_FLAG: &str = "eth_sender_reload";
+/// Name of the flag that requests the revert of the unverified blocks. The revert is performed
+/// by the server leader before it starts the actors, and the flag is reset once it's taken.
+pub const BLOCKS_REVERT_FLAG: &str = "blocks_revert";
+
+/// Request for `eth_sender` to requeue the operation: its transactions are forgotten
+/// and the aggregated operation is sent again.
+pub const ETH_OPERATION_REQUEUE: &str = "requeue";
+/// Request for `eth_sender` to drop the operation: it's not tracked anymore
+/// and the aggregated operation is not sent again.
+pub const ETH_OPERATION_DROP: &str = "drop";
+
+/// Admin schema stores the settings changed at runtime through the admin API,
+/// so they're applied without the server restart.
+#[derive(Debug)]
+pub struct AdminSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> AdminSchema<'a, 'c> {
+    /// Loads the settings of all the tokens that were changed through the admin API.
+    pub async fn load_token_settings(&mut self) -> QueryResult<Vec<StorageTokenSettings>> {
+        let start = Instant::now();
+        let settings = sqlx::query_as!(
+            StorageTokenSettings,
+            "SELECT * FROM token_settings ORDER BY token_id ASC",
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.admin.load_token_settings", start.elapsed());
+        Ok(settings)
+    }
+
+    /// Marks the token as disabled, so the transactions with it are rejected.
+    pub async fn set_token_disabled(
+        &mut self,
+        token_id: TokenId,
+        disabled: bool,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            INSERT INTO token_settings ( token_id, disabled )
+            VALUES ( $1, $2 )
+            ON CONFLICT (token_id)
+            DO
+              UPDATE SET disabled = $2
+            "#,
+            i32::from(token_id),
+            disabled,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.admin.set_token_disabled", start.elapsed());
+        Ok(())
+    }
+
+    /// Overrides the server configuration on whether the token can be used to pay fees.
+    /// `None` makes the configuration decide again.
+    pub async fn set_token_fee_allowed(
+        &mut self,
+        token_id: TokenId,
+        fee_allowed: Option<bool>,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            INSERT INTO token_settings ( token_id, fee_allowed )
+            VALUES ( $1, $2 )
+            ON CONFLICT (token_id)
+            DO
+              UPDATE SET fee_allowed = $2
+            "#,
+            i32::from(token_id),
+            fee_allowed,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.admin.set_token_fee_allowed", start.elapsed());
+        Ok(())
+    }
+
+    /// Sets the market liquidity tier of the token, `None` resets the assessment.
+    pub async fn set_token_liquidity_tier(
+        &mut self,
+        token_id: TokenId,
+        liquidity_tier: Option<&str>,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            INSERT INTO token_settings ( token_id, liquidity_tier )
+            VALUES ( $1, $2 )
+            ON CONFLICT (token_id)
+            DO
+              UPDATE SET liquidity_tier = $2
+            "#,
+            i32::from(token_id),
+            liquidity_tier,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.admin.set_token_liquidity_tier", start.elapsed());
+        Ok(())
+    }
+
+    /// Sets the value of the server flag.
+    pub async fn set_flag(&mut self, name: &str, enabled: bool) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            INSERT INTO server_flags ( name, enabled )
+            VALUES ( $1, $2 )
+            ON CONFLICT (name)
+            DO
+              UPDATE SET enabled = $2, updated_at = now()
+            "#,
+            name,
+            enabled,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.admin.set_flag", start.elapsed());
+        Ok(())
+    }
+
+    /// Returns the value of the server flag, flags that were never set are disabled.
+    pub async fn is_flag_enabled(&mut self, name: &str) -> QueryResult<bool> {
+        let start = Instant::now();
+        let enabled = sqlx::query!("SELECT enabled FROM server_flags WHERE name = $1", name)
+            .fetch_optional(self.0.conn())
+            .await?
+            .map(|record| record.enabled)
+            .unwrap_or(false);
+
+        metrics::histogram!("sql.admin.is_flag_enabled", start.elapsed());
+        Ok(enabled)
+    }
+
+    /// Returns the value of the server flag and resets it, so the request
+    /// represented by the flag is handled only once.
+    pub async fn take_flag(&mut self, name: &str) -> QueryResult<bool> {
+        let start = Instant::now();
+        let enabled = sqlx::query!(
+            "DELETE FROM server_flags WHERE name = $1 RETURNING enabled",
+            name
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(|record| record.enabled)
+        .unwrap_or(false);
+
+        metrics::histogram!("sql.admin.take_flag", start.elapsed());
+        Ok(enabled)
+    }
+
+    /// Stores the request for `eth_sender` to requeue or drop the Ethereum operation,
+    /// the previous request for the same operation is replaced.
+    pub async fn request_eth_operation_action(
+        &mut self,
+        eth_op_id: i64,
+        action: &str,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            INSERT INTO eth_operation_requests ( eth_op_id, action )
+            VALUES ( $1, $2 )
+            ON CONFLICT (eth_op_id)
+            DO
+              UPDATE SET action = $2, created_at = now()
+            "#,
+            eth_op_id,
+            action,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.admin.request_eth_operation_action", start.elapsed());
+        Ok(())
+    }
+
+    /// Returns the pending requests for the Ethereum operations and removes them,
+    /// so every request is handled only once.
+    pub async fn take_eth_operation_requests(
+        &mut self,
+    ) -> QueryResult<Vec<StorageEthOperationRequest>> {
+        let start = Instant::now();
+        let mut requests = sqlx::query_as!(
+            StorageEthOperationRequest,
+            "DELETE FROM eth_operation_requests RETURNING *",
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+        requests.sort_by_key(|request| request.created_at);
+
+        metrics::histogram!("sql.admin.take_eth_operation_requests", start.elapsed());
+        Ok(requests)
+    }
+
+    /// Adds the rule under which the fee of the transactions is subsidized, returns its ID.
+    pub async fn add_subsidy_rule(
+        &mut self,
+        tx_type: &str,
+        token_id: Option<TokenId>,
+        subsidy_percent: u8,
+        daily_budget_usd: BigDecimal,
+    ) -> QueryResult<i32> {
+        let start = Instant::now();
+        let id = sqlx::query!(
+            r#"
+            INSERT INTO subsidy_rules ( tx_type, token_id, subsidy_percent, daily_budget_usd )
+            VALUES ( $1, $2, $3, $4 )
+            RETURNING id
+            "#,
+            tx_type,
+            token_id.map(i32::from),
+            i32::from(subsidy_percent),
+            daily_budget_usd,
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .id;
+
+        metrics::histogram!("sql.admin.add_subsidy_rule", start.elapsed());
+        Ok(id)
+    }
+
+    /// Removes the subsidy rule, returns `false` if there was no such rule.
+    /// Transactions already subsidized under the rule are kept for reporting.
+    pub async fn remove_subsidy_rule(&mut self, id: i32) -> QueryResult<bool> {
+        let start = Instant::now();
+        let removed = sqlx::query!("DELETE FROM subsidy_rules WHERE id = $1", id)
+            .execute(self.0.conn())
+            .await?
+            .rows_affected()
+            > 0;
+
+        metrics::histogram!("sql.admin.remove_subsidy_rule", start.elapsed());
+        Ok(removed)
+    }
+
+    pub async fn load_subsidy_rules(&mut self) -> QueryResult<Vec<StorageSubsidyRule>> {
+        let start = Instant::now();
+        let rules = sqlx::query_as!(
+            StorageSubsidyRule,
+            "SELECT * FROM subsidy_rules ORDER BY id ASC",
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.admin.load_subsidy_rules", start.elapsed());
+        Ok(rules)
+    }
+
+    /// Reserves the fee subsidy of the accepted transaction, unless the subsidies of the rule
+    /// counted since the given moment would exceed its daily budget.
+    /// Returns `false` if the budget is exhausted or the rule no longer exists.
+    ///
+    /// The rule is locked until the reservation is stored, so the concurrent reservations
+    /// can't exceed the budget. The reservation is charged once the transaction is executed,
+    /// and released if its execution fails or it's removed from the mempool.
+    /// Repeated reservations for the same transaction are ignored.
+    pub async fn reserve_subsidized_tx(
+        &mut self,
+        tx_hash: &TxHash,
+        rule: &StorageSubsidyRule,
+        token_id: TokenId,
+        subsidized_amount: BigDecimal,
+        subsidized_usd: BigDecimal,
+        since: DateTime<Utc>,
+    ) -> QueryResult<bool> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let daily_budget_usd = sqlx::query!(
+            "SELECT daily_budget_usd FROM subsidy_rules WHERE id = $1 FOR UPDATE",
+            rule.id,
+        )
+        .fetch_optional(transaction.conn())
+        .await?
+        .map(|row| row.daily_budget_usd);
+
+        let reserved = match daily_budget_usd {
+            Some(daily_budget_usd) => {
+                let subsidized_today_usd = AdminSchema(&mut transaction)
+                    .subsidized_usd_since(rule.id, since)
+                    .await?;
+                &subsidized_today_usd + &subsidized_usd <= daily_budget_usd
+            }
+            None => false,
+        };
+        if reserved {
+            sqlx::query!(
+                r#"
+                INSERT INTO subsidized_transactions
+                    ( tx_hash, rule_id, tx_type, token_id, subsidized_amount, subsidized_usd )
+                VALUES ( $1, $2, $3, $4, $5, $6 )
+                ON CONFLICT (tx_hash) DO NOTHING
+                "#,
+                tx_hash.as_ref(),
+                rule.id,
+                rule.tx_type,
+                i32::from(token_id),
+                subsidized_amount,
+                subsidized_usd,
+            )
+            .execute(transaction.conn())
+            .await?;
+        }
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.admin.reserve_subsidized_tx", start.elapsed());
+        Ok(reserved)
+    }
+
+    /// Charges the subsidy reserved for the executed transaction, if any.
+    pub(crate) async fn charge_subsidized_tx(&mut self, tx_hash: &TxHash) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            UPDATE subsidized_transactions SET executed_at = now()
+            WHERE tx_hash = $1 AND executed_at IS NULL
+            "#,
+            tx_hash.as_ref(),
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.admin.charge_subsidized_tx", start.elapsed());
+        Ok(())
+    }
+
+    /// Releases the subsidies reserved for the transactions which won't be executed.
+    /// Subsidies already charged are kept.
+    pub async fn release_subsidized_txs(&mut self, tx_hashes: &[TxHash]) -> QueryResult<()> {
+        let start = Instant::now();
+        let tx_hashes: Vec<_> = tx_hashes
+            .iter()
+            .map(|hash| hash.as_ref().to_vec())
+            .collect();
+        sqlx::query!(
+            r#"
+            DELETE FROM subsidized_transactions
+            WHERE tx_hash = ANY($1) AND executed_at IS NULL
+            "#,
+            &tx_hashes,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.admin.release_subsidized_txs", start.elapsed());
+        Ok(())
+    }
+
+    /// Returns `true` if the fee of the transaction was subsidized, or the subsidy is reserved for it.
+    pub async fn is_tx_subsidized(&mut self, tx_hash: &TxHash) -> QueryResult<bool> {
+        let start = Instant::now();
+        let subsidized = sqlx::query!(
+            "SELECT tx_hash FROM subsidized_transactions WHERE tx_hash = $1",
+            tx_hash.as_ref(),
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .is_some();
+
+        metrics::histogram!("sql.admin.is_tx_subsidized", start.elapsed());
+        Ok(subsidized)
+    }
+
+    /// Returns the total amount (in USD) subsidized under the rule since the given moment,
+    /// including the subsidies reserved for the pending transactions.
+    pub async fn subsidized_usd_since(
+        &mut self,
+        rule_id: i32,
+        since: DateTime<Utc>,
+    ) -> QueryResult<BigDecimal> {
+        let start = Instant::now();
+        let total = sqlx::query!(
+            r#"
+            SELECT COALESCE(SUM(subsidized_usd), 0) as "total!"
+            FROM subsidized_transactions
+            WHERE rule_id = $1
+                AND (executed_at >= $2 OR (executed_at IS NULL AND created_at >= $2))
+            "#,
+            rule_id,
+            since,
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .total;
+
+        metrics::histogram!("sql.admin.subsidized_usd_since", start.elapsed());
+        Ok(total)
+    }
+
+    /// Returns the fee subsidized for the transactions executed since the given moment
+    /// grouped by the rule and the token.
+    pub async fn load_subsidy_report(
+        &mut self,
+        since: DateTime<Utc>,
+    ) -> QueryResult<Vec<StorageSubsidyReport>> {
+        let start = Instant::now();
+        let report = sqlx::query_as!(
+            StorageSubsidyReport,
+            r#"
+            SELECT
+                rule_id, tx_type, token_id,
+                COUNT(*) as "tx_count!",
+                SUM(subsidized_amount) as "subsidized_amount!",
+                SUM(subsidized_usd) as "subsidized_usd!"
+            FROM subsidized_transactions
+            WHERE executed_at >= $1
+            GROUP BY rule_id, tx_type, token_id
+            ORDER BY rule_id ASC, token_id ASC
+            "#,
+            since,
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.admin.load_subsidy_report", start.elapsed());
+        Ok(report)
+    }
+}
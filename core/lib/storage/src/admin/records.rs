@@ -0,0 +1,54 @@
+// External imports
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{types::BigDecimal, FromRow};
+// Workspace imports
+// Local imports
+
+/// Runtime settings of a single token.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, FromRow)]
+pub struct StorageTokenSettings {
+    pub token_id: i32,
+    /// Whether the transactions with this token are rejected.
+    pub disabled: bool,
+    /// Overrides the server configuration on whether the token can be used to pay fees.
+    pub fee_allowed: Option<bool>,
+    /// Market liquidity tier of the token, `None` if not assessed.
+    pub liquidity_tier: Option<String>,
+}
+
+/// Rule under which the operator covers the fee of the transactions.
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct StorageSubsidyRule {
+    pub id: i32,
+    /// Fee type of the subsidized transactions, e.g. `Transfer`.
+    pub tx_type: String,
+    /// Token the fee is paid in, `None` matches any token.
+    pub token_id: Option<i32>,
+    /// Share (in percent) of the required fee the subsidy can cover.
+    pub subsidy_percent: i32,
+    /// Maximum amount (in USD) subsidized under the rule during a UTC day.
+    pub daily_budget_usd: BigDecimal,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Fee subsidized under a single rule in a single token.
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct StorageSubsidyReport {
+    pub rule_id: i32,
+    pub tx_type: String,
+    pub token_id: i32,
+    pub tx_count: i64,
+    /// Subsidized amount in the token units.
+    pub subsidized_amount: BigDecimal,
+    pub subsidized_usd: BigDecimal,
+}
+
+/// Request for `eth_sender` to requeue or drop the Ethereum operation.
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct StorageEthOperationRequest {
+    pub eth_op_id: i64,
+    /// Requested action, either `requeue` or `drop`.
+    pub action: String,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,382 @@
+// Built-in deps
+use std::{str::FromStr, time::Instant};
+// External imports
+use sqlx::types::BigDecimal;
+// Workspace imports
+use zksync_types::{
+    aggregated_operations::AggregatedActionType, AccountUpdate, Action, Operation, PubKeyHash,
+    TokenId, H256,
+};
+// Local imports
+use self::records::{
+    AggregatedOperationBackup, ConfirmedEthTxBackup, EthParametersBackup, RuntimeSettingsBackup,
+    StateBackup, StoredConfirmedEthTx, StoredTokenOverride, SubsidyRuleBackup, TokenOverrideBackup,
+};
+use crate::{
+    admin::{AdminSchema, MAINTENANCE_MODE_FLAG, TX_ACCEPTANCE_PAUSED_FLAG},
+    chain::{block::BlockSchema, operations::OperationsSchema, state::StateSchema},
+    data_restore::DataRestoreSchema,
+    ethereum::EthereumSchema,
+    tokens::TokensSchema,
+    QueryResult, StorageProcessor,
+};
+
+pub mod records;
+
+/// Server flags that represent the settings rather than the one-shot requests.
+const PERSISTENT_FLAGS: [&str; 2] = [TX_ACCEPTANCE_PAUSED_FLAG, MAINTENANCE_MODE_FLAG];
+
+/// Backup schema exports the server state at the last verified block and imports it
+/// into a fresh database.
+///
+/// Only the data that can't be changed anymore is exported: the mempool, the pending
+/// block and the blocks committed after the last verified one are not the part of
+/// the backup, thus the backup can only be created once all the committed blocks are verified.
+#[derive(Debug)]
+pub struct BackupSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> BackupSchema<'a, 'c> {
+    /// Exports the state at the last verified block.
+    ///
+    /// All the data is loaded within a single repeatable read transaction, so the
+    /// backup is consistent even if the server is running during the export.
+    pub async fn create_backup(&mut self) -> QueryResult<StateBackup> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        sqlx::query!("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ")
+            .execute(transaction.conn())
+            .await?;
+
+        let (block_number, accounts) = StateSchema(&mut transaction).load_verified_state().await?;
+        if block_number == 0 {
+            anyhow::bail!("There are no verified blocks to back up");
+        }
+        let last_committed = BlockSchema(&mut transaction)
+            .get_last_committed_block()
+            .await?;
+        if last_committed != block_number {
+            anyhow::bail!(
+                "Blocks up to #{} are committed, but only blocks up to #{} are verified; \
+                 backup can only be created once all the committed blocks are verified",
+                last_committed,
+                block_number
+            );
+        }
+
+        let block = BlockSchema(&mut transaction)
+            .get_block(block_number)
+            .await?
+            .ok_or_else(|| anyhow::format_err!("Block #{} is not stored", block_number))?;
+
+        let mut tokens: Vec<_> = TokensSchema(&mut transaction)
+            .load_tokens()
+            .await?
+            .into_iter()
+            .map(|(_, token)| token)
+            .collect();
+        tokens.sort_by_key(|token| token.id);
+
+        let mut accounts: Vec<_> = accounts.into_iter().collect();
+        accounts.sort_by_key(|(id, _)| *id);
+
+        let mut aggregated_operations = Vec::new();
+        for action_type in &[
+            AggregatedActionType::CommitBlocks,
+            AggregatedActionType::CreateProofBlocks,
+            AggregatedActionType::PublishProofBlocksOnchain,
+            AggregatedActionType::ExecuteBlocks,
+        ] {
+            let (op_id, operation) = match OperationsSchema(&mut transaction)
+                .get_aggregated_op_that_affects_block(*action_type, block_number)
+                .await?
+            {
+                Some(op) => op,
+                None => continue,
+            };
+
+            let eth_tx = sqlx::query_as!(
+                StoredConfirmedEthTx,
+                "SELECT eth_operations.nonce, eth_operations.final_hash FROM eth_aggregated_ops_binding
+                INNER JOIN eth_operations ON eth_operations.id = eth_aggregated_ops_binding.eth_op_id
+                WHERE eth_aggregated_ops_binding.op_id = $1 AND eth_operations.confirmed = true",
+                op_id
+            )
+            .fetch_optional(transaction.conn())
+            .await?
+            .and_then(|tx| {
+                tx.final_hash.map(|hash| ConfirmedEthTxBackup {
+                    nonce: tx.nonce,
+                    tx_hash: H256::from_slice(&hash),
+                })
+            });
+
+            aggregated_operations.push(AggregatedOperationBackup { operation, eth_tx });
+        }
+
+        let params = EthereumSchema(&mut transaction).load_eth_params().await?;
+        let eth_parameters = EthParametersBackup {
+            nonce: params.nonce,
+            gas_price_limit: params.gas_price_limit,
+            average_gas_price: params.average_gas_price,
+            commit_ops: params.commit_ops,
+            verify_ops: params.verify_ops,
+            withdraw_ops: params.withdraw_ops,
+        };
+
+        let token_overrides = sqlx::query_as!(
+            StoredTokenOverride,
+            "SELECT id, metadata_overridden, withdrawal_gas_limit FROM tokens
+            WHERE metadata_overridden OR withdrawal_gas_limit IS NOT NULL
+            ORDER BY id ASC"
+        )
+        .fetch_all(transaction.conn())
+        .await?
+        .into_iter()
+        .map(|token| TokenOverrideBackup {
+            token_id: token.id as TokenId,
+            metadata_overridden: token.metadata_overridden,
+            withdrawal_gas_limit: token.withdrawal_gas_limit.map(|gas_limit| gas_limit as u64),
+        })
+        .collect();
+
+        let runtime_settings = Self::load_runtime_settings(&mut transaction).await?;
+
+        transaction.commit().await?;
+        metrics::histogram!("sql.backup.create_backup", start.elapsed());
+        Ok(StateBackup {
+            block,
+            tokens,
+            accounts,
+            aggregated_operations,
+            eth_parameters,
+            token_overrides,
+            runtime_settings,
+        })
+    }
+
+    async fn load_runtime_settings(
+        storage: &mut StorageProcessor<'_>,
+    ) -> QueryResult<RuntimeSettingsBackup> {
+        let token_settings = AdminSchema(storage).load_token_settings().await?;
+
+        let mut enabled_flags = Vec::new();
+        for flag in &PERSISTENT_FLAGS {
+            if AdminSchema(storage).is_flag_enabled(flag).await? {
+                enabled_flags.push(flag.to_string());
+            }
+        }
+
+        let subsidy_rules = AdminSchema(storage)
+            .load_subsidy_rules()
+            .await?
+            .into_iter()
+            .map(|rule| SubsidyRuleBackup {
+                tx_type: rule.tx_type,
+                token_id: rule.token_id.map(|token_id| token_id as TokenId),
+                subsidy_percent: rule.subsidy_percent as u8,
+                daily_budget_usd: rule.daily_budget_usd.to_string(),
+            })
+            .collect();
+
+        Ok(RuntimeSettingsBackup {
+            token_settings,
+            enabled_flags,
+            subsidy_rules,
+        })
+    }
+
+    async fn restore_runtime_settings(
+        storage: &mut StorageProcessor<'_>,
+        settings: RuntimeSettingsBackup,
+    ) -> QueryResult<()> {
+        for token_settings in settings.token_settings {
+            sqlx::query!(
+                "INSERT INTO token_settings (token_id, disabled, fee_allowed, liquidity_tier)
+                VALUES ($1, $2, $3, $4)",
+                token_settings.token_id,
+                token_settings.disabled,
+                token_settings.fee_allowed,
+                token_settings.liquidity_tier,
+            )
+            .execute(storage.conn())
+            .await?;
+        }
+
+        for flag in settings.enabled_flags {
+            if !PERSISTENT_FLAGS.contains(&flag.as_str()) {
+                anyhow::bail!("Unexpected server flag in the backup: {}", flag);
+            }
+            AdminSchema(storage).set_flag(&flag, true).await?;
+        }
+
+        for rule in settings.subsidy_rules {
+            let daily_budget_usd = BigDecimal::from_str(&rule.daily_budget_usd)?;
+            AdminSchema(storage)
+                .add_subsidy_rule(
+                    &rule.tx_type,
+                    rule.token_id,
+                    rule.subsidy_percent,
+                    daily_budget_usd,
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Imports the backup into the database.
+    ///
+    /// The database is expected to be fresh (i.e. with migrations applied, but without the genesis block).
+    /// The block from the backup is stored as committed and verified, and its state is applied.
+    pub async fn restore_backup(&mut self, backup: StateBackup) -> QueryResult<()> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let last_committed = BlockSchema(&mut transaction)
+            .get_last_committed_block()
+            .await?;
+        let (_, existing_accounts) = StateSchema(&mut transaction)
+            .load_committed_state(None)
+            .await?;
+        if last_committed != 0 || !existing_accounts.is_empty() {
+            anyhow::bail!("Backup can only be restored into an empty database");
+        }
+
+        for token in backup.tokens {
+            TokensSchema(&mut transaction).store_token(token).await?;
+        }
+        for token in backup.token_overrides {
+            sqlx::query!(
+                "UPDATE tokens SET metadata_overridden = $2, withdrawal_gas_limit = $3
+                WHERE id = $1",
+                i32::from(token.token_id),
+                token.metadata_overridden,
+                token.withdrawal_gas_limit.map(|gas_limit| gas_limit as i64),
+            )
+            .execute(transaction.conn())
+            .await?;
+        }
+        Self::restore_runtime_settings(&mut transaction, backup.runtime_settings).await?;
+
+        let block_number = backup.block.block_number;
+        let mut updates = Vec::new();
+        for (id, account) in backup.accounts {
+            updates.push((
+                id,
+                AccountUpdate::Create {
+                    address: account.address,
+                    nonce: account.nonce,
+                },
+            ));
+            let mut balances: Vec<_> = account.get_nonzero_balances().into_iter().collect();
+            balances.sort_by_key(|(token, _)| *token);
+            for (token, balance) in balances {
+                updates.push((
+                    id,
+                    AccountUpdate::UpdateBalance {
+                        old_nonce: account.nonce,
+                        new_nonce: account.nonce,
+                        balance_update: (token, 0u32.into(), balance.0),
+                    },
+                ));
+            }
+            if account.pub_key_hash != PubKeyHash::default() {
+                updates.push((
+                    id,
+                    AccountUpdate::ChangePubKeyHash {
+                        old_pub_key_hash: PubKeyHash::default(),
+                        new_pub_key_hash: account.pub_key_hash,
+                        old_nonce: account.nonce,
+                        new_nonce: account.nonce,
+                    },
+                ));
+            }
+        }
+        StateSchema(&mut transaction)
+            .commit_state_update(block_number, &updates, 0)
+            .await?;
+
+        // The block is stored the same way as the blocks restored from the Ethereum.
+        let commit_op = Operation {
+            id: None,
+            action: Action::Commit,
+            block: backup.block.clone(),
+        };
+        let verify_op = Operation {
+            id: None,
+            action: Action::Verify {
+                proof: Box::new(Default::default()),
+            },
+            block: backup.block,
+        };
+        DataRestoreSchema(&mut transaction)
+            .save_block_operations(commit_op, verify_op)
+            .await?;
+
+        for aggregated_op in backup.aggregated_operations {
+            let action_type = aggregated_op.operation.get_action_type();
+            let (from_block, to_block) = aggregated_op.operation.get_block_range();
+            let op_id = sqlx::query!(
+                "INSERT INTO aggregate_operations (action_type, arguments, from_block, to_block)
+                VALUES ($1, $2, $3, $4)
+                RETURNING id",
+                action_type.to_string(),
+                serde_json::to_value(aggregated_op.operation)
+                    .expect("aggregated op serialize fail"),
+                i64::from(from_block),
+                i64::from(to_block)
+            )
+            .fetch_one(transaction.conn())
+            .await?
+            .id;
+
+            if let Some(eth_tx) = aggregated_op.eth_tx {
+                // Raw transaction is not needed for the confirmed operation, it won't be resent.
+                let eth_op_id = sqlx::query!(
+                    "INSERT INTO eth_operations (op_type, nonce, confirmed, raw_tx, last_deadline_block, last_used_gas_price, final_hash, confirmed_at)
+                    VALUES ($1, $2, true, $3, 0, 0, $4, now())
+                    RETURNING id",
+                    action_type.to_string(),
+                    eth_tx.nonce,
+                    Vec::<u8>::new(),
+                    eth_tx.tx_hash.as_bytes()
+                )
+                .fetch_one(transaction.conn())
+                .await?
+                .id;
+
+                sqlx::query!(
+                    "INSERT INTO eth_aggregated_ops_binding (op_id, eth_op_id) VALUES ($1, $2)",
+                    op_id,
+                    eth_op_id
+                )
+                .execute(transaction.conn())
+                .await?;
+                EthereumSchema(&mut transaction)
+                    .add_hash_entry(eth_op_id, &eth_tx.tx_hash)
+                    .await?;
+            }
+        }
+
+        let params = backup.eth_parameters;
+        EthereumSchema(&mut transaction)
+            .initialize_eth_data()
+            .await?;
+        sqlx::query!(
+            "UPDATE eth_parameters
+            SET nonce = $1, gas_price_limit = $2, average_gas_price = $3, commit_ops = $4, verify_ops = $5, withdraw_ops = $6
+            WHERE id = true",
+            params.nonce,
+            params.gas_price_limit,
+            params.average_gas_price,
+            params.commit_ops,
+            params.verify_ops,
+            params.withdraw_ops
+        )
+        .execute(transaction.conn())
+        .await?;
+
+        transaction.commit().await?;
+        metrics::histogram!("sql.backup.restore_backup", start.elapsed());
+        Ok(())
+    }
+}
@@ -0,0 +1,100 @@
+// External imports
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+// Workspace imports
+use zksync_types::{
+    aggregated_operations::AggregatedOperation, block::Block, Account, AccountId, Token, TokenId,
+    H256,
+};
+// Local imports
+use crate::admin::records::StorageTokenSettings;
+
+/// Consistent snapshot of the server state at the last verified block.
+///
+/// Blocks are not stored one by one: the snapshot contains the header of the
+/// last verified block and the account state after it, which is enough for
+/// the server to continue producing blocks on top of it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateBackup {
+    /// The last verified block.
+    pub block: Block,
+    /// All the known tokens.
+    pub tokens: Vec<Token>,
+    /// Verified state of the accounts.
+    pub accounts: Vec<(AccountId, Account)>,
+    /// Aggregated operations that affect the last verified block.
+    pub aggregated_operations: Vec<AggregatedOperationBackup>,
+    /// State of the `eth_sender`.
+    pub eth_parameters: EthParametersBackup,
+    /// Token metadata and withdrawal gas limits set through the admin API.
+    pub token_overrides: Vec<TokenOverrideBackup>,
+    /// Runtime settings changed through the admin API.
+    pub runtime_settings: RuntimeSettingsBackup,
+}
+
+/// Aggregated operation along with the Ethereum transaction that has executed it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AggregatedOperationBackup {
+    pub operation: AggregatedOperation,
+    /// Confirmed Ethereum transaction. Operations that are not sent to
+    /// Ethereum (e.g. proof creation) don't have one.
+    pub eth_tx: Option<ConfirmedEthTxBackup>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfirmedEthTxBackup {
+    pub nonce: i64,
+    pub tx_hash: H256,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EthParametersBackup {
+    pub nonce: i64,
+    pub gas_price_limit: i64,
+    pub average_gas_price: Option<i64>,
+    pub commit_ops: i64,
+    pub verify_ops: i64,
+    pub withdraw_ops: i64,
+}
+
+/// Token properties that are not the part of the `Token` itself.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenOverrideBackup {
+    pub token_id: TokenId,
+    /// Whether the symbol and decimals of the token were set manually.
+    pub metadata_overridden: bool,
+    pub withdrawal_gas_limit: Option<u64>,
+}
+
+/// Settings of the server that are changed without the restart.
+///
+/// One-shot requests to the server (e.g. the blocks revert) are not the part of the backup.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RuntimeSettingsBackup {
+    pub token_settings: Vec<StorageTokenSettings>,
+    /// Names of the enabled persistent server flags.
+    pub enabled_flags: Vec<String>,
+    pub subsidy_rules: Vec<SubsidyRuleBackup>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubsidyRuleBackup {
+    pub tx_type: String,
+    pub token_id: Option<TokenId>,
+    pub subsidy_percent: u8,
+    /// Daily budget in USD, as a decimal string.
+    pub daily_budget_usd: String,
+}
+
+#[derive(Debug, FromRow)]
+pub(crate) struct StoredTokenOverride {
+    pub id: i32,
+    pub metadata_overridden: bool,
+    pub withdrawal_gas_limit: Option<i64>,
+}
+
+#[derive(Debug, FromRow)]
+pub(crate) struct StoredConfirmedEthTx {
+    pub nonce: i64,
+    pub final_hash: Option<Vec<u8>>,
+}
@@ -1,9 +1,9 @@
 // Built-in deps
-use std::time::Instant;
+use std::{collections::HashMap, time::Instant};
 // External imports
 use sqlx::Acquire;
 // Workspace imports
-use zksync_types::{Account, AccountId, AccountUpdates, Address};
+use zksync_types::{Account, AccountId, AccountUpdate, AccountUpdates, Address};
 // Local imports
 use self::records::*;
 use crate::diff::StorageAccountDiff;
@@ -69,6 +69,143 @@ impl<'a, 'c> AccountSchema<'a, 'c> {
         account_state
     }
 
+    /// Obtains both committed and verified states for the accounts by their IDs.
+    ///
+    /// Unlike `account_state_by_id`, the states of all the accounts are loaded with
+    /// the fixed amount of queries, so it's suitable for the bulk requests.
+    pub async fn account_states_by_ids(
+        &mut self,
+        account_ids: &[AccountId],
+    ) -> QueryResult<HashMap<AccountId, StoredAccountState>> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        let ids: Vec<i64> = account_ids.iter().copied().map(i64::from).collect();
+
+        // Verified states are stored in the `accounts` table.
+        let stored_accounts = sqlx::query_as!(
+            StorageAccount,
+            "SELECT * FROM accounts WHERE id = ANY($1)",
+            &ids
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+        let balances = sqlx::query_as!(
+            StorageBalance,
+            "SELECT * FROM balances WHERE account_id = ANY($1)",
+            &ids
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+
+        let mut balances_for_id: HashMap<AccountId, Vec<StorageBalance>> = HashMap::new();
+        for balance in balances {
+            balances_for_id
+                .entry(balance.account_id as AccountId)
+                .or_default()
+                .push(balance);
+        }
+        let mut verified: HashMap<AccountId, Account> = stored_accounts
+            .iter()
+            .map(|stored_account| {
+                let balances = balances_for_id
+                    .remove(&(stored_account.id as AccountId))
+                    .unwrap_or_default();
+                restore_account(stored_account, balances)
+            })
+            .collect();
+
+        // Committed states are obtained by applying the updates made after the
+        // last verified update of each account.
+        let account_balance_diff = sqlx::query_as!(
+            StorageAccountUpdate,
+            "
+                SELECT account_balance_updates.* FROM account_balance_updates
+                LEFT JOIN accounts ON accounts.id = account_balance_updates.account_id
+                WHERE account_balance_updates.account_id = ANY($1)
+                    AND account_balance_updates.block_number > COALESCE(accounts.last_block, 0)
+            ",
+            &ids
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+
+        let account_creation_diff = sqlx::query_as!(
+            StorageAccountCreation,
+            "
+                SELECT account_creates.* FROM account_creates
+                LEFT JOIN accounts ON accounts.id = account_creates.account_id
+                WHERE account_creates.account_id = ANY($1)
+                    AND account_creates.block_number > COALESCE(accounts.last_block, 0)
+            ",
+            &ids
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+
+        let account_pubkey_diff = sqlx::query_as!(
+            StorageAccountPubkeyUpdate,
+            "
+                SELECT account_pubkey_updates.* FROM account_pubkey_updates
+                LEFT JOIN accounts ON accounts.id = account_pubkey_updates.account_id
+                WHERE account_pubkey_updates.account_id = ANY($1)
+                    AND account_pubkey_updates.block_number > COALESCE(accounts.last_block, 0)
+            ",
+            &ids
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+
+        let mut account_diff = Vec::new();
+        account_diff.extend(
+            account_balance_diff
+                .into_iter()
+                .map(StorageAccountDiff::from),
+        );
+        account_diff.extend(
+            account_creation_diff
+                .into_iter()
+                .map(StorageAccountDiff::from),
+        );
+        account_diff.extend(
+            account_pubkey_diff
+                .into_iter()
+                .map(StorageAccountDiff::from),
+        );
+        account_diff.sort_by(StorageAccountDiff::cmp_order);
+
+        let mut updates_for_id: HashMap<AccountId, Vec<AccountUpdate>> = HashMap::new();
+        for (account_id, update) in account_diff
+            .into_iter()
+            .map(Into::into)
+            .collect::<AccountUpdates>()
+        {
+            updates_for_id.entry(account_id).or_default().push(update);
+        }
+
+        transaction.commit().await?;
+
+        let states = account_ids
+            .iter()
+            .map(|&account_id| {
+                let verified = verified.remove(&account_id);
+                let committed = updates_for_id
+                    .remove(&account_id)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .fold(verified.clone(), Account::apply_update);
+
+                let state = StoredAccountState {
+                    committed: committed.map(|account| (account_id, account)),
+                    verified: verified.map(|account| (account_id, account)),
+                };
+                (account_id, state)
+            })
+            .collect();
+
+        metrics::histogram!("sql.chain.account.account_states_by_ids", start.elapsed());
+        Ok(states)
+    }
+
     /// Loads the last committed (e.g. just added but no necessarily verified) state for
     /// account given its ID.
     pub async fn last_committed_state_for_account(
@@ -249,6 +386,45 @@ impl<'a, 'c> AccountSchema<'a, 'c> {
         Ok(account_id)
     }
 
+    /// Resolves the addresses to the account IDs with a single query.
+    /// Addresses without an account are absent in the result.
+    pub async fn account_ids_by_addresses(
+        &mut self,
+        addresses: &[Address],
+    ) -> QueryResult<HashMap<Address, AccountId>> {
+        let start = Instant::now();
+        let addresses: Vec<_> = addresses
+            .iter()
+            .map(|address| address.as_bytes().to_vec())
+            .collect();
+
+        let records = sqlx::query!(
+            r#"
+                SELECT DISTINCT ON (address) address, account_id FROM account_creates
+                WHERE address = ANY($1) AND is_create = true
+                ORDER BY address, block_number DESC
+            "#,
+            &addresses
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        let account_ids = records
+            .into_iter()
+            .map(|record| {
+                (
+                    Address::from_slice(&record.address),
+                    record.account_id as AccountId,
+                )
+            })
+            .collect();
+        metrics::histogram!(
+            "sql.chain.account.account_ids_by_addresses",
+            start.elapsed()
+        );
+        Ok(account_ids)
+    }
+
     pub async fn account_address_by_id(
         &mut self,
         account_id: AccountId,
@@ -67,6 +67,8 @@ impl StoredExecutedTransaction {
             success: self.success,
             op: franklin_op,
             fail_reason: self.fail_reason,
+            // Failure traces are stored separately and are only loaded on request.
+            fail_trace: None,
             block_index: self
                 .block_index
                 .map(|val| u32::try_from(val).expect("Invalid block index")),
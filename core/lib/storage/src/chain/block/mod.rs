@@ -4,16 +4,18 @@ use std::time::Instant;
 use zksync_basic_types::{H256, U256};
 // Workspace imports
 use zksync_crypto::convert::FeConvert;
-use zksync_types::{block::PendingBlock, Action, ActionType, Fr, Operation};
 use zksync_types::{
+    aggregated_operations::AggregatedActionType,
     block::{Block, ExecutedOperations},
     AccountId, BlockNumber, ZkSyncOp,
 };
+use zksync_types::{block::PendingBlock, Action, ActionType, Fr, Operation};
 // Local imports
 use self::records::{
     AccountTreeCache, BlockDetails, BlockTransactionItem, StorageBlock, StoragePendingBlock,
 };
 use crate::{
+    chain::mempool::MempoolSchema,
     chain::operations::{
         records::{
             NewExecutedPriorityOperation, NewExecutedTransaction, NewOperation,
@@ -21,6 +23,9 @@ use crate::{
         },
         OperationsSchema,
     },
+    event_journal::{records::NewJournalEvent, EventJournalSchema},
+    tx_traces::TxTracesSchema,
+    withdrawals::{records::NewWithdrawal, WithdrawalsSchema},
     QueryResult, StorageProcessor,
 };
 
@@ -75,12 +80,28 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         for block_tx in operations.into_iter() {
             match block_tx {
                 ExecutedOperations::Tx(tx) => {
+                    if let Some(withdrawal) = NewWithdrawal::from_executed_tx(&tx, block_number) {
+                        WithdrawalsSchema(self.0)
+                            .store_withdrawal(withdrawal)
+                            .await?;
+                    }
+                    if let Some(trace) = &tx.fail_trace {
+                        TxTracesSchema(self.0)
+                            .store_failure_trace(&tx.signed_tx.hash(), block_number, trace)
+                            .await?;
+                    }
                     // Store the executed operation in the corresponding schema.
                     let new_tx = NewExecutedTransaction::prepare_stored_tx(*tx, block_number);
                     OperationsSchema(self.0).store_executed_tx(new_tx).await?;
                 }
                 ExecutedOperations::PriorityOp(prior_op) => {
-                    // For priority operation we should only store it in the Operations schema.
+                    if let Some(withdrawal) =
+                        NewWithdrawal::from_executed_priority_op(&prior_op, block_number)
+                    {
+                        WithdrawalsSchema(self.0)
+                            .store_withdrawal(withdrawal)
+                            .await?;
+                    }
                     let new_priority_op = NewExecutedPriorityOperation::prepare_stored_priority_op(
                         *prior_op,
                         block_number,
@@ -170,6 +191,17 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
     pub async fn get_block_transactions(
         &mut self,
         block: BlockNumber,
+    ) -> QueryResult<Vec<BlockTransactionItem>> {
+        self.get_block_transactions_page(block, 0, None).await
+    }
+
+    /// Loads up to `limit` transactions of the block (or all of them if `limit` is `None`)
+    /// in the same order as `get_block_transactions`, skipping the first `offset` ones.
+    pub async fn get_block_transactions_page(
+        &mut self,
+        block: BlockNumber,
+        offset: u64,
+        limit: Option<u32>,
     ) -> QueryResult<Vec<BlockTransactionItem>> {
         let start = Instant::now();
         let block_txs = sqlx::query_as!(
@@ -208,14 +240,21 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
                     fail_reason as "fail_reason?",
                     created_at as "created_at!"
                 FROM everything
-                ORDER BY created_at DESC
+                ORDER BY created_at DESC, tx_hash
+                OFFSET $2
+                LIMIT $3
             "#,
-            i64::from(block)
+            i64::from(block),
+            offset as i64,
+            limit.map(i64::from),
         )
         .fetch_all(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.chain.block.get_block_transactions", start.elapsed());
+        metrics::histogram!(
+            "sql.chain.block.get_block_transactions_page",
+            start.elapsed()
+        );
         Ok(block_txs)
     }
 
@@ -338,6 +377,55 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         Ok(details)
     }
 
+    /// Same as `load_block_range`, but loads the details of the blocks with the given numbers.
+    /// Blocks which are not committed yet are omitted.
+    pub async fn load_blocks_details(
+        &mut self,
+        block_numbers: &[BlockNumber],
+    ) -> QueryResult<Vec<BlockDetails>> {
+        let start = Instant::now();
+        let block_numbers: Vec<i64> = block_numbers.iter().copied().map(i64::from).collect();
+        let details = sqlx::query_as!(
+            BlockDetails,
+            r#"
+            WITH eth_ops AS (
+                SELECT DISTINCT ON (block_number, action_type)
+                    operations.block_number,
+                    eth_tx_hashes.tx_hash,
+                    operations.action_type,
+                    operations.created_at,
+                    confirmed
+                FROM operations
+                    left join eth_ops_binding on eth_ops_binding.op_id = operations.id
+                    left join eth_tx_hashes on eth_tx_hashes.eth_op_id = eth_ops_binding.eth_op_id
+                WHERE operations.block_number = ANY($1)
+                ORDER BY block_number DESC, action_type, confirmed
+            )
+            SELECT
+                blocks.number AS "block_number!",
+                blocks.root_hash AS "new_state_root!",
+                blocks.block_size AS "block_size!",
+                committed.tx_hash AS "commit_tx_hash?",
+                verified.tx_hash AS "verify_tx_hash?",
+                committed.created_at AS "committed_at!",
+                verified.created_at AS "verified_at?"
+            FROM blocks
+            INNER JOIN eth_ops committed ON
+                committed.block_number = blocks.number AND committed.action_type = 'COMMIT' AND committed.confirmed = true
+            LEFT JOIN eth_ops verified ON
+                verified.block_number = blocks.number AND verified.action_type = 'VERIFY' AND verified.confirmed = true
+            WHERE
+                blocks.number = ANY($1)
+            ORDER BY blocks.number DESC;
+            "#,
+            &block_numbers
+        ).fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.chain.block.load_blocks_details", start.elapsed());
+        Ok(details)
+    }
+
     /// Helper method for `find_block_by_height_or_hash`. It checks whether
     /// provided string can be interpreted like a hash, and if so, returns the
     /// hexadecimal string without prefix.
@@ -505,6 +593,22 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         result
     }
 
+    /// Returns the timestamp of the last sealed block, if it's known.
+    pub async fn get_last_block_timestamp(&mut self) -> QueryResult<Option<u64>> {
+        let start = Instant::now();
+        let timestamp = sqlx::query!(
+            "SELECT timestamp FROM blocks
+            ORDER BY number DESC
+            LIMIT 1"
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .and_then(|block| block.timestamp);
+        metrics::histogram!("sql.chain.block.get_last_block_timestamp", start.elapsed());
+
+        Ok(timestamp.map(|timestamp| timestamp as u64))
+    }
+
     async fn load_storage_pending_block(&mut self) -> QueryResult<Option<StoragePendingBlock>> {
         let start = Instant::now();
         let maybe_block = sqlx::query_as!(
@@ -654,6 +758,16 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         let commitment = block.block_commitment.as_bytes().to_vec();
         let timestamp = Some(block.timestamp as i64);
 
+        let mut events = vec![NewJournalEvent::block_sealed(&block)];
+        events.extend(block.block_transactions.iter().filter_map(|op| match op {
+            ExecutedOperations::Tx(tx) => {
+                Some(NewJournalEvent::tx_executed(tx, block.block_number))
+            }
+            ExecutedOperations::PriorityOp(op) => {
+                NewJournalEvent::deposit_processed(op, block.block_number)
+            }
+        }));
+
         BlockSchema(&mut transaction)
             .save_block_transactions(block.block_number, block.block_transactions)
             .await?;
@@ -692,12 +806,180 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         ).execute(transaction.conn())
         .await?;
 
+        EventJournalSchema(&mut transaction)
+            .append_events(&events)
+            .await?;
+
         transaction.commit().await?;
 
         metrics::histogram!("sql.chain.block.save_block", start.elapsed());
         Ok(())
     }
 
+    /// Removes all the blocks after `last_block` along with their operations, state updates
+    /// and proofs, so the chain continues from `last_block` as if these blocks were never created.
+    ///
+    /// Successfully executed transactions of the removed blocks are returned to the mempool.
+    /// Priority operations are not restored, since they will be received from Ethereum once again.
+    /// This method is meant to be used only after these blocks were reverted on the contract.
+    ///
+    /// Aggregated operations confirmed on Ethereum are never changed: the revert is refused if
+    /// any of the removed blocks is executed on Ethereum, or if a confirmed operation covers
+    /// both the remaining and the removed blocks.
+    pub async fn revert_blocks(&mut self, last_block: BlockNumber) -> QueryResult<()> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let confirmed_op = sqlx::query!(
+            "SELECT aggregate_operations.action_type, aggregate_operations.from_block, aggregate_operations.to_block
+            FROM aggregate_operations
+            INNER JOIN eth_aggregated_ops_binding ON eth_aggregated_ops_binding.op_id = aggregate_operations.id
+            INNER JOIN eth_operations ON eth_operations.id = eth_aggregated_ops_binding.eth_op_id
+            WHERE eth_operations.confirmed = true AND aggregate_operations.to_block > $1
+                AND (aggregate_operations.from_block <= $1 OR aggregate_operations.action_type = $2)
+            ORDER BY aggregate_operations.to_block DESC
+            LIMIT 1",
+            i64::from(last_block),
+            AggregatedActionType::ExecuteBlocks.to_string()
+        )
+        .fetch_optional(transaction.conn())
+        .await?;
+        if let Some(op) = confirmed_op {
+            anyhow::bail!(
+                "Blocks after #{} can't be reverted: {} operation for the blocks {}..={} is confirmed on Ethereum",
+                last_block,
+                op.action_type,
+                op.from_block,
+                op.to_block
+            );
+        }
+
+        MempoolSchema(&mut transaction)
+            .return_executed_txs(last_block)
+            .await?;
+
+        let last_block = i64::from(last_block);
+
+        sqlx::query!(
+            "DELETE FROM executed_transactions WHERE block_number > $1",
+            last_block
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!(
+            "DELETE FROM executed_priority_operations WHERE block_number > $1",
+            last_block
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!(
+            "DELETE FROM withdrawals WHERE block_number > $1",
+            last_block
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!(
+            "DELETE FROM tx_failure_traces WHERE block_number > $1",
+            last_block
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!(
+            "DELETE FROM block_accounting WHERE block_number > $1",
+            last_block
+        )
+        .execute(transaction.conn())
+        .await?;
+
+        // Committed account states are calculated from the updates, so removing them
+        // restores the state of the last remaining block.
+        sqlx::query!(
+            "DELETE FROM account_balance_updates WHERE block_number > $1",
+            last_block
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!(
+            "DELETE FROM account_creates WHERE block_number > $1",
+            last_block
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!(
+            "DELETE FROM account_pubkey_updates WHERE block_number > $1",
+            last_block
+        )
+        .execute(transaction.conn())
+        .await?;
+
+        sqlx::query!("DELETE FROM proofs WHERE block_number > $1", last_block)
+            .execute(transaction.conn())
+            .await?;
+        sqlx::query!(
+            "DELETE FROM aggregated_proofs WHERE last_block > $1",
+            last_block
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!(
+            "DELETE FROM prover_job_queue WHERE last_block > $1",
+            last_block
+        )
+        .execute(transaction.conn())
+        .await?;
+
+        sqlx::query!(
+            "DELETE FROM eth_ops_binding
+            WHERE op_id IN (SELECT id FROM operations WHERE block_number > $1)",
+            last_block
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!("DELETE FROM operations WHERE block_number > $1", last_block)
+            .execute(transaction.conn())
+            .await?;
+
+        // Operations of the removed blocks are created and sent once again. Unconfirmed
+        // operations that also cover the remaining blocks are only truncated, so the aggregator
+        // won't create them once again (confirmed ones are refused above).
+        sqlx::query!(
+            "DELETE FROM eth_aggregated_ops_binding
+            WHERE op_id IN (SELECT id FROM aggregate_operations WHERE from_block > $1)",
+            last_block
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!(
+            "DELETE FROM aggregate_operations WHERE from_block > $1",
+            last_block
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!(
+            "UPDATE aggregate_operations SET to_block = $1 WHERE to_block > $1",
+            last_block
+        )
+        .execute(transaction.conn())
+        .await?;
+
+        sqlx::query!("DELETE FROM pending_block WHERE number > $1", last_block)
+            .execute(transaction.conn())
+            .await?;
+        // Block witnesses and account tree caches are removed along with the blocks.
+        sqlx::query!("DELETE FROM blocks WHERE number > $1", last_block)
+            .execute(transaction.conn())
+            .await?;
+
+        EventJournalSchema(&mut transaction)
+            .append_events(&[NewJournalEvent::blocks_reverted(last_block as BlockNumber)])
+            .await?;
+
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.chain.block.revert_blocks", start.elapsed());
+        Ok(())
+    }
+
     /// Stores account tree cache for a block
     pub async fn store_account_tree_cache(
         &mut self,
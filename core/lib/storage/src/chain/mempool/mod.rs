@@ -6,11 +6,11 @@ use itertools::Itertools;
 use zksync_types::{
     mempool::SignedTxVariant,
     tx::{TxEthSignature, TxHash},
-    SignedZkSyncTx,
+    BlockNumber, SignedZkSyncTx, TokenId,
 };
 // Local imports
-use self::records::MempoolTx;
-use crate::{QueryResult, StorageProcessor};
+use self::records::{MempoolStats, MempoolTx, MempoolTxPosition};
+use crate::{admin::AdminSchema, QueryResult, StorageProcessor};
 
 pub mod records;
 
@@ -260,8 +260,11 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         Ok(())
     }
 
+    /// Removes the transactions from the mempool. Fee subsidies reserved for the transactions
+    /// which weren't executed are released.
     pub async fn remove_txs(&mut self, txs: &[TxHash]) -> QueryResult<()> {
         let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
         let tx_hashes: Vec<_> = txs.iter().map(hex::encode).collect();
 
         sqlx::query!(
@@ -269,13 +272,39 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
             WHERE tx_hash = ANY($1)",
             &tx_hashes
         )
-        .execute(self.0.conn())
+        .execute(transaction.conn())
         .await?;
+        AdminSchema(&mut transaction)
+            .release_subsidized_txs(txs)
+            .await?;
+        transaction.commit().await?;
 
         metrics::histogram!("sql.chain.mempool.remove_txs", start.elapsed());
         Ok(())
     }
 
+    /// Puts the successfully executed transactions of the blocks after `last_block` back
+    /// to the mempool, so they will be executed again once these blocks are reverted.
+    ///
+    /// Batched transactions keep their batch ID, so the stored batch signatures remain valid.
+    pub async fn return_executed_txs(&mut self, last_block: BlockNumber) -> QueryResult<()> {
+        let start = Instant::now();
+
+        sqlx::query!(
+            "INSERT INTO mempool_txs (tx_hash, tx, created_at, eth_sign_data, batch_id)
+            SELECT encode(tx_hash, 'hex'), tx, created_at, eth_sign_data, COALESCE(batch_id, 0)
+            FROM executed_transactions
+            WHERE block_number > $1 AND success = true
+            ORDER BY block_number, block_index",
+            i64::from(last_block)
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.chain.mempool.return_executed_txs", start.elapsed());
+        Ok(())
+    }
+
     /// Checks if the memory pool contains transaction with the given hash.
     pub async fn contains_tx(&mut self, tx_hash: TxHash) -> QueryResult<bool> {
         let start = Instant::now();
@@ -319,6 +348,77 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
             .map_err(anyhow::Error::from)
     }
 
+    /// Returns the aggregated information about the pending transactions.
+    pub async fn get_stats(&mut self) -> QueryResult<MempoolStats> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let stats = sqlx::query!(
+            r#"SELECT COUNT(*) as "txs_count!", COUNT(DISTINCT NULLIF(batch_id, 0)) as "batches_count!", MIN(created_at) as oldest_created_at
+            FROM mempool_txs"#
+        )
+        .fetch_one(transaction.conn())
+        .await?;
+
+        // Transactions which don't have fees (e.g. `Close`) are not taken into account.
+        let txs_by_fee_token = sqlx::query!(
+            r#"SELECT (COALESCE(tx->>'feeToken', tx->>'token'))::integer as "token_id!", COUNT(*) as "count!"
+            FROM mempool_txs
+            WHERE COALESCE(tx->>'feeToken', tx->>'token') IS NOT NULL
+            GROUP BY 1
+            ORDER BY 1"#
+        )
+        .fetch_all(transaction.conn())
+        .await?
+        .into_iter()
+        .map(|row| (row.token_id as TokenId, row.count))
+        .collect();
+
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "get_stats");
+        Ok(MempoolStats {
+            txs_count: stats.txs_count,
+            batches_count: stats.batches_count,
+            oldest_tx_created_at: stats.oldest_created_at,
+            txs_by_fee_token,
+        })
+    }
+
+    /// Returns the pending transaction with the given hash along with its position in the queue.
+    /// Position is determined by the order in which transactions were received.
+    pub async fn get_tx_position(
+        &mut self,
+        tx_hash: TxHash,
+    ) -> QueryResult<Option<MempoolTxPosition>> {
+        let start = Instant::now();
+
+        let tx_hash = hex::encode(tx_hash.as_ref());
+
+        let row = sqlx::query!(
+            r#"SELECT tx, created_at, (
+                SELECT COUNT(*) FROM mempool_txs AS ahead WHERE ahead.id < mempool_txs.id
+            ) as "txs_ahead!"
+            FROM mempool_txs
+            WHERE tx_hash = $1"#,
+            &tx_hash
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        let position = match row {
+            Some(row) => Some(MempoolTxPosition {
+                tx: serde_json::from_value(row.tx)?,
+                created_at: row.created_at,
+                txs_ahead: row.txs_ahead,
+            }),
+            None => None,
+        };
+
+        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "get_tx_position");
+        Ok(position)
+    }
+
     /// Removes transactions that are already committed.
     /// Though it's unlikely that mempool schema will ever contain a committed
     /// transaction, it's better to ensure that we won't process the same transaction
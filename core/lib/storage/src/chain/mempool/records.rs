@@ -6,7 +6,7 @@ use chrono::{DateTime, Utc};
 use sqlx::FromRow;
 
 // Workspace imports
-use zksync_types::SignedZkSyncTx;
+use zksync_types::{SignedZkSyncTx, TokenId, ZkSyncTx};
 
 // Local imports
 
@@ -20,6 +20,28 @@ pub struct MempoolTx {
     pub batch_id: i64,
 }
 
+/// Aggregated information about the transactions stored in the mempool schema.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MempoolStats {
+    /// Amount of the pending transactions, with every transaction of a batch counted separately.
+    pub txs_count: i64,
+    /// Amount of the pending transaction batches.
+    pub batches_count: i64,
+    /// Time the oldest pending transaction was received at.
+    pub oldest_tx_created_at: Option<DateTime<Utc>>,
+    /// Amount of the pending transactions paying fee in each token, ordered by token ID.
+    pub txs_by_fee_token: Vec<(TokenId, i64)>,
+}
+
+/// Pending transaction along with its position in the mempool queue.
+#[derive(Debug, Clone)]
+pub struct MempoolTxPosition {
+    pub tx: ZkSyncTx,
+    pub created_at: DateTime<Utc>,
+    /// Amount of the pending transactions received before this one.
+    pub txs_ahead: i64,
+}
+
 impl TryFrom<MempoolTx> for SignedZkSyncTx {
     type Error = serde_json::Error;
 
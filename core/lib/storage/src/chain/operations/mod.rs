@@ -1,17 +1,20 @@
 // Built-in deps
 use std::time::Instant;
 // External imports
+use chrono::{DateTime, Utc};
+use num::BigInt;
+use sqlx::types::BigDecimal;
 // Workspace imports
-use zksync_types::{tx::TxHash, ActionType, BlockNumber};
+use zksync_types::{tx::TxHash, ActionType, BlockNumber, PriorityOp, ZkSyncPriorityOp};
 // Local imports
 use self::records::{
     NewExecutedPriorityOperation, NewExecutedTransaction, NewOperation, StoredAggregatedOperation,
-    StoredExecutedPriorityOperation, StoredOperation,
+    StoredExecutedPriorityOperation, StoredOperation, StoredPriorityOpAcknowledgment,
 };
 use crate::chain::operations::records::StoredExecutedTransaction;
 use crate::chain::operations_ext::OperationsExtSchema;
 use crate::ethereum::EthereumSchema;
-use crate::{chain::mempool::MempoolSchema, QueryResult, StorageProcessor};
+use crate::{admin::AdminSchema, chain::mempool::MempoolSchema, QueryResult, StorageProcessor};
 use zksync_basic_types::H256;
 use zksync_types::aggregated_operations::{AggregatedActionType, AggregatedOperation};
 
@@ -128,6 +131,29 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         Ok(op)
     }
 
+    /// Loads all the executed priority operations requested by the Ethereum transaction,
+    /// ordered by their serial ids.
+    pub async fn get_executed_priority_operations_by_hash(
+        &mut self,
+        eth_hash: &[u8],
+    ) -> QueryResult<Vec<StoredExecutedPriorityOperation>> {
+        let start = Instant::now();
+        let ops = sqlx::query_as!(
+            StoredExecutedPriorityOperation,
+            "SELECT * FROM executed_priority_operations WHERE eth_hash = $1
+            ORDER BY priority_op_serialid",
+            eth_hash
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.chain.operations.get_executed_priority_operations_by_hash",
+            start.elapsed()
+        );
+        Ok(ops)
+    }
+
     pub(crate) async fn store_operation(
         &mut self,
         operation: NewOperation,
@@ -256,6 +282,19 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
             .await?;
         };
 
+        // Fee subsidy reserved for the transaction is charged only if it's executed successfully.
+        if let Some(tx_hash) = TxHash::from_slice(&operation.tx_hash) {
+            if operation.success {
+                AdminSchema(&mut transaction)
+                    .charge_subsidized_tx(&tx_hash)
+                    .await?;
+            } else {
+                AdminSchema(&mut transaction)
+                    .release_subsidized_txs(&[tx_hash])
+                    .await?;
+            }
+        }
+
         transaction.commit().await?;
         metrics::histogram!("sql.chain.operations.store_executed_tx", start.elapsed());
         Ok(())
@@ -349,6 +388,26 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         Ok(())
     }
 
+    /// Returns the amount of the stored aggregated operations which are not sent to Ethereum yet.
+    pub async fn count_unsent_aggregated_operations(&mut self) -> QueryResult<i64> {
+        let start = Instant::now();
+        let count = sqlx::query!(
+            r#"SELECT COUNT(*) AS "count!" FROM aggregate_operations
+            WHERE action_type != $1
+                AND NOT EXISTS (SELECT 1 FROM eth_aggregated_ops_binding WHERE op_id = aggregate_operations.id)"#,
+            AggregatedActionType::CreateProofBlocks.to_string(),
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .count;
+
+        metrics::histogram!(
+            "sql.chain.operations.count_unsent_aggregated_operations",
+            start.elapsed()
+        );
+        Ok(count)
+    }
+
     pub async fn get_last_affected_block_by_aggregated_action(
         &mut self,
         aggregated_action: AggregatedActionType,
@@ -387,4 +446,148 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         });
         Ok(aggregated_op)
     }
+
+    /// Stores the acknowledgment of the deposit which is not processed yet.
+    /// If the operation with the same serial ID is already acknowledged (e.g. the operation
+    /// was replaced by the reorg or its estimate changed), the stored acknowledgment is overwritten
+    /// and gets a new ID, so it's delivered by `load_priority_op_acknowledgments` once again.
+    pub async fn store_priority_op_acknowledgment(
+        &mut self,
+        op: &PriorityOp,
+        estimated_usable_at: DateTime<Utc>,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let deposit = match &op.data {
+            ZkSyncPriorityOp::Deposit(deposit) => deposit,
+            other => anyhow::bail!("Only deposits are acknowledged, got {:?}", other),
+        };
+        sqlx::query!(
+            "INSERT INTO priority_op_acknowledgments (serial_id, eth_hash, eth_block, address, token, amount, estimated_usable_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (serial_id)
+            DO UPDATE SET id = nextval('priority_op_acknowledgments_id_seq'), eth_hash = $2, eth_block = $3, address = $4, token = $5, amount = $6, estimated_usable_at = $7",
+            op.serial_id as i64,
+            op.eth_hash.as_slice(),
+            op.eth_block as i64,
+            deposit.to.as_bytes(),
+            i32::from(deposit.token),
+            BigDecimal::from(BigInt::from(deposit.amount.clone())),
+            estimated_usable_at,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.chain.operations.store_priority_op_acknowledgment",
+            start.elapsed()
+        );
+        Ok(())
+    }
+
+    /// Loads the acknowledgment of the priority operation with the given serial ID.
+    pub async fn get_priority_op_acknowledgment(
+        &mut self,
+        serial_id: u64,
+    ) -> QueryResult<Option<StoredPriorityOpAcknowledgment>> {
+        let start = Instant::now();
+        let acknowledgment = sqlx::query_as!(
+            StoredPriorityOpAcknowledgment,
+            "SELECT * FROM priority_op_acknowledgments WHERE serial_id = $1",
+            serial_id as i64
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.chain.operations.get_priority_op_acknowledgment",
+            start.elapsed()
+        );
+        Ok(acknowledgment)
+    }
+
+    /// Loads the acknowledgment of the priority operation sent in the given Ethereum transaction.
+    pub async fn get_priority_op_acknowledgment_by_hash(
+        &mut self,
+        eth_hash: &[u8],
+    ) -> QueryResult<Option<StoredPriorityOpAcknowledgment>> {
+        let start = Instant::now();
+        let acknowledgment = sqlx::query_as!(
+            StoredPriorityOpAcknowledgment,
+            "SELECT * FROM priority_op_acknowledgments WHERE eth_hash = $1
+            ORDER BY id DESC
+            LIMIT 1",
+            eth_hash
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.chain.operations.get_priority_op_acknowledgment_by_hash",
+            start.elapsed()
+        );
+        Ok(acknowledgment)
+    }
+
+    /// Loads the acknowledgments stored after the acknowledgment with the given ID,
+    /// in the order they were stored.
+    pub async fn load_priority_op_acknowledgments(
+        &mut self,
+        after_id: i64,
+        limit: u32,
+    ) -> QueryResult<Vec<StoredPriorityOpAcknowledgment>> {
+        let start = Instant::now();
+        let acknowledgments = sqlx::query_as!(
+            StoredPriorityOpAcknowledgment,
+            "SELECT * FROM priority_op_acknowledgments WHERE id > $1
+            ORDER BY id ASC
+            LIMIT $2",
+            after_id,
+            i64::from(limit)
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.chain.operations.load_priority_op_acknowledgments",
+            start.elapsed()
+        );
+        Ok(acknowledgments)
+    }
+
+    /// Removes the acknowledgments of the priority operations with serial IDs lower than the given one,
+    /// i.e. the ones already executed by the state keeper.
+    pub async fn remove_priority_op_acknowledgments(
+        &mut self,
+        before_serial_id: u64,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "DELETE FROM priority_op_acknowledgments WHERE serial_id < $1",
+            before_serial_id as i64
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.chain.operations.remove_priority_op_acknowledgments",
+            start.elapsed()
+        );
+        Ok(())
+    }
+
+    /// Returns the ID of the latest stored acknowledgment, or 0 if there are none.
+    pub async fn get_last_priority_op_acknowledgment_id(&mut self) -> QueryResult<i64> {
+        let start = Instant::now();
+        let id = sqlx::query!("SELECT max(id) FROM priority_op_acknowledgments")
+            .fetch_one(self.0.conn())
+            .await?
+            .max
+            .unwrap_or_default();
+
+        metrics::histogram!(
+            "sql.chain.operations.get_last_priority_op_acknowledgment_id",
+            start.elapsed()
+        );
+        Ok(id)
+    }
 }
@@ -1,7 +1,7 @@
 // External imports
 use chrono::prelude::*;
 use serde_json::value::Value;
-use sqlx::FromRow;
+use sqlx::{types::BigDecimal, FromRow};
 // Workspace imports
 // Local imports
 
@@ -106,3 +106,16 @@ pub struct StoredAggregatedOperation {
     pub to_block: i64,
     pub created_at: DateTime<Utc>,
 }
+
+#[derive(Debug, Clone, FromRow)]
+pub struct StoredPriorityOpAcknowledgment {
+    pub id: i64,
+    pub serial_id: i64,
+    pub eth_hash: Vec<u8>,
+    pub eth_block: i64,
+    pub address: Vec<u8>,
+    pub token: i32,
+    pub amount: BigDecimal,
+    pub estimated_usable_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
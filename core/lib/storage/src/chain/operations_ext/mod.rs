@@ -30,6 +30,21 @@ pub enum SearchDirection {
     Newer,
 }
 
+/// Filters applied to the account transactions history.
+///
+/// Every filter is optional, the default value matches all the transactions.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AccountTxsHistoryFilter {
+    /// Only include operations with the given token.
+    pub token: Option<TokenId>,
+    /// Only include operations of the given type, e.g. `Transfer` or `Deposit`.
+    pub tx_type: Option<String>,
+    /// Only include operations created at or after the given time.
+    pub created_after: Option<DateTime<Utc>>,
+    /// Only include operations created before the given time.
+    pub created_before: Option<DateTime<Utc>>,
+}
+
 /// `OperationsExt` schema is a logical extension for an `Operations` schema,
 /// which provides more getters for transactions.
 /// While `Operations` getters are very basic, `OperationsExt` schema can transform
@@ -505,12 +520,15 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
     /// Unlike `get_account_transactions_history`, this method does not use
     /// a relative offset, and thus not prone to report the same tx twice if new
     /// transactions were added to the database.
+    ///
+    /// Only the transactions matching the `filter` are returned.
     pub async fn get_account_transactions_history_from(
         &mut self,
         address: &Address,
         tx_id: (u64, u64),
         direction: SearchDirection,
         limit: u64,
+        filter: &AccountTxsHistoryFilter,
     ) -> QueryResult<Vec<TransactionsHistoryItem>> {
         let start = Instant::now();
         // Filter for txs that older/newer than provided tx ID.
@@ -574,6 +592,14 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
                         )
                         and
                         (block_number BETWEEN $3 AND $4 or (block_number = $2 and block_index BETWEEN $5 AND $6))
+                        and
+                        ($8::integer is null or (tx->>'token')::integer = $8)
+                        and
+                        ($9::text is null or tx->>'type' = $9)
+                        and
+                        ($10::timestamptz is null or created_at >= $10)
+                        and
+                        ($11::timestamptz is null or created_at < $11)
                     union all
                     select
                         concat_ws(',', block_number, block_index) as tx_id,
@@ -595,6 +621,14 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
                         )
                         and
                         (block_number BETWEEN $3 AND $4 or (block_number = $2 and block_index BETWEEN $5 AND $6))
+                        and
+                        ($8::integer is null or (operation->'priority_op'->>'token')::integer = $8)
+                        and
+                        ($9::text is null or operation->>'type' = $9)
+                        and
+                        ($10::timestamptz is null or created_at >= $10)
+                        and
+                        ($11::timestamptz is null or created_at < $11)
                     ) t
                 order by
                     block_number desc, created_at desc
@@ -623,7 +657,11 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
             block_id as i64,
             block_number_start_idx, block_number_end_idx,
             tx_number_start_idx, tx_number_end_idx,
-            limit as i64
+            limit as i64,
+            filter.token.map(i32::from),
+            filter.tx_type.as_deref(),
+            filter.created_after,
+            filter.created_before,
         ).fetch_all(self.0.conn())
         .await?;
 
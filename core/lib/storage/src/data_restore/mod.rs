@@ -3,6 +3,7 @@ use std::time::Instant;
 // External imports
 use itertools::Itertools;
 // Workspace imports
+use zksync_crypto::{convert::FeConvert, Fr};
 use zksync_types::{AccountId, AccountUpdate, ActionType, BlockNumber, Operation, Token, ZkSyncOp};
 // Local imports
 use self::records::{
@@ -90,10 +91,12 @@ impl<'a, 'c> DataRestoreSchema<'a, 'c> {
                 // let mut ops: Vec<ZkSyncOp> = vec![];
                 let mut block_num: i64 = 0;
                 let mut fee_account: i64 = 0;
+                let mut new_root_hash = None;
                 let ops: Vec<ZkSyncOp> = stored_ops
                     .map(|stored_op| {
                         block_num = stored_op.block_num;
                         fee_account = stored_op.fee_account;
+                        new_root_hash = stored_op.new_root_hash.clone();
                         stored_op.into_franklin_op()
                     })
                     .collect();
@@ -101,6 +104,9 @@ impl<'a, 'c> DataRestoreSchema<'a, 'c> {
                     block_num: block_num as u32,
                     ops,
                     fee_account: fee_account as u32,
+                    new_root_hash: new_root_hash.map(|hash| {
+                        FeConvert::from_bytes(&hash).expect("Unparsable root hash in db")
+                    }),
                 }
             })
             .collect();
@@ -193,7 +199,7 @@ impl<'a, 'c> DataRestoreSchema<'a, 'c> {
 
     pub async fn save_rollup_ops(
         &mut self,
-        ops: &[(BlockNumber, &ZkSyncOp, AccountId)],
+        ops: &[(BlockNumber, &ZkSyncOp, AccountId, Option<Fr>)],
     ) -> QueryResult<()> {
         let start = Instant::now();
         let new_state = self.new_storage_state("Operations");
@@ -203,11 +209,11 @@ impl<'a, 'c> DataRestoreSchema<'a, 'c> {
             .await?;
 
         for op in ops.iter() {
-            let stored_op = NewZkSyncOp::prepare_stored_op(&op.1, op.0, op.2);
+            let stored_op = NewZkSyncOp::prepare_stored_op(&op.1, op.0, op.2, op.3);
 
             sqlx::query!(
-                "INSERT INTO data_restore_rollup_ops (block_num, operation, fee_account) VALUES ($1, $2, $3)",
-                stored_op.block_num, stored_op.operation, stored_op.fee_account
+                "INSERT INTO data_restore_rollup_ops (block_num, operation, fee_account, new_root_hash) VALUES ($1, $2, $3, $4)",
+                stored_op.block_num, stored_op.operation, stored_op.fee_account, stored_op.new_root_hash
             ).execute(transaction.conn())
                 .await?;
         }
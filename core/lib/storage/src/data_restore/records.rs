@@ -3,6 +3,7 @@ use serde_json::Value;
 // Workspace imports
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use zksync_crypto::{convert::FeConvert, Fr};
 use zksync_types::{AccountId, Address, BlockNumber, TokenId, ZkSyncOp};
 // Workspace imports
 // Local imports
@@ -18,6 +19,7 @@ pub struct StoredRollupOpsBlock {
     pub block_num: BlockNumber,
     pub ops: Vec<ZkSyncOp>,
     pub fee_account: AccountId,
+    pub new_root_hash: Option<Fr>,
 }
 
 // #[derive(Debug, Insertable, PartialEq)]
@@ -38,6 +40,7 @@ pub struct StoredZkSyncOp {
     pub block_num: i64,
     pub operation: Value,
     pub fee_account: i64,
+    pub new_root_hash: Option<Vec<u8>>,
 }
 
 impl StoredZkSyncOp {
@@ -50,6 +53,7 @@ pub struct NewZkSyncOp {
     pub block_num: i64,
     pub operation: Value,
     pub fee_account: i64,
+    pub new_root_hash: Option<Vec<u8>>,
 }
 
 impl NewZkSyncOp {
@@ -57,11 +61,13 @@ impl NewZkSyncOp {
         franklin_op: &ZkSyncOp,
         block: BlockNumber,
         fee_account: AccountId,
+        new_root_hash: Option<Fr>,
     ) -> Self {
         Self {
             block_num: i64::from(block),
             operation: serde_json::to_value(franklin_op.clone()).unwrap(),
             fee_account: i64::from(fee_account),
+            new_root_hash: new_root_hash.map(|hash| hash.to_bytes()),
         }
     }
 }
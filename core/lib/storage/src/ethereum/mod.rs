@@ -1,16 +1,27 @@
 // Built-in deps
-use std::{collections::VecDeque, convert::TryFrom, str::FromStr, time::Instant};
+use std::{
+    collections::VecDeque,
+    convert::TryFrom,
+    str::FromStr,
+    time::{Duration, Instant},
+};
 // External imports
 use num::{BigInt, BigUint};
 use sqlx::types::BigDecimal;
 use zksync_basic_types::{H256, U256};
 // Workspace imports
-use zksync_types::ethereum::{ETHOperation, InsertedOperationResponse};
+use zksync_types::ethereum::{
+    ContractUpgradeEvent, ContractUpgradeStage, ETHOperation, InsertedOperationResponse,
+};
 // Local imports
-use self::records::{ETHParams, ETHStats, ETHTxHash, StorageETHOperation};
+use self::records::{
+    ETHParams, ETHStats, ETHTxHash, StorageAggregatedOpStatus, StorageETHOperation,
+    StoredContractUpgrade, StoredEthCancellation,
+};
 use crate::chain::operations::records::StoredAggregatedOperation;
 use crate::{QueryResult, StorageProcessor};
 use zksync_types::aggregated_operations::{AggregatedActionType, AggregatedOperation};
+use zksync_types::BlockNumber;
 
 pub mod records;
 
@@ -42,6 +53,7 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
             StorageETHOperation,
             "SELECT * FROM eth_operations
             WHERE confirmed = false
+                AND NOT EXISTS (SELECT * FROM eth_dropped_operations WHERE eth_op_id = eth_operations.id)
             ORDER BY id ASC"
         )
         .fetch_all(transaction.conn())
@@ -274,6 +286,22 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
         Ok(())
     }
 
+    /// Stores the raw tx data of the Ethereum operation re-encoded for the upgraded contract.
+    /// Transactions sent since then use the new data.
+    pub async fn update_eth_tx_data(&mut self, eth_op_id: i64, raw_tx: &[u8]) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "UPDATE eth_operations SET raw_tx = $1 WHERE id = $2",
+            raw_tx,
+            eth_op_id
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.ethereum.update_eth_tx_data", start.elapsed());
+        Ok(())
+    }
+
     /// Updates the stats counter with the new operation reported.
     /// This method should be called once **per operation**. It means that if transaction
     /// for some operation was stuck, and another transaction was created for it, this method
@@ -366,7 +394,7 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
         Ok(params.into())
     }
 
-    async fn load_eth_params(&mut self) -> QueryResult<ETHParams> {
+    pub(crate) async fn load_eth_params(&mut self) -> QueryResult<ETHParams> {
         let params = sqlx::query_as!(ETHParams, "SELECT * FROM eth_parameters WHERE id = true",)
             .fetch_one(self.0.conn())
             .await?;
@@ -381,10 +409,10 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
 
         let eth_op_id = EthereumSchema(&mut transaction).get_eth_op_id(hash).await?;
 
-        // Set the `confirmed` and `final_hash` field of the entry.
+        // Set the `confirmed`, `final_hash` and `confirmed_at` field of the entry.
         let _eth_op_id: i64 = sqlx::query!(
             "UPDATE eth_operations
-                SET confirmed = $1, final_hash = $2
+                SET confirmed = $1, final_hash = $2, confirmed_at = now()
                 WHERE id = $3
                 RETURNING id",
             true,
@@ -413,13 +441,159 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
         Ok(())
     }
 
+    /// Removes the Ethereum transactions sent for the operation, so the aggregated operation
+    /// is considered unprocessed and will be sent again with a new nonce.
+    ///
+    /// The nonce of the operation is expected to be taken by the cancelling transaction,
+    /// so none of the operation transactions can be mined anymore.
+    pub async fn requeue_eth_operation(&mut self, eth_op_id: i64) -> QueryResult<()> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        EthereumSchema(&mut transaction)
+            .ensure_latest_unconfirmed(eth_op_id)
+            .await?;
+        sqlx::query!("DELETE FROM eth_tx_hashes WHERE eth_op_id = $1", eth_op_id)
+            .execute(transaction.conn())
+            .await?;
+        sqlx::query!(
+            "DELETE FROM eth_aggregated_ops_binding WHERE eth_op_id = $1",
+            eth_op_id
+        )
+        .execute(transaction.conn())
+        .await?;
+        // Cancellation of the operation is removed along with it.
+        sqlx::query!("DELETE FROM eth_operations WHERE id = $1", eth_op_id)
+            .execute(transaction.conn())
+            .await?;
+
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.ethereum.requeue_eth_operation", start.elapsed());
+        Ok(())
+    }
+
+    /// Marks the operation as dropped: it's not loaded as unconfirmed anymore, and its
+    /// aggregated operation is not sent again (the affected blocks are expected to be reverted).
+    ///
+    /// The nonce of the operation is expected to be taken by the cancelling transaction.
+    pub async fn drop_eth_operation(&mut self, eth_op_id: i64) -> QueryResult<()> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        EthereumSchema(&mut transaction)
+            .ensure_latest_unconfirmed(eth_op_id)
+            .await?;
+        sqlx::query!(
+            "INSERT INTO eth_dropped_operations (eth_op_id) VALUES ($1)",
+            eth_op_id
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!(
+            "DELETE FROM eth_operation_cancellations WHERE eth_op_id = $1",
+            eth_op_id
+        )
+        .execute(transaction.conn())
+        .await?;
+
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.ethereum.drop_eth_operation", start.elapsed());
+        Ok(())
+    }
+
+    /// Checks that the operation is not confirmed, and none of the operations sent after it
+    /// is tracked: otherwise requeueing the operation would break the operations order.
+    async fn ensure_latest_unconfirmed(&mut self, eth_op_id: i64) -> QueryResult<()> {
+        let operation = sqlx::query!(
+            "SELECT nonce, confirmed FROM eth_operations WHERE id = $1",
+            eth_op_id
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Ethereum operation {} doesn't exist", eth_op_id))?;
+        anyhow::ensure!(
+            !operation.confirmed,
+            "Ethereum operation {} is already confirmed",
+            eth_op_id
+        );
+
+        let later_operations = sqlx::query!(
+            r#"SELECT COUNT(*) as "count!" FROM eth_operations
+            WHERE confirmed = false AND nonce > $1
+                AND NOT EXISTS (SELECT * FROM eth_dropped_operations WHERE eth_op_id = eth_operations.id)"#,
+            operation.nonce
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .count;
+        anyhow::ensure!(
+            later_operations == 0,
+            "Ethereum operation {} isn't the latest one, the next operations must be handled first",
+            eth_op_id
+        );
+        Ok(())
+    }
+
+    /// Stores the transaction sent to cancel the operation requeued or dropped by the operator.
+    pub async fn save_eth_cancellation(
+        &mut self,
+        eth_op_id: i64,
+        action: &str,
+        tx_hash: &H256,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "INSERT INTO eth_operation_cancellations (eth_op_id, action, tx_hash)
+            VALUES ($1, $2, $3)",
+            eth_op_id,
+            action,
+            tx_hash.as_bytes(),
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.ethereum.save_eth_cancellation", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the cancellations of the operations which are not requeued or dropped yet.
+    pub async fn load_eth_cancellations(&mut self) -> QueryResult<Vec<StoredEthCancellation>> {
+        let start = Instant::now();
+        let cancellations = sqlx::query_as!(
+            StoredEthCancellation,
+            "SELECT * FROM eth_operation_cancellations ORDER BY eth_op_id ASC"
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.ethereum.load_eth_cancellations", start.elapsed());
+        Ok(cancellations)
+    }
+
+    /// Removes the cancellation which didn't happen, e.g. because one of the operation
+    /// transactions was mined first.
+    pub async fn remove_eth_cancellation(&mut self, eth_op_id: i64) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "DELETE FROM eth_operation_cancellations WHERE eth_op_id = $1",
+            eth_op_id
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.ethereum.remove_eth_cancellation", start.elapsed());
+        Ok(())
+    }
+
     /// Obtains the next nonce to use and updates the corresponding entry in the database
     /// for the next invocation.
     ///
     /// This method expects the database to be initially prepared with inserting the actual
     /// nonce value. Currently the script `db-insert-eth-data.sh` is responsible for that
     /// and it's invoked within `db-reset` subcommand.
-    pub(crate) async fn get_next_nonce(&mut self) -> QueryResult<i64> {
+    pub async fn get_next_nonce(&mut self) -> QueryResult<i64> {
         let start = Instant::now();
         let mut transaction = self.0.start_transaction().await?;
 
@@ -501,4 +675,223 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
 
         Ok(final_hash.map(|hash| H256::from_slice(&hash)))
     }
+
+    /// Loads the aggregated operation of the given type that affects the block,
+    /// along with the state of the Ethereum transaction sent for it.
+    /// If the block was aggregated several times (e.g. after the revert), the latest operation is loaded.
+    pub async fn load_aggregated_op_status(
+        &mut self,
+        action_type: AggregatedActionType,
+        block_number: BlockNumber,
+    ) -> QueryResult<Option<StorageAggregatedOpStatus>> {
+        let start = Instant::now();
+        let status = sqlx::query_as!(
+            StorageAggregatedOpStatus,
+            "SELECT aggregate_operations.id AS op_id, aggregate_operations.from_block, aggregate_operations.to_block,
+                aggregate_operations.created_at, eth_operations.id AS eth_op_id, eth_operations.confirmed, eth_operations.final_hash
+            FROM aggregate_operations
+            LEFT JOIN eth_aggregated_ops_binding ON eth_aggregated_ops_binding.op_id = aggregate_operations.id
+            LEFT JOIN eth_operations ON eth_operations.id = eth_aggregated_ops_binding.eth_op_id
+            WHERE aggregate_operations.action_type = $1
+                AND aggregate_operations.from_block <= $2 AND $2 <= aggregate_operations.to_block
+            ORDER BY aggregate_operations.id DESC
+            LIMIT 1",
+            action_type.to_string(),
+            i64::from(block_number)
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.ethereum.load_aggregated_op_status", start.elapsed());
+        Ok(status)
+    }
+
+    /// Loads the hashes of all the Ethereum transactions sent for the operation,
+    /// the latest sent one is the last in the list.
+    pub async fn load_eth_tx_hashes(&mut self, eth_op_id: i64) -> QueryResult<Vec<H256>> {
+        let start = Instant::now();
+        let hashes = sqlx::query_as!(
+            ETHTxHash,
+            "SELECT * FROM eth_tx_hashes
+                WHERE eth_op_id = $1
+                ORDER BY id ASC",
+            eth_op_id
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|entry| H256::from_slice(&entry.tx_hash))
+        .collect();
+
+        metrics::histogram!("sql.ethereum.load_eth_tx_hashes", start.elapsed());
+        Ok(hashes)
+    }
+
+    /// Returns the number of aggregated operations created before the given one
+    /// which still have to be confirmed on Ethereum.
+    ///
+    /// `CreateProofBlocks` operations are not sent to Ethereum, so they are not counted.
+    pub async fn count_unconfirmed_aggregated_ops_before(
+        &mut self,
+        op_id: i64,
+    ) -> QueryResult<u64> {
+        let start = Instant::now();
+        let count = sqlx::query!(
+            "SELECT COUNT(*) FROM aggregate_operations
+            LEFT JOIN eth_aggregated_ops_binding ON eth_aggregated_ops_binding.op_id = aggregate_operations.id
+            LEFT JOIN eth_operations ON eth_operations.id = eth_aggregated_ops_binding.eth_op_id
+            WHERE aggregate_operations.id < $1
+                AND aggregate_operations.action_type != 'CreateProofBlocks'
+                AND eth_operations.confirmed IS DISTINCT FROM true",
+            op_id
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .count
+        .unwrap_or_default();
+
+        metrics::histogram!(
+            "sql.ethereum.count_unconfirmed_aggregated_ops_before",
+            start.elapsed()
+        );
+        Ok(count as u64)
+    }
+
+    /// Returns the average time between the creation of the `CommitBlocks` operation and
+    /// the confirmation of the `ExecuteBlocks` transaction for the last `limit` executed
+    /// operations, i.e. the current time it takes for the block to become final.
+    ///
+    /// Returns `None` if there are no executed operations with the known confirmation time.
+    pub async fn average_finality_latency(&mut self, limit: u32) -> QueryResult<Option<Duration>> {
+        let start = Instant::now();
+        let latency = sqlx::query!(
+            "SELECT AVG(latency)::FLOAT8 AS latency FROM (
+                SELECT EXTRACT(EPOCH FROM (eth_operations.confirmed_at - commit_ops.created_at)) AS latency
+                FROM aggregate_operations execute_ops
+                INNER JOIN eth_aggregated_ops_binding ON eth_aggregated_ops_binding.op_id = execute_ops.id
+                INNER JOIN eth_operations ON eth_operations.id = eth_aggregated_ops_binding.eth_op_id
+                INNER JOIN aggregate_operations commit_ops ON commit_ops.action_type = 'CommitBlocks'
+                    AND commit_ops.from_block <= execute_ops.to_block AND execute_ops.to_block <= commit_ops.to_block
+                WHERE execute_ops.action_type = 'ExecuteBlocks' AND eth_operations.confirmed_at IS NOT NULL
+                ORDER BY execute_ops.id DESC
+                LIMIT $1
+            ) latencies",
+            i64::from(limit)
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .latency;
+
+        metrics::histogram!("sql.ethereum.average_finality_latency", start.elapsed());
+        Ok(latency.map(|secs| Duration::from_secs_f64(secs.max(0.0))))
+    }
+
+    /// Stores the stage of the contract upgrade reported by the upgrade gatekeeper.
+    /// Events older than the stored stage of the upgrade are ignored.
+    pub async fn store_contract_upgrade_event(
+        &mut self,
+        event: &ContractUpgradeEvent,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "INSERT INTO contract_upgrades (version_id, stage, eth_block)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (version_id) DO UPDATE
+            SET stage = $2, eth_block = $3, updated_at = now()
+            WHERE contract_upgrades.eth_block <= $3",
+            event.version_id as i64,
+            event.stage.to_string(),
+            event.eth_block as i64,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.ethereum.store_contract_upgrade_event", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads all the observed contract upgrades in the order of their versions.
+    pub async fn load_contract_upgrades(&mut self) -> QueryResult<Vec<StoredContractUpgrade>> {
+        let start = Instant::now();
+        let upgrades = sqlx::query_as!(
+            StoredContractUpgrade,
+            "SELECT * FROM contract_upgrades ORDER BY version_id"
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.ethereum.load_contract_upgrades", start.elapsed());
+        Ok(upgrades)
+    }
+
+    /// Loads the completed contract upgrade with the latest version, if any.
+    pub async fn load_last_completed_contract_upgrade(
+        &mut self,
+    ) -> QueryResult<Option<StoredContractUpgrade>> {
+        let start = Instant::now();
+        let upgrade = sqlx::query_as!(
+            StoredContractUpgrade,
+            "SELECT * FROM contract_upgrades WHERE stage = $1
+            ORDER BY version_id DESC
+            LIMIT 1",
+            ContractUpgradeStage::Completed.to_string()
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.ethereum.load_last_completed_contract_upgrade",
+            start.elapsed()
+        );
+        Ok(upgrade)
+    }
+
+    /// Loads the latest contract upgrade which is either being prepared or completed, if any.
+    /// Cancelled upgrades and the ones in the notice period are ignored.
+    pub async fn load_last_started_contract_upgrade(
+        &mut self,
+    ) -> QueryResult<Option<StoredContractUpgrade>> {
+        let start = Instant::now();
+        let stages = vec![
+            ContractUpgradeStage::Preparation.to_string(),
+            ContractUpgradeStage::Completed.to_string(),
+        ];
+        let upgrade = sqlx::query_as!(
+            StoredContractUpgrade,
+            "SELECT * FROM contract_upgrades WHERE stage = ANY($1)
+            ORDER BY version_id DESC
+            LIMIT 1",
+            &stages
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.ethereum.load_last_started_contract_upgrade",
+            start.elapsed()
+        );
+        Ok(upgrade)
+    }
+    /// Marks the completed contract upgrade as confirmed by the operator, i.e. the ABI used
+    /// by `eth_sender` is known to match the upgraded contract.
+    pub async fn confirm_contract_upgrade(&mut self, version_id: u64) -> QueryResult<()> {
+        let start = Instant::now();
+        let confirmed = sqlx::query!(
+            "UPDATE contract_upgrades SET confirmed_at = now()
+            WHERE version_id = $1 AND stage = $2 AND confirmed_at IS NULL",
+            version_id as i64,
+            ContractUpgradeStage::Completed.to_string()
+        )
+        .execute(self.0.conn())
+        .await?
+        .rows_affected();
+        anyhow::ensure!(
+            confirmed == 1,
+            "Contract upgrade {} is not completed or is already confirmed",
+            version_id
+        );
+
+        metrics::histogram!("sql.ethereum.confirm_contract_upgrade", start.elapsed());
+        Ok(())
+    }
 }
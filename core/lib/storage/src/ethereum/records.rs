@@ -1,4 +1,5 @@
 // External imports
+use chrono::{DateTime, Utc};
 use sqlx::{types::BigDecimal, FromRow};
 // Workspace imports
 // Local imports
@@ -13,6 +14,7 @@ pub struct StorageETHOperation {
     pub final_hash: Option<Vec<u8>>,
     pub last_deadline_block: i64,
     pub last_used_gas_price: BigDecimal,
+    pub confirmed_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, FromRow, PartialEq)]
@@ -22,6 +24,19 @@ pub struct ETHTxHash {
     pub tx_hash: Vec<u8>,
 }
 
+/// Aggregated operation affecting some block along with the state
+/// of the Ethereum transaction sent for it (if any).
+#[derive(Debug, Clone, FromRow, PartialEq)]
+pub struct StorageAggregatedOpStatus {
+    pub op_id: i64,
+    pub from_block: i64,
+    pub to_block: i64,
+    pub created_at: DateTime<Utc>,
+    pub eth_op_id: Option<i64>,
+    pub confirmed: Option<bool>,
+    pub final_hash: Option<Vec<u8>>,
+}
+
 #[derive(Debug, FromRow, PartialEq)]
 pub struct ETHBinding {
     pub id: i64,
@@ -57,3 +72,24 @@ impl From<ETHParams> for ETHStats {
         }
     }
 }
+
+/// Upgrade of the zkSync contract observed by the Ethereum watcher.
+#[derive(Debug, Clone, FromRow, PartialEq)]
+pub struct StoredContractUpgrade {
+    pub version_id: i64,
+    pub stage: String,
+    pub eth_block: i64,
+    pub confirmed_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Transaction cancelling the Ethereum operation requeued or dropped by the operator.
+#[derive(Debug, Clone, FromRow, PartialEq)]
+pub struct StoredEthCancellation {
+    pub eth_op_id: i64,
+    /// Action requested for the operation, see `admin::ETH_OPERATION_REQUEUE`
+    /// and `admin::ETH_OPERATION_DROP`.
+    pub action: String,
+    pub tx_hash: Vec<u8>,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,179 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+// Workspace imports
+// Local imports
+use self::records::{NewJournalEvent, StoredJournalEvent};
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+/// Event journal schema records the rollup events along with the state changes they describe
+/// and tracks the offsets of the external consumers, so they can tail the journal without
+/// missing the events across restarts.
+///
+/// Delivery is at-least-once: events are delivered to the consumer until it acknowledges them.
+/// Consumers are registered explicitly, and the events acknowledged by all of them are removed.
+#[derive(Debug)]
+pub struct EventJournalSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> EventJournalSchema<'a, 'c> {
+    /// Records the events. Must be called within the transaction making the state changes,
+    /// right before it's committed.
+    ///
+    /// IDs are allocated from the counter row, which stays locked until the transaction is committed,
+    /// so the events become visible in the order of their IDs, and the consumers never skip an event
+    /// by acknowledging the later one. Neither readers nor the removal of the old events are blocked.
+    pub async fn append_events(&mut self, events: &[NewJournalEvent]) -> QueryResult<()> {
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        let start = Instant::now();
+        let last_id = sqlx::query!(
+            "UPDATE event_journal_sequence SET last_id = last_id + $1 RETURNING last_id",
+            events.len() as i64
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .last_id;
+
+        let first_id = last_id - events.len() as i64 + 1;
+        for (id, event) in (first_id..).zip(events) {
+            sqlx::query!(
+                "INSERT INTO event_journal (id, event_type, block_number, data) VALUES ($1, $2, $3, $4)",
+                id,
+                event.event_type.name(),
+                i64::from(event.block_number),
+                event.data,
+            )
+            .execute(self.0.conn())
+            .await?;
+        }
+
+        metrics::histogram!("sql.event_journal.append_events", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the events after the given offset, ordered by their IDs.
+    pub async fn load_events(
+        &mut self,
+        after_id: i64,
+        limit: u32,
+    ) -> QueryResult<Vec<StoredJournalEvent>> {
+        let start = Instant::now();
+        let events = sqlx::query_as!(
+            StoredJournalEvent,
+            "SELECT * FROM event_journal WHERE id > $1 ORDER BY id ASC LIMIT $2",
+            after_id,
+            i64::from(limit),
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.event_journal.load_events", start.elapsed());
+        Ok(events)
+    }
+
+    /// Registers the consumer, which has acknowledged the events up to the given ID.
+    /// Returns `false` if the consumer is already registered, its offset is not changed then.
+    pub async fn register_consumer(&mut self, consumer: &str, acked_id: i64) -> QueryResult<bool> {
+        let start = Instant::now();
+        let registered = sqlx::query!(
+            "INSERT INTO event_journal_consumers (name, acked_id) VALUES ($1, $2)
+            ON CONFLICT (name) DO NOTHING",
+            consumer,
+            acked_id,
+        )
+        .execute(self.0.conn())
+        .await?
+        .rows_affected()
+            == 1;
+
+        metrics::histogram!("sql.event_journal.register_consumer", start.elapsed());
+        Ok(registered)
+    }
+
+    /// Removes the consumer, so the events it didn't acknowledge can be removed.
+    /// Returns `false` if the consumer is not registered.
+    pub async fn remove_consumer(&mut self, consumer: &str) -> QueryResult<bool> {
+        let start = Instant::now();
+        let removed = sqlx::query!(
+            "DELETE FROM event_journal_consumers WHERE name = $1",
+            consumer
+        )
+        .execute(self.0.conn())
+        .await?
+        .rows_affected()
+            == 1;
+
+        metrics::histogram!("sql.event_journal.remove_consumer", start.elapsed());
+        Ok(removed)
+    }
+
+    /// Returns the ID of the last event acknowledged by the consumer,
+    /// or `None` if the consumer is not registered.
+    pub async fn get_consumer_offset(&mut self, consumer: &str) -> QueryResult<Option<i64>> {
+        let start = Instant::now();
+        let offset = sqlx::query!(
+            "SELECT acked_id FROM event_journal_consumers WHERE name = $1",
+            consumer
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(|row| row.acked_id);
+
+        metrics::histogram!("sql.event_journal.get_consumer_offset", start.elapsed());
+        Ok(offset)
+    }
+
+    /// Acknowledges all the events up to the given ID for the consumer.
+    /// The offset never moves back, so the repeated acknowledgments are harmless.
+    /// Returns `false` if the consumer is not registered.
+    pub async fn ack_events(&mut self, consumer: &str, acked_id: i64) -> QueryResult<bool> {
+        let start = Instant::now();
+        let acked = sqlx::query!(
+            "UPDATE event_journal_consumers
+            SET acked_id = GREATEST(acked_id, $2), updated_at = now()
+            WHERE name = $1",
+            consumer,
+            acked_id,
+        )
+        .execute(self.0.conn())
+        .await?
+        .rows_affected()
+            == 1;
+
+        metrics::histogram!("sql.event_journal.ack_events", start.elapsed());
+        Ok(acked)
+    }
+
+    /// Removes the events acknowledged by all the registered consumers and returns their amount.
+    /// Nothing is removed while there are no consumers.
+    pub async fn remove_acked_events(&mut self) -> QueryResult<u64> {
+        let start = Instant::now();
+        let removed = sqlx::query!(
+            "DELETE FROM event_journal
+            WHERE id <= (SELECT MIN(acked_id) FROM event_journal_consumers)"
+        )
+        .execute(self.0.conn())
+        .await?
+        .rows_affected();
+
+        metrics::histogram!("sql.event_journal.remove_acked_events", start.elapsed());
+        Ok(removed)
+    }
+
+    /// Returns the ID of the last recorded event, or 0 if no events were recorded.
+    /// The ID is kept once the events are removed.
+    pub async fn get_last_event_id(&mut self) -> QueryResult<i64> {
+        let start = Instant::now();
+        let id = sqlx::query!("SELECT last_id FROM event_journal_sequence")
+            .fetch_one(self.0.conn())
+            .await?
+            .last_id;
+
+        metrics::histogram!("sql.event_journal.get_last_event_id", start.elapsed());
+        Ok(id)
+    }
+}
@@ -0,0 +1,141 @@
+// External imports
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+use sqlx::FromRow;
+// Workspace imports
+use zksync_types::{
+    block::{Block, ExecutedPriorityOp, ExecutedTx},
+    BlockNumber, ZkSyncOp,
+};
+// Local imports
+use crate::withdrawals::records::StoredWithdrawal;
+
+/// Type of the event recorded in the journal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JournalEventType {
+    BlockSealed,
+    TxExecuted,
+    DepositProcessed,
+    /// Withdrawn funds are transferred to the recipient in L1.
+    WithdrawalFinalized,
+    /// Block of the withdrawal is executed, but the transfer failed, so the funds are stored
+    /// in the pending balance of the recipient in the contract.
+    WithdrawalPendingBalance,
+    /// Blocks after the one the event is recorded for were reverted, so the events
+    /// recorded for them earlier are no longer valid.
+    BlocksReverted,
+}
+
+impl JournalEventType {
+    /// Returns the name the event is stored with.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::BlockSealed => "block_sealed",
+            Self::TxExecuted => "tx_executed",
+            Self::DepositProcessed => "deposit_processed",
+            Self::WithdrawalFinalized => "withdrawal_finalized",
+            Self::WithdrawalPendingBalance => "withdrawal_pending_balance",
+            Self::BlocksReverted => "blocks_reverted",
+        }
+    }
+}
+
+/// Event to be recorded in the journal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewJournalEvent {
+    pub event_type: JournalEventType,
+    pub block_number: BlockNumber,
+    pub data: Value,
+}
+
+impl NewJournalEvent {
+    pub fn block_sealed(block: &Block) -> Self {
+        Self {
+            event_type: JournalEventType::BlockSealed,
+            block_number: block.block_number,
+            data: json!({
+                "root_hash": format!("{:#x}", block.get_eth_encoded_root()),
+                "fee_account": block.fee_account,
+                "chunks": block.block_chunks_size,
+                "operations": block.block_transactions.len(),
+                "timestamp": block.timestamp,
+            }),
+        }
+    }
+
+    pub fn tx_executed(tx: &ExecutedTx, block_number: BlockNumber) -> Self {
+        Self {
+            event_type: JournalEventType::TxExecuted,
+            block_number,
+            data: json!({
+                "tx_hash": tx.signed_tx.hash(),
+                "tx": tx.signed_tx.tx,
+                "success": tx.success,
+                "fail_reason": tx.fail_reason,
+                "block_index": tx.block_index,
+            }),
+        }
+    }
+
+    /// Returns the event of the processed deposit, if the priority operation is a deposit.
+    pub fn deposit_processed(op: &ExecutedPriorityOp, block_number: BlockNumber) -> Option<Self> {
+        let deposit = match &op.op {
+            ZkSyncOp::Deposit(deposit) => &deposit.priority_op,
+            _ => return None,
+        };
+
+        Some(Self {
+            event_type: JournalEventType::DepositProcessed,
+            block_number,
+            data: json!({
+                "serial_id": op.priority_op.serial_id,
+                "eth_hash": format!("0x{}", hex::encode(&op.priority_op.eth_hash)),
+                "from": deposit.from,
+                "to": deposit.to,
+                "token": deposit.token,
+                "amount": deposit.amount.to_string(),
+                "block_index": op.block_index,
+            }),
+        })
+    }
+
+    /// Event of the withdrawal completed by the execution of its block.
+    pub fn withdrawal_completed(withdrawal: &StoredWithdrawal) -> Self {
+        let hex_bytes = |bytes: &[u8]| format!("0x{}", hex::encode(bytes));
+        let event_type = if withdrawal.pending_balance {
+            JournalEventType::WithdrawalPendingBalance
+        } else {
+            JournalEventType::WithdrawalFinalized
+        };
+        Self {
+            event_type,
+            block_number: withdrawal.block_number as BlockNumber,
+            data: json!({
+                "tx_hash": format!("sync-tx:{}", hex::encode(&withdrawal.tx_hash)),
+                "address": hex_bytes(&withdrawal.address),
+                "to": hex_bytes(&withdrawal.to_address),
+                "token": withdrawal.token,
+                "amount": withdrawal.amount.to_string(),
+                "execute_tx_hash": withdrawal.execute_tx_hash.as_deref().map(hex_bytes),
+            }),
+        }
+    }
+
+    pub fn blocks_reverted(last_block: BlockNumber) -> Self {
+        Self {
+            event_type: JournalEventType::BlocksReverted,
+            block_number: last_block,
+            data: json!({}),
+        }
+    }
+}
+
+/// Event recorded in the journal, its ID is the offset of the event.
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct StoredJournalEvent {
+    pub id: i64,
+    pub event_type: String,
+    pub block_number: i64,
+    pub data: Value,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,90 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+use chrono::{DateTime, Utc};
+// Workspace imports
+use zksync_types::TokenId;
+// Local imports
+use self::records::{NewFeeQuote, StorageFeeQuote};
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+/// Fee history schema stores the fees quoted by the ticker, so the charged fees
+/// can be explained and analyzed afterwards.
+#[derive(Debug)]
+pub struct FeeHistorySchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> FeeHistorySchema<'a, 'c> {
+    /// Records the quoted fee.
+    pub async fn store_fee_quote(&mut self, quote: NewFeeQuote) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            INSERT INTO fee_history
+                ( token_id, fee_type, gas_tx_amount, gas_price_wei, gas_fee, zkp_fee, total_fee,
+                  token_price_usd, eth_price_usd )
+            VALUES ( $1, $2, $3, $4, $5, $6, $7, $8, $9 )
+            "#,
+            quote.token_id,
+            quote.fee_type,
+            quote.gas_tx_amount,
+            quote.gas_price_wei,
+            quote.gas_fee,
+            quote.zkp_fee,
+            quote.total_fee,
+            quote.token_price_usd,
+            quote.eth_price_usd,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.fee_history.store_fee_quote", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the fees quoted in the token within the time range, newest first.
+    /// If the fee type is not specified, quotes of all the types are loaded.
+    pub async fn load_fee_history(
+        &mut self,
+        token_id: TokenId,
+        fee_type: Option<&str>,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        limit: u32,
+    ) -> QueryResult<Vec<StorageFeeQuote>> {
+        let start = Instant::now();
+        let quotes = sqlx::query_as!(
+            StorageFeeQuote,
+            r#"
+            SELECT * FROM fee_history
+            WHERE token_id = $1 AND ($2::text IS NULL OR fee_type = $2)
+                AND created_at >= $3 AND created_at <= $4
+            ORDER BY created_at DESC, id DESC
+            LIMIT $5
+            "#,
+            i32::from(token_id),
+            fee_type,
+            from,
+            to,
+            i64::from(limit),
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.fee_history.load_fee_history", start.elapsed());
+        Ok(quotes)
+    }
+
+    /// Removes the quotes recorded before the specified time, returns the amount of removed quotes.
+    pub async fn remove_fee_history_before(&mut self, before: DateTime<Utc>) -> QueryResult<u64> {
+        let start = Instant::now();
+        let removed = sqlx::query!("DELETE FROM fee_history WHERE created_at < $1", before)
+            .execute(self.0.conn())
+            .await?
+            .rows_affected();
+
+        metrics::histogram!("sql.fee_history.remove_fee_history_before", start.elapsed());
+        Ok(removed)
+    }
+}
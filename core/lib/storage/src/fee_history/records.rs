@@ -0,0 +1,38 @@
+// External imports
+use chrono::{DateTime, Utc};
+use sqlx::{types::BigDecimal, FromRow};
+// Workspace imports
+// Local imports
+
+/// Fee quoted by the ticker along with the prices it was calculated with.
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct StorageFeeQuote {
+    pub id: i64,
+    pub token_id: i32,
+    /// Fee type as quoted, e.g. `TransferToNew`.
+    pub fee_type: String,
+    pub gas_tx_amount: BigDecimal,
+    pub gas_price_wei: BigDecimal,
+    pub gas_fee: BigDecimal,
+    pub zkp_fee: BigDecimal,
+    pub total_fee: BigDecimal,
+    /// USD price of the whole token.
+    pub token_price_usd: BigDecimal,
+    /// USD price of the whole ETH.
+    pub eth_price_usd: BigDecimal,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Fee quote to be recorded, see `StorageFeeQuote` for the fields description.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewFeeQuote {
+    pub token_id: i32,
+    pub fee_type: String,
+    pub gas_tx_amount: BigDecimal,
+    pub gas_price_wei: BigDecimal,
+    pub gas_fee: BigDecimal,
+    pub zkp_fee: BigDecimal,
+    pub total_fee: BigDecimal,
+    pub token_price_usd: BigDecimal,
+    pub eth_price_usd: BigDecimal,
+}
@@ -0,0 +1,95 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+// Workspace imports
+// Local imports
+use crate::{QueryResult, StorageProcessor};
+
+/// Leader election schema coordinates the server replicas sharing the database.
+///
+/// Leadership is represented by the Postgres session-level advisory lock, thus it's held as long
+/// as the database session that acquired it is alive, and is released automatically once the
+/// session is closed (e.g. the leader has crashed or lost its connection to the database).
+/// Because of that, the schema must be used with a dedicated connection rather than the pooled one.
+///
+/// The former leader may still be running for a while after its session is closed, so every
+/// new leader starts a new epoch, which is checked by the writes of the leader that must not
+/// be done concurrently by two replicas (see `check_leader_epoch`).
+#[derive(Debug)]
+pub struct LeaderElectionSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> LeaderElectionSchema<'a, 'c> {
+    /// Tries to acquire the lock without waiting, returns whether it's acquired by this session.
+    /// Must be called only if the lock is not held by this session yet, since every successful
+    /// call increases the lock count, and the lock is held until the session is closed anyway.
+    pub async fn try_acquire_lock(&mut self, lock_id: i64) -> QueryResult<bool> {
+        let start = Instant::now();
+        let acquired = sqlx::query!(r#"SELECT pg_try_advisory_lock($1) AS "acquired!""#, lock_id)
+            .fetch_one(self.0.conn())
+            .await?
+            .acquired;
+
+        metrics::histogram!("sql.leader_election.try_acquire_lock", start.elapsed());
+        Ok(acquired)
+    }
+
+    /// Checks whether the lock is held by this session.
+    pub async fn is_lock_held(&mut self, lock_id: i64) -> QueryResult<bool> {
+        let start = Instant::now();
+        // The `bigint` key of the advisory lock is split into the high and low halves.
+        let held = sqlx::query!(
+            r#"SELECT EXISTS (
+                SELECT 1 FROM pg_locks
+                WHERE locktype = 'advisory' AND granted AND pid = pg_backend_pid()
+                    AND objsubid = 1
+                    AND classid::text::bigint = ($1 >> 32) & 4294967295
+                    AND objid::text::bigint = $1 & 4294967295
+            ) AS "held!""#,
+            lock_id
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .held;
+
+        metrics::histogram!("sql.leader_election.is_lock_held", start.elapsed());
+        Ok(held)
+    }
+
+    /// Starts the new leader epoch, must be called by the replica which has just acquired
+    /// the leadership. Returns the epoch which fences the writes of this replica.
+    pub async fn start_leader_epoch(&mut self) -> QueryResult<i64> {
+        let start = Instant::now();
+        let epoch = sqlx::query!(
+            "INSERT INTO leader_epoch (id, epoch) VALUES (true, 1)
+            ON CONFLICT (id) DO UPDATE SET epoch = leader_epoch.epoch + 1
+            RETURNING epoch"
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .epoch;
+
+        metrics::histogram!("sql.leader_election.start_leader_epoch", start.elapsed());
+        Ok(epoch)
+    }
+
+    /// Checks that the leadership wasn't taken over since the `epoch` was started.
+    ///
+    /// The epoch entry stays locked until the end of the transaction, so if the check is done
+    /// within the transaction, the new leader can't start its epoch until the transaction
+    /// is completed, i.e. it observes all the writes made in the transaction.
+    pub async fn check_leader_epoch(&mut self, epoch: i64) -> QueryResult<()> {
+        let start = Instant::now();
+        let current_epoch = sqlx::query!("SELECT epoch FROM leader_epoch FOR SHARE")
+            .fetch_optional(self.0.conn())
+            .await?
+            .map(|row| row.epoch);
+        anyhow::ensure!(
+            current_epoch == Some(epoch),
+            "Leadership is taken over by another replica: epoch {} is outdated",
+            epoch
+        );
+
+        metrics::histogram!("sql.leader_election.check_leader_epoch", start.elapsed());
+        Ok(())
+    }
+}
@@ -30,9 +30,11 @@
 //!
 //! There are the following sets of schemas:
 //!
+//! - backup, for the export and import of the server state.
 //! - config, for the server config.
 //! - data_restore, for the data_restore crate.
 //! - ethereum, for the data associated with the Ethereum blockchain.
+//! - leader_election, for the coordination of the server replicas.
 //! - prover, for the data on prover jobs, proofs, etc.
 //! - tokens, for storing and loading known tokens.
 //! - chain - the biggest one, which includes several schemas for the ZKSync sidechain itself.
@@ -85,15 +87,24 @@ use crate::connection::{holder::ConnectionHolder, PooledConnection};
 #[cfg(test)]
 mod tests;
 
+pub mod accounting;
+pub mod admin;
+pub mod backup;
 pub mod chain;
 pub mod config;
 pub mod connection;
 pub mod data_restore;
 pub mod diff;
 pub mod ethereum;
+pub mod event_journal;
+pub mod fee_history;
+pub mod leader_election;
+pub mod lp_withdrawals;
 pub mod prover;
 pub mod test_data;
 pub mod tokens;
+pub mod tx_traces;
+pub mod withdrawals;
 
 pub use crate::connection::ConnectionPool;
 pub type QueryResult<T> = Result<T, anyhow::Error>;
@@ -165,6 +176,21 @@ impl<'a> StorageProcessor<'a> {
         }
     }
 
+    /// Gains access to the `Accounting` schema.
+    pub fn accounting_schema(&mut self) -> accounting::AccountingSchema<'_, 'a> {
+        accounting::AccountingSchema(self)
+    }
+
+    /// Gains access to the `Admin` schema.
+    pub fn admin_schema(&mut self) -> admin::AdminSchema<'_, 'a> {
+        admin::AdminSchema(self)
+    }
+
+    /// Gains access to the `Backup` schema.
+    pub fn backup_schema(&mut self) -> backup::BackupSchema<'_, 'a> {
+        backup::BackupSchema(self)
+    }
+
     /// Gains access to the `Chain` schemas.
     pub fn chain(&mut self) -> chain::ChainIntermediator<'_, 'a> {
         chain::ChainIntermediator(self)
@@ -185,6 +211,26 @@ impl<'a> StorageProcessor<'a> {
         ethereum::EthereumSchema(self)
     }
 
+    /// Gains access to the `EventJournal` schema.
+    pub fn event_journal_schema(&mut self) -> event_journal::EventJournalSchema<'_, 'a> {
+        event_journal::EventJournalSchema(self)
+    }
+
+    /// Gains access to the `FeeHistory` schema.
+    pub fn fee_history_schema(&mut self) -> fee_history::FeeHistorySchema<'_, 'a> {
+        fee_history::FeeHistorySchema(self)
+    }
+
+    /// Gains access to the `LeaderElection` schema.
+    pub fn leader_election_schema(&mut self) -> leader_election::LeaderElectionSchema<'_, 'a> {
+        leader_election::LeaderElectionSchema(self)
+    }
+
+    /// Gains access to the `LpWithdrawals` schema.
+    pub fn lp_withdrawals_schema(&mut self) -> lp_withdrawals::LpWithdrawalsSchema<'_, 'a> {
+        lp_withdrawals::LpWithdrawalsSchema(self)
+    }
+
     /// Gains access to the `Prover` schema.
     pub fn prover_schema(&mut self) -> prover::ProverSchema<'_, 'a> {
         prover::ProverSchema(self)
@@ -195,6 +241,16 @@ impl<'a> StorageProcessor<'a> {
         tokens::TokensSchema(self)
     }
 
+    /// Gains access to the `TxTraces` schema.
+    pub fn tx_traces_schema(&mut self) -> tx_traces::TxTracesSchema<'_, 'a> {
+        tx_traces::TxTracesSchema(self)
+    }
+
+    /// Gains access to the `Withdrawals` schema.
+    pub fn withdrawals_schema(&mut self) -> withdrawals::WithdrawalsSchema<'_, 'a> {
+        withdrawals::WithdrawalsSchema(self)
+    }
+
     fn conn(&mut self) -> &mut PgConnection {
         match &mut self.conn {
             ConnectionHolder::Pooled(conn) => conn,
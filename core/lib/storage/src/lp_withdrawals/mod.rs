@@ -0,0 +1,239 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+use sqlx::types::BigDecimal;
+// Workspace imports
+use zksync_types::{tx::TxHash, Address, TokenId, H256};
+// Local imports
+use self::records::StorageLpWithdrawal;
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+/// Liquidity provider withdrawals schema stores the withdrawals handed off to the
+/// liquidity providers, so the providers can pay them out in L1.
+#[derive(Debug)]
+pub struct LpWithdrawalsSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> LpWithdrawalsSchema<'a, 'c> {
+    /// Stores the withdrawal for the transfer to the liquidity provider, returns its ID.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn store_withdrawal(
+        &mut self,
+        tx_hash: &TxHash,
+        account_address: Address,
+        lp_address: Address,
+        l1_recipient: Address,
+        token_id: TokenId,
+        amount: BigDecimal,
+        payout_amount: BigDecimal,
+    ) -> QueryResult<i64> {
+        let start = Instant::now();
+        let id = sqlx::query!(
+            r#"
+            INSERT INTO lp_withdrawals
+                ( tx_hash, account_address, lp_address, l1_recipient, token_id, amount, payout_amount )
+            VALUES ( $1, $2, $3, $4, $5, $6, $7 )
+            RETURNING id
+            "#,
+            tx_hash.as_ref(),
+            account_address.as_bytes(),
+            lp_address.as_bytes(),
+            l1_recipient.as_bytes(),
+            i32::from(token_id),
+            amount,
+            payout_amount,
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .id;
+
+        metrics::histogram!("sql.lp_withdrawals.store_withdrawal", start.elapsed());
+        Ok(id)
+    }
+
+    /// Removes the withdrawal, used if the transfer to the provider hasn't been accepted
+    /// by the mempool after the withdrawal was stored.
+    pub async fn delete_withdrawal(&mut self, tx_hash: &TxHash) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "DELETE FROM lp_withdrawals WHERE tx_hash = $1",
+            tx_hash.as_ref(),
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.lp_withdrawals.delete_withdrawal", start.elapsed());
+        Ok(())
+    }
+
+    pub async fn load_withdrawal(
+        &mut self,
+        tx_hash: &TxHash,
+    ) -> QueryResult<Option<StorageLpWithdrawal>> {
+        let start = Instant::now();
+        let withdrawal = sqlx::query_as!(
+            StorageLpWithdrawal,
+            r#"
+            SELECT
+                w.id, w.tx_hash, w.account_address, w.lp_address, w.l1_recipient, w.token_id,
+                w.amount, w.payout_amount, w.l1_tx_hash, w.created_at, w.paid_at,
+                w.payout_confirmed_at,
+                e.block_number as "block_number?",
+                e.success as "success?",
+                e.fail_reason as "fail_reason?"
+            FROM lp_withdrawals w
+            LEFT JOIN executed_transactions e ON e.tx_hash = w.tx_hash
+            WHERE w.tx_hash = $1
+            "#,
+            tx_hash.as_ref(),
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.lp_withdrawals.load_withdrawal", start.elapsed());
+        Ok(withdrawal)
+    }
+
+    /// Loads the withdrawals handed off to the provider with IDs greater than `after_id`,
+    /// so the provider can follow the new withdrawals. If `unpaid_only` is set, the
+    /// withdrawals already paid out are skipped.
+    pub async fn load_lp_withdrawals(
+        &mut self,
+        lp_address: Address,
+        after_id: i64,
+        unpaid_only: bool,
+        limit: u32,
+    ) -> QueryResult<Vec<StorageLpWithdrawal>> {
+        let start = Instant::now();
+        let withdrawals = sqlx::query_as!(
+            StorageLpWithdrawal,
+            r#"
+            SELECT
+                w.id, w.tx_hash, w.account_address, w.lp_address, w.l1_recipient, w.token_id,
+                w.amount, w.payout_amount, w.l1_tx_hash, w.created_at, w.paid_at,
+                w.payout_confirmed_at,
+                e.block_number as "block_number?",
+                e.success as "success?",
+                e.fail_reason as "fail_reason?"
+            FROM lp_withdrawals w
+            LEFT JOIN executed_transactions e ON e.tx_hash = w.tx_hash
+            WHERE w.lp_address = $1 AND w.id > $2 AND (NOT $3 OR w.l1_tx_hash IS NULL)
+            ORDER BY w.id ASC
+            LIMIT $4
+            "#,
+            lp_address.as_bytes(),
+            after_id,
+            unpaid_only,
+            i64::from(limit),
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.lp_withdrawals.load_lp_withdrawals", start.elapsed());
+        Ok(withdrawals)
+    }
+
+    /// Records the Ethereum transaction paying out the withdrawal. Returns `false` if
+    /// there is no such withdrawal of the provider, it's already paid out, or the
+    /// transaction is already reported for another withdrawal.
+    pub async fn mark_paid(
+        &mut self,
+        tx_hash: &TxHash,
+        lp_address: Address,
+        l1_tx_hash: H256,
+    ) -> QueryResult<bool> {
+        let start = Instant::now();
+        let updated = sqlx::query!(
+            r#"
+            UPDATE lp_withdrawals
+            SET l1_tx_hash = $3, paid_at = now()
+            WHERE tx_hash = $1 AND lp_address = $2 AND l1_tx_hash IS NULL
+                AND NOT EXISTS (SELECT 1 FROM lp_withdrawals WHERE l1_tx_hash = $3)
+            "#,
+            tx_hash.as_ref(),
+            lp_address.as_bytes(),
+            l1_tx_hash.as_bytes(),
+        )
+        .execute(self.0.conn())
+        .await?
+        .rows_affected()
+            > 0;
+
+        metrics::histogram!("sql.lp_withdrawals.mark_paid", start.elapsed());
+        Ok(updated)
+    }
+
+    /// Loads the withdrawals with the reported payout transactions not confirmed in L1 yet.
+    pub async fn load_unconfirmed_payouts(
+        &mut self,
+        limit: u32,
+    ) -> QueryResult<Vec<StorageLpWithdrawal>> {
+        let start = Instant::now();
+        let withdrawals = sqlx::query_as!(
+            StorageLpWithdrawal,
+            r#"
+            SELECT
+                w.id, w.tx_hash, w.account_address, w.lp_address, w.l1_recipient, w.token_id,
+                w.amount, w.payout_amount, w.l1_tx_hash, w.created_at, w.paid_at,
+                w.payout_confirmed_at,
+                e.block_number as "block_number?",
+                e.success as "success?",
+                e.fail_reason as "fail_reason?"
+            FROM lp_withdrawals w
+            LEFT JOIN executed_transactions e ON e.tx_hash = w.tx_hash
+            WHERE w.l1_tx_hash IS NOT NULL AND w.payout_confirmed_at IS NULL
+            ORDER BY w.id ASC
+            LIMIT $1
+            "#,
+            i64::from(limit),
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.lp_withdrawals.load_unconfirmed_payouts",
+            start.elapsed()
+        );
+        Ok(withdrawals)
+    }
+
+    /// Marks the payout transaction of the withdrawal as confirmed in L1.
+    pub async fn confirm_payout(&mut self, tx_hash: &TxHash, l1_tx_hash: H256) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            UPDATE lp_withdrawals
+            SET payout_confirmed_at = now()
+            WHERE tx_hash = $1 AND l1_tx_hash = $2
+            "#,
+            tx_hash.as_ref(),
+            l1_tx_hash.as_bytes(),
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.lp_withdrawals.confirm_payout", start.elapsed());
+        Ok(())
+    }
+
+    /// Discards the reported payout transaction, e.g. because it has failed in L1,
+    /// so the withdrawal is expected to be paid out by the provider again.
+    pub async fn reject_payout(&mut self, tx_hash: &TxHash, l1_tx_hash: H256) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            UPDATE lp_withdrawals
+            SET l1_tx_hash = NULL, paid_at = NULL
+            WHERE tx_hash = $1 AND l1_tx_hash = $2 AND payout_confirmed_at IS NULL
+            "#,
+            tx_hash.as_ref(),
+            l1_tx_hash.as_bytes(),
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.lp_withdrawals.reject_payout", start.elapsed());
+        Ok(())
+    }
+}
@@ -0,0 +1,30 @@
+// External imports
+use chrono::{DateTime, Utc};
+use sqlx::{types::BigDecimal, FromRow};
+// Workspace imports
+// Local imports
+
+/// Withdrawal handed off to the liquidity provider along with the execution
+/// result of the transfer to the provider.
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct StorageLpWithdrawal {
+    pub id: i64,
+    pub tx_hash: Vec<u8>,
+    pub account_address: Vec<u8>,
+    pub lp_address: Vec<u8>,
+    pub l1_recipient: Vec<u8>,
+    pub token_id: i32,
+    /// Amount transferred to the provider in L2.
+    pub amount: BigDecimal,
+    /// Amount the provider pays out in L1.
+    pub payout_amount: BigDecimal,
+    pub l1_tx_hash: Option<Vec<u8>>,
+    pub created_at: DateTime<Utc>,
+    pub paid_at: Option<DateTime<Utc>>,
+    /// Time the payout transaction has been confirmed in L1.
+    pub payout_confirmed_at: Option<DateTime<Utc>>,
+    /// Block the transfer was executed in, `None` if it's still pending.
+    pub block_number: Option<i64>,
+    pub success: Option<bool>,
+    pub fail_reason: Option<String>,
+}
@@ -1,11 +1,14 @@
 // Built-in deps
-use std::time::Instant;
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
 // External imports
 use sqlx::Done;
 // Workspace imports
 use zksync_types::BlockNumber;
 // Local imports
-use self::records::{StorageProverJobQueue, StoredAggregatedProof, StoredProof};
+use self::records::{StorageProverInfo, StorageProverJobQueue, StoredAggregatedProof, StoredProof};
 use crate::prover::records::StorageBlockWitness;
 use crate::{QueryResult, StorageProcessor};
 use zksync_crypto::proof::{AggregatedProof, SingleProof};
@@ -61,20 +64,56 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         ).execute(self.0.conn()).await?;
         Ok(())
     }
-    //
-    pub async fn mark_stale_jobs_as_idle(&mut self) -> QueryResult<()> {
-        sqlx::query!(
+
+    /// Returns the jobs whose lease has expired back to the queue, so they can be
+    /// claimed by another prover.
+    ///
+    /// The lease of the job is extended by every heartbeat of the prover that holds it,
+    /// thus the expired lease means that the prover has not reported for `timeout`.
+    pub async fn mark_stale_jobs_as_idle(&mut self, timeout: Duration) -> QueryResult<()> {
+        let start = Instant::now();
+        let reassigned_jobs = sqlx::query!(
             "UPDATE prover_job_queue SET (job_status, updated_at, updated_by) = ($1, now(), 'server_clean_idle')
-            WHERE job_status = $2 and (now() - updated_at) >= interval '120 seconds'",
+            WHERE job_status = $2 and (now() - updated_at) >= make_interval(secs => $3)",
             ProverJobStatus::Idle.to_number(),
             ProverJobStatus::InProgress.to_number(),
+            timeout.as_secs_f64(),
         )
         .execute(self.0.conn())
-        .await?;
+        .await?
+        .rows_affected();
+
+        if reassigned_jobs > 0 {
+            log::warn!(
+                "{} prover jobs were returned to the queue after the lease expiration",
+                reassigned_jobs
+            );
+        }
+        metrics::histogram!("sql", start.elapsed(), "prover" => "mark_stale_jobs_as_idle");
         Ok(())
     }
 
-    pub async fn get_idle_prover_job_from_job_queue(&mut self) -> QueryResult<Option<ProverJob>> {
+    /// Takes the next job from the queue and leases it to the prover.
+    ///
+    /// Jobs are ordered by:
+    /// - jobs containing blocks pinned by the operator go first;
+    /// - job priority, so the aggregated proofs are not starved by the single ones;
+    /// - the closest deadline of the priority operations executed in the job blocks or the
+    ///   following ones, since the priority queue is stalled until these operations are processed
+    ///   on L1, and blocks are verified strictly in order, so all the preceding blocks must be
+    ///   proven to meet the deadline;
+    /// - block age, so the oldest blocks are proven first.
+    ///
+    /// While the lease is held, the job is not given to any other prover.
+    ///
+    /// If the prover already holds the lease of some job (e.g. it was restarted after a crash),
+    /// this job is returned again, so the prover resumes proving from the stored witness
+    /// instead of waiting for the lease expiration. The lease of such a job is renewed as well,
+    /// so it expires as usual once the prover stops reporting again.
+    pub async fn get_idle_prover_job_from_job_queue(
+        &mut self,
+        prover_name: &str,
+    ) -> QueryResult<Option<ProverJob>> {
         let start = Instant::now();
         // Select the block to prove.
         let mut transaction = self.0.start_transaction().await?;
@@ -82,27 +121,84 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
             .execute(transaction.conn())
             .await?;
 
-        let prover_job_queue = sqlx::query_as!(
+        let leased_job = sqlx::query_as!(
             StorageProverJobQueue,
-            r#"
+            "SELECT * FROM prover_job_queue
+            WHERE job_status = $1 AND updated_by = $2
+            ORDER BY id ASC
+            LIMIT 1",
+            ProverJobStatus::InProgress.to_number(),
+            prover_name,
+        )
+        .fetch_optional(transaction.conn())
+        .await?;
+        if let Some(job) = &leased_job {
+            log::info!(
+                "Prover '{}' resumes the job {} for blocks [{},{}]",
+                prover_name,
+                job.id,
+                job.first_block,
+                job.last_block
+            );
+        }
+
+        let prover_job_queue = if leased_job.is_some() {
+            leased_job
+        } else {
+            sqlx::query_as!(
+                StorageProverJobQueue,
+                r#"
                 SELECT * FROM prover_job_queue
                 WHERE job_status = $1
-                ORDER BY (job_priority, id, first_block)
+                ORDER BY
+                    EXISTS (
+                        SELECT 1 FROM pinned_prover_blocks
+                        WHERE pinned_prover_blocks.block_number
+                            BETWEEN prover_job_queue.first_block AND prover_job_queue.last_block
+                    ) DESC,
+                    job_priority ASC,
+                    (
+                        SELECT MIN(deadline_block) FROM executed_priority_operations
+                        WHERE executed_priority_operations.block_number >= prover_job_queue.first_block
+                    ) ASC NULLS LAST,
+                    first_block ASC,
+                    id ASC
                 LIMIT 1
             "#,
-            ProverJobStatus::Idle.to_number()
-        )
-        .fetch_optional(transaction.conn())
-        .await?;
+                ProverJobStatus::Idle.to_number()
+            )
+            .fetch_optional(transaction.conn())
+            .await?
+        };
 
         let prover_job = if let Some(job) = prover_job_queue {
+            // Lease expiration is counted from `updated_at`, so it's reset for the resumed job too.
             sqlx::query!(
                 r#"
                 UPDATE prover_job_queue
-                SET (job_status, updated_at, updated_by) = ($1, now(), 'server_give_job')
-                WHERE id = $2;
+                SET (job_status, updated_at, updated_by) = ($1, now(), $2)
+                WHERE id = $3;
             "#,
                 ProverJobStatus::InProgress.to_number(),
+                prover_name,
+                job.id,
+            )
+            .execute(transaction.conn())
+            .await?;
+            // Time of the resumed job is counted from the first lease.
+            sqlx::query!(
+                "INSERT INTO provers (name, current_job_id, current_job_started_at)
+                VALUES ($1, $2, now())
+                ON CONFLICT (name)
+                DO UPDATE SET
+                    last_heartbeat = now(),
+                    stopped_at = NULL,
+                    current_job_started_at = CASE
+                        WHEN provers.current_job_id = $2 THEN provers.current_job_started_at
+                        ELSE now()
+                    END,
+                    current_job_id = $2",
+                prover_name,
                 job.id,
             )
             .execute(transaction.conn())
@@ -122,27 +218,133 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         Ok(prover_job)
     }
 
-    /// Updates the state of ongoing prover job.
+    /// Pins the block, so the jobs containing it are given to the provers
+    /// before any other job.
+    pub async fn pin_block(&mut self, block_number: BlockNumber) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "INSERT INTO pinned_prover_blocks (block_number) VALUES ($1)
+            ON CONFLICT (block_number) DO NOTHING",
+            i64::from(block_number),
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "pin_block");
+        Ok(())
+    }
+
+    /// Returns the block to the regular order of proving.
+    pub async fn unpin_block(&mut self, block_number: BlockNumber) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "DELETE FROM pinned_prover_blocks WHERE block_number = $1",
+            i64::from(block_number),
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "unpin_block");
+        Ok(())
+    }
+
+    /// Loads the blocks pinned by the operator.
+    pub async fn load_pinned_blocks(&mut self) -> QueryResult<Vec<BlockNumber>> {
+        let start = Instant::now();
+        let blocks =
+            sqlx::query!("SELECT block_number FROM pinned_prover_blocks ORDER BY block_number ASC")
+                .fetch_all(self.0.conn())
+                .await?
+                .into_iter()
+                .map(|row| row.block_number as BlockNumber)
+                .collect();
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "load_pinned_blocks");
+        Ok(blocks)
+    }
+
+    /// Extends the lease of the ongoing prover job.
+    ///
+    /// Returns `false` if the job is not leased to the prover anymore, e.g. if it
+    /// was given to another prover after the lease expiration.
     pub async fn record_prover_is_working(
         &mut self,
         job_id: i32,
         prover_name: &str,
-    ) -> QueryResult<()> {
+    ) -> QueryResult<bool> {
         let start = Instant::now();
-        sqlx::query!(
+        let updated_rows = sqlx::query!(
             "UPDATE prover_job_queue
-            SET (updated_at, updated_by) = (now(), $1)
-            WHERE id = $2",
-            prover_name.to_string(),
+            SET updated_at = now()
+            WHERE id = $1 AND updated_by = $2 AND job_status = $3",
             job_id,
+            prover_name,
+            ProverJobStatus::InProgress.to_number(),
         )
         .execute(self.0.conn())
-        .await?;
+        .await?
+        .rows_affected();
+        let lease_held = updated_rows > 0;
+        if lease_held {
+            self.record_prover_seen(prover_name).await?;
+        }
 
         metrics::histogram!("sql", start.elapsed(), "prover" => "record_prover_is_working");
+        Ok(lease_held)
+    }
+
+    /// Updates the last heartbeat of the prover, registering it if it's not known yet.
+    pub async fn record_prover_seen(&mut self, prover_name: &str) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "INSERT INTO provers (name) VALUES ($1)
+            ON CONFLICT (name)
+            DO UPDATE SET last_heartbeat = now(), stopped_at = NULL",
+            prover_name,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "record_prover_seen");
         Ok(())
     }
 
+    /// Loads the provers that have not reported the stop, along with the jobs they hold.
+    ///
+    /// Provers that died silently are listed too, so they can be spotted by the last heartbeat.
+    pub async fn load_provers(&mut self) -> QueryResult<Vec<StorageProverInfo>> {
+        let start = Instant::now();
+        let provers = sqlx::query_as!(
+            StorageProverInfo,
+            r#"
+            SELECT
+                provers.name,
+                provers.first_seen_at,
+                provers.last_heartbeat,
+                provers.jobs_completed,
+                provers.total_proving_time_secs,
+                prover_job_queue.id AS "current_job_id?",
+                provers.current_job_started_at AS "current_job_started_at?",
+                prover_job_queue.job_type AS "job_type?",
+                prover_job_queue.first_block AS "first_block?",
+                prover_job_queue.last_block AS "last_block?"
+            FROM provers
+            LEFT JOIN prover_job_queue ON
+                prover_job_queue.id = provers.current_job_id
+                AND prover_job_queue.job_status = $1
+                AND prover_job_queue.updated_by = provers.name
+            WHERE provers.stopped_at IS NULL
+            ORDER BY provers.name ASC
+            "#,
+            ProverJobStatus::InProgress.to_number(),
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "load_provers");
+        Ok(provers)
+    }
+
     /// Marks the prover as stopped.
     pub async fn record_prover_stop(&mut self, prover_name: &str) -> QueryResult<()> {
         let start = Instant::now();
@@ -156,20 +358,115 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         )
         .execute(self.0.conn())
         .await?;
+        sqlx::query!(
+            "UPDATE provers
+            SET (stopped_at, current_job_id, current_job_started_at) = (now(), NULL, NULL)
+            WHERE name = $1",
+            prover_name,
+        )
+        .execute(self.0.conn())
+        .await?;
 
         metrics::histogram!("sql", start.elapsed(), "prover" => "record_prover_stop");
         Ok(())
     }
 
-    /// Stores the proof for a block.
+    /// Updates the statistics of the prover that has been working on the completed job.
+    async fn record_job_completed(&mut self, job_id: i32) -> QueryResult<()> {
+        sqlx::query!(
+            "UPDATE provers
+            SET
+                jobs_completed = jobs_completed + 1,
+                total_proving_time_secs = total_proving_time_secs
+                    + EXTRACT(EPOCH FROM now() - current_job_started_at),
+                current_job_id = NULL,
+                current_job_started_at = NULL
+            WHERE current_job_id = $1",
+            job_id,
+        )
+        .execute(self.0.conn())
+        .await?;
+        Ok(())
+    }
+
+    /// Loads the name of the prover holding the lease for the job, if the job is in progress.
+    pub async fn load_job_lease_holder(&mut self, job_id: i32) -> QueryResult<Option<String>> {
+        let start = Instant::now();
+        let lease_holder = sqlx::query!(
+            "SELECT updated_by FROM prover_job_queue WHERE id = $1 AND job_status = $2",
+            job_id,
+            ProverJobStatus::InProgress.to_number(),
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(|row| row.updated_by);
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "load_job_lease_holder");
+        Ok(lease_holder)
+    }
+
+    /// Sets the secret the prover authenticates with instead of the shared one.
+    pub async fn store_prover_secret(
+        &mut self,
+        prover_name: &str,
+        secret: &str,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "INSERT INTO prover_credentials (prover_name, secret) VALUES ($1, $2)
+            ON CONFLICT (prover_name)
+            DO UPDATE SET (secret, created_at) = ($2, now())",
+            prover_name,
+            secret,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "store_prover_secret");
+        Ok(())
+    }
+
+    /// Removes the secret of the prover, so it authenticates with the shared one again.
+    pub async fn remove_prover_secret(&mut self, prover_name: &str) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "DELETE FROM prover_credentials WHERE prover_name = $1",
+            prover_name,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "remove_prover_secret");
+        Ok(())
+    }
+
+    /// Loads the own secrets of the provers by their names.
+    pub async fn load_prover_secrets(&mut self) -> QueryResult<HashMap<String, String>> {
+        let start = Instant::now();
+        let secrets = sqlx::query!("SELECT prover_name, secret FROM prover_credentials")
+            .fetch_all(self.0.conn())
+            .await?
+            .into_iter()
+            .map(|row| (row.prover_name, row.secret))
+            .collect();
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "load_prover_secrets");
+        Ok(secrets)
+    }
+
+    /// Stores the proof for a block produced by the prover.
     pub async fn store_proof(
         &mut self,
         job_id: i32,
         block_number: BlockNumber,
         proof: &SingleProof,
+        prover_name: &str,
     ) -> QueryResult<usize> {
         let start = Instant::now();
         let mut transaction = self.0.start_transaction().await?;
+        ProverSchema(&mut transaction)
+            .record_job_completed(job_id)
+            .await?;
         sqlx::query!(
             "UPDATE prover_job_queue
             SET (updated_at, job_status, updated_by) = (now(), $1, 'server_finish_job')
@@ -180,10 +477,11 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         .execute(transaction.conn())
         .await?;
         let updated_rows = sqlx::query!(
-            "INSERT INTO proofs (block_number, proof)
-            VALUES ($1, $2)",
+            "INSERT INTO proofs (block_number, proof, created_by)
+            VALUES ($1, $2, $3)",
             i64::from(block_number),
-            serde_json::to_value(proof).unwrap()
+            serde_json::to_value(proof).unwrap(),
+            prover_name,
         )
         .execute(transaction.conn())
         .await?
@@ -194,16 +492,20 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         Ok(updated_rows)
     }
 
-    /// Stores the aggregated proof for blocks.
+    /// Stores the aggregated proof for blocks produced by the prover.
     pub async fn store_aggregated_proof(
         &mut self,
         job_id: i32,
         first_block: BlockNumber,
         last_block: BlockNumber,
         proof: &AggregatedProof,
+        prover_name: &str,
     ) -> QueryResult<usize> {
         let start = Instant::now();
         let mut transaction = self.0.start_transaction().await?;
+        ProverSchema(&mut transaction)
+            .record_job_completed(job_id)
+            .await?;
         sqlx::query!(
             "UPDATE prover_job_queue
             SET (updated_at, job_status, updated_by) = (now(), $1, 'server_finish_job')
@@ -214,11 +516,12 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         .execute(transaction.conn())
         .await?;
         let updated_rows = sqlx::query!(
-            "INSERT INTO aggregated_proofs (first_block, last_block, proof)
-            VALUES ($1, $2, $3)",
+            "INSERT INTO aggregated_proofs (first_block, last_block, proof, created_by)
+            VALUES ($1, $2, $3, $4)",
             i64::from(first_block),
             i64::from(last_block),
-            serde_json::to_value(proof).unwrap()
+            serde_json::to_value(proof).unwrap(),
+            prover_name,
         )
         .execute(transaction.conn())
         .await?
@@ -229,6 +532,41 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         Ok(updated_rows)
     }
 
+    /// Removes the stored aggregated proof for blocks and returns its job to the queue,
+    /// so the proof is generated again (e.g. if the stored one turned out to be invalid).
+    /// Returns `false` if there is no such job.
+    pub async fn requeue_aggregated_proof_job(
+        &mut self,
+        first_block: BlockNumber,
+        last_block: BlockNumber,
+    ) -> QueryResult<bool> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        sqlx::query!(
+            "DELETE FROM aggregated_proofs WHERE first_block = $1 AND last_block = $2",
+            i64::from(first_block),
+            i64::from(last_block),
+        )
+        .execute(transaction.conn())
+        .await?;
+        let requeued_jobs = sqlx::query!(
+            "UPDATE prover_job_queue
+            SET (updated_at, job_status, updated_by) = (now(), $1, 'server_requeue_job')
+            WHERE job_type = $2 AND first_block = $3 AND last_block = $4",
+            ProverJobStatus::Idle.to_number(),
+            ProverJobType::AggregatedProof.to_string(),
+            i64::from(first_block),
+            i64::from(last_block),
+        )
+        .execute(transaction.conn())
+        .await?
+        .rows_affected();
+        transaction.commit().await?;
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "requeue_aggregated_proof_job");
+        Ok(requeued_jobs > 0)
+    }
+
     /// Gets the stored proof for a block.
     pub async fn load_proof(
         &mut self,
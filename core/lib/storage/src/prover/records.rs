@@ -25,6 +25,7 @@ pub struct StoredProof {
     pub block_number: i64,
     pub proof: serde_json::Value,
     pub created_at: DateTime<Utc>,
+    pub created_by: Option<String>,
 }
 
 #[derive(Debug, FromRow)]
@@ -33,6 +34,7 @@ pub struct StoredAggregatedProof {
     pub last_block: i64,
     pub proof: serde_json::Value,
     pub created_at: DateTime<Utc>,
+    pub created_by: Option<String>,
 }
 
 // Every time before a prover worker starts generating the proof, a prover run is recorded for monitoring purposes
@@ -69,3 +71,18 @@ pub struct StorageProverJobQueue {
     pub last_block: i64,
     pub job_data: serde_json::Value,
 }
+
+/// Prover along with its statistics and the job it's working on.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct StorageProverInfo {
+    pub name: String,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_heartbeat: DateTime<Utc>,
+    pub jobs_completed: i64,
+    pub total_proving_time_secs: f64,
+    pub current_job_id: Option<i32>,
+    pub current_job_started_at: Option<DateTime<Utc>>,
+    pub job_type: Option<String>,
+    pub first_block: Option<i64>,
+    pub last_block: Option<i64>,
+}
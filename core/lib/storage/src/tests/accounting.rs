@@ -0,0 +1,75 @@
+// Built-in imports
+// External imports
+use num::BigUint;
+use sqlx::types::BigDecimal;
+// Workspace imports
+use zksync_types::aggregated_operations::AggregatedActionType;
+// Local imports
+use crate::tests::db_test;
+use crate::{QueryResult, StorageProcessor};
+
+/// Checks that the costs of the block transactions are accumulated and reduce the block margin.
+#[db_test]
+async fn block_accounting(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let wei = |amount: u64| BigUint::from(amount);
+
+    storage
+        .accounting_schema()
+        .record_block_cost(1, AggregatedActionType::CommitBlocks, wei(1000), wei(300))
+        .await?;
+    storage
+        .accounting_schema()
+        .record_block_cost(2, AggregatedActionType::CommitBlocks, wei(100), wei(300))
+        .await?;
+    // Fees are only taken into account with the first cost of the block.
+    storage
+        .accounting_schema()
+        .record_block_cost(
+            1,
+            AggregatedActionType::PublishProofBlocksOnchain,
+            wei(5000),
+            wei(200),
+        )
+        .await?;
+    storage
+        .accounting_schema()
+        .record_block_cost(1, AggregatedActionType::ExecuteBlocks, wei(1000), wei(100))
+        .await?;
+    // Creating proofs costs nothing on-chain.
+    storage
+        .accounting_schema()
+        .record_block_cost(3, AggregatedActionType::CreateProofBlocks, wei(100), wei(0))
+        .await?;
+
+    let blocks = storage
+        .accounting_schema()
+        .load_block_accounting(1, 3)
+        .await?;
+    assert_eq!(blocks.len(), 2);
+    assert_eq!(blocks[0].block_number, 1);
+    assert_eq!(blocks[0].collected_fees_wei, BigDecimal::from(1000));
+    assert_eq!(blocks[0].commit_cost_wei, BigDecimal::from(300));
+    assert_eq!(blocks[0].proof_cost_wei, BigDecimal::from(200));
+    assert_eq!(blocks[0].execute_cost_wei, BigDecimal::from(100));
+    assert_eq!(blocks[0].margin_wei, BigDecimal::from(400));
+    assert_eq!(blocks[1].block_number, 2);
+    assert_eq!(blocks[1].margin_wei, BigDecimal::from(-200));
+
+    let totals = storage
+        .accounting_schema()
+        .load_accounting_totals(1, 3)
+        .await?;
+    assert_eq!(totals.blocks_count, 2);
+    assert_eq!(totals.collected_fees_wei, BigDecimal::from(1100));
+    assert_eq!(totals.spent_wei, BigDecimal::from(900));
+    assert_eq!(totals.margin_wei, BigDecimal::from(200));
+
+    let totals = storage
+        .accounting_schema()
+        .load_accounting_totals(10, 20)
+        .await?;
+    assert_eq!(totals.blocks_count, 0);
+    assert_eq!(totals.margin_wei, BigDecimal::from(0));
+
+    Ok(())
+}
@@ -0,0 +1,305 @@
+// Built-in imports
+use std::str::FromStr;
+// External imports
+use chrono::{Duration, Utc};
+use sqlx::types::BigDecimal;
+// Workspace imports
+use zksync_types::{tx::TxHash, Token};
+// Local imports
+use crate::admin::{
+    records::StorageTokenSettings, ETH_SENDER_RESUBMIT_FLAG, TX_ACCEPTANCE_PAUSED_FLAG,
+};
+use crate::tests::db_test;
+use crate::{QueryResult, StorageProcessor};
+
+/// Checks that the token settings are created on the first change and then updated.
+#[db_test]
+async fn token_settings(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    assert!(storage
+        .admin_schema()
+        .load_token_settings()
+        .await?
+        .is_empty());
+
+    let token = Token {
+        id: 1,
+        address: "0000000000000000000000000000000000000001".parse().unwrap(),
+        symbol: "ABC".into(),
+        decimals: 9,
+    };
+    storage.tokens_schema().store_token(token).await?;
+
+    storage.admin_schema().set_token_disabled(1, true).await?;
+    storage
+        .admin_schema()
+        .set_token_fee_allowed(0, Some(false))
+        .await?;
+    assert_eq!(
+        storage.admin_schema().load_token_settings().await?,
+        vec![
+            StorageTokenSettings {
+                token_id: 0,
+                disabled: false,
+                fee_allowed: Some(false),
+                liquidity_tier: None,
+            },
+            StorageTokenSettings {
+                token_id: 1,
+                disabled: true,
+                fee_allowed: None,
+                liquidity_tier: None,
+            },
+        ]
+    );
+
+    // Changing one setting must keep the other one intact.
+    storage
+        .admin_schema()
+        .set_token_fee_allowed(1, Some(true))
+        .await?;
+    storage.admin_schema().set_token_disabled(1, false).await?;
+    storage
+        .admin_schema()
+        .set_token_fee_allowed(0, None)
+        .await?;
+    storage
+        .admin_schema()
+        .set_token_liquidity_tier(1, Some("illiquid"))
+        .await?;
+    assert_eq!(
+        storage.admin_schema().load_token_settings().await?,
+        vec![
+            StorageTokenSettings {
+                token_id: 0,
+                disabled: false,
+                fee_allowed: None,
+                liquidity_tier: None,
+            },
+            StorageTokenSettings {
+                token_id: 1,
+                disabled: false,
+                fee_allowed: Some(true),
+                liquidity_tier: Some("illiquid".into()),
+            },
+        ]
+    );
+
+    Ok(())
+}
+
+/// Checks that the flags are disabled by default, and that the taken flag is reset.
+#[db_test]
+async fn server_flags(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    assert!(
+        !storage
+            .admin_schema()
+            .is_flag_enabled(TX_ACCEPTANCE_PAUSED_FLAG)
+            .await?
+    );
+
+    storage
+        .admin_schema()
+        .set_flag(TX_ACCEPTANCE_PAUSED_FLAG, true)
+        .await?;
+    assert!(
+        storage
+            .admin_schema()
+            .is_flag_enabled(TX_ACCEPTANCE_PAUSED_FLAG)
+            .await?
+    );
+    storage
+        .admin_schema()
+        .set_flag(TX_ACCEPTANCE_PAUSED_FLAG, false)
+        .await?;
+    assert!(
+        !storage
+            .admin_schema()
+            .is_flag_enabled(TX_ACCEPTANCE_PAUSED_FLAG)
+            .await?
+    );
+
+    assert!(
+        !storage
+            .admin_schema()
+            .take_flag(ETH_SENDER_RESUBMIT_FLAG)
+            .await?
+    );
+    storage
+        .admin_schema()
+        .set_flag(ETH_SENDER_RESUBMIT_FLAG, true)
+        .await?;
+    assert!(
+        storage
+            .admin_schema()
+            .take_flag(ETH_SENDER_RESUBMIT_FLAG)
+            .await?
+    );
+    assert!(
+        !storage
+            .admin_schema()
+            .take_flag(ETH_SENDER_RESUBMIT_FLAG)
+            .await?
+    );
+    assert!(
+        !storage
+            .admin_schema()
+            .is_flag_enabled(ETH_SENDER_RESUBMIT_FLAG)
+            .await?
+    );
+
+    Ok(())
+}
+
+/// Checks that the subsidies are reserved within the daily budget, released for the transactions
+/// removed from the mempool, and charged and reported for the executed ones.
+#[db_test]
+async fn fee_subsidies(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let decimal = |value: &str| BigDecimal::from_str(value).unwrap();
+
+    let any_token_rule = storage
+        .admin_schema()
+        .add_subsidy_rule("Transfer", None, 100, decimal("10"))
+        .await?;
+    let eth_rule = storage
+        .admin_schema()
+        .add_subsidy_rule("Withdraw", Some(0), 50, decimal("5"))
+        .await?;
+    let rules = storage.admin_schema().load_subsidy_rules().await?;
+    assert_eq!(rules.len(), 2);
+    assert_eq!(rules[0].id, any_token_rule);
+    assert_eq!(rules[0].token_id, None);
+    assert_eq!(rules[1].id, eth_rule);
+    assert_eq!(rules[1].token_id, Some(0));
+    assert_eq!(rules[1].subsidy_percent, 50);
+
+    let since = Utc::now() - Duration::hours(1);
+    let tx_hash = |byte: u8| TxHash::from_str(&format!("sync-tx:{}", hex::encode([byte; 32])));
+    let tx_hash_1 = tx_hash(1).unwrap();
+    let tx_hash_2 = tx_hash(2).unwrap();
+    let tx_hash_3 = tx_hash(3).unwrap();
+    assert!(!storage.admin_schema().is_tx_subsidized(&tx_hash_1).await?);
+
+    for _ in 0..2 {
+        // The repeated reservation for the same transaction is ignored.
+        assert!(
+            storage
+                .admin_schema()
+                .reserve_subsidized_tx(
+                    &tx_hash_1,
+                    &rules[0],
+                    0,
+                    decimal("1000"),
+                    decimal("1.5"),
+                    since
+                )
+                .await?
+        );
+    }
+    assert!(
+        storage
+            .admin_schema()
+            .reserve_subsidized_tx(
+                &tx_hash_2,
+                &rules[0],
+                0,
+                decimal("500"),
+                decimal("0.5"),
+                since
+            )
+            .await?
+    );
+    assert!(storage.admin_schema().is_tx_subsidized(&tx_hash_1).await?);
+    // Reservations count towards the daily budget of the rule.
+    assert_eq!(
+        storage
+            .admin_schema()
+            .subsidized_usd_since(any_token_rule, since)
+            .await?,
+        decimal("2")
+    );
+    assert_eq!(
+        storage
+            .admin_schema()
+            .subsidized_usd_since(eth_rule, since)
+            .await?,
+        decimal("0")
+    );
+    // The reservation exceeding the budget is rejected.
+    assert!(
+        !storage
+            .admin_schema()
+            .reserve_subsidized_tx(
+                &tx_hash_3,
+                &rules[0],
+                0,
+                decimal("8500"),
+                decimal("8.5"),
+                since
+            )
+            .await?
+    );
+    assert!(!storage.admin_schema().is_tx_subsidized(&tx_hash_3).await?);
+    // Nothing is charged until the transactions are executed.
+    assert!(storage
+        .admin_schema()
+        .load_subsidy_report(since)
+        .await?
+        .is_empty());
+
+    // The first transaction is executed, the second one is removed from the mempool.
+    storage
+        .admin_schema()
+        .charge_subsidized_tx(&tx_hash_1)
+        .await?;
+    storage
+        .chain()
+        .mempool_schema()
+        .remove_txs(&[tx_hash_1, tx_hash_2])
+        .await?;
+    assert!(storage.admin_schema().is_tx_subsidized(&tx_hash_1).await?);
+    assert!(!storage.admin_schema().is_tx_subsidized(&tx_hash_2).await?);
+    assert_eq!(
+        storage
+            .admin_schema()
+            .subsidized_usd_since(any_token_rule, since)
+            .await?,
+        decimal("1.5")
+    );
+
+    // The subsidies are kept for reporting once the rule is removed,
+    // and nothing is reserved under the removed rule.
+    assert!(
+        storage
+            .admin_schema()
+            .remove_subsidy_rule(any_token_rule)
+            .await?
+    );
+    assert!(
+        !storage
+            .admin_schema()
+            .remove_subsidy_rule(any_token_rule)
+            .await?
+    );
+    assert!(
+        !storage
+            .admin_schema()
+            .reserve_subsidized_tx(
+                &tx_hash_3,
+                &rules[0],
+                0,
+                decimal("100"),
+                decimal("0.1"),
+                since
+            )
+            .await?
+    );
+    let report = storage.admin_schema().load_subsidy_report(since).await?;
+    assert_eq!(report.len(), 1);
+    assert_eq!(report[0].rule_id, any_token_rule);
+    assert_eq!(report[0].tx_type, "Transfer");
+    assert_eq!(report[0].tx_count, 1);
+    assert_eq!(report[0].subsidized_amount, decimal("1000"));
+    assert_eq!(report[0].subsidized_usd, decimal("1.5"));
+
+    Ok(())
+}
@@ -0,0 +1,156 @@
+// Built-in imports
+// External imports
+// Workspace imports
+use zksync_types::{helpers::apply_updates, AccountMap, Action, Address, Token};
+// Local imports
+use crate::tests::{create_rng, db_test};
+use crate::{
+    admin::{records::StorageTokenSettings, MAINTENANCE_MODE_FLAG},
+    backup::records::{
+        EthParametersBackup, RuntimeSettingsBackup, StateBackup, SubsidyRuleBackup,
+        TokenOverrideBackup,
+    },
+    test_data::{gen_acc_random_updates, gen_operation, BLOCK_SIZE_CHUNKS},
+    QueryResult, StorageProcessor,
+};
+
+fn backup_with_accounts(accounts: &AccountMap) -> StateBackup {
+    let mut backup_accounts: Vec<_> = accounts.clone().into_iter().collect();
+    backup_accounts.sort_by_key(|(id, _)| *id);
+
+    StateBackup {
+        block: gen_operation(5, Action::Commit, BLOCK_SIZE_CHUNKS).block,
+        tokens: Vec::new(),
+        accounts: backup_accounts,
+        aggregated_operations: Vec::new(),
+        eth_parameters: EthParametersBackup {
+            nonce: 10,
+            gas_price_limit: 1000,
+            average_gas_price: None,
+            commit_ops: 5,
+            verify_ops: 5,
+            withdraw_ops: 0,
+        },
+        token_overrides: Vec::new(),
+        runtime_settings: RuntimeSettingsBackup::default(),
+    }
+}
+
+/// Checks that the restored backup becomes the verified state and that
+/// the backup can't be restored into a non-empty database.
+#[db_test]
+async fn restore_backup(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let mut rng = create_rng();
+
+    let mut accounts = AccountMap::default();
+    for _ in 0..5 {
+        apply_updates(&mut accounts, gen_acc_random_updates(&mut rng).collect());
+    }
+    let backup = backup_with_accounts(&accounts);
+
+    storage
+        .backup_schema()
+        .restore_backup(backup.clone())
+        .await?;
+
+    let (block_number, restored_accounts) =
+        storage.chain().state_schema().load_verified_state().await?;
+    assert_eq!(block_number, 5);
+    assert_eq!(restored_accounts, accounts);
+    assert_eq!(
+        storage
+            .chain()
+            .block_schema()
+            .get_last_committed_block()
+            .await?,
+        5
+    );
+
+    // The database is not empty anymore.
+    assert!(storage
+        .backup_schema()
+        .restore_backup(backup)
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+/// Checks that the backup created from the restored state contains the same state,
+/// along with the token overrides and the runtime settings, and that the backup
+/// is refused while there are unverified blocks.
+#[db_test]
+async fn create_backup(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let mut rng = create_rng();
+
+    let mut accounts = AccountMap::default();
+    for _ in 0..5 {
+        apply_updates(&mut accounts, gen_acc_random_updates(&mut rng).collect());
+    }
+    let mut backup = backup_with_accounts(&accounts);
+    backup.tokens = vec![
+        Token::new(0, Address::zero(), "ETH", 18),
+        Token::new(1, Address::repeat_byte(1), "ABC", 6),
+    ];
+    backup.token_overrides = vec![TokenOverrideBackup {
+        token_id: 1,
+        metadata_overridden: true,
+        withdrawal_gas_limit: Some(50_000),
+    }];
+    backup.runtime_settings = RuntimeSettingsBackup {
+        token_settings: vec![StorageTokenSettings {
+            token_id: 1,
+            disabled: false,
+            fee_allowed: Some(false),
+            liquidity_tier: Some("low".to_string()),
+        }],
+        enabled_flags: vec![MAINTENANCE_MODE_FLAG.to_string()],
+        subsidy_rules: vec![SubsidyRuleBackup {
+            tx_type: "Transfer".to_string(),
+            token_id: Some(1),
+            subsidy_percent: 50,
+            daily_budget_usd: "100".to_string(),
+        }],
+    };
+    storage
+        .backup_schema()
+        .restore_backup(backup.clone())
+        .await?;
+
+    let created = storage.backup_schema().create_backup().await?;
+    assert_eq!(created.block.block_number, 5);
+    assert_eq!(created.accounts, backup.accounts);
+    assert_eq!(created.tokens, backup.tokens);
+    assert_eq!(created.token_overrides, backup.token_overrides);
+    assert_eq!(
+        created.runtime_settings.token_settings,
+        backup.runtime_settings.token_settings
+    );
+    assert_eq!(
+        created.runtime_settings.enabled_flags,
+        backup.runtime_settings.enabled_flags
+    );
+    assert_eq!(created.runtime_settings.subsidy_rules.len(), 1);
+    assert_eq!(
+        created.runtime_settings.subsidy_rules[0].tx_type,
+        "Transfer"
+    );
+    assert_eq!(
+        created.runtime_settings.subsidy_rules[0].subsidy_percent,
+        50
+    );
+    assert_eq!(created.eth_parameters.nonce, 10);
+
+    // The committed but unverified block makes the state inconsistent for the backup.
+    storage
+        .chain()
+        .block_schema()
+        .execute_operation(gen_operation(6, Action::Commit, BLOCK_SIZE_CHUNKS))
+        .await?;
+    let err = storage.backup_schema().create_backup().await.unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("only blocks up to #5 are verified"));
+
+    Ok(())
+}
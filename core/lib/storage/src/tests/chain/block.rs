@@ -610,6 +610,7 @@ async fn pending_block_workflow(mut storage: StorageProcessor<'_>) -> QueryResul
             success: true,
             op: Some(change_pubkey_op),
             fail_reason: None,
+            fail_trace: None,
             block_index: None,
             created_at: chrono::Utc::now(),
             batch_id: None,
@@ -644,6 +645,7 @@ async fn pending_block_workflow(mut storage: StorageProcessor<'_>) -> QueryResul
             success: true,
             op: Some(transfer_to_new_op),
             fail_reason: None,
+            fail_trace: None,
             block_index: None,
             created_at: chrono::Utc::now(),
             batch_id: None,
@@ -955,3 +957,204 @@ async fn test_operations_counter(mut storage: StorageProcessor<'_>) -> QueryResu
 
     Ok(())
 }
+
+/// Checks that reverted blocks are removed from the database and
+/// their successful transactions are returned to the mempool.
+#[db_test]
+async fn revert_blocks(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    use crate::chain::mempool::MempoolSchema;
+    use num::BigUint;
+    use zksync_test_account::ZkSyncAccount;
+    use zksync_types::{
+        aggregated_operations::AggregatedActionType, operations::TransferToNewOp,
+        ExecutedOperations, ExecutedTx, ZkSyncOp, ZkSyncTx,
+    };
+
+    let from_account_id = 0xbabe;
+    let from_zksync_account = ZkSyncAccount::rand();
+    from_zksync_account.set_account_id(Some(from_account_id));
+    let to_zksync_account = ZkSyncAccount::rand();
+
+    let executed_transfer = |nonce: u32, success: bool| {
+        let tx = from_zksync_account
+            .sign_transfer(
+                0,
+                "",
+                1u32.into(),
+                0u32.into(),
+                &to_zksync_account.address,
+                Some(nonce),
+                false,
+            )
+            .0;
+        let op = ZkSyncOp::TransferToNew(Box::new(TransferToNewOp {
+            tx: tx.clone(),
+            from: from_account_id,
+            to: 0xdcba,
+        }));
+
+        let executed_tx = ExecutedTx {
+            signed_tx: op.try_get_tx().unwrap().into(),
+            success,
+            op: if success { Some(op) } else { None },
+            fail_reason: if success {
+                None
+            } else {
+                Some("Not enough balance".to_string())
+            },
+            fail_trace: None,
+            block_index: None,
+            created_at: chrono::Utc::now(),
+            batch_id: None,
+        };
+
+        (
+            ZkSyncTx::Transfer(Box::new(tx)).hash(),
+            ExecutedOperations::Tx(Box::new(executed_tx)),
+        )
+    };
+
+    let (tx_hash_1, executed_tx_1) = executed_transfer(0, true);
+    let (tx_hash_2, executed_tx_2) = executed_transfer(1, true);
+    let (tx_hash_3, executed_tx_3) = executed_transfer(2, false);
+
+    let blocks_txs = vec![
+        vec![executed_tx_1],
+        vec![executed_tx_2],
+        vec![executed_tx_3],
+    ];
+    for (block_number, txs) in (1..=3).zip(blocks_txs) {
+        BlockSchema(&mut storage)
+            .execute_operation(get_operation_with_txs(
+                block_number,
+                Action::Commit,
+                BLOCK_SIZE_CHUNKS,
+                txs,
+            ))
+            .await?;
+    }
+    assert!(MempoolSchema(&mut storage).load_txs().await?.is_empty());
+    for block_number in 1..=3 {
+        storage
+            .accounting_schema()
+            .record_block_cost(
+                block_number,
+                AggregatedActionType::CommitBlocks,
+                BigUint::from(1000u32),
+                BigUint::from(300u32),
+            )
+            .await?;
+    }
+
+    BlockSchema(&mut storage).revert_blocks(1).await?;
+
+    assert_eq!(
+        BlockSchema(&mut storage).get_last_committed_block().await?,
+        1
+    );
+    assert!(BlockSchema(&mut storage).get_block(1).await?.is_some());
+    assert!(BlockSchema(&mut storage).get_block(2).await?.is_none());
+    assert!(BlockSchema(&mut storage)
+        .get_block_executed_ops(2)
+        .await?
+        .is_empty());
+    let accounting = storage
+        .accounting_schema()
+        .load_block_accounting(1, 3)
+        .await?;
+    assert_eq!(accounting.len(), 1);
+    assert_eq!(accounting[0].block_number, 1);
+
+    // Only the successful transaction of the reverted blocks is returned to the mempool.
+    let mut mempool = MempoolSchema(&mut storage);
+    assert!(!mempool.contains_tx(tx_hash_1).await?);
+    assert!(mempool.contains_tx(tx_hash_2).await?);
+    assert!(!mempool.contains_tx(tx_hash_3).await?);
+
+    Ok(())
+}
+
+/// Checks that the aggregated operations confirmed on Ethereum are not changed by the revert:
+/// the revert is refused if it would truncate a confirmed operation or remove an executed block.
+#[db_test]
+async fn revert_blocks_confirmed_operations(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    use crate::tests::ethereum::send_aggregated_op;
+    use zksync_types::aggregated_operations::{
+        AggregatedActionType, AggregatedOperation, BlocksCommitOperation, BlocksExecuteOperation,
+    };
+
+    storage.ethereum_schema().initialize_eth_data().await?;
+    let blocks: Vec<_> = (0..=3)
+        .map(|block_number| get_operation(block_number, Action::Commit, BLOCK_SIZE_CHUNKS).block)
+        .collect();
+    for block in &blocks[1..] {
+        BlockSchema(&mut storage)
+            .execute_operation(get_operation(
+                block.block_number,
+                Action::Commit,
+                BLOCK_SIZE_CHUNKS,
+            ))
+            .await?;
+    }
+
+    // Block 1 is committed and executed, blocks 2 and 3 are committed by a single operation.
+    let operations = vec![
+        AggregatedOperation::CommitBlocks(BlocksCommitOperation {
+            last_committed_block: blocks[0].clone(),
+            blocks: vec![blocks[1].clone()],
+        }),
+        AggregatedOperation::CommitBlocks(BlocksCommitOperation {
+            last_committed_block: blocks[1].clone(),
+            blocks: vec![blocks[2].clone(), blocks[3].clone()],
+        }),
+        AggregatedOperation::ExecuteBlocks(BlocksExecuteOperation {
+            blocks: vec![blocks[1].clone()],
+        }),
+    ];
+    for (i, operation) in operations.into_iter().enumerate() {
+        let hash = send_aggregated_op(&mut storage, operation, H256::from_low_u64_ne(i as u64 + 1))
+            .await?;
+        storage.ethereum_schema().confirm_eth_tx(&hash).await?;
+    }
+
+    // Confirmed operation for the blocks 2 and 3 can't be truncated.
+    let err = BlockSchema(&mut storage)
+        .revert_blocks(2)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("blocks 2..=3 is confirmed"));
+    assert_eq!(
+        BlockSchema(&mut storage).get_last_committed_block().await?,
+        3
+    );
+
+    // Operation is removed along with all the blocks it covers.
+    BlockSchema(&mut storage).revert_blocks(1).await?;
+    assert_eq!(
+        BlockSchema(&mut storage).get_last_committed_block().await?,
+        1
+    );
+    assert_eq!(
+        storage
+            .chain()
+            .operations_schema()
+            .get_last_affected_block_by_aggregated_action(AggregatedActionType::CommitBlocks)
+            .await?,
+        1
+    );
+
+    // Executed block can't be reverted.
+    let err = BlockSchema(&mut storage)
+        .revert_blocks(0)
+        .await
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("ExecuteBlocks operation for the blocks 1..=1"));
+    assert_eq!(
+        BlockSchema(&mut storage).get_last_committed_block().await?,
+        1
+    );
+
+    Ok(())
+}
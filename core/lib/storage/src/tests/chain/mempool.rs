@@ -343,3 +343,43 @@ async fn contains_and_get_tx(mut storage: StorageProcessor<'_>) -> QueryResult<(
     //
     // Ok(())
 }
+
+/// Checks the aggregated statistics and the queue position of the pending transactions.
+#[db_test]
+async fn stats_and_position(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let stats = MempoolSchema(&mut storage).get_stats().await?;
+    assert_eq!(stats.txs_count, 0);
+    assert_eq!(stats.oldest_tx_created_at, None);
+
+    // All the generated transactions pay fee in ETH.
+    let txs = franklin_txs();
+    for tx in &txs {
+        MempoolSchema(&mut storage).insert_tx(tx).await?;
+    }
+    let batch = gen_transfers(2);
+    MempoolSchema(&mut storage)
+        .insert_batch(&batch, vec![])
+        .await?;
+
+    let stats = MempoolSchema(&mut storage).get_stats().await?;
+    let total_txs = (txs.len() + batch.len()) as i64;
+    assert_eq!(stats.txs_count, total_txs);
+    assert_eq!(stats.batches_count, 1);
+    assert!(stats.oldest_tx_created_at.is_some());
+    assert_eq!(stats.txs_by_fee_token, vec![(0, total_txs)]);
+
+    // Check the position of the transaction in the queue.
+    let position = MempoolSchema(&mut storage)
+        .get_tx_position(txs[2].hash())
+        .await?
+        .expect("Pending transaction must be found");
+    assert_eq!(position.txs_ahead, 2);
+    assert_eq!(position.tx.hash(), txs[2].hash());
+
+    let position = MempoolSchema(&mut storage)
+        .get_tx_position(Default::default())
+        .await?;
+    assert!(position.is_none());
+
+    Ok(())
+}
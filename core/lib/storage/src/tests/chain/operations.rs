@@ -1,6 +1,6 @@
 // External imports
 // Workspace imports
-use zksync_types::ActionType;
+use zksync_types::{ActionType, Deposit, PriorityOp, ZkSyncPriorityOp};
 // Local imports
 use crate::tests::db_test;
 use crate::{
@@ -122,6 +122,23 @@ async fn executed_priority_operations(mut storage: StorageProcessor<'_>) -> Quer
     assert_eq!(stored_operation.deadline_block, executed_tx.deadline_block);
     assert_eq!(stored_operation.eth_hash, executed_tx.eth_hash);
 
+    // Single Ethereum transaction may request several priority operations.
+    OperationsSchema(&mut storage)
+        .store_executed_priority_op(NewExecutedPriorityOperation {
+            block_index: 2,
+            priority_op_serialid: 1,
+            ..executed_tx.clone()
+        })
+        .await?;
+    let stored_operations = OperationsSchema(&mut storage)
+        .get_executed_priority_operations_by_hash(&executed_tx.eth_hash)
+        .await?;
+    let serial_ids: Vec<_> = stored_operations
+        .iter()
+        .map(|op| op.priority_op_serialid)
+        .collect();
+    assert_eq!(serial_ids, vec![0, 1]);
+
     Ok(())
 }
 
@@ -270,3 +287,102 @@ async fn transaction_resent(mut storage: StorageProcessor<'_>) -> QueryResult<()
 
     Ok(())
 }
+
+/// Checks that the priority operation acknowledgments are stored, overwritten by the operations
+/// with the same serial ID, loaded in the order of storing and removed once executed.
+#[db_test]
+async fn priority_op_acknowledgments(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let deposit = |serial_id: u64, eth_hash: u8| PriorityOp {
+        serial_id,
+        data: ZkSyncPriorityOp::Deposit(Deposit {
+            from: Default::default(),
+            token: 0,
+            amount: 1000u32.into(),
+            to: [0xAB; 20].into(),
+        }),
+        deadline_block: 100,
+        eth_hash: vec![eth_hash; 32],
+        eth_block: 10,
+    };
+
+    assert_eq!(
+        OperationsSchema(&mut storage)
+            .get_last_priority_op_acknowledgment_id()
+            .await?,
+        0
+    );
+
+    OperationsSchema(&mut storage)
+        .store_priority_op_acknowledgment(&deposit(0, 1), chrono::Utc::now())
+        .await?;
+    OperationsSchema(&mut storage)
+        .store_priority_op_acknowledgment(&deposit(1, 2), chrono::Utc::now())
+        .await?;
+    // Operation with the serial ID 1 is replaced by another one.
+    OperationsSchema(&mut storage)
+        .store_priority_op_acknowledgment(&deposit(1, 3), chrono::Utc::now())
+        .await?;
+
+    let stored = OperationsSchema(&mut storage)
+        .get_priority_op_acknowledgment(1)
+        .await?
+        .expect("Acknowledgment should be stored");
+    assert_eq!(stored.eth_hash, vec![3; 32]);
+    assert!(OperationsSchema(&mut storage)
+        .get_priority_op_acknowledgment_by_hash(&[2; 32])
+        .await?
+        .is_none());
+    let stored = OperationsSchema(&mut storage)
+        .get_priority_op_acknowledgment_by_hash(&[1; 32])
+        .await?
+        .expect("Acknowledgment should be stored");
+    assert_eq!(stored.serial_id, 0);
+
+    let loaded = OperationsSchema(&mut storage)
+        .load_priority_op_acknowledgments(0, 10)
+        .await?;
+    assert_eq!(
+        loaded.iter().map(|ack| ack.serial_id).collect::<Vec<_>>(),
+        vec![0, 1]
+    );
+    let loaded = OperationsSchema(&mut storage)
+        .load_priority_op_acknowledgments(loaded[0].id, 10)
+        .await?;
+    assert_eq!(loaded.len(), 1);
+    assert_eq!(
+        OperationsSchema(&mut storage)
+            .get_last_priority_op_acknowledgment_id()
+            .await?,
+        loaded[0].id
+    );
+
+    // Updated acknowledgment is loaded once again.
+    let last_id = loaded[0].id;
+    let estimated_usable_at = chrono::Utc::now() + chrono::Duration::minutes(5);
+    OperationsSchema(&mut storage)
+        .store_priority_op_acknowledgment(&deposit(0, 1), estimated_usable_at)
+        .await?;
+    let loaded = OperationsSchema(&mut storage)
+        .load_priority_op_acknowledgments(last_id, 10)
+        .await?;
+    assert_eq!(loaded.len(), 1);
+    assert_eq!(loaded[0].serial_id, 0);
+    assert_eq!(
+        loaded[0].estimated_usable_at.timestamp(),
+        estimated_usable_at.timestamp()
+    );
+
+    OperationsSchema(&mut storage)
+        .remove_priority_op_acknowledgments(1)
+        .await?;
+    assert!(OperationsSchema(&mut storage)
+        .get_priority_op_acknowledgment(0)
+        .await?
+        .is_none());
+    assert!(OperationsSchema(&mut storage)
+        .get_priority_op_acknowledgment(1)
+        .await?
+        .is_some());
+
+    Ok(())
+}
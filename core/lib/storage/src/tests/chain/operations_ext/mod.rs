@@ -7,7 +7,10 @@ use zksync_types::{ethereum::OperationType, Action};
 
 use self::setup::TransactionsHistoryTestSetup;
 use crate::{
-    chain::operations_ext::{records::AccountTxReceiptResponse, SearchDirection},
+    chain::operations_ext::{
+        records::{AccountTxReceiptResponse, TransactionsHistoryItem},
+        AccountTxsHistoryFilter, SearchDirection,
+    },
     test_data::{dummy_ethereum_tx_hash, gen_unique_operation, BLOCK_SIZE_CHUNKS},
     tests::db_test,
     QueryResult, StorageProcessor,
@@ -233,6 +236,7 @@ async fn get_account_transactions_history_from(
                 (block_id, tx_id),
                 direction,
                 limit_from,
+                &AccountTxsHistoryFilter::default(),
             )
             .await?;
         let to_history = storage
@@ -243,6 +247,7 @@ async fn get_account_transactions_history_from(
                 (block_id, tx_id),
                 direction,
                 limit_to,
+                &AccountTxsHistoryFilter::default(),
             )
             .await?;
 
@@ -263,6 +268,103 @@ async fn get_account_transactions_history_from(
     Ok(())
 }
 
+/// Checks that the `get_account_transactions_history_from` method returns only
+/// the transactions matching the provided filter.
+#[db_test]
+async fn get_account_transactions_history_filtered(
+    mut storage: StorageProcessor<'_>,
+) -> QueryResult<()> {
+    let mut setup = TransactionsHistoryTestSetup::new();
+    setup.add_block(1);
+    let second_block_start = setup.next_tx_time;
+    setup.add_block(2);
+
+    commit_schema_data(&mut storage, &setup).await?;
+
+    // Tokens are replaced with their symbols in the history items.
+    let token_symbol = |item: &TransactionsHistoryItem| {
+        item.tx
+            .get("priority_op")
+            .unwrap_or(&item.tx)
+            .get("token")
+            .and_then(|token| token.as_str())
+            .map(String::from)
+    };
+
+    let test_vector = vec![
+        (
+            AccountTxsHistoryFilter {
+                token: Some(setup.tokens[1].id),
+                ..Default::default()
+            },
+            4,
+        ),
+        (
+            AccountTxsHistoryFilter {
+                tx_type: Some("Deposit".to_string()),
+                ..Default::default()
+            },
+            2,
+        ),
+        (
+            AccountTxsHistoryFilter {
+                token: Some(setup.tokens[2].id),
+                tx_type: Some("Withdraw".to_string()),
+                ..Default::default()
+            },
+            2,
+        ),
+        (
+            AccountTxsHistoryFilter {
+                created_before: Some(second_block_start),
+                ..Default::default()
+            },
+            7,
+        ),
+        (
+            AccountTxsHistoryFilter {
+                created_after: Some(second_block_start),
+                tx_type: Some("Transfer".to_string()),
+                ..Default::default()
+            },
+            2,
+        ),
+    ];
+
+    for (filter, expected_len) in test_vector {
+        let history = storage
+            .chain()
+            .operations_ext_schema()
+            .get_account_transactions_history_from(
+                &setup.from_zksync_account.address,
+                (3, 0),
+                SearchDirection::Older,
+                100,
+                &filter,
+            )
+            .await?;
+
+        assert_eq!(history.len(), expected_len, "Filter: {:?}", filter);
+        for item in &history {
+            if let Some(token) = filter.token {
+                let symbol = &setup.tokens[token as usize].symbol;
+                assert_eq!(token_symbol(item).as_ref(), Some(symbol));
+            }
+            if let Some(tx_type) = &filter.tx_type {
+                assert_eq!(item.tx["type"].as_str(), Some(tx_type.as_str()));
+            }
+            if let Some(created_after) = filter.created_after {
+                assert!(item.created_at >= created_after);
+            }
+            if let Some(created_before) = filter.created_before {
+                assert!(item.created_at < created_before);
+            }
+        }
+    }
+
+    Ok(())
+}
+
 /// Checks that all the transaction receipts related to account address can be loaded
 /// with the `get_account_transactions_receipts` method and the result will be
 /// same as expected.
@@ -219,6 +219,7 @@ impl TransactionsHistoryTestSetup {
             success: true,
             op: Some(transfer_to_new_op),
             fail_reason: None,
+            fail_trace: None,
             block_index,
             created_at: self.get_tx_time(),
             batch_id: None,
@@ -250,6 +251,7 @@ impl TransactionsHistoryTestSetup {
             success: block_index.is_some(),
             op: Some(transfer_op),
             fail_reason: None,
+            fail_trace: None,
             block_index,
             created_at: self.get_tx_time(),
             batch_id: None,
@@ -280,6 +282,7 @@ impl TransactionsHistoryTestSetup {
             success: true,
             op: Some(withdraw_op),
             fail_reason: None,
+            fail_trace: None,
             block_index,
             created_at: self.get_tx_time(),
             batch_id: None,
@@ -299,6 +302,7 @@ impl TransactionsHistoryTestSetup {
             success: true,
             op: Some(close_op),
             fail_reason: None,
+            fail_trace: None,
             block_index,
             created_at: self.get_tx_time(),
             batch_id: None,
@@ -324,6 +328,7 @@ impl TransactionsHistoryTestSetup {
             success: true,
             op: Some(change_pubkey_op),
             fail_reason: None,
+            fail_trace: None,
             block_index,
             created_at: self.get_tx_time(),
             batch_id: None,
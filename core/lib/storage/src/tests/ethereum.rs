@@ -1,15 +1,19 @@
 // Built-in deps
-use std::str::FromStr;
+use std::{str::FromStr, time::Duration};
 // External imports
 use zksync_basic_types::{H256, U256};
 // Workspace imports
 use zksync_crypto::Fr;
 use zksync_types::{
-    ethereum::{ETHOperation, OperationType},
+    aggregated_operations::{
+        AggregatedActionType, AggregatedOperation, BlocksCommitOperation, BlocksExecuteOperation,
+    },
+    ethereum::{ContractUpgradeEvent, ContractUpgradeStage, ETHOperation, OperationType},
     Action, Operation,
     {block::Block, BlockNumber},
 };
 // Local imports
+use crate::admin::{ETH_OPERATION_DROP, ETH_OPERATION_REQUEUE};
 use crate::tests::db_test;
 use crate::{chain::block::BlockSchema, ethereum::EthereumSchema, QueryResult, StorageProcessor};
 use num::BigUint;
@@ -374,3 +378,579 @@ async fn ethereum_gas_update(mut storage: StorageProcessor<'_>) -> QueryResult<(
 
     Ok(())
 }
+
+/// Stores the aggregated operation and sends the Ethereum transaction for it.
+/// Returns the hash of the sent transaction.
+pub(crate) async fn send_aggregated_op(
+    storage: &mut StorageProcessor<'_>,
+    operation: AggregatedOperation,
+    hash: H256,
+) -> QueryResult<H256> {
+    let action_type = operation.get_action_type();
+    let (block_number, _) = operation.get_block_range();
+    storage
+        .chain()
+        .operations_schema()
+        .store_aggregated_action(operation)
+        .await?;
+    let (op_id, _) = storage
+        .chain()
+        .operations_schema()
+        .get_aggregated_op_that_affects_block(action_type, block_number)
+        .await?
+        .expect("Aggregated operation must be stored");
+
+    let response = storage
+        .ethereum_schema()
+        .save_new_eth_tx(
+            action_type,
+            Some(op_id),
+            100,
+            1000u32.into(),
+            Default::default(),
+        )
+        .await?;
+    storage
+        .ethereum_schema()
+        .add_hash_entry(response.id, &hash)
+        .await?;
+
+    Ok(hash)
+}
+
+/// Checks the L1 status of the aggregated operations affecting the block:
+/// the queue position, sent hashes, confirmation and the finality latency.
+#[db_test]
+async fn aggregated_op_status(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    storage.ethereum_schema().initialize_eth_data().await?;
+
+    let block = get_commit_operation(1).block;
+
+    // There are no aggregated operations yet.
+    let status = storage
+        .ethereum_schema()
+        .load_aggregated_op_status(AggregatedActionType::CommitBlocks, 1)
+        .await?;
+    assert!(status.is_none());
+    assert!(storage
+        .ethereum_schema()
+        .average_finality_latency(10)
+        .await?
+        .is_none());
+
+    let commit_hash = send_aggregated_op(
+        &mut storage,
+        AggregatedOperation::CommitBlocks(BlocksCommitOperation {
+            last_committed_block: get_commit_operation(0).block,
+            blocks: vec![block.clone()],
+        }),
+        H256::from_low_u64_ne(1),
+    )
+    .await?;
+    let execute_hash = send_aggregated_op(
+        &mut storage,
+        AggregatedOperation::ExecuteBlocks(BlocksExecuteOperation {
+            blocks: vec![block],
+        }),
+        H256::from_low_u64_ne(2),
+    )
+    .await?;
+
+    // Commit operation is sent, but not confirmed.
+    let commit_status = storage
+        .ethereum_schema()
+        .load_aggregated_op_status(AggregatedActionType::CommitBlocks, 1)
+        .await?
+        .expect("Commit operation must be loaded");
+    assert_eq!(commit_status.confirmed, Some(false));
+    assert_eq!(commit_status.final_hash, None);
+    let eth_op_id = commit_status.eth_op_id.expect("Commit must be sent");
+    assert_eq!(
+        storage
+            .ethereum_schema()
+            .load_eth_tx_hashes(eth_op_id)
+            .await?,
+        vec![commit_hash]
+    );
+
+    // Execute operation waits for the commit one.
+    let execute_status = storage
+        .ethereum_schema()
+        .load_aggregated_op_status(AggregatedActionType::ExecuteBlocks, 1)
+        .await?
+        .expect("Execute operation must be loaded");
+    assert_eq!(
+        storage
+            .ethereum_schema()
+            .count_unconfirmed_aggregated_ops_before(commit_status.op_id)
+            .await?,
+        0
+    );
+    assert_eq!(
+        storage
+            .ethereum_schema()
+            .count_unconfirmed_aggregated_ops_before(execute_status.op_id)
+            .await?,
+        1
+    );
+
+    // After the commit confirmation the execute operation is the first in the queue.
+    storage
+        .ethereum_schema()
+        .confirm_eth_tx(&commit_hash)
+        .await?;
+    let commit_status = storage
+        .ethereum_schema()
+        .load_aggregated_op_status(AggregatedActionType::CommitBlocks, 1)
+        .await?
+        .unwrap();
+    assert_eq!(commit_status.confirmed, Some(true));
+    assert_eq!(
+        commit_status.final_hash,
+        Some(commit_hash.as_bytes().to_vec())
+    );
+    assert_eq!(
+        storage
+            .ethereum_schema()
+            .count_unconfirmed_aggregated_ops_before(execute_status.op_id)
+            .await?,
+        0
+    );
+
+    // Finality latency is known once the block is executed.
+    assert!(storage
+        .ethereum_schema()
+        .average_finality_latency(10)
+        .await?
+        .is_none());
+    storage
+        .ethereum_schema()
+        .confirm_eth_tx(&execute_hash)
+        .await?;
+    // The test runs in a single database transaction, so the operations are created
+    // and confirmed at the same moment.
+    assert_eq!(
+        storage
+            .ethereum_schema()
+            .average_finality_latency(10)
+            .await?,
+        Some(Duration::from_secs(0))
+    );
+    let execute_status = storage
+        .ethereum_schema()
+        .load_aggregated_op_status(AggregatedActionType::ExecuteBlocks, 1)
+        .await?
+        .unwrap();
+    assert_eq!(execute_status.confirmed, Some(true));
+    assert_eq!(
+        execute_status.final_hash,
+        Some(execute_hash.as_bytes().to_vec())
+    );
+
+    // Once the block is aggregated again, the latest operation is loaded.
+    storage
+        .chain()
+        .operations_schema()
+        .store_aggregated_action(AggregatedOperation::CommitBlocks(BlocksCommitOperation {
+            last_committed_block: get_commit_operation(0).block,
+            blocks: vec![get_commit_operation(1).block],
+        }))
+        .await?;
+    let new_commit_status = storage
+        .ethereum_schema()
+        .load_aggregated_op_status(AggregatedActionType::CommitBlocks, 1)
+        .await?
+        .unwrap();
+    assert!(new_commit_status.op_id > commit_status.op_id);
+    assert_eq!(new_commit_status.eth_op_id, None);
+    assert_eq!(new_commit_status.confirmed, None);
+
+    Ok(())
+}
+
+/// Checks that the aggregated operations are counted as unsent until they're bound
+/// to the Ethereum transactions.
+#[db_test]
+async fn unsent_aggregated_operations(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    storage.ethereum_schema().initialize_eth_data().await?;
+
+    let block = get_commit_operation(1).block;
+    let commit_operation = AggregatedOperation::CommitBlocks(BlocksCommitOperation {
+        last_committed_block: get_commit_operation(0).block,
+        blocks: vec![block.clone()],
+    });
+    storage
+        .chain()
+        .operations_schema()
+        .store_aggregated_action(commit_operation)
+        .await?;
+    assert_eq!(
+        storage
+            .chain()
+            .operations_schema()
+            .count_unsent_aggregated_operations()
+            .await?,
+        1
+    );
+
+    // Once the operation is sent, it's not counted anymore.
+    let (op_id, _) = storage
+        .chain()
+        .operations_schema()
+        .get_aggregated_op_that_affects_block(AggregatedActionType::CommitBlocks, 1)
+        .await?
+        .expect("Aggregated operation must be stored");
+    storage
+        .ethereum_schema()
+        .save_new_eth_tx(
+            AggregatedActionType::CommitBlocks,
+            Some(op_id),
+            100,
+            1000u32.into(),
+            Default::default(),
+        )
+        .await?;
+    assert_eq!(
+        storage
+            .chain()
+            .operations_schema()
+            .count_unsent_aggregated_operations()
+            .await?,
+        0
+    );
+
+    send_aggregated_op(
+        &mut storage,
+        AggregatedOperation::ExecuteBlocks(BlocksExecuteOperation {
+            blocks: vec![block],
+        }),
+        H256::from_low_u64_ne(1),
+    )
+    .await?;
+    assert_eq!(
+        storage
+            .chain()
+            .operations_schema()
+            .count_unsent_aggregated_operations()
+            .await?,
+        0
+    );
+
+    Ok(())
+}
+
+/// Checks that only the latest sent operation can be requeued or dropped, and that
+/// its nonce is not reused afterwards, since it's taken by the cancelling transaction.
+#[db_test]
+async fn requeue_and_drop_operations(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    storage.ethereum_schema().initialize_eth_data().await?;
+
+    let block = get_commit_operation(1).block;
+    send_aggregated_op(
+        &mut storage,
+        AggregatedOperation::CommitBlocks(BlocksCommitOperation {
+            last_committed_block: get_commit_operation(0).block,
+            blocks: vec![block.clone()],
+        }),
+        H256::from_low_u64_ne(1),
+    )
+    .await?;
+    send_aggregated_op(
+        &mut storage,
+        AggregatedOperation::ExecuteBlocks(BlocksExecuteOperation {
+            blocks: vec![block],
+        }),
+        H256::from_low_u64_ne(2),
+    )
+    .await?;
+
+    let unconfirmed = storage
+        .ethereum_schema()
+        .load_unconfirmed_operations()
+        .await?;
+    assert_eq!(unconfirmed.len(), 2);
+    let (commit_op, execute_op) = (unconfirmed[0].clone(), unconfirmed[1].clone());
+    assert_eq!(storage.ethereum_schema().load_eth_params().await?.nonce, 2);
+
+    // The commit operation isn't the latest one, so it can't be handled.
+    assert!(storage
+        .ethereum_schema()
+        .requeue_eth_operation(commit_op.id)
+        .await
+        .is_err());
+    assert!(storage
+        .ethereum_schema()
+        .drop_eth_operation(commit_op.id)
+        .await
+        .is_err());
+
+    // Cancellation is stored until the operation is requeued.
+    let cancel_hash = H256::from_low_u64_ne(3);
+    storage
+        .ethereum_schema()
+        .save_eth_cancellation(execute_op.id, ETH_OPERATION_REQUEUE, &cancel_hash)
+        .await?;
+    let cancellations = storage.ethereum_schema().load_eth_cancellations().await?;
+    assert_eq!(cancellations.len(), 1);
+    assert_eq!(cancellations[0].eth_op_id, execute_op.id);
+    assert_eq!(cancellations[0].action, ETH_OPERATION_REQUEUE);
+    assert_eq!(cancellations[0].tx_hash, cancel_hash.as_bytes().to_vec());
+
+    // Requeued operation is forgotten, and its aggregated operation is unprocessed again.
+    storage
+        .ethereum_schema()
+        .requeue_eth_operation(execute_op.id)
+        .await?;
+    let unconfirmed = storage
+        .ethereum_schema()
+        .load_unconfirmed_operations()
+        .await?;
+    assert_eq!(unconfirmed.len(), 1);
+    assert_eq!(unconfirmed[0].id, commit_op.id);
+    let unprocessed = storage
+        .ethereum_schema()
+        .load_unprocessed_operations()
+        .await?;
+    assert_eq!(unprocessed.len(), 1);
+    assert_eq!(unprocessed[0].0, execute_op.op.as_ref().unwrap().0);
+    assert_eq!(storage.ethereum_schema().load_eth_params().await?.nonce, 2);
+    assert!(storage
+        .ethereum_schema()
+        .load_eth_cancellations()
+        .await?
+        .is_empty());
+
+    // Cancellation that didn't happen is removed.
+    storage
+        .ethereum_schema()
+        .save_eth_cancellation(commit_op.id, ETH_OPERATION_DROP, &cancel_hash)
+        .await?;
+    storage
+        .ethereum_schema()
+        .remove_eth_cancellation(commit_op.id)
+        .await?;
+    assert!(storage
+        .ethereum_schema()
+        .load_eth_cancellations()
+        .await?
+        .is_empty());
+
+    // Dropped operation is not loaded anymore, but its aggregated operation isn't sent again.
+    storage
+        .ethereum_schema()
+        .save_eth_cancellation(commit_op.id, ETH_OPERATION_DROP, &cancel_hash)
+        .await?;
+    storage
+        .ethereum_schema()
+        .drop_eth_operation(commit_op.id)
+        .await?;
+    assert!(storage
+        .ethereum_schema()
+        .load_unconfirmed_operations()
+        .await?
+        .is_empty());
+    let unprocessed = storage
+        .ethereum_schema()
+        .load_unprocessed_operations()
+        .await?;
+    assert_eq!(unprocessed.len(), 1);
+    assert_eq!(unprocessed[0].0, execute_op.op.as_ref().unwrap().0);
+    assert_eq!(storage.ethereum_schema().load_eth_params().await?.nonce, 2);
+    assert!(storage
+        .ethereum_schema()
+        .load_eth_cancellations()
+        .await?
+        .is_empty());
+
+    Ok(())
+}
+
+/// Checks that the requests for the Ethereum operations are replaced per operation
+/// and taken only once.
+#[db_test]
+async fn eth_operation_requests(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    storage.ethereum_schema().initialize_eth_data().await?;
+    let response = storage
+        .ethereum_schema()
+        .save_new_eth_tx(
+            AggregatedActionType::CommitBlocks,
+            None,
+            100,
+            1000u32.into(),
+            Default::default(),
+        )
+        .await?;
+
+    assert!(storage
+        .admin_schema()
+        .take_eth_operation_requests()
+        .await?
+        .is_empty());
+
+    storage
+        .admin_schema()
+        .request_eth_operation_action(response.id, ETH_OPERATION_DROP)
+        .await?;
+    storage
+        .admin_schema()
+        .request_eth_operation_action(response.id, ETH_OPERATION_REQUEUE)
+        .await?;
+    let requests = storage.admin_schema().take_eth_operation_requests().await?;
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].eth_op_id, response.id);
+    assert_eq!(requests[0].action, ETH_OPERATION_REQUEUE);
+
+    assert!(storage
+        .admin_schema()
+        .take_eth_operation_requests()
+        .await?
+        .is_empty());
+
+    Ok(())
+}
+
+/// Checks that the re-encoded tx data replaces the stored one.
+#[db_test]
+async fn update_eth_tx_data(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    storage.ethereum_schema().initialize_eth_data().await?;
+    send_aggregated_op(
+        &mut storage,
+        AggregatedOperation::CommitBlocks(BlocksCommitOperation {
+            last_committed_block: get_commit_operation(0).block,
+            blocks: vec![get_commit_operation(1).block],
+        }),
+        H256::from_low_u64_ne(1),
+    )
+    .await?;
+
+    let eth_op = storage
+        .ethereum_schema()
+        .load_unconfirmed_operations()
+        .await?
+        .pop_front()
+        .expect("no unconfirmed operation");
+    assert!(eth_op.encoded_tx_data.is_empty());
+
+    storage
+        .ethereum_schema()
+        .update_eth_tx_data(eth_op.id, &[1, 2, 3])
+        .await?;
+    let eth_op = storage
+        .ethereum_schema()
+        .load_unconfirmed_operations()
+        .await?
+        .pop_front()
+        .expect("no unconfirmed operation");
+    assert_eq!(eth_op.encoded_tx_data, vec![1, 2, 3]);
+
+    Ok(())
+}
+
+/// Checks that the contract upgrade stages are updated by the newer events only,
+/// only the completed upgrade can be confirmed, and the upgrades in preparation are
+/// considered started.
+#[db_test]
+async fn contract_upgrades(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let event = |version_id, stage, eth_block| ContractUpgradeEvent {
+        version_id,
+        stage,
+        eth_block,
+    };
+
+    assert!(storage
+        .ethereum_schema()
+        .load_last_completed_contract_upgrade()
+        .await?
+        .is_none());
+
+    for event in &[
+        event(1, ContractUpgradeStage::NoticePeriod, 10),
+        event(1, ContractUpgradeStage::Completed, 20),
+        event(2, ContractUpgradeStage::NoticePeriod, 30),
+        // Outdated event doesn't override the stage.
+        event(1, ContractUpgradeStage::Preparation, 15),
+    ] {
+        storage
+            .ethereum_schema()
+            .store_contract_upgrade_event(event)
+            .await?;
+    }
+
+    let upgrades = storage.ethereum_schema().load_contract_upgrades().await?;
+    assert_eq!(upgrades.len(), 2);
+    assert_eq!(
+        upgrades[0].stage,
+        ContractUpgradeStage::Completed.to_string()
+    );
+    assert_eq!(upgrades[0].eth_block, 20);
+    assert_eq!(
+        upgrades[1].stage,
+        ContractUpgradeStage::NoticePeriod.to_string()
+    );
+
+    // Upgrade in the notice period can't be confirmed.
+    assert!(storage
+        .ethereum_schema()
+        .confirm_contract_upgrade(2)
+        .await
+        .is_err());
+
+    let upgrade = storage
+        .ethereum_schema()
+        .load_last_completed_contract_upgrade()
+        .await?
+        .expect("no completed upgrade");
+    assert_eq!(upgrade.version_id, 1);
+    assert!(upgrade.confirmed_at.is_none());
+
+    storage
+        .ethereum_schema()
+        .confirm_contract_upgrade(1)
+        .await?;
+    let upgrade = storage
+        .ethereum_schema()
+        .load_last_completed_contract_upgrade()
+        .await?
+        .expect("no completed upgrade");
+    assert!(upgrade.confirmed_at.is_some());
+    // Upgrade can be confirmed only once.
+    assert!(storage
+        .ethereum_schema()
+        .confirm_contract_upgrade(1)
+        .await
+        .is_err());
+
+    // Upgrade in the notice period isn't started yet.
+    let upgrade = storage
+        .ethereum_schema()
+        .load_last_started_contract_upgrade()
+        .await?
+        .expect("no started upgrade");
+    assert_eq!(upgrade.version_id, 1);
+
+    storage
+        .ethereum_schema()
+        .store_contract_upgrade_event(&event(2, ContractUpgradeStage::Preparation, 40))
+        .await?;
+    let upgrade = storage
+        .ethereum_schema()
+        .load_last_started_contract_upgrade()
+        .await?
+        .expect("no started upgrade");
+    assert_eq!(upgrade.version_id, 2);
+    assert_eq!(upgrade.stage, ContractUpgradeStage::Preparation.to_string());
+
+    // Cancelled upgrade is ignored.
+    storage
+        .ethereum_schema()
+        .store_contract_upgrade_event(&event(2, ContractUpgradeStage::Cancelled, 50))
+        .await?;
+    let upgrade = storage
+        .ethereum_schema()
+        .load_last_started_contract_upgrade()
+        .await?
+        .expect("no started upgrade");
+    assert_eq!(upgrade.version_id, 1);
+
+    Ok(())
+}
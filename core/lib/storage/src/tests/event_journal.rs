@@ -0,0 +1,244 @@
+// External imports
+use serde_json::json;
+use sqlx::types::BigDecimal;
+// Workspace imports
+use zksync_types::{ethereum::OnchainWithdrawal, Address, H256};
+// Local imports
+use crate::event_journal::records::{JournalEventType, NewJournalEvent};
+use crate::tests::db_test;
+use crate::withdrawals::records::NewWithdrawal;
+use crate::{QueryResult, StorageProcessor};
+
+fn event(block_number: u32) -> NewJournalEvent {
+    NewJournalEvent {
+        event_type: JournalEventType::BlockSealed,
+        block_number,
+        data: json!({ "block": block_number }),
+    }
+}
+
+/// Checks that the events are delivered to the consumer until they are acknowledged.
+#[db_test]
+async fn consumer_offsets(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    assert_eq!(storage.event_journal_schema().get_last_event_id().await?, 0);
+
+    let mut transaction = storage.start_transaction().await?;
+    transaction
+        .event_journal_schema()
+        .append_events(&[event(1), event(2), event(3)])
+        .await?;
+    transaction.commit().await?;
+
+    let last_event_id = storage.event_journal_schema().get_last_event_id().await?;
+    assert_eq!(
+        storage
+            .event_journal_schema()
+            .get_consumer_offset("consumer")
+            .await?,
+        None
+    );
+    assert!(
+        storage
+            .event_journal_schema()
+            .register_consumer("consumer", 0)
+            .await?
+    );
+    let offset = storage
+        .event_journal_schema()
+        .get_consumer_offset("consumer")
+        .await?
+        .unwrap();
+    assert_eq!(offset, 0);
+
+    let events = storage
+        .event_journal_schema()
+        .load_events(offset, 2)
+        .await?;
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].event_type, "block_sealed");
+    assert_eq!(events[0].block_number, 1);
+    assert_eq!(events[0].data, json!({ "block": 1 }));
+    assert!(events[0].id < events[1].id);
+
+    // Unacknowledged events are delivered once again.
+    let offset = storage
+        .event_journal_schema()
+        .get_consumer_offset("consumer")
+        .await?
+        .unwrap();
+    assert_eq!(
+        storage
+            .event_journal_schema()
+            .load_events(offset, 2)
+            .await?,
+        events
+    );
+
+    assert!(
+        storage
+            .event_journal_schema()
+            .ack_events("consumer", events[1].id)
+            .await?
+    );
+    // Outdated acknowledgment doesn't move the offset back.
+    assert!(
+        storage
+            .event_journal_schema()
+            .ack_events("consumer", events[0].id)
+            .await?
+    );
+    let offset = storage
+        .event_journal_schema()
+        .get_consumer_offset("consumer")
+        .await?
+        .unwrap();
+    assert_eq!(offset, events[1].id);
+
+    let events = storage
+        .event_journal_schema()
+        .load_events(offset, 10)
+        .await?;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].id, last_event_id);
+
+    // Consumers must be registered, and the registered one keeps its offset.
+    assert!(
+        !storage
+            .event_journal_schema()
+            .ack_events("other", events[0].id)
+            .await?
+    );
+    assert!(
+        !storage
+            .event_journal_schema()
+            .register_consumer("consumer", 0)
+            .await?
+    );
+    assert_eq!(
+        storage
+            .event_journal_schema()
+            .get_consumer_offset("consumer")
+            .await?,
+        Some(offset)
+    );
+
+    Ok(())
+}
+
+/// Checks that the events are removed once acknowledged by all the consumers,
+/// and the IDs of the new events continue after the removed ones.
+#[db_test]
+async fn acked_events_removal(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    storage
+        .event_journal_schema()
+        .append_events(&[event(1), event(2), event(3)])
+        .await?;
+    let last_event_id = storage.event_journal_schema().get_last_event_id().await?;
+
+    // Events are kept while there are no consumers.
+    assert_eq!(
+        storage.event_journal_schema().remove_acked_events().await?,
+        0
+    );
+
+    storage
+        .event_journal_schema()
+        .register_consumer("first", 0)
+        .await?;
+    storage
+        .event_journal_schema()
+        .register_consumer("second", 0)
+        .await?;
+    storage
+        .event_journal_schema()
+        .ack_events("first", last_event_id)
+        .await?;
+    storage
+        .event_journal_schema()
+        .ack_events("second", last_event_id - 1)
+        .await?;
+    assert_eq!(
+        storage.event_journal_schema().remove_acked_events().await?,
+        2
+    );
+
+    // Events of the removed consumer aren't kept.
+    assert!(
+        storage
+            .event_journal_schema()
+            .remove_consumer("second")
+            .await?
+    );
+    assert_eq!(
+        storage.event_journal_schema().remove_acked_events().await?,
+        1
+    );
+    assert!(storage
+        .event_journal_schema()
+        .load_events(0, 10)
+        .await?
+        .is_empty());
+    assert_eq!(
+        storage.event_journal_schema().get_last_event_id().await?,
+        last_event_id
+    );
+
+    storage
+        .event_journal_schema()
+        .append_events(&[event(4)])
+        .await?;
+    let events = storage
+        .event_journal_schema()
+        .load_events(last_event_id, 10)
+        .await?;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].id, last_event_id + 1);
+
+    Ok(())
+}
+
+/// Checks that the completed withdrawals and the reverted blocks are recorded in the journal.
+#[db_test]
+async fn state_change_events(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    storage
+        .withdrawals_schema()
+        .store_withdrawal(NewWithdrawal {
+            tx_hash: vec![1; 32],
+            block_number: 1,
+            address: Address::repeat_byte(0x10).as_bytes().to_vec(),
+            to_address: Address::repeat_byte(0x20).as_bytes().to_vec(),
+            token: 0,
+            amount: BigDecimal::from(1000),
+        })
+        .await?;
+    let transferred = OnchainWithdrawal {
+        owner: Address::repeat_byte(0x20),
+        token: 0,
+        amount: 1000u32.into(),
+    };
+    storage
+        .withdrawals_schema()
+        .complete_withdrawals(1, 1, &H256::repeat_byte(0x30), &[transferred.clone()])
+        .await?;
+    // Withdrawals are finalized only once.
+    storage
+        .withdrawals_schema()
+        .complete_withdrawals(1, 1, &H256::repeat_byte(0x30), &[transferred])
+        .await?;
+
+    storage.chain().block_schema().revert_blocks(0).await?;
+
+    let events = storage.event_journal_schema().load_events(0, 10).await?;
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].event_type, "withdrawal_finalized");
+    assert_eq!(events[0].block_number, 1);
+    assert_eq!(events[0].data["amount"], json!("1000"));
+    assert_eq!(
+        events[0].data["execute_tx_hash"],
+        json!(format!("0x{}", hex::encode([0x30; 32])))
+    );
+    assert_eq!(events[1].event_type, "blocks_reverted");
+    assert_eq!(events[1].block_number, 0);
+
+    Ok(())
+}
@@ -0,0 +1,90 @@
+// Built-in imports
+use std::str::FromStr;
+// External imports
+use chrono::{Duration, Utc};
+use sqlx::types::BigDecimal;
+// Workspace imports
+// Local imports
+use crate::fee_history::records::NewFeeQuote;
+use crate::tests::db_test;
+use crate::{QueryResult, StorageProcessor};
+
+fn fee_quote(token_id: i32, fee_type: &str, total_fee: &str) -> NewFeeQuote {
+    let decimal = |value: &str| BigDecimal::from_str(value).unwrap();
+    NewFeeQuote {
+        token_id,
+        fee_type: fee_type.into(),
+        gas_tx_amount: decimal("2000"),
+        gas_price_wei: decimal("10000000000"),
+        gas_fee: decimal("1000"),
+        zkp_fee: decimal("500"),
+        total_fee: decimal(total_fee),
+        token_price_usd: decimal("1.5"),
+        eth_price_usd: decimal("600"),
+    }
+}
+
+/// Checks that the fee quotes are stored and filtered by the token, fee type and time.
+#[db_test]
+async fn fee_history(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let from = Utc::now() - Duration::hours(1);
+    let to = Utc::now() + Duration::hours(1);
+
+    let quotes = vec![
+        fee_quote(0, "Transfer", "1500"),
+        fee_quote(0, "Withdraw", "2500"),
+        fee_quote(1, "Transfer", "3500"),
+        fee_quote(0, "Transfer", "4500"),
+    ];
+    for quote in quotes.clone() {
+        storage.fee_history_schema().store_fee_quote(quote).await?;
+    }
+
+    // Newest quotes go first.
+    let history = storage
+        .fee_history_schema()
+        .load_fee_history(0, None, from, to, 10)
+        .await?;
+    let total_fees: Vec<_> = history
+        .iter()
+        .map(|quote| quote.total_fee.clone())
+        .collect();
+    assert_eq!(
+        total_fees,
+        vec![
+            quotes[3].total_fee.clone(),
+            quotes[1].total_fee.clone(),
+            quotes[0].total_fee.clone(),
+        ]
+    );
+    assert_eq!(history[0].gas_price_wei, quotes[3].gas_price_wei);
+    assert_eq!(history[0].eth_price_usd, quotes[3].eth_price_usd);
+
+    let history = storage
+        .fee_history_schema()
+        .load_fee_history(0, Some("Transfer"), from, to, 1)
+        .await?;
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].total_fee, quotes[3].total_fee);
+
+    // Quotes outside of the time range are not loaded.
+    assert!(storage
+        .fee_history_schema()
+        .load_fee_history(0, None, to, to + Duration::hours(1), 10)
+        .await?
+        .is_empty());
+
+    // Old quotes are removed.
+    let removed = storage
+        .fee_history_schema()
+        .remove_fee_history_before(to)
+        .await?;
+    assert_eq!(removed, 4);
+    assert!(storage
+        .fee_history_schema()
+        .load_fee_history(1, None, from, to, 10)
+        .await?
+        .is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,78 @@
+// Built-in imports
+// External imports
+// Workspace imports
+// Local imports
+use crate::tests::db_test;
+use crate::{QueryResult, StorageProcessor};
+
+/// Checks that the leadership lock is held by only one session at a time.
+#[db_test]
+async fn leadership_lock(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    const LOCK_ID: i64 = 1001;
+
+    assert!(
+        storage
+            .leader_election_schema()
+            .try_acquire_lock(LOCK_ID)
+            .await?
+    );
+    assert!(
+        storage
+            .leader_election_schema()
+            .is_lock_held(LOCK_ID)
+            .await?
+    );
+    assert!(
+        !storage
+            .leader_election_schema()
+            .is_lock_held(LOCK_ID + 1)
+            .await?
+    );
+
+    // Other sessions can't acquire it until it's released.
+    let mut standby = StorageProcessor::establish_connection().await?;
+    assert!(
+        !standby
+            .leader_election_schema()
+            .try_acquire_lock(LOCK_ID)
+            .await?
+    );
+    assert!(
+        !standby
+            .leader_election_schema()
+            .is_lock_held(LOCK_ID)
+            .await?
+    );
+
+    Ok(())
+}
+
+/// Checks that the writes of the former leader are fenced by the leader epoch.
+#[db_test]
+async fn leader_epoch(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let first_epoch = storage
+        .leader_election_schema()
+        .start_leader_epoch()
+        .await?;
+    storage
+        .leader_election_schema()
+        .check_leader_epoch(first_epoch)
+        .await?;
+
+    let second_epoch = storage
+        .leader_election_schema()
+        .start_leader_epoch()
+        .await?;
+    assert_eq!(second_epoch, first_epoch + 1);
+    storage
+        .leader_election_schema()
+        .check_leader_epoch(second_epoch)
+        .await?;
+    assert!(storage
+        .leader_election_schema()
+        .check_leader_epoch(first_epoch)
+        .await
+        .is_err());
+
+    Ok(())
+}
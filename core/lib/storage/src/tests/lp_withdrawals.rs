@@ -0,0 +1,209 @@
+// Built-in imports
+use std::str::FromStr;
+// External imports
+use sqlx::types::BigDecimal;
+// Workspace imports
+use zksync_types::{tx::TxHash, Address, H256};
+// Local imports
+use crate::tests::db_test;
+use crate::{QueryResult, StorageProcessor};
+
+/// Checks that the withdrawals are stored, listed for the provider and marked as paid out.
+#[db_test]
+async fn lp_withdrawals(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let decimal = |value: &str| BigDecimal::from_str(value).unwrap();
+    let tx_hash = |byte: u8| TxHash::from_str(&format!("sync-tx:{}", hex::encode([byte; 32])));
+    let tx_hash_1 = tx_hash(1).unwrap();
+    let tx_hash_2 = tx_hash(2).unwrap();
+    let account = Address::repeat_byte(0x10);
+    let lp = Address::repeat_byte(0x20);
+    let other_lp = Address::repeat_byte(0x30);
+    let recipient = Address::repeat_byte(0x40);
+
+    assert!(storage
+        .lp_withdrawals_schema()
+        .load_withdrawal(&tx_hash_1)
+        .await?
+        .is_none());
+
+    let first_id = storage
+        .lp_withdrawals_schema()
+        .store_withdrawal(
+            &tx_hash_1,
+            account,
+            lp,
+            recipient,
+            0,
+            decimal("1000"),
+            decimal("990"),
+        )
+        .await?;
+    storage
+        .lp_withdrawals_schema()
+        .store_withdrawal(
+            &tx_hash_2,
+            account,
+            lp,
+            recipient,
+            1,
+            decimal("500"),
+            decimal("495"),
+        )
+        .await?;
+
+    let withdrawal = storage
+        .lp_withdrawals_schema()
+        .load_withdrawal(&tx_hash_1)
+        .await?
+        .expect("withdrawal must be stored");
+    assert_eq!(withdrawal.id, first_id);
+    assert_eq!(withdrawal.lp_address, lp.as_bytes().to_vec());
+    assert_eq!(withdrawal.payout_amount, decimal("990"));
+    // The transfer is not executed yet.
+    assert_eq!(withdrawal.block_number, None);
+    assert_eq!(withdrawal.l1_tx_hash, None);
+
+    let withdrawals = storage
+        .lp_withdrawals_schema()
+        .load_lp_withdrawals(lp, 0, true, 10)
+        .await?;
+    assert_eq!(withdrawals.len(), 2);
+    let withdrawals = storage
+        .lp_withdrawals_schema()
+        .load_lp_withdrawals(lp, first_id, true, 10)
+        .await?;
+    assert_eq!(withdrawals.len(), 1);
+    assert_eq!(withdrawals[0].tx_hash, tx_hash_2.as_ref().to_vec());
+    assert!(storage
+        .lp_withdrawals_schema()
+        .load_lp_withdrawals(other_lp, 0, false, 10)
+        .await?
+        .is_empty());
+
+    // Only the provider the withdrawal is handed off to can pay it out, and only once.
+    let l1_tx_hash = H256::repeat_byte(0x50);
+    assert!(
+        !storage
+            .lp_withdrawals_schema()
+            .mark_paid(&tx_hash_1, other_lp, l1_tx_hash)
+            .await?
+    );
+    assert!(
+        storage
+            .lp_withdrawals_schema()
+            .mark_paid(&tx_hash_1, lp, l1_tx_hash)
+            .await?
+    );
+    assert!(
+        !storage
+            .lp_withdrawals_schema()
+            .mark_paid(&tx_hash_1, lp, l1_tx_hash)
+            .await?
+    );
+    // The same payout transaction can't be reported for another withdrawal.
+    assert!(
+        !storage
+            .lp_withdrawals_schema()
+            .mark_paid(&tx_hash_2, lp, l1_tx_hash)
+            .await?
+    );
+
+    let unpaid = storage
+        .lp_withdrawals_schema()
+        .load_lp_withdrawals(lp, 0, true, 10)
+        .await?;
+    assert_eq!(unpaid.len(), 1);
+    assert_eq!(unpaid[0].tx_hash, tx_hash_2.as_ref().to_vec());
+    let all = storage
+        .lp_withdrawals_schema()
+        .load_lp_withdrawals(lp, 0, false, 10)
+        .await?;
+    assert_eq!(all.len(), 2);
+    assert_eq!(all[0].l1_tx_hash, Some(l1_tx_hash.as_bytes().to_vec()));
+    assert!(all[0].paid_at.is_some());
+
+    // Reported payout is waiting for the L1 confirmation.
+    let unconfirmed = storage
+        .lp_withdrawals_schema()
+        .load_unconfirmed_payouts(10)
+        .await?;
+    assert_eq!(unconfirmed.len(), 1);
+    assert_eq!(unconfirmed[0].tx_hash, tx_hash_1.as_ref().to_vec());
+    assert_eq!(unconfirmed[0].payout_confirmed_at, None);
+
+    // Failed payout is discarded, so the provider has to pay out the withdrawal again.
+    storage
+        .lp_withdrawals_schema()
+        .reject_payout(&tx_hash_1, l1_tx_hash)
+        .await?;
+    let withdrawal = storage
+        .lp_withdrawals_schema()
+        .load_withdrawal(&tx_hash_1)
+        .await?
+        .unwrap();
+    assert_eq!(withdrawal.l1_tx_hash, None);
+    assert_eq!(withdrawal.paid_at, None);
+    assert!(storage
+        .lp_withdrawals_schema()
+        .load_unconfirmed_payouts(10)
+        .await?
+        .is_empty());
+
+    let new_l1_tx_hash = H256::repeat_byte(0x51);
+    assert!(
+        storage
+            .lp_withdrawals_schema()
+            .mark_paid(&tx_hash_1, lp, new_l1_tx_hash)
+            .await?
+    );
+    // Confirmation of a stale payout transaction is ignored.
+    storage
+        .lp_withdrawals_schema()
+        .confirm_payout(&tx_hash_1, l1_tx_hash)
+        .await?;
+    assert_eq!(
+        storage
+            .lp_withdrawals_schema()
+            .load_unconfirmed_payouts(10)
+            .await?
+            .len(),
+        1
+    );
+    storage
+        .lp_withdrawals_schema()
+        .confirm_payout(&tx_hash_1, new_l1_tx_hash)
+        .await?;
+    assert!(storage
+        .lp_withdrawals_schema()
+        .load_unconfirmed_payouts(10)
+        .await?
+        .is_empty());
+    // Confirmed payout can't be rejected anymore.
+    storage
+        .lp_withdrawals_schema()
+        .reject_payout(&tx_hash_1, new_l1_tx_hash)
+        .await?;
+    let withdrawal = storage
+        .lp_withdrawals_schema()
+        .load_withdrawal(&tx_hash_1)
+        .await?
+        .unwrap();
+    assert_eq!(
+        withdrawal.l1_tx_hash,
+        Some(new_l1_tx_hash.as_bytes().to_vec())
+    );
+    assert!(withdrawal.payout_confirmed_at.is_some());
+
+    // Withdrawal is removed if the transfer is not accepted by the mempool.
+    storage
+        .lp_withdrawals_schema()
+        .delete_withdrawal(&tx_hash_2)
+        .await?;
+    assert!(storage
+        .lp_withdrawals_schema()
+        .load_withdrawal(&tx_hash_2)
+        .await?
+        .is_none());
+
+    Ok(())
+}
@@ -30,12 +30,21 @@
 use zksync_crypto::rand::{SeedableRng, XorShiftRng};
 // use diesel::Connection;
 
+mod accounting;
+mod admin;
+mod backup;
 pub(crate) mod chain;
 mod config;
 mod data_restore;
 mod ethereum;
+mod event_journal;
+mod fee_history;
+mod leader_election;
+mod lp_withdrawals;
 mod prover;
 mod tokens;
+mod tx_traces;
+mod withdrawals;
 
 pub use db_test_macro::test as db_test;
 
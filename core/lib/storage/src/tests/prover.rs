@@ -3,10 +3,21 @@ use std::time::Duration;
 // External imports
 // Workspace imports
 use zksync_config::ConfigurationOptions;
-use zksync_types::{block::PendingBlock, Action};
+use zksync_types::{
+    block::PendingBlock,
+    prover::{ProverJobType, AGGREGATED_PROOF_JOB_PRIORITY, SINGLE_PROOF_JOB_PRIORITY},
+    Action,
+};
 // Local imports
 use crate::tests::{chain::utils::get_operation, db_test};
-use crate::{chain::block::BlockSchema, prover::ProverSchema, QueryResult, StorageProcessor};
+use crate::{
+    chain::{
+        block::BlockSchema,
+        operations::{records::NewExecutedPriorityOperation, OperationsSchema},
+    },
+    prover::ProverSchema,
+    QueryResult, StorageProcessor,
+};
 use zksync_basic_types::H256;
 
 /// Checks that the proof can be stored and loaded.
@@ -308,3 +319,356 @@ async fn unstarted_prover_jobs_count(mut storage: StorageProcessor<'_>) -> Query
     //
     // Ok(())
 }
+
+/// Checks that the prover job is leased to the single prover, and is given to
+/// another prover only after the lease expiration.
+#[db_test]
+async fn prover_job_leases(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    ProverSchema(&mut storage)
+        .add_prover_job_to_job_queue(
+            1,
+            1,
+            Default::default(),
+            SINGLE_PROOF_JOB_PRIORITY,
+            ProverJobType::SingleProof,
+        )
+        .await?;
+
+    // Job is given to the first prover only.
+    let job = ProverSchema(&mut storage)
+        .get_idle_prover_job_from_job_queue("prover_1")
+        .await?
+        .expect("Job should be in the queue");
+    assert!(ProverSchema(&mut storage)
+        .get_idle_prover_job_from_job_queue("prover_2")
+        .await?
+        .is_none());
+    assert_eq!(ProverSchema(&mut storage).pending_jobs_count().await?, 0);
+
+    // Only the lease holder can extend the lease.
+    assert!(
+        ProverSchema(&mut storage)
+            .record_prover_is_working(job.job_id, "prover_1")
+            .await?
+    );
+    assert!(
+        !ProverSchema(&mut storage)
+            .record_prover_is_working(job.job_id, "prover_2")
+            .await?
+    );
+
+    // Lease is not expired yet.
+    ProverSchema(&mut storage)
+        .mark_stale_jobs_as_idle(Duration::from_secs(3600))
+        .await?;
+    assert_eq!(ProverSchema(&mut storage).pending_jobs_count().await?, 0);
+
+    // After the lease expiration job is given to another prover.
+    ProverSchema(&mut storage)
+        .mark_stale_jobs_as_idle(Duration::from_secs(0))
+        .await?;
+    assert_eq!(ProverSchema(&mut storage).pending_jobs_count().await?, 1);
+    let reassigned_job = ProverSchema(&mut storage)
+        .get_idle_prover_job_from_job_queue("prover_2")
+        .await?
+        .expect("Job should be returned to the queue");
+    assert_eq!(reassigned_job.job_id, job.job_id);
+
+    // The first prover has lost the lease.
+    assert!(
+        !ProverSchema(&mut storage)
+            .record_prover_is_working(job.job_id, "prover_1")
+            .await?
+    );
+    assert!(
+        ProverSchema(&mut storage)
+            .record_prover_is_working(job.job_id, "prover_2")
+            .await?
+    );
+
+    Ok(())
+}
+
+/// Checks the order in which the prover jobs are given to the provers.
+#[db_test]
+async fn prover_jobs_order(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    for block in 1..=5 {
+        ProverSchema(&mut storage)
+            .add_prover_job_to_job_queue(
+                block,
+                block,
+                Default::default(),
+                SINGLE_PROOF_JOB_PRIORITY,
+                ProverJobType::SingleProof,
+            )
+            .await?;
+    }
+    // Block 4 contains the priority operation with the closest deadline, so it must be proven
+    // along with all the preceding blocks, the oldest first.
+    for (serial_id, (block_number, deadline_block)) in [(2, 200), (4, 100)].iter().enumerate() {
+        OperationsSchema(&mut storage)
+            .store_executed_priority_op(NewExecutedPriorityOperation {
+                block_number: *block_number,
+                block_index: 0,
+                operation: Default::default(),
+                from_account: Default::default(),
+                to_account: Default::default(),
+                priority_op_serialid: serial_id as i64,
+                deadline_block: *deadline_block,
+                eth_hash: vec![serial_id as u8; 32],
+                eth_block: 10,
+                created_at: chrono::Utc::now(),
+            })
+            .await?;
+    }
+    // Block 3 is pinned by the operator.
+    ProverSchema(&mut storage).pin_block(3).await?;
+    assert_eq!(
+        ProverSchema(&mut storage).load_pinned_blocks().await?,
+        vec![3]
+    );
+
+    let mut proven_blocks = Vec::new();
+    for prover_id in 0.. {
+        let prover_name = format!("prover_{}", prover_id);
+        match ProverSchema(&mut storage)
+            .get_idle_prover_job_from_job_queue(&prover_name)
+            .await?
+        {
+            Some(job) => proven_blocks.push(job.first_block),
+            None => break,
+        }
+    }
+    assert_eq!(proven_blocks, vec![3, 1, 2, 4, 5]);
+
+    // Aggregated proofs go before the single ones, unless the block is pinned.
+    ProverSchema(&mut storage).unpin_block(3).await?;
+    assert!(ProverSchema(&mut storage)
+        .load_pinned_blocks()
+        .await?
+        .is_empty());
+    ProverSchema(&mut storage)
+        .add_prover_job_to_job_queue(
+            5,
+            5,
+            Default::default(),
+            SINGLE_PROOF_JOB_PRIORITY,
+            ProverJobType::SingleProof,
+        )
+        .await?;
+    ProverSchema(&mut storage)
+        .add_prover_job_to_job_queue(
+            1,
+            4,
+            Default::default(),
+            AGGREGATED_PROOF_JOB_PRIORITY,
+            ProverJobType::AggregatedProof,
+        )
+        .await?;
+    let job = ProverSchema(&mut storage)
+        .get_idle_prover_job_from_job_queue("prover")
+        .await?
+        .expect("Job should be in the queue");
+    assert_eq!((job.first_block, job.last_block), (1, 4));
+
+    Ok(())
+}
+
+/// Checks that the invalid aggregated proof is removed and its job is returned to the queue.
+#[db_test]
+async fn aggregated_proof_job_requeue(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    // There is no such job.
+    assert!(
+        !ProverSchema(&mut storage)
+            .requeue_aggregated_proof_job(1, 2)
+            .await?
+    );
+
+    ProverSchema(&mut storage)
+        .add_prover_job_to_job_queue(
+            1,
+            2,
+            Default::default(),
+            AGGREGATED_PROOF_JOB_PRIORITY,
+            ProverJobType::AggregatedProof,
+        )
+        .await?;
+    let job = ProverSchema(&mut storage)
+        .get_idle_prover_job_from_job_queue("prover")
+        .await?
+        .expect("Job should be in the queue");
+    ProverSchema(&mut storage)
+        .store_aggregated_proof(job.job_id, 1, 2, &Default::default(), "prover")
+        .await?;
+    assert_eq!(ProverSchema(&mut storage).pending_jobs_count().await?, 0);
+
+    assert!(
+        ProverSchema(&mut storage)
+            .requeue_aggregated_proof_job(1, 2)
+            .await?
+    );
+    assert!(ProverSchema(&mut storage)
+        .load_aggregated_proof(1, 2)
+        .await?
+        .is_none());
+    let requeued_job = ProverSchema(&mut storage)
+        .get_idle_prover_job_from_job_queue("prover")
+        .await?
+        .expect("Job should be returned to the queue");
+    assert_eq!(requeued_job.job_id, job.job_id);
+
+    Ok(())
+}
+
+/// Checks that the restarted prover gets back the job it was working on.
+#[db_test]
+async fn prover_job_resume(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    for block in 1..=2 {
+        ProverSchema(&mut storage)
+            .add_prover_job_to_job_queue(
+                block,
+                block,
+                Default::default(),
+                SINGLE_PROOF_JOB_PRIORITY,
+                ProverJobType::SingleProof,
+            )
+            .await?;
+    }
+
+    let job = ProverSchema(&mut storage)
+        .get_idle_prover_job_from_job_queue("prover_1")
+        .await?
+        .expect("Job should be in the queue");
+    assert_eq!(job.first_block, 1);
+
+    // Prover is restarted before finishing the job and requests a new one.
+    let resumed_job = ProverSchema(&mut storage)
+        .get_idle_prover_job_from_job_queue("prover_1")
+        .await?
+        .expect("Leased job should be returned");
+    assert_eq!(resumed_job.job_id, job.job_id);
+    assert_eq!(ProverSchema(&mut storage).pending_jobs_count().await?, 1);
+
+    // The lease of the resumed job is renewed, so it's not taken from the prover right away,
+    // but still expires once the prover stops reporting.
+    sqlx::query(
+        "UPDATE prover_job_queue SET updated_at = now() - interval '2 hours' WHERE id = $1",
+    )
+    .bind(job.job_id)
+    .execute(storage.conn())
+    .await?;
+    ProverSchema(&mut storage)
+        .get_idle_prover_job_from_job_queue("prover_1")
+        .await?
+        .expect("Leased job should be returned");
+    ProverSchema(&mut storage)
+        .mark_stale_jobs_as_idle(Duration::from_secs(3600))
+        .await?;
+    assert_eq!(ProverSchema(&mut storage).pending_jobs_count().await?, 1);
+    ProverSchema(&mut storage)
+        .mark_stale_jobs_as_idle(Duration::from_secs(0))
+        .await?;
+    assert_eq!(ProverSchema(&mut storage).pending_jobs_count().await?, 2);
+
+    // The expired job is given to another prover.
+    let other_job = ProverSchema(&mut storage)
+        .get_idle_prover_job_from_job_queue("prover_2")
+        .await?
+        .expect("Job should be in the queue");
+    assert_eq!(other_job.job_id, job.job_id);
+
+    Ok(())
+}
+
+/// Checks that the provers statistics are collected.
+#[db_test]
+async fn provers_info(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    ProverSchema(&mut storage)
+        .add_prover_job_to_job_queue(
+            1,
+            1,
+            Default::default(),
+            SINGLE_PROOF_JOB_PRIORITY,
+            ProverJobType::SingleProof,
+        )
+        .await?;
+    ProverSchema(&mut storage)
+        .record_prover_seen("prover_2")
+        .await?;
+
+    // The first prover takes the job, the second one is idle.
+    let job = ProverSchema(&mut storage)
+        .get_idle_prover_job_from_job_queue("prover_1")
+        .await?
+        .expect("Job should be in the queue");
+    let provers = ProverSchema(&mut storage).load_provers().await?;
+    assert_eq!(provers.len(), 2);
+    assert_eq!(provers[0].name, "prover_1");
+    assert_eq!(provers[0].current_job_id, Some(job.job_id));
+    assert_eq!(provers[0].first_block, Some(1));
+    assert_eq!(provers[1].name, "prover_2");
+    assert_eq!(provers[1].current_job_id, None);
+
+    // Job is completed.
+    assert_eq!(
+        ProverSchema(&mut storage)
+            .load_job_lease_holder(job.job_id)
+            .await?,
+        Some("prover_1".to_string())
+    );
+    ProverSchema(&mut storage)
+        .store_proof(job.job_id, 1, &Default::default(), "prover_1")
+        .await?;
+    assert_eq!(
+        ProverSchema(&mut storage)
+            .load_job_lease_holder(job.job_id)
+            .await?,
+        None
+    );
+    let provers = ProverSchema(&mut storage).load_provers().await?;
+    assert_eq!(provers[0].jobs_completed, 1);
+    assert_eq!(provers[0].current_job_id, None);
+    assert_eq!(provers[1].jobs_completed, 0);
+
+    // Stopped provers are not listed.
+    ProverSchema(&mut storage)
+        .record_prover_stop("prover_2")
+        .await?;
+    let provers = ProverSchema(&mut storage).load_provers().await?;
+    assert_eq!(provers.len(), 1);
+    assert_eq!(provers[0].name, "prover_1");
+
+    Ok(())
+}
+
+/// Checks that the own secrets of the provers can be set, replaced and removed.
+#[db_test]
+async fn prover_credentials(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    assert!(ProverSchema(&mut storage)
+        .load_prover_secrets()
+        .await?
+        .is_empty());
+
+    ProverSchema(&mut storage)
+        .store_prover_secret("prover_1", "secret_1")
+        .await?;
+    ProverSchema(&mut storage)
+        .store_prover_secret("prover_1", "secret_2")
+        .await?;
+    ProverSchema(&mut storage)
+        .store_prover_secret("prover_2", "secret_3")
+        .await?;
+    let secrets = ProverSchema(&mut storage).load_prover_secrets().await?;
+    assert_eq!(secrets.len(), 2);
+    assert_eq!(secrets["prover_1"], "secret_2");
+    assert_eq!(secrets["prover_2"], "secret_3");
+
+    ProverSchema(&mut storage)
+        .remove_prover_secret("prover_1")
+        .await?;
+    let secrets = ProverSchema(&mut storage).load_prover_secrets().await?;
+    assert_eq!(secrets.len(), 1);
+    assert!(!secrets.contains_key("prover_1"));
+
+    Ok(())
+}
@@ -150,3 +150,137 @@ async fn test_ticker_price(mut storage: StorageProcessor<'_>) -> QueryResult<()>
 
     Ok(())
 }
+
+/// Checks that the withdrawal gas limits are stored only for the tokens they're set for.
+#[db_test]
+async fn withdrawal_gas_limits(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let token = Token {
+        id: 1,
+        address: "0000000000000000000000000000000000000001".parse().unwrap(),
+        symbol: "ABC".into(),
+        decimals: 9,
+    };
+    storage.tokens_schema().store_token(token.clone()).await?;
+    assert!(storage
+        .tokens_schema()
+        .load_withdrawal_gas_limits()
+        .await?
+        .is_empty());
+
+    storage
+        .tokens_schema()
+        .set_withdrawal_gas_limit(1, Some(250_000))
+        .await?;
+    let gas_limits = storage.tokens_schema().load_withdrawal_gas_limits().await?;
+    assert_eq!(gas_limits.len(), 1);
+    assert_eq!(gas_limits[&1], 250_000);
+    // The token itself is not affected.
+    assert_eq!(
+        storage.tokens_schema().get_token(TokenLike::Id(1)).await?,
+        Some(token)
+    );
+
+    storage
+        .tokens_schema()
+        .set_withdrawal_gas_limit(1, None)
+        .await?;
+    assert!(storage
+        .tokens_schema()
+        .load_withdrawal_gas_limits()
+        .await?
+        .is_empty());
+
+    Ok(())
+}
+
+/// Checks that the token metadata is not updated from the contract once it's overridden.
+#[db_test]
+async fn token_metadata_override(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let token = Token {
+        id: 1,
+        address: "0000000000000000000000000000000000000001".parse().unwrap(),
+        symbol: "ABC".into(),
+        decimals: 9,
+    };
+    storage.tokens_schema().store_token(token.clone()).await?;
+    assert_eq!(
+        storage
+            .tokens_schema()
+            .load_tokens_for_metadata_update()
+            .await?,
+        vec![token.clone()]
+    );
+
+    assert!(
+        storage
+            .tokens_schema()
+            .update_token_symbol(1, "DEF")
+            .await?
+    );
+    let updated_token = storage
+        .tokens_schema()
+        .get_token(TokenLike::Id(1))
+        .await?
+        .unwrap();
+    assert_eq!(updated_token.symbol, "DEF");
+    assert_eq!(updated_token.decimals, 9);
+
+    // Symbol used by another token is not taken.
+    storage
+        .tokens_schema()
+        .store_token(Token {
+            id: 2,
+            address: "0000000000000000000000000000000000000002".parse().unwrap(),
+            symbol: "XYZ".into(),
+            decimals: 18,
+        })
+        .await?;
+    assert!(
+        !storage
+            .tokens_schema()
+            .update_token_symbol(1, "XYZ")
+            .await?
+    );
+
+    storage
+        .tokens_schema()
+        .override_token_metadata(1, "GHI", 6)
+        .await?;
+    assert_eq!(
+        storage
+            .tokens_schema()
+            .load_tokens_for_metadata_update()
+            .await?
+            .into_iter()
+            .map(|token| token.id)
+            .collect::<Vec<_>>(),
+        vec![2]
+    );
+    assert!(
+        !storage
+            .tokens_schema()
+            .update_token_symbol(1, "DEF")
+            .await?
+    );
+    let overridden_token = storage
+        .tokens_schema()
+        .get_token(TokenLike::Id(1))
+        .await?
+        .unwrap();
+    assert_eq!(overridden_token.symbol, "GHI");
+    assert_eq!(overridden_token.decimals, 6);
+
+    // Once the override is reset, the token is updated again.
+    storage
+        .tokens_schema()
+        .reset_token_metadata_override(1)
+        .await?;
+    assert!(
+        storage
+            .tokens_schema()
+            .update_token_symbol(1, "DEF")
+            .await?
+    );
+
+    Ok(())
+}
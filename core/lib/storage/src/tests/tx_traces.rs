@@ -0,0 +1,71 @@
+// Built-in imports
+use std::str::FromStr;
+// External imports
+use num::BigUint;
+// Workspace imports
+use zksync_types::tx::{BalanceTrace, NonceTrace, TxFailureTrace, TxHash};
+// Local imports
+use crate::tests::db_test;
+use crate::{QueryResult, StorageProcessor};
+
+/// Checks that the failure traces are stored, overwritten and removed along with the reverted blocks.
+#[db_test]
+async fn failure_traces(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let tx_hash = TxHash::from_str(&format!("sync-tx:{}", hex::encode([1u8; 32]))).unwrap();
+    let mut trace = TxFailureTrace {
+        reason: "Not enough balance".to_string(),
+        account_id: Some(1),
+        nonce: Some(NonceTrace {
+            expected: 0,
+            actual: 0,
+        }),
+        balance: Some(BalanceTrace {
+            token: 0,
+            required: BigUint::from(146u32),
+            available: BigUint::from(100u32),
+        }),
+        signature: None,
+        batch_rolled_back: false,
+    };
+
+    assert!(storage
+        .tx_traces_schema()
+        .load_failure_trace(&tx_hash)
+        .await?
+        .is_none());
+
+    storage
+        .tx_traces_schema()
+        .store_failure_trace(&tx_hash, 1, &trace)
+        .await?;
+    assert_eq!(
+        storage
+            .tx_traces_schema()
+            .load_failure_trace(&tx_hash)
+            .await?,
+        Some(trace.clone())
+    );
+
+    // Failed transaction of the pending block is stored once again with the sealed block.
+    trace.nonce = None;
+    storage
+        .tx_traces_schema()
+        .store_failure_trace(&tx_hash, 2, &trace)
+        .await?;
+    assert_eq!(
+        storage
+            .tx_traces_schema()
+            .load_failure_trace(&tx_hash)
+            .await?,
+        Some(trace)
+    );
+
+    storage.chain().block_schema().revert_blocks(1).await?;
+    assert!(storage
+        .tx_traces_schema()
+        .load_failure_trace(&tx_hash)
+        .await?
+        .is_none());
+
+    Ok(())
+}
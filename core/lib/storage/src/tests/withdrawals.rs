@@ -0,0 +1,118 @@
+// Built-in imports
+use std::str::FromStr;
+// External imports
+use sqlx::types::BigDecimal;
+// Workspace imports
+use zksync_types::{ethereum::OnchainWithdrawal, tx::TxHash, Address, H256};
+// Local imports
+use crate::tests::db_test;
+use crate::withdrawals::records::NewWithdrawal;
+use crate::{QueryResult, StorageProcessor};
+
+/// Checks that the withdrawals are stored and completed along with their blocks.
+#[db_test]
+async fn withdrawals(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let tx_hash = |byte: u8| TxHash::from_str(&format!("sync-tx:{}", hex::encode([byte; 32])));
+    let withdrawal = |tx_hash: &TxHash, block_number: i64| NewWithdrawal {
+        tx_hash: tx_hash.as_ref().to_vec(),
+        block_number,
+        address: Address::repeat_byte(0x10).as_bytes().to_vec(),
+        to_address: Address::repeat_byte(0x20).as_bytes().to_vec(),
+        token: 0,
+        amount: BigDecimal::from(1000),
+    };
+    let tx_hash_1 = tx_hash(1).unwrap();
+    let tx_hash_2 = tx_hash(2).unwrap();
+    let tx_hash_3 = tx_hash(3).unwrap();
+
+    assert!(storage
+        .withdrawals_schema()
+        .load_withdrawal(&tx_hash_1)
+        .await?
+        .is_none());
+
+    // Withdrawal of the pending block is stored once again with the sealed block.
+    storage
+        .withdrawals_schema()
+        .store_withdrawal(withdrawal(&tx_hash_1, 1))
+        .await?;
+    storage
+        .withdrawals_schema()
+        .store_withdrawal(withdrawal(&tx_hash_1, 2))
+        .await?;
+    storage
+        .withdrawals_schema()
+        .store_withdrawal(withdrawal(&tx_hash_2, 3))
+        .await?;
+    storage
+        .withdrawals_schema()
+        .store_withdrawal(NewWithdrawal {
+            amount: BigDecimal::from(2000),
+            ..withdrawal(&tx_hash_3, 1)
+        })
+        .await?;
+
+    let stored = storage
+        .withdrawals_schema()
+        .load_withdrawal(&tx_hash_1)
+        .await?
+        .expect("withdrawal must be stored");
+    assert_eq!(stored.block_number, 2);
+    assert_eq!(stored.amount, BigDecimal::from(1000));
+    assert_eq!(stored.execute_tx_hash, None);
+    assert_eq!(
+        storage
+            .withdrawals_schema()
+            .get_last_completed_withdrawal_block()
+            .await?,
+        0
+    );
+
+    // Only the withdrawals of the executed blocks are completed. The withdrawal without
+    // the transfer event is stored in the pending balance.
+    let execute_tx_hash = H256::repeat_byte(0x30);
+    let transferred = OnchainWithdrawal {
+        owner: Address::repeat_byte(0x20),
+        token: 0,
+        amount: 1000u32.into(),
+    };
+    storage
+        .withdrawals_schema()
+        .complete_withdrawals(1, 2, &execute_tx_hash, &[transferred])
+        .await?;
+
+    let completed = storage
+        .withdrawals_schema()
+        .load_completed_withdrawals(0)
+        .await?;
+    assert_eq!(completed.len(), 2);
+    let transferred = completed
+        .iter()
+        .find(|withdrawal| withdrawal.tx_hash == tx_hash_1.as_ref().to_vec())
+        .expect("withdrawal must be completed");
+    assert_eq!(
+        transferred.execute_tx_hash,
+        Some(execute_tx_hash.as_bytes().to_vec())
+    );
+    assert!(transferred.executed_at.is_some());
+    assert!(!transferred.pending_balance);
+    let pending = completed
+        .iter()
+        .find(|withdrawal| withdrawal.tx_hash == tx_hash_3.as_ref().to_vec())
+        .expect("withdrawal must be completed");
+    assert!(pending.pending_balance);
+    assert!(storage
+        .withdrawals_schema()
+        .load_completed_withdrawals(2)
+        .await?
+        .is_empty());
+    assert_eq!(
+        storage
+            .withdrawals_schema()
+            .get_last_completed_withdrawal_block()
+            .await?,
+        2
+    );
+
+    Ok(())
+}
@@ -53,7 +53,7 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         let tokens = sqlx::query_as!(
             DbToken,
             r#"
-            SELECT * FROM tokens
+            SELECT id, address, symbol, decimals FROM tokens
             ORDER BY id ASC
             "#,
         )
@@ -96,7 +96,7 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
                 sqlx::query_as!(
                     DbToken,
                     r#"
-                    SELECT * FROM tokens
+                    SELECT id, address, symbol, decimals FROM tokens
                     WHERE id = $1
                     LIMIT 1
                     "#,
@@ -109,7 +109,7 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
                 sqlx::query_as!(
                     DbToken,
                     r#"
-                    SELECT * FROM tokens
+                    SELECT id, address, symbol, decimals FROM tokens
                     WHERE address = $1
                     LIMIT 1
                     "#,
@@ -122,7 +122,7 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
                 sqlx::query_as!(
                     DbToken,
                     r#"
-                    SELECT * FROM tokens
+                    SELECT id, address, symbol, decimals FROM tokens
                     WHERE symbol = $1
                     LIMIT 1
                     "#,
@@ -183,4 +183,136 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         metrics::histogram!("sql.token.update_historical_ticker_price", start.elapsed());
         Ok(())
     }
+
+    /// Sets the gas limit of the token withdrawal in L1, `None` resets it to the default one.
+    pub async fn set_withdrawal_gas_limit(
+        &mut self,
+        token_id: TokenId,
+        gas_limit: Option<u64>,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            UPDATE tokens SET withdrawal_gas_limit = $2
+            WHERE id = $1
+            "#,
+            i32::from(token_id),
+            gas_limit.map(|gas_limit| gas_limit as i64),
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.token.set_withdrawal_gas_limit", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the withdrawal gas limits of the tokens which have them set.
+    pub async fn load_withdrawal_gas_limits(&mut self) -> QueryResult<HashMap<TokenId, u64>> {
+        let start = Instant::now();
+        let gas_limits = sqlx::query!(
+            r#"
+            SELECT id, withdrawal_gas_limit as "withdrawal_gas_limit!" FROM tokens
+            WHERE withdrawal_gas_limit IS NOT NULL
+            "#,
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|row| (row.id as TokenId, row.withdrawal_gas_limit as u64))
+        .collect();
+
+        metrics::histogram!("sql.token.load_withdrawal_gas_limits", start.elapsed());
+        Ok(gas_limits)
+    }
+
+    /// Loads the tokens which metadata is updated from the token contracts,
+    /// i.e. all the tokens except `ETH` and the ones with the overridden metadata.
+    pub async fn load_tokens_for_metadata_update(&mut self) -> QueryResult<Vec<Token>> {
+        let start = Instant::now();
+        let tokens = sqlx::query_as!(
+            DbToken,
+            r#"
+            SELECT id, address, symbol, decimals FROM tokens
+            WHERE id != 0 AND NOT metadata_overridden
+            ORDER BY id ASC
+            "#,
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+        metrics::histogram!("sql.token.load_tokens_for_metadata_update", start.elapsed());
+        Ok(tokens)
+    }
+
+    /// Updates the token symbol obtained from the token contract, returns whether the token
+    /// was updated. Tokens with the overridden metadata are not updated, as well as the tokens
+    /// which symbol is already used by another token.
+    pub async fn update_token_symbol(
+        &mut self,
+        token_id: TokenId,
+        symbol: &str,
+    ) -> QueryResult<bool> {
+        let start = Instant::now();
+        let updated_rows = sqlx::query!(
+            r#"
+            UPDATE tokens SET symbol = $2
+            WHERE id = $1 AND NOT metadata_overridden
+                AND NOT EXISTS (SELECT 1 FROM tokens WHERE symbol = $2 AND id != $1)
+            "#,
+            i32::from(token_id),
+            symbol,
+        )
+        .execute(self.0.conn())
+        .await?
+        .rows_affected();
+
+        metrics::histogram!("sql.token.update_token_symbol", start.elapsed());
+        Ok(updated_rows > 0)
+    }
+
+    /// Sets the token symbol and decimals manually, so they are no longer updated
+    /// from the token contract.
+    pub async fn override_token_metadata(
+        &mut self,
+        token_id: TokenId,
+        symbol: &str,
+        decimals: u8,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            UPDATE tokens SET symbol = $2, decimals = $3, metadata_overridden = true
+            WHERE id = $1
+            "#,
+            i32::from(token_id),
+            symbol,
+            i16::from(decimals),
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.token.override_token_metadata", start.elapsed());
+        Ok(())
+    }
+
+    /// Removes the manual override of the token metadata, so it's updated from the token
+    /// contract again. The current symbol and decimals are kept until the next update.
+    pub async fn reset_token_metadata_override(&mut self, token_id: TokenId) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            UPDATE tokens SET metadata_overridden = false
+            WHERE id = $1
+            "#,
+            i32::from(token_id),
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.token.reset_token_metadata_override", start.elapsed());
+        Ok(())
+    }
 }
@@ -0,0 +1,68 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+// Workspace imports
+use zksync_types::{
+    tx::{TxFailureTrace, TxHash},
+    BlockNumber,
+};
+// Local imports
+use self::records::StoredTxFailureTrace;
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+/// Transaction traces schema stores the details of the transactions failed in the state keeper,
+/// so the failures can be debugged beyond the reason reported to the user.
+#[derive(Debug)]
+pub struct TxTracesSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> TxTracesSchema<'a, 'c> {
+    /// Stores the failure details of the transaction. Failed transactions of the pending block
+    /// are stored more than once, so the stored trace is overwritten.
+    pub async fn store_failure_trace(
+        &mut self,
+        tx_hash: &TxHash,
+        block_number: BlockNumber,
+        trace: &TxFailureTrace,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let trace = serde_json::to_value(trace).expect("Failed to encode TxFailureTrace");
+        sqlx::query!(
+            "INSERT INTO tx_failure_traces (tx_hash, block_number, trace)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (tx_hash)
+            DO UPDATE SET block_number = $2, trace = $3",
+            tx_hash.as_ref(),
+            i64::from(block_number),
+            trace,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.tx_traces.store_failure_trace", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the failure details of the transaction, if the transaction failed while
+    /// the transaction traces were enabled.
+    pub async fn load_failure_trace(
+        &mut self,
+        tx_hash: &TxHash,
+    ) -> QueryResult<Option<TxFailureTrace>> {
+        let start = Instant::now();
+        let stored = sqlx::query_as!(
+            StoredTxFailureTrace,
+            "SELECT * FROM tx_failure_traces WHERE tx_hash = $1",
+            tx_hash.as_ref(),
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+        let trace = stored
+            .map(|stored| serde_json::from_value(stored.trace))
+            .transpose()?;
+
+        metrics::histogram!("sql.tx_traces.load_failure_trace", start.elapsed());
+        Ok(trace)
+    }
+}
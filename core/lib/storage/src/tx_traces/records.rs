@@ -0,0 +1,15 @@
+// External imports
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::FromRow;
+// Workspace imports
+// Local imports
+
+/// Details of the failed transaction, `trace` is the serialized `TxFailureTrace`.
+#[derive(Debug, Clone, FromRow)]
+pub struct StoredTxFailureTrace {
+    pub tx_hash: Vec<u8>,
+    pub block_number: i64,
+    pub trace: Value,
+    pub created_at: DateTime<Utc>,
+}
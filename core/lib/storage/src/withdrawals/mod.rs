@@ -0,0 +1,178 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+use num::BigInt;
+use sqlx::types::BigDecimal;
+// Workspace imports
+use zksync_types::{ethereum::OnchainWithdrawal, tx::TxHash, BlockNumber, H256};
+// Local imports
+use self::records::{NewWithdrawal, StoredWithdrawal};
+use crate::{
+    event_journal::{records::NewJournalEvent, EventJournalSchema},
+    QueryResult, StorageProcessor,
+};
+
+pub mod records;
+
+/// Withdrawals schema tracks the withdrawals from their L2 block until the Ethereum
+/// transaction executing the block transfers the withdrawn funds in L1.
+#[derive(Debug)]
+pub struct WithdrawalsSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> WithdrawalsSchema<'a, 'c> {
+    /// Stores the withdrawal of the executed transaction. Transactions of the pending block
+    /// are stored more than once, so the stored withdrawal is overwritten.
+    pub async fn store_withdrawal(&mut self, withdrawal: NewWithdrawal) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "INSERT INTO withdrawals (tx_hash, block_number, address, to_address, token, amount)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (tx_hash)
+            DO UPDATE SET block_number = $2, address = $3, to_address = $4, token = $5, amount = $6",
+            withdrawal.tx_hash,
+            withdrawal.block_number,
+            withdrawal.address,
+            withdrawal.to_address,
+            withdrawal.token,
+            withdrawal.amount,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.withdrawals.store_withdrawal", start.elapsed());
+        Ok(())
+    }
+
+    pub async fn load_withdrawal(
+        &mut self,
+        tx_hash: &TxHash,
+    ) -> QueryResult<Option<StoredWithdrawal>> {
+        let start = Instant::now();
+        let withdrawal = sqlx::query_as!(
+            StoredWithdrawal,
+            "SELECT * FROM withdrawals WHERE tx_hash = $1",
+            tx_hash.as_ref(),
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.withdrawals.load_withdrawal", start.elapsed());
+        Ok(withdrawal)
+    }
+
+    /// Records the confirmed Ethereum transaction executing the blocks, which completes
+    /// the withdrawals of these blocks.
+    ///
+    /// Withdrawals transferred by the transaction are matched against the `OnchainWithdrawal`
+    /// events it emitted. Transfers of the rest of the withdrawals have failed, so their funds are
+    /// stored in the pending balances of the recipients in the contract. The events don't refer
+    /// to the operations, so the identical withdrawals (same recipient, token and amount) of
+    /// the executed blocks can't be told apart.
+    pub async fn complete_withdrawals(
+        &mut self,
+        first_block: BlockNumber,
+        last_block: BlockNumber,
+        execute_tx_hash: &H256,
+        onchain_withdrawals: &[OnchainWithdrawal],
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let executed = sqlx::query_as!(
+            StoredWithdrawal,
+            "SELECT * FROM withdrawals
+            WHERE block_number >= $1 AND block_number <= $2 AND execute_tx_hash IS NULL
+            ORDER BY block_number ASC",
+            i64::from(first_block),
+            i64::from(last_block),
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+
+        let mut transferred = onchain_withdrawals.to_vec();
+        let mut pending_balance_hashes = Vec::new();
+        for withdrawal in &executed {
+            let position = transferred.iter().position(|event| {
+                event.owner.as_bytes() == withdrawal.to_address.as_slice()
+                    && i32::from(event.token) == withdrawal.token
+                    && BigDecimal::from(BigInt::from(event.amount.clone())) == withdrawal.amount
+            });
+            match position {
+                Some(position) => {
+                    transferred.swap_remove(position);
+                }
+                None => pending_balance_hashes.push(withdrawal.tx_hash.clone()),
+            }
+        }
+
+        let completed = sqlx::query_as!(
+            StoredWithdrawal,
+            "UPDATE withdrawals
+            SET execute_tx_hash = $1, executed_at = now(), pending_balance = (tx_hash = ANY($4))
+            WHERE block_number >= $2 AND block_number <= $3 AND execute_tx_hash IS NULL
+            RETURNING *",
+            execute_tx_hash.as_bytes(),
+            i64::from(first_block),
+            i64::from(last_block),
+            &pending_balance_hashes,
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+
+        let events: Vec<_> = completed
+            .iter()
+            .map(NewJournalEvent::withdrawal_completed)
+            .collect();
+        EventJournalSchema(&mut transaction)
+            .append_events(&events)
+            .await?;
+
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.withdrawals.complete_withdrawals", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the completed withdrawals of the blocks after the given one, ordered by the block.
+    /// Blocks are executed in order, so the block of the last loaded withdrawal can be used
+    /// to follow the newly completed withdrawals.
+    pub async fn load_completed_withdrawals(
+        &mut self,
+        after_block: BlockNumber,
+    ) -> QueryResult<Vec<StoredWithdrawal>> {
+        let start = Instant::now();
+        let withdrawals = sqlx::query_as!(
+            StoredWithdrawal,
+            "SELECT * FROM withdrawals
+            WHERE block_number > $1 AND execute_tx_hash IS NOT NULL
+            ORDER BY block_number ASC",
+            i64::from(after_block),
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.withdrawals.load_completed_withdrawals",
+            start.elapsed()
+        );
+        Ok(withdrawals)
+    }
+
+    /// Returns the last block with the completed withdrawals, or 0 if there are none.
+    pub async fn get_last_completed_withdrawal_block(&mut self) -> QueryResult<BlockNumber> {
+        let start = Instant::now();
+        let block_number = sqlx::query!(
+            "SELECT max(block_number) FROM withdrawals WHERE execute_tx_hash IS NOT NULL"
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .max
+        .unwrap_or(0);
+
+        metrics::histogram!(
+            "sql.withdrawals.get_last_completed_withdrawal_block",
+            start.elapsed()
+        );
+        Ok(block_number as BlockNumber)
+    }
+}
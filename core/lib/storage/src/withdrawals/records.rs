@@ -0,0 +1,94 @@
+// External imports
+use chrono::{DateTime, Utc};
+use num::BigInt;
+use sqlx::{types::BigDecimal, FromRow};
+// Workspace imports
+use zksync_types::{
+    block::{ExecutedPriorityOp, ExecutedTx},
+    BlockNumber, ZkSyncOp,
+};
+// Local imports
+
+/// Withdrawal of the executed L2 transaction, to be stored in the database.
+#[derive(Debug, Clone)]
+pub struct NewWithdrawal {
+    pub tx_hash: Vec<u8>,
+    pub block_number: i64,
+    pub address: Vec<u8>,
+    pub to_address: Vec<u8>,
+    pub token: i32,
+    pub amount: BigDecimal,
+}
+
+impl NewWithdrawal {
+    /// Returns the withdrawal performed by the transaction, if it's a successful
+    /// `Withdraw` or `ForcedExit`.
+    pub fn from_executed_tx(tx: &ExecutedTx, block_number: BlockNumber) -> Option<Self> {
+        if !tx.success {
+            return None;
+        }
+
+        let (address, to_address, token, amount) = match tx.op.as_ref()? {
+            ZkSyncOp::Withdraw(op) => (op.tx.from, op.tx.to, op.tx.token, op.tx.amount.clone()),
+            ZkSyncOp::ForcedExit(op) => (
+                op.tx.target,
+                op.tx.target,
+                op.tx.token,
+                op.withdraw_amount.clone()?.0,
+            ),
+            _ => return None,
+        };
+
+        Some(Self {
+            tx_hash: tx.signed_tx.hash().as_ref().to_vec(),
+            block_number: i64::from(block_number),
+            address: address.as_bytes().to_vec(),
+            to_address: to_address.as_bytes().to_vec(),
+            token: i32::from(token),
+            amount: BigDecimal::from(BigInt::from(amount)),
+        })
+    }
+
+    /// Returns the withdrawal performed by the priority operation, if it's a successful `FullExit`.
+    /// Priority operations are identified by the hash of their Ethereum transaction.
+    pub fn from_executed_priority_op(
+        op: &ExecutedPriorityOp,
+        block_number: BlockNumber,
+    ) -> Option<Self> {
+        let (address, token, amount) = match &op.op {
+            // `FullExit` with no amount withdrawn has failed.
+            ZkSyncOp::FullExit(full_exit) => (
+                full_exit.priority_op.eth_address,
+                full_exit.priority_op.token,
+                full_exit.withdraw_amount.clone()?.0,
+            ),
+            _ => return None,
+        };
+
+        Some(Self {
+            tx_hash: op.priority_op.eth_hash.clone(),
+            block_number: i64::from(block_number),
+            address: address.as_bytes().to_vec(),
+            to_address: address.as_bytes().to_vec(),
+            token: i32::from(token),
+            amount: BigDecimal::from(BigInt::from(amount)),
+        })
+    }
+}
+
+/// Withdrawal executed in L2, along with the Ethereum transaction executing its block.
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct StoredWithdrawal {
+    pub tx_hash: Vec<u8>,
+    pub block_number: i64,
+    pub address: Vec<u8>,
+    pub to_address: Vec<u8>,
+    pub token: i32,
+    pub amount: BigDecimal,
+    /// Hash of the confirmed `executeBlocks` transaction, which transfers the funds in L1.
+    pub execute_tx_hash: Option<Vec<u8>>,
+    pub executed_at: Option<DateTime<Utc>>,
+    /// Whether the transfer failed during the block execution, so the funds are stored
+    /// in the pending balance of the recipient in the contract.
+    pub pending_balance: bool,
+}
@@ -0,0 +1,72 @@
+//! Types of the admin API shared by the server and the operator tools.
+
+// External uses
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+// Workspace uses
+use zksync_basic_types::{H256, U256};
+// Local uses
+use crate::{ethereum::ETHOperation, BlockNumber};
+
+/// Ethereum operation sent by `eth_sender` and not confirmed yet.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct EthOperationInfo {
+    pub id: i64,
+    pub op_type: String,
+    /// Range of the blocks affected by the operation.
+    pub blocks: Option<(BlockNumber, BlockNumber)>,
+    pub nonce: U256,
+    pub last_deadline_block: u64,
+    pub last_used_gas_price: U256,
+    /// Hashes of all the transactions sent for the operation, the latest one is the last.
+    pub tx_hashes: Vec<H256>,
+}
+
+impl From<ETHOperation> for EthOperationInfo {
+    fn from(op: ETHOperation) -> Self {
+        Self {
+            id: op.id,
+            op_type: op.op_type.to_string(),
+            blocks: op.op.map(|(_, operation)| operation.get_block_range()),
+            nonce: op.nonce,
+            last_deadline_block: op.last_deadline_block,
+            last_used_gas_price: op.last_used_gas_price,
+            tx_hashes: op.used_tx_hashes,
+        }
+    }
+}
+
+/// Upgrade of the zkSync contract observed by the Ethereum watcher.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ContractUpgradeInfo {
+    pub version_id: u64,
+    pub stage: String,
+    /// Ethereum block the upgrade entered the current stage in.
+    pub eth_block: u64,
+    /// Time of the confirmation by the operator, `eth_sender` doesn't send new transactions
+    /// after the upgrade is completed until it's confirmed.
+    pub confirmed_at: Option<DateTime<Utc>>,
+}
+
+/// Progress of the maintenance: the server can be stopped without interrupting the users
+/// once the mempool is drained, the pending block is sealed and `eth_sender` has no
+/// unconfirmed operations.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct MaintenanceStatus {
+    pub enabled: bool,
+    /// Amount of the transactions in the mempool, which are yet to be executed.
+    pub mempool_txs: usize,
+    /// Amount of the operations in the pending block, which is yet to be sealed.
+    pub pending_block_operations: usize,
+    /// Amount of the operations sent by `eth_sender` and not confirmed yet.
+    pub unconfirmed_eth_operations: usize,
+}
+
+impl MaintenanceStatus {
+    pub fn can_be_stopped(&self) -> bool {
+        self.enabled
+            && self.mempool_txs == 0
+            && self.pending_block_operations == 0
+            && self.unconfirmed_eth_operations == 0
+    }
+}
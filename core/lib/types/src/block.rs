@@ -2,8 +2,8 @@
 
 use super::PriorityOp;
 use super::ZkSyncOp;
-use super::{AccountId, BlockNumber, Fr};
-use crate::SignedZkSyncTx;
+use super::{AccountId, BlockNumber, Fr, TokenId};
+use crate::{tx::TxFailureTrace, SignedZkSyncTx};
 use chrono::Utc;
 use chrono::{DateTime, TimeZone};
 use parity_crypto::digest::sha256;
@@ -47,6 +47,9 @@ pub struct ExecutedTx {
     pub success: bool,
     pub op: Option<ZkSyncOp>,
     pub fail_reason: Option<String>,
+    /// Details of the failure, recorded if the transaction traces are enabled.
+    #[serde(default)]
+    pub fail_trace: Option<TxFailureTrace>,
     pub block_index: Option<u32>,
     pub created_at: DateTime<Utc>,
     pub batch_id: Option<i64>,
@@ -286,6 +289,15 @@ impl Block {
         withdrawals_data
     }
 
+    /// Returns the tokens of the withdrawals completed in L1 along with the block execution.
+    pub fn withdrawal_tokens(&self) -> Vec<TokenId> {
+        self.block_transactions
+            .iter()
+            .filter_map(|block_tx| block_tx.get_executed_op())
+            .filter_map(|op| op.withdrawal_token())
+            .collect()
+    }
+
     pub fn get_onchain_operations_block_info(
         &self,
     ) -> (Vec<OnchainOperationsBlockInfo>, H256, u64) {
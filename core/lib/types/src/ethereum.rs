@@ -3,10 +3,11 @@
 use std::{convert::TryFrom, fmt, str::FromStr};
 // External uses
 use ethabi::{decode, ParamType};
+use num::BigUint;
 use serde::{Deserialize, Serialize};
 // Local uses
 use crate::aggregated_operations::{AggregatedActionType, AggregatedOperation};
-use zksync_basic_types::{Log, H256, U256};
+use zksync_basic_types::{Address, Log, TokenId, H256, U256};
 
 /// Numerical identifier of the Ethereum operation.
 pub type EthOpId = i64;
@@ -116,6 +117,77 @@ pub struct InsertedOperationResponse {
     pub nonce: U256,
 }
 
+/// Stage of the zkSync contract upgrade managed by the upgrade gatekeeper contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ContractUpgradeStage {
+    /// Upgrade is announced, the new contract targets are applied after the notice period.
+    NoticePeriod,
+    /// Notice period is over, the upgrade can be finished.
+    Preparation,
+    /// Upgrade is cancelled, the contract interface stays the same.
+    Cancelled,
+    /// New contract targets are applied, the contract interface may be changed.
+    Completed,
+}
+
+impl fmt::Display for ContractUpgradeStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoticePeriod => write!(f, "notice_period"),
+            Self::Preparation => write!(f, "preparation"),
+            Self::Cancelled => write!(f, "cancelled"),
+            Self::Completed => write!(f, "completed"),
+        }
+    }
+}
+
+impl FromStr for ContractUpgradeStage {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let stage = match s {
+            "notice_period" => Self::NoticePeriod,
+            "preparation" => Self::Preparation,
+            "cancelled" => Self::Cancelled,
+            "completed" => Self::Completed,
+            _ => anyhow::bail!("Unknown stage of the contract upgrade: {}", s),
+        };
+
+        Ok(stage)
+    }
+}
+
+/// Event of the upgrade gatekeeper contract changing the stage of the contract upgrade.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContractUpgradeEvent {
+    /// Version of the contracts the upgrade results in.
+    pub version_id: u64,
+    pub stage: ContractUpgradeStage,
+    /// Number of the Ethereum block the event is emitted in.
+    pub eth_block: u64,
+}
+
+impl ContractUpgradeEvent {
+    /// Parses the event log, the stage is determined by the caller from the event signature.
+    pub fn from_log(stage: ContractUpgradeStage, event: &Log) -> anyhow::Result<Self> {
+        // Version ID is the first indexed parameter of every upgrade event.
+        let version_id = event
+            .topics
+            .get(1)
+            .map(|topic| U256::from_big_endian(topic.as_bytes()))
+            .ok_or_else(|| anyhow::format_err!("Upgrade event has no version ID"))?;
+        let eth_block = event
+            .block_number
+            .ok_or_else(|| anyhow::format_err!("Upgrade event has no block number"))?;
+
+        Ok(Self {
+            version_id: version_id.as_u64(),
+            stage,
+            eth_block: eth_block.as_u64(),
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CompleteWithdrawalsTx {
     pub tx_hash: H256,
@@ -155,3 +227,48 @@ impl TryFrom<Log> for CompleteWithdrawalsTx {
         })
     }
 }
+
+/// Withdrawal transferred to the recipient in L1 during the block execution
+/// (`OnchainWithdrawal` event of the zkSync contract).
+///
+/// If the transfer fails (e.g. the recipient contract rejects it), the event isn't emitted,
+/// and the funds are stored in the pending balance of the recipient in the contract instead.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OnchainWithdrawal {
+    pub owner: Address,
+    pub token: TokenId,
+    pub amount: BigUint,
+}
+
+impl TryFrom<Log> for OnchainWithdrawal {
+    type Error = anyhow::Error;
+
+    fn try_from(event: Log) -> Result<OnchainWithdrawal, anyhow::Error> {
+        // Owner and token ID are indexed, the topic 0 is the event signature.
+        anyhow::ensure!(
+            event.topics.len() == 3,
+            "Withdrawal event has {} topics instead of 3",
+            event.topics.len()
+        );
+        let owner = Address::from_slice(&event.topics[1].as_bytes()[12..]);
+        let token = U256::from_big_endian(event.topics[2].as_bytes());
+        anyhow::ensure!(
+            token <= U256::from(TokenId::max_value()),
+            "Withdrawal event token ID {} is out of range",
+            token
+        );
+        let amount = decode(&[ParamType::Uint(128)], &event.data.0)
+            .map_err(|e| anyhow::format_err!("Event data decode: {:?}", e))?
+            .remove(0)
+            .to_uint()
+            .ok_or_else(|| anyhow::format_err!("Withdrawal event amount is not an integer"))?;
+        let mut amount_bytes = [0u8; 32];
+        amount.to_big_endian(&mut amount_bytes);
+
+        Ok(OnchainWithdrawal {
+            owner,
+            token: token.as_u32() as TokenId,
+            amount: BigUint::from_bytes_be(&amount_bytes),
+        })
+    }
+}
@@ -13,6 +13,14 @@ use crate::{config::MAX_WITHDRAWALS_TO_COMPLETE_IN_A_CALL, ZkSyncOp};
 /// but at the same time it should not exceed the block gas limit.
 pub const TX_GAS_LIMIT: u64 = 4_000_000;
 
+/// Gas limit of a single ERC20 transfer made by the contract to complete the withdrawal,
+/// must match `ERC20_WITHDRAWAL_GAS_LIMIT` in `Config.sol`.
+///
+/// The transfer is called with this limit regardless of the gas limit of the transaction,
+/// and if the token requires more gas, the transfer fails and the withdrawn amount is stored
+/// as the pending balance to be withdrawn by the recipient in a separate transaction.
+pub const ERC20_WITHDRAWAL_GAS_LIMIT: u64 = 50_000;
+
 #[derive(Debug)]
 pub struct CommitCost;
 
@@ -38,16 +46,17 @@ impl CommitCost {
     }
 
     pub fn op_cost(op: &ZkSyncOp) -> U256 {
-        // let x = ChangePubKeyEthAuthDa;
         let cost = match op {
             ZkSyncOp::Noop(_) => 0,
             ZkSyncOp::Deposit(_) => Self::DEPOSIT_COST,
             ZkSyncOp::ChangePubKeyOffchain(change_pubkey) => {
                 // TODO: determine correct cost of this tx
-                if change_pubkey.tx.eth_auth_data.is_ecdsa() {
-                    Self::CHANGE_PUBKEY_COST_OFFCHAIN
-                } else {
+                // Both ECDSA and CREATE2 authorizations are passed to the contract as
+                // the Ethereum witness, only the onchain one is checked against the auth fact.
+                if change_pubkey.tx.eth_auth_data.is_onchain() {
                     Self::CHANGE_PUBKEY_COST_ONCHAIN
+                } else {
+                    Self::CHANGE_PUBKEY_COST_OFFCHAIN
                 }
             }
             ZkSyncOp::Transfer(_) => Self::TRANSFER_COST,
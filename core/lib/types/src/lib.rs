@@ -37,6 +37,7 @@
 //! [`Account`]: ./account/struct.Account.html
 
 pub mod account;
+pub mod admin;
 pub mod aggregated_operations;
 pub mod block;
 pub mod config;
@@ -60,7 +61,9 @@ pub use self::operations::{
     ChangePubKeyOp, DepositOp, ForcedExitOp, FullExitOp, TransferOp, TransferToNewOp, WithdrawOp,
     ZkSyncOp,
 };
-pub use self::priority_ops::{Deposit, FullExit, PriorityOp, ZkSyncPriorityOp};
+pub use self::priority_ops::{
+    Deposit, FullExit, PriorityOp, PriorityOpClaim, PriorityOpClaimSource, ZkSyncPriorityOp,
+};
 pub use self::tokens::{Token, TokenGenesisListItem, TokenLike, TokenPrice, TxFeeTypes};
 pub use self::tx::{ForcedExit, SignedZkSyncTx, Transfer, Withdraw, ZkSyncTx};
 
@@ -6,7 +6,7 @@ use anyhow::{ensure, format_err};
 use serde::{Deserialize, Serialize};
 use zksync_basic_types::Address;
 use zksync_crypto::params::{
-    ACCOUNT_ID_BIT_WIDTH, ADDRESS_WIDTH, CHUNK_BYTES, FEE_EXPONENT_BIT_WIDTH,
+    ACCOUNT_ID_BIT_WIDTH, ADDRESS_WIDTH, CHANGE_PUBKEY_CHUNKS, CHUNK_BYTES, FEE_EXPONENT_BIT_WIDTH,
     FEE_MANTISSA_BIT_WIDTH, NEW_PUBKEY_HASH_WIDTH, NONCE_BIT_WIDTH, TOKEN_BIT_WIDTH,
 };
 use zksync_crypto::primitives::FromBytes;
@@ -19,7 +19,7 @@ pub struct ChangePubKeyOp {
 }
 
 impl ChangePubKeyOp {
-    pub const CHUNKS: usize = 6;
+    pub const CHUNKS: usize = CHANGE_PUBKEY_CHUNKS;
     pub const OP_CODE: u8 = 0x07;
 
     pub fn get_public_data(&self) -> Vec<u8> {
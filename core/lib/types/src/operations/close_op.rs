@@ -4,7 +4,7 @@ use crate::Close;
 use anyhow::{ensure, format_err};
 use serde::{Deserialize, Serialize};
 use zksync_basic_types::Address;
-use zksync_crypto::params::{ACCOUNT_ID_BIT_WIDTH, CHUNK_BYTES};
+use zksync_crypto::params::{ACCOUNT_ID_BIT_WIDTH, CHUNK_BYTES, CLOSE_CHUNKS};
 use zksync_crypto::primitives::FromBytes;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,7 +14,7 @@ pub struct CloseOp {
 }
 
 impl CloseOp {
-    pub const CHUNKS: usize = 1;
+    pub const CHUNKS: usize = CLOSE_CHUNKS;
     pub const OP_CODE: u8 = 0x04;
 
     pub(crate) fn get_public_data(&self) -> Vec<u8> {
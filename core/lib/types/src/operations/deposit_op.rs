@@ -5,7 +5,8 @@ use num::{BigUint, ToPrimitive};
 use serde::{Deserialize, Serialize};
 use zksync_basic_types::Address;
 use zksync_crypto::params::{
-    ACCOUNT_ID_BIT_WIDTH, BALANCE_BIT_WIDTH, CHUNK_BYTES, FR_ADDRESS_LEN, TOKEN_BIT_WIDTH,
+    ACCOUNT_ID_BIT_WIDTH, BALANCE_BIT_WIDTH, CHUNK_BYTES, DEPOSIT_CHUNKS, FR_ADDRESS_LEN,
+    TOKEN_BIT_WIDTH,
 };
 use zksync_crypto::primitives::FromBytes;
 
@@ -17,7 +18,7 @@ pub struct DepositOp {
 }
 
 impl DepositOp {
-    pub const CHUNKS: usize = 6;
+    pub const CHUNKS: usize = DEPOSIT_CHUNKS;
     pub const OP_CODE: u8 = 0x01;
 
     pub fn get_public_data(&self) -> Vec<u8> {
@@ -9,7 +9,7 @@ use serde::{Deserialize, Serialize};
 use zksync_basic_types::Address;
 use zksync_crypto::params::{
     ACCOUNT_ID_BIT_WIDTH, BALANCE_BIT_WIDTH, CHUNK_BYTES, ETH_ADDRESS_BIT_WIDTH,
-    FEE_EXPONENT_BIT_WIDTH, FEE_MANTISSA_BIT_WIDTH, TOKEN_BIT_WIDTH,
+    FEE_EXPONENT_BIT_WIDTH, FEE_MANTISSA_BIT_WIDTH, FORCED_EXIT_CHUNKS, TOKEN_BIT_WIDTH,
 };
 use zksync_crypto::primitives::FromBytes;
 use zksync_utils::BigUintSerdeWrapper;
@@ -25,7 +25,7 @@ pub struct ForcedExitOp {
 }
 
 impl ForcedExitOp {
-    pub const CHUNKS: usize = 6;
+    pub const CHUNKS: usize = FORCED_EXIT_CHUNKS;
     pub const OP_CODE: u8 = 0x08;
     pub const WITHDRAW_DATA_PREFIX: [u8; 1] = [1];
 
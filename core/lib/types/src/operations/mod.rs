@@ -23,7 +23,7 @@ pub use self::{
     full_exit_op::FullExitOp, noop_op::NoopOp, transfer_op::TransferOp,
     transfer_to_new_op::TransferToNewOp, withdraw_op::WithdrawOp,
 };
-use zksync_basic_types::AccountId;
+use zksync_basic_types::{AccountId, TokenId};
 
 /// zkSync network operation.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -105,6 +105,16 @@ impl ZkSyncOp {
         }
     }
 
+    /// Returns the token withdrawn to L1 by the operation, if any.
+    pub fn withdrawal_token(&self) -> Option<TokenId> {
+        match self {
+            ZkSyncOp::Withdraw(op) => Some(op.tx.token),
+            ZkSyncOp::FullExit(op) => Some(op.priority_op.token),
+            ZkSyncOp::ForcedExit(op) => Some(op.tx.token),
+            _ => None,
+        }
+    }
+
     /// Attempts to restore the operation from the public data committed on the Ethereum smart contract.
     pub fn from_public_data(bytes: &[u8]) -> Result<Self, anyhow::Error> {
         let op_type: u8 = *bytes.first().ok_or_else(|| format_err!("Empty pubdata"))?;
@@ -1,14 +1,14 @@
 use anyhow::ensure;
 use serde::{Deserialize, Serialize};
 use zksync_basic_types::AccountId;
-use zksync_crypto::params::CHUNK_BYTES;
+use zksync_crypto::params::{CHUNK_BYTES, NOOP_CHUNKS};
 
 /// Noop operation. For details, see the documentation of [`ZkSyncOp`](./operations/enum.ZkSyncOp.html).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NoopOp {}
 
 impl NoopOp {
-    pub const CHUNKS: usize = 1;
+    pub const CHUNKS: usize = NOOP_CHUNKS;
     pub const OP_CODE: u8 = 0x00;
 
     pub fn from_public_data(bytes: &[u8]) -> Result<Self, anyhow::Error> {
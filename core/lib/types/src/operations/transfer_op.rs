@@ -8,7 +8,7 @@ use serde::{Deserialize, Serialize};
 use zksync_basic_types::Address;
 use zksync_crypto::params::{
     ACCOUNT_ID_BIT_WIDTH, AMOUNT_EXPONENT_BIT_WIDTH, AMOUNT_MANTISSA_BIT_WIDTH, CHUNK_BYTES,
-    FEE_EXPONENT_BIT_WIDTH, FEE_MANTISSA_BIT_WIDTH, TOKEN_BIT_WIDTH,
+    FEE_EXPONENT_BIT_WIDTH, FEE_MANTISSA_BIT_WIDTH, TOKEN_BIT_WIDTH, TRANSFER_CHUNKS,
 };
 use zksync_crypto::primitives::FromBytes;
 
@@ -21,7 +21,7 @@ pub struct TransferOp {
 }
 
 impl TransferOp {
-    pub const CHUNKS: usize = 2;
+    pub const CHUNKS: usize = TRANSFER_CHUNKS;
     pub const OP_CODE: u8 = 0x05;
 
     pub(crate) fn get_public_data(&self) -> Vec<u8> {
@@ -9,6 +9,7 @@ use zksync_basic_types::Address;
 use zksync_crypto::params::{
     ACCOUNT_ID_BIT_WIDTH, AMOUNT_EXPONENT_BIT_WIDTH, AMOUNT_MANTISSA_BIT_WIDTH, CHUNK_BYTES,
     FEE_EXPONENT_BIT_WIDTH, FEE_MANTISSA_BIT_WIDTH, FR_ADDRESS_LEN, TOKEN_BIT_WIDTH,
+    TRANSFER_TO_NEW_CHUNKS,
 };
 use zksync_crypto::primitives::FromBytes;
 
@@ -21,7 +22,7 @@ pub struct TransferToNewOp {
 }
 
 impl TransferToNewOp {
-    pub const CHUNKS: usize = 6;
+    pub const CHUNKS: usize = TRANSFER_TO_NEW_CHUNKS;
     pub const OP_CODE: u8 = 0x02;
 
     pub(crate) fn get_public_data(&self) -> Vec<u8> {
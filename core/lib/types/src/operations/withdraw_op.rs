@@ -9,7 +9,7 @@ use serde::{Deserialize, Serialize};
 use zksync_basic_types::Address;
 use zksync_crypto::params::{
     ACCOUNT_ID_BIT_WIDTH, BALANCE_BIT_WIDTH, CHUNK_BYTES, ETH_ADDRESS_BIT_WIDTH,
-    FEE_EXPONENT_BIT_WIDTH, FEE_MANTISSA_BIT_WIDTH, TOKEN_BIT_WIDTH,
+    FEE_EXPONENT_BIT_WIDTH, FEE_MANTISSA_BIT_WIDTH, TOKEN_BIT_WIDTH, WITHDRAW_CHUNKS,
 };
 use zksync_crypto::primitives::FromBytes;
 
@@ -21,7 +21,7 @@ pub struct WithdrawOp {
 }
 
 impl WithdrawOp {
-    pub const CHUNKS: usize = 6;
+    pub const CHUNKS: usize = WITHDRAW_CHUNKS;
     pub const OP_CODE: u8 = 0x03;
     pub const WITHDRAW_DATA_PREFIX: [u8; 1] = [1];
 
@@ -3,6 +3,7 @@
 use super::AccountId;
 use super::TokenId;
 use anyhow::{bail, ensure, format_err};
+use chrono::{DateTime, Utc};
 use ethabi::{decode, ParamType};
 use num::BigUint;
 use serde::{Deserialize, Serialize};
@@ -227,3 +228,29 @@ impl TryFrom<Log> for PriorityOp {
         })
     }
 }
+
+/// Source the claimed priority operation was found in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PriorityOpClaimSource {
+    /// Operation is already known to the Ethereum watcher.
+    EthWatch,
+    /// Operation is not processed by the Ethereum watcher yet, and was found
+    /// in the logs of the Ethereum transaction.
+    Ethereum,
+}
+
+/// Pending priority operation claimed by the hash of its Ethereum transaction,
+/// along with its position in the priority queue and the expected time of its inclusion.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PriorityOpClaim {
+    pub op: PriorityOp,
+    pub source: PriorityOpClaimSource,
+    /// Amount of the Ethereum confirmations the operation waits for before it can be executed.
+    pub confirmations_left: u64,
+    /// Amount of the priority operations to be executed before the claimed one.
+    /// Unknown until the state keeper requests the priority queue for the first time.
+    pub queue_position: Option<u64>,
+    pub estimated_inclusion_at: Option<DateTime<Utc>>,
+}
@@ -12,6 +12,7 @@ use web3::types::Bytes;
 
 use crate::{
     account::PubKeyHash,
+    ethereum::OnchainWithdrawal,
     operations::{
         ChangePubKeyOp, DepositOp, ForcedExitOp, FullExitOp, NoopOp, TransferOp, TransferToNewOp,
         WithdrawOp,
@@ -406,3 +407,41 @@ fn test_priority_op_from_valid_logs() {
         assert!(op.is_ok());
     }
 }
+
+#[test]
+fn test_onchain_withdrawal_from_log() {
+    let log = Log {
+        address: Address::from_str("bd2ea2073d4efa1a82269800a362f889545983c2").unwrap(),
+        topics: vec![
+            // Signature is checked by the log filter, not by the parser.
+            H256::zero(),
+            H256::from_str("0000000000000000000000002a0a81e257a2f5d6ed4f07b81dbda09f107bd026")
+                .unwrap(),
+            H256::from_low_u64_be(42),
+        ],
+        data: Bytes(H256::from_low_u64_be(1_000_000).as_bytes().to_vec()),
+        block_hash: None,
+        block_number: Some(1196475.into()),
+        transaction_hash: None,
+        transaction_index: None,
+        log_index: None,
+        transaction_log_index: None,
+        log_type: None,
+        removed: Some(false),
+    };
+
+    let withdrawal = OnchainWithdrawal::try_from(log.clone()).unwrap();
+    assert_eq!(
+        withdrawal,
+        OnchainWithdrawal {
+            owner: Address::from_str("2a0a81e257a2f5d6ed4f07b81dbda09f107bd026").unwrap(),
+            token: 42,
+            amount: BigUint::from(1_000_000u32),
+        }
+    );
+
+    // Token ID doesn't fit into `TokenId`.
+    let mut invalid_log = log;
+    invalid_log.topics[2] = H256::from_low_u64_be(1 << 16);
+    assert!(OnchainWithdrawal::try_from(invalid_log).is_err());
+}
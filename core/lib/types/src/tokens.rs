@@ -131,6 +131,10 @@ pub enum TxFeeTypes {
     /// Fee for the `Transfer` operation.
     Transfer,
     /// Fee for the `ChangePubKey` operation.
+    ///
+    /// `onchain_pubkey_auth` is set only for the transactions authorized by the auth fact
+    /// on the contract, ECDSA and CREATE2 authorizations cost more as they're sent as the
+    /// Ethereum witness.
     ChangePubKey {
         #[serde(rename = "onchainPubkeyAuth")]
         onchain_pubkey_auth: bool,
@@ -289,11 +289,16 @@ impl ChangePubKey {
     /// - `account_id` field must be within supported range.
     /// - `fee_token` field must be within supported range.
     /// - `fee` field must represent a packable value.
-    pub fn check_correctness(&self) -> bool {
-        self.is_eth_auth_data_valid()
-            && self.verify_signature() == Some(self.new_pk_hash)
+    pub fn check_correctness(&mut self) -> bool {
+        let mut valid = self.is_eth_auth_data_valid()
             && self.account_id <= max_account_id()
             && self.fee_token <= max_token_id()
-            && is_fee_amount_packable(&self.fee)
+            && is_fee_amount_packable(&self.fee);
+        if valid {
+            let signer = self.verify_signature();
+            valid = signer == Some(self.new_pk_hash);
+            self.cached_signer = VerifiedSignatureCache::Cached(signer);
+        }
+        valid
     }
 }
@@ -0,0 +1,55 @@
+use num::BigUint;
+use serde::{Deserialize, Serialize};
+use zksync_utils::BigUintSerdeAsRadix10Str;
+
+use crate::{AccountId, Nonce, PubKeyHash, TokenId};
+
+/// Structured description of the transaction rejected by the state keeper.
+///
+/// Besides the reason reported to the user, it contains the values of the check which failed
+/// during the transaction execution, as observed in the state the transaction was applied to.
+/// At most one of the nonce, balance and signature details is set, and none of them
+/// if the transaction failed for another reason.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TxFailureTrace {
+    /// Failed check, as reported to the user.
+    pub reason: String,
+    /// ID of the account which failed the check.
+    pub account_id: Option<AccountId>,
+    pub nonce: Option<NonceTrace>,
+    pub balance: Option<BalanceTrace>,
+    pub signature: Option<SignatureTrace>,
+    /// Whether the transaction is rejected only because another transaction of its batch failed.
+    #[serde(default)]
+    pub batch_rolled_back: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct NonceTrace {
+    /// Current nonce of the account.
+    pub expected: Nonce,
+    /// Nonce of the transaction.
+    pub actual: Nonce,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BalanceTrace {
+    pub token: TokenId,
+    /// Amount written off the account by the transaction, including the fee.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub required: BigUint,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub available: BigUint,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SignatureTrace {
+    /// Public key hash the transaction must be signed with.
+    pub expected_signer: PubKeyHash,
+    /// Public key hash the transaction is actually signed with, `None` if the signature is malformed.
+    pub actual_signer: Option<PubKeyHash>,
+}
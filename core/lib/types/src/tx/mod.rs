@@ -2,6 +2,7 @@
 
 mod change_pubkey;
 mod close;
+mod failure_trace;
 mod forced_exit;
 mod primitives;
 mod transfer;
@@ -19,6 +20,7 @@ pub use self::{
     change_pubkey::{
         ChangePubKey, ChangePubKeyCREATE2Data, ChangePubKeyECDSAData, ChangePubKeyEthAuthData,
     },
+    failure_trace::{BalanceTrace, NonceTrace, SignatureTrace, TxFailureTrace},
     forced_exit::ForcedExit,
     transfer::Transfer,
     withdraw::Withdraw,
@@ -28,9 +30,9 @@ pub use self::{
 // Re-export primitives associated with transactions.
 pub use self::primitives::{
     batch_sign_data::BatchSignData, eip1271_signature::EIP1271Signature,
-    eth_signature::TxEthSignature, packed_eth_signature::PackedEthSignature,
-    packed_public_key::PackedPublicKey, packed_signature::PackedSignature, signature::TxSignature,
-    tx_hash::TxHash,
+    eip712_signature::Eip712Domain, eth_signature::TxEthSignature,
+    packed_eth_signature::PackedEthSignature, packed_public_key::PackedPublicKey,
+    packed_signature::PackedSignature, signature::TxSignature, tx_hash::TxHash,
 };
 
 pub(crate) use self::primitives::signature_cache::VerifiedSignatureCache;
@@ -0,0 +1,138 @@
+//! Structured data signing of the zkSync transactions according to EIP-712.
+//!
+//! Instead of the human-readable message, the Ethereum signature authorizes the hash of
+//! the typed transaction data bound to the domain of the zkSync contract:
+//!
+//! `keccak256("\x19\x01" ‖ domainSeparator ‖ hashStruct(tx))`
+
+// External uses
+use ethabi::{encode, Token};
+use num::BigUint;
+use serde::{Deserialize, Serialize};
+use tiny_keccak::keccak256;
+// Workspace uses
+use zksync_basic_types::{Address, H256, U256};
+// Local uses
+use crate::tx::{ChangePubKey, Transfer, Withdraw};
+use crate::ZkSyncTx;
+
+const DOMAIN_TYPE: &str =
+    "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)";
+const TRANSFER_TYPE: &str = "Transfer(uint32 accountId,address from,address to,uint16 token,uint256 amount,uint256 fee,uint32 nonce)";
+const WITHDRAW_TYPE: &str = "Withdraw(uint32 accountId,address from,address to,uint16 token,uint256 amount,uint256 fee,uint32 nonce)";
+const CHANGE_PUBKEY_TYPE: &str = "ChangePubKey(uint32 accountId,address account,bytes20 pubKeyHash,uint16 feeToken,uint256 fee,uint32 nonce)";
+
+/// Domain the zkSync transactions are signed in.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Eip712Domain {
+    pub name: String,
+    pub version: String,
+    pub chain_id: u64,
+    /// Address of the zkSync contract.
+    pub verifying_contract: Address,
+}
+
+impl Eip712Domain {
+    pub const NAME: &'static str = "zkSync";
+    pub const VERSION: &'static str = "1";
+
+    pub fn new(chain_id: u64, verifying_contract: Address) -> Self {
+        Self {
+            name: Self::NAME.to_string(),
+            version: Self::VERSION.to_string(),
+            chain_id,
+            verifying_contract,
+        }
+    }
+
+    /// Returns the `domainSeparator` value.
+    pub fn separator(&self) -> H256 {
+        hash_struct(
+            DOMAIN_TYPE,
+            vec![
+                Token::FixedBytes(keccak256(self.name.as_bytes()).to_vec()),
+                Token::FixedBytes(keccak256(self.version.as_bytes()).to_vec()),
+                Token::Uint(U256::from(self.chain_id)),
+                Token::Address(self.verifying_contract),
+            ],
+        )
+    }
+
+    /// Returns the hash to be signed for the structure with the given `hashStruct` value.
+    pub fn typed_data_hash(&self, struct_hash: H256) -> H256 {
+        let mut bytes = Vec::with_capacity(66);
+        bytes.extend_from_slice(b"\x19\x01");
+        bytes.extend_from_slice(self.separator().as_bytes());
+        bytes.extend_from_slice(struct_hash.as_bytes());
+        H256(keccak256(&bytes))
+    }
+
+    /// Returns the hash to be signed for the transaction.
+    /// Returns `None` if the transaction has no typed data definition.
+    pub fn tx_hash_to_sign(&self, tx: &ZkSyncTx) -> Option<H256> {
+        let struct_hash = match tx {
+            ZkSyncTx::Transfer(tx) => transfer_struct_hash(tx),
+            ZkSyncTx::Withdraw(tx) => withdraw_struct_hash(tx),
+            ZkSyncTx::ChangePubKey(tx) => change_pubkey_struct_hash(tx),
+            _ => return None,
+        };
+        Some(self.typed_data_hash(struct_hash))
+    }
+}
+
+fn hash_struct(type_definition: &str, mut members: Vec<Token>) -> H256 {
+    members.insert(
+        0,
+        Token::FixedBytes(keccak256(type_definition.as_bytes()).to_vec()),
+    );
+    H256(keccak256(&encode(&members)))
+}
+
+fn uint(value: &BigUint) -> Token {
+    Token::Uint(U256::from_big_endian(&value.to_bytes_be()))
+}
+
+pub fn transfer_struct_hash(tx: &Transfer) -> H256 {
+    hash_struct(
+        TRANSFER_TYPE,
+        vec![
+            Token::Uint(tx.account_id.into()),
+            Token::Address(tx.from),
+            Token::Address(tx.to),
+            Token::Uint(tx.token.into()),
+            uint(&tx.amount),
+            uint(&tx.fee),
+            Token::Uint(tx.nonce.into()),
+        ],
+    )
+}
+
+pub fn withdraw_struct_hash(tx: &Withdraw) -> H256 {
+    hash_struct(
+        WITHDRAW_TYPE,
+        vec![
+            Token::Uint(tx.account_id.into()),
+            Token::Address(tx.from),
+            Token::Address(tx.to),
+            Token::Uint(tx.token.into()),
+            uint(&tx.amount),
+            uint(&tx.fee),
+            Token::Uint(tx.nonce.into()),
+        ],
+    )
+}
+
+pub fn change_pubkey_struct_hash(tx: &ChangePubKey) -> H256 {
+    hash_struct(
+        CHANGE_PUBKEY_TYPE,
+        vec![
+            Token::Uint(tx.account_id.into()),
+            Token::Address(tx.account),
+            Token::FixedBytes(tx.new_pk_hash.data.to_vec()),
+            Token::Uint(tx.fee_token.into()),
+            uint(&tx.fee),
+            Token::Uint(tx.nonce.into()),
+        ],
+    )
+}
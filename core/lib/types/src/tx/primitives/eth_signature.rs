@@ -5,9 +5,12 @@ use serde::{Deserialize, Serialize};
 /// May be either a signature generated via Ethereum private key
 /// corresponding to the account address,
 /// or on-chain signature via EIP-1271.
+/// Signature generated via Ethereum private key may authorize either the
+/// human-readable transaction message or the EIP-712 typed transaction data.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "type", content = "signature")]
 pub enum TxEthSignature {
     EthereumSignature(PackedEthSignature),
     EIP1271Signature(EIP1271Signature),
+    EIP712Signature(PackedEthSignature),
 }
@@ -54,6 +54,13 @@ impl PackedEthSignature {
         Ok(PackedEthSignature(signature))
     }
 
+    /// Signs the already hashed data (e.g. EIP-712 typed data hash) without adding any prefix.
+    pub fn sign_hash(private_key: &H256, hash: &H256) -> Result<PackedEthSignature, anyhow::Error> {
+        let secret_key = (*private_key).into();
+        let signature = sign(&secret_key, hash)?;
+        Ok(PackedEthSignature(signature))
+    }
+
     fn message_to_signed_bytes(msg: &[u8]) -> H256 {
         let prefix = format!("\x19Ethereum Signed Message:\n{}", msg.len());
         let mut bytes = Vec::with_capacity(prefix.len() + msg.len());
@@ -71,6 +78,15 @@ impl PackedEthSignature {
         Ok(public_to_address(&public_key))
     }
 
+    /// Checks signature of the already hashed data and returns ethereum address of the signer.
+    pub fn signature_recover_signer_from_hash(
+        &self,
+        hash: &H256,
+    ) -> Result<Address, anyhow::Error> {
+        let public_key = recover(&self.0, hash)?;
+        Ok(public_to_address(&public_key))
+    }
+
     /// Get Ethereum address from private key.
     pub fn address_from_private_key(private_key: &H256) -> Result<Address, anyhow::Error> {
         Ok(KeyPair::from_secret((*private_key).into())?.address())
@@ -1,12 +1,14 @@
-use std::ops::Deref;
+use std::{ops::Deref, str::FromStr};
 // External uses
 use anyhow::Result;
 use parity_crypto::publickey::{Generator, Random};
 // Workspace uses
 use zksync_basic_types::{Address, H256};
 // Local uses
-use super::packed_eth_signature::PackedEthSignature;
-use crate::{tx::*, Transfer, Withdraw, ZkSyncTx};
+use super::{
+    eip712_signature::change_pubkey_struct_hash, packed_eth_signature::PackedEthSignature,
+};
+use crate::{tx::*, PubKeyHash, Transfer, Withdraw, ZkSyncTx};
 
 fn get_packed_signature() -> PackedEthSignature {
     let keypair = Random.generate();
@@ -122,3 +124,98 @@ fn test_batch_message() -> Result<()> {
     assert_eq!(batch_sign_data.message, batch_hash);
     Ok(())
 }
+
+#[test]
+fn eip712_signature_recovery() -> Result<()> {
+    let keypair = Random.generate();
+    let private_key = *keypair.secret().deref();
+    let address = PackedEthSignature::address_from_private_key(&private_key)?;
+
+    let txs = get_batch();
+    let domain = Eip712Domain::new(9, Address::random());
+    for tx in &txs {
+        let hash = domain.tx_hash_to_sign(tx).unwrap();
+        let signature = PackedEthSignature::sign_hash(&private_key, &hash)?;
+        assert_eq!(
+            signature.signature_recover_signer_from_hash(&hash)?,
+            address
+        );
+
+        // Signature is bound to the domain.
+        let other_domain = Eip712Domain::new(1, domain.verifying_contract);
+        assert_ne!(other_domain.tx_hash_to_sign(tx).unwrap(), hash);
+    }
+    Ok(())
+}
+
+/// Checks the hashing against the `Mail` example of the EIP-712 specification,
+/// signed with the `keccak256("cow")` private key by the reference implementation.
+#[test]
+fn eip712_reference_vector() -> Result<()> {
+    let domain = Eip712Domain {
+        name: "Ether Mail".to_string(),
+        version: "1".to_string(),
+        chain_id: 1,
+        verifying_contract: Address::from_str("cccccccccccccccccccccccccccccccccccccccc")?,
+    };
+    assert_eq!(
+        domain.separator(),
+        H256::from_str("f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f")?
+    );
+
+    let mail_hash =
+        H256::from_str("c52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e")?;
+    let hash = domain.typed_data_hash(mail_hash);
+    assert_eq!(
+        hash,
+        H256::from_str("be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2")?
+    );
+
+    let signature = PackedEthSignature::deserialize_packed(&hex::decode(
+        "4355c47d63924e8a72e509b65029052eb6c299d53a04e167c5775fd466751c9d\
+         07299936d304c153f6443dfa05f40ff007d72911b6f72307f996231605b91562\
+         1c",
+    )?)?;
+    let cow = Address::from_str("cd2a3d9f938e13cd947ec05abc7fe734df8dd826")?;
+    assert_eq!(signature.signature_recover_signer_from_hash(&hash)?, cow);
+
+    let private_key = H256(tiny_keccak::keccak256(b"cow"));
+    assert_eq!(
+        PackedEthSignature::address_from_private_key(&private_key)?,
+        cow
+    );
+    Ok(())
+}
+
+/// Checks the typed data hash of `ChangePubKey` against the value computed independently
+/// according to the EIP-712 specification.
+#[test]
+fn eip712_change_pubkey_vector() -> Result<()> {
+    let change_pubkey = ChangePubKey::new(
+        123,
+        Address::repeat_byte(0x11),
+        PubKeyHash { data: [0x22; 20] },
+        1,
+        1000u32.into(),
+        13,
+        None,
+        None,
+    );
+    assert_eq!(
+        change_pubkey_struct_hash(&change_pubkey),
+        H256::from_str("3b0df8aa20c4b969a1ebf2d469fbbc5d464339fa816d7f076c28b4780c7917b9")?
+    );
+
+    let domain = Eip712Domain::new(9, Address::repeat_byte(0x33));
+    assert_eq!(
+        domain.separator(),
+        H256::from_str("345c0c024a9606387f5c6333b2bb68df1b33528890c9fca96f81a0ac1dfb0340")?
+    );
+    assert_eq!(
+        domain.tx_hash_to_sign(&ZkSyncTx::ChangePubKey(Box::new(change_pubkey))),
+        Some(H256::from_str(
+            "e658c06acc922ea2668007914b2cb18e46c7e2e42dda9589c7d2b46bdd895e18"
+        )?)
+    );
+    Ok(())
+}
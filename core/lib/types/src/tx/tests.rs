@@ -1,4 +1,4 @@
-use zksync_basic_types::Address;
+use zksync_basic_types::{Address, H256};
 use zksync_crypto::franklin_crypto::{
     eddsa::{PrivateKey, PublicKey},
     jubjub::FixedGenerators,
@@ -13,7 +13,7 @@ use serde::{Deserialize, Serialize};
 use super::*;
 use crate::{
     helpers::{pack_fee_amount, pack_token_amount},
-    AccountId, Engine, TokenId,
+    AccountId, Engine, PubKeyHash, TokenId, TransferOp, TransferToNewOp,
 };
 
 fn gen_pk_and_msg() -> (PrivateKey<Engine>, Vec<Vec<u8>>) {
@@ -294,3 +294,62 @@ fn test_check_signature() {
 
     assert_eq!(hex::encode(signature), "4e3298ac8cc13868dbbc94ad6fb41085ffe05b3c2eee22f88b05e69b7a5126aea723d7a3e7282ef5a32d9479c9c8dde52b3e3c462dd445dcd8158ebb6edb6000");
 }
+
+/// Checks that `ChangePubKey` authorized by CREATE2 is valid only for the address
+/// derived from the new public key hash.
+#[test]
+fn change_pubkey_create2_auth() {
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let key = gen_pk_and_msg().0;
+    let new_pk_hash = PubKeyHash::from_privkey(&key);
+    let create2_data = ChangePubKeyCREATE2Data {
+        creator_address: Address::from(rng.gen::<[u8; 20]>()),
+        salt_arg: H256::from(rng.gen::<[u8; 32]>()),
+        code_hash: H256::from(rng.gen::<[u8; 32]>()),
+    };
+
+    let create_tx = |account: Address| {
+        let mut tx = ChangePubKey::new_signed(
+            1,
+            account,
+            new_pk_hash,
+            0,
+            BigUint::from(1_000u32),
+            0,
+            None,
+            &key,
+        )
+        .expect("failed to sign change pubkey");
+        tx.eth_auth_data = ChangePubKeyEthAuthData::CREATE2(create2_data.clone());
+        tx
+    };
+
+    let mut tx = create_tx(create2_data.get_address(&new_pk_hash));
+    assert!(tx.check_correctness());
+
+    let mut tx = create_tx(Address::from(rng.gen::<[u8; 20]>()));
+    assert!(!tx.check_correctness());
+}
+
+/// Checks that the transfers to the new accounts take the chunks of `TransferToNew` operation.
+#[test]
+fn transfer_chunks() {
+    let recipient = Address::repeat_byte(0x20);
+    let transfer = ZkSyncTx::from(Transfer::new(
+        1,
+        Address::repeat_byte(0x10),
+        recipient,
+        0,
+        BigUint::from(100u32),
+        BigUint::from(1u32),
+        0,
+        None,
+    ));
+
+    assert_eq!(
+        transfer.chunks(|address| *address == recipient),
+        TransferOp::CHUNKS
+    );
+    assert_eq!(transfer.chunks(|_| false), TransferToNewOp::CHUNKS);
+    assert_eq!(transfer.min_chunks(), TransferOp::CHUNKS);
+}
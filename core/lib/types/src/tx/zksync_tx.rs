@@ -2,7 +2,7 @@ use crate::Nonce;
 
 use crate::{
     tx::{ChangePubKey, Close, ForcedExit, Transfer, TxEthSignature, TxHash, Withdraw},
-    CloseOp, ForcedExitOp, TokenLike, TransferOp, TxFeeTypes, WithdrawOp,
+    CloseOp, ForcedExitOp, TokenLike, TransferOp, TransferToNewOp, TxFeeTypes, WithdrawOp,
 };
 use num::BigUint;
 use parity_crypto::digest::sha256;
@@ -166,6 +166,16 @@ impl ZkSyncTx {
         }
     }
 
+    /// Returns the amount of block chunks required to execute this transaction.
+    /// Transfers to the accounts that don't exist yet are executed as `TransferToNew`
+    /// operations, so the existence of the recipient is checked with `account_exists`.
+    pub fn chunks(&self, account_exists: impl Fn(&Address) -> bool) -> usize {
+        match self {
+            ZkSyncTx::Transfer(tx) if !account_exists(&tx.to) => TransferToNewOp::CHUNKS,
+            _ => self.min_chunks(),
+        }
+    }
+
     /// Returns `true` if transaction is `ZkSyncTx::Withdraw`.
     pub fn is_withdraw(&self) -> bool {
         matches!(self, ZkSyncTx::Withdraw(_) | ZkSyncTx::ForcedExit(_))
@@ -217,7 +227,7 @@ impl ZkSyncTx {
             )),
             ZkSyncTx::ChangePubKey(change_pubkey) => Some((
                 TxFeeTypes::ChangePubKey {
-                    onchain_pubkey_auth: !change_pubkey.eth_auth_data.is_ecdsa(),
+                    onchain_pubkey_auth: change_pubkey.eth_auth_data.is_onchain(),
                 },
                 TokenLike::Id(change_pubkey.fee_token),
                 change_pubkey.account,
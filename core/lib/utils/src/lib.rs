@@ -5,6 +5,9 @@ mod env_tools;
 mod format;
 pub mod panic_notify;
 mod serde_wrappers;
+pub mod shutdown;
+#[cfg(feature = "tls")]
+pub mod tls;
 
 pub use convert::*;
 pub use env_tools::*;
@@ -0,0 +1,92 @@
+//! Coordination of the graceful shutdown of the server actors.
+
+// Built-in deps
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+// External uses
+use futures::channel::oneshot;
+
+/// Signal shared between the shutdown controller and an actor (or a group of actors).
+///
+/// Controller requests the shutdown, the actor checks the request in its main loop, finishes
+/// the work in progress and reports that it has stopped, so the controller can proceed to
+/// the next actor.
+#[derive(Debug, Clone, Default)]
+pub struct ShutdownSignal {
+    inner: Arc<ShutdownState>,
+}
+
+#[derive(Debug, Default)]
+struct ShutdownState {
+    requested: AtomicBool,
+    stopped: AtomicBool,
+    stop_listeners: Mutex<Vec<oneshot::Sender<()>>>,
+}
+
+impl ShutdownSignal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests the actor to stop.
+    pub fn request(&self) {
+        self.inner.requested.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_requested(&self) -> bool {
+        self.inner.requested.load(Ordering::SeqCst)
+    }
+
+    /// Reports that the actor has finished its work and stopped.
+    pub fn mark_stopped(&self) {
+        self.inner.stopped.store(true, Ordering::SeqCst);
+        for listener in self.inner.stop_listeners.lock().unwrap().drain(..) {
+            listener.send(()).unwrap_or_default();
+        }
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.inner.stopped.load(Ordering::SeqCst)
+    }
+
+    /// Resolves once the actor reports that it has stopped.
+    pub async fn stopped(&self) {
+        let receiver = {
+            let mut listeners = self.inner.stop_listeners.lock().unwrap();
+            if self.is_stopped() {
+                return;
+            }
+            let (sender, receiver) = oneshot::channel();
+            listeners.push(sender);
+            receiver
+        };
+        receiver.await.unwrap_or_default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{executor::block_on, future::FutureExt};
+
+    #[test]
+    fn shutdown_signal_is_shared() {
+        let signal = ShutdownSignal::new();
+        let actor_signal = signal.clone();
+        assert!(!actor_signal.is_requested());
+
+        signal.request();
+        assert!(actor_signal.is_requested());
+        assert!(!signal.is_stopped());
+
+        let mut stopped = Box::pin(signal.stopped());
+        assert!((&mut stopped).now_or_never().is_none());
+
+        actor_signal.mark_stopped();
+        assert!(signal.is_stopped());
+        block_on(stopped);
+        block_on(signal.stopped());
+    }
+}
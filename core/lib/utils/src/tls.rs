@@ -0,0 +1,42 @@
+//! Loading of the TLS certificates served by the API and prover servers.
+
+// Built-in uses
+use std::{fs::File, io::BufReader, path::Path};
+// External uses
+use anyhow::{anyhow, ensure, Context};
+use rustls::{
+    internal::pemfile::{certs, pkcs8_private_keys, rsa_private_keys},
+    Certificate, PrivateKey,
+};
+
+fn open_pem(path: &Path) -> anyhow::Result<BufReader<File>> {
+    let file = File::open(path).with_context(|| format!("Unable to open {}", path.display()))?;
+    Ok(BufReader::new(file))
+}
+
+/// Loads the certificate chain and the private key (either PKCS#8 or RSA) from the PEM files.
+pub fn load_pem(
+    cert_path: &Path,
+    key_path: &Path,
+) -> anyhow::Result<(Vec<Certificate>, PrivateKey)> {
+    let cert_chain = certs(&mut open_pem(cert_path)?)
+        .map_err(|_| anyhow!("Unable to parse certificates in {}", cert_path.display()))?;
+    ensure!(
+        !cert_chain.is_empty(),
+        "No certificates found in {}",
+        cert_path.display()
+    );
+
+    let mut keys = pkcs8_private_keys(&mut open_pem(key_path)?)
+        .map_err(|_| anyhow!("Unable to parse private key in {}", key_path.display()))?;
+    if keys.is_empty() {
+        keys = rsa_private_keys(&mut open_pem(key_path)?)
+            .map_err(|_| anyhow!("Unable to parse private key in {}", key_path.display()))?;
+    }
+    let key = keys
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow!("No private key found in {}", key_path.display()))?;
+
+    Ok((cert_chain, key))
+}
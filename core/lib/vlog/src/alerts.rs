@@ -0,0 +1,201 @@
+//! Alerts about the critical failures of the server.
+//!
+//! Panics of any thread or task and the errors reported with the `vlog::critical!` macro are
+//! forwarded to Sentry and/or posted to a webhook, so a server running with some of its
+//! actors dead doesn't go unnoticed. Optionally, a panic also triggers the server shutdown
+//! through the stop signal channel of the server actors.
+
+// Built-in deps
+use std::{
+    panic,
+    sync::{mpsc as std_mpsc, Mutex},
+    thread,
+    time::Duration,
+};
+// External deps
+use futures::channel::mpsc;
+use once_cell::sync::OnceCell;
+use serde_json::json;
+
+/// Timeout of a single webhook request.
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+struct AlertSink {
+    sentry_enabled: bool,
+    /// Channel of the webhook thread, `None` stops the thread.
+    webhook: Option<Mutex<std_mpsc::Sender<Option<String>>>>,
+    shutdown: Option<Mutex<mpsc::Sender<bool>>>,
+}
+
+static SINK: OnceCell<AlertSink> = OnceCell::new();
+
+/// Sends the pending alerts once dropped, so it should be kept for the lifetime of the application.
+pub struct AlertsGuard {
+    _sentry: Option<sentry::ClientInitGuard>,
+    webhook_thread: Option<thread::JoinHandle<()>>,
+}
+
+impl Drop for AlertsGuard {
+    fn drop(&mut self) {
+        if let Some(webhook_thread) = self.webhook_thread.take() {
+            send_to_webhook(None);
+            webhook_thread.join().unwrap_or_default();
+        }
+    }
+}
+
+/// Starts forwarding the alerts to Sentry and the webhook (if any of them is set) and installs
+/// the panic hook reporting the panics. If the shutdown channel is provided, the first panic
+/// sends the stop signal to it.
+///
+/// Panics if called more than once.
+pub fn init(
+    sentry_dsn: Option<&str>,
+    webhook_url: Option<&str>,
+    shutdown: Option<mpsc::Sender<bool>>,
+) -> AlertsGuard {
+    // Sentry reports the panics on its own, so its hook is installed before ours.
+    let sentry = sentry_dsn.map(|dsn| {
+        sentry::init((
+            dsn,
+            sentry::ClientOptions {
+                release: sentry::release_name!(),
+                ..Default::default()
+            },
+        ))
+    });
+    let (webhook, webhook_thread) = match webhook_url {
+        Some(url) => {
+            let (sender, thread) = start_webhook_sender(url.to_string());
+            (Some(Mutex::new(sender)), Some(thread))
+        }
+        None => (None, None),
+    };
+
+    let sink = AlertSink {
+        sentry_enabled: sentry.is_some(),
+        webhook,
+        shutdown: shutdown.map(Mutex::new),
+    };
+    if SINK.set(sink).is_err() {
+        panic!("Alerts are already initialized");
+    }
+    install_panic_hook();
+
+    AlertsGuard {
+        _sentry: sentry,
+        webhook_thread,
+    }
+}
+
+/// Reports the critical error. Does nothing if the alerts are not initialized.
+pub fn report_critical(message: &str) {
+    if let Some(sink) = SINK.get() {
+        if sink.sentry_enabled {
+            sentry::capture_message(message, sentry::Level::Fatal);
+        }
+    }
+    send_to_webhook(Some(message.to_string()));
+}
+
+fn send_to_webhook(message: Option<String>) {
+    let webhook = SINK.get().and_then(|sink| sink.webhook.as_ref());
+    if let Some(Ok(webhook)) = webhook.map(Mutex::lock) {
+        webhook.send(message).unwrap_or_default();
+    }
+}
+
+fn start_webhook_sender(url: String) -> (std_mpsc::Sender<Option<String>>, thread::JoinHandle<()>) {
+    let (sender, receiver) = std_mpsc::channel::<Option<String>>();
+    let handle = thread::Builder::new()
+        .name("alert_webhook".to_string())
+        .spawn(move || {
+            let client = reqwest::blocking::Client::builder()
+                .timeout(WEBHOOK_TIMEOUT)
+                .build()
+                .expect("Unable to create the webhook client");
+
+            while let Ok(Some(text)) = receiver.recv() {
+                let result = client
+                    .post(&url)
+                    .json(&json!({ "text": text }))
+                    .send()
+                    .and_then(|response| response.error_for_status());
+                if let Err(err) = result {
+                    log::error!("Unable to post the alert to the webhook: {}", err);
+                }
+            }
+        })
+        .expect("Unable to start the alert webhook thread");
+    (sender, handle)
+}
+
+fn install_panic_hook() {
+    let previous_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        previous_hook(info);
+
+        let message = format!(
+            "Thread '{}' {}",
+            thread::current().name().unwrap_or("<unnamed>"),
+            info
+        );
+        // Sentry has already captured the panic in its own hook.
+        send_to_webhook(Some(message));
+
+        let shutdown = SINK.get().and_then(|sink| sink.shutdown.as_ref());
+        if let Some(Ok(mut shutdown)) = shutdown.map(Mutex::lock) {
+            shutdown.try_send(true).unwrap_or_default();
+        }
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        io::{Read, Write},
+        net::TcpListener,
+    };
+
+    /// Checks that the errors reported with `critical!` are posted to the webhook.
+    #[test]
+    fn critical_error_is_posted_to_webhook() {
+        // Nothing is sent until the alerts are initialized.
+        report_critical("Not initialized");
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/alerts", listener.local_addr().unwrap());
+        let webhook = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            stream.set_read_timeout(Some(WEBHOOK_TIMEOUT)).unwrap();
+
+            let mut request = Vec::new();
+            let mut buffer = [0u8; 1024];
+            while !request.ends_with(b"}") {
+                let read = stream.read(&mut buffer).unwrap();
+                if read == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buffer[..read]);
+            }
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n")
+                .unwrap();
+            String::from_utf8(request).unwrap()
+        });
+
+        let guard = init(None, Some(&url), None);
+        crate::critical!("Block {} can't be committed", 42);
+        // Pending alerts are sent before the guard is dropped.
+        drop(guard);
+
+        let request = webhook.join().unwrap();
+        assert!(request.starts_with("POST /alerts "), "{}", request);
+        assert!(
+            request.ends_with(r#"{"text":"Block 42 can't be committed"}"#),
+            "{}",
+            request
+        );
+    }
+}
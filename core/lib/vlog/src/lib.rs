@@ -20,6 +20,18 @@
 //!
 //! But I couldn't easily replicate its default behavior in my custom logger.
 //!
+//! The crate also provides the [`init`] function which sets up the logging of the server
+//! binaries. Transactions are traced through the server with `tracing` spans: the `tx_hash`
+//! field is the correlation ID of the `submit_tx` (API), `mempool_add_tx` and `tx` (state keeper)
+//! spans, while the `seal_block`, `commit_block` and `eth_operation` spans carry the block numbers.
+//! Every event emitted inside a span is printed with the fields of the span, so the life of a
+//! transaction can be reconstructed by grepping the logs for its hash and then for the number
+//! of the block it was included into.
+//!
+//! Panics and critical errors are reported to the external services by the [`alerts`] module.
+//!
+
+pub mod alerts;
 
 #[macro_export]
 macro_rules! warn {
@@ -46,3 +58,27 @@ macro_rules! error {
         );
     };
 }
+
+/// Logs the error and reports it as an alert (see the [`alerts`] module), should be used
+/// for the failures requiring the immediate attention of the operator.
+#[macro_export]
+macro_rules! critical {
+    ($($arg:tt)*) => {{
+        let message = format!($($arg)*);
+        log::error!("[{}:{}:{}] {}", file!(), line!(), column!(), message);
+        $crate::alerts::report_critical(&message);
+    }};
+}
+
+/// Initializes the logging of the application: both `tracing` events and `log` records
+/// are printed, filtered according to the `RUST_LOG` variable.
+///
+/// Setting `LOG_FORMAT=json` switches the output to JSON lines suitable for the log aggregation.
+pub fn init() {
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env());
+    match std::env::var("LOG_FORMAT").as_deref() {
+        Ok("json") => builder.json().init(),
+        _ => builder.init(),
+    }
+}
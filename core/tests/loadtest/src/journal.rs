@@ -1,7 +1,7 @@
 // Built-in import
 use std::{
     cmp::{max, min},
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     time::{Duration, Instant},
 };
 // External uses
@@ -31,6 +31,16 @@ impl TxLifecycle {
     pub fn verify_duration(&self) -> Duration {
         self.verified_at.duration_since(self.committed_at)
     }
+
+    /// Time from the submission to the moment the operation was committed.
+    pub fn total_commit_duration(&self) -> Duration {
+        self.committed_at.duration_since(self.created_at)
+    }
+
+    /// Time from the submission to the moment the operation was verified.
+    pub fn total_verify_duration(&self) -> Duration {
+        self.verified_at.duration_since(self.created_at)
+    }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -38,6 +48,9 @@ pub struct Journal {
     txs: HashMap<TxHash, TxLifecycle>,
     total_count: usize,
     errored_count: usize,
+    priority_ops: HashMap<u64, TxLifecycle>,
+    priority_ops_total_count: usize,
+    priority_ops_errored_count: usize,
 }
 
 impl Journal {
@@ -55,40 +68,74 @@ impl Journal {
         }
     }
 
+    pub fn record_priority_op(
+        &mut self,
+        serial_id: u64,
+        op_result: Result<TxLifecycle, anyhow::Error>,
+    ) {
+        self.priority_ops_total_count += 1;
+
+        match op_result {
+            Ok(op_lifecycle) => {
+                self.priority_ops.insert(serial_id, op_lifecycle);
+            }
+            Err(err) => {
+                self.priority_ops_errored_count += 1;
+                save_error("scenarios", err);
+            }
+        }
+    }
+
     pub fn clear(&mut self) {
-        self.txs.clear()
+        self.txs.clear();
+        self.priority_ops.clear();
     }
 
     pub fn report(&self) -> ScenariosTestsReport {
+        let mut summary = BTreeMap::new();
+        Self::summarize(&mut summary, "", self.txs.values());
+        Self::summarize(&mut summary, "priority_op/", self.priority_ops.values());
+
+        ScenariosTestsReport {
+            summary,
+            total_txs_count: self.total_count,
+            failed_txs_count: self.errored_count,
+            total_priority_ops_count: self.priority_ops_total_count,
+            failed_priority_ops_count: self.priority_ops_errored_count,
+        }
+    }
+
+    /// Adds the stats for each lifecycle step to the summary.
+    /// Steps without enough samples are skipped.
+    fn summarize<'a>(
+        summary: &mut BTreeMap<String, FiveSummaryStats>,
+        prefix: &str,
+        lifecycles: impl Iterator<Item = &'a TxLifecycle>,
+    ) {
         let mut sending = Vec::new();
         let mut committing = Vec::new();
         let mut verifying = Vec::new();
-
-        for tx_lifecycle in self.txs.values() {
-            sending.push(tx_lifecycle.send_duration().as_micros());
-            committing.push(tx_lifecycle.commit_duration().as_micros());
-            verifying.push(tx_lifecycle.verify_duration().as_micros());
+        let mut total_committing = Vec::new();
+        let mut total_verifying = Vec::new();
+
+        for lifecycle in lifecycles {
+            sending.push(lifecycle.send_duration().as_micros());
+            committing.push(lifecycle.commit_duration().as_micros());
+            verifying.push(lifecycle.verify_duration().as_micros());
+            total_committing.push(lifecycle.total_commit_duration().as_micros());
+            total_verifying.push(lifecycle.total_verify_duration().as_micros());
         }
 
-        let summary = [
+        for (category, data) in &[
             ("sending", sending),
             ("committing", committing),
             ("verifying", verifying),
-        ]
-        .iter()
-        .map(|(category, data)| {
-            (
-                category.to_string(),
-                FiveSummaryStats::from_data(data)
-                    .expect("Not enough data to compute summary stats"),
-            )
-        })
-        .collect();
-
-        ScenariosTestsReport {
-            summary,
-            total_txs_count: self.total_count,
-            failed_txs_count: self.errored_count,
+            ("total_committing", total_committing),
+            ("total_verifying", total_verifying),
+        ] {
+            if let Some(stats) = FiveSummaryStats::from_data(data) {
+                summary.insert(format!("{}{}", prefix, category), stats);
+            }
         }
     }
 }
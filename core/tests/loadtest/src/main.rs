@@ -16,6 +16,13 @@
 //!
 //! - full_exit (incomplete) - performs several full_exit / deposit operations.
 //!
+//! - mix - performs a configurable mix of transfers, withdrawals and deposits. The sequence
+//!   of operations is determined by the seed, so the runs with the same config are comparable.
+//!
+//! For every scenario the time from the submission to the commitment and verification
+//! is measured. The report is saved as `output.json` into the output directory and can be
+//! used to track the performance regressions between the runs.
+//!
 
 // Built-in import
 use std::path::PathBuf;
@@ -126,6 +133,12 @@ async fn main() -> Result<(), anyhow::Error> {
             report.scenarios.failed_txs_count,
             report.scenarios.total_txs_count,
         );
+        if report.scenarios.total_priority_ops_count > 0 {
+            print_counters(
+                report.scenarios.failed_priority_ops_count,
+                report.scenarios.total_priority_ops_count,
+            );
+        }
 
         println!("Statistics for API tests:");
         for (category, stats) in &report.api {
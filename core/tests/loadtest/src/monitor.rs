@@ -122,6 +122,12 @@ impl MonitorInner {
         }
     }
 
+    fn record_priority_op(&mut self, serial_id: SerialId, op_result: anyhow::Result<TxLifecycle>) {
+        if self.enabled {
+            self.journal.record_priority_op(serial_id, op_result);
+        }
+    }
+
     fn store_stats(&mut self) {
         let mut stats = Stats::default();
 
@@ -311,6 +317,7 @@ impl Monitor {
         eth_provider: &EthereumProvider<S>,
         eth_tx_hash: H256,
     ) -> anyhow::Result<PriorityOp> {
+        let created_at = Instant::now();
         // Wait for the corresponing priority operation ID.
         let priority_op = eth_provider
             .wait_for_tx(eth_tx_hash)
@@ -323,20 +330,26 @@ impl Monitor {
             .write()
             .await
             .store_priority_op(priority_op.clone());
+        let sent_at = Instant::now();
 
         let monitor = self.clone();
         let priority_op2 = priority_op.clone();
         let handle = tokio::spawn(async move {
-            if let Err(e) = monitor
+            let op_result = monitor
                 .clone()
-                .monitor_priority_op(priority_op2.clone())
-                .await
-            {
+                .monitor_priority_op(created_at, sent_at, priority_op2.clone())
+                .await;
+
+            if let Err(e) = &op_result {
                 log::warn!("Monitored priority op execution failed. {}", e);
                 monitor
                     .log_event(Event::OpErrored(priority_op2.serial_id))
                     .await;
             }
+
+            monitor
+                .record_priority_op(priority_op2.serial_id, op_result)
+                .await;
         });
         self.inner().await.pending_tasks.push(handle);
 
@@ -355,6 +368,14 @@ impl Monitor {
         self.inner().await.record_tx(tx_hash, tx_result)
     }
 
+    async fn record_priority_op(
+        &self,
+        serial_id: SerialId,
+        op_result: anyhow::Result<TxLifecycle>,
+    ) {
+        self.inner().await.record_priority_op(serial_id, op_result)
+    }
+
     async fn monitor_tx(
         self,
         created_at: Instant,
@@ -390,21 +411,33 @@ impl Monitor {
         })
     }
 
-    async fn monitor_priority_op(self, priority_op: PriorityOp) -> anyhow::Result<()> {
+    async fn monitor_priority_op(
+        self,
+        created_at: Instant,
+        sent_at: Instant,
+        priority_op: PriorityOp,
+    ) -> anyhow::Result<TxLifecycle> {
         self.log_event(Event::OpSent(priority_op.serial_id)).await;
 
         // Wait until the priority operation is committed.
         self.wait_for_priority_op(BlockStatus::Committed, &priority_op)
             .await?;
+        let committed_at = Instant::now();
         self.log_event(Event::OpExecuted(priority_op.serial_id))
             .await;
 
         // Wait until the priority operation is became a part of some block and get verified.
         self.wait_for_priority_op(BlockStatus::Verified, &priority_op)
             .await?;
+        let verified_at = Instant::now();
         self.log_event(Event::OpVerified(priority_op.serial_id))
             .await;
 
-        Ok(())
+        Ok(TxLifecycle {
+            created_at,
+            sent_at,
+            committed_at,
+            verified_at,
+        })
     }
 }
@@ -0,0 +1,271 @@
+// Built-in uses
+use std::fmt;
+// External uses
+use async_trait::async_trait;
+use num::BigUint;
+use rand::{Rng, SeedableRng, XorShiftRng};
+use serde::{Deserialize, Serialize};
+// Workspace uses
+use zksync::{types::BlockStatus, utils::closest_packable_token_amount};
+use zksync_types::{tx::PackedEthSignature, ZkSyncTx};
+// Local uses
+use super::{Fees, Scenario, ScenarioResources};
+use crate::{
+    monitor::Monitor,
+    test_wallet::TestWallet,
+    utils::{gwei_to_wei, wait_all_failsafe, wait_all_failsafe_chunks, CHUNK_SIZES},
+};
+
+/// Configuration options for the mixed operations scenario.
+#[derive(Debug, Serialize, Deserialize, Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
+pub struct MixScenarioConfig {
+    /// Amount of money to be used in every operation, in gwei.
+    pub operation_size: u64,
+    /// Total amount of operations to perform.
+    pub operations: u64,
+    /// Relative frequency of the transfers.
+    pub transfer_weight: u32,
+    /// Relative frequency of the withdrawals.
+    pub withdraw_weight: u32,
+    /// Relative frequency of the deposits.
+    pub deposit_weight: u32,
+    /// Amount of intermediate wallets to use.
+    pub wallets_amount: u64,
+    /// Seed for the operations sequence: the same seed results in the same operations.
+    #[serde(default)]
+    pub seed: u32,
+}
+
+impl Default for MixScenarioConfig {
+    fn default() -> Self {
+        Self {
+            operation_size: 1,
+            operations: 100,
+            transfer_weight: 8,
+            withdraw_weight: 1,
+            deposit_weight: 1,
+            wallets_amount: 10,
+            seed: 0,
+        }
+    }
+}
+
+impl From<MixScenarioConfig> for MixScenario {
+    fn from(config: MixScenarioConfig) -> Self {
+        Self::new(config)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OperationKind {
+    Transfer,
+    Withdraw,
+    Deposit,
+}
+
+/// Mixed scenario performs a configurable mix of transfers, withdrawals and deposits.
+///
+/// The sequence of operations is generated from the seed, and operations are assigned
+/// to the wallets in turn, so the runs with the same config send the same load and
+/// their reports can be compared with each other.
+#[derive(Debug)]
+pub struct MixScenario {
+    config: MixScenarioConfig,
+    operation_size: BigUint,
+    /// Operations in the order of sending along with the index of the sender wallet.
+    operations: Vec<(usize, OperationKind)>,
+    txs: Vec<(ZkSyncTx, Option<PackedEthSignature>)>,
+}
+
+impl MixScenario {
+    pub fn new(config: MixScenarioConfig) -> Self {
+        let total_weight = config.transfer_weight + config.withdraw_weight + config.deposit_weight;
+        assert!(
+            total_weight > 0,
+            "At least one operation weight should be set"
+        );
+        assert!(
+            config.wallets_amount > 0,
+            "At least one wallet should be used"
+        );
+
+        let seed = config.seed;
+        let mut rng = XorShiftRng::from_seed([
+            seed,
+            seed.wrapping_add(1),
+            seed.wrapping_add(2),
+            seed.wrapping_add(3),
+        ]);
+        let operations = (0..config.operations)
+            .map(|idx| {
+                let value = rng.gen_range(0, total_weight);
+                let kind = if value < config.transfer_weight {
+                    OperationKind::Transfer
+                } else if value < config.transfer_weight + config.withdraw_weight {
+                    OperationKind::Withdraw
+                } else {
+                    OperationKind::Deposit
+                };
+                ((idx % config.wallets_amount) as usize, kind)
+            })
+            .collect();
+
+        Self {
+            operation_size: gwei_to_wei(config.operation_size),
+            config,
+            operations,
+            txs: Vec::new(),
+        }
+    }
+
+    /// Returns the amount of operations of the given kind performed by each wallet.
+    fn operations_per_wallet(&self, kind: OperationKind) -> Vec<u64> {
+        let mut counts = vec![0; self.config.wallets_amount as usize];
+        for (wallet, _) in self.operations.iter().filter(|(_, op)| *op == kind) {
+            counts[*wallet] += 1;
+        }
+        counts
+    }
+
+    /// Amount of funds on Ethereum required for the wallet to perform its deposits.
+    fn deposits_amount(&self, fees: &Fees, deposits: u64) -> BigUint {
+        closest_packable_token_amount(
+            &((&self.operation_size + &fees.eth) * BigUint::from(deposits)),
+        )
+    }
+}
+
+impl fmt::Display for MixScenario {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("mix")
+    }
+}
+
+#[async_trait]
+impl Scenario for MixScenario {
+    fn requested_resources(&self, fees: &Fees) -> ScenarioResources {
+        let max_operations = |kind| {
+            self.operations_per_wallet(kind)
+                .into_iter()
+                .max()
+                .unwrap_or_default()
+        };
+        let zksync_operations =
+            max_operations(OperationKind::Transfer) + max_operations(OperationKind::Withdraw);
+        let deposits = max_operations(OperationKind::Deposit);
+
+        let balance_per_wallet = (&self.operation_size + &fees.zksync)
+            * BigUint::from(zksync_operations)
+            + self.deposits_amount(fees, deposits)
+            + &fees.zksync;
+
+        ScenarioResources {
+            balance_per_wallet: closest_packable_token_amount(&balance_per_wallet),
+            wallets_amount: self.config.wallets_amount,
+        }
+    }
+
+    async fn prepare(
+        &mut self,
+        monitor: &Monitor,
+        fees: &Fees,
+        wallets: &[TestWallet],
+    ) -> anyhow::Result<()> {
+        // Withdraw the funds required for the deposits to the Ethereum.
+        let deposits = self.operations_per_wallet(OperationKind::Deposit);
+        let mut withdrawals = Vec::new();
+        for (wallet, count) in wallets.iter().zip(deposits).filter(|(_, n)| *n > 0) {
+            let amount = self.deposits_amount(fees, count);
+            let (tx, sign) = wallet
+                .sign_withdraw(amount.clone(), fees.zksync.clone())
+                .await?;
+            let tx_hash = monitor.send_tx(tx, sign).await?;
+            withdrawals.push(async move {
+                monitor.wait_for_tx(BlockStatus::Verified, tx_hash).await?;
+                await_condition!(
+                    std::time::Duration::from_millis(100),
+                    wallet.eth_balance().await? >= amount
+                );
+                Ok(()) as anyhow::Result<()>
+            });
+        }
+        wait_all_failsafe("mix/prepare/withdraw", withdrawals.into_iter()).await?;
+
+        log::info!(
+            "Funds for the deposits are withdrawn, creating {} transactions",
+            self.operations
+                .iter()
+                .filter(|(_, op)| *op != OperationKind::Deposit)
+                .count()
+        );
+
+        // Transactions are signed in the order of sending to keep the nonces sequential.
+        let wallets_amount = wallets.len();
+        for (from, kind) in &self.operations {
+            let wallet = &wallets[*from];
+            let tx = match kind {
+                OperationKind::Transfer => {
+                    let to = wallets[(from + 1) % wallets_amount].address();
+                    wallet
+                        .sign_transfer(
+                            to,
+                            closest_packable_token_amount(&self.operation_size),
+                            fees.zksync.clone(),
+                        )
+                        .await?
+                }
+                OperationKind::Withdraw => {
+                    wallet
+                        .sign_withdraw(
+                            closest_packable_token_amount(&self.operation_size),
+                            fees.zksync.clone(),
+                        )
+                        .await?
+                }
+                OperationKind::Deposit => continue,
+            };
+            self.txs.push(tx);
+        }
+
+        Ok(())
+    }
+
+    async fn run(
+        &mut self,
+        monitor: &Monitor,
+        _fees: &Fees,
+        wallets: &[TestWallet],
+    ) -> anyhow::Result<()> {
+        let deposit_amount = closest_packable_token_amount(&self.operation_size);
+        let deposits = self
+            .operations
+            .iter()
+            .filter(|(_, op)| *op == OperationKind::Deposit)
+            .map(|(from, _)| wallets[*from].deposit(deposit_amount.clone()));
+        let txs = self
+            .txs
+            .drain(..)
+            .map(|(tx, sign)| monitor.send_tx(tx, sign));
+
+        // Deposits are sent concurrently with the zkSync transactions.
+        let (deposits, txs) = futures::join!(
+            wait_all_failsafe_chunks("mix/run/deposit", CHUNK_SIZES, deposits),
+            wait_all_failsafe_chunks("mix/run/send_tx", CHUNK_SIZES, txs)
+        );
+        deposits?;
+        txs?;
+
+        log::info!("Mix scenario has been finished");
+
+        Ok(())
+    }
+
+    async fn finalize(
+        &mut self,
+        _monitor: &Monitor,
+        _fees: &Fees,
+        _wallets: &[TestWallet],
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
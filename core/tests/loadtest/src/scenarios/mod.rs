@@ -4,7 +4,7 @@
 //! operations between them".
 
 pub use self::{
-    full_exit::FullExitScenarioConfig, transfers::TransferScenarioConfig,
+    full_exit::FullExitScenarioConfig, mix::MixScenarioConfig, transfers::TransferScenarioConfig,
     withdraw::WithdrawScenarioConfig,
 };
 
@@ -19,10 +19,14 @@ use num::BigUint;
 use serde::{Deserialize, Serialize};
 // Workspace uses
 // Local uses
-use self::{full_exit::FullExitScenario, transfers::TransferScenario, withdraw::WithdrawScenario};
+use self::{
+    full_exit::FullExitScenario, mix::MixScenario, transfers::TransferScenario,
+    withdraw::WithdrawScenario,
+};
 use crate::{monitor::Monitor, test_wallet::TestWallet, FiveSummaryStats};
 
 mod full_exit;
+mod mix;
 mod transfers;
 mod withdraw;
 
@@ -87,6 +91,8 @@ pub enum ScenarioConfig {
     Withdraw(WithdrawScenarioConfig),
     /// Full exit / deposit scenario.
     FullExit(FullExitScenarioConfig),
+    /// Mix of transfers, withdrawals and deposits scenario.
+    Mix(MixScenarioConfig),
 }
 
 impl ScenarioConfig {
@@ -96,6 +102,7 @@ impl ScenarioConfig {
             Self::Transfer(cfg) => Box::new(TransferScenario::from(cfg)),
             Self::Withdraw(cfg) => Box::new(WithdrawScenario::from(cfg)),
             Self::FullExit(cfg) => Box::new(FullExitScenario::from(cfg)),
+            Self::Mix(cfg) => Box::new(MixScenario::from(cfg)),
         }
     }
 }
@@ -109,4 +116,8 @@ pub struct ScenariosTestsReport {
     pub total_txs_count: usize,
     /// Amount of failed requests regardless of the cause of the failure.
     pub failed_txs_count: usize,
+    /// Total amount of sent priority operations.
+    pub total_priority_ops_count: usize,
+    /// Amount of failed priority operations regardless of the cause of the failure.
+    pub failed_priority_ops_count: usize,
 }
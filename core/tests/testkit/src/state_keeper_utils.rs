@@ -3,6 +3,7 @@ use futures::{
     SinkExt,
 };
 use std::thread::JoinHandle;
+use std::time::Duration;
 use tokio::runtime::Runtime;
 use zksync_core::committer::CommitRequest;
 use zksync_core::state_keeper::{start_state_keeper, StateKeeperRequest, ZkSyncStateKeeper};
@@ -57,10 +58,13 @@ pub fn spawn_state_keeper(
         *fee_account,
         state_keeper_req_receiver,
         proposed_blocks_sender,
+        Default::default(),
         block_chunks_sizes,
         max_miniblock_iterations,
         max_miniblock_iterations,
+        Duration::from_secs(3600),
         super::MAX_WITHDRAWALS_PER_BLOCK as usize,
+        false,
     );
 
     let (stop_state_keeper_sender, stop_state_keeper_receiver) = oneshot::channel::<()>();
@@ -0,0 +1,15 @@
+#!/bin/bash
+
+set -e
+
+export ZKSYNC_HOME="/"
+
+# Server verifies the aggregated proofs before sending them to the contract,
+# so it needs the verification keys used by the provers.
+VERIFY_KEYS_TARBAL="verify-keys-`basename $KEY_DIR`-account-"$ACCOUNT_TREE_DEPTH"_-balance-$BALANCE_TREE_DEPTH.tar.gz"
+
+[ -f keys/packed/$VERIFY_KEYS_TARBAL ] || (echo Keys file $VERIFY_KEYS_TARBAL not found && exit 1)
+tar xf keys/packed/$VERIFY_KEYS_TARBAL
+echo Keys unpacked, starting server
+
+exec zksync_server "$@"
@@ -0,0 +1,137 @@
+//! Client of the server admin API.
+
+// Built-in deps
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// External uses
+use anyhow::Result;
+use jsonwebtoken::{encode, EncodingKey, Header};
+use reqwest::{Client, RequestBuilder, StatusCode};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+// Workspace uses
+use zksync_config::AdminServerOptions;
+use zksync_types::admin::{ContractUpgradeInfo, EthOperationInfo, MaintenanceStatus};
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PayloadAuthToken {
+    /// Subject (whom auth token refers to).
+    sub: String,
+    /// Expiration time (as UTC timestamp).
+    exp: usize,
+}
+
+/// Client of the admin API, which authorizes every request with a short-lived token.
+#[derive(Debug)]
+pub struct AdminClient {
+    client: Client,
+    url: Url,
+    secret_auth: String,
+}
+
+impl AdminClient {
+    pub fn new(options: AdminServerOptions) -> Self {
+        Self {
+            client: Client::new(),
+            url: options.admin_http_server_url,
+            secret_auth: options.secret_auth,
+        }
+    }
+
+    pub async fn eth_operations(&self) -> Result<Vec<EthOperationInfo>> {
+        self.send(self.client.get(self.url.join("eth_sender/operations")?))
+            .await?
+            .json()
+            .await
+            .map_err(From::from)
+    }
+
+    pub async fn requeue_eth_operation(&self, id: i64) -> Result<()> {
+        let path = format!("eth_sender/operations/{}/requeue", id);
+        self.send(self.client.post(self.url.join(&path)?)).await?;
+        Ok(())
+    }
+
+    pub async fn drop_eth_operation(&self, id: i64) -> Result<()> {
+        let path = format!("eth_sender/operations/{}/drop", id);
+        self.send(self.client.post(self.url.join(&path)?)).await?;
+        Ok(())
+    }
+
+    pub async fn reload_eth_operations(&self) -> Result<()> {
+        self.send(self.client.post(self.url.join("eth_sender/reload")?))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn revert_blocks(&self) -> Result<()> {
+        self.send(self.client.post(self.url.join("blocks/revert")?))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn contract_upgrades(&self) -> Result<Vec<ContractUpgradeInfo>> {
+        self.send(
+            self.client
+                .get(self.url.join("eth_sender/contract_upgrades")?),
+        )
+        .await?
+        .json()
+        .await
+        .map_err(From::from)
+    }
+
+    pub async fn confirm_contract_upgrade(&self, version_id: u64) -> Result<()> {
+        let path = format!("eth_sender/contract_upgrades/{}/confirm", version_id);
+        self.send(self.client.post(self.url.join(&path)?)).await?;
+        Ok(())
+    }
+
+    pub async fn maintenance_status(&self) -> Result<MaintenanceStatus> {
+        self.send(self.client.get(self.url.join("maintenance")?))
+            .await?
+            .json()
+            .await
+            .map_err(From::from)
+    }
+
+    pub async fn enable_maintenance(&self) -> Result<()> {
+        self.send(self.client.post(self.url.join("maintenance/enable")?))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn disable_maintenance(&self) -> Result<()> {
+        self.send(self.client.post(self.url.join("maintenance/disable")?))
+            .await?;
+        Ok(())
+    }
+
+    async fn send(&self, request: RequestBuilder) -> Result<reqwest::Response> {
+        let response = request.bearer_auth(self.auth_token()?).send().await?;
+        if response.status() != StatusCode::OK {
+            anyhow::bail!(
+                "Admin server responded with a non-OK response: {}: {}",
+                response.status(),
+                response.text().await.unwrap_or_default()
+            );
+        }
+        Ok(response)
+    }
+
+    /// Encodes the JsonWebToken valid for a minute.
+    fn auth_token(&self) -> Result<String> {
+        let exp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() + 60;
+        let payload = PayloadAuthToken {
+            sub: "Authorization".to_string(),
+            exp: exp as usize,
+        };
+        let token = encode(
+            &Header::default(),
+            &payload,
+            &EncodingKey::from_secret(self.secret_auth.as_ref()),
+        )?;
+        Ok(token)
+    }
+}
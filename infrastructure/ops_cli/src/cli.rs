@@ -0,0 +1,94 @@
+//! Command-line interface for the operator tool.
+
+use structopt::StructOpt;
+
+/// CLI parameters.
+#[derive(Debug, StructOpt)]
+#[structopt(
+    name = "ops_cli",
+    about = "Tool for inspecting and changing the operational state of the server"
+)]
+pub enum App {
+    #[structopt(
+        name = "eth-sender",
+        about = "Inspect and manage the operations sent by eth_sender"
+    )]
+    EthSender(EthSenderCommand),
+    #[structopt(
+        name = "revert-blocks",
+        about = "Request the revert of the unverified blocks on the next start of the server leader"
+    )]
+    RevertBlocks,
+    #[structopt(
+        name = "maintenance",
+        about = "Stop accepting transactions and creating blocks before the server shutdown"
+    )]
+    Maintenance(MaintenanceCommand),
+}
+
+#[derive(Debug, StructOpt)]
+pub enum EthSenderCommand {
+    #[structopt(
+        name = "list",
+        about = "List the operations sent but not confirmed yet"
+    )]
+    List,
+    #[structopt(
+        name = "requeue",
+        about = "Cancel the transactions of the latest operation and send it again"
+    )]
+    Requeue(OperationOpts),
+    #[structopt(
+        name = "drop",
+        about = "Cancel the transactions of the latest operation and stop tracking it"
+    )]
+    Drop(OperationOpts),
+    #[structopt(
+        name = "reload",
+        about = "Reload the unconfirmed operations and check their confirmations again"
+    )]
+    Reload,
+    #[structopt(
+        name = "upgrades",
+        about = "List the observed upgrades of the zkSync contract"
+    )]
+    Upgrades,
+    #[structopt(
+        name = "confirm-upgrade",
+        about = "Confirm the completed contract upgrade, so eth_sender resumes sending transactions"
+    )]
+    ConfirmUpgrade(UpgradeOpts),
+}
+
+#[derive(Debug, StructOpt)]
+pub enum MaintenanceCommand {
+    #[structopt(
+        name = "status",
+        about = "Show whether the server can be stopped without interrupting the users"
+    )]
+    Status,
+    #[structopt(
+        name = "enable",
+        about = "Reject the incoming transactions and seal the pending block"
+    )]
+    Enable,
+    #[structopt(
+        name = "disable",
+        about = "Resume accepting transactions and creating blocks"
+    )]
+    Disable,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct OperationOpts {
+    /// ID of the Ethereum operation, as shown by `eth-sender list`.
+    #[structopt(name = "id")]
+    pub id: i64,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct UpgradeOpts {
+    /// Version of the contract the upgrade results in, as shown by `eth-sender upgrades`.
+    #[structopt(name = "version")]
+    pub version_id: u64,
+}
@@ -0,0 +1,150 @@
+//! Operator tool, which changes the operational state of the server through its admin
+//! interfaces rather than through the raw database queries.
+
+mod admin;
+mod cli;
+
+// External uses
+use anyhow::Result;
+use structopt::StructOpt;
+
+// Workspace uses
+use zksync_config::AdminServerOptions;
+use zksync_types::admin::{ContractUpgradeInfo, EthOperationInfo};
+
+// Local uses
+use admin::AdminClient;
+use cli::{App, EthSenderCommand, MaintenanceCommand};
+
+fn print_eth_operation(op: &EthOperationInfo) {
+    let blocks = op
+        .blocks
+        .map(|(from, to)| format!("{}-{}", from, to))
+        .unwrap_or_else(|| "-".to_string());
+    println!(
+        "{}\t{}\tblocks {}\tnonce {}\tgas price {}\tdeadline block {}",
+        op.id, op.op_type, blocks, op.nonce, op.last_used_gas_price, op.last_deadline_block
+    );
+    for hash in &op.tx_hashes {
+        println!("\t{:#x}", hash);
+    }
+}
+
+fn print_contract_upgrade(upgrade: &ContractUpgradeInfo) {
+    let confirmed = upgrade
+        .confirmed_at
+        .map(|time| format!("confirmed at {}", time))
+        .unwrap_or_else(|| "not confirmed".to_string());
+    println!(
+        "version {}\t{}\tEthereum block {}\t{}",
+        upgrade.version_id, upgrade.stage, upgrade.eth_block, confirmed
+    );
+}
+
+async fn run_eth_sender_command(command: EthSenderCommand) -> Result<()> {
+    let client = AdminClient::new(AdminServerOptions::from_env());
+
+    match command {
+        EthSenderCommand::List => {
+            let operations = client.eth_operations().await?;
+            if operations.is_empty() {
+                println!("There are no unconfirmed operations");
+            }
+            for op in &operations {
+                print_eth_operation(op);
+            }
+        }
+        EthSenderCommand::Requeue(opts) => {
+            client.requeue_eth_operation(opts.id).await?;
+            println!(
+                "Requeue of operation {} is requested, check the eth_sender logs for the result",
+                opts.id
+            );
+        }
+        EthSenderCommand::Drop(opts) => {
+            client.drop_eth_operation(opts.id).await?;
+            println!(
+                "Drop of operation {} is requested, check the eth_sender logs for the result",
+                opts.id
+            );
+        }
+        EthSenderCommand::Reload => {
+            client.reload_eth_operations().await?;
+            println!("Reload of the unconfirmed operations is requested");
+        }
+        EthSenderCommand::Upgrades => {
+            let upgrades = client.contract_upgrades().await?;
+            if upgrades.is_empty() {
+                println!("There are no observed contract upgrades");
+            }
+            for upgrade in &upgrades {
+                print_contract_upgrade(upgrade);
+            }
+        }
+        EthSenderCommand::ConfirmUpgrade(opts) => {
+            client.confirm_contract_upgrade(opts.version_id).await?;
+            println!(
+                "Contract upgrade to version {} is confirmed, eth_sender will resume sending transactions",
+                opts.version_id
+            );
+        }
+    }
+    Ok(())
+}
+
+async fn run_maintenance_command(command: MaintenanceCommand) -> Result<()> {
+    let client = AdminClient::new(AdminServerOptions::from_env());
+
+    match command {
+        MaintenanceCommand::Status => {
+            let status = client.maintenance_status().await?;
+            println!(
+                "maintenance mode {}	mempool transactions {}	pending block operations {}	unconfirmed eth operations {}",
+                if status.enabled {
+                    "enabled"
+                } else {
+                    "disabled"
+                },
+                status.mempool_txs,
+                status.pending_block_operations,
+                status.unconfirmed_eth_operations
+            );
+            if status.can_be_stopped() {
+                println!("The server can be stopped");
+            }
+        }
+        MaintenanceCommand::Enable => {
+            client.enable_maintenance().await?;
+            println!("Maintenance mode is enabled, wait for `maintenance status` to report the server can be stopped");
+        }
+        MaintenanceCommand::Disable => {
+            client.disable_maintenance().await?;
+            println!("Maintenance mode is disabled");
+        }
+    }
+    Ok(())
+}
+
+/// Requests the revert of the unverified blocks. The revert is performed by the server
+/// leader on start, so no replica can process the blocks in the meantime.
+async fn revert_blocks() -> Result<()> {
+    let client = AdminClient::new(AdminServerOptions::from_env());
+    client.revert_blocks().await?;
+    println!(
+        "Revert of the unverified blocks is requested, restart the server leader to perform it \
+         and check the server logs for the result"
+    );
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    env_logger::init();
+
+    match App::from_args() {
+        App::EthSender(command) => run_eth_sender_command(command).await?,
+        App::RevertBlocks => revert_blocks().await?,
+        App::Maintenance(command) => run_maintenance_command(command).await?,
+    }
+    Ok(())
+}
@@ -180,11 +180,14 @@ command
     .command('exit-proof')
     .option('--account <id>')
     .option('--token <id>')
+    .option('--backup <path>', 'load the verified state from the backup file instead of the database')
     .option('--help')
     .description('generate exit proof')
     .action(async (cmd: Command) => {
         if (!cmd.account || !cmd.token) {
             await exitProof('--help');
+        } else if (cmd.backup) {
+            await exitProof('--account_id', cmd.account, '--token', cmd.token, '--backup', cmd.backup);
         } else {
             await exitProof('--account_id', cmd.account, '--token', cmd.token);
         }
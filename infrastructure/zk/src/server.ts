@@ -4,8 +4,9 @@ import * as env from './env';
 import fs from 'fs';
 import * as db from './db/db';
 
-export async function server() {
-    await utils.spawn('cargo run --bin zksync_server --release');
+export async function server(apiOnly: boolean = false) {
+    const args = apiOnly ? ' -- --api-only' : '';
+    await utils.spawn(`cargo run --bin zksync_server --release${args}`);
 }
 
 export async function genesis() {
@@ -31,10 +32,11 @@ export async function genesis() {
 export const command = new Command('server')
     .description('start zksync server')
     .option('--genesis', 'generate genesis data via server')
+    .option('--api-only', 'serve only the read-only API from the shared database')
     .action(async (cmd: Command) => {
         if (cmd.genesis) {
             await genesis();
         } else {
-            await server();
+            await server(cmd.apiOnly);
         }
     });
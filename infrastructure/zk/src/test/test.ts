@@ -55,6 +55,16 @@ export async function prover() {
     await utils.spawn('cargo test -p zksync_prover --release -- --ignored');
 }
 
+export async function soak(iterations?: string, seed?: string) {
+    if (iterations) {
+        process.env.SOAK_ITERATIONS = iterations;
+    }
+    if (seed) {
+        process.env.SOAK_SEED = seed;
+    }
+    await utils.spawn('cargo test -p zksync_core --release soak -- --ignored --nocapture');
+}
+
 export async function js() {
     await utils.spawn('yarn zksync tests');
     await utils.spawn('yarn fee-seller tests');
@@ -74,6 +84,10 @@ export const command = new Command('test').description('run test suites').addCom
 command.command('js').description('run unit-tests for javascript packages').action(js);
 command.command('prover').description('run unit-tests for the prover').action(prover);
 command.command('contracts').description('run unit-tests for the contracts').action(contracts);
+command
+    .command('soak [iterations] [seed]')
+    .description('run randomized state keeper tests for a long time')
+    .action(soak);
 command.command('rust').description('run unit-tests for all rust binaries and libraries').action(rust);
 
 command
@@ -3,6 +3,7 @@ use zksync_eth_signer::EthereumSigner;
 use zksync_types::{
     helpers::{closest_packable_fee_amount, is_fee_amount_packable},
     tokens::TxFeeTypes,
+    tx::{ChangePubKeyCREATE2Data, ChangePubKeyEthAuthData},
     Nonce, Token, TokenLike, ZkSyncTx,
 };
 
@@ -13,7 +14,7 @@ use crate::{
 #[derive(Debug)]
 pub struct ChangePubKeyBuilder<'a, S: EthereumSigner, P: Provider> {
     wallet: &'a Wallet<S, P>,
-    onchain_auth: bool,
+    eth_auth_data: Option<ChangePubKeyEthAuthData>,
     fee_token: Option<Token>,
     fee: Option<BigUint>,
     nonce: Option<Nonce>,
@@ -28,7 +29,7 @@ where
     pub fn new(wallet: &'a Wallet<S, P>) -> Self {
         Self {
             wallet,
-            onchain_auth: false,
+            eth_auth_data: None,
             fee_token: None,
             fee: None,
             nonce: None,
@@ -37,6 +38,7 @@ where
 
     /// Sends the transaction, returning the handle for its awaiting.
     pub async fn tx(self) -> Result<ZkSyncTx, ClientError> {
+        let onchain_pubkey_auth = self.is_onchain_auth();
         let fee_token = self
             .fee_token
             .ok_or_else(|| ClientError::MissingRequiredField("fee_token".into()))?;
@@ -49,7 +51,7 @@ where
                     .provider
                     .get_tx_fee(
                         TxFeeTypes::ChangePubKey {
-                            onchain_pubkey_auth: self.onchain_auth,
+                            onchain_pubkey_auth,
                         },
                         self.wallet.address(),
                         fee_token.id,
@@ -74,7 +76,7 @@ where
         Ok(ZkSyncTx::from(
             self.wallet
                 .signer
-                .sign_change_pubkey_tx(nonce, self.onchain_auth, fee_token, fee)
+                .sign_change_pubkey_tx(nonce, self.eth_auth_data, fee_token, fee)
                 .await
                 .map_err(ClientError::SigningError)?,
         ))
@@ -134,4 +136,29 @@ where
         self.nonce = Some(nonce);
         self
     }
+
+    /// Authorizes the transaction by the auth fact set on the zkSync contract
+    /// via `setAuthPubkeyHash` instead of the Ethereum signature.
+    ///
+    /// Useful for the accounts that can't produce an Ethereum signature, e.g. contract wallets.
+    pub fn onchain_auth(mut self) -> Self {
+        self.eth_auth_data = Some(ChangePubKeyEthAuthData::Onchain);
+        self
+    }
+
+    /// Authorizes the transaction by the CREATE2 data the wallet address is derived from.
+    ///
+    /// The CREATE2 salt must commit to the new public key hash, so the address can't
+    /// be derived for any other signing key.
+    pub fn create2_auth(mut self, create2_data: ChangePubKeyCREATE2Data) -> Self {
+        self.eth_auth_data = Some(ChangePubKeyEthAuthData::CREATE2(create2_data));
+        self
+    }
+
+    fn is_onchain_auth(&self) -> bool {
+        self.eth_auth_data
+            .as_ref()
+            .map(ChangePubKeyEthAuthData::is_onchain)
+            .unwrap_or(false)
+    }
 }
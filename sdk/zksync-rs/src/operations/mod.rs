@@ -7,10 +7,12 @@ use zksync_types::tx::TxHash;
 use crate::{error::ClientError, provider::Provider, types::TransactionInfo};
 
 pub use self::{
-    change_pubkey::ChangePubKeyBuilder, transfer::TransferBuilder, withdraw::WithdrawBuilder,
+    change_pubkey::ChangePubKeyBuilder, multi_transfer::MultiTransferBuilder,
+    transfer::TransferBuilder, withdraw::WithdrawBuilder,
 };
 
 mod change_pubkey;
+mod multi_transfer;
 mod transfer;
 mod withdraw;
 
@@ -0,0 +1,216 @@
+use num::BigUint;
+use zksync_eth_signer::EthereumSigner;
+use zksync_types::{
+    helpers::{
+        closest_packable_fee_amount, closest_packable_token_amount, is_fee_amount_packable,
+        is_token_amount_packable,
+    },
+    tx::PackedEthSignature,
+    Address, Nonce, Token, TokenLike, TransferToNewOp, TxFeeTypes, ZkSyncTx,
+};
+
+use crate::{
+    error::ClientError, operations::SyncTransactionHandle, provider::Provider, wallet::Wallet,
+};
+
+/// Maximum number of chunks of the batch by default, which is the size of the largest block
+/// of the default server configuration.
+pub const DEFAULT_MAX_BATCH_CHUNKS: usize = 30;
+
+/// Builder of the transfer of one token to many recipients.
+///
+/// A batch must fit into a single block, so the recipients are split into the batches
+/// of at most `max_batch_chunks` chunks. The transfers of every batch are authorized by
+/// the single Ethereum signature and are executed atomically: either all of them or none.
+/// The fee is paid once for every batch and grows with the number of its recipients.
+#[derive(Debug)]
+pub struct MultiTransferBuilder<'a, S: EthereumSigner, P: Provider> {
+    wallet: &'a Wallet<S, P>,
+    token: Option<Token>,
+    recipients: Vec<(Address, BigUint)>,
+    fee: Option<BigUint>,
+    nonce: Option<Nonce>,
+    max_batch_chunks: usize,
+}
+
+impl<'a, S, P> MultiTransferBuilder<'a, S, P>
+where
+    S: EthereumSigner + Clone,
+    P: Provider + Clone,
+{
+    /// Initializes a multi-transfer transaction building process.
+    pub fn new(wallet: &'a Wallet<S, P>) -> Self {
+        Self {
+            wallet,
+            token: None,
+            recipients: Vec::new(),
+            fee: None,
+            nonce: None,
+            max_batch_chunks: DEFAULT_MAX_BATCH_CHUNKS,
+        }
+    }
+
+    /// Directly returns the batches of the signed transfer transactions along with
+    /// the Ethereum signatures of the batches for the subsequent usage.
+    pub async fn txs(self) -> Result<Vec<(Vec<ZkSyncTx>, PackedEthSignature)>, ClientError> {
+        let token = self
+            .token
+            .ok_or_else(|| ClientError::MissingRequiredField("token".into()))?;
+        if self.recipients.is_empty() {
+            return Err(ClientError::MissingRequiredField("recipients".into()));
+        }
+
+        let mut nonce = match self.nonce {
+            Some(nonce) => nonce,
+            None => {
+                let account_info = self
+                    .wallet
+                    .provider
+                    .account_info(self.wallet.address())
+                    .await?;
+                account_info.committed.nonce
+            }
+        };
+
+        // Recipients may not have the accounts yet, so every transfer is expected to take
+        // as many chunks as the transfer to the new account.
+        let batch_size = (self.max_batch_chunks / TransferToNewOp::CHUNKS).max(1);
+        let mut batches = Vec::new();
+        for recipients in self.recipients.chunks(batch_size) {
+            let fee = match &self.fee {
+                Some(fee) => fee.clone(),
+                None => {
+                    let addresses: Vec<_> = recipients.iter().map(|(to, _)| *to).collect();
+                    let tx_types = vec![TxFeeTypes::Transfer; addresses.len()];
+                    let fee = self
+                        .wallet
+                        .provider
+                        .get_txs_batch_fee(tx_types, addresses, token.id)
+                        .await?;
+                    fee.total_fee
+                }
+            };
+
+            let (txs, signature) = self
+                .wallet
+                .signer
+                .sign_multi_transfer(token.clone(), recipients.to_vec(), fee, nonce)
+                .await
+                .map_err(ClientError::SigningError)?;
+            nonce += txs.len() as Nonce;
+
+            let txs = txs
+                .into_iter()
+                .map(|tx| ZkSyncTx::Transfer(Box::new(tx)))
+                .collect();
+            batches.push((txs, signature));
+        }
+
+        Ok(batches)
+    }
+
+    /// Sends the batches one by one, returning the handles for awaiting the transactions.
+    ///
+    /// If a batch is rejected, the following ones are not sent, while the previous ones
+    /// are executed regardless.
+    pub async fn send(self) -> Result<Vec<SyncTransactionHandle<P>>, ClientError> {
+        let provider = self.wallet.provider.clone();
+
+        let mut handles = Vec::new();
+        for (txs, eth_signature) in self.txs().await? {
+            let txs_signed = txs.into_iter().map(|tx| (tx, None)).collect();
+            let tx_hashes = provider
+                .send_txs_batch(txs_signed, vec![eth_signature])
+                .await?;
+
+            handles.extend(
+                tx_hashes
+                    .into_iter()
+                    .map(|tx_hash| SyncTransactionHandle::new(tx_hash, provider.clone())),
+            );
+        }
+
+        Ok(handles)
+    }
+
+    /// Sets the transaction token. Returns an error if token is not supported by zkSync.
+    pub fn token(mut self, token: impl Into<TokenLike>) -> Result<Self, ClientError> {
+        let token_like = token.into();
+        let token = self
+            .wallet
+            .tokens
+            .resolve(token_like)
+            .ok_or(ClientError::UnknownToken)?;
+
+        self.token = Some(token);
+
+        Ok(self)
+    }
+
+    /// Adds the recipient of the transfer. If the provided amount is not packable,
+    /// rounds it to the closest packable amount.
+    ///
+    /// For more details, see [utils](../utils/index.html) functions.
+    pub fn to(mut self, to: Address, amount: impl Into<BigUint>) -> Self {
+        let amount = closest_packable_token_amount(&amount.into());
+        self.recipients.push((to, amount));
+
+        self
+    }
+
+    /// Adds the recipient of the transfer. If the provided amount is not packable,
+    /// returns an error.
+    ///
+    /// For more details, see [utils](../utils/index.html) functions.
+    pub fn to_exact(
+        mut self,
+        to: Address,
+        amount: impl Into<BigUint>,
+    ) -> Result<Self, ClientError> {
+        let amount = amount.into();
+        if !is_token_amount_packable(&amount) {
+            return Err(ClientError::NotPackableValue);
+        }
+        self.recipients.push((to, amount));
+
+        Ok(self)
+    }
+
+    /// Set the fee amount paid by every batch. If the provided fee is not packable,
+    /// rounds it to the closest packable fee amount.
+    ///
+    /// For more details, see [utils](../utils/index.html) functions.
+    pub fn fee(mut self, fee: impl Into<BigUint>) -> Self {
+        let fee = closest_packable_fee_amount(&fee.into());
+        self.fee = Some(fee);
+
+        self
+    }
+
+    /// Set the fee amount paid by every batch. If the provided fee is not packable,
+    /// returns an error.
+    ///
+    /// For more details, see [utils](../utils/index.html) functions.
+    pub fn fee_exact(mut self, fee: impl Into<BigUint>) -> Result<Self, ClientError> {
+        let fee = fee.into();
+        if !is_fee_amount_packable(&fee) {
+            return Err(ClientError::NotPackableValue);
+        }
+        self.fee = Some(fee);
+
+        Ok(self)
+    }
+
+    /// Sets the nonce of the first transaction, the following ones get the consecutive nonces.
+    pub fn nonce(mut self, nonce: Nonce) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+
+    /// Sets the maximum number of chunks of the batch, which must not exceed the size
+    /// of the largest block of the server. The default is `DEFAULT_MAX_BATCH_CHUNKS`.
+    pub fn max_batch_chunks(mut self, max_batch_chunks: usize) -> Self {
+        self.max_batch_chunks = max_batch_chunks;
+        self
+    }
+}
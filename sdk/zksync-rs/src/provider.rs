@@ -7,6 +7,7 @@ use std::time::Duration;
 // External uses
 use async_trait::async_trait;
 use jsonrpc_core::{types::response::Output, ErrorCode};
+use num::BigUint;
 
 // Workspace uses
 use zksync_types::{
@@ -52,6 +53,30 @@ pub trait Provider {
         token: impl Into<TokenLike> + Send + 'async_trait,
     ) -> Result<Fee, ClientError>;
 
+    /// Obtains minimum fee required to process the batch of transactions in zkSync network.
+    ///
+    /// By default, the fees of the transactions are requested one by one and summed up,
+    /// which is never less than the fee of the batch.
+    async fn get_txs_batch_fee(
+        &self,
+        tx_types: Vec<TxFeeTypes>,
+        addresses: Vec<Address>,
+        token: impl Into<TokenLike> + Send + 'async_trait,
+    ) -> Result<BatchFee, ClientError> {
+        let token = token.into();
+        let mut total_fee = BigUint::from(0u32);
+        let mut is_price_stale = false;
+        for (tx_type, address) in tx_types.into_iter().zip(addresses) {
+            let fee = self.get_tx_fee(tx_type, address, token.clone()).await?;
+            total_fee += fee.total_fee;
+            is_price_stale |= fee.is_price_stale;
+        }
+        Ok(BatchFee {
+            total_fee,
+            is_price_stale,
+        })
+    }
+
     /// Submits a transaction to the zkSync network.
     /// Returns the hash of the created transaction.
     async fn send_tx(
@@ -60,6 +85,19 @@ pub trait Provider {
         eth_signature: Option<PackedEthSignature>,
     ) -> Result<TxHash, ClientError>;
 
+    /// Submits a batch transaction to the zkSync network.
+    /// Returns the hashes of the created transactions.
+    ///
+    /// Batches can't be emulated with the separate transactions, so the providers
+    /// not supporting them return `ClientError::UnsupportedOperation`.
+    async fn send_txs_batch(
+        &self,
+        _txs_signed: Vec<(ZkSyncTx, Option<PackedEthSignature>)>,
+        _eth_signatures: Vec<PackedEthSignature>,
+    ) -> Result<Vec<TxHash>, ClientError> {
+        Err(ClientError::UnsupportedOperation("send_txs_batch".into()))
+    }
+
     /// Type of network this provider is allowing access to.
     fn network(&self) -> Network;
 
@@ -104,6 +142,17 @@ impl Provider for RpcProvider {
         self.send_and_deserialize(&msg).await
     }
 
+    async fn get_txs_batch_fee(
+        &self,
+        tx_types: Vec<TxFeeTypes>,
+        addresses: Vec<Address>,
+        token: impl Into<TokenLike> + Send + 'async_trait,
+    ) -> Result<BatchFee, ClientError> {
+        let token = token.into();
+        let msg = JsonRpcRequest::get_txs_batch_fee_in_wei(tx_types, addresses, token);
+        self.send_and_deserialize(&msg).await
+    }
+
     async fn send_tx(
         &self,
         tx: ZkSyncTx,
@@ -113,6 +162,15 @@ impl Provider for RpcProvider {
         self.send_and_deserialize(&msg).await
     }
 
+    async fn send_txs_batch(
+        &self,
+        txs_signed: Vec<(ZkSyncTx, Option<PackedEthSignature>)>,
+        eth_signatures: Vec<PackedEthSignature>,
+    ) -> Result<Vec<TxHash>, ClientError> {
+        let msg = JsonRpcRequest::submit_tx_batch(txs_signed, eth_signatures);
+        self.send_and_deserialize(&msg).await
+    }
+
     fn network(&self) -> Network {
         self.network
     }
@@ -142,17 +200,6 @@ impl RpcProvider {
         }
     }
 
-    /// Submits a batch transaction to the zkSync network.
-    /// Returns the hashes of the created transactions.
-    pub async fn send_txs_batch(
-        &self,
-        txs_signed: Vec<(ZkSyncTx, Option<PackedEthSignature>)>,
-        eth_signatures: Vec<PackedEthSignature>,
-    ) -> Result<Vec<TxHash>, ClientError> {
-        let msg = JsonRpcRequest::submit_tx_batch(txs_signed, eth_signatures);
-        self.send_and_deserialize(&msg).await
-    }
-
     /// Requests and returns information about an Ethereum operation given its `serial_id`.
     pub async fn ethop_info(&self, serial_id: u32) -> Result<EthOpInfo, ClientError> {
         let msg = JsonRpcRequest::ethop_info(serial_id);
@@ -349,5 +396,17 @@ mod messages {
             params.push(serde_json::to_value(token_symbol).expect("serialization fail"));
             Self::create("get_tx_fee", params)
         }
+
+        pub fn get_txs_batch_fee_in_wei(
+            tx_types: Vec<TxFeeTypes>,
+            addresses: Vec<Address>,
+            token_symbol: TokenLike,
+        ) -> Self {
+            let mut params = Vec::with_capacity(3);
+            params.push(serde_json::to_value(tx_types).expect("serialization fail"));
+            params.push(serde_json::to_value(addresses).expect("serialization fail"));
+            params.push(serde_json::to_value(token_symbol).expect("serialization fail"));
+            Self::create("get_txs_batch_fee_in_wei", params)
+        }
     }
 }
@@ -2,14 +2,16 @@
 use std::fmt;
 use zksync_eth_signer::error::SignerError;
 use zksync_eth_signer::EthereumSigner;
-use zksync_types::tx::{ChangePubKeyECDSAData, ChangePubKeyEthAuthData, TxEthSignature};
+use zksync_types::tx::{
+    BatchSignData, ChangePubKeyECDSAData, ChangePubKeyEthAuthData, TxEthSignature,
+};
 // External uses
 use num::BigUint;
 // Workspace uses
 use zksync_crypto::PrivateKey;
 use zksync_types::tx::{ChangePubKey, PackedEthSignature};
 use zksync_types::{
-    AccountId, Address, ForcedExit, Nonce, PubKeyHash, Token, Transfer, Withdraw, H256,
+    AccountId, Address, ForcedExit, Nonce, PubKeyHash, Token, Transfer, Withdraw, ZkSyncTx, H256,
 };
 // Local imports
 use crate::WalletCredentials;
@@ -18,6 +20,16 @@ fn signing_failed_error(err: impl ToString) -> SignerError {
     SignerError::SigningFailed(err.to_string())
 }
 
+/// Returns the signature of the plain message, which is the only kind of the Ethereum
+/// signatures accepted for the transactions signed by the SDK.
+fn packed_eth_signature(signature: TxEthSignature) -> Result<PackedEthSignature, SignerError> {
+    match signature {
+        TxEthSignature::EthereumSignature(packed_signature) => Ok(packed_signature),
+        TxEthSignature::EIP1271Signature(..) => Err(SignerError::UnsupportedEIP1271Signature),
+        TxEthSignature::EIP712Signature(..) => Err(SignerError::UnsupportedEIP712Signature),
+    }
+}
+
 pub struct Signer<S: EthereumSigner> {
     pub pubkey_hash: PubKeyHash,
     pub address: Address,
@@ -73,10 +85,15 @@ impl<S: EthereumSigner> Signer<S> {
         self.account_id
     }
 
+    /// Signs the `ChangePubKey` transaction.
+    ///
+    /// `eth_auth_data` is the Ethereum authorization of the transaction, e.g. an onchain
+    /// auth fact or the CREATE2 data of a contract wallet. If not provided, the transaction
+    /// is authorized by the signature of the Ethereum signer.
     pub async fn sign_change_pubkey_tx(
         &self,
         nonce: Nonce,
-        auth_onchain: bool,
+        eth_auth_data: Option<ChangePubKeyEthAuthData>,
         fee_token: Token,
         fee: BigUint,
     ) -> Result<ChangePubKey, SignerError> {
@@ -94,8 +111,8 @@ impl<S: EthereumSigner> Signer<S> {
         )
         .map_err(signing_failed_error)?;
 
-        let eth_auth_data = if auth_onchain {
-            ChangePubKeyEthAuthData::Onchain
+        let eth_auth_data = if let Some(eth_auth_data) = eth_auth_data {
+            eth_auth_data
         } else {
             let eth_signer = self
                 .eth_signer
@@ -110,12 +127,7 @@ impl<S: EthereumSigner> Signer<S> {
                 .await
                 .map_err(signing_failed_error)?;
 
-            let eth_signature = match eth_signature {
-                TxEthSignature::EthereumSignature(packed_signature) => Ok(packed_signature),
-                TxEthSignature::EIP1271Signature(..) => Err(SignerError::CustomError(
-                    "Can't sign ChangePubKey message with EIP1271 signer".to_string(),
-                )),
-            }?;
+            let eth_signature = packed_eth_signature(eth_signature)?;
 
             ChangePubKeyEthAuthData::ECDSA(ChangePubKeyECDSAData {
                 eth_signature,
@@ -124,10 +136,11 @@ impl<S: EthereumSigner> Signer<S> {
         };
         change_pubkey.eth_auth_data = eth_auth_data;
 
-        assert!(
-            change_pubkey.is_eth_auth_data_valid(),
-            "eth auth data is incorrect"
-        );
+        if !change_pubkey.is_eth_auth_data_valid() {
+            return Err(SignerError::CustomError(
+                "Ethereum authorization data doesn't match the account address".to_string(),
+            ));
+        }
 
         Ok(change_pubkey)
     }
@@ -159,11 +172,7 @@ impl<S: EthereumSigner> Signer<S> {
                 let message = transfer.get_ethereum_sign_message(&token.symbol, token.decimals);
                 let signature = signer.sign_message(&message.as_bytes()).await?;
 
-                if let TxEthSignature::EthereumSignature(packed_signature) = signature {
-                    Some(packed_signature)
-                } else {
-                    return Err(SignerError::MissingEthSigner);
-                }
+                Some(packed_eth_signature(signature)?)
             }
             _ => None,
         };
@@ -171,6 +180,61 @@ impl<S: EthereumSigner> Signer<S> {
         Ok((transfer, eth_signature))
     }
 
+    /// Signs the transfers of the `token` to the multiple recipients.
+    ///
+    /// Every transfer is signed with the zkSync key and gets its own nonce starting from
+    /// the `nonce`, while the whole list is authorized by the single Ethereum signature
+    /// of the batch. The `fee` is paid by the first transfer on behalf of all of them.
+    pub async fn sign_multi_transfer(
+        &self,
+        token: Token,
+        recipients: Vec<(Address, BigUint)>,
+        fee: BigUint,
+        nonce: Nonce,
+    ) -> Result<(Vec<Transfer>, PackedEthSignature), SignerError> {
+        let account_id = self.account_id.ok_or(SignerError::NoSigningKey)?;
+        let eth_signer = self
+            .eth_signer
+            .as_ref()
+            .ok_or(SignerError::MissingEthSigner)?;
+        if recipients.is_empty() {
+            return Err(SignerError::CustomError(
+                "Multi-transfer must have at least one recipient".to_string(),
+            ));
+        }
+
+        let mut fee = Some(fee);
+        let transfers = recipients
+            .into_iter()
+            .zip(nonce..)
+            .map(|((to, amount), nonce)| {
+                Transfer::new_signed(
+                    account_id,
+                    self.address,
+                    to,
+                    token.id,
+                    amount,
+                    fee.take().unwrap_or_default(),
+                    nonce,
+                    &self.private_key,
+                )
+                .map_err(signing_failed_error)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let txs = transfers
+            .iter()
+            .cloned()
+            .map(|tx| ZkSyncTx::Transfer(Box::new(tx)))
+            .collect::<Vec<_>>();
+        let message = BatchSignData::new(&txs, Vec::new())
+            .map_err(signing_failed_error)?
+            .message;
+        let eth_signature = packed_eth_signature(eth_signer.sign_message(&message).await?)?;
+
+        Ok((transfers, eth_signature))
+    }
+
     pub async fn sign_withdraw(
         &self,
         token: Token,
@@ -198,11 +262,7 @@ impl<S: EthereumSigner> Signer<S> {
                 let message = withdraw.get_ethereum_sign_message(&token.symbol, token.decimals);
                 let signature = signer.sign_message(&message.as_bytes()).await?;
 
-                if let TxEthSignature::EthereumSignature(packed_signature) = signature {
-                    Some(packed_signature)
-                } else {
-                    return Err(SignerError::MissingEthSigner);
-                }
+                Some(packed_eth_signature(signature)?)
             }
             _ => None,
         };
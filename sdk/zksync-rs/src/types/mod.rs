@@ -14,6 +14,16 @@ pub struct AccountState {
     pub pub_key_hash: PubKeyHash,
 }
 
+/// Token balance split into the finalized part and the part pending finality.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BalanceBreakdown {
+    pub verified: BigUintSerdeWrapper,
+    pub committed: BigUintSerdeWrapper,
+    pub pending_increase: BigUintSerdeWrapper,
+    pub pending_decrease: BigUintSerdeWrapper,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct DepositingFunds {
@@ -43,6 +53,9 @@ pub struct AccountInfo {
     pub depositing: DepositingAccountBalances,
     pub committed: AccountState,
     pub verified: AccountState,
+    /// Not provided by the older servers.
+    #[serde(default)]
+    pub balances: HashMap<String, BalanceBreakdown>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -159,4 +172,17 @@ pub struct Fee {
     pub zkp_fee: BigUint,
     #[serde(with = "BigUintSerdeAsRadix10Str")]
     pub total_fee: BigUint,
+    /// Set if the fee is quoted at the outdated token price.
+    #[serde(default)]
+    pub is_price_stale: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchFee {
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub total_fee: BigUint,
+    /// Set if the fee is quoted at the outdated token price.
+    #[serde(default)]
+    pub is_price_stale: bool,
 }
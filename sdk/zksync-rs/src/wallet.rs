@@ -119,6 +119,11 @@ where
         TransferBuilder::new(self)
     }
 
+    /// Initializes sending of the `Transfer` transactions to many recipients in batches.
+    pub fn start_multi_transfer(&self) -> MultiTransferBuilder<'_, S, P> {
+        MultiTransferBuilder::new(self)
+    }
+
     /// Initializes `ChangePubKey` transaction sending.
     pub fn start_change_pubkey(&self) -> ChangePubKeyBuilder<'_, S, P> {
         ChangePubKeyBuilder::new(self)
@@ -747,3 +747,28 @@ async fn batch_transfer() -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+async fn multi_transfer() -> Result<(), anyhow::Error> {
+    let wallet = init_account_with_one_ether().await?;
+
+    const RECIPIENT_COUNT: usize = 4;
+    let mut builder = wallet.start_multi_transfer().token("ETH")?;
+    for _ in 0..RECIPIENT_COUNT {
+        builder = builder.to(eth_random_account_credentials().0, 1_000_000u64);
+    }
+
+    // All the transfers are authorized with a single Ethereum signature.
+    let handles = builder.send().await?;
+    assert_eq!(handles.len(), RECIPIENT_COUNT);
+
+    for handle in handles {
+        handle
+            .verify_timeout(Duration::from_secs(180))
+            .wait_for_verify()
+            .await?;
+    }
+
+    Ok(())
+}
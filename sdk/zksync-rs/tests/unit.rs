@@ -306,7 +306,7 @@ mod signatures_with_vectors {
                 let change_pub_key = signer
                     .sign_change_pubkey_tx(
                         sign_data.nonce,
-                        false,
+                        None,
                         token,
                         change_pubkey_tx.fee.clone(),
                     )
@@ -394,7 +394,7 @@ mod wallet_tests {
     use zksync_eth_signer::PrivateKeySigner;
     use zksync_types::{
         tokens::get_genesis_token_list,
-        tx::{PackedEthSignature, TxHash},
+        tx::{BatchSignData, PackedEthSignature, TxHash},
         Address, PubKeyHash, TokenLike, TxFeeTypes, ZkSyncTx, H256,
     };
 
@@ -606,6 +606,104 @@ mod wallet_tests {
         assert!(wallet.is_signing_key_set().await.unwrap());
     }
 
+    #[tokio::test]
+    async fn test_wallet_multi_transfer() {
+        let wallet = get_test_wallet(&[60; 32], Network::Mainnet).await;
+        let recipients = [Address::repeat_byte(1), Address::repeat_byte(2)];
+
+        let mut batches = wallet
+            .start_multi_transfer()
+            .token("DAI")
+            .unwrap()
+            .to(recipients[0], 100u32)
+            .to(recipients[1], 200u32)
+            .fee(10u32)
+            .nonce(5)
+            .txs()
+            .await
+            .unwrap();
+        assert_eq!(batches.len(), 1);
+        let (txs, eth_signature) = batches.remove(0);
+
+        assert_eq!(txs.len(), recipients.len());
+        for (nonce, (tx, to)) in (5..).zip(txs.iter().zip(recipients.iter())) {
+            match tx {
+                ZkSyncTx::Transfer(transfer) => {
+                    assert_eq!(&transfer.to, to);
+                    assert_eq!(transfer.nonce, nonce);
+                    assert!(transfer.verify_signature().is_some());
+                }
+                _ => panic!("Multi-transfer must consist of transfers"),
+            }
+        }
+        // The fee is paid only once for the whole batch.
+        let fees: Vec<_> = txs
+            .iter()
+            .map(|tx| match tx {
+                ZkSyncTx::Transfer(transfer) => transfer.fee.to_u32().unwrap(),
+                _ => unreachable!(),
+            })
+            .collect();
+        assert_eq!(fees, vec![10, 0]);
+
+        let message = BatchSignData::new(&txs, Vec::new()).unwrap().message;
+        assert_eq!(
+            eth_signature.signature_recover_signer(&message).unwrap(),
+            wallet.address()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_wallet_multi_transfer_split() {
+        let wallet = get_test_wallet(&[60; 32], Network::Mainnet).await;
+
+        // Every batch fits 2 transfers to the new accounts.
+        let mut builder = wallet
+            .start_multi_transfer()
+            .token("DAI")
+            .unwrap()
+            .fee(10u32)
+            .nonce(5)
+            .max_batch_chunks(15);
+        for byte in 1..=5 {
+            builder = builder.to(Address::repeat_byte(byte), 100u32);
+        }
+        let batches = builder.txs().await.unwrap();
+
+        let batch_sizes: Vec<_> = batches.iter().map(|(txs, _)| txs.len()).collect();
+        assert_eq!(batch_sizes, vec![2, 2, 1]);
+        let mut nonce = 5;
+        for (txs, eth_signature) in &batches {
+            let message = BatchSignData::new(txs, Vec::new()).unwrap().message;
+            assert_eq!(
+                eth_signature.signature_recover_signer(&message).unwrap(),
+                wallet.address()
+            );
+
+            // Nonces are consecutive across the batches, every batch pays its fee.
+            for (i, tx) in txs.iter().enumerate() {
+                match tx {
+                    ZkSyncTx::Transfer(transfer) => {
+                        assert_eq!(transfer.nonce, nonce);
+                        let expected_fee = if i == 0 { 10u32 } else { 0 };
+                        assert_eq!(transfer.fee.to_u32().unwrap(), expected_fee);
+                    }
+                    _ => panic!("Multi-transfer must consist of transfers"),
+                }
+                nonce += 1;
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_provider_batches_not_supported_by_default() {
+        let wallet = get_test_wallet(&[60; 32], Network::Mainnet).await;
+        assert_eq!(
+            wallet.provider.send_txs_batch(Vec::new(), Vec::new()).await,
+            Err(ClientError::UnsupportedOperation("send_txs_batch".into()))
+        );
+    }
+
     #[tokio::test]
     async fn test_wallet_ethereum() {
         let wallet = get_test_wallet(&[50; 32], Network::Mainnet).await;
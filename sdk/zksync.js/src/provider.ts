@@ -217,7 +217,8 @@ export class Provider {
             gasPriceWei: BigNumber.from(transactionFee.gasPriceWei),
             gasFee: BigNumber.from(transactionFee.gasFee),
             zkpFee: BigNumber.from(transactionFee.zkpFee),
-            totalFee: BigNumber.from(transactionFee.totalFee)
+            totalFee: BigNumber.from(transactionFee.totalFee),
+            isPriceStale: !!transactionFee.isPriceStale
         };
     }
 
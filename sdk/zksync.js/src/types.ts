@@ -47,6 +47,18 @@ export interface AccountState {
         nonce: number;
         pubKeyHash: PubKeyHash;
     };
+    // Balances of the tokens present in either of the states, split by finality.
+    balances?: {
+        // Token are indexed by their symbol (e.g. "ETH")
+        [token: string]: {
+            // Finalized balance (in accordance with the last verified block).
+            verified: BigNumberish;
+            committed: BigNumberish;
+            // Difference between the committed and the verified balance.
+            pendingIncrease: BigNumberish;
+            pendingDecrease: BigNumberish;
+        };
+    };
 }
 
 export type EthSignerType = {
@@ -156,9 +168,21 @@ export interface TransactionReceipt {
     block?: BlockInfo;
 }
 
+export interface DepositAcknowledgment {
+    serialId: number;
+    ethTxHash: string;
+    ethBlock: number;
+    token: number;
+    amount: string;
+    // ISO 8601 timestamp of the moment when the deposited funds are expected to become usable.
+    estimatedUsableAt: string;
+}
+
 export interface PriorityOperationReceipt {
     executed: boolean;
     block?: BlockInfo;
+    // Only set for the deposits seen on Ethereum but not executed yet.
+    acknowledgment?: DepositAcknowledgment;
 }
 
 export interface ContractAddress {
@@ -203,6 +227,8 @@ export interface Fee {
     zkpFee: BigNumber;
     // Total fee amount (in wei)
     totalFee: BigNumber;
+    // Whether the fee is quoted at the outdated token price (the price API is unavailable)
+    isPriceStale: boolean;
 }
 
 export interface BatchFee {
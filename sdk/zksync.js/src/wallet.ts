@@ -456,7 +456,7 @@ export class Wallet {
             if (this.ethSigner instanceof Create2WalletSigner) {
                 const create2data = this.ethSigner.create2WalletData;
                 ethAuthData = {
-                    type: 'Onchain',
+                    type: 'CREATE2',
                     creatorAddress: create2data.creatorAddress,
                     saltArg: create2data.saltArg,
                     codeHash: create2data.codeHash